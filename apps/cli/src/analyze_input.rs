@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use cap_input_analysis::{AnalysisOptions, HeatmapOptions, InputAnalysis, analyze_project};
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+const HEATMAP: &str = "heatmap.png";
+const REPORT: &str = "input-analysis.json";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyzeInputOutput<'a> {
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    heatmap: PathBuf,
+    report: PathBuf,
+    /// Recording time of the frame under the heatmap, `null` when drawn over black.
+    heatmap_frame_time: Option<f64>,
+    #[serde(flatten)]
+    analysis: &'a InputAnalysis,
+}
+
+pub async fn run(
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    options: AnalysisOptions,
+    force_ffmpeg_decoder: bool,
+    format: OutputFormat,
+) -> Result<(), String> {
+    if !options.dwell_radius.is_finite() || options.dwell_radius <= 0.0 {
+        return Err("--dwell-radius must be greater than zero".to_string());
+    }
+    for (flag, value) in [
+        ("--min-dwell", options.min_dwell),
+        ("--typing-gap", options.typing_gap),
+        ("--min-idle", options.min_idle),
+    ] {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(format!("{flag} must be greater than zero"));
+        }
+    }
+
+    crate::project::validate_project(&project_path)?;
+    let project = ProjectAccess::open(&project_path)?;
+
+    let result = analyze_project(
+        project.path(),
+        options,
+        HeatmapOptions::default(),
+        force_ffmpeg_decoder,
+    )
+    .await?;
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", output_dir.display()))?;
+
+    let heatmap_path = output_dir.join(HEATMAP);
+    result
+        .heatmap
+        .save(&heatmap_path)
+        .map_err(|e| format!("Failed to write {}: {e}", heatmap_path.display()))?;
+
+    let report_path = output_dir.join(REPORT);
+    let report = serde_json::to_vec_pretty(&result.analysis).map_err(|e| e.to_string())?;
+    std::fs::write(&report_path, report)
+        .map_err(|e| format!("Failed to write {}: {e}", report_path.display()))?;
+
+    let output = AnalyzeInputOutput {
+        project_path,
+        output_dir,
+        heatmap: heatmap_path,
+        report: report_path,
+        heatmap_frame_time: result.frame_time,
+        analysis: &result.analysis,
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&output),
+        OutputFormat::Text => {
+            let summary = &output.analysis.summary;
+            println!(
+                "Analysed {:.1}s of input: {} clicks ({:.1}/min), {} key presses",
+                output.analysis.duration,
+                summary.click_count,
+                summary.clicks_per_minute,
+                summary.key_count
+            );
+            println!(
+                "  {} dwell areas ({:.1}s), {} typing bursts ({:.1}s), {} idle spans ({:.1}s)",
+                output.analysis.dwells.len(),
+                summary.dwell_time,
+                output.analysis.typing_bursts.len(),
+                summary.typing_time,
+                output.analysis.idle_spans.len(),
+                summary.idle_time
+            );
+            println!("  heatmap  {}", output.heatmap.display());
+            println!("  report   {}", output.report.display());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use cap_project::{
+    RecordingMeta, RecordingMetaInner, TimelineConfiguration, edit_script::EditScript,
+};
+use cap_rendering::ProjectRecordingsMeta;
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApplyEditsOutput {
+    project_path: PathBuf,
+    script_path: PathBuf,
+    edits: usize,
+    written: bool,
+    recording_duration: f64,
+    duration: f64,
+    clips: usize,
+    zooms: usize,
+    scenes: usize,
+    texts: usize,
+    /// The compiled timeline, only included for `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeline: Option<TimelineConfiguration>,
+}
+
+/// JSON scripts are read as JSON so errors point at JSON syntax; anything
+/// else is YAML.
+fn load_script(path: &Path) -> Result<EditScript, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let is_json = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    if is_json {
+        serde_json::from_str(&text).map_err(|e| format!("Invalid edit script: {e}"))
+    } else {
+        serde_yaml::from_str(&text).map_err(|e| format!("Invalid edit script: {e}"))
+    }
+}
+
+pub fn run(
+    project_path: PathBuf,
+    script_path: PathBuf,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let script = load_script(&script_path)?;
+
+    let mut project = ProjectAccess::open(&project_path)?;
+    let meta = RecordingMeta::load_for_project(project.path())
+        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let RecordingMetaInner::Studio(studio) = &meta.inner else {
+        return Err("Edit scripts can only be applied to studio recordings".to_string());
+    };
+    let recordings = ProjectRecordingsMeta::new(project.path(), studio)?;
+    let durations: Vec<f64> = recordings
+        .segments
+        .iter()
+        .map(|segment| segment.duration())
+        .collect();
+
+    // The script owns clips, zooms, scenes and text; tracks it can't express
+    // (masks, captions, keyboard, audio, 3D camera) are kept and moved through
+    // the new cuts.
+    let mut config = meta.project_config();
+    let timeline = match config.timeline.take() {
+        Some(existing) => script.apply(&durations, &existing)?,
+        None => script.compile(&durations)?,
+    };
+
+    let output = ApplyEditsOutput {
+        project_path: project_path.clone(),
+        script_path,
+        edits: script.edits.len(),
+        written: !dry_run,
+        recording_duration: durations.iter().sum(),
+        duration: timeline.duration(),
+        clips: timeline.segments.len(),
+        zooms: timeline.zoom_segments.len(),
+        scenes: timeline.scene_segments.len(),
+        texts: timeline.text_segments.len(),
+        timeline: dry_run.then(|| timeline.clone()),
+    };
+
+    if !dry_run {
+        config.timeline = Some(timeline);
+        config
+            .write(project.path())
+            .map_err(|e| format!("Failed to write project config: {e}"))?;
+        project.persist()?;
+    }
+
+    match format {
+        OutputFormat::Json => write_json(&output),
+        OutputFormat::Text => {
+            println!(
+                "{} {} edits: {:.2}s of recording -> {:.2}s ({} clips, {} zooms, {} scenes, {} text)",
+                if output.written { "Applied" } else { "Checked" },
+                output.edits,
+                output.recording_duration,
+                output.duration,
+                output.clips,
+                output.zooms,
+                output.scenes,
+                output.texts,
+            );
+            Ok(())
+        }
+    }
+}
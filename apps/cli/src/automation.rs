@@ -40,7 +40,9 @@ pub fn rule_counts() -> (usize, usize) {
 
 fn capture_target_kind(target: &ScreenCaptureTarget) -> Option<cap_automation::CaptureTargetKind> {
     match target {
-        ScreenCaptureTarget::Window { .. } => Some(cap_automation::CaptureTargetKind::Window),
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. } => {
+            Some(cap_automation::CaptureTargetKind::Window)
+        }
         ScreenCaptureTarget::Display { .. } => Some(cap_automation::CaptureTargetKind::Display),
         ScreenCaptureTarget::Area { .. } => Some(cap_automation::CaptureTargetKind::Area),
         ScreenCaptureTarget::CameraOnly => None,
@@ -143,6 +145,8 @@ impl AutomationHost for CliAutomationHost {
                     custom_bpp: None,
                     force_ffmpeg_decoder: false,
                     optimize_filesize: false,
+                    target_file_size: None,
+                    audio: Default::default(),
                 }
                 .export(base, |_| true)
                 .await
@@ -161,6 +165,7 @@ impl AutomationHost for CliAutomationHost {
                     fps: profile.fps,
                     resolution_base: profile.resolution_base,
                     cursor_only: false,
+                    audio: Default::default(),
                 }
                 .export(base, |_| true)
                 .await
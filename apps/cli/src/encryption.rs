@@ -0,0 +1,187 @@
+//! Finds the key for encrypted projects and opens them for the commands that read or edit them.
+//!
+//! The master key comes from `--encryption-key-file` where a command takes one, then the keyfile
+//! named by `CAP_RECORDING_KEY_FILE`, then the OS keyring, where `cap record --encrypt` stores a
+//! new key the first time it needs one. Commands that only read or edit a project's JSON do so in
+//! memory through [`ProjectMetadata`]. Commands that hand media to decoders work on a decrypted copy
+//! of the files they need and seal whatever they change back into the project; see
+//! [`cap_project::encryption`] for where that copy lives.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use cap_project::{
+    ProjectConfiguration, RecordingMeta,
+    encryption::{self, DecryptedProject, KEY_FILE_ENV, MasterKey, ProjectKey},
+};
+use tracing::info;
+
+/// The master key from `key_file`, `CAP_RECORDING_KEY_FILE` or the keyring, in that order.
+pub fn master_key(key_file: Option<&Path>) -> Result<MasterKey, String> {
+    if let Some(path) = key_file {
+        return MasterKey::from_keyfile(path)
+            .map_err(|e| format!("Failed to read encryption key {}: {e}", path.display()));
+    }
+
+    load_from_env_or_keyring()?.ok_or_else(|| {
+        format!(
+            "No recording encryption key found; pass --encryption-key-file or set {KEY_FILE_ENV}"
+        )
+    })
+}
+
+/// Like [`master_key`], but generates a key and stores it in the keyring when there is none yet.
+pub fn master_key_or_create(key_file: Option<&Path>) -> Result<MasterKey, String> {
+    if key_file.is_some() {
+        return master_key(key_file);
+    }
+    if let Some(key) = load_from_env_or_keyring()? {
+        return Ok(key);
+    }
+
+    let key = MasterKey::generate();
+    key.store_in_keyring().map_err(|e| {
+        format!(
+            "Failed to store a new recording encryption key: {e}; pass --encryption-key-file instead"
+        )
+    })?;
+    info!(key_id = %key.id(), "Stored a new recording encryption key in the OS keyring");
+    Ok(key)
+}
+
+fn load_from_env_or_keyring() -> Result<Option<MasterKey>, String> {
+    MasterKey::load_from_env_or_keyring()
+        .map_err(|e| format!("Failed to load the recording encryption key: {e}"))
+}
+
+/// A project's `recording-meta.json`, `project-config.json` and `captions.json`, read and written
+/// in memory when the project is encrypted.
+pub struct ProjectMetadata {
+    path: PathBuf,
+    key: Option<ProjectKey>,
+}
+
+impl ProjectMetadata {
+    pub fn open(project_path: &Path) -> Result<Self, String> {
+        let key = if encryption::is_encrypted(project_path) {
+            let master = master_key(None)?;
+            Some(
+                ProjectKey::open(project_path, &master)
+                    .map_err(|e| format!("Failed to decrypt {}: {e}", project_path.display()))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path: project_path.to_path_buf(),
+            key,
+        })
+    }
+
+    fn read(&self, name: &str) -> io::Result<String> {
+        let path = self.path.join(name);
+        let bytes = match &self.key {
+            Some(key) => key.read(&path)?,
+            None => std::fs::read(&path)?,
+        };
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn load_meta(&self) -> Result<RecordingMeta, String> {
+        if self.key.is_none() {
+            return RecordingMeta::load_for_project(&self.path).map_err(|e| e.to_string());
+        }
+
+        let json = self
+            .read("recording-meta.json")
+            .map_err(|e| e.to_string())?;
+        RecordingMeta::from_json_for_project(&self.path, &json).map_err(|e| e.to_string())
+    }
+
+    /// The saved `project-config.json`, failing with [`io::ErrorKind::NotFound`] when there is none.
+    pub fn load_config(&self) -> io::Result<ProjectConfiguration> {
+        if self.key.is_none() {
+            return ProjectConfiguration::load(&self.path);
+        }
+
+        ProjectConfiguration::from_json(&self.read("project-config.json")?)
+    }
+
+    /// The configuration the editor and exporter would use, like [`RecordingMeta::project_config`].
+    pub fn project_config(&self, meta: &RecordingMeta) -> ProjectConfiguration {
+        if self.key.is_none() {
+            return meta.project_config();
+        }
+
+        let config = self.load_config().unwrap_or_default();
+        let captions = self.read("captions.json").ok();
+        RecordingMeta::with_captions(config, captions.as_deref())
+    }
+
+    pub fn write_config(&self, config: &ProjectConfiguration) -> io::Result<()> {
+        match &self.key {
+            Some(key) => key.write(
+                &self.path.join("project-config.json"),
+                config.to_json()?.as_bytes(),
+            ),
+            None => config.write(&self.path),
+        }
+    }
+}
+
+/// A project opened for a command: the project itself, or a decrypted working copy when it is
+/// encrypted.
+pub enum ProjectAccess {
+    Plain(PathBuf),
+    Decrypted(Box<DecryptedProject>),
+}
+
+impl ProjectAccess {
+    /// Opens the whole project, media included.
+    pub fn open(project_path: &Path) -> Result<Self, String> {
+        Self::open_only(project_path, |_| true)
+    }
+
+    /// Opens the project, decrypting only the files for which `include` returns true, given their
+    /// path relative to the project.
+    pub fn open_only(project_path: &Path, include: impl Fn(&Path) -> bool) -> Result<Self, String> {
+        if !encryption::is_encrypted(project_path) {
+            return Ok(Self::Plain(project_path.to_path_buf()));
+        }
+
+        let master = master_key(None)?;
+        let project = DecryptedProject::open_only(project_path, &master, include)
+            .map_err(|e| format!("Failed to decrypt {}: {e}", project_path.display()))?;
+
+        Ok(Self::Decrypted(Box::new(project)))
+    }
+
+    /// Where the project can be read and written as plain files.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Plain(path) => path,
+            Self::Decrypted(project) => project.path(),
+        }
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, Self::Decrypted(_))
+    }
+
+    /// Seals changes made under [`ProjectAccess::path`] back into the project. Does nothing for
+    /// plain projects, which were changed in place.
+    pub fn persist(&mut self) -> Result<(), String> {
+        match self {
+            Self::Plain(_) => Ok(()),
+            Self::Decrypted(project) => project.persist().map_err(|e| {
+                format!(
+                    "Failed to save changes to encrypted project {}: {e}",
+                    project.source().display()
+                )
+            }),
+        }
+    }
+}
@@ -13,11 +13,16 @@ use clap::{Args, ValueEnum};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
+use crate::encryption::ProjectAccess;
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
 pub enum ExportFormat {
     Mp4,
     Gif,
     Mov,
+    Webp,
+    Apng,
+    Stems,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
@@ -39,10 +44,40 @@ impl From<QualityArg> for cap_export::mp4::ExportCompression {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum RendererArg {
+    Gpu,
+    Cpu,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum StemFormatArg {
+    Wav,
+    Flac,
+}
+
+impl From<StemFormatArg> for cap_export::stems::StemFileFormat {
+    fn from(value: StemFormatArg) -> Self {
+        match value {
+            StemFormatArg::Wav => Self::Wav,
+            StemFormatArg::Flac => Self::Flac,
+        }
+    }
+}
+
+impl From<RendererArg> for cap_export::Renderer {
+    fn from(value: RendererArg) -> Self {
+        match value {
+            RendererArg::Gpu => Self::Gpu,
+            RendererArg::Cpu => Self::Cpu,
+        }
+    }
+}
+
 #[derive(Args)]
 #[command(long_about = "Render a '.cap' project to a video file.
 
-NOTE: here --format selects the CONTAINER (mp4/gif/mov), NOT the output mode. For machine-readable
+NOTE: here --format selects the CONTAINER (mp4/gif/mov/webp/apng/stems), NOT the output mode. For machine-readable
 output pass --json (the global flag), which streams NDJSON progress + completion events to stdout.
 The NDJSON uses PascalCase type tags and snake_case fields ({\"type\":\"Progress\",\"rendered_count\":N,
 \"total_frames\":N} then {\"type\":\"Completed\",\"path\":\"...\"}); on failure a final
@@ -55,13 +90,13 @@ pub struct Export {
     /// Output file to write the export to
     #[arg(long, short = 'o')]
     output: Option<PathBuf>,
-    /// Container to export: mp4 (default), gif, or mov. NOT the output mode — use --json for JSON
+    /// Container to export: mp4 (default), gif, mov, webp, apng or stems (audio only). NOT the output mode — use --json for JSON
     #[arg(long, value_enum)]
     format: Option<ExportFormat>,
-    /// Frames per second to render
+    /// Frames per second to render (default 60 for mp4/mov, 30 for gif, 15 for webp/apng)
     #[arg(long)]
     fps: Option<u32>,
-    /// Output resolution as WIDTHxHEIGHT, e.g. 1920x1080
+    /// Output resolution as WIDTHxHEIGHT, e.g. 1920x1080 (default 1920x1080, 960x540 for webp/apng)
     #[arg(long)]
     resolution: Option<String>,
     /// Compression preset (mp4 only)
@@ -70,12 +105,39 @@ pub struct Export {
     /// Optimise for smaller files using CRF (mp4 only)
     #[arg(long)]
     optimize_filesize: bool,
+    /// Fit the file within this size, e.g. 25M, re-encoding if it overshoots (mp4 only)
+    #[arg(long, value_parser = parse_file_size)]
+    target_size: Option<u64>,
+    /// Encode every pixel exactly (webp/apng only)
+    #[arg(long)]
+    lossless: bool,
+    /// Times to play the animation, 0 for forever (webp/apng only)
+    #[arg(long)]
+    loop_count: Option<u16>,
+    /// Lower the quality until the file fits, e.g. 10M or 500K (webp/apng only)
+    #[arg(long, value_parser = parse_file_size)]
+    max_file_size: Option<u64>,
+    /// Normalise the audio mix to this integrated loudness in LUFS, e.g. -16 (mp4/mov/stems)
+    #[arg(long, allow_negative_numbers = true)]
+    loudness: Option<f32>,
+    /// True-peak ceiling in dBTP when normalising, default -1 (requires --loudness)
+    #[arg(long, allow_negative_numbers = true)]
+    true_peak: Option<f32>,
+    /// Add mic, system audio and music as separate tracks after the mix (mp4/mov)
+    #[arg(long)]
+    separate_audio_tracks: bool,
+    /// File type for each stem: wav (default) or flac (stems only)
+    #[arg(long, value_enum)]
+    stem_format: Option<StemFormatArg>,
     /// Full export settings as JSON, e.g. {"format":"Mp4","fps":60,"resolution_base":{"x":1920,"y":1080},"compression":"Maximum","custom_bpp":null} (mutually exclusive with the flags above)
     #[arg(long)]
     settings_json: Option<String>,
     /// Decode source video with FFmpeg instead of the platform hardware decoder
     #[arg(long)]
     force_ffmpeg_decoder: bool,
+    /// Compositor to render frames with: gpu (wgpu, default) or cpu (Skia raster, for hosts without a GPU; rejects camera background blur)
+    #[arg(long, value_enum, default_value_t = RendererArg::Gpu)]
+    renderer: RendererArg,
     /// Stream newline-delimited JSON progress events to stdout ({"type":"Progress","rendered_count":N,"total_frames":N}; also emits a terminal {"type":"Error","error":"..."} on failure). Implied by --json
     #[arg(long)]
     progress_json: bool,
@@ -91,6 +153,14 @@ pub struct ExportFlags {
     pub resolution: Option<String>,
     pub quality: Option<QualityArg>,
     pub optimize_filesize: bool,
+    pub target_size: Option<u64>,
+    pub lossless: bool,
+    pub loop_count: Option<u16>,
+    pub max_file_size: Option<u64>,
+    pub loudness: Option<f32>,
+    pub true_peak: Option<f32>,
+    pub separate_audio_tracks: bool,
+    pub stem_format: Option<StemFormatArg>,
     pub force_ffmpeg_decoder: bool,
 }
 
@@ -101,6 +171,26 @@ impl ExportFlags {
             || self.resolution.is_some()
             || self.quality.is_some()
             || self.optimize_filesize
+            || self.target_size.is_some()
+            || self.has_animated_options()
+            || self.has_audio_options()
+            || self.stem_format.is_some()
+    }
+
+    fn has_animated_options(&self) -> bool {
+        self.lossless || self.loop_count.is_some() || self.max_file_size.is_some()
+    }
+
+    fn has_audio_options(&self) -> bool {
+        self.loudness.is_some() || self.true_peak.is_some() || self.separate_audio_tracks
+    }
+
+    fn audio_settings(&self) -> cap_export::audio::ExportAudioSettings {
+        cap_export::audio::ExportAudioSettings {
+            loudness_target: self.loudness,
+            true_peak_limit: self.true_peak,
+            separate_tracks: self.separate_audio_tracks,
+        }
     }
 }
 
@@ -113,6 +203,12 @@ pub enum CliExportSettings {
     Gif(cap_export::gif::GifExportSettings),
     #[serde(alias = "mov")]
     Mov(cap_export::mov::MovExportSettings),
+    #[serde(alias = "webp")]
+    WebP(cap_export::animated::WebpExportSettings),
+    #[serde(alias = "apng")]
+    Apng(cap_export::animated::ApngExportSettings),
+    #[serde(alias = "stems")]
+    Stems(cap_export::stems::StemsExportSettings),
 }
 
 impl CliExportSettings {
@@ -121,20 +217,38 @@ impl CliExportSettings {
             Self::Mp4(settings) => settings.fps,
             Self::Gif(settings) => settings.fps,
             Self::Mov(settings) => settings.fps,
+            Self::WebP(settings) => settings.fps,
+            Self::Apng(settings) => settings.fps,
+            Self::Stems(_) => cap_export::stems::StemsExportSettings::PROGRESS_FPS,
         }
     }
 
     fn force_ffmpeg_decoder(&self) -> bool {
         match self {
             Self::Mp4(settings) => settings.force_ffmpeg_decoder,
-            Self::Gif(_) | Self::Mov(_) => false,
+            Self::Gif(_) | Self::Mov(_) | Self::WebP(_) | Self::Apng(_) | Self::Stems(_) => false,
         }
     }
 
     fn cursor_only(&self) -> bool {
         match self {
             Self::Mov(settings) => settings.cursor_only,
-            Self::Mp4(_) | Self::Gif(_) => false,
+            Self::Mp4(_) | Self::Gif(_) | Self::WebP(_) | Self::Apng(_) | Self::Stems(_) => false,
+        }
+    }
+
+    async fn export(
+        self,
+        exporter_base: ExporterBase,
+        on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<PathBuf, String> {
+        match self {
+            Self::Mp4(settings) => settings.export(exporter_base, on_progress).await,
+            Self::Gif(settings) => settings.export(exporter_base, on_progress).await,
+            Self::Mov(settings) => settings.export(exporter_base, on_progress).await,
+            Self::WebP(settings) => settings.export(exporter_base, on_progress).await,
+            Self::Apng(settings) => settings.export(exporter_base, on_progress).await,
+            Self::Stems(settings) => settings.export(exporter_base, on_progress).await,
         }
     }
 }
@@ -143,9 +257,36 @@ fn default_fps(format: ExportFormat) -> u32 {
     match format {
         ExportFormat::Mp4 | ExportFormat::Mov => 60,
         ExportFormat::Gif => 30,
+        ExportFormat::Webp | ExportFormat::Apng => cap_export::animated::DEFAULT_FPS,
+        ExportFormat::Stems => cap_export::stems::StemsExportSettings::PROGRESS_FPS,
     }
 }
 
+/// Bytes, optionally suffixed with K or M (powers of 1024), e.g. `10M`.
+fn parse_file_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let upper = trimmed.to_ascii_uppercase();
+    let upper = upper.strip_suffix('B').unwrap_or(&upper);
+    let (digits, multiplier) = if let Some(digits) = upper.strip_suffix('K') {
+        (digits, 1024)
+    } else if let Some(digits) = upper.strip_suffix('M') {
+        (digits, 1024 * 1024)
+    } else {
+        (upper, 1)
+    };
+
+    let size: f64 = digits
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid file size '{value}', expected e.g. 10M or 500K"))?;
+    let bytes = size * multiplier as f64;
+    if !(1.0..=u64::MAX as f64).contains(&bytes) {
+        return Err(format!("File size '{value}' is out of range"));
+    }
+
+    Ok(bytes as u64)
+}
+
 fn parse_resolution(value: &str) -> Result<XY<u32>, String> {
     let (w, h) = value
         .split_once(['x', 'X'])
@@ -170,10 +311,76 @@ pub fn settings_from_flags(flags: &ExportFlags) -> Result<CliExportSettings, Str
     if fps == 0 {
         return Err("--fps must be greater than zero".to_string());
     }
+    let animated = matches!(format, ExportFormat::Webp | ExportFormat::Apng);
     let resolution_base = match &flags.resolution {
         Some(value) => parse_resolution(value)?,
+        None if animated => cap_export::animated::DEFAULT_RESOLUTION_BASE,
         None => XY::new(1920, 1080),
     };
+    if !animated && flags.has_animated_options() {
+        return Err(
+            "--lossless, --loop-count and --max-file-size are only supported for --format webp or apng"
+                .to_string(),
+        );
+    }
+    if animated && flags.quality.is_some() {
+        return Err(
+            "--quality is only supported for --format mp4; use --settings-json for WebP/APNG quality"
+                .to_string(),
+        );
+    }
+    if animated && flags.optimize_filesize {
+        return Err(
+            "--optimize-filesize is only supported for --format mp4; use --max-file-size"
+                .to_string(),
+        );
+    }
+
+    if flags.target_size.is_some() {
+        if format != ExportFormat::Mp4 {
+            return Err("--target-size is only supported for --format mp4".to_string());
+        }
+        if flags.quality.is_some() || flags.optimize_filesize {
+            return Err(
+                "--target-size picks its own bitrate and cannot be combined with --quality or --optimize-filesize"
+                    .to_string(),
+            );
+        }
+    }
+
+    let has_audio = matches!(
+        format,
+        ExportFormat::Mp4 | ExportFormat::Mov | ExportFormat::Stems
+    );
+    if !has_audio && flags.has_audio_options() {
+        return Err(
+            "--loudness, --true-peak and --separate-audio-tracks are only supported for --format mp4, mov or stems"
+                .to_string(),
+        );
+    }
+    if flags.true_peak.is_some() && flags.loudness.is_none() {
+        return Err("--true-peak requires --loudness".to_string());
+    }
+    if format == ExportFormat::Stems {
+        if flags.separate_audio_tracks {
+            return Err(
+                "--separate-audio-tracks is implied by --format stems, which writes one file per source"
+                    .to_string(),
+            );
+        }
+        if flags.fps.is_some()
+            || flags.resolution.is_some()
+            || flags.quality.is_some()
+            || flags.optimize_filesize
+        {
+            return Err(
+                "--format stems exports audio only; --fps, --resolution, --quality and --optimize-filesize do not apply"
+                    .to_string(),
+            );
+        }
+    } else if flags.stem_format.is_some() {
+        return Err("--stem-format is only supported for --format stems".to_string());
+    }
 
     match format {
         ExportFormat::Mp4 => Ok(CliExportSettings::Mp4(cap_export::mp4::Mp4ExportSettings {
@@ -186,6 +393,8 @@ pub fn settings_from_flags(flags: &ExportFlags) -> Result<CliExportSettings, Str
             custom_bpp: None,
             force_ffmpeg_decoder: flags.force_ffmpeg_decoder,
             optimize_filesize: flags.optimize_filesize,
+            target_file_size: flags.target_size,
+            audio: flags.audio_settings(),
         })),
         ExportFormat::Gif => {
             if flags.quality.is_some() {
@@ -214,8 +423,35 @@ pub fn settings_from_flags(flags: &ExportFlags) -> Result<CliExportSettings, Str
                 fps,
                 resolution_base,
                 cursor_only: false,
+                audio: flags.audio_settings(),
             }))
         }
+        ExportFormat::Webp => Ok(CliExportSettings::WebP(
+            cap_export::animated::WebpExportSettings {
+                fps,
+                resolution_base,
+                quality: None,
+                lossless: flags.lossless,
+                loop_count: flags.loop_count,
+                max_file_size: flags.max_file_size,
+            },
+        )),
+        ExportFormat::Apng => Ok(CliExportSettings::Apng(
+            cap_export::animated::ApngExportSettings {
+                fps,
+                resolution_base,
+                quality: None,
+                lossless: flags.lossless,
+                loop_count: flags.loop_count,
+                max_file_size: flags.max_file_size,
+            },
+        )),
+        ExportFormat::Stems => Ok(CliExportSettings::Stems(
+            cap_export::stems::StemsExportSettings {
+                file_format: flags.stem_format.map(Into::into).unwrap_or_default(),
+                audio: flags.audio_settings(),
+            },
+        )),
     }
 }
 
@@ -256,6 +492,14 @@ impl Export {
             resolution: self.resolution.clone(),
             quality: self.quality,
             optimize_filesize: self.optimize_filesize,
+            target_size: self.target_size,
+            lossless: self.lossless,
+            loop_count: self.loop_count,
+            max_file_size: self.max_file_size,
+            loudness: self.loudness,
+            true_peak: self.true_peak,
+            separate_audio_tracks: self.separate_audio_tracks,
+            stem_format: self.stem_format,
             force_ffmpeg_decoder: self.force_ffmpeg_decoder,
         };
 
@@ -263,7 +507,7 @@ impl Export {
             Some(json) => {
                 if flags.is_set() {
                     return Err(
-                        "--settings-json cannot be combined with --format/--fps/--resolution/--quality/--optimize-filesize"
+                        "--settings-json cannot be combined with --format/--fps/--resolution/--quality/--optimize-filesize/--target-size/--lossless/--loop-count/--max-file-size/--loudness/--true-peak/--separate-audio-tracks/--stem-format"
                             .to_string(),
                     );
                 }
@@ -309,13 +553,23 @@ impl Export {
         let output = self.resolve_output()?;
         let settings = self.resolve_settings()?;
 
-        ensure_remuxed(self.project_path.clone()).await?;
-        let meta = RecordingMeta::load_for_project(&self.project_path)
+        let mut project = ProjectAccess::open(&self.project_path)?;
+        // The default output is inside the project, where it would be left unencrypted.
+        if project.is_encrypted() && output.is_none() {
+            return Err(
+                "Encrypted projects must be exported to an explicit --output path".to_string(),
+            );
+        }
+        let project_path = project.path().to_path_buf();
+
+        ensure_remuxed(project_path.clone()).await?;
+        project.persist()?;
+        let meta = RecordingMeta::load_for_project(&project_path)
             .map_err(|e| format!("Failed to load recording meta: {e}"))?;
 
         if matches!(&meta.inner, RecordingMetaInner::Instant(_)) {
             return export_instant_project(
-                self.project_path,
+                project_path,
                 output,
                 &settings,
                 progress_json,
@@ -326,8 +580,9 @@ impl Export {
         }
 
         let force_ffmpeg_decoder = self.force_ffmpeg_decoder || settings.force_ffmpeg_decoder();
-        let mut builder = ExporterBase::builder(self.project_path.clone())
-            .with_force_ffmpeg_decoder(force_ffmpeg_decoder);
+        let mut builder = ExporterBase::builder(project_path)
+            .with_force_ffmpeg_decoder(force_ffmpeg_decoder)
+            .with_renderer(self.renderer.into());
 
         if let Some(output_path) = output {
             builder = builder.with_output_path(output_path);
@@ -375,12 +630,10 @@ impl Export {
             true
         };
 
-        let output_path = match settings {
-            CliExportSettings::Mp4(settings) => settings.export(exporter_base, on_progress).await,
-            CliExportSettings::Gif(settings) => settings.export(exporter_base, on_progress).await,
-            CliExportSettings::Mov(settings) => settings.export(exporter_base, on_progress).await,
-        }
-        .map_err(|v| format!("Exporter error: {v}"))?;
+        let output_path = settings
+            .export(exporter_base, on_progress)
+            .await
+            .map_err(|v| format!("Exporter error: {v}"))?;
 
         // Defense in depth: an export that renders no frames writes an empty (~few hundred byte) file
         // but otherwise "succeeds". An agent must never silently get/upload that, so fail loudly and
@@ -460,8 +713,14 @@ fn instant_export_settings_supported(settings: &CliExportSettings) -> bool {
                 )
                 && settings.custom_bpp.is_none()
                 && !settings.optimize_filesize
+                && settings.target_file_size.is_none()
+                && settings.audio == cap_export::audio::ExportAudioSettings::default()
         }
-        CliExportSettings::Gif(_) | CliExportSettings::Mov(_) => false,
+        CliExportSettings::Gif(_)
+        | CliExportSettings::Mov(_)
+        | CliExportSettings::WebP(_)
+        | CliExportSettings::Apng(_)
+        | CliExportSettings::Stems(_) => false,
     }
 }
 
@@ -568,6 +827,12 @@ async fn export_instant_project(
 /// Render a project to its default output path with default settings (mp4, 1080p60, Maximum). Used by
 /// `cap upload --export` to glue record -> export -> upload into one step.
 pub async fn export_project_default(project_path: PathBuf) -> Result<PathBuf, String> {
+    if cap_project::encryption::is_encrypted(&project_path) {
+        return Err(
+            "Encrypted projects can't be exported to their default output; run `cap export` with --output"
+                .to_string(),
+        );
+    }
     let settings = settings_from_flags(&ExportFlags::default())?;
     ensure_remuxed(project_path.clone()).await?;
     let meta = RecordingMeta::load_for_project(&project_path)
@@ -590,12 +855,10 @@ pub async fn export_project_default(project_path: PathBuf) -> Result<PathBuf, St
         true
     };
 
-    let output_path = match settings {
-        CliExportSettings::Mp4(settings) => settings.export(exporter_base, on_progress).await,
-        CliExportSettings::Gif(settings) => settings.export(exporter_base, on_progress).await,
-        CliExportSettings::Mov(settings) => settings.export(exporter_base, on_progress).await,
-    }
-    .map_err(|v| format!("Exporter error: {v}"))?;
+    let output_path = settings
+        .export(exporter_base, on_progress)
+        .await
+        .map_err(|v| format!("Exporter error: {v}"))?;
 
     // Same 0-frame guard as Export::run_inner: a recording with missing media renders an empty,
     // unplayable file that otherwise "succeeds", and `cap upload --export` would sign + upload it and
@@ -636,8 +899,9 @@ impl ExportPreview {
         let settings =
             serde_json::from_str::<cap_export::preview::ExportPreviewSettings>(&self.settings_json)
                 .map_err(|e| format!("Invalid preview settings JSON: {e}"))?;
+        let project = ProjectAccess::open(&self.project_path)?;
         let result = cap_export::preview::render_preview(
-            self.project_path,
+            project.path().to_path_buf(),
             self.frame_time,
             settings,
             self.force_ffmpeg_decoder,
@@ -775,6 +1039,149 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn webp_flags_build_animated_settings() {
+        let settings = settings_from_flags(&ExportFlags {
+            format: Some(ExportFormat::Webp),
+            lossless: true,
+            max_file_size: Some(parse_file_size("10M").unwrap()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(settings.fps(), 15);
+        match settings {
+            CliExportSettings::WebP(s) => {
+                assert_eq!(s.resolution_base, XY::new(960, 540));
+                assert!(s.lossless);
+                assert_eq!(s.max_file_size, Some(10 * 1024 * 1024));
+            }
+            _ => panic!("expected webp settings"),
+        }
+    }
+
+    #[test]
+    fn animated_flags_only_for_webp_and_apng() {
+        assert!(
+            settings_from_flags(&ExportFlags {
+                format: Some(ExportFormat::Gif),
+                loop_count: Some(1),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_file_size_accepts_suffixes() {
+        assert_eq!(parse_file_size("2048").unwrap(), 2048);
+        assert_eq!(parse_file_size("500K").unwrap(), 500 * 1024);
+        assert_eq!(parse_file_size("1.5mb").unwrap(), 1536 * 1024);
+        assert_eq!(parse_file_size("6000M").unwrap(), 6000 * 1024 * 1024);
+        assert!(parse_file_size("0").is_err());
+        assert!(parse_file_size("lots").is_err());
+    }
+
+    #[test]
+    fn target_size_applies_to_mp4_only() {
+        let settings = settings_from_flags(&ExportFlags {
+            target_size: Some(parse_file_size("25M").unwrap()),
+            ..Default::default()
+        })
+        .unwrap();
+        match settings {
+            CliExportSettings::Mp4(s) => assert_eq!(s.target_file_size, Some(25 * 1024 * 1024)),
+            _ => panic!("expected mp4 settings"),
+        }
+
+        assert!(
+            settings_from_flags(&ExportFlags {
+                format: Some(ExportFormat::Gif),
+                target_size: Some(1024),
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            settings_from_flags(&ExportFlags {
+                target_size: Some(1024),
+                quality: Some(QualityArg::Web),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn audio_flags_apply_to_mp4_and_mov() {
+        let settings = settings_from_flags(&ExportFlags {
+            format: Some(ExportFormat::Mov),
+            loudness: Some(-16.0),
+            separate_audio_tracks: true,
+            ..Default::default()
+        })
+        .unwrap();
+        match settings {
+            CliExportSettings::Mov(s) => {
+                assert_eq!(s.audio.loudness_target, Some(-16.0));
+                assert_eq!(s.audio.true_peak_limit, None);
+                assert!(s.audio.separate_tracks);
+            }
+            _ => panic!("expected mov settings"),
+        }
+
+        assert!(
+            settings_from_flags(&ExportFlags {
+                format: Some(ExportFormat::Gif),
+                loudness: Some(-16.0),
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            settings_from_flags(&ExportFlags {
+                true_peak: Some(-2.0),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn stems_flags_build_stems_settings() {
+        let settings = settings_from_flags(&ExportFlags {
+            format: Some(ExportFormat::Stems),
+            stem_format: Some(StemFormatArg::Flac),
+            loudness: Some(-16.0),
+            true_peak: Some(-2.0),
+            ..Default::default()
+        })
+        .unwrap();
+        match settings {
+            CliExportSettings::Stems(s) => {
+                assert_eq!(s.file_format, cap_export::stems::StemFileFormat::Flac);
+                assert_eq!(s.audio.loudness_target, Some(-16.0));
+                assert_eq!(s.audio.true_peak_limit, Some(-2.0));
+            }
+            _ => panic!("expected stems settings"),
+        }
+
+        assert!(
+            settings_from_flags(&ExportFlags {
+                stem_format: Some(StemFormatArg::Wav),
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            settings_from_flags(&ExportFlags {
+                format: Some(ExportFormat::Stems),
+                resolution: Some("1280x720".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
     #[test]
     fn optimize_filesize_only_for_mp4() {
         assert!(
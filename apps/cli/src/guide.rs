@@ -126,6 +126,12 @@ fn build() -> Guide {
                 used_by: "caps, mcp",
                 description: "Overrides the OS-stored Cap agent credential for headless use. Mint one in the Cap dashboard under Settings -> Account.",
             },
+            EnvVar {
+                name: "CAP_RECORDING_KEY_FILE",
+                required: false,
+                used_by: "record --encrypt, export, project, editing commands",
+                description: "Keyfile holding the master key for encrypted studio projects. Defaults to a key stored in the OS keyring.",
+            },
             EnvVar {
                 name: "CAP_NO_MODIFY_PATH",
                 required: false,
@@ -169,7 +175,22 @@ fn build() -> Guide {
             CommandDoc {
                 requires_duration: false,
                 notes: Some(
-                    "The `started` then `stopped` sequence applies to the foreground run. With `--detach` the stream emits only `started` (or `error`) and returns immediately with recordingId+pid; the `stopped` event is delivered by `cap record stop`.",
+                    "The `started` then `stopped` sequence applies to the foreground run. With `--detach` the stream emits only `started` (or `error`) and returns immediately with recordingId+pid; the `stopped` event is delivered by `cap record stop`. \
+                     `--stream-url rtmp://…|srt://…` also streams the screen and one audio track live; \
+                     connection drops are retried with backoff and logged to stderr without stopping the recording. \
+                     `--input <file|frames/%05d.png|->` records a media file, image sequence or piped frames in place of a screen (studio, cursor-less; \
+                     raw frames need `--input-size WxH`); it replaces `--screen`/`--window` and stops at the end of the input when `--duration` is omitted. \
+                     Pipeline health events are appended to `health.jsonl` in the project; `--metrics-addr 127.0.0.1:9464` serves Prometheus counters at /metrics while recording. \
+                     `--duration` accepts seconds or units (90s, 45m, 1h30m); `--stop-after-idle 2m` stops once mouse and keyboard have been idle that long. \
+                     `--mode replay` keeps only the last `--replay-length` (default 5m) on disk; stopping it saves that buffer as an instant project. \
+                     Repeat `--mic` in studio mode to record each microphone as its own track, mixed and adjusted per track in the editor. \
+                     `--follow-window <id>` records the screen under a window cropped to it as it moves, letterboxed to the window's starting size; its cursor is always drawn into the video. \
+                     On Linux, `--app-audio <name|pid>` (repeatable) records system audio from only those applications and `--exclude-app-audio` from every other one; \
+                     `cap targets audio-apps` lists the applications playing audio. \
+                     `--screen-filter` (repeatable, applied in order) burns filters into the screen before encoding: `blur=X,Y,W,H`, `watermark=TEXT`, `timestamp` or `downscale=WxH`, \
+                     with an optional corner for overlays such as `watermark:top-left=TEXT`; `--camera-filter` does the same for the camera on Linux. \
+                     `--encrypt` (studio) encrypts the project's files as they are written, with a key from `--encryption-key-file`, \
+                     CAP_RECORDING_KEY_FILE or the OS keyring; export and `cap project` decrypt them transparently.",
                 ),
                 ..cmd(
                     "record start",
@@ -184,12 +205,47 @@ fn build() -> Guide {
                 OutputMode::Ndjson,
                 &["stopped", "error"],
             ),
-            cmd(
-                "record status",
-                "List active detached recording sessions.",
-                OutputMode::SingleJson,
-                &[],
-            ),
+            CommandDoc {
+                notes: Some(
+                    "Works on a running `--mode replay` recording, picked with --id or --path (--path also finds foreground ones). \
+                     The recording keeps running; `--last 60s` saves only the newest minute, rounded out to whole segments.",
+                ),
+                ..cmd(
+                    "record save-replay",
+                    "Save a replay buffer's recent footage as a new instant .cap project. JSON emits {path,durationSecs}.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "`--follow` instead streams one detached recording's health events (from its `health.jsonl`) as NDJSON \
+                     objects with `timestampMs` and a camelCase `type` such as stalled, frameDropRateHigh or diskSpaceLow, \
+                     and exits once the recording stops. Pick the recording with --id or --path when several are active.",
+                ),
+                ..cmd(
+                    "record status",
+                    "List active detached recording sessions.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Takes one trigger (`--at 14:00`, `--cron \"0 2 * * 1-5\"` or `--when-window <title>`) plus any `record start` flags, \
+                     saves the schedule under ~/.cap/schedules and starts a background scheduler if none is running. Each firing starts a \
+                     detached recording, so `record status`/`record stop` manage it. `--when-window` records the matched window unless a \
+                     target is given. Any later `cap` command restarts the scheduler if it stopped (e.g. after a reboot). \
+                     `record schedule list|remove <id>` manage schedules; `record schedule run` runs the scheduler in the foreground, e.g. \
+                     from a login item so schedules resume at login without running `cap`.
+                ),
+                ..cmd(
+                    "record schedule",
+                    "Schedule a recording at a time, on a cron expression, or when a window appears.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
             CommandDoc {
                 notes: Some(
                     "EXCEPTION: export NDJSON uses PascalCase `type` tags and snake_case fields \
@@ -377,6 +433,100 @@ fn build() -> Guide {
                 OutputMode::SingleJson,
                 &[],
             ),
+            CommandDoc {
+                notes: Some(
+                    "Uses Vision on macOS, Windows.Media.Ocr on Windows and the `tesseract` binary \
+                     elsewhere. --report is a dry run; without it masks are appended to \
+                     project-config.json on new mask lanes.",
+                ),
+                ..cmd(
+                    "project redact",
+                    "OCR the output timeline and blur emails, API keys, card numbers and --list regexes with keyframed sensitive masks.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Copies the file into the project as a new recording segment and inserts it \
+                     at --at (default: the end). Later timeline items move back by the clip's \
+                     length; a video's own audio joins the mix.",
+                ),
+                ..cmd(
+                    "project import-clip",
+                    "Place an intro, B-roll clip or title card (MP4/MOV/WebM/PNG/JPEG) on the timeline, optionally with --transition.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Sources play back to back; clips with a different size or frame rate are letterboxed and listed under warnings.",
+                ),
+                ..cmd(
+                    "project merge",
+                    "Combine several studio projects into a new multi-segment project (-o <dir>), keeping cursors, keyboard events and captions aligned.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Lines look like `cut 00:10-00:14`, `speed 01:00-02:00 x2`, `zoom at 00:30 \
+                     for 3s on cursor`, `text \"Step 1\" at 00:05` or `scene camera-only \
+                     00:00-00:04`, with times in recording time. Clips, zooms, scenes and text \
+                     are replaced; other tracks are kept. --dry-run adds the compiled timeline.",
+                ),
+                ..cmd(
+                    "project apply-edits",
+                    "Apply a reviewable YAML/JSON edit script to a project's timeline.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Renders the edited output with the GPU renderer. Writes frame-NNN.png, a \
+                     timestamped contact-sheet.png, sprite.png and thumbnails.vtt (cues point at \
+                     sprite.png#xywh=...) into -o. --scene-detect samples every --sample-interval \
+                     seconds and keeps up to --count frames where the picture changes most.",
+                ),
+                ..cmd(
+                    "project thumbnails",
+                    "Render evenly spaced or scene-change thumbnails, a contact sheet and a WebVTT scrubbing sprite.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Studio recordings only. Times are seconds of recording time with segments \
+                     back to back; positions are 0-1 over the recorded display. Writes \
+                     heatmap.png (clicks over a display frame from the busiest segment) and \
+                     input-analysis.json (clicks, dwells, typingBursts, idleSpans, summary) into -o.",
+                ),
+                ..cmd(
+                    "project analyze-input",
+                    "Summarise recorded clicks, cursor dwell, typing and idle time, with a click heatmap.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
+            CommandDoc {
+                notes: Some(
+                    "Uses the timeline's keyboard track (output time) when it has segments, \
+                     otherwise groups the recorded key presses with the project's keyboard \
+                     settings (recording time). Writes shortcuts.vtt, shortcuts.srt, \
+                     shortcuts.json and steps.md into -o, plus steps/step-NNN.png rendered as \
+                     each shortcut ends unless --no-frames is given.",
+                ),
+                ..cmd(
+                    "project shortcuts",
+                    "Export grouped key presses as caption tracks, JSON and a Markdown steps guide.",
+                    OutputMode::SingleJson,
+                    &[],
+                )
+            },
             cmd(
                 "version",
                 "CLI version + execution context (distribution, bundled binaries).",
@@ -0,0 +1,415 @@
+//! Health telemetry for recordings run by `cap record`.
+//!
+//! The recorder appends every pipeline health event (frame drops, stalls, audio gaps, muxer crashes,
+//! low disk, …) to `health.jsonl` in the project, which `cap record status --follow` tails. With
+//! `--metrics-addr` it also serves the process-wide recording counters as a Prometheus `/metrics`
+//! endpoint, so long unattended recordings can be alerted on before they silently degrade.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{Read, Seek, SeekFrom, Write as _},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use cap_recording::{HealthReceiver, PipelineHealthEvent, metrics::MetricsSnapshot};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{debug, info, warn};
+
+use crate::{OutputFormat, write_json_line};
+
+pub const HEALTH_LOG_FILE_NAME: &str = "health.jsonl";
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Health events seen so far, by `type`, for the metrics endpoint.
+#[derive(Clone, Default)]
+pub struct HealthCounts(Arc<Mutex<BTreeMap<String, u64>>>);
+
+impl HealthCounts {
+    fn increment(&self, kind: &str) {
+        let mut counts = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        *counts.entry(kind.to_string()).or_default() += 1;
+    }
+
+    fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthLogEntry<'a> {
+    timestamp_ms: u64,
+    #[serde(flatten)]
+    event: &'a PipelineHealthEvent,
+}
+
+/// Drains `rx` into `<project>/health.jsonl` for as long as the recording runs, logging each event
+/// and counting it in `counts`.
+pub fn spawn_health_log(mut rx: HealthReceiver, project_path: &Path, counts: HealthCounts) {
+    let log_path = project_path.join(HEALTH_LOG_FILE_NAME);
+
+    tokio::spawn(async move {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .inspect_err(|e| warn!("Could not open {}: {e}", log_path.display()))
+            .ok();
+
+        while let Some(event) = rx.recv().await {
+            match &event {
+                PipelineHealthEvent::SourceRestarted
+                | PipelineHealthEvent::StreamConnected { .. } => {
+                    info!(?event, "Recording health event")
+                }
+                _ => warn!(?event, "Recording health event"),
+            }
+
+            let entry = HealthLogEntry {
+                timestamp_ms: now_ms(),
+                event: &event,
+            };
+            let Ok(line) = serde_json::to_value(&entry) else {
+                continue;
+            };
+            if let Some(kind) = line.get("type").and_then(Value::as_str) {
+                counts.increment(kind);
+            }
+
+            if let Some(file) = file.as_mut()
+                && let Err(e) = writeln!(file, "{line}").and_then(|()| file.flush())
+            {
+                warn!("Could not write {}: {e}", log_path.display());
+            }
+        }
+    });
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Prints the health log of the recording at `project_path`, then keeps printing new events while
+/// `is_live` returns true. Text output is one event per line; JSON output is the log's own NDJSON.
+pub async fn follow_log(
+    project_path: &Path,
+    format: OutputFormat,
+    mut is_live: impl FnMut() -> bool,
+) -> Result<(), String> {
+    let log_path = project_path.join(HEALTH_LOG_FILE_NAME);
+    let mut offset = 0;
+    let mut pending = String::new();
+
+    loop {
+        // Checked before reading so the events written right before the recording stopped are
+        // still printed.
+        let live = is_live();
+
+        if let Ok(mut file) = std::fs::File::open(&log_path) {
+            file.seek(SeekFrom::Start(offset))
+                .map_err(|e| format!("Could not read {}: {e}", log_path.display()))?;
+            let mut chunk = String::new();
+            let read = file
+                .read_to_string(&mut chunk)
+                .map_err(|e| format!("Could not read {}: {e}", log_path.display()))?;
+            offset += read as u64;
+            pending.push_str(&chunk);
+
+            while let Some(end) = pending.find('\n') {
+                let line: String = pending.drain(..=end).collect();
+                print_log_line(line.trim_end(), format)?;
+            }
+        }
+
+        if !live {
+            return Ok(());
+        }
+
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+    }
+}
+
+fn print_log_line(line: &str, format: OutputFormat) -> Result<(), String> {
+    let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(line) else {
+        return Ok(());
+    };
+
+    if format == OutputFormat::Json {
+        return write_json_line(&fields);
+    }
+
+    let time = fields
+        .remove("timestampMs")
+        .and_then(|ms| ms.as_i64())
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let kind = fields
+        .remove("type")
+        .and_then(|kind| kind.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let details = fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => format!("{key}={value}"),
+            value => format!("{key}={value}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    println!("{time}  {kind}  {details}");
+    Ok(())
+}
+
+/// Serves `GET /metrics` on `listener` until the process exits.
+pub fn spawn_metrics_server(listener: TcpListener, project_path: PathBuf, counts: HealthCounts) {
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    debug!("Metrics endpoint accept failed: {e}");
+                    continue;
+                }
+            };
+
+            let project_path = project_path.clone();
+            let counts = counts.clone();
+            tokio::spawn(async move {
+                if let Err(e) = respond(stream, &project_path, &counts).await {
+                    debug!("Metrics request failed: {e}");
+                }
+            });
+        }
+    });
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    project_path: &Path,
+    counts: &HealthCounts,
+) -> Result<(), String> {
+    let mut head = Vec::new();
+    let mut buffer = [0_u8; 1024];
+    tokio::time::timeout(REQUEST_TIMEOUT, async {
+        while !head.windows(4).any(|window| window == b"\r\n\r\n")
+            && head.len() < MAX_REQUEST_HEAD_BYTES
+        {
+            let read = stream.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            head.extend_from_slice(&buffer[..read]);
+        }
+        Ok::<_, std::io::Error>(())
+    })
+    .await
+    .map_err(|_| "request timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    let response = if method == "GET" && path == "/metrics" {
+        let project_path = project_path.to_path_buf();
+        let bytes_written = tokio::task::spawn_blocking(move || dir_size(&project_path))
+            .await
+            .unwrap_or_default();
+        let body = render_metrics(
+            &cap_recording::metrics::snapshot(),
+            bytes_written,
+            &counts.snapshot(),
+        );
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    } else {
+        let body = "Not found. Metrics are served at /metrics.\n";
+        format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    };
+
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or_default(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn render_metrics(
+    snapshot: &MetricsSnapshot,
+    bytes_written: u64,
+    health_events: &BTreeMap<String, u64>,
+) -> String {
+    let mut out = String::new();
+
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+        for (suffix, value) in samples {
+            let _ = writeln!(out, "{name}{suffix} {value}");
+        }
+    };
+
+    metric(
+        "cap_recording_frames_captured_total",
+        "counter",
+        "Video frames delivered to an encoder.",
+        &[(String::new(), snapshot.frames_captured.to_string())],
+    );
+    metric(
+        "cap_recording_frames_dropped_total",
+        "counter",
+        "Frames a capture source dropped because its pipeline stayed full.",
+        &snapshot
+            .frames_dropped
+            .iter()
+            .map(|(source, count)| {
+                (
+                    format!("{{source=\"{}\"}}", escape_label(source)),
+                    count.to_string(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    metric(
+        "cap_recording_encode_latency_seconds",
+        "summary",
+        "Time the video encoder spent accepting each frame.",
+        &[
+            (
+                "_sum".to_string(),
+                snapshot.encode_time.as_secs_f64().to_string(),
+            ),
+            ("_count".to_string(), snapshot.frames_encoded.to_string()),
+        ],
+    );
+    metric(
+        "cap_recording_bytes_written",
+        "gauge",
+        "Size of the recording's project directory on disk.",
+        &[(String::new(), bytes_written.to_string())],
+    );
+    metric(
+        "cap_recording_mixer_stalls_total",
+        "counter",
+        "Audio mixer stalls waiting on a source past its buffering window.",
+        &[(String::new(), snapshot.mixer_stalls.to_string())],
+    );
+    metric(
+        "cap_recording_health_events_total",
+        "counter",
+        "Pipeline health events, by type.",
+        &health_events
+            .iter()
+            .map(|(kind, count)| {
+                (
+                    format!("{{type=\"{}\"}}", escape_label(kind)),
+                    count.to_string(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_text_format() {
+        let snapshot = MetricsSnapshot {
+            frames_captured: 120,
+            frames_encoded: 118,
+            encode_time: Duration::from_millis(590),
+            mixer_stalls: 2,
+            frames_dropped: BTreeMap::from([("screen-video", 3)]),
+        };
+        let health_events = BTreeMap::from([("stalled".to_string(), 4)]);
+
+        let body = render_metrics(&snapshot, 4096, &health_events);
+
+        assert!(body.contains("# TYPE cap_recording_frames_captured_total counter\n"));
+        assert!(body.contains("cap_recording_frames_captured_total 120\n"));
+        assert!(body.contains("cap_recording_frames_dropped_total{source=\"screen-video\"} 3\n"));
+        assert!(body.contains("cap_recording_encode_latency_seconds_sum 0.59\n"));
+        assert!(body.contains("cap_recording_encode_latency_seconds_count 118\n"));
+        assert!(body.contains("cap_recording_bytes_written 4096\n"));
+        assert!(body.contains("cap_recording_mixer_stalls_total 2\n"));
+        assert!(body.contains("cap_recording_health_events_total{type=\"stalled\"} 4\n"));
+    }
+
+    #[test]
+    fn health_log_entries_are_flat_camel_case() {
+        let event = PipelineHealthEvent::Stalled {
+            source: "screen-video".to_string(),
+            waited_ms: 520,
+        };
+        let line = serde_json::to_value(HealthLogEntry {
+            timestamp_ms: 1,
+            event: &event,
+        })
+        .unwrap();
+
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "timestampMs": 1,
+                "type": "stalled",
+                "source": "screen-video",
+                "waitedMs": 520,
+            })
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::{Path, PathBuf};
+
+use cap_project::{
+    AudioMeta, ClipConfiguration, ClipTransitionType, ImportedMediaKind, ImportedMediaMeta,
+    MultipleSegment, RecordingMeta, RecordingMetaInner, TimelineSegment, VideoMeta,
+};
+use cap_rendering::{DEFAULT_STILL_DURATION, ProjectRecordingsMeta, Video};
+use clap::ValueEnum;
+use relative_path::RelativePathBuf;
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "webm"];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum TransitionArg {
+    CrossFade,
+    FadeThroughBlack,
+}
+
+impl From<TransitionArg> for ClipTransitionType {
+    fn from(value: TransitionArg) -> Self {
+        match value {
+            TransitionArg::CrossFade => ClipTransitionType::CrossFade,
+            TransitionArg::FadeThroughBlack => ClipTransitionType::FadeThroughBlack,
+        }
+    }
+}
+
+pub struct ImportClipOptions {
+    /// Timeline position; `None` appends.
+    pub at: Option<usize>,
+    /// Seconds to show an image for, or to trim a video to.
+    pub duration: Option<f64>,
+    pub transition: Option<(ClipTransitionType, f64)>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportClipOutput {
+    project_path: PathBuf,
+    path: RelativePathBuf,
+    kind: ImportedMediaKind,
+    recording_segment: u32,
+    timeline_index: usize,
+    duration: f64,
+    has_audio: bool,
+}
+
+fn media_kind(path: &Path) -> Result<ImportedMediaKind, String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        Ok(ImportedMediaKind::Video)
+    } else if cap_rendering::decoder::is_still_image(path) {
+        Ok(ImportedMediaKind::Image)
+    } else {
+        Err(format!(
+            "Unsupported clip '{}': expected an MP4, MOV, WebM, PNG or JPEG file",
+            path.display()
+        ))
+    }
+}
+
+fn has_audio_stream(path: &Path) -> bool {
+    ffmpeg::format::input(path)
+        .map(|input| input.streams().best(ffmpeg::media::Type::Audio).is_some())
+        .unwrap_or(false)
+}
+
+/// Same layout as recorded segments, so the clip's folder can't collide with
+/// one the project already has.
+fn unique_segment_dir(project_path: &Path, index: usize) -> Result<(PathBuf, String), String> {
+    let segments_root = project_path.join("content").join("segments");
+    let mut counter = 0;
+    loop {
+        let name = if counter == 0 {
+            format!("segment-{index}")
+        } else {
+            format!("segment-{index}-import-{counter}")
+        };
+        let path = segments_root.join(&name);
+        if !path.exists() {
+            std::fs::create_dir_all(&path)
+                .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+            return Ok((path, format!("content/segments/{name}")));
+        }
+        counter += 1;
+    }
+}
+
+pub fn run(
+    project_path: PathBuf,
+    source_path: PathBuf,
+    options: ImportClipOptions,
+    format: OutputFormat,
+) -> Result<(), String> {
+    if !source_path.is_file() {
+        return Err(format!("Clip not found: {}", source_path.display()));
+    }
+    let kind = media_kind(&source_path)?;
+    if let Some(duration) = options.duration
+        && (!duration.is_finite() || duration <= 0.0)
+    {
+        return Err("--duration must be a positive number of seconds".to_string());
+    }
+    if let Some((_, duration)) = options.transition
+        && (!duration.is_finite() || duration <= 0.0)
+    {
+        return Err("--transition-duration must be a positive number of seconds".to_string());
+    }
+
+    let mut project = ProjectAccess::open(&project_path)?;
+    let mut meta = RecordingMeta::load_for_project(project.path())
+        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let mut config = meta.project_config();
+    let RecordingMetaInner::Studio(studio) = &mut meta.inner else {
+        return Err("Clips can only be imported into studio recordings".to_string());
+    };
+
+    let mut timeline = match config.timeline.take() {
+        Some(timeline) => timeline,
+        None => ProjectRecordingsMeta::new(project.path(), studio)?
+            .default_timeline()
+            .ok_or_else(|| "Project has no timeline to place the clip on".to_string())?,
+    };
+    let project_fps = studio.max_fps();
+
+    let segments = studio.ensure_multiple_segments(project.path());
+    let index = segments.segments.len();
+    let (segment_dir, relative_dir) = unique_segment_dir(project.path(), index)?;
+    let extension = source_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let relative_path = RelativePathBuf::from(format!("{relative_dir}/display.{extension}"));
+    let target_path = relative_path.to_path(project.path());
+
+    std::fs::copy(&source_path, &target_path).map_err(|e| {
+        format!(
+            "Failed to copy {} into the project: {e}",
+            source_path.display()
+        )
+    })?;
+
+    let probe = match kind {
+        ImportedMediaKind::Video => Video::new(&target_path, 0.0),
+        ImportedMediaKind::Image => Video::still(
+            &target_path,
+            0.0,
+            options.duration.unwrap_or(DEFAULT_STILL_DURATION),
+            project_fps,
+        ),
+    };
+    let video = match probe {
+        Ok(video) if video.duration > 0.0 => video,
+        Ok(_) => {
+            let _ = std::fs::remove_dir_all(&segment_dir);
+            return Err(format!("{} has no playable frames", source_path.display()));
+        }
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&segment_dir);
+            return Err(format!("Failed to read {}: {e}", source_path.display()));
+        }
+    };
+    let duration = options
+        .duration
+        .map_or(video.duration, |duration| duration.min(video.duration));
+    let has_audio = kind == ImportedMediaKind::Video && has_audio_stream(&target_path);
+
+    let source_name = source_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let segment = MultipleSegment {
+        display: VideoMeta {
+            path: relative_path.clone(),
+            fps: video.fps.max(1),
+            start_time: Some(0.0),
+            device_id: None,
+        },
+        camera: None,
+        mic: None,
+        system_audio: has_audio.then(|| AudioMeta {
+            path: relative_path.clone(),
+            start_time: Some(0.0),
+            device_id: None,
+            gap_summary: None,
+        }),
+        cursor: None,
+        keyboard: None,
+        display_notch: None,
+        additional_videos: Vec::new(),
+        additional_mics: Vec::new(),
+        imported: Some(ImportedMediaMeta {
+            kind,
+            source_name: source_name.clone(),
+            duration: (kind == ImportedMediaKind::Image).then_some(duration),
+        }),
+    };
+
+    let recording_segment = index as u32;
+    config.clips.retain(|clip| clip.index != recording_segment);
+    config.clips.push(ClipConfiguration {
+        index: recording_segment,
+        offsets: segment.calculate_audio_offsets(),
+        offsets_auto_calculated: true,
+    });
+    segments.segments.push(segment);
+
+    let timeline_index = options
+        .at
+        .unwrap_or(timeline.segments.len())
+        .min(timeline.segments.len());
+    timeline.insert_segment(
+        timeline_index,
+        TimelineSegment {
+            recording_clip: recording_segment,
+            timescale: 1.0,
+            start: 0.0,
+            end: duration,
+            name: Some(source_name),
+            speed_audio_mode: None,
+        },
+        options.transition,
+    );
+    config.timeline = Some(timeline);
+
+    meta.save_for_project()
+        .map_err(|e| format!("Failed to save recording meta: {e:?}"))?;
+    config
+        .write(project.path())
+        .map_err(|e| format!("Failed to write project config: {e}"))?;
+    project.persist()?;
+
+    let output = ImportClipOutput {
+        project_path,
+        path: relative_path,
+        kind,
+        recording_segment,
+        timeline_index,
+        duration,
+        has_audio,
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&output),
+        OutputFormat::Text => {
+            println!(
+                "Imported {} as clip {} at timeline position {} ({:.2}s{})",
+                output.path,
+                output.recording_segment,
+                output.timeline_index,
+                output.duration,
+                if output.has_audio { ", with audio" } else { "" }
+            );
+            Ok(())
+        }
+    }
+}
@@ -3,6 +3,8 @@ mod agent_auth;
 mod agent_client;
 mod agents;
 mod analytics;
+mod analyze_input;
+mod apply_edits;
 mod atomic;
 mod automation;
 mod caps;
@@ -10,20 +12,28 @@ mod confirmation;
 mod credentials;
 mod developers;
 mod doctor;
+mod encryption;
 mod export;
 mod guide;
+mod health;
+mod import_clip;
 mod jobs;
 mod library;
 mod mcp;
+mod merge;
 mod notifications;
 mod organizations;
 mod project;
 mod record;
 mod recordings;
+mod redact;
+mod schedule;
 mod screenshot;
 mod selftest;
 mod session;
+mod shortcuts;
 mod targets;
+mod thumbnails;
 mod update;
 mod upload;
 
@@ -135,6 +145,9 @@ ENVIRONMENT
   CAP_NO_MODIFY_PATH  Set to skip editing shell profiles during `cap desktop install-cli`.
   CAP_DESKTOP_FORCE_INSTALL
                       Force the web installer scripts to replace Cap Desktop before linking the CLI.
+  CAP_RECORDING_KEY_FILE
+                      Keyfile for projects recorded with `cap record --encrypt`; used instead of
+                      the key in the OS keyring.
 
 TYPICAL AGENT WORKFLOW
   cap doctor --json                          # verify permissions & capture readiness
@@ -280,8 +293,12 @@ enum RecordCommands {
     Start(RecordStart),
     /// Stop a detached recording started with `cap record start --detach`
     Stop(record::RecordStopArgs),
-    /// List active and recent detached recording sessions
-    Status(FormatArgs),
+    /// List active and recent detached recording sessions, or follow one's health events
+    Status(record::RecordStatusArgs),
+    /// Record at a set time, on a cron schedule, or when a window appears
+    Schedule(schedule::ScheduleArgs),
+    /// Save the last part of a replay buffer (`--mode replay`) as a new '.cap' project
+    SaveReplay(record::SaveReplayArgs),
     /// Internal: background worker for detached recordings (do not call directly)
     #[command(name = "__session-run", hide = true)]
     SessionRun(record::SessionRunArgs),
@@ -314,6 +331,8 @@ enum TargetCommands {
     Cameras(FormatArgs),
     /// List microphones available for capturing
     Mics(FormatArgs),
+    /// List applications playing audio, for `cap record --app-audio` (Linux)
+    AudioApps(FormatArgs),
 }
 
 #[derive(Args)]
@@ -342,6 +361,20 @@ enum ProjectCommands {
     Validate(ProjectTarget),
     /// Read or write a project's editor configuration (project-config.json)
     Config(ProjectConfigArgs),
+    /// Find emails, API keys, card numbers and custom patterns on screen and blur them
+    Redact(ProjectRedactArgs),
+    /// Add an MP4, MOV, WebM, PNG or JPEG file to the project's timeline as a clip
+    ImportClip(ProjectImportClipArgs),
+    /// Combine several studio projects, in order, into a new project
+    Merge(ProjectMergeArgs),
+    /// Compile a YAML/JSON edit script (cuts, speed, zoom, text, scenes) into the project's timeline
+    ApplyEdits(ProjectApplyEditsArgs),
+    /// Render evenly spaced or scene-change frames, a contact sheet and a WebVTT thumbnail sprite
+    Thumbnails(ProjectThumbnailsArgs),
+    /// Turn recorded clicks, cursor moves and key presses into a heatmap and interaction timeline
+    AnalyzeInput(ProjectAnalyzeInputArgs),
+    /// Export recorded keyboard shortcuts as WebVTT/SRT captions, JSON and a Markdown steps guide
+    Shortcuts(ProjectShortcutsArgs),
 }
 
 #[derive(Args)]
@@ -375,6 +408,147 @@ struct ProjectConfigSet {
     format: OutputFormat,
 }
 
+#[derive(Args)]
+struct ProjectRedactArgs {
+    project_path: PathBuf,
+    /// JSON redaction list (`{"rules": [{"kind": "email"}, {"kind": "regex", "pattern": "..."}]}`).
+    /// Defaults to emails, API keys and credit card numbers
+    #[arg(long)]
+    list: Option<PathBuf>,
+    /// Only report what would be blurred; leave project-config.json untouched
+    #[arg(long)]
+    report: bool,
+    /// Seconds between OCR samples
+    #[arg(long, default_value_t = 0.5)]
+    interval: f64,
+    /// Extra margin around each match, as a fraction of the output frame
+    #[arg(long, default_value_t = 0.004)]
+    padding: f64,
+    #[arg(long)]
+    force_ffmpeg_decoder: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectImportClipArgs {
+    project_path: PathBuf,
+    /// Video or image file to import
+    file: PathBuf,
+    /// Timeline position to insert at (0 = before the first clip). Defaults to the end
+    #[arg(long)]
+    at: Option<usize>,
+    /// Seconds to show an image for (default 5), or to trim a video to
+    #[arg(long)]
+    duration: Option<f64>,
+    /// Transition into and out of the new clip
+    #[arg(long, value_enum)]
+    transition: Option<import_clip::TransitionArg>,
+    #[arg(long, default_value_t = 0.5, requires = "transition")]
+    transition_duration: f64,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectMergeArgs {
+    /// Projects to combine, in playback order
+    #[arg(required = true, num_args = 2..)]
+    projects: Vec<PathBuf>,
+    /// Path of the new .cap project; must not exist yet
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Name of the merged project. Defaults to the first project's name
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectApplyEditsArgs {
+    project_path: PathBuf,
+    /// Edit script (.yaml, .yml or .json)
+    script: PathBuf,
+    /// Validate and print the compiled timeline without writing project-config.json
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectAnalyzeInputArgs {
+    project_path: PathBuf,
+    /// Directory for heatmap.png and input-analysis.json
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Distance the cursor can drift while dwelling, as a fraction of the display (0-1)
+    #[arg(long, default_value_t = 0.02)]
+    dwell_radius: f64,
+    /// Seconds the cursor must stay put to count as a dwell
+    #[arg(long, default_value_t = 1.0)]
+    min_dwell: f64,
+    /// Longest pause in seconds between key presses within one typing burst
+    #[arg(long, default_value_t = 1.0)]
+    typing_gap: f64,
+    /// Seconds without any input that count as idle
+    #[arg(long, default_value_t = 5.0)]
+    min_idle: f64,
+    #[arg(long)]
+    force_ffmpeg_decoder: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectShortcutsArgs {
+    project_path: PathBuf,
+    /// Directory for shortcuts.vtt, shortcuts.srt, shortcuts.json, steps.md and steps/
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Width of the screenshot rendered for each step
+    #[arg(long, default_value_t = 1280)]
+    frame_width: u32,
+    /// Skip rendering step screenshots
+    #[arg(long)]
+    no_frames: bool,
+    #[arg(long)]
+    force_ffmpeg_decoder: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ProjectThumbnailsArgs {
+    project_path: PathBuf,
+    /// Directory for the frames, contact-sheet.png, sprite.png and thumbnails.vtt
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Number of frames (the maximum with --scene-detect)
+    #[arg(long, default_value_t = 12)]
+    count: u32,
+    /// Pick frames where the picture changes instead of spacing them evenly
+    #[arg(long)]
+    scene_detect: bool,
+    /// Average luma change (0-1) that counts as a scene change
+    #[arg(long, default_value_t = 0.1, requires = "scene_detect")]
+    scene_threshold: f64,
+    /// Seconds between frames sampled for scene detection
+    #[arg(long, default_value_t = 0.5, requires = "scene_detect")]
+    sample_interval: f64,
+    /// Thumbnail width in pixels
+    #[arg(long, default_value_t = 320)]
+    width: u32,
+    /// Tiles per row in the contact sheet and sprite
+    #[arg(long, default_value_t = 4)]
+    columns: u32,
+    #[arg(long)]
+    force_ffmpeg_decoder: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
 #[derive(Args)]
 struct RecordingsArgs {
     #[command(subcommand)]
@@ -552,18 +726,27 @@ async fn run(cli: Cli) -> Result<(), String> {
         return print_welcome(json);
     };
 
+    if !matches!(
+        &command,
+        Commands::Record(RecordArgs {
+            command: Some(RecordCommands::Schedule(args)),
+            ..
+        }) if args.runs_scheduler()
+    ) {
+        schedule::revive_scheduler();
+    }
+
     match command {
         Commands::Export(e) => e.run(json).await,
         Commands::ExportPreview(e) => e.run().await,
         Commands::Selftest(args) => args.run(json).await,
-        Commands::Project(args) => args.run(json),
+        Commands::Project(args) => args.run(json).await,
         Commands::Record(RecordArgs { command, args }) => match command {
             Some(RecordCommands::Start(args)) => args.run(json).await,
             Some(RecordCommands::Stop(args)) => args.run(json).await,
-            Some(RecordCommands::Status(args)) => {
-                let format = resolve_format(json, args.format);
-                finish_json(format, record::status(format))
-            }
+            Some(RecordCommands::Status(args)) => args.run(json).await,
+            Some(RecordCommands::Schedule(args)) => args.run(json).await,
+            Some(RecordCommands::SaveReplay(args)) => args.run(json).await,
             Some(RecordCommands::SessionRun(args)) => args.run().await,
             Some(RecordCommands::Screens(args)) => {
                 let format = resolve_format(json, args.format);
@@ -686,6 +869,10 @@ impl TargetsArgs {
                 let format = resolve_format(json, args.format);
                 (format, targets::print_mics(format))
             }
+            Some(TargetCommands::AudioApps(args)) => {
+                let format = resolve_format(json, args.format);
+                (format, targets::print_audio_apps(format))
+            }
             None => {
                 let format = resolve_format(json, self.format);
                 (format, targets::print_all(format))
@@ -696,7 +883,7 @@ impl TargetsArgs {
 }
 
 impl ProjectArgs {
-    fn run(self, json: bool) -> Result<(), String> {
+    async fn run(self, json: bool) -> Result<(), String> {
         match self.command {
             // validate emits its own structured report (incl. JSON errors), so it is not wrapped.
             ProjectCommands::Validate(args) => {
@@ -719,6 +906,112 @@ impl ProjectArgs {
                     )
                 }
             },
+            ProjectCommands::Redact(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    redact::run(
+                        args.project_path,
+                        args.list,
+                        cap_redaction::ScanOptions {
+                            interval: args.interval,
+                            padding: args.padding,
+                            force_ffmpeg_decoder: args.force_ffmpeg_decoder,
+                        },
+                        args.report,
+                        format,
+                    )
+                    .await,
+                )
+            }
+            ProjectCommands::ImportClip(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    import_clip::run(
+                        args.project_path,
+                        args.file,
+                        import_clip::ImportClipOptions {
+                            at: args.at,
+                            duration: args.duration,
+                            transition: args
+                                .transition
+                                .map(|kind| (kind.into(), args.transition_duration)),
+                        },
+                        format,
+                    ),
+                )
+            }
+            ProjectCommands::Merge(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    merge::run(args.projects, args.output, args.name, format),
+                )
+            }
+            ProjectCommands::ApplyEdits(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    apply_edits::run(args.project_path, args.script, args.dry_run, format),
+                )
+            }
+            ProjectCommands::Thumbnails(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    thumbnails::run(
+                        args.project_path,
+                        args.output,
+                        thumbnails::ThumbnailsOptions {
+                            count: args.count,
+                            scene_threshold: args.scene_detect.then_some(args.scene_threshold),
+                            sample_interval: args.sample_interval,
+                            width: args.width,
+                            columns: args.columns,
+                            force_ffmpeg_decoder: args.force_ffmpeg_decoder,
+                        },
+                        format,
+                    )
+                    .await,
+                )
+            }
+            ProjectCommands::AnalyzeInput(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    analyze_input::run(
+                        args.project_path,
+                        args.output,
+                        cap_input_analysis::AnalysisOptions {
+                            dwell_radius: args.dwell_radius,
+                            min_dwell: args.min_dwell,
+                            typing_gap: args.typing_gap,
+                            min_idle: args.min_idle,
+                            ..Default::default()
+                        },
+                        args.force_ffmpeg_decoder,
+                        format,
+                    )
+                    .await,
+                )
+            }
+            ProjectCommands::Shortcuts(args) => {
+                let format = resolve_format(json, args.format);
+                finish_json(
+                    format,
+                    shortcuts::run(
+                        args.project_path,
+                        args.output,
+                        shortcuts::ShortcutsOptions {
+                            frame_width: (!args.no_frames).then_some(args.frame_width),
+                            force_ffmpeg_decoder: args.force_ffmpeg_decoder,
+                        },
+                        format,
+                    )
+                    .await,
+                )
+            }
         }
     }
 }
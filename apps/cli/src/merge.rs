@@ -0,0 +1,738 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use cap_project::{
+    AudioMeta, CaptionSegment, CaptionWord, CaptionsData, ClipConfiguration, CursorEvents,
+    CursorMeta, Cursors, MultipleSegment, MultipleSegments, ProjectConfiguration, RecordingMeta,
+    RecordingMetaInner, StudioRecordingMeta, StudioRecordingStatus, TimelineConfiguration,
+    VideoMeta, XY,
+};
+use cap_rendering::ProjectRecordingsMeta;
+use relative_path::RelativePathBuf;
+use serde::Serialize;
+
+use crate::{OutputFormat, write_json};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MergedSource {
+    project_path: PathBuf,
+    segments: usize,
+    width: u32,
+    height: u32,
+    fps: u32,
+    duration: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MergeOutput {
+    project_path: PathBuf,
+    segments: usize,
+    duration: f64,
+    sources: Vec<MergedSource>,
+    /// Sources whose size or frame rate differs from the first one. They're
+    /// letterboxed into the first source's frame on export.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+/// One loaded input, with its timeline resolved so every source can be
+/// appended the same way whether or not it was ever opened in the editor.
+struct Source {
+    meta: RecordingMeta,
+    segments: Vec<MultipleSegment>,
+    cursors: Cursors,
+    config: ProjectConfiguration,
+    timeline: TimelineConfiguration,
+    recordings: ProjectRecordingsMeta,
+}
+
+fn load_source(project_path: &Path) -> Result<Source, String> {
+    // The merged project would hold the media unencrypted.
+    if cap_project::encryption::is_encrypted(project_path) {
+        return Err(format!(
+            "{} is encrypted; encrypted projects can't be merged",
+            project_path.display()
+        ));
+    }
+    let meta = RecordingMeta::load_for_project(project_path).map_err(|e| {
+        format!(
+            "Failed to load recording meta for {}: {e}",
+            project_path.display()
+        )
+    })?;
+    let RecordingMetaInner::Studio(studio) = &meta.inner else {
+        return Err(format!(
+            "{} is an instant recording; only studio projects can be merged",
+            project_path.display()
+        ));
+    };
+    if !matches!(studio.status(), StudioRecordingStatus::Complete) {
+        return Err(format!(
+            "{} isn't a complete recording; recover or finish it first",
+            project_path.display()
+        ));
+    }
+
+    let (segments, cursors) = studio_segments(project_path, studio);
+    if segments.is_empty() {
+        return Err(format!("{} has no segments", project_path.display()));
+    }
+
+    let recordings = ProjectRecordingsMeta::new(&project_path.to_path_buf(), studio)
+        .map_err(|e| format!("{}: {e}", project_path.display()))?;
+    let mut config = meta.project_config();
+    let timeline = match config.timeline.take() {
+        Some(timeline) => timeline,
+        None => recordings
+            .default_timeline()
+            .ok_or_else(|| format!("{} has no playable media", project_path.display()))?,
+    };
+
+    Ok(Source {
+        meta,
+        segments,
+        cursors,
+        config,
+        timeline,
+        recordings,
+    })
+}
+
+/// A studio recording's segments and cursor images. Single-segment recordings
+/// keep their cursor images inside `cursor.json`, so they're read out the same
+/// way the editor converts those recordings.
+fn studio_segments(
+    project_path: &Path,
+    studio: &StudioRecordingMeta,
+) -> (Vec<MultipleSegment>, Cursors) {
+    let mut studio = studio.clone();
+    let inner = studio.ensure_multiple_segments(project_path);
+    (inner.segments.clone(), inner.cursors.clone())
+}
+
+/// Copies a file or fragmented-recording directory.
+fn copy_path(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    if source.is_dir() {
+        std::fs::create_dir_all(target)
+            .map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+        let entries = std::fs::read_dir(source)
+            .map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+            copy_path(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(source, target)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {e}", source.display()))
+    }
+}
+
+/// Copies one segment's media into `content/segments/segment-<index>`,
+/// sharing a single copy between tracks that point at the same file (an
+/// imported clip's video and audio).
+struct SegmentCopier<'a> {
+    source: &'a RecordingMeta,
+    output_path: &'a Path,
+    relative_dir: String,
+    copied: HashMap<RelativePathBuf, RelativePathBuf>,
+}
+
+impl SegmentCopier<'_> {
+    fn copy(&mut self, path: &RelativePathBuf, name: &str) -> Result<RelativePathBuf, String> {
+        if let Some(copied) = self.copied.get(path) {
+            return Ok(copied.clone());
+        }
+
+        let file_name = match path.extension() {
+            Some(extension) => format!("{name}.{extension}"),
+            None => name.to_string(),
+        };
+        let target = RelativePathBuf::from(format!("{}/{file_name}", self.relative_dir));
+        copy_path(&self.source.path(path), &target.to_path(self.output_path))?;
+        self.copied.insert(path.clone(), target.clone());
+        Ok(target)
+    }
+
+    fn copy_video(&mut self, video: &VideoMeta, name: &str) -> Result<VideoMeta, String> {
+        Ok(VideoMeta {
+            path: self.copy(&video.path, name)?,
+            ..video.clone()
+        })
+    }
+
+    fn copy_audio(&mut self, audio: &AudioMeta, name: &str) -> Result<AudioMeta, String> {
+        Ok(AudioMeta {
+            path: self.copy(&audio.path, name)?,
+            ..audio.clone()
+        })
+    }
+}
+
+fn copy_segment(
+    source: &RecordingMeta,
+    segment: &MultipleSegment,
+    output_path: &Path,
+    index: usize,
+    cursor_ids: &HashMap<String, String>,
+) -> Result<MultipleSegment, String> {
+    let mut copier = SegmentCopier {
+        source,
+        output_path,
+        relative_dir: format!("content/segments/segment-{index}"),
+        copied: HashMap::new(),
+    };
+
+    let cursor = match &segment.cursor {
+        Some(path) if source.path(path).is_file() => {
+            let target = RelativePathBuf::from(format!("{}/cursor.json", copier.relative_dir));
+            match CursorEvents::load_from_file(&source.path(path)) {
+                Ok(mut events) => {
+                    for event in &mut events.moves {
+                        if let Some(id) = cursor_ids.get(&event.cursor_id) {
+                            event.cursor_id = id.clone();
+                        }
+                    }
+                    for event in &mut events.clicks {
+                        if let Some(id) = cursor_ids.get(&event.cursor_id) {
+                            event.cursor_id = id.clone();
+                        }
+                    }
+                    let json = serde_json::to_string(&events)
+                        .map_err(|e| format!("Failed to serialize cursor events: {e}"))?;
+                    let target_path = target.to_path(output_path);
+                    if let Some(parent) = target_path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+                    }
+                    std::fs::write(&target_path, json)
+                        .map_err(|e| format!("Failed to write cursor events: {e}"))?;
+                }
+                Err(_) => copy_path(&source.path(path), &target.to_path(output_path))?,
+            }
+            Some(target)
+        }
+        _ => None,
+    };
+
+    // Older recordings keep keyboard events next to the display video
+    // without listing them; write the resolved path so it survives the move.
+    let keyboard_path = segment.keyboard.clone().or_else(|| {
+        let display_dir = segment.display.path.parent()?;
+        [
+            cap_project::KEYBOARD_EVENTS_FILE_NAME,
+            cap_project::LEGACY_KEYBOARD_EVENTS_FILE_NAME,
+        ]
+        .into_iter()
+        .map(|name| display_dir.join(name))
+        .find(|path| source.path(path).is_file())
+    });
+    let keyboard = match keyboard_path {
+        Some(path) if source.path(&path).is_file() => {
+            let name = path.file_stem().unwrap_or("keyboard").to_string();
+            Some(copier.copy(&path, &name)?)
+        }
+        _ => None,
+    };
+
+    Ok(MultipleSegment {
+        display: copier.copy_video(&segment.display, "display")?,
+        camera: segment
+            .camera
+            .as_ref()
+            .map(|camera| copier.copy_video(camera, "camera"))
+            .transpose()?,
+        mic: segment
+            .mic
+            .as_ref()
+            .map(|mic| copier.copy_audio(mic, "mic"))
+            .transpose()?,
+        additional_mics: segment
+            .additional_mics
+            .iter()
+            .map(|additional| {
+                Ok(cap_project::AdditionalAudioMeta {
+                    audio: copier.copy_audio(&additional.audio, &additional.id)?,
+                    ..additional.clone()
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        system_audio: segment
+            .system_audio
+            .as_ref()
+            .map(|audio| copier.copy_audio(audio, "system_audio"))
+            .transpose()?,
+        cursor,
+        keyboard,
+        display_notch: segment.display_notch,
+        additional_videos: segment
+            .additional_videos
+            .iter()
+            .map(|additional| {
+                Ok(cap_project::AdditionalVideoMeta {
+                    video: copier.copy_video(&additional.video, &additional.id)?,
+                    ..additional.clone()
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        imported: segment.imported.clone(),
+    })
+}
+
+/// Copies a source's cursor images under ids prefixed with the source's
+/// position, returning the old-to-new id map for its cursor events.
+fn copy_cursors(
+    source: &RecordingMeta,
+    cursors: &Cursors,
+    output_path: &Path,
+    prefix: &str,
+    merged: &mut HashMap<String, CursorMeta>,
+) -> Result<HashMap<String, String>, String> {
+    let entries: Vec<(String, CursorMeta)> = match cursors {
+        Cursors::Correct(map) => map
+            .iter()
+            .map(|(id, cursor)| (id.clone(), cursor.clone()))
+            .collect(),
+        Cursors::Old(map) => map
+            .iter()
+            .filter_map(|(id, path)| {
+                let image_path = RelativePathBuf::from_path(path).ok()?;
+                Some((
+                    id.clone(),
+                    CursorMeta {
+                        image_path,
+                        hotspot: XY::new(0.0, 0.0),
+                        shape: None,
+                    },
+                ))
+            })
+            .collect(),
+    };
+
+    let mut ids = HashMap::new();
+    for (id, cursor) in entries {
+        let image = source.path(&cursor.image_path);
+        if !image.is_file() {
+            continue;
+        }
+
+        let new_id = format!("{prefix}{id}");
+        let extension = cursor.image_path.extension().unwrap_or("png");
+        let image_path = RelativePathBuf::from(format!("content/cursors/{new_id}.{extension}"));
+        copy_path(&image, &image_path.to_path(output_path))?;
+
+        merged.insert(
+            new_id.clone(),
+            CursorMeta {
+                image_path,
+                ..cursor
+            },
+        );
+        ids.insert(id, new_id);
+    }
+
+    Ok(ids)
+}
+
+/// Copies a timeline audio clip (music, voice-over) to the same
+/// project-relative path, or under a prefixed name if an earlier source
+/// already used that path.
+fn copy_audio_track(
+    source: &RecordingMeta,
+    path: &str,
+    output_path: &Path,
+    prefix: &str,
+) -> Result<String, String> {
+    let source_path = source.project_path.join(path);
+    if !source_path.is_file() {
+        return Ok(path.to_string());
+    }
+
+    let mut relative = RelativePathBuf::from(path);
+    if relative.to_path(output_path).exists() {
+        let file_name = format!("{prefix}{}", relative.file_name().unwrap_or("audio"));
+        relative.set_file_name(file_name);
+    }
+    copy_path(&source_path, &relative.to_path(output_path))?;
+    Ok(relative.into_string())
+}
+
+fn shifted_caption(segment: &CaptionSegment, prefix: &str, offset: f64) -> CaptionSegment {
+    let offset = offset as f32;
+    CaptionSegment {
+        id: format!("{prefix}{}", segment.id),
+        start: segment.start + offset,
+        end: segment.end + offset,
+        text: segment.text.clone(),
+        words: segment
+            .words
+            .iter()
+            .map(|word| CaptionWord {
+                text: word.text.clone(),
+                start: word.start + offset,
+                end: word.end + offset,
+            })
+            .collect(),
+    }
+}
+
+struct CaptionSource<'a> {
+    captions: &'a CaptionsData,
+    timeline: &'a TimelineConfiguration,
+    prefix: String,
+    source_time: f64,
+    output_time: f64,
+}
+
+/// Source-timed captions are re-projected through the edit list by the
+/// editor, so they move by source time. If any input still has legacy
+/// output-timed captions the merge stays in output time, and source-timed
+/// inputs contribute their already-projected track instead.
+fn merged_captions(sources: &[CaptionSource]) -> Option<CaptionsData> {
+    let first = sources.first()?;
+    let source_timed = sources.iter().all(|source| source.captions.source_timed);
+
+    let mut segments = Vec::new();
+    for source in sources {
+        if source_timed {
+            segments.extend(
+                source
+                    .captions
+                    .segments
+                    .iter()
+                    .map(|segment| shifted_caption(segment, &source.prefix, source.source_time)),
+            );
+        } else if !source.captions.source_timed {
+            segments.extend(
+                source
+                    .captions
+                    .segments
+                    .iter()
+                    .map(|segment| shifted_caption(segment, &source.prefix, source.output_time)),
+            );
+        } else {
+            segments.extend(source.timeline.caption_segments.iter().map(|track| {
+                let segment = CaptionSegment {
+                    id: track.id.clone(),
+                    start: track.start as f32,
+                    end: track.end as f32,
+                    text: track.text.clone(),
+                    words: track.words.clone(),
+                };
+                shifted_caption(&segment, &source.prefix, source.output_time)
+            }));
+        }
+    }
+
+    Some(CaptionsData {
+        segments,
+        settings: first.captions.settings.clone(),
+        source_timed,
+    })
+}
+
+pub fn run(
+    inputs: Vec<PathBuf>,
+    output_path: PathBuf,
+    name: Option<String>,
+    format: OutputFormat,
+) -> Result<(), String> {
+    if inputs.len() < 2 {
+        return Err("Pass at least two projects to merge".to_string());
+    }
+    if output_path.exists()
+        && std::fs::read_dir(&output_path)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(true)
+    {
+        return Err(format!(
+            "{} already exists; choose a new output path",
+            output_path.display()
+        ));
+    }
+
+    let sources = inputs
+        .iter()
+        .map(|path| load_source(path))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    std::fs::create_dir_all(&output_path)
+        .map_err(|e| format!("Failed to create {}: {e}", output_path.display()))?;
+
+    match merge(&sources, &output_path, name) {
+        Ok(output) => match format {
+            OutputFormat::Json => write_json(&output),
+            OutputFormat::Text => {
+                for warning in &output.warnings {
+                    eprintln!("warning: {warning}");
+                }
+                println!(
+                    "Merged {} projects ({} segments, {:.1}s) into {}",
+                    output.sources.len(),
+                    output.segments,
+                    output.duration,
+                    output.project_path.display()
+                );
+                Ok(())
+            }
+        },
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&output_path);
+            Err(e)
+        }
+    }
+}
+
+fn merge(
+    sources: &[Source],
+    output_path: &Path,
+    name: Option<String>,
+) -> Result<MergeOutput, String> {
+    let first = &sources[0];
+    let mut config = first.config.clone();
+    config.clips.clear();
+    config.picture_in_picture.clear();
+
+    let mut segments = Vec::new();
+    let mut cursors = HashMap::new();
+    let mut timeline: Option<TimelineConfiguration> = None;
+    let mut caption_sources = Vec::new();
+    let mut merged_sources = Vec::new();
+    let mut warnings = Vec::new();
+    // Start of each source in the editor's source-time domain: the recording
+    // segments' display durations laid end to end.
+    let mut source_time = 0.0;
+
+    let reference = &first.recordings.segments[0].display;
+
+    for (position, source) in sources.iter().enumerate() {
+        let prefix = format!("p{position}-");
+        let base = segments.len();
+
+        let cursor_ids = copy_cursors(
+            &source.meta,
+            &source.cursors,
+            output_path,
+            &prefix,
+            &mut cursors,
+        )?;
+
+        for (index, segment) in source.segments.iter().enumerate() {
+            segments.push(copy_segment(
+                &source.meta,
+                segment,
+                output_path,
+                base + index,
+                &cursor_ids,
+            )?);
+        }
+
+        for clip in &source.config.clips {
+            config.clips.push(ClipConfiguration {
+                index: clip.index + base as u32,
+                ..clip.clone()
+            });
+        }
+        for layer in &source.config.picture_in_picture {
+            if !config
+                .picture_in_picture
+                .iter()
+                .any(|existing| existing.source_id == layer.source_id)
+            {
+                config.picture_in_picture.push(layer.clone());
+            }
+        }
+
+        let mut source_timeline = source.timeline.clone();
+        for item in &mut source_timeline.caption_segments {
+            item.id = format!("{prefix}{}", item.id);
+        }
+        for item in &mut source_timeline.keyboard_segments {
+            item.id = format!("{prefix}{}", item.id);
+        }
+        let mut audio_tracks = HashMap::new();
+        for item in &mut source_timeline.audio_segments {
+            if let Some(copied) = audio_tracks.get(&item.path) {
+                item.path = copied.clone();
+                continue;
+            }
+            let copied = copy_audio_track(&source.meta, &item.path, output_path, &prefix)?;
+            audio_tracks.insert(item.path.clone(), copied.clone());
+            item.path = copied;
+        }
+        let output_time = timeline
+            .as_ref()
+            .map_or(0.0, TimelineConfiguration::duration);
+        match &mut timeline {
+            Some(timeline) => timeline.append(source_timeline, base as u32),
+            None => timeline = Some(source_timeline),
+        }
+
+        if let Some(captions) = &source.config.captions {
+            caption_sources.push(CaptionSource {
+                captions,
+                timeline: &source.timeline,
+                prefix: prefix.clone(),
+                source_time,
+                output_time,
+            });
+        }
+
+        let display = &source.recordings.segments[0].display;
+        if position > 0
+            && (display.width != reference.width
+                || display.height != reference.height
+                || display.fps != reference.fps)
+        {
+            warnings.push(format!(
+                "{} is {}x{} at {}fps; {} is {}x{} at {}fps",
+                source.meta.project_path.display(),
+                display.width,
+                display.height,
+                display.fps,
+                first.meta.project_path.display(),
+                reference.width,
+                reference.height,
+                reference.fps,
+            ));
+        }
+
+        let duration = source.recordings.duration();
+        source_time += source
+            .recordings
+            .segments
+            .iter()
+            .map(|segment| segment.display.duration)
+            .sum::<f64>();
+        merged_sources.push(MergedSource {
+            project_path: source.meta.project_path.clone(),
+            segments: source.segments.len(),
+            width: display.width,
+            height: display.height,
+            fps: display.fps,
+            duration,
+        });
+    }
+
+    config.captions = merged_captions(&caption_sources);
+    config.timeline = timeline;
+
+    let segment_count = segments.len();
+    let meta = RecordingMeta {
+        platform: first.meta.platform.clone(),
+        project_path: output_path.to_path_buf(),
+        pretty_name: name.unwrap_or_else(|| first.meta.pretty_name.clone()),
+        sharing: None,
+        inner: RecordingMetaInner::Studio(Box::new(StudioRecordingMeta::MultipleSegments {
+            inner: MultipleSegments {
+                segments,
+                cursors: Cursors::Correct(cursors),
+                status: Some(StudioRecordingStatus::Complete),
+            },
+        })),
+        upload: None,
+    };
+
+    meta.save_for_project()
+        .map_err(|e| format!("Failed to save recording meta: {e:?}"))?;
+    config
+        .write(output_path)
+        .map_err(|e| format!("Failed to write project config: {e}"))?;
+
+    Ok(MergeOutput {
+        project_path: output_path.to_path_buf(),
+        segments: segment_count,
+        duration: config
+            .timeline
+            .as_ref()
+            .map_or(0.0, TimelineConfiguration::duration),
+        sources: merged_sources,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_segment_sources_keep_their_cursor_images() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        let project_path = source_dir.path();
+
+        let cursors_dir = project_path.join("content/cursors");
+        std::fs::create_dir_all(&cursors_dir).unwrap();
+        std::fs::write(cursors_dir.join("cursor_0.png"), b"png").unwrap();
+        std::fs::write(project_path.join("content/display.mp4"), b"mp4").unwrap();
+        std::fs::write(
+            project_path.join("content/cursor.json"),
+            serde_json::json!({
+                "clicks": [],
+                "moves": [{
+                    "active_modifiers": [],
+                    "cursor_id": "0",
+                    "time_ms": 0.0,
+                    "x": 0.5,
+                    "y": 0.5,
+                }],
+                "cursor_images": {
+                    "0": {
+                        "path": cursors_dir.join("cursor_0.png"),
+                        "hotspot": { "x": 0.25, "y": 0.5 },
+                    },
+                },
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let studio = StudioRecordingMeta::SingleSegment {
+            segment: cap_project::SingleSegment {
+                display: VideoMeta {
+                    path: RelativePathBuf::from("content/display.mp4"),
+                    fps: 30,
+                    start_time: None,
+                    device_id: None,
+                },
+                camera: None,
+                audio: None,
+                cursor: Some(RelativePathBuf::from("content/cursor.json")),
+            },
+        };
+        let meta = RecordingMeta {
+            platform: None,
+            project_path: project_path.to_path_buf(),
+            pretty_name: "Single".to_string(),
+            sharing: None,
+            inner: RecordingMetaInner::Studio(Box::new(studio.clone())),
+            upload: None,
+        };
+
+        let (segments, cursors) = studio_segments(project_path, &studio);
+        let mut merged = HashMap::new();
+        let ids = copy_cursors(&meta, &cursors, output_dir.path(), "p0-", &mut merged).unwrap();
+        let segment = copy_segment(&meta, &segments[0], output_dir.path(), 0, &ids).unwrap();
+
+        assert_eq!(ids.get("0").map(String::as_str), Some("p0-0"));
+        let cursor = &merged["p0-0"];
+        assert_eq!(cursor.image_path.as_str(), "content/cursors/p0-0.png");
+        assert_eq!(cursor.hotspot, XY::new(0.25, 0.5));
+        assert!(cursor.image_path.to_path(output_dir.path()).is_file());
+
+        let events =
+            CursorEvents::load_from_file(&output_dir.path().join(segment.cursor.unwrap().as_str()))
+                .unwrap();
+        assert_eq!(events.moves[0].cursor_id, "p0-0");
+    }
+}
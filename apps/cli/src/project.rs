@@ -6,7 +6,7 @@ use cap_project::{
 };
 use serde::Serialize;
 
-use crate::{OutputFormat, write_json};
+use crate::{OutputFormat, encryption::ProjectMetadata, write_json};
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,7 +21,8 @@ pub struct ProjectInspection {
 }
 
 pub fn config_get(project_path: PathBuf) -> Result<(), String> {
-    let config = match cap_project::ProjectConfiguration::load(&project_path) {
+    let project = ProjectMetadata::open(&project_path)?;
+    let config = match project.load_config() {
         Ok(config) => config,
         // Instant and un-edited studio recordings have no project-config.json; return the
         // effective default the editor/exporter would use rather than erroring.
@@ -39,9 +40,9 @@ pub fn config_set(
 ) -> Result<(), String> {
     let config: cap_project::ProjectConfiguration = serde_json::from_str(settings_json)
         .map_err(|e| format!("Invalid project config JSON: {e}"))?;
-    // write() validates internally before its atomic temp-file-then-rename.
-    config
-        .write(&project_path)
+    // Validated before its atomic temp-file-then-rename, sealed when the project is encrypted.
+    ProjectMetadata::open(&project_path)?
+        .write_config(&config)
         .map_err(|e| format!("Failed to write project config: {e}"))?;
     if let OutputFormat::Json = format {
         crate::write_json(&serde_json::json!({ "ok": true }))?;
@@ -50,10 +51,12 @@ pub fn config_set(
 }
 
 pub fn inspect(project_path: PathBuf, format: OutputFormat) -> Result<(), String> {
-    let meta = RecordingMeta::load_for_project(&project_path)
+    let project = ProjectMetadata::open(&project_path)?;
+    let meta = project
+        .load_meta()
         .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let config = project.project_config(&meta);
     let output_path = meta.output_path();
-    let config = meta.project_config();
 
     match format {
         OutputFormat::Text => {
@@ -154,6 +157,9 @@ fn studio_checks(meta: &RecordingMeta, studio: &StudioRecordingMeta) -> Vec<File
                 if let Some(mic) = &segment.mic {
                     checks.push(required_check("mic", meta.path(&mic.path)));
                 }
+                for mic in &segment.additional_mics {
+                    checks.push(required_check("additionalMic", meta.path(&mic.audio.path)));
+                }
                 if let Some(system_audio) = &segment.system_audio {
                     checks.push(required_check("systemAudio", meta.path(&system_audio.path)));
                 }
@@ -258,9 +264,15 @@ fn build_report(project_path: &Path, meta: &RecordingMeta) -> ValidationReport {
     }
 }
 
+/// Loads the recording meta of a project that may be encrypted. The media its paths name exist in
+/// the project either way, sealed or not.
+fn load_meta(project_path: &Path) -> Result<RecordingMeta, String> {
+    ProjectMetadata::open(project_path)?.load_meta()
+}
+
 pub(crate) fn validate_project(project_path: &Path) -> Result<(), String> {
-    let meta = RecordingMeta::load_for_project(project_path)
-        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let meta =
+        load_meta(project_path).map_err(|e| format!("Failed to load recording meta: {e}"))?;
     let report = build_report(project_path, &meta);
 
     if report.valid {
@@ -273,7 +285,7 @@ pub(crate) fn validate_project(project_path: &Path) -> Result<(), String> {
 }
 
 pub fn validate(project_path: PathBuf, format: OutputFormat) -> Result<(), String> {
-    let report = match RecordingMeta::load_for_project(&project_path) {
+    let report = match load_meta(&project_path) {
         Ok(meta) => build_report(&project_path, &meta),
         Err(e) => ValidationReport {
             checks: vec![required_check(
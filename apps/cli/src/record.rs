@@ -1,12 +1,20 @@
 use cap_project::{
     InstantRecordingMeta, Platform, ProjectConfiguration, RecordingMeta, RecordingMetaInner,
+    encryption::ProjectKey,
 };
 use cap_recording::{
-    CameraFeed, MicrophoneFeed,
-    feeds::{camera, microphone},
+    AdditionalVideoInput, CameraFeed, HealthReceiver, LiveStream, MicrophoneFeed, StreamProtocol,
+    cursor::InputActivity,
+    feeds::{
+        camera::{self, CameraFeedLock},
+        microphone::{self, MicrophoneFeedLock},
+    },
     instant_recording,
-    screen_capture::ScreenCaptureTarget,
+    replay::{self, ReplayBuffer},
+    screen_capture::{ScreenCaptureTarget, app_audio::AppAudioFilter},
+    sources::{MediaInput, MediaInputFeed},
     studio_recording::{self, ActorHandle as StudioActorHandle},
+    video_filter::VideoFilters,
 };
 use clap::{Args, ValueEnum};
 use futures::FutureExt;
@@ -16,17 +24,18 @@ use serde::Serialize;
 use std::{
     env::current_dir,
     io::IsTerminal,
+    net::SocketAddr,
     path::{Path, PathBuf},
     process::Stdio,
     sync::Arc,
     time::{Duration, Instant},
 };
 use tokio::io::AsyncBufReadExt;
-use tracing::debug;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::{
-    OutputFormat, resolve_format,
+    OutputFormat, health, resolve_format,
     session::{self, Session, SessionStatus},
     write_json, write_json_line,
 };
@@ -41,41 +50,288 @@ pub struct RecordParams {
     /// Recording mode to use
     #[arg(long, value_enum, default_value_t = RecordMode::Studio)]
     mode: RecordMode,
+    /// How much footage a replay buffer keeps, e.g. 60s or 10m (replay mode) [default: 5m]
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+    replay_length: Option<f64>,
     /// Capture from the camera with this device id (see `cap targets cameras`)
     #[arg(long)]
     camera: Option<String>,
-    /// Capture from the microphone with this device name (see `cap targets mics`)
-    #[arg(long)]
-    mic: Option<String>,
+    /// Also record this camera as a picture-in-picture source (studio mode; repeatable)
+    #[arg(long = "pip-camera", value_name = "DEVICE_ID")]
+    pip_cameras: Vec<String>,
+    /// Also record this screen as a picture-in-picture source (studio mode; repeatable)
+    #[arg(long = "pip-screen", value_name = "SCREEN_ID")]
+    pip_screens: Vec<DisplayId>,
+    /// Capture from the microphone with this device name (see `cap targets mics`). Repeat to
+    /// record more microphones as separate tracks (studio mode); the first is the main mic
+    #[arg(long = "mic", value_name = "NAME")]
+    mics: Vec<String>,
     /// Whether to capture system audio
     #[arg(long)]
     system_audio: bool,
+    /// Record system audio from only this application, by name or PID (Linux; repeatable; see
+    /// `cap targets audio-apps`)
+    #[arg(long = "app-audio", value_name = "APP")]
+    app_audio: Vec<String>,
+    /// Record system audio from every application except this one, by name or PID (Linux;
+    /// repeatable)
+    #[arg(
+        long = "exclude-app-audio",
+        value_name = "APP",
+        conflicts_with = "app_audio"
+    )]
+    exclude_app_audio: Vec<String>,
+    /// Run the screen through a filter before encoding: blur=X,Y,W,H, watermark=TEXT, timestamp or
+    /// downscale=WxH; overlays take a corner, e.g. watermark:top-left=TEXT (repeatable; in order)
+    #[arg(long = "screen-filter", value_name = "FILTER")]
+    screen_filters: Vec<String>,
+    /// Run the camera through a filter before encoding, like --screen-filter (Linux; repeatable)
+    #[arg(long = "camera-filter", value_name = "FILTER", requires = "camera")]
+    camera_filters: Vec<String>,
     /// Path to save the '.cap' project to (defaults to <recordingId>.cap in the working directory)
     #[arg(long)]
     path: Option<PathBuf>,
     /// Maximum fps to record at (clamped to 1-120; camera recordings follow the desktop camera cap)
     #[arg(long)]
     fps: Option<u32>,
-    /// Stop automatically after N seconds
-    #[arg(long)]
+    /// Stop automatically after this long, in seconds or with a unit like 90s, 45m or 1h30m
+    #[arg(long, value_parser = parse_duration_secs)]
     duration: Option<f64>,
+    /// Stop once there has been no mouse or keyboard input for this long (e.g. 120 or 2m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+    stop_after_idle: Option<f64>,
+    /// Also stream live to this rtmp://, rtmps:// or srt:// URL while recording
+    #[arg(long, value_name = "URL")]
+    stream_url: Option<String>,
+    /// Record a media file, an image sequence (e.g. frames/%05d.png), or frames from a named pipe or
+    /// stdin (`-`) instead of a screen or window (studio mode)
+    #[arg(long, value_name = "PATH", conflicts_with = "target")]
+    input: Option<PathBuf>,
+    /// Frame rate of an --input image sequence or raw video [default: 30]
+    #[arg(long, value_name = "FPS", requires = "input")]
+    input_fps: Option<u32>,
+    /// Read --input as headerless raw frames of this size, e.g. 1280x720
+    #[arg(long, value_name = "WxH", requires = "input")]
+    input_size: Option<String>,
+    /// Pixel format of raw --input frames, as an ffmpeg name like rgba or yuv420p [default: rgba]
+    #[arg(long, value_name = "FORMAT", requires = "input_size")]
+    input_pixel_format: Option<String>,
+    /// Serve recording counters for Prometheus at http://<ADDR>/metrics, e.g. 127.0.0.1:9464
+    #[arg(long, value_name = "ADDR")]
+    metrics_addr: Option<String>,
+    /// Encrypt the project's files at rest (studio mode). The key comes from
+    /// --encryption-key-file, $CAP_RECORDING_KEY_FILE or the OS keyring, which gets a new key if
+    /// it has none
+    #[arg(long)]
+    encrypt: bool,
+    /// Read the 32-byte encryption key (raw or hex) from this file
+    #[arg(long, value_name = "PATH", requires = "encrypt")]
+    encryption_key_file: Option<PathBuf>,
 }
 
 impl RecordParams {
-    fn validate(&self) -> Result<(), String> {
+    pub(crate) fn validate(&self) -> Result<(), String> {
         if self.duration.is_some_and(|duration| {
             // `> u64::MAX` would panic in `Duration::from_secs_f64` (used by `wait_for_stop`).
             !duration.is_finite() || duration <= 0.0 || duration > u64::MAX as f64
         }) {
             return Err("Duration must be a positive, finite number of seconds".to_string());
         }
+        if self
+            .stop_after_idle
+            .is_some_and(|idle| !idle.is_finite() || idle <= 0.0 || idle > u64::MAX as f64)
+        {
+            return Err("--stop-after-idle must be a positive, finite duration".to_string());
+        }
         if self.fps == Some(0) {
             return Err("--fps must be greater than 0".to_string());
         }
+        if let Some(length) = self.replay_length {
+            if self.mode != RecordMode::Replay {
+                return Err("--replay-length requires --mode replay".to_string());
+            }
+            if !length.is_finite() || length <= 0.0 || length > u64::MAX as f64 {
+                return Err("--replay-length must be a positive, finite duration".to_string());
+            }
+        }
+        if self.mode != RecordMode::Studio
+            && (!self.pip_cameras.is_empty() || !self.pip_screens.is_empty())
+        {
+            return Err("--pip-camera and --pip-screen require --mode studio".to_string());
+        }
+        if self.mode != RecordMode::Studio && self.mics.len() > 1 {
+            return Err("Recording more than one --mic requires --mode studio".to_string());
+        }
+        if let Some(mic) = self
+            .mics
+            .iter()
+            .enumerate()
+            .find_map(|(i, mic)| self.mics[..i].contains(mic).then_some(mic))
+        {
+            return Err(format!("--mic '{mic}' is given more than once"));
+        }
+        if cfg!(not(target_os = "linux")) && self.app_audio_filter().is_some() {
+            return Err(
+                "--app-audio and --exclude-app-audio are only supported on Linux".to_string(),
+            );
+        }
+        self.video_filters()?;
+        if cfg!(not(target_os = "linux")) && !self.camera_filters.is_empty() {
+            return Err("--camera-filter is only supported on Linux".to_string());
+        }
+        if self.encrypt && self.mode != RecordMode::Studio {
+            return Err("--encrypt requires --mode studio".to_string());
+        }
+        if cfg!(target_os = "macos") && self.encrypt && self.camera.is_some() {
+            return Err(
+                "--encrypt can't be combined with --camera on macOS, where camera recordings aren't fragmented"
+                    .to_string(),
+            );
+        }
+        if let Some(url) = &self.stream_url {
+            StreamProtocol::from_url(url).map_err(|e| format!("--stream-url: {e}"))?;
+        }
+        if let Some(addr) = &self.metrics_addr
+            && addr.parse::<SocketAddr>().is_err()
+        {
+            return Err(format!(
+                "--metrics-addr must be an IP address and port like 127.0.0.1:9464 (got '{addr}')"
+            ));
+        }
+        if self.input.is_some() {
+            if self.mode != RecordMode::Studio {
+                return Err("--input requires --mode studio".to_string());
+            }
+            if self.system_audio {
+                return Err(
+                    "--system-audio cannot be combined with --input; the input's own audio is recorded"
+                        .to_string(),
+                );
+            }
+            if self.app_audio_filter().is_some() {
+                return Err(
+                    "--app-audio and --exclude-app-audio cannot be combined with --input"
+                        .to_string(),
+                );
+            }
+            if self.input_fps == Some(0) {
+                return Err("--input-fps must be greater than 0".to_string());
+            }
+            if let Some(MediaInput::File(_)) = self.media_input()?
+                && self.input_fps.is_some()
+            {
+                return Err(
+                    "--input-fps only applies to image sequences and raw --input frames"
+                        .to_string(),
+                );
+            }
+        }
         Ok(())
     }
 
-    fn to_cli_args(&self) -> Vec<String> {
+    pub(crate) fn reads_stdin(&self) -> bool {
+        self.input.as_deref() == Some(Path::new("-"))
+    }
+
+    /// Parses flags previously produced by [`RecordParams::to_cli_args`], e.g. from a saved schedule.
+    pub(crate) fn from_cli_args(args: &[String]) -> Result<Self, String> {
+        #[derive(clap::Parser)]
+        struct Wrapper {
+            #[command(flatten)]
+            params: RecordParams,
+        }
+
+        <Wrapper as clap::Parser>::try_parse_from(
+            std::iter::once("cap").chain(args.iter().map(String::as_str)),
+        )
+        .map(|wrapper| wrapper.params)
+        .map_err(|e| e.to_string())
+    }
+
+    /// Whether a screen, window or `--input` was chosen, rather than the default primary screen.
+    pub(crate) fn has_target(&self) -> bool {
+        self.target.screen.is_some()
+            || self.target.window.is_some()
+            || self.target.follow_window.is_some()
+            || self.input.is_some()
+    }
+
+    pub(crate) fn set_window(&mut self, window: WindowId) {
+        self.target.screen = None;
+        self.target.follow_window = None;
+        self.target.window = Some(window);
+    }
+
+    /// The applications whose audio `--app-audio` or `--exclude-app-audio` selects.
+    fn app_audio_filter(&self) -> Option<AppAudioFilter> {
+        if !self.app_audio.is_empty() {
+            Some(AppAudioFilter::Include(self.app_audio.clone()))
+        } else if !self.exclude_app_audio.is_empty() {
+            Some(AppAudioFilter::Exclude(self.exclude_app_audio.clone()))
+        } else {
+            None
+        }
+    }
+
+    /// The filters `--screen-filter` and `--camera-filter` chain onto each source.
+    fn video_filters(&self) -> Result<VideoFilters, String> {
+        let parse = |flag: &str, specs: &[String]| {
+            specs
+                .iter()
+                .map(|spec| spec.parse().map_err(|e| format!("{flag} '{spec}': {e}")))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(VideoFilters {
+            screen: parse("--screen-filter", &self.screen_filters)?,
+            camera: parse("--camera-filter", &self.camera_filters)?,
+        })
+    }
+
+    fn replay_length(&self) -> Duration {
+        self.replay_length
+            .map(Duration::from_secs_f64)
+            .unwrap_or(replay::DEFAULT_LENGTH)
+    }
+
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub(crate) fn set_path(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    /// How `--input` should be read: raw frames when `--input-size` is given, an image sequence when
+    /// the path is a `%d`-style pattern, and a media file or stream otherwise.
+    fn media_input(&self) -> Result<Option<MediaInput>, String> {
+        let Some(path) = self.input.clone() else {
+            return Ok(None);
+        };
+        let fps = self.input_fps.unwrap_or(30);
+
+        if let Some(size) = &self.input_size {
+            let (width, height) = parse_input_size(size)?;
+            return Ok(Some(MediaInput::RawVideo {
+                path,
+                width,
+                height,
+                pixel_format: self
+                    .input_pixel_format
+                    .clone()
+                    .unwrap_or_else(|| "rgba".to_string()),
+                fps,
+            }));
+        }
+
+        if path.to_string_lossy().contains('%') {
+            Ok(Some(MediaInput::ImageSequence { pattern: path, fps }))
+        } else {
+            Ok(Some(MediaInput::File(path)))
+        }
+    }
+
+    pub(crate) fn to_cli_args(&self) -> Vec<String> {
         let mut args = Vec::new();
         if let Some(id) = &self.target.screen {
             args.push("--screen".to_string());
@@ -85,19 +341,51 @@ impl RecordParams {
             args.push("--window".to_string());
             args.push(id.to_string());
         }
+        if let Some(id) = &self.target.follow_window {
+            args.push("--follow-window".to_string());
+            args.push(id.to_string());
+        }
         args.push("--mode".to_string());
         args.push(self.mode.to_string());
+        if let Some(length) = self.replay_length {
+            args.push("--replay-length".to_string());
+            args.push(length.to_string());
+        }
         if let Some(camera) = &self.camera {
             args.push("--camera".to_string());
             args.push(camera.clone());
         }
-        if let Some(mic) = &self.mic {
+        for camera in &self.pip_cameras {
+            args.push("--pip-camera".to_string());
+            args.push(camera.clone());
+        }
+        for screen in &self.pip_screens {
+            args.push("--pip-screen".to_string());
+            args.push(screen.to_string());
+        }
+        for mic in &self.mics {
             args.push("--mic".to_string());
             args.push(mic.clone());
         }
         if self.system_audio {
             args.push("--system-audio".to_string());
         }
+        for app in &self.app_audio {
+            args.push("--app-audio".to_string());
+            args.push(app.clone());
+        }
+        for app in &self.exclude_app_audio {
+            args.push("--exclude-app-audio".to_string());
+            args.push(app.clone());
+        }
+        for filter in &self.screen_filters {
+            args.push("--screen-filter".to_string());
+            args.push(filter.clone());
+        }
+        for filter in &self.camera_filters {
+            args.push("--camera-filter".to_string());
+            args.push(filter.clone());
+        }
         if let Some(path) = &self.path {
             args.push("--path".to_string());
             args.push(path.display().to_string());
@@ -110,14 +398,90 @@ impl RecordParams {
             args.push("--duration".to_string());
             args.push(duration.to_string());
         }
+        if let Some(idle) = self.stop_after_idle {
+            args.push("--stop-after-idle".to_string());
+            args.push(idle.to_string());
+        }
+        if let Some(url) = &self.stream_url {
+            args.push("--stream-url".to_string());
+            args.push(url.clone());
+        }
+        if let Some(input) = &self.input {
+            args.push("--input".to_string());
+            args.push(input.display().to_string());
+        }
+        if let Some(fps) = self.input_fps {
+            args.push("--input-fps".to_string());
+            args.push(fps.to_string());
+        }
+        if let Some(size) = &self.input_size {
+            args.push("--input-size".to_string());
+            args.push(size.clone());
+        }
+        if let Some(pixel_format) = &self.input_pixel_format {
+            args.push("--input-pixel-format".to_string());
+            args.push(pixel_format.clone());
+        }
+        if let Some(addr) = &self.metrics_addr {
+            args.push("--metrics-addr".to_string());
+            args.push(addr.clone());
+        }
+        if self.encrypt {
+            args.push("--encrypt".to_string());
+        }
+        if let Some(path) = &self.encryption_key_file {
+            args.push("--encryption-key-file".to_string());
+            args.push(path.display().to_string());
+        }
         args
     }
 }
 
+/// Parses a duration given as plain seconds (`90`, `2.5`) or as whole numbers with `h`, `m` and `s`
+/// units (`45m`, `1h30m`), into seconds.
+pub(crate) fn parse_duration_secs(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Ok(seconds);
+    }
+
+    let invalid =
+        || format!("invalid duration '{value}'; use seconds or a value like 90s, 45m or 1h30m");
+    let mut total = 0.0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return Err(invalid()),
+        };
+        let amount: f64 = number.parse().map_err(|_| invalid())?;
+        total += amount * unit;
+        number.clear();
+    }
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+fn parse_input_size(size: &str) -> Result<(u32, u32), String> {
+    size.split_once(['x', 'X'])
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .ok_or_else(|| format!("--input-size must be WIDTHxHEIGHT, e.g. 1280x720 (got '{size}')"))
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
 pub enum RecordMode {
     Studio,
     Instant,
+    Replay,
 }
 
 impl std::fmt::Display for RecordMode {
@@ -125,6 +489,7 @@ impl std::fmt::Display for RecordMode {
         match self {
             Self::Studio => f.write_str("studio"),
             Self::Instant => f.write_str("instant"),
+            Self::Replay => f.write_str("replay"),
         }
     }
 }
@@ -151,6 +516,22 @@ pub struct SessionRunArgs {
     session_id: String,
 }
 
+#[derive(Args)]
+pub struct RecordStatusArgs {
+    /// Stream a recording's health events until it stops (detached recordings; see --id/--path)
+    #[arg(long)]
+    follow: bool,
+    /// recordingId of the recording to follow (defaults to the only active recording)
+    #[arg(long, requires = "follow")]
+    id: Option<String>,
+    /// The '.cap' project path of the recording to follow (alternative to --id)
+    #[arg(long, requires = "follow")]
+    path: Option<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
 #[derive(Args)]
 pub struct RecordStopArgs {
     /// recordingId returned by `cap record start --detach`
@@ -167,10 +548,35 @@ pub struct RecordStopArgs {
     format: OutputFormat,
 }
 
+#[derive(Args)]
+pub struct SaveReplayArgs {
+    /// How much of the buffer to save, e.g. 60s or 2m (defaults to all of it)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+    last: Option<f64>,
+    /// recordingId of the replay recording (defaults to the only active recording)
+    #[arg(long)]
+    id: Option<String>,
+    /// The '.cap' project path of the replay recording (alternative to --id; also works for a
+    /// recording running in the foreground)
+    #[arg(long)]
+    path: Option<PathBuf>,
+    /// Path to save the replay to (defaults to <project>-<unix time>.cap next to the recording)
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
 impl RecordStart {
     pub async fn run(self, json: bool) -> Result<(), String> {
         let format = resolve_format(json, self.format);
         self.params.validate()?;
+        if self.detach && self.params.reads_stdin() {
+            return Err(
+                "--input - reads from stdin, which --detach does not keep open".to_string(),
+            );
+        }
         if self.detach {
             run_detached(self.params, format).await
         } else {
@@ -192,12 +598,18 @@ async fn run_foreground(params: RecordParams, format: OutputFormat) -> Result<()
 }
 
 async fn foreground_inner(params: RecordParams, format: OutputFormat) -> Result<(), String> {
-    let interactive = std::io::stdin().is_terminal();
+    // Frames piped in on stdin leave no terminal to press Enter in.
+    let interactive = std::io::stdin().is_terminal() && !params.reads_stdin();
 
     // Without --duration the recorder blocks for a stop signal. A non-interactive caller (agent, CI,
     // /dev/null stdin) hits EOF immediately and would otherwise produce a ~0s recording that exits
-    // successfully. Require an explicit duration, or point them at the detached lifecycle.
-    if params.duration.is_none() && !interactive {
+    // successfully. Require an explicit duration, or point them at the detached lifecycle. An --input
+    // recording also stops when the input ends.
+    if params.duration.is_none()
+        && params.stop_after_idle.is_none()
+        && !interactive
+        && params.input.is_none()
+    {
         return Err(
             "Recording without --duration requires an interactive terminal; pass --duration <seconds>, \
              or use `cap record start --detach` and stop it later with `cap record stop`"
@@ -208,7 +620,10 @@ async fn foreground_inner(params: RecordParams, format: OutputFormat) -> Result<
     let recording_id = new_recording_id();
     let target = resolve_target(&params)?;
     let path = resolve_path(&params, &recording_id)?;
-    let actor = start_recording(&params, target, path.clone()).await?;
+    let media_input = open_media_input(&params).await?;
+    let metrics_listener = bind_metrics(&params).await?;
+    let mut actor = start_recording(&params, target, path.clone(), media_input.clone()).await?;
+    monitor_health(&mut actor, &path, metrics_listener);
     let path_display = path.display().to_string();
 
     // The recording is now writing to disk, so every path from here must finalize the actor.
@@ -221,7 +636,7 @@ async fn foreground_inner(params: RecordParams, format: OutputFormat) -> Result<
         },
     ) {
         if let Ok(completed) = actor.stop().await {
-            let _ = finalize_completed(completed).await;
+            let _ = finalize_completed(completed, params.encryption_key_file.as_deref()).await;
         }
         return Err(error);
     }
@@ -230,7 +645,16 @@ async fn foreground_inner(params: RecordParams, format: OutputFormat) -> Result<
         println!("Press Enter to stop (or send SIGINT/SIGTERM)");
     }
 
-    let completed = finalize(actor, params.duration, interactive, None).await?;
+    let completed = finalize(
+        actor,
+        params.duration,
+        params.stop_after_idle,
+        interactive,
+        None,
+        media_input.as_ref(),
+        params.encryption_key_file.as_deref(),
+    )
+    .await?;
     crate::automation::run_recording_finished(
         completed.project_path(),
         automation_mode(params.mode),
@@ -242,7 +666,9 @@ async fn foreground_inner(params: RecordParams, format: OutputFormat) -> Result<
 fn automation_mode(mode: RecordMode) -> cap_automation::AutomationRecordingMode {
     match mode {
         RecordMode::Studio => cap_automation::AutomationRecordingMode::Studio,
-        RecordMode::Instant => cap_automation::AutomationRecordingMode::Instant,
+        RecordMode::Instant | RecordMode::Replay => {
+            cap_automation::AutomationRecordingMode::Instant
+        }
     }
 }
 
@@ -273,36 +699,78 @@ async fn detached_inner(params: RecordParams, format: OutputFormat) -> Result<()
             .join(path)
     };
 
+    let (pid, session) = spawn_worker(params, &recording_id, path).await?;
+
+    if let Err(error) = emit_record_event(
+        format,
+        &RecordEvent::Started {
+            recording_id: &recording_id,
+            pid,
+            path: &session.path.display().to_string(),
+        },
+    ) {
+        // The worker is already recording in its own process group. If we cannot hand the caller the
+        // recordingId it could never stop it, so tear the recording down rather than leak an orphan.
+        let _ = session::request_stop(&recording_id);
+        if session::process_alive(pid) {
+            session::terminate(pid);
+        }
+        return Err(format!(
+            "{error}; background recording {recording_id} was stopped because its start event could not be delivered"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Starts the detached `__session-run` worker recording to `path` and waits until it reports the
+/// recording as started. Returns the worker's pid and its session.
+pub(crate) async fn spawn_worker(
+    params: RecordParams,
+    recording_id: &str,
+    path: PathBuf,
+) -> Result<(u32, Session), String> {
     let exe =
         std::env::current_exe().map_err(|e| format!("Could not locate the cap executable: {e}"))?;
 
     let sessions_dir = session::sessions_dir()?;
     std::fs::create_dir_all(&sessions_dir)
         .map_err(|e| format!("Could not create sessions dir: {e}"))?;
-    let log_path = session::log_file(&recording_id)?;
+    let log_path = session::log_file(recording_id)?;
     let log = std::fs::File::create(&log_path)
         .map_err(|e| format!("Could not create session log {}: {e}", log_path.display()))?;
     let log_err = log
         .try_clone()
         .map_err(|e| format!("Could not prepare session log: {e}"))?;
 
-    let mut worker_params = params.clone();
-    worker_params.path = Some(path.clone());
+    let mut worker_params = params;
+    worker_params.path = Some(path);
 
     let mut command = std::process::Command::new(&exe);
     command
         .arg("record")
         .arg("__session-run")
         .arg("--session-id")
-        .arg(&recording_id)
+        .arg(recording_id)
         .args(worker_params.to_cli_args())
         .stdin(Stdio::null())
         .stdout(Stdio::from(log))
         .stderr(Stdio::from(log_err));
+    detach(&mut command);
 
-    // Detach from the parent so closing the parent's shell/pipeline does not signal the recording
-    // worker. On unix this means a new process group; on Windows, no console + a new process group so
-    // a Ctrl-C / console-close in the launching terminal does not reach the worker.
+    let child = command
+        .spawn()
+        .map_err(|e| format!("Could not start the recording worker: {e}"))?;
+    let pid = child.id();
+
+    let session = wait_for_session_ready(recording_id, child).await?;
+    Ok((pid, session))
+}
+
+/// Detach from the parent so closing the parent's shell/pipeline does not signal the background
+/// process. On unix this means a new process group; on Windows, no console + a new process group so
+/// a Ctrl-C / console-close in the launching terminal does not reach it.
+pub(crate) fn detach(command: &mut std::process::Command) {
     #[cfg(unix)]
     {
         use std::os::unix::process::CommandExt;
@@ -315,34 +783,6 @@ async fn detached_inner(params: RecordParams, format: OutputFormat) -> Result<()
         const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
         command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
     }
-
-    let child = command
-        .spawn()
-        .map_err(|e| format!("Could not start the recording worker: {e}"))?;
-    let pid = child.id();
-
-    let session = wait_for_session_ready(&recording_id, child).await?;
-
-    if let Err(error) = emit_record_event(
-        format,
-        &RecordEvent::Started {
-            recording_id: &recording_id,
-            pid,
-            path: &session.path.display().to_string(),
-        },
-    ) {
-        // The worker is already recording in its own process group. If we cannot hand the caller the
-        // recordingId it could never stop it, so tear the recording down rather than leak an orphan.
-        let _ = session::request_stop(&recording_id);
-        if session::process_alive(pid) {
-            session::terminate(pid);
-        }
-        return Err(format!(
-            "{error}; background recording {recording_id} was stopped because its start event could not be delivered"
-        ));
-    }
-
-    Ok(())
 }
 
 async fn wait_for_session_ready(
@@ -433,7 +873,10 @@ async fn session_worker(params: RecordParams, recording_id: &str) -> Result<(),
         .path
         .clone()
         .ok_or_else(|| "internal: detached worker started without --path".to_string())?;
-    let actor = start_recording(&params, target, path.clone()).await?;
+    let media_input = open_media_input(&params).await?;
+    let metrics_listener = bind_metrics(&params).await?;
+    let mut actor = start_recording(&params, target, path.clone(), media_input.clone()).await?;
+    monitor_health(&mut actor, &path, metrics_listener);
 
     // Stamp the start time once; reusing it for the Stopped write keeps `startedAt` meaning the start
     // (not the stop), which `list_sessions` relies on to sort recordings newest-first.
@@ -449,7 +892,16 @@ async fn session_worker(params: RecordParams, recording_id: &str) -> Result<(),
     })?;
 
     let stop_path = session::stop_file(recording_id)?;
-    let completed = finalize(actor, params.duration, false, Some(&stop_path)).await?;
+    let completed = finalize(
+        actor,
+        params.duration,
+        params.stop_after_idle,
+        false,
+        Some(&stop_path),
+        media_input.as_ref(),
+        params.encryption_key_file.as_deref(),
+    )
+    .await?;
     crate::automation::run_recording_finished(
         completed.project_path(),
         automation_mode(params.mode),
@@ -565,6 +1017,94 @@ impl RecordStopArgs {
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedReplayOutput {
+    path: PathBuf,
+    duration_secs: f64,
+}
+
+impl SaveReplayArgs {
+    pub async fn run(self, json: bool) -> Result<(), String> {
+        let format = resolve_format(json, self.format);
+        let result = self.run_inner(format).await;
+        if format == OutputFormat::Json
+            && let Err(error) = &result
+        {
+            let _ = write_json(&serde_json::json!({ "error": error }));
+        }
+        result
+    }
+
+    async fn run_inner(self, format: OutputFormat) -> Result<(), String> {
+        if self
+            .last
+            .is_some_and(|last| !last.is_finite() || last <= 0.0 || last > u64::MAX as f64)
+        {
+            return Err("--last must be a positive, finite duration".to_string());
+        }
+
+        // A foreground replay recording has no session, so accept its project path directly.
+        let recording_path = match &self.path {
+            Some(path) if path.join(replay::BUFFER_DIR).is_dir() => path.clone(),
+            _ => resolve_session(self.id.as_deref(), self.path.as_deref())?.path,
+        };
+        let buffer_dir = recording_path.join(replay::BUFFER_DIR);
+        if !buffer_dir.is_dir() {
+            return Err(format!(
+                "{} is not a running replay recording; start one with `cap record start --mode replay`",
+                recording_path.display()
+            ));
+        }
+
+        let output = self.output.unwrap_or_else(|| {
+            let stem = recording_path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("replay");
+            let timestamp = session::now_unix().unwrap_or_default();
+            recording_path.with_file_name(format!("{stem}-{timestamp}.cap"))
+        });
+        if output.exists() {
+            return Err(format!("{} already exists", output.display()));
+        }
+
+        let saved = match save_replay(
+            buffer_dir,
+            self.last.map(Duration::from_secs_f64),
+            output.clone(),
+        )
+        .await
+        {
+            Ok(saved) => saved,
+            Err(error) => {
+                let _ = std::fs::remove_dir_all(&output);
+                return Err(error);
+            }
+        };
+        crate::automation::run_recording_finished(
+            &saved.project_path,
+            cap_automation::AutomationRecordingMode::Instant,
+        )
+        .await;
+
+        match format {
+            OutputFormat::Json => write_json(&SavedReplayOutput {
+                path: saved.project_path,
+                duration_secs: saved.duration.as_secs_f64(),
+            }),
+            OutputFormat::Text => {
+                println!(
+                    "Saved the last {:.1}s to {}",
+                    saved.duration.as_secs_f64(),
+                    saved.project_path.display()
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
 fn cleanup_session(id: &str, project_path: &Path) {
     if let Err(error) = session::archive_log(id, project_path) {
         debug!("{error}");
@@ -631,7 +1171,42 @@ struct SessionStatusRow {
     error: Option<String>,
 }
 
-pub fn status(format: OutputFormat) -> Result<(), String> {
+impl RecordStatusArgs {
+    pub async fn run(self, json: bool) -> Result<(), String> {
+        let format = resolve_format(json, self.format);
+        if !self.follow {
+            let result = status(format);
+            if format == OutputFormat::Json
+                && let Err(error) = &result
+            {
+                let _ = write_json(&serde_json::json!({ "error": error }));
+            }
+            return result;
+        }
+
+        let result = self.follow(format).await;
+        if format == OutputFormat::Json
+            && let Err(error) = &result
+        {
+            let _ = write_json_line(&RecordEvent::Error { error });
+        }
+        result
+    }
+
+    async fn follow(&self, format: OutputFormat) -> Result<(), String> {
+        let session = resolve_session(self.id.as_deref(), self.path.as_deref())?;
+        let id = session.recording_id.clone();
+
+        health::follow_log(&session.path, format, || {
+            session::read_session(&id).is_ok_and(|current| {
+                current.status == SessionStatus::Recording && session::process_alive(current.pid)
+            })
+        })
+        .await
+    }
+}
+
+fn status(format: OutputFormat) -> Result<(), String> {
     let rows: Vec<SessionStatusRow> = session::list_sessions()?
         .into_iter()
         .map(session_status_row)
@@ -694,6 +1269,15 @@ fn session_status_row_with_alive(session: Session, alive: bool) -> SessionStatus
 enum ActorHandle {
     Studio(StudioActorHandle),
     Instant(instant_recording::ActorHandle),
+    Replay(ReplayActor),
+}
+
+/// An instant recording into `<project>/replay` that is pruned to the last `length` while it runs.
+struct ReplayActor {
+    actor: instant_recording::ActorHandle,
+    project_path: PathBuf,
+    length: Duration,
+    _buffer: ReplayBuffer,
 }
 
 impl ActorHandle {
@@ -710,6 +1294,24 @@ impl ActorHandle {
                 .await
                 .map(CompletedRecording::Instant)
                 .map_err(|e| e.to_string()),
+            Self::Replay(replay) => replay
+                .actor
+                .stop()
+                .await
+                .map(|recording| CompletedRecording::Replay {
+                    recording,
+                    project_path: replay.project_path.clone(),
+                    length: replay.length,
+                })
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    fn take_health_rx(&mut self) -> Option<HealthReceiver> {
+        match self {
+            Self::Studio(actor) => actor.take_health_rx(),
+            Self::Instant(actor) => actor.take_health_rx(),
+            Self::Replay(replay) => replay.actor.take_health_rx(),
         }
     }
 }
@@ -717,6 +1319,12 @@ impl ActorHandle {
 enum CompletedRecording {
     Studio(Box<studio_recording::CompletedRecording>),
     Instant(instant_recording::CompletedRecording),
+    /// `recording` is the buffer, which is saved into `project_path` when the recording finalizes.
+    Replay {
+        recording: instant_recording::CompletedRecording,
+        project_path: PathBuf,
+        length: Duration,
+    },
 }
 
 impl CompletedRecording {
@@ -724,6 +1332,7 @@ impl CompletedRecording {
         match self {
             Self::Studio(recording) => &recording.project_path,
             Self::Instant(recording) => &recording.project_path,
+            Self::Replay { project_path, .. } => project_path,
         }
     }
 }
@@ -732,87 +1341,85 @@ async fn start_recording(
     params: &RecordParams,
     target: ScreenCaptureTarget,
     path: PathBuf,
+    media_input: Option<MediaInputFeed>,
 ) -> Result<ActorHandle, String> {
     #[cfg(target_os = "macos")]
     let target_for_shareable_content = target.clone();
     let mut studio_builder = studio_recording::Actor::builder(path.clone(), target.clone())
         .with_system_audio(params.system_audio);
-    let mut instant_builder =
-        instant_recording::Actor::builder(path, target).with_system_audio(params.system_audio);
+    // A replay buffer records into a subdirectory that is saved into the project when it stops.
+    let instant_path = match params.mode {
+        RecordMode::Replay => path.join(replay::BUFFER_DIR),
+        RecordMode::Studio | RecordMode::Instant => path.clone(),
+    };
+    let mut instant_builder = instant_recording::Actor::builder(instant_path.clone(), target)
+        .with_system_audio(params.system_audio);
+    if let Some(filter) = params.app_audio_filter() {
+        studio_builder = studio_builder.with_app_audio(filter.clone());
+        instant_builder = instant_builder.with_app_audio(filter);
+    }
+    let video_filters = params.video_filters()?;
+    studio_builder = studio_builder.with_video_filters(video_filters.clone());
+    instant_builder = instant_builder.with_video_filters(video_filters);
     let mut camera_active = false;
 
     // Feeds must be locked and attached before build(); the lock keeps the device open for the whole
     // recording, so the feed actor handle itself does not need to be retained.
     if let Some(device_id) = params.camera.as_deref() {
-        let info = cap_camera::list_cameras()
-            .find(|c| c.device_id() == device_id)
-            .ok_or_else(|| {
-                let available: Vec<String> = cap_camera::list_cameras()
-                    .map(|c| c.device_id().to_string())
-                    .collect();
-                format!(
-                    "Camera with id '{device_id}' not found. Available device ids: {available:?} \
-                     (see `cap targets cameras`)"
-                )
-            })?;
-        let id = camera::DeviceOrModelID::from_info(&info);
-
-        let camera_feed = CameraFeed::spawn(CameraFeed::default());
-        camera_feed
-            .ask(camera::SetInput { id, settings: None })
-            .await
-            .map_err(|e| format!("Failed to set camera input: {e}"))?
-            .await
-            .map_err(|e| format!("Camera failed to connect: {e}"))?;
-        let lock = camera_feed
-            .ask(camera::Lock)
-            .await
-            .map_err(|e| format!("Failed to lock camera feed: {e}"))?;
-        let lock = Arc::new(lock);
+        let lock = lock_camera(device_id).await?;
         studio_builder = studio_builder.with_camera_feed(lock.clone());
         instant_builder = instant_builder.with_camera_feed(lock);
         camera_active = true;
     }
 
-    if let Some(mic_name) = params.mic.as_deref() {
-        let available = MicrophoneFeed::list();
-        if !available.contains_key(mic_name) {
-            let names: Vec<&str> = available.keys().map(String::as_str).collect();
-            return Err(format!(
-                "Microphone '{mic_name}' not found. Available: {names:?} (see `cap targets mics`)"
-            ));
-        }
+    for device_id in &params.pip_cameras {
+        let lock = lock_camera(device_id).await?;
+        studio_builder = studio_builder.with_additional_video(AdditionalVideoInput::Camera(lock));
+    }
 
-        let (error_tx, _error_rx) = flume::bounded(16);
-        let mic_feed = MicrophoneFeed::spawn(MicrophoneFeed::new(error_tx));
-        mic_feed
-            .ask(microphone::SetInput {
-                label: mic_name.to_string(),
-                settings: None,
-            })
-            .await
-            .map_err(|e| format!("Failed to set mic input: {e}"))?
-            .await
-            .map_err(|e| format!("Mic failed to connect: {e}"))?;
-        // The stream needs a moment to warm up before locking on slower devices.
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        let lock = mic_feed
-            .ask(microphone::Lock)
-            .await
-            .map_err(|e| format!("Failed to lock mic feed: {e}"))?;
-        let lock = Arc::new(lock);
+    for id in &params.pip_screens {
+        let target = resolve_display(id)?;
+        studio_builder =
+            studio_builder.with_additional_video(AdditionalVideoInput::Display(target));
+    }
+
+    if let Some((mic_name, additional)) = params.mics.split_first() {
+        let lock = lock_mic(mic_name).await?;
         studio_builder = studio_builder.with_mic_feed(lock.clone());
         instant_builder = instant_builder.with_mic_feed(lock);
+
+        for mic_name in additional {
+            studio_builder = studio_builder.with_additional_mic_feed(lock_mic(mic_name).await?);
+        }
+    }
+
+    if let Some(url) = params.stream_url.as_deref() {
+        let stream = LiveStream::new(url).map_err(|e| format!("--stream-url: {e}"))?;
+        studio_builder = studio_builder.with_live_stream(stream.clone());
+        instant_builder = instant_builder.with_live_stream(stream);
+    }
+
+    if let Some(media_input) = media_input {
+        studio_builder = studio_builder.with_media_input(media_input);
     }
 
     match params.mode {
         RecordMode::Studio => {
-            let builder = cap_recording::RecordingDefaults::default().apply_to_studio_builder(
+            let mut builder = cap_recording::RecordingDefaults::default().apply_to_studio_builder(
                 studio_builder,
                 camera_active,
                 params.fps,
             );
 
+            if params.encrypt {
+                let master =
+                    crate::encryption::master_key_or_create(params.encryption_key_file.as_deref())?;
+                let key = ProjectKey::create(&path, &master).map_err(|e| {
+                    format!("Failed to set up encryption for {}: {e}", path.display())
+                })?;
+                builder = builder.with_encryption(key);
+            }
+
             builder
                 .build(
                     #[cfg(target_os = "macos")]
@@ -824,7 +1431,7 @@ async fn start_recording(
                 .map(ActorHandle::Studio)
                 .map_err(|e| e.to_string())
         }
-        RecordMode::Instant => {
+        RecordMode::Instant | RecordMode::Replay => {
             let mut builder = instant_builder;
             builder = builder.with_max_output_size(
                 cap_recording::RecordingDefaults::default().instant_mode_max_resolution,
@@ -841,12 +1448,67 @@ async fn start_recording(
                     ),
                 )
                 .await
-                .map(ActorHandle::Instant)
+                .map(|actor| match params.mode {
+                    RecordMode::Replay => {
+                        let length = params.replay_length();
+                        ActorHandle::Replay(ReplayActor {
+                            actor,
+                            project_path: path,
+                            length,
+                            _buffer: ReplayBuffer::spawn(instant_path, length),
+                        })
+                    }
+                    RecordMode::Studio | RecordMode::Instant => ActorHandle::Instant(actor),
+                })
                 .map_err(|e| e.to_string())
         }
     }
 }
 
+/// Opens and probes `--input` up front, since stdin and pipes can only be read once.
+async fn open_media_input(params: &RecordParams) -> Result<Option<MediaInputFeed>, String> {
+    let Some(input) = params.media_input()? else {
+        return Ok(None);
+    };
+
+    tokio::task::spawn_blocking(move || MediaInputFeed::open(input))
+        .await
+        .map_err(|e| format!("Failed to open --input: {e}"))?
+        .map(Some)
+        .map_err(|e| format!("Failed to open --input: {e:#}"))
+}
+
+/// Binds `--metrics-addr` before recording starts, so a port in use fails the command rather than
+/// a recording that is already writing to disk.
+async fn bind_metrics(params: &RecordParams) -> Result<Option<tokio::net::TcpListener>, String> {
+    let Some(addr) = params.metrics_addr.as_deref() else {
+        return Ok(None);
+    };
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("--metrics-addr: could not listen on {addr}: {e}"))?;
+    info!("Serving recording metrics at http://{addr}/metrics");
+    Ok(Some(listener))
+}
+
+/// Logs the recording's health events to the project's health log and serves metrics if asked to.
+fn monitor_health(
+    actor: &mut ActorHandle,
+    project_path: &Path,
+    metrics_listener: Option<tokio::net::TcpListener>,
+) {
+    let counts = health::HealthCounts::default();
+
+    if let Some(health_rx) = actor.take_health_rx() {
+        health::spawn_health_log(health_rx, project_path, counts.clone());
+    }
+
+    if let Some(listener) = metrics_listener {
+        health::spawn_metrics_server(listener, project_path.to_path_buf(), counts);
+    }
+}
+
 #[cfg(target_os = "macos")]
 async fn acquire_shareable_content_for_target(
     target: &ScreenCaptureTarget,
@@ -931,11 +1593,21 @@ fn shareable_content_display_ids(
 async fn finalize(
     actor: ActorHandle,
     duration: Option<f64>,
+    stop_after_idle: Option<f64>,
     interactive: bool,
     stop_file: Option<&Path>,
+    media_input: Option<&MediaInputFeed>,
+    encryption_key_file: Option<&Path>,
 ) -> Result<CompletedRecording, String> {
     let outcome = std::panic::AssertUnwindSafe(async {
-        wait_for_stop(duration, interactive, stop_file).await;
+        wait_for_stop(
+            duration,
+            stop_after_idle,
+            interactive,
+            stop_file,
+            media_input,
+        )
+        .await;
         actor.stop().await.map_err(|e| e.to_string())
     })
     .catch_unwind()
@@ -950,23 +1622,54 @@ async fn finalize(
             .map_err(|e| format!("recording panicked; finalize failed: {e}"))?,
     };
 
-    finalize_completed(completed).await
+    finalize_completed(completed, encryption_key_file).await
 }
 
-async fn finalize_completed(completed: CompletedRecording) -> Result<CompletedRecording, String> {
+async fn finalize_completed(
+    completed: CompletedRecording,
+    encryption_key_file: Option<&Path>,
+) -> Result<CompletedRecording, String> {
     match &completed {
         CompletedRecording::Studio(recording) => {
             let project_path = recording.project_path.clone();
+            let encryption_key_file = encryption_key_file.map(Path::to_path_buf);
             tokio::task::spawn_blocking(move || {
-                cap_recording::recovery::RecoveryManager::remux_if_needed(&project_path)
+                // An encrypted recording is remuxed in a decrypted copy and sealed back.
+                let master_key = if cap_project::encryption::is_encrypted(&project_path) {
+                    Some(crate::encryption::master_key(
+                        encryption_key_file.as_deref(),
+                    )?)
+                } else {
+                    None
+                };
+                cap_recording::recovery::RecoveryManager::remux_if_needed_with_key(
+                    &project_path,
+                    master_key.as_ref(),
+                )
+                .map(|_| ())
+                .map_err(|e| format!("Failed to finalize recording: {e}"))
             })
             .await
-            .map_err(|e| format!("recording finalize task failed: {e}"))?
-            .map_err(|e| format!("Failed to finalize recording: {e}"))?;
+            .map_err(|e| format!("recording finalize task failed: {e}"))??;
         }
         CompletedRecording::Instant(recording) => {
             finalize_instant_output(recording.project_path.clone()).await?;
-            persist_instant_recording_meta(recording)?;
+            persist_instant_recording_meta(&recording.project_path, &recording.meta)?;
+        }
+        CompletedRecording::Replay {
+            recording,
+            project_path,
+            length,
+        } => {
+            save_replay(
+                recording.project_path.clone(),
+                Some(*length),
+                project_path.clone(),
+            )
+            .await?;
+            if let Err(e) = std::fs::remove_dir_all(&recording.project_path) {
+                warn!("Failed to remove replay buffer: {e}");
+            }
         }
     }
 
@@ -1000,26 +1703,26 @@ async fn finalize_instant_output(project_path: PathBuf) -> Result<(), String> {
 }
 
 fn persist_instant_recording_meta(
-    recording: &instant_recording::CompletedRecording,
+    project_path: &Path,
+    meta: &InstantRecordingMeta,
 ) -> Result<(), String> {
-    let pretty_name = recording
-        .project_path
+    let pretty_name = project_path
         .file_stem()
         .and_then(|name| name.to_str())
         .filter(|name| !name.is_empty())
         .unwrap_or("Cap Recording")
         .to_string();
-    let meta = match &recording.meta {
-        InstantRecordingMeta::Complete { .. } => recording.meta.clone(),
+    let meta = match meta {
+        InstantRecordingMeta::Complete { .. } => meta.clone(),
         InstantRecordingMeta::InProgress { .. } => InstantRecordingMeta::Failed {
             error: "instant recording stopped before completion".to_string(),
         },
-        InstantRecordingMeta::Failed { .. } => recording.meta.clone(),
+        InstantRecordingMeta::Failed { .. } => meta.clone(),
     };
 
     RecordingMeta {
         platform: Some(Platform::default()),
-        project_path: recording.project_path.clone(),
+        project_path: project_path.to_path_buf(),
         pretty_name,
         sharing: None,
         inner: RecordingMetaInner::Instant(meta),
@@ -1029,10 +1732,33 @@ fn persist_instant_recording_meta(
     .map_err(|e| format!("Failed to save instant recording meta: {e}"))?;
 
     ProjectConfiguration::default()
-        .write(&recording.project_path)
+        .write(project_path)
         .map_err(|e| format!("Failed to save instant project config: {e}"))
 }
 
+/// Saves the newest `last` of the replay buffer at `buffer_dir` (all of it when `None`) as an instant
+/// project at `project_path`.
+async fn save_replay(
+    buffer_dir: PathBuf,
+    last: Option<Duration>,
+    project_path: PathBuf,
+) -> Result<replay::SavedReplay, String> {
+    let saved =
+        tokio::task::spawn_blocking(move || replay::save_replay(&buffer_dir, last, &project_path))
+            .await
+            .map_err(|e| format!("replay save task failed: {e}"))?
+            .map_err(|e| format!("Failed to save replay: {e}"))?;
+
+    persist_instant_recording_meta(
+        &saved.project_path,
+        &InstantRecordingMeta::Complete {
+            fps: saved.fps.unwrap_or(cap_recording::DEFAULT_INSTANT_MODE_FPS),
+            sample_rate: None,
+        },
+    )?;
+    Ok(saved)
+}
+
 fn emit_stopped(format: OutputFormat, completed: &CompletedRecording) -> Result<(), String> {
     let recording_meta_exists = completed
         .project_path()
@@ -1047,7 +1773,7 @@ fn emit_stopped(format: OutputFormat, completed: &CompletedRecording) -> Result<
     )
 }
 
-fn new_recording_id() -> String {
+pub(crate) fn new_recording_id() -> String {
     Uuid::new_v4().simple().to_string()[..12].to_string()
 }
 
@@ -1060,42 +1786,122 @@ fn resolve_path(params: &RecordParams, recording_id: &str) -> Result<PathBuf, St
     }
 }
 
+async fn lock_camera(device_id: &str) -> Result<Arc<CameraFeedLock>, String> {
+    let info = cap_camera::list_cameras()
+        .find(|c| c.device_id() == device_id)
+        .ok_or_else(|| {
+            let available: Vec<String> = cap_camera::list_cameras()
+                .map(|c| c.device_id().to_string())
+                .collect();
+            format!(
+                "Camera with id '{device_id}' not found. Available device ids: {available:?} \
+                 (see `cap targets cameras`)"
+            )
+        })?;
+    let id = camera::DeviceOrModelID::from_info(&info);
+
+    let camera_feed = CameraFeed::spawn(CameraFeed::default());
+    camera_feed
+        .ask(camera::SetInput { id, settings: None })
+        .await
+        .map_err(|e| format!("Failed to set camera input: {e}"))?
+        .await
+        .map_err(|e| format!("Camera failed to connect: {e}"))?;
+    let lock = camera_feed
+        .ask(camera::Lock)
+        .await
+        .map_err(|e| format!("Failed to lock camera feed: {e}"))?;
+    Ok(Arc::new(lock))
+}
+
+async fn lock_mic(mic_name: &str) -> Result<Arc<MicrophoneFeedLock>, String> {
+    let available = MicrophoneFeed::list();
+    if !available.contains_key(mic_name) {
+        let names: Vec<&str> = available.keys().map(String::as_str).collect();
+        return Err(format!(
+            "Microphone '{mic_name}' not found. Available: {names:?} (see `cap targets mics`)"
+        ));
+    }
+
+    let (error_tx, _error_rx) = flume::bounded(16);
+    let mic_feed = MicrophoneFeed::spawn(MicrophoneFeed::new(error_tx));
+    mic_feed
+        .ask(microphone::SetInput {
+            label: mic_name.to_string(),
+            settings: None,
+        })
+        .await
+        .map_err(|e| format!("Failed to set mic input: {e}"))?
+        .await
+        .map_err(|e| format!("Mic failed to connect: {e}"))?;
+    // The stream needs a moment to warm up before locking on slower devices.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let lock = mic_feed
+        .ask(microphone::Lock)
+        .await
+        .map_err(|e| format!("Failed to lock mic feed: {e}"))?;
+    Ok(Arc::new(lock))
+}
+
+fn resolve_display(id: &DisplayId) -> Result<ScreenCaptureTarget, String> {
+    cap_recording::screen_capture::list_displays()
+        .into_iter()
+        .find(|s| &s.0.id == id)
+        .map(|(s, _)| ScreenCaptureTarget::Display { id: s.id })
+        .ok_or_else(|| {
+            let available: Vec<String> = cap_recording::screen_capture::list_displays()
+                .into_iter()
+                .map(|(s, _)| s.id.to_string())
+                .collect();
+            format!(
+                "Screen with id '{id}' not found. Available screen ids: {available:?} \
+                 (see `cap targets screens`)"
+            )
+        })
+}
+
 fn resolve_target(params: &RecordParams) -> Result<ScreenCaptureTarget, String> {
-    match (&params.target.screen, &params.target.window) {
-        (Some(id), _) => cap_recording::screen_capture::list_displays()
-            .into_iter()
-            .find(|s| &s.0.id == id)
-            .map(|(s, _)| ScreenCaptureTarget::Display { id: s.id })
-            .ok_or_else(|| {
-                let available: Vec<String> = cap_recording::screen_capture::list_displays()
-                    .into_iter()
-                    .map(|(s, _)| s.id.to_string())
-                    .collect();
-                format!(
-                    "Screen with id '{id}' not found. Available screen ids: {available:?} \
-                     (see `cap targets screens`)"
-                )
-            }),
-        (_, Some(id)) => cap_recording::screen_capture::list_windows()
-            .into_iter()
-            .find(|s| &s.0.id == id)
-            .map(|(s, _)| ScreenCaptureTarget::Window { id: s.id })
-            .ok_or_else(|| {
-                format!(
-                    "Window with id '{id}' not found. Run `cap targets windows` to list window ids"
-                )
-            }),
+    // The recorder ignores the capture target when it has a media input.
+    if params.input.is_some() {
+        return Ok(ScreenCaptureTarget::CameraOnly);
+    }
+
+    match (
+        &params.target.screen,
+        &params.target.window,
+        &params.target.follow_window,
+    ) {
+        (Some(id), _, _) => resolve_display(id),
+        (_, Some(id), _) => resolve_window(id).map(|id| ScreenCaptureTarget::Window { id }),
+        (_, _, Some(id)) => resolve_window(id).map(|id| ScreenCaptureTarget::FollowWindow { id }),
         _ => Err(
-            "No target specified; pass --screen <id> or --window <id> (see `cap targets`)"
+            "No target specified; pass --screen <id>, --window <id>, --follow-window <id> or --input <path> (see `cap targets`)"
                 .to_string(),
         ),
     }
 }
 
-/// Block until the recording should stop: the duration elapses, the user presses Enter (interactive
-/// only), the process receives SIGINT/SIGTERM, or a detached worker's stop file appears. Every branch
-/// resolves so the caller can finalize the recording gracefully instead of being killed mid-write.
-async fn wait_for_stop(duration: Option<f64>, interactive: bool, stop_file: Option<&Path>) {
+fn resolve_window(id: &WindowId) -> Result<WindowId, String> {
+    cap_recording::screen_capture::list_windows()
+        .into_iter()
+        .find(|s| &s.0.id == id)
+        .map(|(s, _)| s.id)
+        .ok_or_else(|| {
+            format!("Window with id '{id}' not found. Run `cap targets windows` to list window ids")
+        })
+}
+
+/// Block until the recording should stop: the duration elapses, input has been idle for
+/// `--stop-after-idle`, the user presses Enter (interactive only), the process receives
+/// SIGINT/SIGTERM, a detached worker's stop file appears, or the `--input` media ends. Every branch resolves so the caller can finalize the recording gracefully
+/// instead of being killed mid-write.
+async fn wait_for_stop(
+    duration: Option<f64>,
+    stop_after_idle: Option<f64>,
+    interactive: bool,
+    stop_file: Option<&Path>,
+    media_input: Option<&MediaInputFeed>,
+) {
     #[cfg(unix)]
     let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).ok();
 
@@ -1106,6 +1912,21 @@ async fn wait_for_stop(duration: Option<f64>, interactive: bool, stop_file: Opti
                 None => std::future::pending::<()>().await,
             }
         } => {}
+        _ = async {
+            match stop_after_idle.map(Duration::from_secs_f64) {
+                Some(limit) => {
+                    let activity = InputActivity::spawn();
+                    loop {
+                        let idle = activity.idle_for();
+                        if idle >= limit {
+                            return;
+                        }
+                        tokio::time::sleep((limit - idle).min(Duration::from_secs(1))).await;
+                    }
+                }
+                None => std::future::pending::<()>().await,
+            }
+        } => {}
         _ = async {
             if duration.is_none() && interactive {
                 let _ = tokio::io::BufReader::new(tokio::io::stdin())
@@ -1135,6 +1956,12 @@ async fn wait_for_stop(duration: Option<f64>, interactive: bool, stop_file: Opti
                 None => std::future::pending::<()>().await,
             }
         } => {}
+        _ = async {
+            match media_input {
+                Some(media_input) => media_input.wait_finished().await,
+                None => std::future::pending::<()>().await,
+            }
+        } => {}
     }
 }
 
@@ -1146,6 +1973,10 @@ struct RecordTargets {
     /// ID of the window to capture
     #[arg(long, group = "target")]
     window: Option<WindowId>,
+    /// ID of a window to follow: records the screen under it, cropped to the window as it moves
+    /// between positions and displays
+    #[arg(long, group = "target")]
+    follow_window: Option<WindowId>,
 }
 
 // `rename_all` only renames the variant tags (started/stopped/error); `rename_all_fields` is what
@@ -1224,6 +2055,33 @@ mod tests {
         assert!(stopped.get("recording_meta_exists").is_none());
     }
 
+    #[test]
+    fn durations_accept_seconds_and_units() {
+        assert_eq!(parse_duration_secs("90"), Ok(90.0));
+        assert_eq!(parse_duration_secs("2.5"), Ok(2.5));
+        assert_eq!(parse_duration_secs("45m"), Ok(2700.0));
+        assert_eq!(parse_duration_secs("1h30m"), Ok(5400.0));
+        assert_eq!(parse_duration_secs("1m30s"), Ok(90.0));
+        for invalid in ["", "m", "10x", "1h30", "1.5m"] {
+            assert!(parse_duration_secs(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn schedule_args_round_trip_through_cli_args() {
+        let params = RecordParams::from_cli_args(&[
+            "--duration".to_string(),
+            "45m".to_string(),
+            "--stop-after-idle".to_string(),
+            "30".to_string(),
+        ])
+        .unwrap();
+        let reparsed = RecordParams::from_cli_args(&params.to_cli_args()).unwrap();
+        assert_eq!(reparsed.duration, Some(2700.0));
+        assert_eq!(reparsed.stop_after_idle, Some(30.0));
+        assert_eq!(reparsed.mode, RecordMode::Studio);
+    }
+
     #[test]
     fn dead_recording_session_reports_error_status() {
         let row = session_status_row_with_alive(
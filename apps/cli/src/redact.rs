@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use cap_redaction::{RedactionList, RedactionReport, ScanOptions};
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RedactOutput<'a> {
+    project_path: PathBuf,
+    /// False for `--report`, where masks are proposed but not saved.
+    applied: bool,
+    masks_added: usize,
+    #[serde(flatten)]
+    report: &'a RedactionReport,
+}
+
+pub async fn run(
+    project_path: PathBuf,
+    list: Option<PathBuf>,
+    options: ScanOptions,
+    report_only: bool,
+    format: OutputFormat,
+) -> Result<(), String> {
+    let list = match list {
+        Some(path) => RedactionList::load(&path)?,
+        None => RedactionList::default(),
+    };
+
+    let mut project = ProjectAccess::open(&project_path)?;
+    let report = cap_redaction::scan_project(project.path(), &list, options).await?;
+
+    let masks_added = if report_only || report.masks.is_empty() {
+        0
+    } else {
+        let added = cap_redaction::write_masks(project.path(), report.masks.clone())?;
+        project.persist()?;
+        added
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&RedactOutput {
+            project_path,
+            applied: !report_only,
+            masks_added,
+            report: &report,
+        }),
+        OutputFormat::Text => {
+            println!(
+                "Scanned {} frames with {} ({:.1}s of output)",
+                report.frames_scanned, report.engine, report.duration
+            );
+            for finding in &report.findings {
+                println!(
+                    "  {:>8.2}s  {:<12} {}",
+                    finding.time, finding.rule, finding.preview
+                );
+            }
+            if report.findings.is_empty() {
+                println!("No sensitive text found");
+            } else if report_only {
+                println!(
+                    "{} masks proposed; re-run without --report to add them",
+                    report.masks.len()
+                );
+            } else {
+                println!(
+                    "Added {masks_added} blur masks to {}",
+                    project_path.display()
+                );
+            }
+            Ok(())
+        }
+    }
+}
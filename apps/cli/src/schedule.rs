@@ -0,0 +1,863 @@
+//! Scheduled and triggered recordings for `cap record schedule`.
+//!
+//! Each schedule is a `<id>.json` file in `~/.cap/schedules` holding its trigger and the
+//! `cap record start` flags to record with. A background scheduler (`cap record schedule run`)
+//! polls that directory once a second, so schedules added or removed while it runs take effect on
+//! the next tick and persist across restarts of the scheduler or the machine. A firing trigger
+//! starts the same `__session-run` worker as `cap record start --detach`, so the recording shows up
+//! in `cap record status` and can be stopped with `cap record stop`.
+
+use std::{
+    collections::HashSet,
+    env::current_dir,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use clap::{Args, Subcommand};
+use scap_targets::WindowId;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{
+    OutputFormat,
+    record::{self, RecordParams},
+    resolve_format,
+    session::{self, SessionStatus},
+    write_json,
+};
+
+/// A one-shot schedule whose time passed while no scheduler was running is dropped rather than
+/// started late.
+const MISSED_GRACE_SECS: i64 = 60;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct ScheduleArgs {
+    #[command(subcommand)]
+    command: Option<ScheduleCommands>,
+    #[command(flatten)]
+    trigger: ScheduleTrigger,
+    #[command(flatten)]
+    params: RecordParams,
+    /// Directory to save repeating recordings to, as <recordingId>.cap [default: current directory]
+    #[arg(long, value_name = "DIR")]
+    dir: Option<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct ScheduleTrigger {
+    /// Start once at this local time: HH:MM (its next occurrence), YYYY-MM-DD HH:MM, or RFC 3339
+    #[arg(long, value_name = "TIME")]
+    at: Option<String>,
+    /// Start on a five-field cron schedule in local time, e.g. "0 2 * * 1-5"
+    #[arg(long, value_name = "EXPR")]
+    cron: Option<String>,
+    /// Start whenever a window whose title contains TITLE (case-insensitive) appears; the window is
+    /// recorded unless --screen, --window or --input is given
+    #[arg(long, value_name = "TITLE")]
+    when_window: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// List saved schedules and whether the scheduler is running
+    List(ScheduleFormatArgs),
+    /// Delete a saved schedule
+    Remove(ScheduleRemoveArgs),
+    /// Run the scheduler in the foreground (e.g. from a login item or service unit)
+    Run,
+}
+
+#[derive(Args)]
+struct ScheduleFormatArgs {
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct ScheduleRemoveArgs {
+    /// scheduleId shown by `cap record schedule list`
+    id: String,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Trigger {
+    /// Unix seconds.
+    At {
+        at: i64,
+    },
+    Cron {
+        expression: String,
+    },
+    Window {
+        title: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub schedule_id: String,
+    pub trigger: Trigger,
+    /// Where recordings without an explicit `--path` are saved.
+    pub directory: PathBuf,
+    /// `cap record start` flags, as produced by [`RecordParams::to_cli_args`].
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_recording_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchedulerFile {
+    pid: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleRow<'a> {
+    #[serde(flatten)]
+    schedule: &'a Schedule,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_run_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduleList<'a> {
+    scheduler_pid: Option<u32>,
+    schedules: Vec<ScheduleRow<'a>>,
+}
+
+impl ScheduleArgs {
+    pub async fn run(self, json: bool) -> Result<(), String> {
+        let (format, result) = match self.command {
+            Some(ScheduleCommands::List(args)) => {
+                let format = resolve_format(json, args.format);
+                (format, list(format))
+            }
+            Some(ScheduleCommands::Remove(args)) => {
+                let format = resolve_format(json, args.format);
+                (format, remove(&args.id, format))
+            }
+            Some(ScheduleCommands::Run) => return run_scheduler().await,
+            None => {
+                let format = resolve_format(json, self.format);
+                (format, add(self.trigger, self.params, self.dir, format))
+            }
+        };
+
+        if format == OutputFormat::Json
+            && let Err(error) = &result
+        {
+            let _ = write_json(&serde_json::json!({ "error": error }));
+        }
+        result
+    }
+
+    /// Whether this invocation is the scheduler itself.
+    pub fn runs_scheduler(&self) -> bool {
+        matches!(self.command, Some(ScheduleCommands::Run))
+    }
+}
+
+fn add(
+    trigger: ScheduleTrigger,
+    mut params: RecordParams,
+    dir: Option<PathBuf>,
+    format: OutputFormat,
+) -> Result<(), String> {
+    params.validate()?;
+    if params.reads_stdin() {
+        return Err(
+            "--input - reads from stdin, which scheduled recordings cannot use".to_string(),
+        );
+    }
+
+    let now = Local::now();
+    let trigger = if let Some(at) = trigger.at {
+        Trigger::At {
+            at: parse_at(&at, &now)?.timestamp(),
+        }
+    } else if let Some(expression) = trigger.cron {
+        CronSchedule::parse(&expression)?;
+        Trigger::Cron { expression }
+    } else if let Some(title) = trigger.when_window {
+        if title.trim().is_empty() {
+            return Err("--when-window needs a non-empty title".to_string());
+        }
+        Trigger::Window { title }
+    } else {
+        return Err("pass one of --at, --cron or --when-window".to_string());
+    };
+
+    let cwd = current_dir().map_err(|e| format!("Could not determine current directory: {e}"))?;
+    if let Some(path) = params.path().map(Path::to_path_buf) {
+        if !matches!(trigger, Trigger::At { .. }) {
+            return Err(
+                "--path only applies to --at schedules; repeating schedules save each recording \
+                 as <recordingId>.cap in --dir"
+                    .to_string(),
+            );
+        }
+        params.set_path(cwd.join(path));
+    }
+
+    let schedule = Schedule {
+        schedule_id: record::new_recording_id(),
+        trigger,
+        directory: dir.map_or_else(|| cwd.clone(), |dir| cwd.join(dir)),
+        args: params.to_cli_args(),
+        created_at: session::now_unix(),
+        last_run_at: None,
+        last_recording_id: None,
+        last_error: None,
+    };
+    write_schedule(&schedule)?;
+    let scheduler_pid = ensure_scheduler()?;
+
+    let row = schedule_row(&schedule, &now);
+    match format {
+        OutputFormat::Json => write_json(&row),
+        OutputFormat::Text => {
+            println!(
+                "Scheduled {}: {}",
+                schedule.schedule_id,
+                describe(&schedule.trigger, row.next_run_at)
+            );
+            println!("Scheduler running (pid {scheduler_pid})");
+            Ok(())
+        }
+    }
+}
+
+fn list(format: OutputFormat) -> Result<(), String> {
+    let schedules = list_schedules()?;
+    let now = Local::now();
+    let list = ScheduleList {
+        scheduler_pid: scheduler_pid(),
+        schedules: schedules.iter().map(|s| schedule_row(s, &now)).collect(),
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&list),
+        OutputFormat::Text => {
+            match list.scheduler_pid {
+                Some(pid) => println!("Scheduler running (pid {pid})"),
+                None => println!(
+                    "Scheduler not running; start it with `cap record schedule run` or add a schedule"
+                ),
+            }
+            if list.schedules.is_empty() {
+                println!("No schedules");
+            }
+            for row in &list.schedules {
+                println!(
+                    "{}  {}",
+                    row.schedule.schedule_id,
+                    describe(&row.schedule.trigger, row.next_run_at)
+                );
+                if let Some(recording_id) = &row.schedule.last_recording_id {
+                    println!("    last recording: {recording_id}");
+                }
+                if let Some(error) = &row.schedule.last_error {
+                    println!("    last error: {error}");
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn remove(id: &str, format: OutputFormat) -> Result<(), String> {
+    let path = schedule_file(id)?;
+    std::fs::remove_file(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("No schedule found with id '{id}'"),
+        _ => format!("Could not remove schedule: {e}"),
+    })?;
+
+    match format {
+        OutputFormat::Json => write_json(&serde_json::json!({ "removed": id })),
+        OutputFormat::Text => {
+            println!("Removed schedule {id}");
+            Ok(())
+        }
+    }
+}
+
+fn schedule_row<'a>(schedule: &'a Schedule, now: &DateTime<Local>) -> ScheduleRow<'a> {
+    let next_run_at = match &schedule.trigger {
+        Trigger::At { at } => Some(*at),
+        Trigger::Cron { expression } => CronSchedule::parse(expression)
+            .ok()
+            .and_then(|cron| cron.next_after(now))
+            .map(|next| next.timestamp()),
+        Trigger::Window { .. } => None,
+    };
+    ScheduleRow {
+        schedule,
+        next_run_at,
+    }
+}
+
+fn describe(trigger: &Trigger, next_run_at: Option<i64>) -> String {
+    let next = next_run_at
+        .and_then(|at| Local.timestamp_opt(at, 0).single())
+        .map(|at| at.format("%Y-%m-%d %H:%M").to_string());
+    match trigger {
+        Trigger::At { .. } => format!("once at {}", next.unwrap_or_default()),
+        Trigger::Cron { expression } => match next {
+            Some(next) => format!("cron '{expression}' (next {next})"),
+            None => format!("cron '{expression}'"),
+        },
+        Trigger::Window { title } => format!("when a window titled '{title}' appears"),
+    }
+}
+
+/// Parses `--at` relative to `now`: a bare `HH:MM` is its next occurrence, while dates and RFC 3339
+/// timestamps must be in the future.
+fn parse_at<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Result<DateTime<Tz>, String> {
+    let timezone = now.timezone();
+    let local = |naive: NaiveDateTime| timezone.from_local_datetime(&naive).earliest();
+
+    let at = if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        Some(at.with_timezone(&timezone))
+    } else if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+    {
+        local(naive)
+    } else if let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M") {
+        let today = now.date_naive();
+        local(today.and_time(time))
+            .filter(|at| at > now)
+            .or_else(|| local(today.succ_opt()?.and_time(time)))
+    } else {
+        return Err(format!(
+            "--at must be HH:MM, YYYY-MM-DD HH:MM or an RFC 3339 timestamp (got '{value}')"
+        ));
+    };
+
+    match at {
+        Some(at) if at > *now => Ok(at),
+        Some(_) => Err(format!("--at {value} is in the past")),
+        None => Err(format!(
+            "--at {value} does not exist in the local time zone"
+        )),
+    }
+}
+
+/// A standard five-field cron expression (minute, hour, day of month, month, day of week) with `*`,
+/// lists, ranges and `/step`. As in cron, a restricted day of month and day of week match either.
+#[derive(Debug, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "cron expression '{expression}' must have five fields: minute hour day-of-month month day-of-week"
+            ));
+        };
+
+        let field = |spec: &str, name: &str, min: u32, max: u32| {
+            parse_cron_field(spec, min, max)
+                .map_err(|e| format!("cron expression '{expression}': {name} {e}"))
+        };
+
+        let mut days_of_week = field(day_of_week, "day of week", 0, 7)?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: field(minute, "minute", 0, 59)?,
+            hours: field(hour, "hour", 0, 23)?,
+            days_of_month: field(day_of_month, "day of month", 1, 31)?,
+            months: field(month, "month", 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day_of_month = self.days_of_month & (1 << date.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    /// The first matching minute strictly after `after`, looking up to five years ahead.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let mut date = after.date_naive();
+        let mut first_minute = after.hour() * 60 + after.minute() + 1;
+
+        for _ in 0..366 * 5 {
+            if self.matches_date(date) {
+                for minute_of_day in first_minute..24 * 60 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if self.hours & (1 << hour) == 0 || self.minutes & (1 << minute) == 0 {
+                        continue;
+                    }
+                    // Minutes skipped by a DST change have no local time and are passed over.
+                    if let Some(next) = date
+                        .and_hms_opt(hour, minute, 0)
+                        .and_then(|naive| timezone.from_local_datetime(&naive).earliest())
+                        .filter(|next| next > after)
+                    {
+                        return Some(next);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+            first_minute = 0;
+        }
+        None
+    }
+}
+
+fn parse_cron_field(spec: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or_else(|| format!("has an invalid step in '{part}'"))?,
+            ),
+            None => (part, 1),
+        };
+
+        let value = |v: &str| {
+            v.parse::<u32>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(|| format!("'{part}' is outside {min}-{max}"))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` means every 15 from 5.
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("has a reversed range in '{part}'"));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+fn schedules_dir() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .ok_or_else(|| "Could not determine home directory".to_string())
+        .map(|home| home.join(".cap").join("schedules"))
+}
+
+fn schedule_file(id: &str) -> Result<PathBuf, String> {
+    Ok(schedules_dir()?.join(format!("{id}.json")))
+}
+
+fn scheduler_file() -> Result<PathBuf, String> {
+    Ok(schedules_dir()?.join("scheduler.pid.json"))
+}
+
+fn write_json_file(path: &Path, value: &impl Serialize) -> Result<(), String> {
+    let dir = schedules_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Could not create schedules dir: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    let body = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    // Write to a temp file then rename so the scheduler never reads a half-written file.
+    std::fs::write(&tmp, body).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Could not finalize {}: {e}", path.display()))
+}
+
+fn write_schedule(schedule: &Schedule) -> Result<(), String> {
+    write_json_file(&schedule_file(&schedule.schedule_id)?, schedule)
+}
+
+fn remove_schedule_file(id: &str) {
+    if let Ok(path) = schedule_file(id) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn list_schedules() -> Result<Vec<Schedule>, String> {
+    let dir = schedules_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut schedules: Vec<Schedule> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Could not read schedules dir: {e}"))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()?.to_str()? != "json" {
+                return None;
+            }
+            serde_json::from_slice(&std::fs::read(&path).ok()?).ok()
+        })
+        .collect();
+    schedules.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(schedules)
+}
+
+/// The pid of the running scheduler, if any.
+fn scheduler_pid() -> Option<u32> {
+    let body = std::fs::read(scheduler_file().ok()?).ok()?;
+    let file: SchedulerFile = serde_json::from_slice(&body).ok()?;
+    session::process_alive(file.pid).then_some(file.pid)
+}
+
+/// Starts a background scheduler unless one is already running, returning its pid.
+fn ensure_scheduler() -> Result<u32, String> {
+    if let Some(pid) = scheduler_pid() {
+        return Ok(pid);
+    }
+
+    let exe =
+        std::env::current_exe().map_err(|e| format!("Could not locate the cap executable: {e}"))?;
+    let log_path = schedules_dir()?.join("scheduler.log");
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Could not open scheduler log {}: {e}", log_path.display()))?;
+    let log_err = log
+        .try_clone()
+        .map_err(|e| format!("Could not prepare scheduler log: {e}"))?;
+
+    let mut command = std::process::Command::new(&exe);
+    command
+        .args(["record", "schedule", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(log_err));
+    record::detach(&mut command);
+
+    let child = command
+        .spawn()
+        .map_err(|e| format!("Could not start the scheduler: {e}"))?;
+    Ok(child.id())
+}
+
+/// Restarts the scheduler when schedules are saved but nothing is running them, e.g. after a
+/// reboot or a crash. Every `cap` invocation calls this, so schedules keep firing once the CLI has
+/// been used again.
+pub fn revive_scheduler() {
+    if scheduler_pid().is_some() {
+        return;
+    }
+    let count = match list_schedules() {
+        Ok(schedules) if !schedules.is_empty() => schedules.len(),
+        _ => return,
+    };
+    match ensure_scheduler() {
+        Ok(pid) => info!("Restarted the scheduler for {count} saved schedules (pid {pid})"),
+        Err(error) => warn!("Could not restart the scheduler: {error}"),
+    }
+}
+
+async fn run_scheduler() -> Result<(), String> {
+    let pid = std::process::id();
+    if let Some(running) = scheduler_pid().filter(|&running| running != pid) {
+        return Err(format!("The scheduler is already running (pid {running})"));
+    }
+    write_json_file(&scheduler_file()?, &SchedulerFile { pid })?;
+    info!("Scheduler started (pid {pid})");
+
+    #[cfg(unix)]
+    let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).ok();
+
+    let mut scheduler = Scheduler {
+        last_tick: Local::now(),
+        visible_windows: HashSet::new(),
+    };
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            _ = tokio::signal::ctrl_c() => break,
+            _ = async {
+                #[cfg(unix)]
+                if let Some(term) = term.as_mut() {
+                    term.recv().await;
+                    return;
+                }
+                std::future::pending::<()>().await;
+            } => break,
+        }
+        scheduler.tick().await;
+    }
+
+    if scheduler_pid() == Some(pid) {
+        let _ = std::fs::remove_file(scheduler_file()?);
+    }
+    info!("Scheduler stopped");
+    Ok(())
+}
+
+struct Scheduler {
+    last_tick: DateTime<Local>,
+    /// Window schedules whose window was already open on the last tick, so they only fire when it
+    /// appears rather than on every tick it stays open.
+    visible_windows: HashSet<String>,
+}
+
+impl Scheduler {
+    async fn tick(&mut self) {
+        let now = Local::now();
+        let schedules = match list_schedules() {
+            Ok(schedules) => schedules,
+            Err(error) => {
+                warn!("Could not load schedules: {error}");
+                return;
+            }
+        };
+
+        let windows: Vec<(WindowId, String)> = if schedules
+            .iter()
+            .any(|s| matches!(s.trigger, Trigger::Window { .. }))
+        {
+            scap_targets::Window::list()
+                .into_iter()
+                .filter_map(|window| Some((window.id(), window.name()?.to_lowercase())))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for schedule in schedules {
+            let mut window = None;
+            let due = match &schedule.trigger {
+                Trigger::At { at } => {
+                    if now.timestamp() - at > MISSED_GRACE_SECS {
+                        warn!(
+                            "Dropping schedule {}: its start time passed while the scheduler was not running",
+                            schedule.schedule_id
+                        );
+                        remove_schedule_file(&schedule.schedule_id);
+                        continue;
+                    }
+                    now.timestamp() >= *at
+                }
+                Trigger::Cron { expression } => CronSchedule::parse(expression)
+                    .ok()
+                    .and_then(|cron| cron.next_after(&self.last_tick))
+                    .is_some_and(|next| next <= now),
+                Trigger::Window { title } => {
+                    let title = title.to_lowercase();
+                    window = windows
+                        .iter()
+                        .find(|(_, name)| name.contains(&title))
+                        .map(|(id, _)| id.clone());
+                    let appeared =
+                        window.is_some() && !self.visible_windows.contains(&schedule.schedule_id);
+                    if window.is_some() {
+                        self.visible_windows.insert(schedule.schedule_id.clone());
+                    } else {
+                        self.visible_windows.remove(&schedule.schedule_id);
+                    }
+                    appeared
+                }
+            };
+
+            if due {
+                fire(schedule, window).await;
+            }
+        }
+
+        self.last_tick = now;
+    }
+}
+
+/// Starts a recording for `schedule`, unless its previous recording is still going.
+async fn fire(mut schedule: Schedule, window: Option<WindowId>) {
+    let id = schedule.schedule_id.clone();
+    if let Some(previous) = &schedule.last_recording_id
+        && session::read_session(previous).is_ok_and(|session| {
+            session.status == SessionStatus::Recording && session::process_alive(session.pid)
+        })
+    {
+        info!("Schedule {id} is due but its recording {previous} is still running");
+        return;
+    }
+
+    let result = start(&schedule, window).await;
+    if matches!(schedule.trigger, Trigger::At { .. }) {
+        remove_schedule_file(&id);
+    }
+
+    schedule.last_run_at = session::now_unix();
+    match result {
+        Ok(recording_id) => {
+            info!("Schedule {id} started recording {recording_id}");
+            schedule.last_recording_id = Some(recording_id);
+            schedule.last_error = None;
+        }
+        Err(error) => {
+            warn!("Schedule {id} could not start recording: {error}");
+            schedule.last_error = Some(error);
+        }
+    }
+
+    // Skip the write if the schedule was removed (or was one-shot) while the recording started.
+    if !matches!(schedule.trigger, Trigger::At { .. })
+        && schedule_file(&id).is_ok_and(|path| path.exists())
+        && let Err(error) = write_schedule(&schedule)
+    {
+        warn!("Could not update schedule {id}: {error}");
+    }
+}
+
+async fn start(schedule: &Schedule, window: Option<WindowId>) -> Result<String, String> {
+    let mut params = RecordParams::from_cli_args(&schedule.args)?;
+    if let Some(window) = window
+        && !params.has_target()
+    {
+        params.set_window(window);
+    }
+
+    let recording_id = record::new_recording_id();
+    let path = match params.path() {
+        Some(path) => path.to_path_buf(),
+        None => schedule.directory.join(format!("{recording_id}.cap")),
+    };
+    record::spawn_worker(params, &recording_id, path).await?;
+    Ok(recording_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn cron_finds_next_matching_minute() {
+        let weekday_nights = CronSchedule::parse("30 2 * * 1-5").unwrap();
+        // 2026-10-17 is a Saturday, so the next run is Monday night.
+        assert_eq!(
+            weekday_nights.next_after(&utc("2026-10-17T12:00:00Z")),
+            Some(utc("2026-10-19T02:30:00Z"))
+        );
+
+        let every_quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            every_quarter.next_after(&utc("2026-10-19T10:15:00Z")),
+            Some(utc("2026-10-19T10:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn cron_matches_either_restricted_day_field() {
+        // The 1st of the month or any Sunday (7 is also Sunday).
+        let cron = CronSchedule::parse("0 0 1 * 7").unwrap();
+        assert_eq!(
+            cron.next_after(&utc("2026-10-19T00:00:00Z")),
+            Some(utc("2026-10-25T00:00:00Z"))
+        );
+        assert_eq!(
+            cron.next_after(&utc("2026-10-25T00:00:00Z")),
+            Some(utc("2026-11-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn cron_rejects_malformed_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{expression} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn at_resolves_clock_times_to_their_next_occurrence() {
+        let now = utc("2026-10-19T15:00:00Z");
+        assert_eq!(
+            parse_at("14:00", &now).unwrap(),
+            utc("2026-10-20T14:00:00Z")
+        );
+        assert_eq!(
+            parse_at("16:30", &now).unwrap(),
+            utc("2026-10-19T16:30:00Z")
+        );
+        assert_eq!(
+            parse_at("2026-10-21 09:00", &now).unwrap(),
+            utc("2026-10-21T09:00:00Z")
+        );
+        assert!(parse_at("2026-10-18 09:00", &now).is_err());
+        assert!(parse_at("tomorrow", &now).is_err());
+    }
+
+    #[test]
+    fn schedule_trigger_is_tagged_camel_case() {
+        let value = serde_json::to_value(Trigger::Window {
+            title: "Test Runner".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "type": "window", "title": "Test Runner" })
+        );
+    }
+}
@@ -571,7 +571,7 @@ mod fixture {
     use cap_recording::{
         AudioFrame, ChannelAudioSource, ChannelAudioSourceConfig, ChannelVideoSource,
         ChannelVideoSourceConfig, OutputPipeline,
-        ffmpeg::{FFmpegVideoFrame, Mp4Muxer, OggMuxer},
+        ffmpeg::{FFmpegVideoFrame, Mp4Muxer, OggMuxer, OggMuxerConfig},
     };
     use cap_timestamp::{Timestamp, Timestamps};
     use relative_path::RelativePathBuf;
@@ -783,7 +783,7 @@ mod fixture {
                 audio_info, mic_rx,
             ))
             .with_timestamps(timestamps)
-            .build::<OggMuxer>(())
+            .build::<OggMuxer>(OggMuxerConfig::default())
             .await
             .map_err(|e| format!("mic pipeline: {e}"))?;
         let sys_pipeline = OutputPipeline::builder(audio_path.clone())
@@ -794,7 +794,7 @@ mod fixture {
             // System audio anchors at the recording epoch, exactly like the
             // studio recorder configures it.
             .with_audio_anchor(cap_recording::AudioAnchor::PipelineEpoch)
-            .build::<OggMuxer>(())
+            .build::<OggMuxer>(OggMuxerConfig::default())
             .await
             .map_err(|e| format!("system audio pipeline: {e}"))?;
 
@@ -870,6 +870,9 @@ mod fixture {
             cursor: None,
             keyboard: None,
             display_notch: None,
+            additional_videos: Vec::new(),
+            additional_mics: Vec::new(),
+            imported: None,
         };
         // Clip offsets exactly as the studio recorder persists them.
         let offsets = segment.calculate_audio_offsets();
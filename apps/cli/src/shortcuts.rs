@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use cap_export::{
+    shortcuts::{Shortcut, ShortcutSource, load_shortcuts, to_markdown, to_srt, to_webvtt},
+    thumbnails::render_frames_at,
+};
+use cap_project::{KEYBOARD_EVENTS_FILE_NAME, LEGACY_KEYBOARD_EVENTS_FILE_NAME, RecordingMeta};
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+const VTT: &str = "shortcuts.vtt";
+const SRT: &str = "shortcuts.srt";
+const JSON: &str = "shortcuts.json";
+const STEPS: &str = "steps.md";
+const FRAMES_DIR: &str = "steps";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShortcutsOutput {
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    source: ShortcutSource,
+    vtt: PathBuf,
+    srt: PathBuf,
+    json: PathBuf,
+    steps: PathBuf,
+    frames: Vec<PathBuf>,
+    shortcuts: Vec<Shortcut>,
+}
+
+pub struct ShortcutsOptions {
+    /// Width of step screenshots, `None` to skip them.
+    pub frame_width: Option<u32>,
+    pub force_ffmpeg_decoder: bool,
+}
+
+fn is_metadata_or_key_presses(relative: &Path) -> bool {
+    relative.components().count() == 1
+        || relative.file_name().is_some_and(|name| {
+            name == KEYBOARD_EVENTS_FILE_NAME || name == LEGACY_KEYBOARD_EVENTS_FILE_NAME
+        })
+}
+
+pub async fn run(
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    options: ShortcutsOptions,
+    format: OutputFormat,
+) -> Result<(), String> {
+    if options.frame_width == Some(0) {
+        return Err("--frame-width must be at least 1".to_string());
+    }
+
+    crate::project::validate_project(&project_path)?;
+    // Step screenshots are rendered from the media; the shortcuts alone only need the key presses.
+    let project = if options.frame_width.is_some() {
+        ProjectAccess::open(&project_path)?
+    } else {
+        ProjectAccess::open_only(&project_path, is_metadata_or_key_presses)?
+    };
+
+    let meta = RecordingMeta::load_for_project(project.path())
+        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let track = load_shortcuts(project.path())?;
+
+    // Each step is captured as it ends, once its result is on screen.
+    let images = match options.frame_width {
+        Some(width) if !track.shortcuts.is_empty() => {
+            let times: Vec<f64> = track.shortcuts.iter().map(|s| s.end).collect();
+            render_frames_at(
+                project.path().to_path_buf(),
+                &times,
+                width,
+                options.force_ffmpeg_decoder,
+            )
+            .await
+            .map_err(|e| format!("Failed to render step frames: {e}"))?
+        }
+        _ => vec![],
+    };
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", output_dir.display()))?;
+
+    let mut frames = Vec::with_capacity(images.len());
+    let mut frame_links = Vec::with_capacity(images.len());
+    if !images.is_empty() {
+        let frames_dir = output_dir.join(FRAMES_DIR);
+        std::fs::create_dir_all(&frames_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", frames_dir.display()))?;
+
+        for (i, image) in images.iter().enumerate() {
+            let name = format!("step-{:03}.png", i + 1);
+            let path = frames_dir.join(&name);
+            image
+                .save(&path)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            frame_links.push(format!("{FRAMES_DIR}/{name}"));
+            frames.push(path);
+        }
+    }
+
+    let json = serde_json::to_vec_pretty(&track).map_err(|e| e.to_string())?;
+    let files = [
+        (VTT, to_webvtt(&track.shortcuts).into_bytes()),
+        (SRT, to_srt(&track.shortcuts).into_bytes()),
+        (JSON, json),
+        (
+            STEPS,
+            to_markdown(&meta.pretty_name, &track.shortcuts, &frame_links).into_bytes(),
+        ),
+    ];
+    for (name, contents) in files {
+        let path = output_dir.join(name);
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+
+    let output = ShortcutsOutput {
+        vtt: output_dir.join(VTT),
+        srt: output_dir.join(SRT),
+        json: output_dir.join(JSON),
+        steps: output_dir.join(STEPS),
+        project_path,
+        output_dir,
+        source: track.source,
+        frames,
+        shortcuts: track.shortcuts,
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&output),
+        OutputFormat::Text => {
+            let source = match output.source {
+                ShortcutSource::Timeline => "keyboard track",
+                ShortcutSource::Recording => "recorded key presses",
+            };
+            println!(
+                "Exported {} shortcuts from the {source} to {}",
+                output.shortcuts.len(),
+                output.output_dir.display()
+            );
+            println!("  webvtt track  {}", output.vtt.display());
+            println!("  srt track     {}", output.srt.display());
+            println!("  json          {}", output.json.display());
+            println!("  steps         {}", output.steps.display());
+            if !output.frames.is_empty() {
+                println!("  screenshots   {} in {FRAMES_DIR}/", output.frames.len());
+            }
+            Ok(())
+        }
+    }
+}
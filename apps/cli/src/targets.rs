@@ -1,3 +1,4 @@
+use cap_recording::screen_capture::app_audio::{self, AudioApp};
 use scap_targets::{DisplayId, WindowId};
 use serde::Serialize;
 
@@ -67,6 +68,15 @@ pub struct MicTarget {
     pub name: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioAppTarget {
+    pub index: usize,
+    /// The stream; its `name` or `pid` is what `cap record --app-audio` expects.
+    #[serde(flatten)]
+    pub app: AudioApp,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AllTargets {
@@ -146,6 +156,16 @@ pub fn mics() -> Vec<MicTarget> {
         .collect()
 }
 
+pub fn audio_apps() -> Result<Vec<AudioAppTarget>, String> {
+    let apps = app_audio::list_audio_apps().map_err(|e| e.to_string())?;
+
+    Ok(apps
+        .into_iter()
+        .enumerate()
+        .map(|(index, app)| AudioAppTarget { index, app })
+        .collect())
+}
+
 pub fn all() -> AllTargets {
     AllTargets {
         screens: screens(),
@@ -213,6 +233,17 @@ pub fn print_mics(format: OutputFormat) -> Result<(), String> {
     }
 }
 
+pub fn print_audio_apps(format: OutputFormat) -> Result<(), String> {
+    let apps = audio_apps()?;
+    match format {
+        OutputFormat::Text => {
+            print_audio_app_table(&apps);
+            Ok(())
+        }
+        OutputFormat::Json => write_json(&apps),
+    }
+}
+
 fn print_screen_table(screens: &[ScreenTarget]) {
     for screen in screens {
         let primary = if screen.primary { " (primary)" } else { "" };
@@ -261,3 +292,22 @@ fn print_mic_table(mics: &[MicTarget]) {
         println!("mic {}:\n  name: {}", mic.index, mic.name);
     }
 }
+
+fn print_audio_app_table(apps: &[AudioAppTarget]) {
+    for target in apps {
+        let app = &target.app;
+        println!(
+            "audio app {}:\n  name: {}\n  node_id: {}",
+            target.index, app.name, app.node_id
+        );
+        if let Some(binary) = &app.binary {
+            println!("  binary: {binary}");
+        }
+        if let Some(pid) = app.pid {
+            println!("  pid: {pid}");
+        }
+        if let Some(media_name) = &app.media_name {
+            println!("  media: {media_name}");
+        }
+    }
+}
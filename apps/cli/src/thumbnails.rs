@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use cap_export::thumbnails::{
+    ThumbnailOptions, ThumbnailSelection, contact_sheet, render_thumbnails, thumbnail_sprite,
+};
+use image::RgbaImage;
+use serde::Serialize;
+
+use crate::{OutputFormat, encryption::ProjectAccess, write_json};
+
+const CONTACT_SHEET: &str = "contact-sheet.png";
+const SPRITE: &str = "sprite.png";
+const VTT: &str = "thumbnails.vtt";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThumbnailsOutput {
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    duration: f64,
+    contact_sheet: PathBuf,
+    sprite: PathBuf,
+    vtt: PathBuf,
+    frames: Vec<FrameOutput>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameOutput {
+    time: f64,
+    start: f64,
+    end: f64,
+    path: PathBuf,
+}
+
+pub struct ThumbnailsOptions {
+    pub count: u32,
+    /// Pick frames at scene changes above this threshold instead of evenly.
+    pub scene_threshold: Option<f64>,
+    pub sample_interval: f64,
+    pub width: u32,
+    pub columns: u32,
+    pub force_ffmpeg_decoder: bool,
+}
+
+pub async fn run(
+    project_path: PathBuf,
+    output_dir: PathBuf,
+    options: ThumbnailsOptions,
+    format: OutputFormat,
+) -> Result<(), String> {
+    if options.count == 0 {
+        return Err("--count must be at least 1".to_string());
+    }
+    if options.columns == 0 {
+        return Err("--columns must be at least 1".to_string());
+    }
+
+    crate::project::validate_project(&project_path)?;
+    let project = ProjectAccess::open(&project_path)?;
+
+    let selection = match options.scene_threshold {
+        Some(threshold) => ThumbnailSelection::SceneChange {
+            threshold,
+            max_count: options.count,
+            sample_interval: options.sample_interval,
+        },
+        None => ThumbnailSelection::Even {
+            count: options.count,
+        },
+    };
+
+    let set = render_thumbnails(
+        project.path().to_path_buf(),
+        ThumbnailOptions {
+            selection,
+            width: options.width,
+        },
+        options.force_ffmpeg_decoder,
+    )
+    .await
+    .map_err(|e| format!("Failed to render thumbnails: {e}"))?;
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", output_dir.display()))?;
+
+    let mut frames = Vec::with_capacity(set.thumbnails.len());
+    for (i, thumbnail) in set.thumbnails.iter().enumerate() {
+        let path = output_dir.join(format!("frame-{:03}.png", i + 1));
+        save_png(&thumbnail.image, &path)?;
+        frames.push(FrameOutput {
+            time: thumbnail.time,
+            start: thumbnail.start,
+            end: thumbnail.end,
+            path,
+        });
+    }
+
+    let contact_sheet_path = output_dir.join(CONTACT_SHEET);
+    save_png(
+        &contact_sheet(&set.thumbnails, options.columns),
+        &contact_sheet_path,
+    )?;
+
+    // Cues reference the sprite relative to the track so the pair can be
+    // uploaded side by side.
+    let sprite = thumbnail_sprite(&set.thumbnails, options.columns, SPRITE);
+    let sprite_path = output_dir.join(SPRITE);
+    save_png(&sprite.image, &sprite_path)?;
+    let vtt_path = output_dir.join(VTT);
+    std::fs::write(&vtt_path, &sprite.vtt)
+        .map_err(|e| format!("Failed to write {}: {e}", vtt_path.display()))?;
+
+    let output = ThumbnailsOutput {
+        project_path,
+        output_dir,
+        duration: set.duration,
+        contact_sheet: contact_sheet_path,
+        sprite: sprite_path,
+        vtt: vtt_path,
+        frames,
+    };
+
+    match format {
+        OutputFormat::Json => write_json(&output),
+        OutputFormat::Text => {
+            println!(
+                "Rendered {} thumbnails of {:.2}s to {}",
+                output.frames.len(),
+                output.duration,
+                output.output_dir.display()
+            );
+            println!("  contact sheet  {}", output.contact_sheet.display());
+            println!("  sprite         {}", output.sprite.display());
+            println!("  webvtt track   {}", output.vtt.display());
+            Ok(())
+        }
+    }
+}
+
+fn save_png(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    image
+        .save(path)
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
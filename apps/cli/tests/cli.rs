@@ -237,6 +237,194 @@ fn project_inspect_missing_fails() {
     assert!(stderr(&output).to_lowercase().contains("failed to load"));
 }
 
+#[test]
+fn project_redact_rejects_missing_list_before_scanning() {
+    let dir = tempfile::tempdir().unwrap();
+    let list = dir.path().join("missing.json");
+    let output = run(&[
+        "project",
+        "redact",
+        dir.path().to_str().unwrap(),
+        "--list",
+        list.to_str().unwrap(),
+        "--report",
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("Failed to read redaction list")
+    );
+}
+
+#[test]
+fn project_import_clip_rejects_unsupported_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let clip = dir.path().join("notes.txt");
+    std::fs::write(&clip, "not a video").unwrap();
+    let output = run(&[
+        "project",
+        "import-clip",
+        dir.path().to_str().unwrap(),
+        clip.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(json["error"].as_str().unwrap().contains("Unsupported clip"));
+}
+
+#[test]
+fn project_merge_rejects_projects_without_meta() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.cap");
+    let second = dir.path().join("second.cap");
+    std::fs::create_dir_all(&first).unwrap();
+    std::fs::create_dir_all(&second).unwrap();
+    let combined = dir.path().join("combined.cap");
+    let output = run(&[
+        "project",
+        "merge",
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+        "-o",
+        combined.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("Failed to load recording meta")
+    );
+    assert!(!combined.exists());
+}
+
+#[test]
+fn project_apply_edits_rejects_malformed_scripts() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("edits.yaml");
+    std::fs::write(&script, "cuts:\n  - 00:10-00:14\n").unwrap();
+    let output = run(&[
+        "project",
+        "apply-edits",
+        dir.path().to_str().unwrap(),
+        script.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid edit script")
+    );
+}
+
+#[test]
+fn project_thumbnails_rejects_zero_count_before_rendering() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("thumbs");
+    let output = run(&[
+        "project",
+        "thumbnails",
+        dir.path().to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "--count",
+        "0",
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("--count must be at least 1")
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn project_thumbnails_scene_threshold_requires_scene_detect() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(&[
+        "project",
+        "thumbnails",
+        dir.path().to_str().unwrap(),
+        "-o",
+        dir.path().join("thumbs").to_str().unwrap(),
+        "--scene-threshold",
+        "0.2",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("--scene-detect"));
+}
+
+#[test]
+fn project_analyze_input_rejects_bad_thresholds_before_loading() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("analysis");
+    let output = run(&[
+        "project",
+        "analyze-input",
+        dir.path().to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "--min-idle",
+        "0",
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("--min-idle must be greater than zero")
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn project_shortcuts_rejects_zero_frame_width_before_loading() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = dir.path().join("shortcuts");
+    let output = run(&[
+        "project",
+        "shortcuts",
+        dir.path().to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+        "--frame-width",
+        "0",
+        "--format",
+        "json",
+    ]);
+    assert!(!output.status.success());
+    let json = parse_json(&output);
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("--frame-width must be at least 1")
+    );
+    assert!(!output_dir.exists());
+}
+
 #[test]
 fn project_validate_missing_meta_reports_invalid() {
     let dir = tempfile::tempdir().unwrap();
@@ -372,6 +560,209 @@ fn record_rejects_zero_fps() {
     assert!(!output.status.success());
 }
 
+#[test]
+fn record_rejects_unsupported_stream_url() {
+    let output = run(&[
+        "record",
+        "--stream-url",
+        "https://live.example.com/app",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--stream-url"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_rejects_invalid_screen_filter() {
+    let output = run(&[
+        "record",
+        "--screen-filter",
+        "blur=10,10,0,40",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--screen-filter"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_input_requires_studio_mode() {
+    let output = run(&[
+        "record",
+        "--input",
+        "/tmp/does-not-exist.mp4",
+        "--mode",
+        "instant",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--input requires --mode studio"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_encrypt_requires_studio_mode() {
+    let output = run(&[
+        "record",
+        "--encrypt",
+        "--mode",
+        "instant",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--encrypt requires --mode studio"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn record_encrypt_rejects_a_camera_on_macos() {
+    let output = run(&["record", "--encrypt", "--camera", "0", "--duration", "1"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--encrypt can't be combined with --camera on macOS"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_rejects_malformed_input_size() {
+    let output = run(&["record", "--input", "-", "--input-size", "1280"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--input-size must be WIDTHxHEIGHT"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_rejects_invalid_metrics_addr() {
+    let output = run(&["record", "--metrics-addr", "localhost", "--duration", "1"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--metrics-addr"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn record_schedule_requires_a_trigger() {
+    let output = run(&["record", "schedule", "--duration", "45m"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--at"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_schedule_rejects_malformed_cron() {
+    let output = run(&[
+        "record",
+        "schedule",
+        "--cron",
+        "61 * * * *",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("cron expression"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_rejects_malformed_duration_units() {
+    let output = run(&["record", "--duration", "10x"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("invalid duration"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_replay_length_requires_replay_mode() {
+    let output = run(&["record", "--replay-length", "2m", "--duration", "1"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--mode replay"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_multiple_mics_require_studio_mode() {
+    let output = run(&[
+        "record",
+        "--mode",
+        "instant",
+        "--mic",
+        "Host",
+        "--mic",
+        "Guest",
+        "--duration",
+        "1",
+    ]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--mode studio"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_follow_window_conflicts_with_window() {
+    let output = run(&["record", "--window", "1", "--follow-window", "2"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--follow-window"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_app_audio_conflicts_with_exclude_app_audio() {
+    let output = run(&[
+        "record",
+        "--app-audio",
+        "firefox",
+        "--exclude-app-audio",
+        "slack",
+    ]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--exclude-app-audio"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_save_replay_rejects_invalid_last() {
+    let output = run(&["record", "save-replay", "--last", "0"]);
+    assert!(!output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains("--last"), "stderr: {stderr}");
+}
+
+#[test]
+fn record_status_id_requires_follow() {
+    let output = run(&["record", "status", "--id", "abc"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--follow"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
 #[test]
 fn export_rejects_settings_json_with_flags() {
     let output = run(&[
@@ -436,6 +827,17 @@ fn export_rejects_duplicate_output() {
     assert!(!output.status.success());
 }
 
+#[test]
+fn export_rejects_unknown_renderer() {
+    let output = run(&["export", "/tmp/whatever.cap", "--renderer", "metal"]);
+    assert!(!output.status.success());
+    assert!(
+        stderr(&output).contains("--renderer"),
+        "stderr: {}",
+        stderr(&output)
+    );
+}
+
 #[test]
 fn targets_mics_json_is_parseable() {
     let output = run(&["targets", "mics", "--format", "json"]);
@@ -589,6 +991,13 @@ fn guide_json_is_parseable_and_self_describing() {
     assert!(json["outputConvention"].is_object());
     let commands = serde_json::to_string(&json["commands"]).unwrap();
     assert!(commands.contains("caps unlock"));
+    assert!(commands.contains("project redact"));
+    assert!(commands.contains("project import-clip"));
+    assert!(commands.contains("project merge"));
+    assert!(commands.contains("project apply-edits"));
+    assert!(commands.contains("project thumbnails"));
+    assert!(commands.contains("project analyze-input"));
+    assert!(commands.contains("project shortcuts"));
     assert!(commands.contains("caps comments|reactions|update|sharing"));
     assert!(commands.contains("caps import loom"));
     assert!(commands.contains("account get|update|image|referrals|sign-out-all"));
@@ -167,6 +167,9 @@ impl AutomationHost for DesktopAutomationHost {
             crate::export::ExportSettings::Mp4(s) => s.export(base, |_| true).await,
             crate::export::ExportSettings::Gif(s) => s.export(base, |_| true).await,
             crate::export::ExportSettings::Mov(s) => s.export(base, |_| true).await,
+            crate::export::ExportSettings::WebP(s) => s.export(base, |_| true).await,
+            crate::export::ExportSettings::Apng(s) => s.export(base, |_| true).await,
+            crate::export::ExportSettings::Stems(s) => s.export(base, |_| true).await,
         }
         .map_err(|e| format!("Export failed: {e}"))?;
 
@@ -568,6 +571,8 @@ fn build_desktop_export_settings(profile: &ExportProfile) -> crate::export::Expo
                 custom_bpp: None,
                 force_ffmpeg_decoder: false,
                 optimize_filesize: false,
+                target_file_size: None,
+                audio: Default::default(),
             })
         }
         ExportFormat::Gif => {
@@ -582,6 +587,7 @@ fn build_desktop_export_settings(profile: &ExportProfile) -> crate::export::Expo
                 fps: profile.fps,
                 resolution_base: profile.resolution_base,
                 cursor_only: false,
+                audio: Default::default(),
             })
         }
     }
@@ -618,7 +624,9 @@ fn apply_body_template(template: &str, ctx: &TriggerContext) -> String {
 
 pub fn capture_target_kind(target: &ScreenCaptureTarget) -> Option<CaptureTargetKind> {
     match target {
-        ScreenCaptureTarget::Window { .. } => Some(CaptureTargetKind::Window),
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. } => {
+            Some(CaptureTargetKind::Window)
+        }
         ScreenCaptureTarget::Display { .. } => Some(CaptureTargetKind::Display),
         ScreenCaptureTarget::Area { .. } => Some(CaptureTargetKind::Area),
         ScreenCaptureTarget::CameraOnly => None,
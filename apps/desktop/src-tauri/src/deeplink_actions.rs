@@ -46,6 +46,11 @@ pub enum DeepLinkAction {
         mode: RecordingMode,
     },
     StopRecording,
+    /// Saves the newest `last_secs` of a running replay recording, or its whole window.
+    SaveReplay {
+        #[serde(default)]
+        last_secs: Option<f64>,
+    },
     #[cfg(debug_assertions)]
     PauseRecording,
     #[cfg(debug_assertions)]
@@ -244,6 +249,18 @@ impl DeepLinkAction {
             DeepLinkAction::StopRecording => {
                 crate::recording::stop_recording(app.clone(), app.state()).await
             }
+            DeepLinkAction::SaveReplay { last_secs } => {
+                let last = match last_secs {
+                    Some(secs) if secs.is_finite() && secs > 0.0 => {
+                        Some(std::time::Duration::from_secs_f64(secs))
+                    }
+                    Some(_) => return Err("last_secs must be a positive duration".to_string()),
+                    None => None,
+                };
+                crate::recording::save_current_replay(app, &app.state(), last)
+                    .await
+                    .map(|_| ())
+            }
             #[cfg(debug_assertions)]
             DeepLinkAction::PauseRecording => {
                 crate::recording::pause_recording(app.clone(), app.state()).await
@@ -310,6 +327,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_save_replay_action_urls() {
+        let value = serde_json::json!({ "save_replay": { "last_secs": 30.0 } }).to_string();
+        let url = Url::parse_with_params("cap-desktop://action", &[("value", value)]).unwrap();
+        assert_eq!(
+            DeepLinkAction::try_from(&url),
+            Ok(DeepLinkAction::SaveReplay {
+                last_secs: Some(30.0)
+            })
+        );
+
+        let value = serde_json::json!({ "save_replay": {} }).to_string();
+        let url = Url::parse_with_params("cap-desktop://action", &[("value", value)]).unwrap();
+        assert_eq!(
+            DeepLinkAction::try_from(&url),
+            Ok(DeepLinkAction::SaveReplay { last_secs: None })
+        );
+    }
+
     #[cfg(debug_assertions)]
     #[test]
     fn parses_open_camera_action_url() {
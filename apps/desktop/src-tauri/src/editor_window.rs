@@ -405,7 +405,7 @@ impl EditorInstances {
         let instances = instances.0.read().await;
         instances
             .values()
-            .map(|instance| instance.project_path.clone())
+            .map(|instance| instance.source_path().to_path_buf())
             .collect()
     }
 
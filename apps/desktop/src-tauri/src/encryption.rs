@@ -0,0 +1,30 @@
+//! Loads the key for projects recorded with `cap record --encrypt`.
+//!
+//! The key is looked up the same way the CLI does, through
+//! [`MasterKey::load_from_env_or_keyring`]. The app only reads keys; it never
+//! creates one.
+
+use std::path::Path;
+
+use cap_project::encryption::{KEY_FILE_ENV, MasterKey};
+
+/// The recording encryption key, or `None` when none has been set up.
+pub fn master_key() -> Result<Option<MasterKey>, String> {
+    MasterKey::load_from_env_or_keyring()
+        .map_err(|e| format!("Failed to load the recording encryption key: {e}"))
+}
+
+/// The key for `project_path` when it is encrypted, or `None` for a plain
+/// project, so that opening one never touches the keyring.
+pub fn master_key_for(project_path: &Path) -> Result<Option<MasterKey>, String> {
+    if !cap_project::encryption::is_encrypted(project_path) {
+        return Ok(None);
+    }
+
+    master_key()?.map(Some).ok_or_else(|| {
+        format!(
+            "{} is encrypted and no recording encryption key is available; set {KEY_FILE_ENV} or add the key to the OS keyring",
+            project_path.display()
+        )
+    })
+}
@@ -899,6 +899,9 @@ pub enum ExportSettings {
     Mp4(cap_export::mp4::Mp4ExportSettings),
     Gif(cap_export::gif::GifExportSettings),
     Mov(cap_export::mov::MovExportSettings),
+    WebP(cap_export::animated::WebpExportSettings),
+    Apng(cap_export::animated::ApngExportSettings),
+    Stems(cap_export::stems::StemsExportSettings),
 }
 
 impl ExportSettings {
@@ -907,13 +910,20 @@ impl ExportSettings {
             ExportSettings::Mp4(settings) => settings.fps,
             ExportSettings::Gif(settings) => settings.fps,
             ExportSettings::Mov(settings) => settings.fps,
+            ExportSettings::WebP(settings) => settings.fps,
+            ExportSettings::Apng(settings) => settings.fps,
+            ExportSettings::Stems(_) => cap_export::stems::StemsExportSettings::PROGRESS_FPS,
         }
     }
 
     fn force_ffmpeg_decoder(&self) -> bool {
         match self {
             ExportSettings::Mp4(settings) => settings.force_ffmpeg_decoder,
-            ExportSettings::Gif(_) | ExportSettings::Mov(_) => false,
+            ExportSettings::Gif(_)
+            | ExportSettings::Mov(_)
+            | ExportSettings::WebP(_)
+            | ExportSettings::Apng(_)
+            | ExportSettings::Stems(_) => false,
         }
     }
 
@@ -1016,6 +1026,54 @@ async fn do_export(
                 })
                 .await
         }
+        ExportSettings::WebP(webp_settings) => {
+            let progress = progress.clone();
+            let cancel_token = cancel_token.clone();
+            webp_settings
+                .export(exporter_base, move |frame_index| {
+                    if cancel_token.is_cancelled() {
+                        return false;
+                    }
+
+                    progress.send(FramesRendered {
+                        rendered_count: (frame_index + 1).min(total_frames),
+                        total_frames,
+                    })
+                })
+                .await
+        }
+        ExportSettings::Apng(apng_settings) => {
+            let progress = progress.clone();
+            let cancel_token = cancel_token.clone();
+            apng_settings
+                .export(exporter_base, move |frame_index| {
+                    if cancel_token.is_cancelled() {
+                        return false;
+                    }
+
+                    progress.send(FramesRendered {
+                        rendered_count: (frame_index + 1).min(total_frames),
+                        total_frames,
+                    })
+                })
+                .await
+        }
+        ExportSettings::Stems(stems_settings) => {
+            let progress = progress.clone();
+            let cancel_token = cancel_token.clone();
+            stems_settings
+                .export(exporter_base, move |frame_index| {
+                    if cancel_token.is_cancelled() {
+                        return false;
+                    }
+
+                    progress.send(FramesRendered {
+                        rendered_count: (frame_index + 1).min(total_frames),
+                        total_frames,
+                    })
+                })
+                .await
+        }
     }
 }
 
@@ -1361,6 +1419,12 @@ pub async fn get_export_estimates(
         ExportSettings::Mp4(s) => (s.resolution_base, s.fps),
         ExportSettings::Gif(s) => (s.resolution_base, s.fps),
         ExportSettings::Mov(s) => (s.resolution_base, s.fps),
+        ExportSettings::WebP(s) => (s.resolution_base, s.fps),
+        ExportSettings::Apng(s) => (s.resolution_base, s.fps),
+        ExportSettings::Stems(_) => (
+            XY::new(0, 0),
+            cap_export::stems::StemsExportSettings::PROGRESS_FPS,
+        ),
     };
 
     let (width, height) = (resolution.x, resolution.y);
@@ -1384,6 +1448,10 @@ pub async fn get_export_estimates(
                 _ => 290.0,
             };
             let time_estimate = total_frames / effective_render_fps;
+            let size_mb = match mp4_settings.target_file_size {
+                Some(target) => target as f64 / (1024.0 * 1024.0),
+                None => size_mb,
+            };
 
             (size_mb, time_estimate)
         }
@@ -1409,6 +1477,54 @@ pub async fn get_export_estimates(
             };
             let time_estimate = total_frames / effective_render_fps;
 
+            (size_mb, time_estimate)
+        }
+        ExportSettings::WebP(_) | ExportSettings::Apng(_) => {
+            let max_file_size = match &settings {
+                ExportSettings::WebP(s) => s.max_file_size,
+                ExportSettings::Apng(s) => s.max_file_size,
+                _ => None,
+            };
+            let bytes_per_frame = total_pixels * 0.5;
+            let efficiency = match &settings {
+                ExportSettings::WebP(_) => 0.03,
+                _ => 0.06,
+            };
+            let mut size_mb = (bytes_per_frame * efficiency * total_frames) / (1024.0 * 1024.0);
+            if let Some(max) = max_file_size {
+                size_mb = size_mb.min(max as f64 / (1024.0 * 1024.0));
+            }
+
+            // Frames are encoded once all are rendered, and size targeting may
+            // re-encode several times.
+            let frames_per_sec = match (width, height) {
+                (w, h) if w <= 1280 && h <= 720 => 8.0,
+                (w, h) if w <= 1920 && h <= 1080 => 4.0,
+                _ => 1.5,
+            };
+            let time_estimate = total_frames / frames_per_sec;
+
+            (size_mb, time_estimate)
+        }
+        ExportSettings::Stems(stems_settings) => {
+            // Upper bound: every source present, as 24-bit stereo at 48kHz.
+            let bytes_per_second = 48_000.0 * 2.0 * 3.0;
+            let compression = match stems_settings.file_format {
+                cap_export::stems::StemFileFormat::Wav => 1.0,
+                cap_export::stems::StemFileFormat::Flac => 0.6,
+            };
+            let stem_count = cap_export::audio::AudioStem::ALL.len() as f64;
+            let size_mb = (bytes_per_second * compression * duration_seconds * stem_count)
+                / (1024.0 * 1024.0);
+
+            // Normalising renders the mix once more to measure it.
+            let passes = if stems_settings.audio.loudness_target.is_some() {
+                2.0
+            } else {
+                1.0
+            };
+            let time_estimate = duration_seconds * passes / 50.0;
+
             (size_mb, time_estimate)
         }
     };
@@ -1752,6 +1868,8 @@ mod tests {
             custom_bpp: None,
             force_ffmpeg_decoder: true,
             optimize_filesize: false,
+            target_file_size: None,
+            audio: Default::default(),
         });
         let gif_settings = ExportSettings::Gif(cap_export::gif::GifExportSettings {
             fps: 15,
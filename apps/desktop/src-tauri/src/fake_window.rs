@@ -298,7 +298,7 @@ pub fn calculate_recording_controls_position_for_target(
     capture_target: &ScreenCaptureTarget,
 ) -> Option<(f64, f64)> {
     match capture_target {
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let window = ScapWindow::from_id(id)?;
             let bounds = window.raw_handle().logical_bounds()?;
             let pos_x =
@@ -380,7 +380,8 @@ pub fn spawn_fake_window_listener(app: AppHandle, window: WebviewWindow) {
 
                 if let Some(ref target) = capture_target {
                     match target {
-                        ScreenCaptureTarget::Window { .. } => {
+                        ScreenCaptureTarget::Window { .. }
+                        | ScreenCaptureTarget::FollowWindow { .. } => {
                             if let Some((px, py)) =
                                 calculate_recording_controls_position_for_target(target)
                             {
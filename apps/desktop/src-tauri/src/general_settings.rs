@@ -211,6 +211,9 @@ pub struct GeneralSettingsStore {
     pub delete_instant_recordings_after_upload: bool,
     #[serde(default = "default_instant_mode_max_resolution")]
     pub instant_mode_max_resolution: u32,
+    /// How many seconds of footage replay mode keeps.
+    #[serde(default = "default_replay_length_secs")]
+    pub replay_length_secs: u32,
     #[serde(default)]
     pub default_project_name_template: Option<String>,
     #[serde(default = "default_crash_recovery_recording")]
@@ -267,6 +270,10 @@ fn default_instant_mode_max_resolution() -> u32 {
     cap_recording::DEFAULT_INSTANT_MODE_MAX_RESOLUTION
 }
 
+fn default_replay_length_secs() -> u32 {
+    cap_recording::replay::DEFAULT_LENGTH.as_secs() as u32
+}
+
 fn default_max_fps() -> u32 {
     cap_recording::DEFAULT_STUDIO_MAX_FPS
 }
@@ -337,6 +344,7 @@ impl Default for GeneralSettingsStore {
             excluded_windows: default_excluded_windows(),
             delete_instant_recordings_after_upload: false,
             instant_mode_max_resolution: cap_recording::DEFAULT_INSTANT_MODE_MAX_RESOLUTION,
+            replay_length_secs: default_replay_length_secs(),
             default_project_name_template: None,
             crash_recovery_recording: cap_recording::DEFAULT_CRASH_RECOVERY_RECORDING,
             max_fps: cap_recording::DEFAULT_STUDIO_MAX_FPS,
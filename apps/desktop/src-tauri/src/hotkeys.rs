@@ -66,6 +66,7 @@ pub enum HotkeyAction {
     ScreenshotDisplay,
     ScreenshotWindow,
     ScreenshotArea,
+    SaveReplay,
     #[serde(other)]
     Other,
 }
@@ -250,7 +251,8 @@ async fn handle_hotkey(app: AppHandle, action: HotkeyAction) -> Result<(), Strin
             let next = match current {
                 cap_recording::RecordingMode::Studio => cap_recording::RecordingMode::Instant,
                 cap_recording::RecordingMode::Instant => cap_recording::RecordingMode::Screenshot,
-                cap_recording::RecordingMode::Screenshot => cap_recording::RecordingMode::Studio,
+                cap_recording::RecordingMode::Screenshot => cap_recording::RecordingMode::Replay,
+                cap_recording::RecordingMode::Replay => cap_recording::RecordingMode::Studio,
             };
 
             RecordingSettingsStore::set_mode(&app, next)
@@ -332,6 +334,9 @@ async fn handle_hotkey(app: AppHandle, action: HotkeyAction) -> Result<(), Strin
             .emit(&app);
             Ok(())
         }
+        HotkeyAction::SaveReplay => recording::save_current_replay(&app, &app.state(), None)
+            .await
+            .map(|_| ()),
         HotkeyAction::Other => Ok(()),
     }
 }
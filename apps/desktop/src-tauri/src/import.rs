@@ -6,10 +6,10 @@ use cap_enc_ffmpeg::{
 };
 use cap_media_info::{AudioInfo, FFRational, Pixel, VideoInfo, ensure_even};
 use cap_project::{
-    AudioMeta, ClipConfiguration, CursorEvents, CursorMeta, Cursors, InstantRecordingMeta,
-    MultipleSegment, MultipleSegments, Platform, ProjectConfiguration, RecordingMeta,
-    RecordingMetaInner, SingleSegment, StudioRecordingMeta, StudioRecordingStatus,
-    TimelineConfiguration, TimelineSegment, VideoMeta, XY,
+    AdditionalAudioMeta, AdditionalVideoMeta, AudioMeta, ClipConfiguration, CursorEvents,
+    CursorMeta, Cursors, InstantRecordingMeta, MultipleSegment, MultipleSegments, Platform,
+    ProjectConfiguration, RecordingMeta, RecordingMetaInner, SingleSegment, StudioRecordingMeta,
+    StudioRecordingStatus, TimelineConfiguration, TimelineSegment, VideoMeta, XY,
 };
 use ffmpeg::{
     ChannelLayout,
@@ -287,6 +287,9 @@ fn ensure_multiple_segments(meta: &mut RecordingMeta) -> Result<&mut MultipleSeg
                     cursor: segment.cursor,
                     keyboard: None,
                     display_notch: None,
+                    additional_videos: Vec::new(),
+                    additional_mics: Vec::new(),
+                    imported: None,
                 }],
                 cursors: Cursors::default(),
                 status: Some(StudioRecordingStatus::Complete),
@@ -847,6 +850,9 @@ fn single_segment_to_multiple(segment: &SingleSegment) -> MultipleSegment {
         cursor: segment.cursor.clone(),
         keyboard: None,
         display_notch: None,
+        additional_videos: Vec::new(),
+        additional_mics: Vec::new(),
+        imported: None,
     }
 }
 
@@ -993,6 +999,27 @@ fn copy_source_segment(
         .transpose()?
         .flatten();
 
+    let additional_mics = source_segment
+        .additional_mics
+        .iter()
+        .filter_map(|additional| {
+            copy_audio_meta(
+                &source_meta.project_path,
+                target_project_path,
+                &additional.audio,
+                target_relative_dir,
+                &additional.id,
+            )
+            .map(|audio| {
+                audio.map(|audio| AdditionalAudioMeta {
+                    id: additional.id.clone(),
+                    audio,
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
     let cursor = source_segment
         .cursor
         .as_ref()
@@ -1015,14 +1042,40 @@ fn copy_source_segment(
         target_relative_dir,
     )?;
 
+    let additional_videos = source_segment
+        .additional_videos
+        .iter()
+        .filter_map(|additional| {
+            copy_video_meta(
+                &source_meta.project_path,
+                target_project_path,
+                &additional.video,
+                target_relative_dir,
+                &additional.id,
+                false,
+            )
+            .map(|video| {
+                video.map(|video| AdditionalVideoMeta {
+                    id: additional.id.clone(),
+                    kind: additional.kind,
+                    video,
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
     Ok(MultipleSegment {
         display,
         camera,
         mic,
+        additional_mics,
         system_audio,
         cursor,
         keyboard,
         display_notch: source_segment.display_notch,
+        additional_videos,
+        imported: source_segment.imported.clone(),
     })
 }
 
@@ -1430,6 +1483,9 @@ pub async fn start_video_import(app: AppHandle, source_path: PathBuf) -> Result<
                     cursor: None,
                     keyboard: None,
                     display_notch: None,
+                    additional_videos: Vec::new(),
+                    additional_mics: Vec::new(),
+                    imported: None,
                 }],
                 cursors: Cursors::default(),
                 status: Some(StudioRecordingStatus::InProgress),
@@ -1526,6 +1582,9 @@ pub async fn start_video_import(app: AppHandle, source_path: PathBuf) -> Result<
                                     cursor: None,
                                     keyboard: None,
                                     display_notch: None,
+                                    additional_videos: Vec::new(),
+                                    additional_mics: Vec::new(),
+                                    imported: None,
                                 }],
                                 cursors: Cursors::default(),
                                 status: Some(StudioRecordingStatus::Complete),
@@ -1701,6 +1760,9 @@ async fn append_mp4_to_editor_project(
         cursor: None,
         keyboard: None,
         display_notch: None,
+        additional_videos: Vec::new(),
+        additional_mics: Vec::new(),
+        imported: None,
     };
 
     {
@@ -1877,6 +1939,12 @@ pub async fn add_existing_recording_to_editor(
         return Err("Cannot import a recording into itself".to_string());
     }
 
+    if cap_project::encryption::is_encrypted(&target_project_path)
+        || cap_project::encryption::is_encrypted(&source_path)
+    {
+        return Err("Importing into or from encrypted projects isn't supported yet".to_string());
+    }
+
     let app = window.app_handle().clone();
     let imported_count = if is_mp4_import_path(&source_path) {
         append_mp4_to_editor_project(app, target_project_path, source_path).await?
@@ -16,6 +16,7 @@ mod clip_thumbnails;
 mod crash_sentinel;
 mod deeplink_actions;
 mod editor_window;
+mod encryption;
 mod exit_shutdown;
 mod export;
 mod fake_window;
@@ -2554,7 +2555,7 @@ async fn get_current_recording(
 
     let target = match capture_target {
         ScreenCaptureTarget::Display { id } => CurrentRecordingTarget::Screen { id: id.clone() },
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let bounds =
                 scap_targets::Window::from_id(id).and_then(|w| w.display_relative_logical_bounds());
             CurrentRecordingTarget::Window {
@@ -3117,6 +3118,8 @@ async fn create_editor_instance(window: Window) -> Result<SerializedEditorInstan
             project_config.clone()
         },
         recordings: editor_instance.recordings.clone(),
+        // The frontend reads project files through this path, so for an
+        // encrypted project it is the decrypted working copy.
         path: editor_instance.project_path.clone(),
         notch_base: editor_instance
             .render_constants
@@ -3356,6 +3359,8 @@ async fn set_project_config(
         .write(&editor_instance.project_path)
         .map_err(|error| format!("Failed to write project config: {error}"))?;
 
+    editor_instance.persist()?;
+
     Ok(())
 }
 
@@ -4445,7 +4450,7 @@ async fn editor_delete_project(
 
     tokio::time::sleep(std::time::Duration::from_millis(20)).await;
 
-    let path = editor_instance.0.project_path.clone();
+    let path = editor_instance.0.source_path().to_path_buf();
     drop(editor_instance);
 
     let _ = tokio::fs::remove_dir_all(&path).await;
@@ -4889,6 +4894,7 @@ fn specta_builder() -> tauri_specta::Builder {
             cli::uninstall_cli,
             recording::start_recording,
             recording::stop_recording,
+            recording::save_replay,
             recording::pause_recording,
             recording::resume_recording,
             recording::toggle_pause_recording,
@@ -6574,7 +6580,10 @@ async fn create_editor_instance_impl(
 
     wait_for_recording_ready(&app, &path).await?;
 
-    recording::spawn_heal_oversized_desktop_background_snapshots(path.clone());
+    let master_key = encryption::master_key_for(&path)?;
+    if master_key.is_none() {
+        recording::spawn_heal_oversized_desktop_background_snapshots(path.clone());
+    }
 
     let shared_device =
         gpu_context::get_shared_gpu()
@@ -6589,8 +6598,9 @@ async fn create_editor_instance_impl(
 
     let instance = {
         let app = app.clone();
-        EditorInstance::new(
+        EditorInstance::new_with_key(
             path,
+            master_key.as_ref(),
             move |state| {
                 let _ = EditorStateChanged::new(state).emit(&app);
             },
@@ -6632,6 +6642,26 @@ pub(crate) async fn wait_for_recording_ready(app: &AppHandle, path: &Path) -> Re
         return Ok(());
     }
 
+    // Encrypted projects only come from `cap record --encrypt`, never from
+    // imports, so the only preparation they can need is the crash-recovery
+    // remux, which runs on a decrypted copy and seals its output back.
+    if let Some(master_key) = encryption::master_key_for(path)? {
+        let path = path.to_path_buf();
+        let remuxed = tokio::task::spawn_blocking(move || {
+            cap_recording::recovery::RecoveryManager::remux_if_needed_with_key(
+                &path,
+                Some(&master_key),
+            )
+        })
+        .await
+        .map_err(|e| format!("Remux task panicked: {e}"))?
+        .map_err(|e| format!("Failed to remux recording: {e}"))?;
+        if remuxed {
+            info!("Crash recovery remux of encrypted recording completed");
+        }
+        return Ok(());
+    }
+
     let meta = match RecordingMeta::load_for_project(path) {
         Ok(meta) => meta,
         Err(e) => {
@@ -19,6 +19,7 @@ use cap_recording::{
     feeds::{camera, microphone},
     instant_recording,
     recovery::RecoveryManager,
+    replay::{self, ReplayBuffer},
     sources::MicrophoneSourceError,
     sources::{
         screen_capture,
@@ -608,6 +609,15 @@ pub enum InProgressRecording {
         mic_feed: Option<Arc<microphone::MicrophoneFeedLock>>,
         camera_feed: Option<Arc<CameraFeedLock>>,
     },
+    /// An instant recording into `<recording_dir>/replay` that is pruned to the last `length`.
+    Replay {
+        handle: instant_recording::ActorHandle,
+        length: Duration,
+        _buffer: ReplayBuffer,
+        common: InProgressRecordingCommon,
+        mic_feed: Option<Arc<microphone::MicrophoneFeedLock>>,
+        camera_feed: Option<Arc<CameraFeedLock>>,
+    },
 }
 
 #[cfg(target_os = "macos")]
@@ -695,49 +705,49 @@ fn is_shareable_content_error(err: &anyhow::Error) -> bool {
 impl InProgressRecording {
     pub fn capture_target(&self) -> &ScreenCaptureTarget {
         match self {
-            Self::Instant { handle, .. } => &handle.capture_target,
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => &handle.capture_target,
             Self::Studio { handle, .. } => &handle.capture_target,
         }
     }
 
     pub fn inputs(&self) -> &StartRecordingInputs {
         match self {
-            Self::Instant { common, .. } => &common.inputs,
+            Self::Instant { common, .. } | Self::Replay { common, .. } => &common.inputs,
             Self::Studio { common, .. } => &common.inputs,
         }
     }
 
     pub fn common(&self) -> &InProgressRecordingCommon {
         match self {
-            Self::Instant { common, .. } => common,
+            Self::Instant { common, .. } | Self::Replay { common, .. } => common,
             Self::Studio { common, .. } => common,
         }
     }
 
     pub async fn pause(&self) -> anyhow::Result<()> {
         match self {
-            Self::Instant { handle, .. } => handle.pause().await,
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.pause().await,
             Self::Studio { handle, .. } => handle.pause().await,
         }
     }
 
     pub async fn resume(&self) -> anyhow::Result<()> {
         match self {
-            Self::Instant { handle, .. } => handle.resume().await,
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.resume().await,
             Self::Studio { handle, .. } => handle.resume().await,
         }
     }
 
     pub async fn is_paused(&self) -> anyhow::Result<bool> {
         match self {
-            Self::Instant { handle, .. } => handle.is_paused().await,
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.is_paused().await,
             Self::Studio { handle, .. } => handle.is_paused().await,
         }
     }
 
     pub fn recording_dir(&self) -> &PathBuf {
         match self {
-            Self::Instant { common, .. } => &common.recording_dir,
+            Self::Instant { common, .. } | Self::Replay { common, .. } => &common.recording_dir,
             Self::Studio { common, .. } => &common.recording_dir,
         }
     }
@@ -775,26 +785,40 @@ impl InProgressRecording {
                 }),
                 Err(e) => Err((e, None)),
             },
+            Self::Replay {
+                handle,
+                length,
+                common,
+                ..
+            } => match handle.stop().await {
+                Ok(recording) => Ok(CompletedRecording::Replay {
+                    recording,
+                    length,
+                    project_path: common.recording_dir,
+                    target_name: common.target_name,
+                }),
+                Err(e) => Err((e, None)),
+            },
         }
     }
 
     pub fn done_fut(&self) -> cap_recording::DoneFut {
         match self {
-            Self::Instant { handle, .. } => handle.done_fut(),
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.done_fut(),
             Self::Studio { handle, .. } => handle.done_fut(),
         }
     }
 
     pub fn take_health_rx(&mut self) -> Option<cap_recording::HealthReceiver> {
         match self {
-            Self::Instant { handle, .. } => handle.take_health_rx(),
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.take_health_rx(),
             Self::Studio { .. } => None,
         }
     }
 
     pub async fn cancel(self) -> anyhow::Result<()> {
         match self {
-            Self::Instant { handle, .. } => handle.cancel().await,
+            Self::Instant { handle, .. } | Self::Replay { handle, .. } => handle.cancel().await,
             Self::Studio { handle, .. } => handle.cancel().await,
         }
     }
@@ -803,6 +827,7 @@ impl InProgressRecording {
         match self {
             Self::Instant { .. } => RecordingMode::Instant,
             Self::Studio { .. } => RecordingMode::Studio,
+            Self::Replay { .. } => RecordingMode::Replay,
         }
     }
 }
@@ -819,6 +844,13 @@ pub enum CompletedRecording {
         target_name: String,
         capture_target: ScreenCaptureTarget,
     },
+    /// `recording` is the buffer, which is saved into `project_path` when the recording finishes.
+    Replay {
+        recording: instant_recording::CompletedRecording,
+        length: Duration,
+        project_path: PathBuf,
+        target_name: String,
+    },
 }
 
 impl CompletedRecording {
@@ -826,13 +858,15 @@ impl CompletedRecording {
         match self {
             Self::Instant { recording, .. } => &recording.project_path,
             Self::Studio { recording, .. } => &recording.project_path,
+            Self::Replay { project_path, .. } => project_path,
         }
     }
 
     pub fn target_name(&self) -> &String {
         match self {
-            Self::Instant { target_name, .. } => target_name,
-            Self::Studio { target_name, .. } => target_name,
+            Self::Instant { target_name, .. }
+            | Self::Studio { target_name, .. }
+            | Self::Replay { target_name, .. } => target_name,
         }
     }
 }
@@ -1431,6 +1465,7 @@ pub fn format_project_name<'a>(
         RecordingMode::Studio => ("Studio", "studio"),
         RecordingMode::Instant => ("Instant", "instant"),
         RecordingMode::Screenshot => ("Screenshot", "screenshot"),
+        RecordingMode::Replay => ("Replay", "replay"),
     };
 
     let result = AC
@@ -1696,6 +1731,13 @@ pub async fn start_recording(
             )
         }
         RecordingMode::Studio => (None, cap_recording::PRO_INSTANT_MODE_MAX_RESOLUTION),
+        // A replay stays on this machine until it is saved, so there is no link to create.
+        RecordingMode::Replay => (
+            None,
+            general_settings.map_or(cap_recording::PRO_INSTANT_MODE_MAX_RESOLUTION, |settings| {
+                settings.instant_mode_max_resolution
+            }),
+        ),
         RecordingMode::Screenshot => {
             let error = "Use take_screenshot for screenshots".to_string();
             state_mtx.write().await.clear_pending_recording();
@@ -1718,7 +1760,7 @@ pub async fn start_recording(
                     },
                 }))
             }
-            RecordingMode::Instant => {
+            RecordingMode::Instant | RecordingMode::Replay => {
                 RecordingMetaInner::Instant(InstantRecordingMeta::InProgress { recording: true })
             }
             RecordingMode::Screenshot => {
@@ -1735,7 +1777,7 @@ pub async fn start_recording(
     ));
 
     match &inputs.capture_target {
-        ScreenCaptureTarget::Window { id: _id } => {
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. } => {
             if let Some(show) = inputs
                 .capture_target
                 .display()
@@ -1884,15 +1926,16 @@ pub async fn start_recording(
                         settings.excluded_windows.clone()
                     });
 
-                let window_exclusions = if matches!(inputs.mode, RecordingMode::Instant) {
-                    let camera_title = CapWindowId::Camera.title();
-                    crate::window_exclusion::filter_for_instant_mode(
-                        window_exclusions,
-                        &camera_title,
-                    )
-                } else {
-                    window_exclusions
-                };
+                let window_exclusions =
+                    if matches!(inputs.mode, RecordingMode::Instant | RecordingMode::Replay) {
+                        let camera_title = CapWindowId::Camera.title();
+                        crate::window_exclusion::filter_for_instant_mode(
+                            window_exclusions,
+                            &camera_title,
+                        )
+                    } else {
+                        window_exclusions
+                    };
 
                 let teleprompter_exclusion = WindowExclusion {
                     bundle_identifier: None,
@@ -2084,6 +2127,54 @@ pub async fn start_recording(
                                 camera_feed: camera_feed.clone(),
                             })
                         }
+                        RecordingMode::Replay => {
+                            let buffer_dir = recording_dir.join(replay::BUFFER_DIR);
+                            let mut builder = instant_recording::Actor::builder(
+                                buffer_dir.clone(),
+                                inputs.capture_target.clone(),
+                            )
+                            .with_system_audio(inputs.capture_system_audio)
+                            .with_max_output_size(instant_mode_max_resolution);
+
+                            #[cfg(target_os = "macos")]
+                            {
+                                builder = builder.with_excluded_windows(excluded_windows.clone());
+                            }
+
+                            if let Some(camera_feed) = camera_feed.clone() {
+                                builder = builder.with_camera_feed(camera_feed);
+                            }
+
+                            if let Some(mic_feed) = mic_feed.clone() {
+                                builder = builder.with_mic_feed(mic_feed);
+                            }
+
+                            let handle = builder
+                                .build(
+                                    #[cfg(target_os = "macos")]
+                                    shareable_content.clone(),
+                                )
+                                .await
+                                .map_err(|e| {
+                                    error!("Failed to spawn replay recording actor: {e:#}");
+                                    e
+                                })?;
+
+                            let length = general_settings
+                                .as_ref()
+                                .map_or(replay::DEFAULT_LENGTH, |settings| {
+                                    Duration::from_secs(settings.replay_length_secs.into())
+                                });
+
+                            Ok(InProgressRecording::Replay {
+                                handle,
+                                length,
+                                _buffer: ReplayBuffer::spawn(buffer_dir, length),
+                                common: common.clone(),
+                                mic_feed: mic_feed.clone(),
+                                camera_feed: camera_feed.clone(),
+                            })
+                        }
                         RecordingMode::Screenshot => Err(anyhow!(
                             "Screenshot mode should be handled via take_screenshot"
                         )),
@@ -2391,7 +2482,12 @@ pub async fn start_recording(
                         cap_recording::PipelineHealthEvent::CaptureTargetLost { target } => {
                             Some(format!("Capture target lost: {target}"))
                         }
-                        cap_recording::PipelineHealthEvent::SourceRestarted => None,
+                        cap_recording::PipelineHealthEvent::StreamDisconnected { reason } => {
+                            Some(format!("Live stream disconnected: {reason}"))
+                        }
+                        cap_recording::PipelineHealthEvent::SourceRestarted
+                        | cap_recording::PipelineHealthEvent::StreamConnected { .. }
+                        | cap_recording::PipelineHealthEvent::StreamReconnecting { .. } => None,
                     };
 
                     if let Some(reason) = reason {
@@ -2457,9 +2553,10 @@ pub async fn set_mic_recording_muted(
     };
 
     let mic_feed = match recording {
-        InProgressRecording::Instant { mic_feed, .. } => mic_feed.as_ref(),
+        InProgressRecording::Instant { mic_feed, .. }
+        | InProgressRecording::Replay { mic_feed, .. } => mic_feed.as_ref(),
         // Studio records the mic as an editable track; muting would silently
-        // bake zeros into it. The bar only offers mute for instant mode —
+        // bake zeros into it. The bar only offers mute for instant and replay mode —
         // enforce the same contract here so no future caller can corrupt a
         // studio track.
         InProgressRecording::Studio { .. } => {
@@ -2676,6 +2773,13 @@ async fn cancel_discarded_recording(
                 warn!("Failed to cancel studio recording while discarding: {err:#}");
             }
 
+            None
+        }
+        InProgressRecording::Replay { handle, .. } => {
+            if let Err(err) = handle.cancel().await {
+                warn!("Failed to cancel replay recording while discarding: {err:#}");
+            }
+
             None
         }
     }
@@ -3073,6 +3177,9 @@ async fn handle_recording_end(
                 }
                 | InProgressRecording::Studio {
                     common, mic_feed, ..
+                }
+                | InProgressRecording::Replay {
+                    common, mic_feed, ..
                 } => (
                     common.health.snapshot(),
                     common.health.seconds_since_start() as u64,
@@ -3580,6 +3687,30 @@ async fn handle_recording_finish(
                 }),
             )
         }
+        CompletedRecording::Replay {
+            recording, length, ..
+        } => {
+            let buffer_dir = recording.project_path;
+            let saved =
+                save_replay_buffer(buffer_dir.clone(), Some(length), recording_dir.clone()).await?;
+            if let Err(err) = tokio::fs::remove_dir_all(&buffer_dir).await {
+                warn!("Failed to remove replay buffer: {err}");
+            }
+
+            tokio::spawn(create_screenshot(
+                recording_dir.join("content/output.mp4"),
+                screenshots_dir.join("display.jpg"),
+                None,
+            ));
+
+            (
+                RecordingMetaInner::Instant(InstantRecordingMeta::Complete {
+                    fps: saved.fps.unwrap_or(cap_recording::DEFAULT_INSTANT_MODE_FPS),
+                    sample_rate: None,
+                }),
+                None,
+            )
+        }
     };
 
     let instant_share = sharing.as_ref().map(|s| (s.link.clone(), s.id.clone()));
@@ -3626,6 +3757,116 @@ async fn handle_recording_finish(
     Ok(editor_took_foreground)
 }
 
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app, state))]
+pub async fn save_replay(app: AppHandle, state: MutableState<'_, App>) -> Result<(), String> {
+    save_current_replay(&app, &state, None).await.map(|_| ())
+}
+
+/// Saves the newest `last` of the replay buffer at `buffer_dir` (all of it when `None`) as an
+/// instant project at `project_path`.
+async fn save_replay_buffer(
+    buffer_dir: PathBuf,
+    last: Option<Duration>,
+    project_path: PathBuf,
+) -> Result<replay::SavedReplay, String> {
+    tokio::task::spawn_blocking(move || replay::save_replay(&buffer_dir, last, &project_path))
+        .await
+        .map_err(|e| format!("Replay save task failed: {e}"))?
+        .map_err(|e| format!("Failed to save replay: {e}"))
+}
+
+/// Saves the newest `last` of the running replay recording (its whole window when `None`) as a new
+/// instant project, leaving the recording running.
+pub async fn save_current_replay(
+    app: &AppHandle,
+    state_mtx: &MutableState<'_, App>,
+    last: Option<Duration>,
+) -> Result<PathBuf, String> {
+    let (buffer_dir, length, capture_target) = {
+        let state = state_mtx.read().await;
+        match state.current_recording() {
+            Some(InProgressRecording::Replay { length, common, .. }) => (
+                common.recording_dir.join(replay::BUFFER_DIR),
+                *length,
+                common.inputs.capture_target.clone(),
+            ),
+            _ => return Err("No replay recording in progress".to_string()),
+        }
+    };
+
+    let general_settings = GeneralSettingsStore::get(app).ok().flatten();
+    let project_name = format_project_name(
+        general_settings
+            .as_ref()
+            .and_then(|s| s.default_project_name_template.clone())
+            .as_deref(),
+        capture_target.title().as_deref().unwrap_or("Unknown"),
+        capture_target.kind_str(),
+        RecordingMode::Replay,
+        None,
+    );
+    let filename = project_name.replace(":", ".");
+    let filename = format!("{}.cap", sanitize_filename::sanitize(&filename));
+
+    let recordings_base_dir = GeneralSettingsStore::recordings_dir(app);
+    ensure_dir(&recordings_base_dir)
+        .map_err(|e| format!("Failed to create recordings directory: {e}"))?;
+    let project_path = recordings_base_dir.join(cap_utils::ensure_unique_filename(
+        &filename,
+        &recordings_base_dir,
+    )?);
+
+    let saved = match save_replay_buffer(
+        buffer_dir,
+        Some(last.unwrap_or(length)),
+        project_path.clone(),
+    )
+    .await
+    {
+        Ok(saved) => saved,
+        Err(error) => {
+            let _ = std::fs::remove_dir_all(&project_path);
+            return Err(error);
+        }
+    };
+
+    RecordingMeta {
+        platform: Some(Platform::default()),
+        project_path: project_path.clone(),
+        pretty_name: project_name,
+        sharing: None,
+        inner: RecordingMetaInner::Instant(InstantRecordingMeta::Complete {
+            fps: saved.fps.unwrap_or(cap_recording::DEFAULT_INSTANT_MODE_FPS),
+            sample_rate: None,
+        }),
+        upload: None,
+    }
+    .save_for_project()
+    .map_err(|e| format!("Failed to save recording meta: {e}"))?;
+
+    let screenshots_dir = project_path.join("screenshots");
+    std::fs::create_dir_all(&screenshots_dir).ok();
+    tokio::spawn(create_screenshot(
+        project_path.join("content/output.mp4"),
+        screenshots_dir.join("display.jpg"),
+        None,
+    ));
+
+    let _ = crate::NewNotification {
+        title: "Replay saved".to_string(),
+        body: format!(
+            "Saved the last {} seconds of your recording.",
+            saved.duration.as_secs()
+        ),
+        is_error: false,
+    }
+    .emit(app);
+
+    Ok(project_path)
+}
+
 async fn finalize_studio_recording(
     app: &AppHandle,
     recording_dir: PathBuf,
@@ -4015,7 +4256,11 @@ fn apply_screen_recording_presentation_defaults(
 
     if matches!(
         capture_target,
-        Some(ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::Display { .. })
+        Some(
+            ScreenCaptureTarget::Window { .. }
+                | ScreenCaptureTarget::FollowWindow { .. }
+                | ScreenCaptureTarget::Display { .. }
+        )
     ) && config.background.rounding <= f64::EPSILON
     {
         config.background.rounding = DEFAULT_SCREEN_RECORDING_BACKGROUND_ROUNDING_PERCENT;
@@ -4191,7 +4436,8 @@ async fn emit_recording_started_telemetry(app: &AppHandle, state_mtx: &MutableSt
         let target_kind = target_kind_label(recording.capture_target());
         let has_camera = match recording {
             InProgressRecording::Instant { camera_feed, .. }
-            | InProgressRecording::Studio { camera_feed, .. } => camera_feed.is_some(),
+            | InProgressRecording::Studio { camera_feed, .. }
+            | InProgressRecording::Replay { camera_feed, .. } => camera_feed.is_some(),
         };
         (
             mode_label(inputs.mode),
@@ -4211,7 +4457,7 @@ async fn emit_recording_started_telemetry(app: &AppHandle, state_mtx: &MutableSt
     // fixed fps, so report the value each mode actually uses rather than the raw studio cap.
     let target_fps = match recording_mode {
         RecordingMode::Studio => defaults.studio_max_fps(has_camera, None),
-        RecordingMode::Instant | RecordingMode::Screenshot => {
+        RecordingMode::Instant | RecordingMode::Screenshot | RecordingMode::Replay => {
             cap_recording::DEFAULT_INSTANT_MODE_FPS
         }
     };
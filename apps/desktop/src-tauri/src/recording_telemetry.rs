@@ -139,7 +139,10 @@ impl RecordingHealthAccumulator {
             | PipelineHealthEvent::EncoderRebuilt { .. }
             | PipelineHealthEvent::SourceAudioReset { .. }
             | PipelineHealthEvent::RecoveryFragmentCorrupt { .. }
-            | PipelineHealthEvent::CaptureTargetLost { .. } => None,
+            | PipelineHealthEvent::CaptureTargetLost { .. }
+            | PipelineHealthEvent::StreamConnected { .. }
+            | PipelineHealthEvent::StreamDisconnected { .. }
+            | PipelineHealthEvent::StreamReconnecting { .. } => None,
         }
     }
 
@@ -174,6 +177,7 @@ pub fn mode_label(mode: cap_recording::RecordingMode) -> &'static str {
         cap_recording::RecordingMode::Studio => "studio",
         cap_recording::RecordingMode::Instant => "instant",
         cap_recording::RecordingMode::Screenshot => "screenshot",
+        cap_recording::RecordingMode::Replay => "replay",
     }
 }
 
@@ -185,6 +189,7 @@ pub fn target_kind_label(
         ScreenCaptureTarget::Display { .. } => "display",
         ScreenCaptureTarget::Window { .. } => "window",
         ScreenCaptureTarget::Area { .. } => "area",
+        ScreenCaptureTarget::FollowWindow { .. } => "follow_window",
         ScreenCaptureTarget::CameraOnly => "camera_only",
     }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::path::PathBuf;
 use tauri::AppHandle;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::create_screenshot;
 
@@ -47,9 +47,15 @@ pub async fn find_incomplete_recordings(
     let recordings_dirs = crate::recordings_locations::known_recordings_dirs(&app);
 
     let result = tokio::task::spawn_blocking(move || {
+        // Without a key, encrypted recordings are skipped and plain ones are
+        // still scanned.
+        let master_key = crate::encryption::master_key().unwrap_or_else(|e| {
+            warn!("{e}");
+            None
+        });
         let incomplete_list = recordings_dirs
             .iter()
-            .flat_map(|dir| RecoveryManager::find_incomplete(dir));
+            .flat_map(|dir| RecoveryManager::find_incomplete_with_key(dir, master_key.as_ref()));
 
         incomplete_list
             .into_iter()
@@ -72,11 +78,15 @@ pub async fn find_incomplete_recordings(
 #[specta::specta]
 pub async fn recover_recording(app: AppHandle, project_path: String) -> Result<String, String> {
     let path = PathBuf::from(&project_path);
+    let master_key = crate::encryption::master_key_for(&path)?;
+    let is_encrypted = master_key.is_some();
 
-    let recording = tokio::task::spawn_blocking(move || RecoveryManager::inspect_recording(&path))
-        .await
-        .map_err(|e| format!("Recovery scan task failed: {e}"))?
-        .ok_or_else(|| "No recoverable segments found".to_string())?;
+    let recording = tokio::task::spawn_blocking(move || {
+        RecoveryManager::inspect_recording_with_key(&path, master_key.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Recovery scan task failed: {e}"))?
+    .ok_or_else(|| "No recoverable segments found".to_string())?;
 
     if recording.recoverable_segments.is_empty() {
         return Err("No recoverable segments found".to_string());
@@ -120,6 +130,11 @@ pub async fn recover_recording(app: AppHandle, project_path: String) -> Result<S
         },
     );
 
+    // A screenshot written here would sit unencrypted in the project.
+    if is_encrypted {
+        return Ok(project_path);
+    }
+
     let display_output_path = match &recovered.meta {
         StudioRecordingMeta::SingleSegment { segment } => {
             segment.display.path.to_path(&recovered.project_path)
@@ -389,6 +389,7 @@ impl ScreenshotEditorInstances {
                         decoded_frame.height(),
                     )),
                     camera_frame: None,
+                    additional_frames: Vec::new(),
                     segment_time: 0.0,
                     recording_time: 0.0,
                     segment_has_camera: false,
@@ -856,6 +857,7 @@ pub async fn prewarm_screenshot_renderer() {
             height,
         )),
         camera_frame: None,
+        additional_frames: Vec::new(),
         segment_time: 0.0,
         recording_time: 0.0,
         segment_has_camera: false,
@@ -1657,6 +1659,7 @@ pub async fn render_screenshot_png(instance: &ScreenshotEditorInstance) -> Resul
             decoded_frame.height(),
         )),
         camera_frame: None,
+        additional_frames: Vec::new(),
         segment_time: 0.0,
         recording_time: 0.0,
         segment_has_camera: false,
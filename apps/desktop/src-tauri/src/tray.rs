@@ -85,6 +85,7 @@ pub enum TrayItem {
     ModeStudio,
     ModeInstant,
     ModeScreenshot,
+    ModeReplay,
     RequestPermissions,
 }
 
@@ -108,6 +109,7 @@ impl From<TrayItem> for MenuId {
             TrayItem::ModeStudio => "mode_studio",
             TrayItem::ModeInstant => "mode_instant",
             TrayItem::ModeScreenshot => "mode_screenshot",
+            TrayItem::ModeReplay => "mode_replay",
             TrayItem::RequestPermissions => "request_permissions",
         }
         .into()
@@ -139,6 +141,7 @@ impl TryFrom<MenuId> for TrayItem {
             "mode_studio" => Ok(TrayItem::ModeStudio),
             "mode_instant" => Ok(TrayItem::ModeInstant),
             "mode_screenshot" => Ok(TrayItem::ModeScreenshot),
+            "mode_replay" => Ok(TrayItem::ModeReplay),
             "request_permissions" => Ok(TrayItem::RequestPermissions),
             value => Err(format!("Invalid tray item id {value}")),
         }
@@ -394,6 +397,7 @@ fn create_mode_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
             RecordingMode::Screenshot,
             "Screenshot",
         ),
+        (TrayItem::ModeReplay, RecordingMode::Replay, "Replay"),
     ];
 
     for (tray_item, mode, label) in modes {
@@ -658,7 +662,9 @@ pub fn get_mode_icon(mode: RecordingMode) -> &'static [u8] {
     }
     match mode {
         RecordingMode::Studio => include_bytes!("../icons/tray-default-icon-studio.png"),
-        RecordingMode::Instant => include_bytes!("../icons/tray-default-icon-instant.png"),
+        RecordingMode::Instant | RecordingMode::Replay => {
+            include_bytes!("../icons/tray-default-icon-instant.png")
+        }
         RecordingMode::Screenshot => include_bytes!("../icons/tray-default-icon-screenshot.png"),
     }
 }
@@ -667,7 +673,7 @@ pub fn get_mode_icon(mode: RecordingMode) -> &'static [u8] {
 fn linux_tray_icon_for_mode(mode: RecordingMode) -> LinuxTrayIcon {
     match mode {
         RecordingMode::Studio => LinuxTrayIcon::Studio,
-        RecordingMode::Instant => LinuxTrayIcon::Instant,
+        RecordingMode::Instant | RecordingMode::Replay => LinuxTrayIcon::Instant,
         RecordingMode::Screenshot => LinuxTrayIcon::Screenshot,
     }
 }
@@ -963,6 +969,9 @@ pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
                 Ok(TrayItem::ModeScreenshot) => {
                     handle_mode_selection(app, RecordingMode::Screenshot, &cache);
                 }
+                Ok(TrayItem::ModeReplay) => {
+                    handle_mode_selection(app, RecordingMode::Replay, &cache);
+                }
                 Ok(TrayItem::RequestPermissions) => {
                     let app = app.clone();
                     tokio::spawn(async move {
@@ -45,6 +45,15 @@ const MODE_BUTTONS: ModeButtonConfig[] = [
 		icon: (p) => <IconCapScreenshot {...p} />,
 		iconClass: "size-[0.9rem] invert dark:invert-0",
 	},
+	{
+		mode: "replay",
+		label: "Replay mode",
+		description:
+			"Keeps the last few minutes on this machine. Save a replay with a shortcut whenever something worth keeping happens.",
+		settingsSection: null,
+		icon: (p) => <IconLucideHistory {...p} />,
+		iconClass: "size-[0.9rem] text-white invert dark:invert-0",
+	},
 ];
 
 const Mode = (props: ModeProps) => {
@@ -80,6 +80,14 @@ const ModeSelect = (props: { onClose?: () => void; standalone?: boolean }) => {
 			description: "Capture and annotate screenshots instantly.",
 			icon: IconCapScreenshot,
 		},
+		{
+			mode: "replay" as const,
+			title: "Replay",
+			description: "Keep the last few minutes and save them on demand.",
+			icon: (p: { class: string }) => (
+				<IconLucideHistory class={cx(p.class, "text-white")} />
+			),
+		},
 	];
 
 	return (
@@ -102,7 +110,7 @@ const ModeSelect = (props: { onClose?: () => void; standalone?: boolean }) => {
 				</div>
 			</Show>
 
-			<div class="grid grid-cols-3 gap-4">
+			<div class="grid grid-cols-2 gap-4">
 				{modeOptions.map((option) => (
 					<ModeOption
 						mode={option.mode}
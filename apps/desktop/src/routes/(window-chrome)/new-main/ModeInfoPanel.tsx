@@ -3,6 +3,7 @@ import { For } from "solid-js";
 import { Transition } from "solid-transition-group";
 import { commands, type RecordingMode } from "~/utils/tauri";
 import IconLucideArrowLeft from "~icons/lucide/arrow-left";
+import IconLucideHistory from "~icons/lucide/history";
 import { useRecordingOptions } from "../OptionsContext";
 
 interface ModeInfoPanelProps {
@@ -31,6 +32,15 @@ const modeOptions = [
 			"Capture and annotate screenshots instantly. Great for quick captures, bug reports, and visual communication.",
 		icon: IconCapScreenshot,
 	},
+	{
+		mode: "replay" as RecordingMode,
+		title: "Replay",
+		description:
+			"Keep a rolling buffer of the last few minutes on this machine. Save a replay with a shortcut, or stop to keep the whole buffer.",
+		icon: (p: { class?: string }) => (
+			<IconLucideHistory class={cx(p.class, "text-white")} />
+		),
+	},
 ];
 
 export default function ModeInfoPanel(props: ModeInfoPanelProps) {
@@ -589,6 +589,19 @@ function Inner(props: {
 								{ text: "10 seconds", value: 10 },
 							]}
 						/>
+						<SelectSettingItem
+							label="Replay length"
+							description="How much footage replay mode keeps before it is saved."
+							value={settings.replayLengthSecs ?? 300}
+							onChange={(value) => handleChange("replayLengthSecs", value)}
+							options={[
+								{ text: "30 seconds", value: 30 },
+								{ text: "1 minute", value: 60 },
+								{ text: "2 minutes", value: 120 },
+								{ text: "5 minutes", value: 300 },
+								{ text: "10 minutes", value: 600 },
+							]}
+						/>
 						<ToggleSettingItem
 							label="Confirm before recording without a microphone"
 							description="Require confirmation when no microphone is selected or the selected microphone is unavailable."
@@ -36,6 +36,7 @@ const ACTION_TEXT = {
 	screenshotDisplay: "Screenshot current display",
 	screenshotWindow: "Screenshot current window",
 	screenshotArea: "Screenshot area picker",
+	saveReplay: "Save replay",
 } satisfies { [K in HotkeyAction]?: string };
 
 export default function () {
@@ -95,6 +96,7 @@ function Inner(props: { initialStore: HotkeysStore | null }) {
 			"openRecordingPickerDisplay",
 			"openRecordingPickerWindow",
 			"openRecordingPickerArea",
+			"saveReplay",
 		] satisfies Array<keyof typeof ACTION_TEXT>;
 
 	return (
@@ -137,7 +137,7 @@ function InProgressRecordingInner() {
 		return (
 			mode === "studio" ||
 			os === "macos" ||
-			(os === "windows" && mode === "instant")
+			(os === "windows" && (mode === "instant" || mode === "replay"))
 		);
 	});
 
@@ -490,11 +490,11 @@ function InProgressRecordingInner() {
 
 	// Muting zeroes the mic samples backend-side while the stream keeps its
 	// normal cadence, so the recording timeline is unaffected. Only exposed for
-	// instant mode: studio records the mic as an editable track, where muted
+	// instant and replay mode: studio records the mic as an editable track, where muted
 	// spans would silently bake zeros into it.
 	const canToggleMicMute = createMemo(
 		() =>
-			recordingMode() === "instant" &&
+			(recordingMode() === "instant" || recordingMode() === "replay") &&
 			optionsQuery.rawOptions.micName != null &&
 			!disconnectedInputs.microphone &&
 			(state().variant === "recording" || state().variant === "paused"),
@@ -513,6 +513,15 @@ function InProgressRecordingInner() {
 		},
 	}));
 
+	const saveReplay = createMutation(() => ({
+		mutationFn: () => commands.saveReplay(),
+		onError: (error) =>
+			dialog.message(String(error), {
+				title: "Failed to save replay",
+				kind: "error",
+			}),
+	}));
+
 	const restartRecording = createMutation(() => ({
 		mutationFn: async () => {
 			const shouldRestart = await dialog.confirm(
@@ -1016,6 +1025,17 @@ function InProgressRecordingInner() {
 											</ActionButton>
 										)}
 
+										<Show when={recordingMode() === "replay"}>
+											<ActionButton
+												disabled={saveReplay.isPending || isCountdown()}
+												onClick={() => saveReplay.mutate()}
+												title="Save replay"
+												aria-label="Save replay"
+											>
+												<IconLucideHistory class="size-5" />
+											</ActionButton>
+										</Show>
+
 										<ActionButton
 											disabled={restartRecording.isPending || isCountdown()}
 											onClick={() => restartRecording.mutate()}
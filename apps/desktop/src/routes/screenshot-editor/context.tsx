@@ -128,7 +128,9 @@ const DEFAULT_AUDIO: AudioConfiguration = {
 	improve: false,
 	micVolumeDb: 0,
 	micStereoMode: "stereo",
+	micMute: false,
 	systemVolumeDb: 0,
+	additionalMics: [],
 };
 
 const DEFAULT_CURSOR: CursorConfiguration = {
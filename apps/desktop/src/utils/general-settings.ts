@@ -51,6 +51,7 @@ export function createDefaultGeneralSettings(): GeneralSettingsStore {
 		custom_cursor_capture2: true,
 		excludedWindows: [],
 		instantModeMaxResolution: 1920,
+		replayLengthSecs: 300,
 		crashRecoveryRecording: true,
 		maxFps: 60,
 		transcriptionHints: [...DEFAULT_TRANSCRIPTION_HINTS],
@@ -38,6 +38,9 @@ async startRecording(inputs: StartRecordingInputs) : Promise<RecordingAction> {
 async stopRecording() : Promise<null> {
     return await TAURI_INVOKE("stop_recording");
 },
+async saveReplay() : Promise<null> {
+    return await TAURI_INVOKE("save_replay");
+},
 async pauseRecording() : Promise<null> {
     return await TAURI_INVOKE("pause_recording");
 },
@@ -569,12 +572,47 @@ videoImportProgress: "video-import-progress"
 /** user-defined types **/
 
 export type Action = { type: "copyToClipboard"; source?: ClipboardSource } | { type: "saveToLocation"; dir: string; filenameTemplate?: string | null } | { type: "export"; profile: ExportProfile; destination?: ExportDestination } | { type: "upload"; organizationId?: string | null; copyLink?: boolean; openInBrowser?: boolean } | { type: "revealInFileManager" } | { type: "openFile" } | { type: "runCommand"; program: string; args?: string[]; cwd?: string | null; env?: { [key in string]: string }; useShell?: boolean } | { type: "webhook"; url: string; method?: string; headers?: { [key in string]: string }; bodyTemplate?: string | null } | { type: "recognizeTextToClipboard" } | { type: "notify"; titleTemplate?: string; bodyTemplate?: string } | { type: "openEditor" } | { type: "skipEditor" } | { type: "applyPreset"; name: string } | { type: "deleteLocalFiles" }
+export type AdditionalAudioMeta = ({ path: string; start_time?: number | null; device_id?: string | null; gap_summary?: AudioGapSummary | null }) & { 
+/**
+ * Stable across segments (e.g. `mic-2`), so one audio setting applies to
+ * every segment the microphone was recorded in.
+ */
+id: string }
+export type AdditionalMicConfiguration = { 
+/**
+ * Matches `AdditionalAudioMeta::id` in the recording meta.
+ */
+sourceId: string; volumeDb: number; stereoMode: StereoMode; mute: boolean }
+export type AdditionalVideoKind = "camera" | "display"
+export type AdditionalVideoMeta = ({ path: string; fps?: number; start_time?: number | null; device_id?: string | null }) & { id: string; kind: AdditionalVideoKind }
 export type Annotation = { id: string; type: AnnotationType; x: number; y: number; width: number; height: number; strokeColor: string; strokeWidth: number; fillColor: string; opacity: number; rotation: number; text: string | null; maskType?: MaskType | null; maskLevel?: number | null; points?: ([number, number])[] | null }
 export type AnnotationType = "arrow" | "circle" | "rectangle" | "text" | "mask" | "draw"
+export type ApngExportSettings = { fps: number; resolution_base: XY<number>; 
+/**
+ * Palette quality from 1-100, ignored when lossless (default: 80)
+ */
+quality?: number | null; 
+/**
+ * Keep full RGBA colour instead of a 256-colour palette (default: false)
+ */
+lossless?: boolean; 
+/**
+ * Times to play the animation, 0 for forever (default: 0)
+ */
+loop_count?: number | null; 
+/**
+ * Lower the quality until the file fits in this many bytes
+ */
+max_file_size?: number | null }
 export type AppTheme = "system" | "light" | "dark"
 export type AspectRatio = "wide" | "vertical" | "square" | "classic" | "tall"
 export type Audio = { duration: number; sample_rate: number; channels: number; start_time: number }
-export type AudioConfiguration = { mute: boolean; improve: boolean; micVolumeDb: number; micStereoMode: StereoMode; systemVolumeDb: number }
+export type AudioConfiguration = { mute: boolean; improve: boolean; micVolumeDb: number; micStereoMode: StereoMode; micMute: boolean; systemVolumeDb: number; 
+/**
+ * Mix settings for a segment's `additional_mics`. Microphones without an
+ * entry play at 0 dB in stereo.
+ */
+additionalMics: AdditionalMicConfiguration[] }
 /**
  * Overlap-trim accounting captured by the recorder's audio gap tracker, persisted so the
  * editor can compensate for stale-startup audio drift from typed data instead of scraping
@@ -880,7 +918,36 @@ export type CurrentRecording = { target: CurrentRecordingTarget; mode: Recording
 export type CurrentRecordingChanged = null
 export type CurrentRecordingTarget = { window: { id: WindowId; bounds: LogicalBounds | null } } | { screen: { id: DisplayId } } | { area: { screen: DisplayId; bounds: LogicalBounds } } | "camera"
 export type CursorAnimationStyle = "slow" | "smooth" | "mellow" | "fast" | "custom"
-export type CursorConfiguration = { hide: boolean; hideWhenIdle: boolean; hideWhenIdleDelay: number; size: number; type: CursorType; animationStyle: CursorAnimationStyle; tension: number; mass: number; friction: number; raw: boolean; motionBlur: number; useSvg: boolean; rotationAmount?: number; baseRotation?: number; clickSpring?: ClickSpringConfig | null; stopMovementInLastSeconds?: number | null }
+export type CursorConfiguration = { hide: boolean; hideWhenIdle: boolean; hideWhenIdleDelay: number; size: number; type: CursorType; animationStyle: CursorAnimationStyle; tension: number; mass: number; friction: number; raw: boolean; motionBlur: number; useSvg: boolean; rotationAmount?: number; baseRotation?: number; clickSpring?: ClickSpringConfig | null; stopMovementInLastSeconds?: number | null; 
+/**
+ * Corrections to the recorded cursor path, applied before smoothing.
+ */
+edits?: CursorEdit[] }
+export type CursorEdit = ({ clip?: number; start: number; end: number }) & (
+/**
+ * Holds the cursor at a normalised display position.
+ */
+{ type: "position"; position: XY<number> } | 
+/**
+ * Replaces the recorded path with smooth curves between the range
+ * boundaries and every click inside it. `curvature` bows each curve
+ * sideways by that fraction of its length; 0 gives straight lines.
+ */
+{ type: "straighten"; curvature?: number } | { type: "hide" } | 
+/**
+ * Draws a different cursor image for the range.
+ */
+{ type: "image"; image: CursorEditImage })
+export type CursorEditImage = 
+/**
+ * Another cursor captured in the same recording.
+ */
+{ source: "recorded"; cursorId: string } | 
+/**
+ * An image file relative to the project directory. `hotspot` is
+ * normalised to the image size.
+ */
+{ source: "custom"; path: string; hotspot: XY<number> }
 export type CursorMeta = { imagePath: string; hotspot: XY<number>; shape?: string | null }
 export type CursorType = "auto" | "pointer" | "circle"
 export type Cursors = { [key in string]: string } | { [key in string]: CursorMeta }
@@ -905,6 +972,19 @@ export type DownloadProgress = { progress: number; message: string }
 export type EditorPreviewQuality = "quarter" | "half" | "full"
 export type EditorRecordingAdded = { editor_path: string; recording_path: string }
 export type EditorStateChanged = { playhead_position: number }
+export type ExportAudioSettings = { 
+/**
+ * Normalise the mix to this integrated loudness in LUFS, e.g. -16
+ */
+loudness_target?: number | null; 
+/**
+ * True-peak ceiling in dBTP when normalising (default: -1)
+ */
+true_peak_limit?: number | null; 
+/**
+ * Add mic, system audio and music as separate tagged tracks after the mix
+ */
+separate_tracks?: boolean }
 export type ExportCompression = "Maximum" | "Social" | "Web" | "Potato"
 export type ExportDestination = "projectFolder" | { customPath: { dir: string } }
 export type ExportEstimates = { duration_seconds: number; estimated_time_seconds: number; estimated_size_mb: number }
@@ -912,7 +992,7 @@ export type ExportFormat = "mp4" | "gif" | "mov"
 export type ExportPreviewResult = { jpeg_base64: string; estimated_size_mb: number; actual_width: number; actual_height: number; frame_render_time_ms: number; total_frames: number }
 export type ExportPreviewSettings = { fps: number; resolution_base: XY<number>; compression_bpp: number; cursor_only?: boolean }
 export type ExportProfile = { format: ExportFormat; fps?: number; resolutionBase?: XY<number>; compression?: AutomationExportCompression | null; presetName?: string | null }
-export type ExportSettings = ({ format: "Mp4" } & Mp4ExportSettings) | ({ format: "Gif" } & GifExportSettings) | ({ format: "Mov" } & MovExportSettings)
+export type ExportSettings = ({ format: "Mp4" } & Mp4ExportSettings) | ({ format: "Gif" } & GifExportSettings) | ({ format: "Mov" } & MovExportSettings) | ({ format: "WebP" } & WebpExportSettings) | ({ format: "Apng" } & ApngExportSettings) | ({ format: "Stems" } & StemsExportSettings)
 export type FileType = "recording" | "screenshot"
 export type Flags = { captions: boolean }
 export type FrameConfiguration = { style: FrameStyle; theme: FrameTheme; 
@@ -964,7 +1044,7 @@ export type GeneralSettingsStore = { instanceId?: string; uploadIndividualFiles?
  * display. From then on it is the user's preference and nothing re-reads
  * the hardware, so moving between machines can't silently flip it.
  */
-macbookNotchOverlay?: boolean | null; captureKeyboardEvents?: boolean; postDeletionBehaviour?: PostDeletionBehaviour; excludedWindows?: WindowExclusion[]; deleteInstantRecordingsAfterUpload?: boolean; instantModeMaxResolution?: number; defaultProjectNameTemplate?: string | null; crashRecoveryRecording?: boolean; maxFps?: number; transcriptionHints?: string[]; editorPreviewQuality?: EditorPreviewQuality; studioRecordingQuality?: StudioRecordingQuality; mainWindowPosition?: WindowPosition | null; cameraWindowPosition?: WindowPosition | null; cameraWindowPositionsByMonitorName?: { [key in string]: WindowPosition }; hasCompletedOnboarding?: boolean; enableTelemetry?: boolean; outOfProcessMuxer?: boolean; recordingsPath?: string | null; 
+macbookNotchOverlay?: boolean | null; captureKeyboardEvents?: boolean; postDeletionBehaviour?: PostDeletionBehaviour; excludedWindows?: WindowExclusion[]; deleteInstantRecordingsAfterUpload?: boolean; instantModeMaxResolution?: number; replayLengthSecs?: number; defaultProjectNameTemplate?: string | null; crashRecoveryRecording?: boolean; maxFps?: number; transcriptionHints?: string[]; editorPreviewQuality?: EditorPreviewQuality; studioRecordingQuality?: StudioRecordingQuality; mainWindowPosition?: WindowPosition | null; cameraWindowPosition?: WindowPosition | null; cameraWindowPositionsByMonitorName?: { [key in string]: WindowPosition }; hasCompletedOnboarding?: boolean; enableTelemetry?: boolean; outOfProcessMuxer?: boolean; recordingsPath?: string | null; 
 /**
  * Custom recordings folders that were used before; recordings left in
  * them stay visible in the library. Most recent last.
@@ -991,7 +1071,7 @@ export type GlideDirection = "none" | "left" | "right" | "up" | "down"
 export type HapticPattern = "alignment" | "levelChange" | "generic"
 export type HapticPerformanceTime = "default" | "now" | "drawCompleted"
 export type Hotkey = { code: string; meta: boolean; ctrl: boolean; alt: boolean; shift: boolean }
-export type HotkeyAction = "startStudioRecording" | "startInstantRecording" | "stopRecording" | "restartRecording" | "togglePauseRecording" | "cycleRecordingMode" | "openRecordingPicker" | "openRecordingPickerDisplay" | "openRecordingPickerWindow" | "openRecordingPickerArea" | "screenshotDisplay" | "screenshotWindow" | "screenshotArea" | "other"
+export type HotkeyAction = "startStudioRecording" | "startInstantRecording" | "stopRecording" | "restartRecording" | "togglePauseRecording" | "cycleRecordingMode" | "openRecordingPicker" | "openRecordingPickerDisplay" | "openRecordingPickerWindow" | "openRecordingPickerArea" | "screenshotDisplay" | "screenshotWindow" | "screenshotArea" | "saveReplay" | "other"
 export type HotkeysConfiguration = { show: boolean }
 export type HotkeysStore = { hotkeys: { [key in HotkeyAction]: Hotkey } }
 export type ImportStage = "Probing" | "Converting" | "Finalizing" | "Complete" | "Failed"
@@ -1000,6 +1080,16 @@ export type ImportedAudioTrack = {
  * Path relative to the project directory, e.g. `assets/audio/<file>`.
  */
 path: string; name: string; duration: number }
+export type ImportedMediaKind = "video" | "image"
+export type ImportedMediaMeta = { kind: ImportedMediaKind; 
+/**
+ * File name the clip was imported from, for display in the editor.
+ */
+sourceName: string; 
+/**
+ * How long a still image is shown. Videos play for their own length.
+ */
+duration?: number | null }
 export type IncompleteRecordingInfo = { projectPath: string; prettyName: string; segmentCount: number; estimatedDurationSecs: number }
 export type InstantRecordingMeta = { recording: boolean } | { error: string } | { fps: number; sample_rate: number | null }
 export type JsonValue<T> = [T]
@@ -1025,9 +1115,30 @@ export type MicrophoneInfo = { name: string; sampleRate: number; channels: numbe
 export type ModelDownloadState = "downloading" | "completed" | "failed"
 export type ModelDownloadStatus = { state: ModelDownloadState; progress: number; message: string }
 export type ModelIDType = string
-export type MovExportSettings = { fps: number; resolution_base: XY<number>; cursor_only?: boolean }
-export type Mp4ExportSettings = { fps: number; resolution_base: XY<number>; compression: ExportCompression; custom_bpp: number | null; force_ffmpeg_decoder?: boolean; optimize_filesize?: boolean }
-export type MultipleSegment = { display: VideoMeta; camera?: VideoMeta | null; mic?: AudioMeta | null; system_audio?: AudioMeta | null; cursor?: string | null; keyboard?: string | null; display_notch?: DisplayNotch | null }
+export type MovExportSettings = { fps: number; resolution_base: XY<number>; cursor_only?: boolean; 
+/**
+ * MOV exports are silent unless normalisation or separate tracks are
+ * requested; tracks are written as 24-bit PCM.
+ */
+audio?: ExportAudioSettings }
+export type Mp4ExportSettings = { fps: number; resolution_base: XY<number>; compression: ExportCompression; custom_bpp: number | null; force_ffmpeg_decoder?: boolean; optimize_filesize?: boolean; 
+/**
+ * Fit the file within this many bytes by deriving the bitrate from the
+ * duration, re-encoding if the result overshoots. Takes precedence over
+ * `compression`, `custom_bpp` and CRF encoding.
+ */
+target_file_size?: number | null; audio?: ExportAudioSettings }
+export type MultipleSegment = { display: VideoMeta; camera?: VideoMeta | null; mic?: AudioMeta | null; system_audio?: AudioMeta | null; 
+/**
+ * Further microphones recorded next to `mic`, each as its own track that
+ * is mixed using `AudioConfiguration::additional_mics`.
+ */
+additional_mics?: AdditionalAudioMeta[]; cursor?: string | null; keyboard?: string | null; display_notch?: DisplayNotch | null; additional_videos?: AdditionalVideoMeta[]; 
+/**
+ * Set when `display` is an external file imported as a clip (an intro
+ * bumper, B-roll, a title card) rather than a Cap recording.
+ */
+imported?: ImportedMediaMeta | null }
 export type MultipleSegments = { segments: MultipleSegment[]; cursors: Cursors; status?: StudioRecordingStatus | null }
 export type NewNotification = { title: string; body: string; is_error: boolean }
 export type NewScreenshotAdded = { path: string }
@@ -1049,13 +1160,14 @@ export type OnEscapePress = null
 export type Organization = { id: string; name: string; ownerId: string; role?: string; canEditBrand?: boolean; iconUrl?: string | null; brandColors?: OrganizationBrandColors }
 export type OrganizationBrandColors = { primary: string | null; secondary: string | null; accent: string | null; background: string | null }
 export type PhysicalSize = { width: number; height: number }
+export type PictureInPictureLayer = { sourceId: string; hide: boolean; mirror: boolean; position: XY<number>; size: number; shape: CameraShape; crop: Crop | null; rounding: number; roundingType: CornerStyle; shadow: number }
 export type Plan = { upgraded: boolean; manual: boolean; last_checked: number }
 export type Platform = "MacOS" | "Windows" | "Linux"
 export type PostDeletionBehaviour = "doNothing" | "reopenRecordingWindow"
 export type PostStudioRecordingBehaviour = "openEditor" | "showOverlay"
 export type Preset = { name: string; config: ProjectConfiguration }
 export type PresetsStore = { presets: Preset[]; default: number | null }
-export type ProjectConfiguration = { aspectRatio: AspectRatio | null; background: BackgroundConfiguration; camera: Camera; audio: AudioConfiguration; cursor: CursorConfiguration; hotkeys: HotkeysConfiguration; timeline: TimelineConfiguration | null; captions: CaptionsData | null; keyboard: KeyboardData | null; clips: ClipConfiguration[]; annotations: Annotation[]; screenMotionBlur?: number; screenMovementSpring?: ScreenMovementSpring; 
+export type ProjectConfiguration = { aspectRatio: AspectRatio | null; background: BackgroundConfiguration; camera: Camera; audio: AudioConfiguration; cursor: CursorConfiguration; hotkeys: HotkeysConfiguration; timeline: TimelineConfiguration | null; captions: CaptionsData | null; keyboard: KeyboardData | null; clips: ClipConfiguration[]; annotations: Annotation[]; pictureInPicture?: PictureInPictureLayer[]; screenMotionBlur?: number; screenMovementSpring?: ScreenMovementSpring; 
 /**
  * Per-layer cinematic color grades. Field-level default keeps old
  * project files (and old saved presets) deserializing to the identity
@@ -1085,7 +1197,7 @@ export type RecordingEvent = { variant: "Countdown"; value: number } | { variant
 export type RecordingInputKind = "microphone" | "camera"
 export type RecordingMeta = (StudioRecordingMeta | InstantRecordingMeta) & { platform?: Platform | null; pretty_name: string; sharing?: SharingMeta | null; upload?: UploadMeta | null }
 export type RecordingMetaWithMetadata = ((StudioRecordingMeta | InstantRecordingMeta) & { platform?: Platform | null; pretty_name: string; sharing?: SharingMeta | null; upload?: UploadMeta | null }) & { mode: RecordingMode; status: StudioRecordingStatus; clip_count: number; sort_time_millis: number }
-export type RecordingMode = "studio" | "instant" | "screenshot"
+export type RecordingMode = "studio" | "instant" | "screenshot" | "replay"
 export type RecordingOptionsChanged = null
 export type RecordingSettingsStore = { target: ScreenCaptureTarget | null; micName: string | null; cameraId: DeviceOrModelID | null; mode: RecordingMode | null; systemAudio: boolean; organizationId: string | null; cameraDeviceSettings: { [key in string]: CameraDeviceSettings }; microphoneDeviceSettings: { [key in string]: MicrophoneDeviceSettings } }
 export type RecordingStarted = null
@@ -1111,7 +1223,7 @@ export type SceneMode = "default" | "cameraOnly" | "hideCamera" | "splitScreen"
  */
 "floating"
 export type SceneSegment = { start: number; end: number; mode?: SceneMode; splitLayout?: SplitLayout | null; transitionIn?: number; transitionOut?: number }
-export type ScreenCaptureTarget = { variant: "window"; id: WindowId } | { variant: "display"; id: DisplayId } | { variant: "area"; screen: DisplayId; bounds: LogicalBounds } | { variant: "cameraOnly" }
+export type ScreenCaptureTarget = { variant: "window"; id: WindowId } | { variant: "display"; id: DisplayId } | { variant: "area"; screen: DisplayId; bounds: LogicalBounds } | { variant: "followWindow"; id: WindowId } | { variant: "cameraOnly" }
 export type ScreenMovementSpring = { stiffness: number; damping: number; mass: number }
 export type ScreenshotMetaWithMetadata = ((StudioRecordingMeta | InstantRecordingMeta) & { platform?: Platform | null; pretty_name: string; sharing?: SharingMeta | null; upload?: UploadMeta | null }) & { sort_time_millis: number }
 export type ScreenshotOcrLine = { text: string; confidence: number | null; bounds: ScreenshotOcrRegion }
@@ -1136,6 +1248,16 @@ export type ShowCapWindow = { Main: { init_target_mode: RecordingTargetMode | nu
 export type SingleSegment = { display: VideoMeta; camera?: VideoMeta | null; audio?: AudioMeta | null; cursor?: string | null }
 export type SplitLayout = { screenZoom: number; screenPosition: XY<number>; cameraZoom: number; cameraPosition: XY<number> }
 export type StartRecordingInputs = { capture_target: ScreenCaptureTarget; capture_system_audio?: boolean; mode: RecordingMode; organization_id?: string | null }
+export type StemFileFormat = "Wav" | "Flac"
+/**
+ * Writes mic, system audio and music to separate files, without video.
+ */
+export type StemsExportSettings = { file_format?: StemFileFormat; 
+/**
+ * Normalisation measured on the full mix and applied to every stem, so
+ * the stems still sum to the normalised mix. `separate_tracks` is ignored.
+ */
+audio?: ExportAudioSettings }
 export type StereoMode = "stereo" | "monoL" | "monoR"
 export type StudioRecordingMeta = { segment: SingleSegment } | { inner: MultipleSegments }
 export type StudioRecordingQuality = "compatibility" | "balanced" | "ultra"
@@ -1199,6 +1321,23 @@ export type VideoImportProgress = { project_path: string; stage: ImportStage; pr
 export type VideoMeta = { path: string; fps?: number; start_time?: number | null; device_id?: string | null }
 export type VideoRecordingMetadata = { duration: number; size: number }
 export type VideoUploadInfo = { id: string; link: string; config: S3UploadMeta }
+export type WebpExportSettings = { fps: number; resolution_base: XY<number>; 
+/**
+ * Lossy quality from 1-100 (default: 80)
+ */
+quality?: number | null; 
+/**
+ * Encode every pixel exactly instead of lossy (default: false)
+ */
+lossless?: boolean; 
+/**
+ * Times to play the animation, 0 for forever (default: 0)
+ */
+loop_count?: number | null; 
+/**
+ * Lower the quality until the file fits in this many bytes
+ */
+max_file_size?: number | null }
 export type WindowExclusion = { bundleIdentifier?: string | null; ownerName?: string | null; windowTitle?: string | null }
 export type WindowId = string
 export type WindowPosition = { x: number; y: number; displayId?: DisplayId | null }
@@ -63,6 +63,27 @@ enum Commands {
         gate: bool,
     },
 
+    /// Render fixture projects over synthetic recordings and compare the
+    /// frames against checked-in goldens.
+    Golden {
+        #[arg(long)]
+        corpus: Option<PathBuf>,
+
+        /// Rewrite the goldens from the current renders.
+        #[arg(long)]
+        update: bool,
+
+        /// Directory to write an HTML diff report into.
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[arg(long)]
+        gate: bool,
+    },
+
     Synthetic {
         #[arg(short, long)]
         config: Option<PathBuf>,
@@ -169,6 +190,16 @@ async fn main() -> Result<()> {
             cmd_suite(&name, duration, recording_path, output, gate).await?;
         }
 
+        Commands::Golden {
+            corpus,
+            update,
+            report,
+            output,
+            gate,
+        } => {
+            cmd_golden(corpus, update, report, output, gate).await?;
+        }
+
         Commands::Synthetic { config, output } => {
             cmd_synthetic(config, output).await?;
         }
@@ -320,6 +351,41 @@ async fn cmd_suite(
     Ok(())
 }
 
+async fn cmd_golden(
+    corpus: Option<PathBuf>,
+    update: bool,
+    report: Option<PathBuf>,
+    output: Option<PathBuf>,
+    gate: bool,
+) -> Result<()> {
+    let hardware = DiscoveredHardware::discover().await?;
+    let options = suites::GoldenOptions {
+        corpus: corpus.unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/golden/corpus.toml")
+        }),
+        update,
+        report_dir: report,
+    };
+
+    let results = suites::run_golden_suite(&hardware, &options).await?;
+
+    results.print_summary();
+
+    if let Some(path) = output {
+        results.save_json(&path)?;
+    }
+
+    if gate && (results.summary.failed > 0 || results.summary.errors > 0) {
+        anyhow::bail!(
+            "Golden gate failed: {} failed, {} errors",
+            results.summary.failed,
+            results.summary.errors
+        );
+    }
+
+    Ok(())
+}
+
 fn resolve_recording_path(explicit: Option<PathBuf>) -> Result<PathBuf> {
     if let Some(path) = explicit {
         return Ok(path);
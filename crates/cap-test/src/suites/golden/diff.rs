@@ -0,0 +1,101 @@
+//! Perceptual frame comparison.
+//!
+//! Pixels are compared by their distance in YIQ space, weighted the way the
+//! eye weighs luma over chroma (the same metric pixelmatch uses), so encoder
+//! and rasterizer noise stays under the threshold while a moved edge or a
+//! shifted colour does not.
+
+use image::{Rgba, RgbaImage};
+
+/// Largest possible weighted YIQ delta between two opaque colours.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+pub struct FrameDiff {
+    pub differing_pixels: u64,
+    pub total_pixels: u64,
+    /// Faded copy of the expected frame with differing pixels in red.
+    pub image: RgbaImage,
+}
+
+impl FrameDiff {
+    pub fn ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            0.0
+        } else {
+            self.differing_pixels as f64 / self.total_pixels as f64
+        }
+    }
+}
+
+/// Compares `actual` against `expected`. `threshold` is the per-pixel
+/// tolerance in 0..1; frames of different sizes differ everywhere.
+pub fn compare(expected: &RgbaImage, actual: &RgbaImage, threshold: f64) -> FrameDiff {
+    if expected.dimensions() != actual.dimensions() {
+        let (width, height) = actual.dimensions();
+        return FrameDiff {
+            differing_pixels: width as u64 * height as u64,
+            total_pixels: width as u64 * height as u64,
+            image: RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 255])),
+        };
+    }
+
+    let max_delta = MAX_YIQ_DELTA * threshold * threshold;
+    let (width, height) = expected.dimensions();
+    let mut image = RgbaImage::new(width, height);
+    let mut differing_pixels = 0;
+
+    for ((a, b), out) in expected
+        .pixels()
+        .zip(actual.pixels())
+        .zip(image.pixels_mut())
+    {
+        if yiq_delta(a, b) > max_delta {
+            differing_pixels += 1;
+            *out = Rgba([255, 0, 0, 255]);
+        } else {
+            let y = luma(blend_white(a));
+            let faded = (255.0 + (y - 255.0) * 0.1) as u8;
+            *out = Rgba([faded, faded, faded, 255]);
+        }
+    }
+
+    FrameDiff {
+        differing_pixels,
+        total_pixels: width as u64 * height as u64,
+        image,
+    }
+}
+
+fn yiq_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+
+    let a = blend_white(a);
+    let b = blend_white(b);
+
+    let y = luma(a) - luma(b);
+    let i = in_phase(a) - in_phase(b);
+    let q = quadrature(a) - quadrature(b);
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+/// Composites a pixel over white so transparent regions compare by what a
+/// viewer would see.
+fn blend_white(pixel: &Rgba<u8>) -> [f64; 3] {
+    let alpha = pixel[3] as f64 / 255.0;
+    [0, 1, 2].map(|c| 255.0 + (pixel[c] as f64 - 255.0) * alpha)
+}
+
+fn luma([r, g, b]: [f64; 3]) -> f64 {
+    r * 0.29889531 + g * 0.58662247 + b * 0.11448223
+}
+
+fn in_phase([r, g, b]: [f64; 3]) -> f64 {
+    r * 0.59597799 - g * 0.2741761 - b * 0.32180189
+}
+
+fn quadrature([r, g, b]: [f64; 3]) -> f64 {
+    r * 0.21147017 - g * 0.52261711 + b * 0.31114694
+}
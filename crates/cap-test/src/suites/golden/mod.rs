@@ -0,0 +1,510 @@
+//! Golden-image regression suite for the render pipeline.
+//!
+//! Each corpus case renders a synthetic recording (a `test_sources` pattern
+//! encoded the way a studio recording reaches disk) through a fixture
+//! `ProjectConfiguration` on the software wgpu adapter, then compares the
+//! selected output frames against checked-in PNGs with a perceptual diff.
+//! A frame without a checked-in PNG fails; `cap-test golden --update` records
+//! the goldens from the current renders. A unit test renders the same corpus
+//! on the CPU renderer and holds it to the GPU output.
+
+mod diff;
+mod report;
+
+use anyhow::{Context, Result, anyhow};
+use cap_enc_ffmpeg::{h264::H264Encoder, mp4::MP4File};
+use cap_media_info::{Pixel, VideoInfo};
+use cap_project::{ProjectConfiguration, RecordingMeta, XY};
+use cap_recording::test_sources::{TestPattern, generate_video_frame};
+use cap_rendering::{
+    CpuRenderConstants, ProjectRecordingsMeta, RenderSegment, RenderVideoConstants, RenderedFrame,
+    render_video_to_channel, render_video_to_channel_cpu,
+};
+use chrono::Utc;
+use image::RgbaImage;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tracing::info;
+
+use crate::discovery::DiscoveredHardware;
+use crate::results::{
+    DisplayTestConfig, ResultsMeta, ResultsSummary, TestCaseConfig, TestResult, TestResults,
+};
+
+use report::{CaseReport, FrameReport, FrameStatus};
+
+const SOURCE_WIDTH: u32 = 1280;
+const SOURCE_HEIGHT: u32 = 720;
+const SOURCE_FPS: u32 = 30;
+const SOURCE_SECS: u32 = 3;
+/// Goldens are rendered at half of 1080p to keep the checked-in PNGs small.
+const OUTPUT_BASE: XY<u32> = XY { x: 960, y: 540 };
+
+pub struct GoldenOptions {
+    /// `corpus.toml`; fixture projects and goldens resolve relative to it.
+    pub corpus: PathBuf,
+    /// Overwrite goldens with the current renders instead of comparing.
+    pub update: bool,
+    /// Directory to write `report.html` and its images into.
+    pub report_dir: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Corpus {
+    /// Per-pixel YIQ distance (0..1) below which pixels count as equal.
+    #[serde(default = "Corpus::default_threshold")]
+    threshold: f64,
+    /// Fraction of differing pixels a frame may have and still pass.
+    #[serde(default = "Corpus::default_max_diff_ratio")]
+    max_diff_ratio: f64,
+    #[serde(rename = "case")]
+    cases: Vec<GoldenCase>,
+}
+
+impl Corpus {
+    fn default_threshold() -> f64 {
+        0.1
+    }
+
+    fn default_max_diff_ratio() -> f64 {
+        0.001
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "kebab-case")]
+enum SourcePattern {
+    FrameCounter,
+    SmpteColorBars,
+}
+
+impl From<SourcePattern> for TestPattern {
+    fn from(value: SourcePattern) -> Self {
+        match value {
+            SourcePattern::FrameCounter => TestPattern::FrameCounter,
+            SourcePattern::SmpteColorBars => TestPattern::SmpteColorBars,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GoldenCase {
+    name: String,
+    pattern: SourcePattern,
+    /// Project configuration JSON, relative to the corpus file.
+    project: PathBuf,
+    /// Output frame numbers at 30 fps.
+    frames: Vec<u32>,
+    #[serde(default)]
+    max_diff_ratio: Option<f64>,
+}
+
+pub async fn run_suite(
+    hardware: &DiscoveredHardware,
+    options: &GoldenOptions,
+) -> Result<TestResults> {
+    let start = Instant::now();
+
+    let corpus_dir = options
+        .corpus
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let corpus = load_corpus(&options.corpus)?;
+
+    ffmpeg::init()?;
+    cap_rendering::set_force_software_wgpu_adapter(true);
+
+    let mut results = Vec::new();
+    let mut case_reports = Vec::new();
+
+    for case in &corpus.cases {
+        info!("Rendering golden case '{}'", case.name);
+
+        let mut result = TestResult::new(
+            format!("golden-{}", case.name),
+            format!("Golden {}", case.name),
+            TestCaseConfig {
+                display: Some(DisplayTestConfig {
+                    width: SOURCE_WIDTH,
+                    height: SOURCE_HEIGHT,
+                    fps: SOURCE_FPS,
+                    display_id: None,
+                }),
+                camera: None,
+                audio: None,
+                duration_secs: SOURCE_SECS as u64,
+            },
+        );
+        let mut case_report = CaseReport {
+            name: case.name.clone(),
+            error: None,
+            frames: Vec::new(),
+        };
+
+        let frames = match render_case(&corpus_dir, case, Renderer::Gpu).await {
+            Ok(frames) => frames,
+            Err(e) => {
+                result.set_error(&format!("{e:#}"));
+                case_report.error = Some(format!("{e:#}"));
+                results.push(result);
+                case_reports.push(case_report);
+                continue;
+            }
+        };
+
+        let max_diff_ratio = case.max_diff_ratio.unwrap_or(corpus.max_diff_ratio);
+        let mut failures = Vec::new();
+
+        for (frame_number, actual) in frames {
+            let golden_path = golden_path(&corpus_dir, &case.name, frame_number);
+
+            if options.update {
+                if let Some(parent) = golden_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                actual
+                    .save(&golden_path)
+                    .with_context(|| format!("Failed to write golden {}", golden_path.display()))?;
+                result
+                    .notes
+                    .push(format!("frame {frame_number}: golden updated"));
+                case_report.frames.push(FrameReport {
+                    frame_number,
+                    status: FrameStatus::Updated,
+                    expected: None,
+                    actual,
+                    diff: None,
+                });
+                continue;
+            }
+
+            let expected = match image::open(&golden_path) {
+                Ok(image) => image.to_rgba8(),
+                // A missing golden would let any render pass, so it fails
+                // until `--update` records one.
+                Err(_) => {
+                    failures.push(format!(
+                        "frame {frame_number}: no golden at {}; record it with `cap-test golden --update`",
+                        golden_path.display()
+                    ));
+                    case_report.frames.push(FrameReport {
+                        frame_number,
+                        status: FrameStatus::Missing,
+                        expected: None,
+                        actual,
+                        diff: None,
+                    });
+                    continue;
+                }
+            };
+
+            let frame_diff = diff::compare(&expected, &actual, corpus.threshold);
+            let ratio = frame_diff.ratio();
+            result.notes.push(format!(
+                "frame {frame_number}: {} of {} pixels differ ({:.4}%)",
+                frame_diff.differing_pixels,
+                frame_diff.total_pixels,
+                ratio * 100.0
+            ));
+
+            let status = if ratio > max_diff_ratio {
+                failures.push(format!(
+                    "frame {frame_number}: {:.4}% of pixels differ (limit {:.4}%)",
+                    ratio * 100.0,
+                    max_diff_ratio * 100.0
+                ));
+                FrameStatus::Failed
+            } else {
+                FrameStatus::Passed
+            };
+
+            case_report.frames.push(FrameReport {
+                frame_number,
+                status,
+                expected: Some(expected),
+                actual,
+                diff: Some(frame_diff),
+            });
+        }
+
+        if !failures.is_empty() {
+            result.set_failed(&failures.join("; "));
+        }
+
+        results.push(result);
+        case_reports.push(case_report);
+    }
+
+    if let Some(report_dir) = &options.report_dir {
+        let report_path = report::write(report_dir, &case_reports)?;
+        println!("Golden report written to: {}", report_path.display());
+    }
+
+    let summary = ResultsSummary::from_results(&results, start.elapsed());
+
+    Ok(TestResults {
+        meta: ResultsMeta {
+            timestamp: Utc::now(),
+            config_name: "Golden Render Suite".to_string(),
+            config_path: Some(options.corpus.display().to_string()),
+            platform: hardware.system_info.platform.clone(),
+            system: hardware.system_info.clone(),
+            cap_version: None,
+        },
+        hardware: Some(hardware.clone()),
+        results,
+        summary,
+    })
+}
+
+fn load_corpus(path: &Path) -> Result<Corpus> {
+    toml::from_str(
+        &std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?,
+    )
+    .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn golden_path(corpus_dir: &Path, case: &str, frame_number: u32) -> PathBuf {
+    corpus_dir
+        .join("goldens")
+        .join(case)
+        .join(format!("frame-{frame_number:04}.png"))
+}
+
+/// Goldens are recorded from the GPU renderer; the CPU renderer is held to
+/// the same output by the parity test below.
+#[derive(Clone, Copy, Debug)]
+enum Renderer {
+    Gpu,
+    Cpu,
+}
+
+async fn render_case(
+    corpus_dir: &Path,
+    case: &GoldenCase,
+    renderer: Renderer,
+) -> Result<BTreeMap<u32, RgbaImage>> {
+    let temp_dir = TempDir::new()?;
+    let project_path = temp_dir.path();
+
+    write_synthetic_recording(project_path, case.pattern.into())?;
+
+    // Loading through project-config.json applies the same migrations as
+    // opening the project in the editor.
+    let fixture_path = corpus_dir.join(&case.project);
+    std::fs::copy(&fixture_path, project_path.join("project-config.json"))
+        .with_context(|| format!("Failed to read fixture {}", fixture_path.display()))?;
+
+    let recording_meta = RecordingMeta::load_for_project(project_path)
+        .map_err(|e| anyhow!("Failed to load recording meta: {e}"))?;
+    let studio_meta = recording_meta
+        .studio_meta()
+        .context("Synthetic recording is not a studio recording")?;
+    let mut project = ProjectConfiguration::load(project_path)
+        .with_context(|| format!("Failed to load fixture {}", fixture_path.display()))?;
+
+    let recordings = Arc::new(
+        ProjectRecordingsMeta::new(&recording_meta.project_path, studio_meta)
+            .map_err(|e| anyhow!("Failed to load recordings meta: {e}"))?,
+    );
+    if project.timeline.is_none() {
+        project.timeline = recordings.default_timeline();
+    }
+
+    // FFmpeg decoding keeps the decoded source identical across platforms.
+    let segments = cap_editor::create_segments(&recording_meta, studio_meta, true)
+        .await
+        .map_err(|e| anyhow!("Failed to load media: {e}"))?;
+    let render_segments = segments
+        .iter()
+        .map(|segment| RenderSegment {
+            cursor: segment.cursor.clone(),
+            keyboard: segment.keyboard.clone(),
+            decoders: segment.decoders.clone(),
+            render_display: true,
+        })
+        .collect();
+
+    let wanted: BTreeSet<u32> = case.frames.iter().copied().collect();
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<(RenderedFrame, u32)>(8);
+    let collect = tokio::spawn({
+        let wanted = wanted.clone();
+        async move {
+            let mut frames = BTreeMap::new();
+            while let Some((frame, frame_number)) = rx.recv().await {
+                if wanted.contains(&frame_number)
+                    && let Some(image) = frame_to_image(&frame)
+                {
+                    frames.insert(frame_number, image);
+                }
+            }
+            frames
+        }
+    });
+
+    match renderer {
+        Renderer::Gpu => {
+            let constants = RenderVideoConstants::new(
+                &recordings.segments,
+                recording_meta.clone(),
+                studio_meta.clone(),
+            )
+            .await
+            .context("Failed to set up renderer")?;
+
+            render_video_to_channel(
+                &constants,
+                &project,
+                tx,
+                &recording_meta,
+                studio_meta,
+                render_segments,
+                SOURCE_FPS,
+                OUTPUT_BASE,
+                &recordings,
+            )
+            .await
+            .context("Render failed")?;
+        }
+        Renderer::Cpu => {
+            let constants = CpuRenderConstants::new(
+                &recordings.segments,
+                recording_meta.clone(),
+                studio_meta.clone(),
+            )
+            .context("Failed to set up CPU renderer")?;
+            constants.ensure_supported(&project)?;
+
+            render_video_to_channel_cpu(
+                &constants,
+                &project,
+                tx,
+                &recording_meta,
+                studio_meta,
+                render_segments,
+                SOURCE_FPS,
+                OUTPUT_BASE,
+                &recordings,
+            )
+            .await
+            .context("CPU render failed")?;
+        }
+    }
+
+    let frames = collect.await?;
+    if let Some(missing) = wanted.iter().find(|frame| !frames.contains_key(frame)) {
+        anyhow::bail!("Frame {missing} was not rendered");
+    }
+
+    Ok(frames)
+}
+
+/// Writes a single-segment studio recording whose display track is `pattern`.
+fn write_synthetic_recording(project_path: &Path, pattern: TestPattern) -> Result<()> {
+    let video_info = VideoInfo {
+        pixel_format: Pixel::NV12,
+        width: SOURCE_WIDTH,
+        height: SOURCE_HEIGHT,
+        time_base: ffmpeg::Rational(1, 1_000_000),
+        frame_rate: ffmpeg::Rational(SOURCE_FPS as i32, 1),
+    };
+
+    let mut file = MP4File::init(
+        "golden-display",
+        project_path.join("content").join("display.mp4"),
+        false,
+        |o| H264Encoder::builder(video_info).build(o),
+        |_| None,
+    )
+    .map_err(|e| anyhow!("Failed to create synthetic display track: {e}"))?;
+
+    for frame_number in 0..SOURCE_FPS * SOURCE_SECS {
+        let frame = generate_video_frame(&video_info, pattern, frame_number as u64);
+        let timestamp = Duration::from_secs_f64(frame_number as f64 / SOURCE_FPS as f64);
+        file.queue_video_frame(frame, timestamp)
+            .map_err(|e| anyhow!("Failed to encode synthetic frame {frame_number}: {e}"))?;
+    }
+
+    let finish = file
+        .finish()
+        .map_err(|e| anyhow!("Failed to finish synthetic display track: {e}"))?;
+    finish
+        .video_finish
+        .map_err(|e| anyhow!("Failed to finish synthetic display track: {e}"))?;
+
+    let meta = serde_json::json!({
+        "pretty_name": "Golden Fixture",
+        "display": { "path": "content/display.mp4", "fps": SOURCE_FPS }
+    });
+    std::fs::write(
+        project_path.join("recording-meta.json"),
+        serde_json::to_vec_pretty(&meta)?,
+    )?;
+
+    Ok(())
+}
+
+fn frame_to_image(frame: &RenderedFrame) -> Option<RgbaImage> {
+    let row_bytes = frame.width as usize * 4;
+    let stride = frame.padded_bytes_per_row as usize;
+    let mut data = Vec::with_capacity(row_bytes * frame.height as usize);
+
+    for row in 0..frame.height as usize {
+        data.extend_from_slice(frame.data.get(row * stride..row * stride + row_bytes)?);
+    }
+
+    RgbaImage::from_raw(frame.width, frame.height, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Share of a frame that may differ between the renderers by more than the
+    /// corpus threshold. They share layout and differ only in rasterization
+    /// rounding, which stays under the threshold away from anti-aliased edges.
+    const PARITY_MAX_DIFF_RATIO: f64 = 0.01;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cpu_renderer_matches_gpu_renderer() {
+        cap_rendering::set_force_software_wgpu_adapter(true);
+        if cap_rendering::probe_software_adapter().await.is_none() {
+            eprintln!("No wgpu adapter available, skipping CPU/GPU parity");
+            return;
+        }
+        ffmpeg::init().unwrap();
+
+        let corpus_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/golden/corpus.toml");
+        let corpus_dir = corpus_path.parent().unwrap();
+        let corpus = load_corpus(&corpus_path).unwrap();
+
+        let mut mismatches = Vec::new();
+        for case in &corpus.cases {
+            let gpu = render_case(corpus_dir, case, Renderer::Gpu).await.unwrap();
+            let cpu = render_case(corpus_dir, case, Renderer::Cpu).await.unwrap();
+
+            for (frame_number, gpu_frame) in &gpu {
+                let frame_diff = diff::compare(gpu_frame, &cpu[frame_number], corpus.threshold);
+                if frame_diff.ratio() > PARITY_MAX_DIFF_RATIO {
+                    mismatches.push(format!(
+                        "{} frame {frame_number}: {:.4}% of pixels differ",
+                        case.name,
+                        frame_diff.ratio() * 100.0
+                    ));
+                }
+            }
+        }
+
+        assert!(
+            mismatches.is_empty(),
+            "CPU and GPU renders differ: {}",
+            mismatches.join("; ")
+        );
+    }
+}
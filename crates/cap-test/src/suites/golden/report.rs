@@ -0,0 +1,171 @@
+//! Static HTML report showing expected, actual and diff images side by side.
+
+use anyhow::{Context, Result};
+use image::RgbaImage;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use super::diff::FrameDiff;
+
+pub enum FrameStatus {
+    Passed,
+    Failed,
+    Missing,
+    Updated,
+}
+
+impl FrameStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Passed => "pass",
+            Self::Failed => "fail",
+            Self::Missing => "missing golden",
+            Self::Updated => "updated",
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Self::Passed | Self::Updated => "pass",
+            Self::Failed | Self::Missing => "fail",
+        }
+    }
+}
+
+pub struct FrameReport {
+    pub frame_number: u32,
+    pub status: FrameStatus,
+    pub expected: Option<RgbaImage>,
+    pub actual: RgbaImage,
+    pub diff: Option<FrameDiff>,
+}
+
+pub struct CaseReport {
+    pub name: String,
+    pub error: Option<String>,
+    pub frames: Vec<FrameReport>,
+}
+
+/// Writes `report.html` and the images it references into `dir`, returning
+/// the path of the HTML file.
+pub fn write(dir: &Path, cases: &[CaseReport]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create report directory {}", dir.display()))?;
+
+    let failed_cases = cases
+        .iter()
+        .filter(|case| {
+            case.error.is_some()
+                || case
+                    .frames
+                    .iter()
+                    .any(|frame| matches!(frame.status, FrameStatus::Failed | FrameStatus::Missing))
+        })
+        .count();
+
+    let mut html = String::new();
+    html.push_str(concat!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">",
+        "<title>Golden render report</title><style>",
+        "body{font-family:system-ui,sans-serif;margin:24px;background:#fafafa}",
+        "h2{margin-top:32px}.pass{color:#1a7f37}.fail{color:#cf222e}.skip{color:#9a6700}",
+        "table{border-collapse:collapse}td,th{padding:6px;vertical-align:top;text-align:left}",
+        "img{max-width:360px;border:1px solid #ccc;background:#fff}",
+        "</style></head><body>\n",
+    ));
+    let _ = writeln!(
+        html,
+        "<h1>Golden render report</h1><p>{} of {} cases failed</p>",
+        failed_cases,
+        cases.len()
+    );
+
+    for case in cases {
+        let _ = writeln!(html, "<h2>{}</h2>", escape(&case.name));
+
+        if let Some(error) = &case.error {
+            let _ = writeln!(html, "<p class=\"fail\">Error: {}</p>", escape(error));
+            continue;
+        }
+
+        html.push_str(
+            "<table><tr><th>Frame</th><th>Expected</th><th>Actual</th><th>Diff</th></tr>\n",
+        );
+
+        for frame in &case.frames {
+            let frame_dir = dir.join(&case.name);
+            std::fs::create_dir_all(&frame_dir)?;
+            let stem = format!("frame-{:04}", frame.frame_number);
+
+            let expected = match &frame.expected {
+                Some(image) => image_cell(image, &frame_dir, &case.name, &stem, "expected")?,
+                None => "<td>&mdash;</td>".to_string(),
+            };
+            let actual = image_cell(&frame.actual, &frame_dir, &case.name, &stem, "actual")?;
+            let diff = match &frame.diff {
+                Some(diff) => image_cell(&diff.image, &frame_dir, &case.name, &stem, "diff")?,
+                None => "<td>&mdash;</td>".to_string(),
+            };
+
+            let stats = frame
+                .diff
+                .as_ref()
+                .map(|diff| {
+                    format!(
+                        "<br>{} / {} px ({:.4}%)",
+                        diff.differing_pixels,
+                        diff.total_pixels,
+                        diff.ratio() * 100.0
+                    )
+                })
+                .unwrap_or_default();
+
+            let _ = writeln!(
+                html,
+                "<tr><td>{}<br><span class=\"{}\">{}</span>{}</td>{}{}{}</tr>",
+                frame.frame_number,
+                frame.status.class(),
+                frame.status.label(),
+                stats,
+                expected,
+                actual,
+                diff
+            );
+        }
+
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body></html>\n");
+
+    let path = dir.join("report.html");
+    std::fs::write(&path, html)
+        .with_context(|| format!("Failed to write report {}", path.display()))?;
+
+    Ok(path)
+}
+
+fn image_cell(
+    image: &RgbaImage,
+    frame_dir: &Path,
+    case: &str,
+    stem: &str,
+    kind: &str,
+) -> Result<String> {
+    let file_name = format!("{stem}-{kind}.png");
+    image
+        .save(frame_dir.join(&file_name))
+        .with_context(|| format!("Failed to write {kind} image for {case} {stem}"))?;
+
+    let src = escape(&format!("{case}/{file_name}"));
+    Ok(format!(
+        "<td><a href=\"{src}\"><img src=\"{src}\"></a></td>"
+    ))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -3,6 +3,7 @@ mod av_step;
 mod drift;
 mod encoding;
 pub mod ffprobe_ext;
+mod golden;
 pub mod kill9;
 mod performance;
 mod playback;
@@ -12,6 +13,7 @@ pub mod scenarios;
 mod sync;
 pub(crate) mod validate;
 
+pub use golden::GoldenOptions;
 pub use recording::RecordingTestRunner;
 pub use scenarios::{ScenarioRunner, classify_test_failure};
 pub use validate::validate_recording;
@@ -86,6 +88,13 @@ pub async fn run_kill9_crash_suite(
     kill9::run_suite(hardware, duration).await
 }
 
+pub async fn run_golden_suite(
+    hardware: &DiscoveredHardware,
+    options: &GoldenOptions,
+) -> Result<TestResults> {
+    golden::run_suite(hardware, options).await
+}
+
 pub async fn run_benchmark(
     hardware: &DiscoveredHardware,
     duration: u64,
@@ -1,2 +1,7 @@
 mod position;
+#[cfg(target_os = "linux")]
+mod xfixes;
+
 pub use position::*;
+#[cfg(target_os = "linux")]
+pub use xfixes::XFixesCursor;
@@ -0,0 +1,191 @@
+use cap_cursor_info::CursorShapeLinux;
+use sha2::{Digest, Sha256};
+use x11rb::{connection::Connection, protocol::xfixes::ConnectionExt as _};
+
+/// The X server's current cursor, as reported by XFixes.
+#[derive(Debug, Clone)]
+pub struct XFixesCursor {
+    pub width: u16,
+    pub height: u16,
+    pub xhot: u16,
+    pub yhot: u16,
+    /// Theme or cursor-font name, empty for cursors created from raw pixmaps.
+    pub name: String,
+    /// Premultiplied ARGB, row by row.
+    pub pixels: Vec<u32>,
+}
+
+impl XFixesCursor {
+    pub fn current() -> Option<Self> {
+        let (conn, _) = x11rb::connect(None).ok()?;
+        Self::query(&conn)
+    }
+
+    fn query(conn: &impl Connection) -> Option<Self> {
+        // Names need XFixes 2; versions below that only expose the image.
+        let version = conn.xfixes_query_version(5, 0).ok()?.reply().ok()?;
+
+        let cursor = if version.major_version >= 2 {
+            let cursor = conn.xfixes_get_cursor_image_and_name().ok()?.reply().ok()?;
+            Self {
+                width: cursor.width,
+                height: cursor.height,
+                xhot: cursor.xhot,
+                yhot: cursor.yhot,
+                name: String::from_utf8_lossy(&cursor.name).into_owned(),
+                pixels: cursor.cursor_image,
+            }
+        } else {
+            let cursor = conn.xfixes_get_cursor_image().ok()?.reply().ok()?;
+            Self {
+                width: cursor.width,
+                height: cursor.height,
+                xhot: cursor.xhot,
+                yhot: cursor.yhot,
+                name: String::new(),
+                pixels: cursor.cursor_image,
+            }
+        };
+
+        let pixel_count = usize::from(cursor.width) * usize::from(cursor.height);
+        (pixel_count > 0 && cursor.pixels.len() == pixel_count).then_some(cursor)
+    }
+
+    /// Cursors set from raw pixels (GTK, Qt and most themed applications) have
+    /// no name, so those are recognised by their image instead.
+    pub fn shape(&self) -> Option<CursorShapeLinux> {
+        CursorShapeLinux::from_name(&self.name)
+            .or_else(|| CursorShapeLinux::from_hash(&self.pixel_hash()))
+    }
+
+    /// SHA-256 of the pixels as the little-endian ARGB words Xcursor files store.
+    pub fn pixel_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for pixel in &self.pixels {
+            hasher.update(pixel.to_le_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Hotspot as a fraction of the cursor image size.
+    pub fn normalized_hotspot(&self) -> (f64, f64) {
+        (
+            f64::from(self.xhot) / f64::from(self.width),
+            f64::from(self.yhot) / f64::from(self.height),
+        )
+    }
+
+    /// RGBA bytes for encoding the cursor image.
+    pub fn rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| {
+                [
+                    ((pixel >> 16) & 0xff) as u8,
+                    ((pixel >> 8) & 0xff) as u8,
+                    (pixel & 0xff) as u8,
+                    ((pixel >> 24) & 0xff) as u8,
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use x11rb::{
+        cursor::Handle,
+        protocol::xproto::{ConnectionExt as _, CreateWindowAux, EventMask, WindowClass},
+        resource_manager::Database,
+        wrapper::ConnectionExt as _,
+    };
+
+    struct Xvfb(Child);
+
+    impl Drop for Xvfb {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    /// Starts a headless X server, returning it with its display name.
+    fn start_xvfb() -> Option<(Xvfb, String)> {
+        let mut child = Command::new("Xvfb")
+            .args([
+                "-displayfd",
+                "1",
+                "-nolisten",
+                "tcp",
+                "-screen",
+                "0",
+                "640x480x24",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let stdout = child.stdout.take()?;
+        let server = Xvfb(child);
+
+        let mut display = String::new();
+        BufReader::new(stdout).read_line(&mut display).ok()?;
+        let display = format!(":{}", display.trim());
+        Some((server, display))
+    }
+
+    #[test]
+    fn recognises_unnamed_theme_cursors_by_their_pixels() {
+        if !std::path::Path::new("/usr/share/icons/Adwaita/cursors/left_ptr").exists() {
+            eprintln!("Adwaita cursor theme not installed, skipping");
+            return;
+        }
+        let Some((_server, display)) = start_xvfb() else {
+            eprintln!("Xvfb not available, skipping");
+            return;
+        };
+
+        let (conn, screen_num) = x11rb::connect(Some(&display)).unwrap();
+        let screen = &conn.setup().roots[screen_num];
+
+        // Themed cursors are uploaded as images, so XFixes reports no name for them.
+        let database = Database::new_from_data(b"Xcursor.theme: Adwaita\nXcursor.size: 24\n");
+        let cursor = Handle::new(&conn, screen_num, &database)
+            .unwrap()
+            .reply()
+            .unwrap()
+            .load_cursor(&conn, "left_ptr")
+            .unwrap();
+
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            screen.root_depth,
+            window,
+            screen.root,
+            0,
+            0,
+            screen.width_in_pixels,
+            screen.height_in_pixels,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            screen.root_visual,
+            &CreateWindowAux::new()
+                .cursor(cursor)
+                .event_mask(EventMask::POINTER_MOTION),
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.warp_pointer(x11rb::NONE, window, 0, 0, 0, 0, 10, 10)
+            .unwrap();
+        conn.sync().unwrap();
+
+        let current = XFixesCursor::query(&conn).unwrap();
+
+        assert_eq!(current.name, "");
+        assert_eq!((current.width, current.height), (24, 24));
+        assert_eq!(current.shape(), Some(CursorShapeLinux::Default));
+    }
+}
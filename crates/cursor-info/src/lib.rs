@@ -1,10 +1,12 @@
 //! Cap Cursor Info: A crate for getting cursor information, assets and hotspot information.
 
+mod linux;
 mod macos;
 mod windows;
 
 use std::{fmt, str::FromStr};
 
+pub use linux::CursorShapeLinux;
 pub use macos::CursorShapeMacOS;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -24,6 +26,7 @@ pub struct ResolvedCursor {
 pub enum CursorShape {
     MacOS(CursorShapeMacOS),
     Windows(CursorShapeWindows),
+    Linux(CursorShapeLinux),
 }
 
 impl CursorShape {
@@ -32,6 +35,7 @@ impl CursorShape {
         match self {
             CursorShape::MacOS(cursor) => cursor.resolve(),
             CursorShape::Windows(cursor) => cursor.resolve(),
+            CursorShape::Linux(cursor) => cursor.resolve(),
         }
     }
 }
@@ -41,11 +45,13 @@ impl fmt::Display for CursorShape {
         let kind = match self {
             CursorShape::MacOS(_) => "MacOS",
             CursorShape::Windows(_) => "Windows",
+            CursorShape::Linux(_) => "Linux",
         };
 
         let variant: &'static str = match self {
             CursorShape::MacOS(cursor) => cursor.into(),
             CursorShape::Windows(cursor) => cursor.into(),
+            CursorShape::Linux(cursor) => cursor.into(),
         };
 
         write!(f, "{kind}|{variant}")
@@ -89,6 +95,13 @@ impl<'de> Deserialize<'de> for CursorShape {
                     ))
                 })?,
             )),
+            "Linux" => Ok(CursorShape::Linux(
+                CursorShapeLinux::from_str(variant).map_err(|err| {
+                    serde::de::Error::custom(
+                        format!("Failed to parse Linux cursor variant: {err}",),
+                    )
+                })?,
+            )),
             _ => Err(serde::de::Error::custom("Failed to parse CursorShape kind")),
         }
     }
@@ -0,0 +1,425 @@
+use strum::{EnumString, IntoStaticStr};
+
+use crate::{CursorShape, ResolvedCursor};
+
+/// Linux (X11) cursors, named after the CSS/freedesktop cursor spec.
+/// https://www.freedesktop.org/wiki/Specifications/cursor-spec/
+///
+/// Themes and toolkits still use the older X cursor-font names
+/// (`left_ptr`, `xterm`, `hand2`, ...) and the Xcursor bitmap hashes that
+/// stand in for them, so [`CursorShapeLinux::from_name`] accepts all three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString, IntoStaticStr)]
+pub enum CursorShapeLinux {
+    /// `default`, `left_ptr`
+    Default,
+    /// `context-menu`
+    ContextMenu,
+    /// `help`, `question_arrow`
+    Help,
+    /// `pointer`, `hand2`
+    Pointer,
+    /// `progress`, `left_ptr_watch`
+    Progress,
+    /// `wait`, `watch`
+    Wait,
+    /// `crosshair`, `cross`
+    Crosshair,
+    /// `text`, `xterm`
+    Text,
+    /// `vertical-text`
+    VerticalText,
+    /// `alias`, `dnd-link`
+    Alias,
+    /// `copy`, `dnd-copy`
+    Copy,
+    /// `move`, `fleur`
+    Move,
+    /// `all-scroll`
+    AllScroll,
+    /// `no-drop`, `dnd-no-drop`
+    NoDrop,
+    /// `not-allowed`, `crossed_circle`
+    NotAllowed,
+    /// `grab`, `openhand`
+    Grab,
+    /// `grabbing`, `closedhand`
+    Grabbing,
+    /// `col-resize`, `sb_h_double_arrow`
+    ColResize,
+    /// `row-resize`, `sb_v_double_arrow`
+    RowResize,
+    /// `n-resize`, `top_side`
+    NResize,
+    /// `e-resize`, `right_side`
+    EResize,
+    /// `s-resize`, `bottom_side`
+    SResize,
+    /// `w-resize`, `left_side`
+    WResize,
+    /// `ne-resize`, `top_right_corner`
+    NeResize,
+    /// `nw-resize`, `top_left_corner`
+    NwResize,
+    /// `se-resize`, `bottom_right_corner`
+    SeResize,
+    /// `sw-resize`, `bottom_left_corner`
+    SwResize,
+    /// `ew-resize`, `h_double_arrow`
+    EwResize,
+    /// `ns-resize`, `v_double_arrow`
+    NsResize,
+    /// `nesw-resize`, `fd_double_arrow`
+    NeswResize,
+    /// `nwse-resize`, `bd_double_arrow`
+    NwseResize,
+    /// `zoom-in`
+    ZoomIn,
+    /// `zoom-out`
+    ZoomOut,
+}
+
+impl CursorShapeLinux {
+    /// Linux themes differ too much to ship one look per theme; these reuse
+    /// the closest macOS and Windows assets.
+    pub fn resolve(&self) -> Option<ResolvedCursor> {
+        Some(match self {
+            Self::Default => ResolvedCursor {
+                raw: include_str!("../assets/mac/arrow.svg"),
+                hotspot: (0.302, 0.226),
+            },
+            Self::ContextMenu => ResolvedCursor {
+                raw: include_str!("../assets/mac/contextual_menu.svg"),
+                hotspot: (0.278, 0.295),
+            },
+            Self::Help => ResolvedCursor {
+                raw: include_str!("../assets/windows/idchelp.svg"),
+                hotspot: (0.056, 0.127),
+            },
+            Self::Pointer => ResolvedCursor {
+                raw: include_str!("../assets/mac/pointing_hand.svg"),
+                hotspot: (0.342, 0.172),
+            },
+            Self::Progress => ResolvedCursor {
+                raw: include_str!("../assets/windows/appstarting.svg"),
+                hotspot: (0.055, 0.368),
+            },
+            Self::Wait => ResolvedCursor {
+                raw: include_str!("../assets/windows/wait.svg"),
+                hotspot: (0.5, 0.52),
+            },
+            Self::Crosshair => ResolvedCursor {
+                raw: include_str!("../assets/mac/crosshair.svg"),
+                hotspot: (0.52, 0.51),
+            },
+            Self::Text => ResolvedCursor {
+                raw: include_str!("../assets/mac/ibeam.svg"),
+                hotspot: (0.484, 0.520),
+            },
+            Self::VerticalText => ResolvedCursor {
+                raw: include_str!("../assets/mac/ibeam_vertical.svg"),
+                hotspot: (0.51, 0.49),
+            },
+            Self::Alias => ResolvedCursor {
+                raw: include_str!("../assets/mac/drag_link.svg"),
+                hotspot: (0.621, 0.309),
+            },
+            Self::Copy => ResolvedCursor {
+                raw: include_str!("../assets/mac/drag_copy.svg"),
+                hotspot: (0.255, 0.1),
+            },
+            Self::Move | Self::AllScroll => ResolvedCursor {
+                raw: include_str!("../assets/windows/sizeall.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::NoDrop | Self::NotAllowed => ResolvedCursor {
+                raw: include_str!("../assets/mac/operation_not_allowed.svg"),
+                hotspot: (0.24, 0.1),
+            },
+            Self::Grab => ResolvedCursor {
+                raw: include_str!("../assets/mac/open_hand.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::Grabbing => ResolvedCursor {
+                raw: include_str!("../assets/mac/closed_hand.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::ColResize | Self::EwResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_left_right.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::RowResize | Self::NsResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_up_down.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::NResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_up.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::EResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_right.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::SResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_down.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::WResize => ResolvedCursor {
+                raw: include_str!("../assets/mac/resize_left.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::NeResize | Self::SwResize | Self::NeswResize => ResolvedCursor {
+                raw: include_str!("../assets/windows/size-nesw.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::NwResize | Self::SeResize | Self::NwseResize => ResolvedCursor {
+                raw: include_str!("../assets/windows/idcsizenwse.svg"),
+                hotspot: (0.5, 0.5),
+            },
+            Self::ZoomIn => ResolvedCursor {
+                raw: include_str!("../assets/mac/tahoe/zoom-in.svg"),
+                hotspot: (0.549, 0.550),
+            },
+            Self::ZoomOut => ResolvedCursor {
+                raw: include_str!("../assets/mac/tahoe/zoom-out.svg"),
+                hotspot: (0.551, 0.552),
+            },
+        })
+    }
+
+    /// Derive the cursor type from the name XFixes reports for the current
+    /// cursor (`XFixesGetCursorImageAndName`).
+    ///
+    /// Besides spec and cursor-font names this matches the Xcursor hashes of
+    /// the classic core bitmaps, which themes install as aliases and which
+    /// Qt and older toolkits request instead of a name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "default" | "left_ptr" | "arrow" | "top_left_arrow" | "left-arrow" => Self::Default,
+            "context-menu" => Self::ContextMenu,
+            "help"
+            | "question_arrow"
+            | "whats_this"
+            | "left_ptr_help"
+            | "5c6cd98b3f3ebcb1f9c7f1c204630408"
+            | "d9ce0ab605698f320427677b458ad60b" => Self::Help,
+            "pointer"
+            | "hand2"
+            | "hand"
+            | "pointing_hand"
+            | "e29285e634086352946a0e7090d73106"
+            | "9d800788f1b08800ae810202380a0822" => Self::Pointer,
+            "progress"
+            | "left_ptr_watch"
+            | "half-busy"
+            | "3ecb610c1bf2410f44200f48c40d3599"
+            | "08e8e1c95fe2fc01f976f1e063a24ccd"
+            | "00000000000000020006000e7e9ffc3f" => Self::Progress,
+            "wait" | "watch" => Self::Wait,
+            "crosshair" | "cross" | "tcross" | "cross_reverse" | "diamond_cross" => Self::Crosshair,
+            "text" | "xterm" | "ibeam" => Self::Text,
+            "vertical-text" => Self::VerticalText,
+            "alias"
+            | "link"
+            | "dnd-link"
+            | "640fb0e74195791501fd1ed57b41487f"
+            | "3085a0e285430894940527032f8b26df"
+            | "a2a266d0498c3104214a47bd64ab0fc8" => Self::Alias,
+            "copy"
+            | "dnd-copy"
+            | "1081e37283d90000800003c07f3ef6bf"
+            | "6407b0e94181790501fd1e167b474872"
+            | "b66166c04f8c3109214a4fbd64a50fc8" => Self::Copy,
+            "move"
+            | "fleur"
+            | "dnd-move"
+            | "4498f0e0c1937ffe01fd06f973665830"
+            | "9081237383d90e509aa00f00170e968f" => Self::Move,
+            "all-scroll" | "size_all" => Self::AllScroll,
+            "no-drop" | "dnd-no-drop" | "dnd-none" => Self::NoDrop,
+            "not-allowed"
+            | "crossed_circle"
+            | "circle"
+            | "forbidden"
+            | "03b6e0fcb3499374a867c041f52298f0" => Self::NotAllowed,
+            "grab" | "openhand" | "hand1" => Self::Grab,
+            "grabbing" | "closedhand" => Self::Grabbing,
+            "col-resize" | "sb_h_double_arrow" | "split_h" | "14fef782d02440884392942c11205230" => {
+                Self::ColResize
+            }
+            "row-resize" | "sb_v_double_arrow" | "split_v" | "2870a09082c103050810ffdffffe0204" => {
+                Self::RowResize
+            }
+            "n-resize" | "top_side" => Self::NResize,
+            "e-resize" | "right_side" => Self::EResize,
+            "s-resize" | "bottom_side" => Self::SResize,
+            "w-resize" | "left_side" => Self::WResize,
+            "ne-resize" | "top_right_corner" => Self::NeResize,
+            "nw-resize" | "top_left_corner" => Self::NwResize,
+            "se-resize" | "bottom_right_corner" => Self::SeResize,
+            "sw-resize" | "bottom_left_corner" => Self::SwResize,
+            "ew-resize" | "h_double_arrow" | "size_hor" | "028006030e0e7ebffc7f7070c0600140" => {
+                Self::EwResize
+            }
+            "ns-resize" | "v_double_arrow" | "size_ver" | "00008160000006810000408080010102" => {
+                Self::NsResize
+            }
+            "nesw-resize"
+            | "fd_double_arrow"
+            | "size_bdiag"
+            | "fcf1c3c7cd4491d801f1e1c78f100000" => Self::NeswResize,
+            "nwse-resize"
+            | "bd_double_arrow"
+            | "size_fdiag"
+            | "c7088f0f3e6c8088236ef8e1e3e70000" => Self::NwseResize,
+            "zoom-in" | "zoom_in" => Self::ZoomIn,
+            "zoom-out" | "zoom_out" => Self::ZoomOut,
+            _ => return None,
+        })
+    }
+
+    /// Derive the cursor type from a hash of its pixels, for cursors that XFixes
+    /// reports without a name because the toolkit uploaded the image itself.
+    ///
+    /// Matches the static Adwaita cursors at their 1x and 2x sizes.
+    pub fn from_hash(hash: &str) -> Option<Self> {
+        Some(match hash {
+            "1df8ef9c389332e360d919b7be014a118384052ee8150f143f8cd8966eebde1c"
+            | "7313ed9f761f7cda5d469d2c77dbc5d964e2d4918d86355c0c1dab87fcffe1a8" => Self::Default,
+            "eb14846eaf406e68b7284ad1e6f8a503ffd095b1a209558df5b1b4ead81deec4"
+            | "62054fd470e41f8e49d120925b87256e22167e4460d3abfc1fa343394b03e13f" => {
+                Self::ContextMenu
+            }
+            "4032d999387f2b085f76a29d285e88539bbb0567e51c747edd91e447527c6e6b"
+            | "d1ff220f650a2fb28aa185fbe7650564e2b22ca091832c5dc64e1291c8d08d07" => Self::Help,
+            "b0cbb6c9cd8b76caa86033d16baf2c8ed404c6fec1856ce20fdc76b7d2f038b9"
+            | "cb4ca43342a045c8666b0e4927709ec92aedfce4519345dc5469c88c5d6fcc97" => Self::Pointer,
+            "457472bb7683a717bbf7fad45168664d96e57d5a439eb8ccad0ad1207132acda"
+            | "3512ea02d133d6eb037fc951019fa184f5647daa89744ff8d0acf312baebfdd7" => Self::Crosshair,
+            "772f09a7d21e993368819b4eda860f7e0a119981a09574a31cbea4cd1004d816"
+            | "461c7d244fca20116971dc9482115ccce9e40ed09f3aa985acf9c236ee7360f1" => Self::Text,
+            "046937e9dc290f1883fcc2641ed779fb4122f94e84ff6e7187771e2898e2c956"
+            | "a5fa51bb8ce6e85402ef080bc6631dab450fa8371ed13c5180a8a2f2329a4b49" => {
+                Self::VerticalText
+            }
+            "adc8fad66d65f71ad86890333d45ab86ca5bc3b10e22861489f520df5c2bfc43"
+            | "520a728c848a0d870122de9343658ffeeae3ff3a98d709935896deb6427ec452" => Self::Alias,
+            "4524a0a7f0bc06fe7c4c2d6e5aa4d8b6ab4a3e17fe54ad8b4454ea41fb7034cc"
+            | "f5c4c076dbcf95d2c5dee4dfe1561b5839b5bd0c09a784fe069896114e6e5729" => Self::Copy,
+            "76aae28ae5ff968c04e2e8351108cb996d88981e2dbc90e081ed63ece6b95415"
+            | "715a8a01c7a1c1aee9cc4795d47580ead3512ad77c9cb7c47db40a0d8a0b7f27" => Self::Move,
+            "be8d61e31ce78988a485e44be2a6972370e80f8d18d0cea4f03d34f032f43719"
+            | "7ba5b53785f4cdb2a6e55a2d63b3168fae94fcd952f131a5d60d8438a77dd3ae" => Self::AllScroll,
+            "70e83844084b98fb91a4b49caa37849e1eb7e396d845b4f69f6f5851a4f22ee4"
+            | "cef40221f95683a7cb794d7082bc900c7a1ed0106431725aa9aa72db752fef5c" => Self::NoDrop,
+            "48601c74c64451a28d9adc7358ec5394bd8233e1758b6a2b626d82b35b09886c"
+            | "a4048784e24cc126f07f9539a7a22af7d38fcde16cfc37cc69df87018c951041" => {
+                Self::NotAllowed
+            }
+            "d792e88126e1947bc829cdcde69c6e81b2e7515301a3860974bc5e3ce76e4bf6"
+            | "4be84d225eb8db0f0f362d24bdeedf18b1e87ff44eced114c977eaab3677fa8e" => Self::Grab,
+            "c1e5cd4ca86ce613f6f80384a83fe357693fe89d5f6246a27029953de3b36990"
+            | "6f127278620bfcbb08652f3c5021f6c854580138d702348b9f99b9030e3fb39c" => Self::Grabbing,
+            "85f8266a4f6b170a9165e93717dfb5c4eb265872f659b4272c51d41d9e787abd"
+            | "e22741aaddaf7d84bad12312cb632040d759beb6538cd3e4ad22d7a057c330c7" => Self::NResize,
+            "3d52679a7f5aa472b4a4b3476f4c905739345e5d23966060c2a4bd918125732b"
+            | "5c27955ab119df5aa9a0956727016fafac0646fa04d98ef1a82732ba86b80c63" => Self::EResize,
+            "059b17a0f98ab7b1064a6c7fe2bc79235131cafb5f62e7f880e8caff3878a603"
+            | "0a2c10e2f7b82508fa2c8435371102c9c6491271a80de99517ab81e687d31f3c" => Self::SResize,
+            "bb0b63bff3e68b1fc41f963e96c1b26b896a1d22f08a2b740054c91d36e766c0"
+            | "42626b7b74c6616007ddb1597e1ffdd612d5684f8082a80cce5bf2b66a9be193" => Self::WResize,
+            "34195ffbf636ca4933dfbce2f2c38c09f1e69d71fe7178a944e8dc938b2241f5"
+            | "71ed853916774d02e89d43f911761b4210bfd804032156ddbd1b199b63f618ed" => Self::NeResize,
+            "165022e74f1f66efa896caa5b58b5ca0bf91382995aa571477d129ee96f63d21"
+            | "cc2256be40d91b5f660b343c0b2af403f9e5123d1be825e7a6806d44b36a38cd" => Self::NwResize,
+            "0976663fa577dc7e5653a4a2d79969216bac6322683e63bb992c2399071afaf1"
+            | "5e48aabf5ebb5c5df386aa0a63af3843a1b60455d1f1ca654a6ec198164db7c5" => Self::SeResize,
+            "b738a52b9f1b08ccfb75960a76804d93fffa85c8cccde1686671d49a34f406b1"
+            | "5f044405eb1bfc09024ec57b2d56474eff3c0675130e31707114f304041e2802" => Self::SwResize,
+            "28632c65ed3c1d59250ee5390ea9fa5d2ed6b8816cf1d5a1d04ddcff857b167d"
+            | "efe542a1cd4f158851af009197dfcc5a982d637661b7c19f0426717eb18fa1dd" => Self::EwResize,
+            "f63502c43705986f4bd3982e51be5c64994a15a900174665643d8b911254d635"
+            | "5b1382cba3acbbf2c71683ed31d28f03d27c28f37ffd20cf4e9de36395344d6a" => Self::NsResize,
+            "ce11bf176896a1619ee04fb7126f0c21ed6221b3b3c949d774dd7547b20e1f2f"
+            | "a8155d8bfd52a81706d44bc61c3f697edb031a949d355f578f9698e0b0ee53ca" => {
+                Self::NeswResize
+            }
+            "aa9db9045b90fbd7625eea1a63b0c0b40b0656d6426dc784f7e61605aa042bf6"
+            | "c6dfc2483d3cc30b61898c5b547078a7b8bdff8701e9220e15018ce2a0b6546d" => {
+                Self::NwseResize
+            }
+            "428e2114ef407fc097dd5f0b6eaa281a35780fc387796287167f7e0f96ab1652"
+            | "4ab7f98f7bd3b90bdc2d76800f0fa9ec2c4add8de881d97c267935c261081eb6" => Self::ZoomIn,
+            "3f04896fa796832f658d2f16aabb3c72750900fd4c9cecc1ed0a34168f48705c"
+            | "47defb02e2d6f5124efae140c78dacfcb89bd45b172a871ec05484dedc75d5a0" => Self::ZoomOut,
+            _ => return None,
+        })
+    }
+}
+
+impl From<CursorShapeLinux> for CursorShape {
+    fn from(value: CursorShapeLinux) -> Self {
+        CursorShape::Linux(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_spec_font_and_hash_names() {
+        assert_eq!(
+            CursorShapeLinux::from_name("left_ptr"),
+            Some(CursorShapeLinux::Default)
+        );
+        assert_eq!(
+            CursorShapeLinux::from_name("xterm"),
+            Some(CursorShapeLinux::Text)
+        );
+        assert_eq!(
+            CursorShapeLinux::from_name("hand2"),
+            CursorShapeLinux::from_name("e29285e634086352946a0e7090d73106")
+        );
+        assert_eq!(
+            CursorShapeLinux::from_name("SB_H_DOUBLE_ARROW"),
+            Some(CursorShapeLinux::ColResize)
+        );
+        assert_eq!(CursorShapeLinux::from_name("not-a-cursor"), None);
+    }
+
+    #[test]
+    fn resolves_adwaita_pixel_hashes() {
+        assert_eq!(
+            CursorShapeLinux::from_hash(
+                "1df8ef9c389332e360d919b7be014a118384052ee8150f143f8cd8966eebde1c"
+            ),
+            Some(CursorShapeLinux::Default)
+        );
+        assert_eq!(
+            CursorShapeLinux::from_hash(
+                "461c7d244fca20116971dc9482115ccce9e40ed09f3aa985acf9c236ee7360f1"
+            ),
+            Some(CursorShapeLinux::Text)
+        );
+        assert_eq!(CursorShapeLinux::from_hash("left_ptr"), None);
+    }
+
+    #[test]
+    fn every_shape_has_an_asset() {
+        use std::str::FromStr;
+
+        for name in ["Default", "Text", "Pointer", "NwseResize", "ZoomOut"] {
+            let shape = CursorShapeLinux::from_str(name).unwrap();
+            assert!(shape.resolve().is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn round_trips_through_cursor_shape() {
+        let shape = CursorShape::from(CursorShapeLinux::NsResize);
+        let json = serde_json::to_string(&shape).unwrap();
+
+        assert_eq!(json, "\"Linux|NsResize\"");
+        assert_eq!(serde_json::from_str::<CursorShape>(&json).unwrap(), shape);
+    }
+}
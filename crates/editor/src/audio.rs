@@ -41,6 +41,8 @@ pub struct AudioRenderer {
     transition_incoming: Vec<f32>,
     speed_audio_processors: [Option<SpeedAudioProcessorSlot>; 2],
     speed_audio_use_counter: u64,
+    // linear gain applied to the final mix, e.g. for loudness normalisation
+    output_gain: f32,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -60,8 +62,8 @@ pub struct AudioSegment {
 #[derive(Clone)]
 pub struct AudioSegmentTrack {
     data: Arc<AudioData>,
-    get_gain: fn(&AudioConfiguration) -> f32,
-    get_stereo_mode: fn(&AudioConfiguration) -> StereoMode,
+    get_gain: Arc<dyn Fn(&AudioConfiguration) -> f32 + Send + Sync>,
+    get_stereo_mode: Arc<dyn Fn(&AudioConfiguration) -> StereoMode + Send + Sync>,
     get_offset: fn(&ClipOffsets) -> f32,
     timing_offset_secs: f32,
 }
@@ -69,14 +71,14 @@ pub struct AudioSegmentTrack {
 impl AudioSegmentTrack {
     pub fn new(
         data: Arc<AudioData>,
-        get_gain: fn(&AudioConfiguration) -> f32,
-        get_stereo_mode: fn(&AudioConfiguration) -> StereoMode,
+        get_gain: impl Fn(&AudioConfiguration) -> f32 + Send + Sync + 'static,
+        get_stereo_mode: impl Fn(&AudioConfiguration) -> StereoMode + Send + Sync + 'static,
         get_offset: fn(&ClipOffsets) -> f32,
     ) -> Self {
         Self {
             data,
-            get_gain,
-            get_stereo_mode,
+            get_gain: Arc::new(get_gain),
+            get_stereo_mode: Arc::new(get_stereo_mode),
             get_offset,
             timing_offset_secs: 0.0,
         }
@@ -120,6 +122,8 @@ struct SpeedAudioProcessorKey {
     segment_start_samples: usize,
     segment_end_samples: usize,
     mic_volume_bits: u32,
+    mic_mute: bool,
+    additional_mics: u64,
     system_volume_bits: u32,
     mic_stereo_mode: u8,
     mic_offset_bits: u32,
@@ -173,6 +177,7 @@ impl AudioRenderer {
             transition_incoming: Vec::new(),
             speed_audio_processors: [None, None],
             speed_audio_use_counter: 0,
+            output_gain: 1.0,
         }
     }
 
@@ -181,6 +186,12 @@ impl AudioRenderer {
         self
     }
 
+    /// Scales the rendered mix by `gain` (linear), clipping at full scale.
+    pub fn with_output_gain(mut self, gain: f32) -> Self {
+        self.output_gain = gain;
+        self
+    }
+
     pub fn set_playhead(&mut self, playhead: f64, project: &ProjectConfiguration) {
         self.elapsed_samples = self.playhead_to_samples(playhead);
         self.speed_audio_processors = [None, None];
@@ -228,6 +239,22 @@ impl AudioRenderer {
         &mut self,
         samples: usize,
         project: &ProjectConfiguration,
+    ) -> Option<(usize, Vec<f32>)> {
+        let mut frame = self.render_mix_raw(samples, project)?;
+
+        if self.output_gain != 1.0 {
+            for sample in &mut frame.1 {
+                *sample = (*sample * self.output_gain).clamp(-1.0, 1.0);
+            }
+        }
+
+        Some(frame)
+    }
+
+    fn render_mix_raw(
+        &mut self,
+        samples: usize,
+        project: &ProjectConfiguration,
     ) -> Option<(usize, Vec<f32>)> {
         if let Some(timeline) = &project.timeline {
             // Capture the output-time playhead before the recording mix advances
@@ -544,6 +571,8 @@ impl AudioRenderer {
             segment_start_samples: self.playhead_to_samples(source.segment.start),
             segment_end_samples: self.playhead_to_samples(source.segment.end),
             mic_volume_bits: project.audio.mic_volume_db.to_bits(),
+            mic_mute: project.audio.mic_mute,
+            additional_mics: additional_mics_key(&project.audio),
             system_volume_bits: project.audio.system_volume_db.to_bits(),
             mic_stereo_mode: project_stereo_mode_key(&project.audio.mic_stereo_mode),
             mic_offset_bits: offsets.mic.to_bits(),
@@ -707,6 +736,19 @@ fn project_stereo_mode_key(mode: &cap_project::StereoMode) -> u8 {
     }
 }
 
+fn additional_mics_key(audio: &AudioConfiguration) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for mic in &audio.additional_mics {
+        mic.source_id.hash(&mut hasher);
+        mic.volume_db.to_bits().hash(&mut hasher);
+        project_stereo_mode_key(&mic.stereo_mode).hash(&mut hasher);
+        mic.mute.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 fn speed_audio_processor_state(
     key: SpeedAudioProcessorKey,
     requested_source_sample: f64,
@@ -2599,6 +2641,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn output_gain_scales_the_final_mix() {
+        let _ = ffmpeg::init();
+        let dir = tempfile::tempdir().unwrap();
+        let music_path = dir.path().join("music.wav");
+        write_step_wav(&music_path, &[8000, 8000, 8000]);
+
+        let mut music = MusicTracks::new();
+        music.insert(
+            "music.wav".to_string(),
+            Arc::new(AudioData::from_file(&music_path).unwrap()),
+        );
+
+        let project = music_project(vec![music_track_segment("music.wav", 0.0, 3.0, 0.0, 0.0)]);
+        let mut renderer = AudioRenderer::new(vec![])
+            .with_music(music)
+            .with_output_gain(0.5);
+        let stream = render_export_audio(&mut renderer, &project, 30, 3 * 30);
+
+        assert!((left_at_second(&stream, 1) - expected(4000)).abs() < 0.02);
+    }
+
     #[test]
     fn speed_audio_mode_does_not_retime_timeline_music() {
         let _ = ffmpeg::init();
@@ -3,8 +3,10 @@ use crate::playback::{self, PlaybackHandle, PlaybackStartError};
 use cap_audio::AudioData;
 use cap_project::StudioRecordingMeta;
 use cap_project::{
-    CursorEvents, ProjectConfiguration, RecordingMeta, RecordingMetaInner, TimelineConfiguration,
+    CursorEvents, Cursors, KEYBOARD_EVENTS_FILE_NAME, LEGACY_KEYBOARD_EVENTS_FILE_NAME,
+    ProjectConfiguration, RecordingMeta, RecordingMetaInner, TimelineConfiguration,
     TimelineFrameMapping, TimelineSegment, XY,
+    encryption::{self, DecryptedProject, MasterKey, ProjectKey},
 };
 use cap_rendering::{
     ProjectRecordingsMeta, ProjectUniforms, RecordingSegmentDecoders, RenderVideoConstants,
@@ -24,6 +26,95 @@ use tracing::warn;
 const PREVIEW_RENDER_MAX_ATTEMPTS: u32 = 3;
 const PREVIEW_RENDER_RETRY_DELAY_MS: u64 = 120;
 
+/// Decrypts the files of an encrypted project the editor reads: the ones at its
+/// root, and the media, cursor, keyboard and music files its metadata and
+/// config refer to.
+fn open_decrypted(source_path: &Path, master_key: &MasterKey) -> Result<DecryptedProject, String> {
+    let key = ProjectKey::open(source_path, master_key).map_err(|e| e.to_string())?;
+    let meta = key
+        .read(&source_path.join("recording-meta.json"))
+        .map_err(|e| format!("Failed to read recording meta: {e}"))?;
+    let meta = RecordingMeta::from_json_for_project(source_path, &String::from_utf8_lossy(&meta))
+        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let config = key
+        .read(&source_path.join("project-config.json"))
+        .ok()
+        .and_then(|json| ProjectConfiguration::from_json(&String::from_utf8_lossy(&json)).ok());
+
+    let files = editor_files(&meta, config.as_ref());
+    DecryptedProject::open_only(source_path, master_key, |relative| {
+        relative.components().count() == 1 || files.iter().any(|file| relative.starts_with(file))
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Paths, relative to the project, of the files below its root the editor reads.
+fn editor_files(meta: &RecordingMeta, config: Option<&ProjectConfiguration>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if let RecordingMetaInner::Studio(studio) = &meta.inner {
+        match studio.as_ref() {
+            StudioRecordingMeta::SingleSegment { segment } => {
+                files.push(segment.display.path.to_path(""));
+                files.extend(segment.camera.iter().map(|camera| camera.path.to_path("")));
+                files.extend(segment.audio.iter().map(|audio| audio.path.to_path("")));
+                files.extend(segment.cursor.iter().map(|cursor| cursor.to_path("")));
+            }
+            StudioRecordingMeta::MultipleSegments { inner } => {
+                for segment in &inner.segments {
+                    let display = segment.display.path.to_path("");
+                    // Keyboard presses recorded before the meta named their
+                    // file sit next to the display recording.
+                    if let Some(dir) = display.parent() {
+                        files.push(dir.join(KEYBOARD_EVENTS_FILE_NAME));
+                        files.push(dir.join(LEGACY_KEYBOARD_EVENTS_FILE_NAME));
+                    }
+                    files.push(display);
+                    files.extend(segment.camera.iter().map(|camera| camera.path.to_path("")));
+                    files.extend(segment.mic.iter().map(|mic| mic.path.to_path("")));
+                    files.extend(
+                        segment
+                            .system_audio
+                            .iter()
+                            .map(|audio| audio.path.to_path("")),
+                    );
+                    files.extend(
+                        segment
+                            .additional_mics
+                            .iter()
+                            .map(|mic| mic.audio.path.to_path("")),
+                    );
+                    files.extend(
+                        segment
+                            .additional_videos
+                            .iter()
+                            .map(|video| video.video.path.to_path("")),
+                    );
+                    files.extend(segment.cursor.iter().map(|cursor| cursor.to_path("")));
+                    files.extend(segment.keyboard.iter().map(|keyboard| keyboard.to_path("")));
+                }
+                match &inner.cursors {
+                    Cursors::Correct(cursors) => {
+                        files.extend(cursors.values().map(|cursor| cursor.image_path.to_path("")))
+                    }
+                    Cursors::Old(cursors) => files.extend(cursors.values().map(PathBuf::from)),
+                }
+            }
+        }
+    }
+
+    files.extend(
+        config
+            .and_then(|config| config.timeline.as_ref())
+            .into_iter()
+            .flat_map(|timeline| &timeline.audio_segments)
+            .map(|segment| PathBuf::from(&segment.path))
+            .filter(|path| path.is_relative()),
+    );
+
+    files
+}
+
 fn get_video_duration_fallback(path: &Path) -> Option<f64> {
     tracing::debug!("get_video_duration_fallback called for: {:?}", path);
     let input = match ffmpeg::format::input(path) {
@@ -86,7 +177,11 @@ fn display_video_duration(path: &Path) -> Option<f64> {
 }
 
 pub struct EditorInstance {
+    /// Where the editor reads and writes the project's files: the project
+    /// itself, or a decrypted working copy of it when it is encrypted.
     pub project_path: PathBuf,
+    source_path: PathBuf,
+    decrypted: Option<std::sync::Mutex<DecryptedProject>>,
     pub recordings: Arc<ProjectRecordingsMeta>,
     pub renderer: Arc<editor::RendererHandle>,
     pub render_constants: Arc<RenderVideoConstants>,
@@ -115,8 +210,22 @@ impl EditorInstance {
         frame_cb: editor::EditorFrameCallback,
         shared_device: Option<SharedWgpuDevice>,
     ) -> Result<Arc<Self>, String> {
-        Self::new_with_audio_output(
+        Self::new_with_key(project_path, None, on_state_change, frame_cb, shared_device).await
+    }
+
+    /// Like [`EditorInstance::new`] but able to open encrypted projects,
+    /// which are edited through a decrypted working copy unlocked with
+    /// `master_key`. Plain projects are opened in place as usual.
+    pub async fn new_with_key(
+        project_path: PathBuf,
+        master_key: Option<&MasterKey>,
+        on_state_change: impl Fn(&EditorState) + Send + Sync + 'static,
+        frame_cb: editor::EditorFrameCallback,
+        shared_device: Option<SharedWgpuDevice>,
+    ) -> Result<Arc<Self>, String> {
+        Self::open(
             project_path,
+            master_key,
             on_state_change,
             frame_cb,
             shared_device,
@@ -134,11 +243,51 @@ impl EditorInstance {
         frame_cb: editor::EditorFrameCallback,
         shared_device: Option<SharedWgpuDevice>,
         audio_output: Arc<crate::AudioOutput>,
+    ) -> Result<Arc<Self>, String> {
+        Self::open(
+            project_path,
+            None,
+            on_state_change,
+            frame_cb,
+            shared_device,
+            audio_output,
+        )
+        .await
+    }
+
+    async fn open(
+        project_path: PathBuf,
+        master_key: Option<&MasterKey>,
+        on_state_change: impl Fn(&EditorState) + Send + Sync + 'static,
+        frame_cb: editor::EditorFrameCallback,
+        shared_device: Option<SharedWgpuDevice>,
+        audio_output: Arc<crate::AudioOutput>,
     ) -> Result<Arc<Self>, String> {
         if !project_path.exists() {
             return Err(format!("Video path {} not found!", project_path.display()));
         }
 
+        let source_path = project_path;
+        let decrypted = if encryption::is_encrypted(&source_path) {
+            let master_key = master_key.cloned().ok_or_else(|| {
+                format!(
+                    "{} is encrypted and no recording encryption key is available",
+                    source_path.display()
+                )
+            })?;
+            let path = source_path.clone();
+            let decrypted = tokio::task::spawn_blocking(move || open_decrypted(&path, &master_key))
+                .await
+                .map_err(|e| format!("Project decryption task failed: {e}"))?
+                .map_err(|e| format!("Failed to decrypt {}: {e}", source_path.display()))?;
+            Some(decrypted)
+        } else {
+            None
+        };
+        let project_path = decrypted
+            .as_ref()
+            .map_or_else(|| source_path.clone(), |d| d.path().to_path_buf());
+
         let recording_meta = cap_project::RecordingMeta::load_for_project(&project_path)
             .map_err(|e| format!("Failed to load recording meta: {e}"))?;
 
@@ -348,6 +497,8 @@ impl EditorInstance {
 
         let this = Arc::new(Self {
             project_path,
+            source_path,
+            decrypted: decrypted.map(std::sync::Mutex::new),
             recordings,
             renderer,
             render_constants,
@@ -380,6 +531,26 @@ impl EditorInstance {
         &self.meta
     }
 
+    /// The project as it is stored on disk. This differs from
+    /// [`EditorInstance::project_path`] only for encrypted projects.
+    pub fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+
+    /// Seals files changed in the decrypted working copy back into an
+    /// encrypted project. Plain projects are edited in place, so there is
+    /// nothing to do for them.
+    pub fn persist(&self) -> Result<(), String> {
+        let Some(decrypted) = &self.decrypted else {
+            return Ok(());
+        };
+        decrypted
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .persist()
+            .map_err(|e| format!("Failed to save {}: {e}", self.source_path.display()))
+    }
+
     pub async fn dispose(&self) {
         let mut state = self.state.lock().await;
 
@@ -405,6 +576,10 @@ impl EditorInstance {
         tokio::task::yield_now().await;
 
         drop(state);
+
+        if let Err(e) = self.persist() {
+            warn!("{e}");
+        }
     }
 
     pub async fn modify_and_emit_state(&self, modify: impl Fn(&mut EditorState)) {
@@ -844,6 +1019,10 @@ impl EditorInstance {
 
 impl Drop for EditorInstance {
     fn drop(&mut self) {
+        if let Err(e) = self.persist() {
+            warn!("{e}");
+        }
+
         let renderer = self.renderer.clone();
         let state = self.state.clone();
         let handle = self.runtime_handle.clone();
@@ -879,12 +1058,23 @@ pub struct EditorState {
 pub struct SegmentMedia {
     pub audio: AudioLoader,
     pub system_audio: AudioLoader,
+    pub additional_mics: Vec<AdditionalMicMedia>,
     pub audio_timing_repair: SegmentAudioTimingRepair,
     pub cursor: Arc<CursorEvents>,
     pub keyboard: Arc<cap_project::KeyboardEvents>,
     pub decoders: RecordingSegmentDecoders,
 }
 
+/// A microphone recorded alongside a segment's main mic.
+pub struct AdditionalMicMedia {
+    /// Matches [`cap_project::AdditionalMicConfiguration::source_id`].
+    pub id: String,
+    pub audio: AudioLoader,
+    /// Added to the clip's mic offset: the start difference from the main mic
+    /// plus any timing repair of this track.
+    pub offset_secs: f32,
+}
+
 /// Shared handle to an audio track that decodes in the background.
 ///
 /// Editor startup doesn't block on decoding entire audio files into memory;
@@ -1145,6 +1335,7 @@ pub async fn create_segments(
             Ok(vec![SegmentMedia {
                 audio,
                 system_audio: AudioLoader::none(),
+                additional_mics: Vec::new(),
                 audio_timing_repair: SegmentAudioTimingRepair {
                     mic_offset_secs: legacy_timing_repair.offset(
                         0,
@@ -1185,6 +1376,20 @@ pub async fn create_segments(
                     })
                     .unwrap_or_else(AudioLoader::none);
 
+                let additional_mics = s
+                    .additional_mics
+                    .iter()
+                    .map(|mic| AdditionalMicMedia {
+                        id: mic.id.clone(),
+                        audio: AudioLoader::spawn(
+                            recording_meta.path(&mic.audio.path),
+                            format!("MultipleSegments {i} Audio {}", mic.id),
+                        ),
+                        offset_secs: s.additional_mic_offset(mic)
+                            + audio_timing_repair_offset(mic.audio.gap_summary.as_ref()),
+                    })
+                    .collect();
+
                 let cursor = Arc::new(s.cursor_events(recording_meta));
 
                 let decoders = RecordingSegmentDecoders::new(
@@ -1205,6 +1410,7 @@ pub async fn create_segments(
                 Ok::<SegmentMedia, String>(SegmentMedia {
                     audio,
                     system_audio,
+                    additional_mics,
                     audio_timing_repair: SegmentAudioTimingRepair {
                         mic_offset_secs: legacy_timing_repair.offset(
                             i,
@@ -6,7 +6,7 @@ mod playback;
 mod segments;
 mod telemetry;
 
-pub use audio::{AudioRenderer, MusicTracks};
+pub use audio::{AudioRenderer, AudioSegment, MusicTracks};
 pub use audio_output::{
     AudioOutput, HEADLESS_BLOCK_FRAMES, HEADLESS_CHANNELS, HEADLESS_SAMPLE_RATE, HeadlessAudioTap,
 };
@@ -16,10 +16,13 @@ pub use editor::{
     finish_renderer_layers_creation, start_renderer_layers_creation,
 };
 pub use editor_instance::{
-    AudioLoader, EditorInstance, EditorState, SegmentMedia, create_segments,
+    AdditionalMicMedia, AudioLoader, EditorInstance, EditorState, SegmentMedia, create_segments,
 };
 pub use playback::{Playback, PlaybackEvent, PlaybackHandle, PlaybackStartError};
-pub use segments::{get_audio_segments, load_music_tracks, load_music_tracks_uncached};
+pub use segments::{
+    get_audio_segments, get_audio_segments_for_sources, load_music_tracks,
+    load_music_tracks_uncached,
+};
 pub use telemetry::{
     PlaybackFrameSource, PlaybackRenderOutputFormat, PlaybackSkipReason, PlaybackTelemetry,
     PlaybackTelemetryEvent,
@@ -663,11 +663,13 @@ impl Playback {
                     let click_spring = project.cursor.click_spring_config();
                     self.segment_medias
                         .iter()
-                        .map(|seg| {
+                        .enumerate()
+                        .map(|(clip, seg)| {
                             Arc::new(PrecomputedCursorTimeline::new(
                                 &seg.cursor,
                                 cursor_smoothing,
                                 Some(click_spring),
+                                &project.cursor.clip_edits(clip as u32),
                             ))
                         })
                         .collect()
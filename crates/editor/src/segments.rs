@@ -74,6 +74,14 @@ pub fn load_music_tracks_uncached(
     load_music_tracks(project, project_path, &mut cache)
 }
 
+fn stereo_mode(mode: &cap_project::StereoMode) -> cap_audio::StereoMode {
+    match mode {
+        cap_project::StereoMode::Stereo => cap_audio::StereoMode::Stereo,
+        cap_project::StereoMode::MonoL => cap_audio::StereoMode::MonoL,
+        cap_project::StereoMode::MonoR => cap_audio::StereoMode::MonoR,
+    }
+}
+
 /// Waits for a segment track's background decode, degrading a failed track to
 /// "no audio" (with a warning) so playback never hard-fails on a corrupt file.
 /// Export validates loaders strictly before reaching this point.
@@ -91,41 +99,89 @@ async fn loaded_track(
 }
 
 pub async fn get_audio_segments(segments: &[SegmentMedia]) -> Vec<AudioSegment> {
+    get_audio_segments_for_sources(segments, true, true).await
+}
+
+/// Like [`get_audio_segments`], leaving out the mic or system audio tracks,
+/// e.g. to render each source on its own.
+pub async fn get_audio_segments_for_sources(
+    segments: &[SegmentMedia],
+    include_mic: bool,
+    include_system_audio: bool,
+) -> Vec<AudioSegment> {
     let mut out = Vec::with_capacity(segments.len());
 
     for s in segments {
-        let audio = loaded_track(&s.audio, "mic audio").await;
-        let system_audio = loaded_track(&s.system_audio, "system audio").await;
+        let audio = if include_mic {
+            loaded_track(&s.audio, "mic audio").await
+        } else {
+            None
+        };
+        let system_audio = if include_system_audio {
+            loaded_track(&s.system_audio, "system audio").await
+        } else {
+            None
+        };
+
+        let mut tracks = [
+            audio.map(|a| {
+                AudioSegmentTrack::new(
+                    a,
+                    |c| {
+                        if c.mic_mute {
+                            f32::NEG_INFINITY
+                        } else {
+                            c.mic_volume_db
+                        }
+                    },
+                    |c| stereo_mode(&c.mic_stereo_mode),
+                    |o| o.mic,
+                )
+                .with_timing_offset_secs(s.audio_timing_repair.mic_offset_secs)
+            }),
+            system_audio.map(|a| -> AudioSegmentTrack {
+                AudioSegmentTrack::new(
+                    a,
+                    |c| c.system_volume_db,
+                    |_| cap_audio::StereoMode::Stereo,
+                    |o| o.system_audio,
+                )
+                .with_timing_offset_secs(s.audio_timing_repair.system_audio_offset_secs)
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if include_mic {
+            for mic in &s.additional_mics {
+                let Some(a) = loaded_track(&mic.audio, &format!("{} audio", mic.id)).await else {
+                    continue;
+                };
 
-        out.push(AudioSegment {
-            tracks: [
-                audio.map(|a| {
+                let gain_id = mic.id.clone();
+                let stereo_id = mic.id.clone();
+                tracks.push(
                     AudioSegmentTrack::new(
                         a,
-                        |c| c.mic_volume_db,
-                        |c| match c.mic_stereo_mode {
-                            cap_project::StereoMode::Stereo => cap_audio::StereoMode::Stereo,
-                            cap_project::StereoMode::MonoL => cap_audio::StereoMode::MonoL,
-                            cap_project::StereoMode::MonoR => cap_audio::StereoMode::MonoR,
+                        move |c| match c.additional_mic(&gain_id) {
+                            Some(mic) if mic.mute => f32::NEG_INFINITY,
+                            Some(mic) => mic.volume_db,
+                            None => 0.0,
+                        },
+                        move |c| {
+                            c.additional_mic(&stereo_id)
+                                .map(|mic| stereo_mode(&mic.stereo_mode))
+                                .unwrap_or(cap_audio::StereoMode::Stereo)
                         },
                         |o| o.mic,
                     )
-                    .with_timing_offset_secs(s.audio_timing_repair.mic_offset_secs)
-                }),
-                system_audio.map(|a| -> AudioSegmentTrack {
-                    AudioSegmentTrack::new(
-                        a,
-                        |c| c.system_volume_db,
-                        |_| cap_audio::StereoMode::Stereo,
-                        |o| o.system_audio,
-                    )
-                    .with_timing_offset_secs(s.audio_timing_repair.system_audio_offset_secs)
-                }),
-            ]
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>(),
-        });
+                    .with_timing_offset_secs(mic.offset_secs),
+                );
+            }
+        }
+
+        out.push(AudioSegment { tracks });
     }
 
     out
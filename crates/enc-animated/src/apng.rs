@@ -0,0 +1,89 @@
+use color_quant::NeuQuant;
+use png::{BitDepth, ColorType, Compression, Encoder};
+
+use crate::{AnimatedEncodingError, AnimationFrames};
+
+/// Frames sampled to train the shared palette; APNG frames all use the one
+/// PLTE chunk.
+const PALETTE_SAMPLE_FRAMES: usize = 8;
+
+pub(crate) fn encode(
+    frames: &AnimationFrames,
+    quality: u8,
+    lossless: bool,
+    loop_count: u16,
+) -> Result<Vec<u8>, AnimatedEncodingError> {
+    let (width, height) = frames.dimensions();
+    let mut data = Vec::new();
+
+    let mut encoder = Encoder::new(&mut data, width, height);
+    encoder.set_depth(BitDepth::Eight);
+    encoder.set_compression(Compression::Best);
+
+    let palette = if lossless {
+        encoder.set_color(ColorType::Rgba);
+        None
+    } else {
+        let quantizer = train_palette(frames, quality)?;
+        let colors = quantizer.color_map_rgba();
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_palette(
+            colors
+                .chunks_exact(4)
+                .flat_map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>(),
+        );
+        encoder.set_trns(colors.chunks_exact(4).map(|c| c[3]).collect::<Vec<_>>());
+        Some(quantizer)
+    };
+
+    encoder
+        .set_animated(frames.held_count() as u32, loop_count as u32)
+        .map_err(apng_error)?;
+    let mut writer = encoder.write_header().map_err(apng_error)?;
+
+    frames.for_each_frame(|rgba, frame_count| {
+        // Delays are fractions of a second, so repeats stay exact at any fps.
+        writer
+            .set_frame_delay(
+                frame_count.min(u16::MAX as u32) as u16,
+                frames.fps().min(u16::MAX as u32) as u16,
+            )
+            .map_err(apng_error)?;
+
+        match &palette {
+            Some(quantizer) => {
+                let indexes: Vec<u8> = rgba
+                    .chunks_exact(4)
+                    .map(|pixel| quantizer.index_of(pixel) as u8)
+                    .collect();
+                writer.write_image_data(&indexes).map_err(apng_error)
+            }
+            None => writer.write_image_data(rgba).map_err(apng_error),
+        }
+    })?;
+
+    writer.finish().map_err(apng_error)?;
+
+    Ok(data)
+}
+
+/// Builds one palette for the whole animation. Quality picks the palette
+/// size, from 16 colours at 1 to 256 at 100; fewer colours compress better.
+fn train_palette(frames: &AnimationFrames, quality: u8) -> Result<NeuQuant, AnimatedEncodingError> {
+    let colors = 16 + (quality.clamp(1, 100) as usize - 1) * 240 / 99;
+    let step = frames.held_count().div_ceil(PALETTE_SAMPLE_FRAMES).max(1);
+
+    let mut sample = Vec::new();
+    let mut rgba = Vec::new();
+    for index in (0..frames.held_count()).step_by(step) {
+        frames.read_frame(index, &mut rgba)?;
+        sample.extend_from_slice(&rgba);
+    }
+
+    Ok(NeuQuant::new(10, colors, &sample))
+}
+
+fn apng_error(e: png::EncodingError) -> AnimatedEncodingError {
+    AnimatedEncodingError::Apng(e.to_string())
+}
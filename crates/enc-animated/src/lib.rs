@@ -0,0 +1,426 @@
+//! Animated WebP and APNG encoding.
+//!
+//! Unlike GIF, both formats are encoded once all frames are in, so a file
+//! that misses its size budget can be re-encoded at a lower quality. Frames
+//! wait for that on disk, compressed, and are streamed back through the
+//! encoder one at a time.
+
+mod apng;
+mod webp;
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use thiserror::Error;
+use tracing::debug;
+
+/// Errors that can occur during animated image encoding
+#[derive(Error, Debug)]
+pub enum AnimatedEncodingError {
+    /// Invalid frame data provided
+    #[error("Invalid frame data")]
+    InvalidFrameData,
+    /// No frames were added before encoding
+    #[error("No frames to encode")]
+    NoFrames,
+    /// Error from libwebp
+    #[error("WebP error: {0}")]
+    WebP(String),
+    /// Error from the PNG encoder
+    #[error("APNG error: {0}")]
+    Apng(String),
+    /// Error reading or writing the frames held on disk
+    #[error("Frame storage error: {0}")]
+    Storage(#[from] std::io::Error),
+    /// Even the lowest quality is larger than the size budget
+    #[error(
+        "Could not fit the animation in {budget} bytes; the smallest encoding was {smallest} bytes"
+    )]
+    OverBudget { smallest: u64, budget: u64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimatedFormat {
+    WebP,
+    Apng,
+}
+
+/// Quality settings shared by WebP and APNG
+#[derive(Clone, Copy, Debug)]
+pub struct AnimatedQuality {
+    /// Lossy quality from 1-100 (default: 80). For APNG this sets the
+    /// palette size frames are quantized to.
+    pub quality: u8,
+    /// Encode every pixel exactly (default: false)
+    pub lossless: bool,
+    /// Times to play the animation, 0 for forever (default: 0)
+    pub loop_count: u16,
+    /// Lower the quality until the file fits in this many bytes. A lossless
+    /// encoding over budget falls back to lossy.
+    pub max_file_size: Option<u64>,
+}
+
+impl Default for AnimatedQuality {
+    fn default() -> Self {
+        Self {
+            quality: 80,
+            lossless: false,
+            loop_count: 0,
+            max_file_size: None,
+        }
+    }
+}
+
+/// The lowest quality size targeting will go down to.
+const MIN_QUALITY: u8 = 5;
+/// Encodes tried by the binary search once the requested quality misses the
+/// budget.
+const MAX_SEARCH_STEPS: usize = 6;
+
+struct HeldFrame {
+    /// Where the LZ4-compressed image starts in the storage file.
+    offset: u64,
+    len: usize,
+    /// Consecutive output frames this image is shown for.
+    frame_count: u32,
+}
+
+/// Frames collected for encoding. Consecutive identical frames, common in
+/// screen recordings, are stored once with a longer duration.
+///
+/// Images live LZ4-compressed in an unnamed temporary file rather than in
+/// memory, so only the most recent frame is resident however long the clip
+/// is.
+pub struct AnimationFrames {
+    width: u32,
+    height: u32,
+    fps: u32,
+    frames: Vec<HeldFrame>,
+    storage: File,
+    storage_len: u64,
+    /// The last image added, kept to spot repeats.
+    last: Vec<u8>,
+    packed: Vec<u8>,
+}
+
+impl AnimationFrames {
+    pub fn new(width: u32, height: u32, fps: u32) -> Result<Self, AnimatedEncodingError> {
+        if fps == 0 || width == 0 || height == 0 {
+            return Err(AnimatedEncodingError::InvalidFrameData);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            fps,
+            frames: Vec::new(),
+            storage: tempfile::tempfile()?,
+            storage_len: 0,
+            last: Vec::new(),
+            packed: Vec::new(),
+        })
+    }
+
+    pub fn add_frame(
+        &mut self,
+        frame_data: &[u8],
+        bytes_per_row: usize,
+    ) -> Result<(), AnimatedEncodingError> {
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let expected_bytes_per_row = w * 4;
+
+        if bytes_per_row < expected_bytes_per_row
+            || frame_data.len() < bytes_per_row * h.saturating_sub(1) + expected_bytes_per_row
+        {
+            return Err(AnimatedEncodingError::InvalidFrameData);
+        }
+
+        self.packed.clear();
+        for y in 0..h {
+            let row_start = y * bytes_per_row;
+            self.packed
+                .extend_from_slice(&frame_data[row_start..row_start + expected_bytes_per_row]);
+        }
+
+        if let Some(last) = self.frames.last_mut()
+            && self.last == self.packed
+        {
+            last.frame_count += 1;
+            return Ok(());
+        }
+
+        let compressed = lz4_flex::compress(&self.packed);
+        self.storage.seek(SeekFrom::Start(self.storage_len))?;
+        self.storage.write_all(&compressed)?;
+        self.frames.push(HeldFrame {
+            offset: self.storage_len,
+            len: compressed.len(),
+            frame_count: 1,
+        });
+        self.storage_len += compressed.len() as u64;
+        std::mem::swap(&mut self.last, &mut self.packed);
+
+        Ok(())
+    }
+
+    /// Distinct images held, each shown for one or more output frames.
+    fn held_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Decodes held image `index` into `rgba`.
+    fn read_frame(&self, index: usize, rgba: &mut Vec<u8>) -> Result<(), AnimatedEncodingError> {
+        let frame = &self.frames[index];
+        let mut compressed = vec![0; frame.len];
+        let mut storage = &self.storage;
+        storage.seek(SeekFrom::Start(frame.offset))?;
+        storage.read_exact(&mut compressed)?;
+
+        rgba.resize(self.width as usize * self.height as usize * 4, 0);
+        let written = lz4_flex::decompress_into(&compressed, rgba)
+            .map_err(|_| AnimatedEncodingError::InvalidFrameData)?;
+        if written != rgba.len() {
+            return Err(AnimatedEncodingError::InvalidFrameData);
+        }
+
+        Ok(())
+    }
+
+    /// Calls `visit` with every held image in order and the number of output
+    /// frames it is shown for, reusing one buffer throughout.
+    fn for_each_frame(
+        &self,
+        mut visit: impl FnMut(&[u8], u32) -> Result<(), AnimatedEncodingError>,
+    ) -> Result<(), AnimatedEncodingError> {
+        let mut rgba = Vec::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            self.read_frame(index, &mut rgba)?;
+            visit(&rgba, frame.frame_count)?;
+        }
+
+        Ok(())
+    }
+
+    /// Total output frames added, counting repeats.
+    pub fn frame_count(&self) -> u32 {
+        self.frames.iter().map(|f| f.frame_count).sum()
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Milliseconds from the start of the animation to output frame `frame`.
+    fn timestamp_ms(&self, frame: u64) -> u64 {
+        (frame * 1000 + self.fps as u64 / 2) / self.fps as u64
+    }
+}
+
+pub struct EncodedAnimation {
+    pub data: Vec<u8>,
+    /// Quality the returned data was encoded at; lower than requested when
+    /// size targeting kicked in.
+    pub quality: u8,
+    pub lossless: bool,
+}
+
+/// Encodes `frames` as `format`, lowering the quality as needed to fit
+/// `quality.max_file_size`.
+pub fn encode(
+    frames: &AnimationFrames,
+    format: AnimatedFormat,
+    quality: &AnimatedQuality,
+) -> Result<EncodedAnimation, AnimatedEncodingError> {
+    if frames.frames.is_empty() {
+        return Err(AnimatedEncodingError::NoFrames);
+    }
+
+    fit_to_budget(quality, |q, lossless| match format {
+        AnimatedFormat::WebP => webp::encode(frames, q, lossless, quality.loop_count),
+        AnimatedFormat::Apng => apng::encode(frames, q, lossless, quality.loop_count),
+    })
+}
+
+fn fit_to_budget(
+    options: &AnimatedQuality,
+    mut encode: impl FnMut(u8, bool) -> Result<Vec<u8>, AnimatedEncodingError>,
+) -> Result<EncodedAnimation, AnimatedEncodingError> {
+    let fits = |data: &[u8]| {
+        options
+            .max_file_size
+            .is_none_or(|budget| data.len() as u64 <= budget)
+    };
+    let mut smallest = u64::MAX;
+
+    if options.lossless {
+        let data = encode(100, true)?;
+        if fits(&data) {
+            return Ok(EncodedAnimation {
+                data,
+                quality: 100,
+                lossless: true,
+            });
+        }
+        smallest = data.len() as u64;
+        debug!("Lossless encoding is {smallest} bytes, over budget; falling back to lossy");
+    }
+
+    let quality = options.quality.clamp(1, 100);
+    let data = encode(quality, false)?;
+    if fits(&data) {
+        return Ok(EncodedAnimation {
+            data,
+            quality,
+            lossless: false,
+        });
+    }
+    smallest = smallest.min(data.len() as u64);
+
+    // Highest quality below the requested one that still fits.
+    let (mut low, mut high) = (MIN_QUALITY, quality.saturating_sub(1));
+    let mut best = None;
+    for _ in 0..MAX_SEARCH_STEPS {
+        if low > high {
+            break;
+        }
+
+        let mid = low + (high - low) / 2;
+        let data = encode(mid, false)?;
+        debug!("Quality {mid} encodes to {} bytes", data.len());
+        smallest = smallest.min(data.len() as u64);
+
+        if fits(&data) {
+            best = Some((mid, data));
+            low = mid + 1;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    match best {
+        Some((quality, data)) => Ok(EncodedAnimation {
+            data,
+            quality,
+            lossless: false,
+        }),
+        None => Err(AnimatedEncodingError::OverBudget {
+            smallest,
+            budget: options.max_file_size.unwrap_or_default(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(lossless: bool, max_file_size: Option<u64>) -> AnimatedQuality {
+        AnimatedQuality {
+            quality: 80,
+            lossless,
+            loop_count: 0,
+            max_file_size,
+        }
+    }
+
+    /// Fake encoder whose output is 10 bytes per quality point, or 2000 bytes
+    /// when lossless.
+    fn sized(quality: u8, lossless: bool) -> Result<Vec<u8>, AnimatedEncodingError> {
+        Ok(vec![
+            0;
+            if lossless {
+                2000
+            } else {
+                quality as usize * 10
+            }
+        ])
+    }
+
+    #[test]
+    fn requested_quality_is_kept_when_it_fits() {
+        let encoded = fit_to_budget(&options(false, Some(1000)), sized).unwrap();
+        assert_eq!(encoded.quality, 80);
+        assert!(!encoded.lossless);
+    }
+
+    #[test]
+    fn size_budget_finds_the_highest_quality_that_fits() {
+        let encoded = fit_to_budget(&options(false, Some(455)), sized).unwrap();
+        assert!(encoded.data.len() <= 455);
+        assert!(encoded.quality >= 40, "quality {}", encoded.quality);
+    }
+
+    #[test]
+    fn lossless_over_budget_falls_back_to_lossy() {
+        let encoded = fit_to_budget(&options(true, Some(1000)), sized).unwrap();
+        assert!(!encoded.lossless);
+        assert_eq!(encoded.quality, 80);
+    }
+
+    #[test]
+    fn unreachable_budget_reports_the_smallest_attempt() {
+        let Err(AnimatedEncodingError::OverBudget { smallest, budget }) =
+            fit_to_budget(&options(false, Some(10)), sized)
+        else {
+            panic!("expected an over-budget error");
+        };
+        assert_eq!(budget, 10);
+        assert!(smallest < 800);
+    }
+
+    #[test]
+    fn repeated_frames_are_stored_once() {
+        let mut frames = AnimationFrames::new(2, 1, 30).unwrap();
+        let red = [255, 0, 0, 255, 255, 0, 0, 255];
+        let blue = [0, 0, 255, 255, 0, 0, 255, 255];
+        frames.add_frame(&red, 8).unwrap();
+        frames.add_frame(&red, 8).unwrap();
+        frames.add_frame(&blue, 8).unwrap();
+
+        assert_eq!(frames.frames.len(), 2);
+        assert_eq!(frames.frame_count(), 3);
+        assert_eq!(frames.timestamp_ms(2), 67);
+
+        let mut held = Vec::new();
+        frames
+            .for_each_frame(|rgba, count| {
+                held.push((rgba.to_vec(), count));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(held, vec![(red.to_vec(), 2), (blue.to_vec(), 1)]);
+    }
+
+    #[test]
+    fn held_frames_encode_to_both_formats() {
+        let mut frames = AnimationFrames::new(4, 4, 10).unwrap();
+        for shade in [0u8, 0, 128, 255] {
+            frames.add_frame(&[shade; 4 * 4 * 4], 16).unwrap();
+        }
+
+        let webp = encode(&frames, AnimatedFormat::WebP, &AnimatedQuality::default()).unwrap();
+        assert_eq!(&webp.data[..4], b"RIFF");
+        assert_eq!(&webp.data[8..12], b"WEBP");
+
+        let apng = encode(&frames, AnimatedFormat::Apng, &AnimatedQuality::default()).unwrap();
+        assert_eq!(&apng.data[1..4], b"PNG");
+        assert!(apng.data.windows(4).any(|chunk| chunk == b"acTL"));
+    }
+
+    #[test]
+    fn padded_rows_are_stored_tightly() {
+        let mut frames = AnimationFrames::new(1, 2, 30).unwrap();
+        frames
+            .add_frame(&[1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8], 8)
+            .unwrap();
+
+        let mut rgba = Vec::new();
+        frames.read_frame(0, &mut rgba).unwrap();
+        assert_eq!(rgba, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+use libwebp_sys::{
+    WebPAnimEncoder, WebPAnimEncoderAdd, WebPAnimEncoderAssemble, WebPAnimEncoderDelete,
+    WebPAnimEncoderGetError, WebPAnimEncoderNewInternal, WebPAnimEncoderOptionsInitInternal,
+    WebPConfig, WebPData, WebPDataClear, WebPGetMuxABIVersion, WebPPicture, WebPPictureFree,
+    WebPPictureImportRGBA,
+};
+
+use crate::{AnimatedEncodingError, AnimationFrames};
+
+pub(crate) fn encode(
+    frames: &AnimationFrames,
+    quality: u8,
+    lossless: bool,
+    loop_count: u16,
+) -> Result<Vec<u8>, AnimatedEncodingError> {
+    let mut config = WebPConfig::new()
+        .map_err(|_| AnimatedEncodingError::WebP("Failed to initialise config".to_string()))?;
+    config.lossless = lossless as i32;
+    // For lossless encoding libwebp reads `quality` as compression effort.
+    config.quality = if lossless { 75.0 } else { quality as f32 };
+    config.method = 4;
+
+    let (width, height) = frames.dimensions();
+    let mut encoder = AnimEncoder::new(width, height, loop_count)?;
+
+    let mut elapsed = 0u64;
+    frames.for_each_frame(|rgba, frame_count| {
+        encoder.add(
+            rgba,
+            width,
+            height,
+            frames.timestamp_ms(elapsed) as i32,
+            &config,
+        )?;
+        elapsed += frame_count as u64;
+        Ok(())
+    })?;
+
+    encoder.finish(frames.timestamp_ms(elapsed) as i32)
+}
+
+/// A libwebp animation encoder. Each frame is compressed as it is added, so
+/// the encoder holds encoded data rather than every image.
+struct AnimEncoder(*mut WebPAnimEncoder);
+
+impl AnimEncoder {
+    fn new(width: u32, height: u32, loop_count: u16) -> Result<Self, AnimatedEncodingError> {
+        let mut options = MaybeUninit::uninit();
+        // SAFETY: the init call fills in every option before they are read.
+        let encoder = unsafe {
+            if WebPAnimEncoderOptionsInitInternal(options.as_mut_ptr(), WebPGetMuxABIVersion()) == 0
+            {
+                return Err(AnimatedEncodingError::WebP(
+                    "Incompatible libwebp version".to_string(),
+                ));
+            }
+            let mut options = options.assume_init();
+            options.anim_params.loop_count = loop_count as i32;
+            WebPAnimEncoderNewInternal(
+                width as i32,
+                height as i32,
+                &options,
+                WebPGetMuxABIVersion(),
+            )
+        };
+
+        if encoder.is_null() {
+            return Err(AnimatedEncodingError::WebP(
+                "Failed to create animation encoder".to_string(),
+            ));
+        }
+
+        Ok(Self(encoder))
+    }
+
+    fn add(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        timestamp_ms: i32,
+        config: &WebPConfig,
+    ) -> Result<(), AnimatedEncodingError> {
+        let mut picture = WebPPicture::new()
+            .map_err(|_| AnimatedEncodingError::WebP("Failed to initialise picture".to_string()))?;
+        picture.use_argb = 1;
+        picture.width = width as i32;
+        picture.height = height as i32;
+
+        // SAFETY: `rgba` holds `height` tightly packed rows of `width` pixels.
+        // The import copies them into the picture's own buffer, which is freed
+        // once the encoder has consumed it.
+        let added = unsafe {
+            let added = WebPPictureImportRGBA(&mut picture, rgba.as_ptr(), width as i32 * 4) != 0
+                && WebPAnimEncoderAdd(self.0, &mut picture, timestamp_ms, config) != 0;
+            WebPPictureFree(&mut picture);
+            added
+        };
+
+        if added { Ok(()) } else { Err(self.error()) }
+    }
+
+    /// Ends the animation at `end_timestamp_ms`, which sets how long the last
+    /// frame is shown, and returns the file.
+    fn finish(self, end_timestamp_ms: i32) -> Result<Vec<u8>, AnimatedEncodingError> {
+        // SAFETY: a null frame is libwebp's end-of-animation marker, and the
+        // assembled data is copied out before being freed.
+        unsafe {
+            if WebPAnimEncoderAdd(self.0, ptr::null_mut(), end_timestamp_ms, ptr::null()) == 0 {
+                return Err(self.error());
+            }
+
+            let mut data = WebPData::default();
+            if WebPAnimEncoderAssemble(self.0, &mut data) == 0 || data.bytes.is_null() {
+                return Err(self.error());
+            }
+            let bytes = std::slice::from_raw_parts(data.bytes, data.size).to_vec();
+            WebPDataClear(&mut data);
+
+            Ok(bytes)
+        }
+    }
+
+    fn error(&self) -> AnimatedEncodingError {
+        // SAFETY: the message is owned by the live encoder and copied here.
+        let message = unsafe {
+            let message = WebPAnimEncoderGetError(self.0);
+            if message.is_null() {
+                "Unknown error".to_string()
+            } else {
+                CStr::from_ptr(message).to_string_lossy().into_owned()
+            }
+        };
+
+        AnimatedEncodingError::WebP(message)
+    }
+}
+
+impl Drop for AnimEncoder {
+    fn drop(&mut self) {
+        // SAFETY: the encoder was created in `new` and is deleted only here.
+        unsafe { WebPAnimEncoderDelete(self.0) }
+    }
+}
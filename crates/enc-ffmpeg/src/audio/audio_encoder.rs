@@ -11,3 +11,63 @@ pub trait AudioEncoder {
     fn send_frame(&mut self, frame: frame::Audio, output: &mut format::context::Output);
     fn flush(&mut self, output: &mut format::context::Output) -> Result<(), ffmpeg::Error>;
 }
+
+pub type AudioEncoderInit<'a> = Box<
+    dyn FnOnce(
+            &mut format::context::Output,
+        ) -> Result<Box<dyn AudioEncoder + Send>, Box<dyn std::error::Error>>
+        + 'a,
+>;
+
+/// An audio stream for a muxer to create, tagged with `title` when set so
+/// players and editors can tell multiple tracks apart.
+pub struct AudioTrack<'a> {
+    pub title: Option<String>,
+    pub init: AudioEncoderInit<'a>,
+}
+
+impl<'a> AudioTrack<'a> {
+    pub fn new(
+        init: impl FnOnce(
+            &mut format::context::Output,
+        ) -> Result<Box<dyn AudioEncoder + Send>, Box<dyn std::error::Error>>
+        + 'a,
+    ) -> Self {
+        Self {
+            title: None,
+            init: Box::new(init),
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Creates each track's encoder in order, tagging its stream with the track
+/// title. Must run before the container header is written.
+pub fn add_audio_tracks(
+    output: &mut format::context::Output,
+    tracks: Vec<AudioTrack<'_>>,
+) -> Result<Vec<Box<dyn AudioEncoder + Send>>, Box<dyn std::error::Error>> {
+    let mut encoders = Vec::with_capacity(tracks.len());
+
+    for track in tracks {
+        let stream_index = output.nb_streams() as usize;
+        let encoder = (track.init)(output)?;
+
+        if let Some(title) = track.title
+            && let Some(mut stream) = output.stream_mut(stream_index)
+        {
+            let mut metadata = ffmpeg::Dictionary::new();
+            metadata.set("title", &title);
+            metadata.set("handler_name", &title);
+            stream.set_metadata(metadata);
+        }
+
+        encoders.push(encoder);
+    }
+
+    Ok(encoders)
+}
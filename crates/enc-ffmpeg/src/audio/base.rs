@@ -3,6 +3,10 @@ use crate::base::EncoderBase;
 use ffmpeg::{codec::encoder, format, frame};
 use std::time::Duration;
 
+/// Samples per frame for codecs that accept any frame size (PCM), which
+/// report a frame size of 0.
+const VARIABLE_FRAME_SIZE: usize = 1024;
+
 pub struct AudioEncoderBase {
     inner: EncoderBase,
     encoder: encoder::Audio,
@@ -32,7 +36,7 @@ impl AudioEncoderBase {
 
         self.resampler.add_frame(frame);
 
-        while let Some(frame) = self.resampler.get_frame(self.encoder.frame_size() as usize) {
+        while let Some(frame) = self.resampler.get_frame(self.frame_size()) {
             self.inner.send_frame(&frame, output, &mut self.encoder)?;
         }
 
@@ -40,10 +44,17 @@ impl AudioEncoderBase {
     }
 
     pub fn flush(&mut self, output: &mut format::context::Output) -> Result<(), ffmpeg::Error> {
-        while let Some(frame) = self.resampler.flush(self.encoder.frame_size() as usize) {
+        while let Some(frame) = self.resampler.flush(self.frame_size()) {
             self.inner.send_frame(&frame, output, &mut self.encoder)?;
         }
 
         self.inner.process_eof(output, &mut self.encoder)
     }
+
+    fn frame_size(&self) -> usize {
+        match self.encoder.frame_size() {
+            0 => VARIABLE_FRAME_SIZE,
+            frame_size => frame_size as usize,
+        }
+    }
 }
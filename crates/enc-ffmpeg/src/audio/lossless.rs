@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use cap_media_info::{AudioInfo, FFRational};
+use ffmpeg::{
+    codec::{context, encoder},
+    format::{self, Sample, sample::Type},
+    frame,
+};
+
+use crate::audio::{
+    audio_encoder::AudioEncoder, base::AudioEncoderBase, buffered_resampler::BufferedResampler,
+};
+
+/// Uncompressed or losslessly compressed audio, for handing stems to an
+/// editor without another generation of lossy encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LosslessAudioFormat {
+    /// 24-bit PCM in a WAV container
+    Wav,
+    /// 24-bit FLAC
+    Flac,
+}
+
+impl LosslessAudioFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+        }
+    }
+
+    fn codec_name(&self) -> &'static str {
+        match self {
+            Self::Wav => "pcm_s24le",
+            Self::Flac => "flac",
+        }
+    }
+}
+
+pub struct LosslessAudioEncoder {
+    base: AudioEncoderBase,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LosslessAudioEncoderError {
+    #[error("{0:?}")]
+    FFmpeg(#[from] ffmpeg::Error),
+    #[error("{0} codec not found")]
+    CodecNotFound(&'static str),
+    #[error("Resampler: {0}")]
+    Resampler(ffmpeg::Error),
+}
+
+impl LosslessAudioEncoder {
+    // Both codecs take 24-bit samples in the high bits of packed s32.
+    const SAMPLE_FORMAT: Sample = Sample::I32(Type::Packed);
+    const BITS_PER_SAMPLE: i32 = 24;
+
+    pub fn factory(
+        input_config: AudioInfo,
+        format: LosslessAudioFormat,
+    ) -> impl FnOnce(&mut format::context::Output) -> Result<Self, LosslessAudioEncoderError> {
+        move |o| Self::init(input_config, format, o)
+    }
+
+    pub fn init(
+        input_config: AudioInfo,
+        format: LosslessAudioFormat,
+        output: &mut format::context::Output,
+    ) -> Result<Self, LosslessAudioEncoderError> {
+        let codec = encoder::find_by_name(format.codec_name()).ok_or(
+            LosslessAudioEncoderError::CodecNotFound(format.codec_name()),
+        )?;
+        let encoder_ctx = context::Context::new_with_codec(codec);
+        let mut encoder = encoder_ctx.encoder().audio()?;
+
+        let mut output_config = input_config;
+        output_config.sample_format = Self::SAMPLE_FORMAT;
+
+        let resampler = BufferedResampler::new(input_config, output_config)
+            .map_err(LosslessAudioEncoderError::Resampler)?;
+
+        encoder.set_rate(output_config.rate());
+        encoder.set_format(output_config.sample_format);
+        encoder.set_channel_layout(output_config.channel_layout());
+        encoder.set_time_base(FFRational(1, output_config.rate()));
+        unsafe {
+            (*encoder.as_mut_ptr()).bits_per_raw_sample = Self::BITS_PER_SAMPLE;
+        }
+
+        let encoder = encoder.open()?;
+
+        let mut output_stream = output.add_stream(codec)?;
+        output_stream.set_time_base(FFRational(1, output_config.rate()));
+        output_stream.set_parameters(&encoder);
+
+        Ok(Self {
+            base: AudioEncoderBase::new(encoder, resampler, output_stream.index()),
+        })
+    }
+
+    pub fn send_frame(
+        &mut self,
+        frame: frame::Audio,
+        timestamp: Duration,
+        output: &mut format::context::Output,
+    ) -> Result<(), ffmpeg::Error> {
+        self.base.send_frame(frame, timestamp, output)
+    }
+
+    pub fn flush(&mut self, output: &mut format::context::Output) -> Result<(), ffmpeg::Error> {
+        self.base.flush(output)
+    }
+}
+
+impl AudioEncoder for LosslessAudioEncoder {
+    fn send_frame(&mut self, frame: frame::Audio, output: &mut format::context::Output) {
+        let _ = self.send_frame(frame, Duration::MAX, output);
+    }
+
+    fn flush(&mut self, output: &mut format::context::Output) -> Result<(), ffmpeg::Error> {
+        self.flush(output)
+    }
+}
@@ -6,4 +6,5 @@ mod base;
 pub mod buffered_resampler;
 
 pub mod aac;
+pub mod lossless;
 pub mod opus;
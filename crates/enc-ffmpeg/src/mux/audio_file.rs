@@ -0,0 +1,135 @@
+use ffmpeg::{format, frame};
+use std::path::PathBuf;
+
+use crate::audio::AudioEncoder;
+
+/// A single audio stream in a container picked from the file extension, e.g.
+/// WAV or FLAC.
+pub struct AudioFile {
+    encoder: Box<dyn AudioEncoder + Send>,
+    output: format::context::Output,
+    finished: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FinishError {
+    #[error("Already finished")]
+    AlreadyFinished,
+    #[error("{0}")]
+    WriteTrailerFailed(ffmpeg::Error),
+}
+
+impl AudioFile {
+    pub fn init<E: AudioEncoder + Send + 'static, Err: std::error::Error + 'static>(
+        output: PathBuf,
+        encoder: impl FnOnce(&mut format::context::Output) -> Result<E, Err>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(parent) = output.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let mut output = format::output(&output)?;
+        let encoder = encoder(&mut output)?.boxed();
+
+        // make sure this happens after adding the encoder!
+        output.write_header()?;
+
+        Ok(Self {
+            encoder,
+            output,
+            finished: false,
+        })
+    }
+
+    pub fn queue_frame(&mut self, frame: frame::Audio) {
+        if self.finished {
+            return;
+        }
+
+        self.encoder.send_frame(frame, &mut self.output);
+    }
+
+    pub fn finish(&mut self) -> Result<Result<(), ffmpeg::Error>, FinishError> {
+        if self.finished {
+            return Err(FinishError::AlreadyFinished);
+        }
+
+        self.finished = true;
+
+        let flush_result = self.encoder.flush(&mut self.output);
+        self.output
+            .write_trailer()
+            .map_err(FinishError::WriteTrailerFailed)?;
+
+        Ok(flush_result)
+    }
+}
+
+impl Drop for AudioFile {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::lossless::{LosslessAudioEncoder, LosslessAudioFormat};
+    use cap_media_info::AudioInfo;
+    use ffmpeg::ChannelLayout;
+
+    fn write_tone(path: PathBuf, format: LosslessAudioFormat, total_samples: usize) {
+        let rate = 48_000;
+        let info = AudioInfo::new_raw(format::Sample::F32(format::sample::Type::Packed), rate, 2);
+
+        let mut file = AudioFile::init(path, LosslessAudioEncoder::factory(info, format)).unwrap();
+
+        let chunk = 1024usize;
+        let mut written = 0usize;
+        while written < total_samples {
+            let samples = chunk.min(total_samples - written);
+            let mut frame = frame::Audio::new(
+                format::Sample::F32(format::sample::Type::Packed),
+                samples,
+                ChannelLayout::STEREO,
+            );
+            frame.set_rate(rate);
+            frame.set_pts(Some(written as i64));
+            for (i, value) in frame.data_mut(0)[..samples * 8]
+                .chunks_exact_mut(4)
+                .enumerate()
+            {
+                let t = (written + i / 2) as f32 / rate as f32;
+                value.copy_from_slice(
+                    &(0.5 * (t * 440.0 * std::f32::consts::TAU).sin()).to_ne_bytes(),
+                );
+            }
+
+            file.queue_frame(frame);
+            written += samples;
+        }
+
+        file.finish().unwrap().unwrap();
+    }
+
+    fn probed_duration_secs(path: &std::path::Path) -> f64 {
+        let input = format::input(path).expect("output should be openable");
+        input.duration() as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE)
+    }
+
+    #[test]
+    fn wav_and_flac_keep_their_duration() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for format in [LosslessAudioFormat::Wav, LosslessAudioFormat::Flac] {
+            let path = dir.path().join(format!("tone.{}", format.extension()));
+            write_tone(path.clone(), format, 48_000 * 2);
+
+            let duration = probed_duration_secs(&path);
+            assert!(
+                (duration - 2.0).abs() < 0.05,
+                "expected ~2s of {format:?}, probed {duration}s"
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::audio::aac::{AACEncoder, AACEncoderError};
-use crate::mux::segmented_stream::{SegmentCompletedEvent, SegmentMediaType};
+use crate::mux::segmented_stream::{
+    PrunedSegments, SegmentCompletedEvent, SegmentMediaType, is_pruned,
+};
 use cap_media_info::AudioInfo;
 use ffmpeg::{format, frame};
 use serde::Serialize;
@@ -423,9 +425,11 @@ impl DashAudioSegmentEncoder {
     }
 
     fn write_in_progress_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let mut segments: Vec<SegmentEntry> = self
             .completed_segments
             .iter()
+            .filter(|s| !is_pruned(pruned.as_ref(), s.index))
             .map(|s| SegmentEntry {
                 path: s
                     .path
@@ -651,6 +655,7 @@ impl DashAudioSegmentEncoder {
     }
 
     fn finalize_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let total_duration: Duration = self.completed_segments.iter().map(|s| s.duration).sum();
 
         let manifest = Manifest {
@@ -660,6 +665,7 @@ impl DashAudioSegmentEncoder {
             segments: self
                 .completed_segments
                 .iter()
+                .filter(|s| !is_pruned(pruned.as_ref(), s.index))
                 .map(|s| SegmentEntry {
                     path: s
                         .path
@@ -1,42 +1,20 @@
 use cap_media_info::VideoInfo;
 use serde::Serialize;
 use std::{
-    io::Write,
     path::{Path, PathBuf},
     time::Duration,
 };
 
-use crate::mux::segmented_stream::{SegmentCompletedEvent, SegmentMediaType, VideoSegmentInfo};
+use crate::mux::segmented_stream::{
+    FragmentSealer, PrunedSegments, SegmentCompletedEvent, SegmentMediaType, VideoSegmentInfo,
+    is_pruned, seal_fragment, write_manifest,
+};
 
 const INIT_SEGMENT_NAME: &str = "init.mp4";
 const MANIFEST_VERSION: u32 = 5;
 const MANIFEST_TYPE: &str = "m4s_segments";
 const PENDING_FLUSH_INTERVAL: u32 = 10;
 
-fn atomic_write_json<T: Serialize>(path: &Path, data: &T) -> std::io::Result<()> {
-    let temp_path = path.with_extension("json.tmp");
-    let json = serde_json::to_string_pretty(data)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-
-    let mut file = std::fs::File::create(&temp_path)?;
-    file.write_all(json.as_bytes())?;
-    file.sync_all()?;
-
-    std::fs::rename(&temp_path, path)?;
-
-    if let Some(parent) = path.parent()
-        && let Ok(dir) = std::fs::File::open(parent)
-        && let Err(e) = dir.sync_all()
-    {
-        tracing::warn!(
-            "Directory fsync failed after rename for {}: {e}",
-            parent.display()
-        );
-    }
-
-    Ok(())
-}
-
 fn sync_file(path: &Path) {
     if let Ok(file) = std::fs::File::open(path)
         && let Err(e) = file.sync_all()
@@ -92,6 +70,7 @@ pub struct FragmentManifestTracker {
     pending_segment_indices: Vec<(u32, Duration)>,
     frames_since_pending_flush: u32,
     codec_info: CodecInfo,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     init_notified: bool,
 }
@@ -118,6 +97,7 @@ impl FragmentManifestTracker {
             pending_segment_indices: Vec::new(),
             frames_since_pending_flush: 0,
             codec_info,
+            fragment_sealer: None,
             segment_tx: None,
             init_notified: false,
         }
@@ -143,6 +123,10 @@ impl FragmentManifestTracker {
         "segment_$Number%03d$.m4s"
     }
 
+    pub fn set_fragment_sealer(&mut self, sealer: FragmentSealer) {
+        self.fragment_sealer = Some(sealer);
+    }
+
     pub fn set_segment_callback(&mut self, tx: std::sync::mpsc::Sender<SegmentCompletedEvent>) {
         self.segment_tx = Some(tx);
         self.try_notify_init_segment();
@@ -192,11 +176,13 @@ impl FragmentManifestTracker {
             && meta.len() > 0
         {
             self.init_notified = true;
+            // The init segment is written once, before the first fragment.
+            let file_size = seal_fragment(self.fragment_sealer.as_ref(), &init_path, meta.len());
             self.notify_segment(SegmentCompletedEvent {
                 path: init_path,
                 index: 0,
                 duration: 0.0,
-                file_size: meta.len(),
+                file_size,
                 is_init: true,
                 media_type: SegmentMediaType::Video,
             });
@@ -253,6 +239,13 @@ impl FragmentManifestTracker {
         };
 
         if resolved_path.exists() && file_size > 0 {
+            // A `.tmp` fragment is still open in the muxer; it is sealed on finalize.
+            let file_size = if resolved_path == segment_path {
+                seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size)
+            } else {
+                file_size
+            };
+
             self.completed_segments.push(VideoSegmentInfo {
                 path: segment_path.clone(),
                 index: completed_index,
@@ -323,6 +316,12 @@ impl FragmentManifestTracker {
                 "Flushing previously pending segment"
             );
 
+            let file_size = if resolved_path == segment_path {
+                seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size)
+            } else {
+                file_size
+            };
+
             self.completed_segments.push(VideoSegmentInfo {
                 path: segment_path,
                 index,
@@ -354,9 +353,11 @@ impl FragmentManifestTracker {
     }
 
     fn write_in_progress_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let mut segments: Vec<SegmentEntry> = self
             .completed_segments
             .iter()
+            .filter(|s| !is_pruned(pruned.as_ref(), s.index))
             .map(|s| SegmentEntry {
                 path: s
                     .path
@@ -395,7 +396,7 @@ impl FragmentManifestTracker {
         };
 
         let manifest_path = self.base_path.join("manifest.json");
-        if let Err(e) = atomic_write_json(&manifest_path, &manifest) {
+        if let Err(e) = write_manifest(self.fragment_sealer.as_ref(), &manifest_path, &manifest) {
             tracing::warn!(
                 "Failed to write in-progress manifest to {}: {e}",
                 manifest_path.display()
@@ -414,9 +415,33 @@ impl FragmentManifestTracker {
         self.finalize_pending_tmp_files();
         self.flush_pending_segments();
         self.collect_orphaned_segments(segment_start, effective_end_timestamp, frames_before_flush);
+        self.seal_remaining_fragments();
         self.finalize_manifest();
     }
 
+    /// Seals any fragment that was still a `.tmp` file when it completed, and
+    /// the init segment if the muxer rewrote it on finish.
+    fn seal_remaining_fragments(&mut self) {
+        let Some(sealer) = self.fragment_sealer.clone() else {
+            return;
+        };
+
+        let init_path = self.init_segment_path();
+        if init_path.exists() {
+            seal_fragment(Some(&sealer), &init_path, 0);
+        }
+
+        for segment in &mut self.completed_segments {
+            if segment.path.exists() {
+                segment.file_size = Some(seal_fragment(
+                    Some(&sealer),
+                    &segment.path,
+                    segment.file_size.unwrap_or(0),
+                ));
+            }
+        }
+    }
+
     fn finalize_pending_tmp_files(&self) {
         let Ok(entries) = std::fs::read_dir(&self.base_path) else {
             return;
@@ -553,6 +578,9 @@ impl FragmentManifestTracker {
                     duration
                 );
 
+                let file_size =
+                    seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size);
+
                 self.completed_segments.push(VideoSegmentInfo {
                     path: segment_path.clone(),
                     index,
@@ -575,6 +603,7 @@ impl FragmentManifestTracker {
     }
 
     fn finalize_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let total_duration: Duration = self.completed_segments.iter().map(|s| s.duration).sum();
 
         let manifest = Manifest {
@@ -585,6 +614,7 @@ impl FragmentManifestTracker {
             segments: self
                 .completed_segments
                 .iter()
+                .filter(|s| !is_pruned(pruned.as_ref(), s.index))
                 .map(|s| SegmentEntry {
                     path: s
                         .path
@@ -603,7 +633,7 @@ impl FragmentManifestTracker {
         };
 
         let manifest_path = self.base_path.join("manifest.json");
-        if let Err(e) = atomic_write_json(&manifest_path, &manifest) {
+        if let Err(e) = write_manifest(self.fragment_sealer.as_ref(), &manifest_path, &manifest) {
             tracing::warn!(
                 "Failed to write final manifest to {}: {e}",
                 manifest_path.display()
@@ -670,6 +700,54 @@ mod tests {
         assert_eq!(parsed["is_complete"], true);
     }
 
+    #[test]
+    fn tracker_seals_completed_fragments() {
+        let temp = tempfile::tempdir().unwrap();
+        let base = temp.path().to_path_buf();
+        let sealed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut tracker = FragmentManifestTracker::new(
+            base.clone(),
+            &test_video_info(),
+            Duration::from_millis(100),
+        );
+        let sealed_paths = sealed.clone();
+        tracker.set_fragment_sealer(FragmentSealer::new(
+            move |path: &Path| {
+                sealed_paths.lock().unwrap().push(path.to_path_buf());
+                std::fs::write(path, b"sealed")?;
+                Ok(6)
+            },
+            |path: &Path, _: &[u8]| std::fs::write(path, b"sealed manifest"),
+        ));
+        tracker.write_initial_manifest();
+        assert_eq!(
+            std::fs::read(base.join("manifest.json")).unwrap(),
+            b"sealed manifest"
+        );
+
+        std::fs::write(base.join(INIT_SEGMENT_NAME), b"init").unwrap();
+        std::fs::write(base.join("segment_001.m4s"), vec![0; 200]).unwrap();
+        tracker.on_frame(Duration::ZERO);
+        tracker.on_frame(Duration::from_millis(150));
+
+        assert_eq!(
+            *sealed.lock().unwrap(),
+            vec![base.join(INIT_SEGMENT_NAME), base.join("segment_001.m4s")]
+        );
+        assert_eq!(
+            std::fs::read(base.join(INIT_SEGMENT_NAME)).unwrap(),
+            b"sealed"
+        );
+        assert_eq!(tracker.completed_segments()[0].file_size, Some(6));
+
+        tracker.finalize(Duration::from_millis(200));
+        assert_eq!(
+            std::fs::read(base.join("segment_001.m4s")).unwrap(),
+            b"sealed"
+        );
+    }
+
     #[test]
     fn tracker_advances_segments_on_boundary() {
         let temp = tempfile::tempdir().unwrap();
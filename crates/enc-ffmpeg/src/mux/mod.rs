@@ -1,3 +1,4 @@
+pub mod audio_file;
 pub mod dash_audio;
 pub mod fragment_manifest;
 pub mod fragmented_audio;
@@ -1,11 +1,15 @@
 use ffmpeg::{format, frame};
 use std::{path::PathBuf, time::Duration};
 
-use crate::video::prores::{ProResEncoder, ProResEncoderError};
+use crate::{
+    audio::{AudioEncoder, AudioTrack, add_audio_tracks},
+    video::prores::{ProResEncoder, ProResEncoderError},
+};
 
 pub struct MOVFile {
     output: format::context::Output,
     video: ProResEncoder,
+    audio: Vec<Box<dyn AudioEncoder + Send>>,
     is_finished: bool,
 }
 
@@ -15,6 +19,8 @@ pub enum InitError {
     Ffmpeg(ffmpeg::Error),
     #[error("Video/{0}")]
     VideoInit(ProResEncoderError),
+    #[error("Audio/{0}")]
+    AudioInit(Box<dyn std::error::Error>),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -27,8 +33,18 @@ pub enum FinishError {
 
 impl MOVFile {
     pub fn init(
+        output: PathBuf,
+        video: impl FnOnce(&mut format::context::Output) -> Result<ProResEncoder, ProResEncoderError>,
+    ) -> Result<Self, InitError> {
+        Self::init_with_audio_tracks(output, video, Vec::new())
+    }
+
+    /// Like [`MOVFile::init`], with audio streams after the video. The first
+    /// track is the one players default to.
+    pub fn init_with_audio_tracks(
         mut output: PathBuf,
         video: impl FnOnce(&mut format::context::Output) -> Result<ProResEncoder, ProResEncoderError>,
+        audio: Vec<AudioTrack<'_>>,
     ) -> Result<Self, InitError> {
         output.set_extension("mov");
 
@@ -38,12 +54,14 @@ impl MOVFile {
 
         let mut output = format::output_as(&output, "mov").map_err(InitError::Ffmpeg)?;
         let video = video(&mut output).map_err(InitError::VideoInit)?;
+        let audio = add_audio_tracks(&mut output, audio).map_err(InitError::AudioInit)?;
 
         output.write_header().map_err(InitError::Ffmpeg)?;
 
         Ok(Self {
             output,
             video,
+            audio,
             is_finished: false,
         })
     }
@@ -60,6 +78,16 @@ impl MOVFile {
         self.video.queue_frame(frame, timestamp, &mut self.output)
     }
 
+    pub fn queue_audio_track_frame(&mut self, track: usize, frame: frame::Audio) {
+        if self.is_finished {
+            return;
+        }
+
+        if let Some(audio) = self.audio.get_mut(track) {
+            audio.send_frame(frame, &mut self.output);
+        }
+    }
+
     pub fn finish(&mut self) -> Result<(), FinishError> {
         if self.is_finished {
             return Err(FinishError::AlreadyFinished);
@@ -70,6 +98,11 @@ impl MOVFile {
         self.video
             .flush(&mut self.output)
             .map_err(FinishError::WriteTrailerFailed)?;
+        for audio in &mut self.audio {
+            audio
+                .flush(&mut self.output)
+                .map_err(FinishError::WriteTrailerFailed)?;
+        }
         self.output
             .write_trailer()
             .map_err(FinishError::WriteTrailerFailed)?;
@@ -4,7 +4,7 @@ use std::{path::PathBuf, time::Duration};
 use tracing::*;
 
 use crate::{
-    audio::AudioEncoder,
+    audio::{AudioEncoder, AudioTrack, add_audio_tracks},
     h264,
     video::h264::{H264Encoder, H264EncoderError},
 };
@@ -14,7 +14,7 @@ pub struct MP4File {
     tag: &'static str,
     output: format::context::Output,
     video: H264Encoder,
-    audio: Option<Box<dyn AudioEncoder + Send>>,
+    audio: Vec<Box<dyn AudioEncoder + Send>>,
     is_finished: bool,
 }
 
@@ -44,13 +44,44 @@ pub struct FinishResult {
 impl MP4File {
     pub fn init(
         tag: &'static str,
-        mut output: PathBuf,
+        output: PathBuf,
         faststart: bool,
         video: impl FnOnce(&mut format::context::Output) -> Result<H264Encoder, H264EncoderError>,
         audio: impl FnOnce(
             &mut format::context::Output,
         )
             -> Option<Result<Box<dyn AudioEncoder + Send>, Box<dyn std::error::Error>>>,
+    ) -> Result<Self, InitError> {
+        Self::init_inner(tag, output, faststart, video, |o| {
+            audio(o)
+                .transpose()
+                .map(|encoder| encoder.into_iter().collect())
+        })
+    }
+
+    /// Like [`MP4File::init`], with any number of audio streams. The first
+    /// track is the one players default to.
+    pub fn init_with_audio_tracks(
+        tag: &'static str,
+        output: PathBuf,
+        faststart: bool,
+        video: impl FnOnce(&mut format::context::Output) -> Result<H264Encoder, H264EncoderError>,
+        audio: Vec<AudioTrack<'_>>,
+    ) -> Result<Self, InitError> {
+        Self::init_inner(tag, output, faststart, video, |o| {
+            add_audio_tracks(o, audio)
+        })
+    }
+
+    fn init_inner(
+        tag: &'static str,
+        mut output: PathBuf,
+        faststart: bool,
+        video: impl FnOnce(&mut format::context::Output) -> Result<H264Encoder, H264EncoderError>,
+        audio: impl FnOnce(
+            &mut format::context::Output,
+        )
+            -> Result<Vec<Box<dyn AudioEncoder + Send>>, Box<dyn std::error::Error>>,
     ) -> Result<Self, InitError> {
         output.set_extension("mp4");
 
@@ -63,9 +94,7 @@ impl MP4File {
         trace!("Preparing encoders for mp4 file");
 
         let video = video(&mut output).map_err(InitError::VideoInit)?;
-        let audio = audio(&mut output)
-            .transpose()
-            .map_err(InitError::AudioInit)?;
+        let audio = audio(&mut output).map_err(InitError::AudioInit)?;
 
         info!("Prepared encoders for mp4 file");
 
@@ -120,11 +149,15 @@ impl MP4File {
     }
 
     pub fn queue_audio_frame(&mut self, frame: frame::Audio) {
+        self.queue_audio_track_frame(0, frame);
+    }
+
+    pub fn queue_audio_track_frame(&mut self, track: usize, frame: frame::Audio) {
         if self.is_finished {
             return;
         }
 
-        let Some(audio) = &mut self.audio else {
+        let Some(audio) = self.audio.get_mut(track) else {
             return;
         };
 
@@ -144,16 +177,16 @@ impl MP4File {
             error!("Failed to finish video encoder: {e:#}");
         });
 
-        let audio_finish = self
-            .audio
-            .as_mut()
-            .map(|enc| {
-                tracing::info!("MP4Encoder: Flushing audio encoder");
-                enc.flush(&mut self.output).inspect_err(|e| {
-                    error!("Failed to finish audio encoder: {e:#}");
-                })
-            })
-            .unwrap_or(Ok(()));
+        let mut audio_finish = Ok(());
+        for enc in &mut self.audio {
+            tracing::info!("MP4Encoder: Flushing audio encoder");
+            let result = enc.flush(&mut self.output).inspect_err(|e| {
+                error!("Failed to finish audio encoder: {e:#}");
+            });
+            if audio_finish.is_ok() {
+                audio_finish = result;
+            }
+        }
 
         tracing::info!("MP4Encoder: Writing trailer");
         self.output
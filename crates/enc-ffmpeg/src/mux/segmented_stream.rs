@@ -25,6 +25,104 @@ pub struct DiskSpaceWarning {
 
 pub type DiskSpaceCallback = Arc<dyn Fn(DiskSpaceWarning) + Send + Sync>;
 
+type SealFn = dyn Fn(&Path) -> std::io::Result<u64> + Send + Sync;
+type WriteSealedFn = dyn Fn(&Path, &[u8]) -> std::io::Result<()> + Send + Sync;
+
+/// Encrypts what a muxer writes: each fragment once it is finished, and
+/// manifests as they are written, so neither is left on disk as plaintext.
+#[derive(Clone)]
+pub struct FragmentSealer {
+    seal: Arc<SealFn>,
+    write: Arc<WriteSealedFn>,
+}
+
+impl FragmentSealer {
+    /// `seal` rewrites a finished file in place and returns its new size;
+    /// `write` writes `contents` to a path already sealed.
+    pub fn new(
+        seal: impl Fn(&Path) -> std::io::Result<u64> + Send + Sync + 'static,
+        write: impl Fn(&Path, &[u8]) -> std::io::Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            seal: Arc::new(seal),
+            write: Arc::new(write),
+        }
+    }
+
+    /// Seals the file at `path` in place. Only called once nothing will write
+    /// to it again.
+    pub fn seal(&self, path: &Path) -> std::io::Result<u64> {
+        (self.seal)(path)
+    }
+
+    pub fn write(&self, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        (self.write)(path, contents)
+    }
+}
+
+/// Seals `path` if there is a sealer, returning the fragment's size
+/// afterwards. A fragment that fails to seal is kept as it is.
+pub(crate) fn seal_fragment(sealer: Option<&FragmentSealer>, path: &Path, file_size: u64) -> u64 {
+    let Some(sealer) = sealer else {
+        return file_size;
+    };
+
+    match sealer.seal(path) {
+        Ok(sealed_size) => sealed_size,
+        Err(e) => {
+            tracing::warn!("Failed to seal fragment {}: {e}", path.display());
+            file_size
+        }
+    }
+}
+
+/// Sidecar a replay buffer writes next to a segment manifest before deleting
+/// old segments, so the manifest writers stop listing them.
+pub const PRUNED_SEGMENTS_FILE: &str = "pruned.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, serde::Deserialize)]
+pub struct PrunedSegments {
+    /// Index of the newest deleted segment; every earlier one is gone too.
+    pub last_index: u32,
+    /// Where the first remaining segment starts on the track's timeline, in seconds.
+    pub end: f64,
+}
+
+impl PrunedSegments {
+    pub fn read(dir: &Path) -> Option<Self> {
+        let content = std::fs::read(dir.join(PRUNED_SEGMENTS_FILE)).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        atomic_write_json(&dir.join(PRUNED_SEGMENTS_FILE), self)
+    }
+
+    pub fn contains(&self, index: u32) -> bool {
+        index <= self.last_index
+    }
+}
+
+/// Whether a replay buffer has deleted the segment at `index`.
+pub(crate) fn is_pruned(pruned: Option<&PrunedSegments>, index: u32) -> bool {
+    pruned.is_some_and(|pruned| pruned.contains(index))
+}
+
+/// Writes a manifest, sealed when there is a sealer.
+pub(crate) fn write_manifest<T: Serialize>(
+    sealer: Option<&FragmentSealer>,
+    path: &Path,
+    manifest: &T,
+) -> std::io::Result<()> {
+    let Some(sealer) = sealer else {
+        return atomic_write_json(path, manifest);
+    };
+
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    sealer.write(path, json.as_bytes())
+}
+
 fn atomic_write_json<T: Serialize>(path: &Path, data: &T) -> std::io::Result<()> {
     let temp_path = path.with_extension("json.tmp");
     let json = serde_json::to_string_pretty(data)
@@ -93,6 +191,7 @@ pub struct SegmentedVideoEncoder {
     codec_info: CodecInfo,
 
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     init_notified: bool,
 }
@@ -285,6 +384,7 @@ impl SegmentedVideoEncoder {
             frames_since_pending_flush: 0,
             codec_info,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
             init_notified: false,
         };
@@ -298,6 +398,10 @@ impl SegmentedVideoEncoder {
         self.disk_space_callback = Some(callback);
     }
 
+    pub fn set_fragment_sealer(&mut self, sealer: FragmentSealer) {
+        self.fragment_sealer = Some(sealer);
+    }
+
     pub fn set_segment_callback(&mut self, tx: std::sync::mpsc::Sender<SegmentCompletedEvent>) {
         self.segment_tx = Some(tx);
         self.try_notify_init_segment();
@@ -312,11 +416,13 @@ impl SegmentedVideoEncoder {
             && meta.len() > 0
         {
             self.init_notified = true;
+            // The init segment is written once, before the first fragment.
+            let file_size = seal_fragment(self.fragment_sealer.as_ref(), &init_path, meta.len());
             self.notify_segment(SegmentCompletedEvent {
                 path: init_path,
                 index: 0,
                 duration: 0.0,
-                file_size: meta.len(),
+                file_size,
                 is_init: true,
                 media_type: SegmentMediaType::Video,
             });
@@ -419,6 +525,13 @@ impl SegmentedVideoEncoder {
         let file_found = resolved_path.exists();
 
         if file_found && file_size > 0 {
+            // A `.tmp` fragment is still open in the muxer; it is sealed on finish.
+            let file_size = if resolved_path == segment_path {
+                seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size)
+            } else {
+                file_size
+            };
+
             self.completed_segments.push(VideoSegmentInfo {
                 path: segment_path.clone(),
                 index: completed_index,
@@ -490,6 +603,12 @@ impl SegmentedVideoEncoder {
                 "Flushing previously pending segment"
             );
 
+            let file_size = if resolved_path == segment_path {
+                seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size)
+            } else {
+                file_size
+            };
+
             self.completed_segments.push(VideoSegmentInfo {
                 path: segment_path,
                 index,
@@ -521,9 +640,11 @@ impl SegmentedVideoEncoder {
     }
 
     fn write_in_progress_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let mut segments: Vec<SegmentEntry> = self
             .completed_segments
             .iter()
+            .filter(|s| !is_pruned(pruned.as_ref(), s.index))
             .map(|s| SegmentEntry {
                 path: s
                     .path
@@ -562,7 +683,7 @@ impl SegmentedVideoEncoder {
         };
 
         let manifest_path = self.base_path.join("manifest.json");
-        if let Err(e) = atomic_write_json(&manifest_path, &manifest) {
+        if let Err(e) = write_manifest(self.fragment_sealer.as_ref(), &manifest_path, &manifest) {
             tracing::warn!(
                 "Failed to write in-progress manifest to {}: {e}",
                 manifest_path.display()
@@ -590,6 +711,7 @@ impl SegmentedVideoEncoder {
         let end_timestamp =
             last_timestamp.unwrap_or_else(|| segment_start.unwrap_or(Duration::ZERO));
         self.collect_orphaned_segments(segment_start, end_timestamp, frames_before_flush);
+        self.seal_remaining_fragments();
 
         self.finalize_manifest();
 
@@ -618,12 +740,36 @@ impl SegmentedVideoEncoder {
             .unwrap_or(timestamp);
 
         self.collect_orphaned_segments(segment_start, effective_end_timestamp, frames_before_flush);
+        self.seal_remaining_fragments();
 
         self.finalize_manifest();
 
         Ok(())
     }
 
+    /// Seals any fragment that was still a `.tmp` file when it completed, and
+    /// the init segment if the muxer rewrote it on finish.
+    fn seal_remaining_fragments(&mut self) {
+        let Some(sealer) = self.fragment_sealer.clone() else {
+            return;
+        };
+
+        let init_path = self.init_segment_path();
+        if init_path.exists() {
+            seal_fragment(Some(&sealer), &init_path, 0);
+        }
+
+        for segment in &mut self.completed_segments {
+            if segment.path.exists() {
+                segment.file_size = Some(seal_fragment(
+                    Some(&sealer),
+                    &segment.path,
+                    segment.file_size.unwrap_or(0),
+                ));
+            }
+        }
+    }
+
     fn finalize_pending_tmp_files(&self) {
         let Ok(entries) = std::fs::read_dir(&self.base_path) else {
             return;
@@ -767,6 +913,9 @@ impl SegmentedVideoEncoder {
                     duration
                 );
 
+                let file_size =
+                    seal_fragment(self.fragment_sealer.as_ref(), &segment_path, file_size);
+
                 self.completed_segments.push(VideoSegmentInfo {
                     path: segment_path.clone(),
                     index,
@@ -789,6 +938,7 @@ impl SegmentedVideoEncoder {
     }
 
     fn finalize_manifest(&self) {
+        let pruned = PrunedSegments::read(&self.base_path);
         let total_duration: Duration = self.completed_segments.iter().map(|s| s.duration).sum();
 
         let manifest = Manifest {
@@ -799,6 +949,7 @@ impl SegmentedVideoEncoder {
             segments: self
                 .completed_segments
                 .iter()
+                .filter(|s| !is_pruned(pruned.as_ref(), s.index))
                 .map(|s| SegmentEntry {
                     path: s
                         .path
@@ -817,7 +968,7 @@ impl SegmentedVideoEncoder {
         };
 
         let manifest_path = self.base_path.join("manifest.json");
-        if let Err(e) = atomic_write_json(&manifest_path, &manifest) {
+        if let Err(e) = write_manifest(self.fragment_sealer.as_ref(), &manifest_path, &manifest) {
             tracing::warn!(
                 "Failed to write final manifest to {}: {e}",
                 manifest_path.display()
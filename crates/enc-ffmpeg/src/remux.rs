@@ -841,6 +841,88 @@ fn rescale_video_timestamps_inner(
     Ok(())
 }
 
+/// Stream-copies `input_path` to `output_path` with every timestamp shifted so the video track
+/// starts at zero, dropping audio that lands before the first video frame. Used for recordings cut
+/// from the middle of a segmented capture, whose timestamps still carry the offset of the segments
+/// that were left out.
+pub fn rebase_to_video_start(input_path: &Path, output_path: &Path) -> Result<(), RemuxError> {
+    suppress_ffmpeg_logs();
+    let result = rebase_to_video_start_inner(input_path, output_path);
+    restore_ffmpeg_logs();
+    result
+}
+
+fn rebase_to_video_start_inner(input_path: &Path, output_path: &Path) -> Result<(), RemuxError> {
+    use ffmpeg::Rescale;
+
+    let mut ictx = avformat::input(input_path)?;
+    let mut octx = avformat::output(output_path)?;
+
+    let (video_start, video_time_base) = ictx
+        .streams()
+        .find(|stream| stream.parameters().medium() == ffmpeg::media::Type::Video)
+        .map(|stream| (stream.start_time(), stream.time_base()))
+        .filter(|(start, _)| *start != ffmpeg::ffi::AV_NOPTS_VALUE)
+        .unwrap_or((0, ffmpeg::Rational::new(1, 1)));
+
+    let mut stream_mapping: Vec<Option<usize>> = Vec::new();
+    let mut output_stream_index = 0usize;
+
+    for input_stream in ictx.streams() {
+        let codec_params = input_stream.parameters();
+        let medium = codec_params.medium();
+
+        if medium == ffmpeg::media::Type::Video || medium == ffmpeg::media::Type::Audio {
+            stream_mapping.push(Some(output_stream_index));
+            output_stream_index += 1;
+
+            let mut output_stream = octx.add_stream(None)?;
+            output_stream.set_parameters(codec_params);
+            unsafe {
+                (*output_stream.as_mut_ptr()).time_base = (*input_stream.as_ptr()).time_base;
+            }
+        } else {
+            stream_mapping.push(None);
+        }
+    }
+
+    octx.write_header()?;
+
+    for (input_stream, packet) in ictx.packets() {
+        let Some(Some(output_index)) = stream_mapping.get(input_stream.index()) else {
+            continue;
+        };
+        let output_index = *output_index;
+        let mut packet = packet;
+        let output_time_base = octx.stream(output_index).unwrap().time_base();
+        packet.rescale_ts(input_stream.time_base(), output_time_base);
+
+        let offset = video_start.rescale(video_time_base, output_time_base);
+        let pts = packet.pts().map(|pts| pts - offset);
+        let is_video = input_stream.parameters().medium() == ffmpeg::media::Type::Video;
+        if !is_video && pts.is_some_and(|pts| pts < 0) {
+            continue;
+        }
+
+        unsafe {
+            if let Some(dts) = packet.dts() {
+                (*packet.as_mut_ptr()).dts = dts - offset;
+            }
+            if let Some(pts) = pts {
+                (*packet.as_mut_ptr()).pts = pts;
+            }
+        }
+
+        packet.set_stream(output_index);
+        packet.set_position(-1);
+        packet.write_interleaved(&mut octx)?;
+    }
+
+    octx.write_trailer()?;
+
+    Ok(())
+}
+
 pub fn merge_video_audio(
     video_path: &Path,
     audio_path: &Path,
@@ -31,6 +31,7 @@ pub struct H264EncoderBuilder {
     encoder_priority_override: Option<&'static [&'static str]>,
     is_export: bool,
     crf: Option<u8>,
+    bitrate: Option<usize>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -77,6 +78,7 @@ impl H264EncoderBuilder {
             encoder_priority_override: None,
             is_export: false,
             crf: None,
+            bitrate: None,
         }
     }
 
@@ -121,6 +123,26 @@ impl H264EncoderBuilder {
         self
     }
 
+    /// Aim for an average of `bitrate` bits per second instead of deriving it
+    /// from the bits per pixel. Ignored when a CRF is set.
+    pub fn with_bitrate(mut self, bitrate: usize) -> Self {
+        self.bitrate = Some(bitrate);
+        self
+    }
+
+    /// Bits per pixel that make `get_bitrate` come out at the requested
+    /// bitrate for this output size.
+    fn bpp_for_output(&self, width: u32, height: u32) -> f32 {
+        let Some(bitrate) = self.bitrate else {
+            return self.bpp;
+        };
+
+        let frame_rate =
+            self.input_config.frame_rate.0 as f32 / self.input_config.frame_rate.1.max(1) as f32;
+        let bits_per_bpp = get_bitrate(width, height, frame_rate, 1.0).max(1);
+        (bitrate as f64 / bits_per_bpp as f64) as f32
+    }
+
     pub fn with_encoder_priority_override(mut self, codecs: &'static [&'static str]) -> Self {
         self.encoder_priority_override = Some(codecs);
         self
@@ -148,6 +170,8 @@ impl H264EncoderBuilder {
             );
         }
 
+        let bpp = self.bpp_for_output(output_width, output_height);
+
         let candidates = get_codec_and_options(
             &input_config,
             self.preset,
@@ -171,7 +195,7 @@ impl H264EncoderBuilder {
                     &input_config,
                     output_width,
                     output_height,
-                    self.bpp,
+                    bpp,
                     self.crf,
                 )
             {
@@ -191,7 +215,7 @@ impl H264EncoderBuilder {
                 output,
                 output_width,
                 output_height,
-                self.bpp,
+                bpp,
                 self.external_conversion,
                 self.crf,
             ) {
@@ -313,6 +337,8 @@ impl H264EncoderBuilder {
             );
         }
 
+        let bpp = self.bpp_for_output(output_width, output_height);
+
         let candidates = get_codec_and_options(
             &input_config,
             self.preset,
@@ -336,7 +362,7 @@ impl H264EncoderBuilder {
                     &input_config,
                     output_width,
                     output_height,
-                    self.bpp,
+                    bpp,
                     self.crf,
                 )
             {
@@ -355,7 +381,7 @@ impl H264EncoderBuilder {
                 &input_config,
                 output_width,
                 output_height,
-                self.bpp,
+                bpp,
                 self.external_conversion,
                 self.crf,
             ) {
@@ -627,8 +653,14 @@ fn open_video_encoder_inner(
                 input_config.frame_rate.0 as f32 / input_config.frame_rate.1 as f32,
                 bpp,
             );
+            let max_bitrate = bitrate * 3 / 2;
             encoder.set_bit_rate(bitrate);
-            encoder.set_max_bit_rate(bitrate * 3 / 2);
+            encoder.set_max_bit_rate(max_bitrate);
+            // Encoders ignore maxrate without a VBV buffer to enforce it in.
+            unsafe {
+                (*encoder.as_mut_ptr()).rc_buffer_size =
+                    i32::try_from(max_bitrate * 2).unwrap_or(i32::MAX);
+            }
         }
 
         encoder.open_as_with(codec, encoder_options)?
@@ -429,6 +429,8 @@ async fn run_mp4_export(
         custom_bpp: None,
         force_ffmpeg_decoder: false,
         optimize_filesize: false,
+        target_file_size: None,
+        audio: Default::default(),
     };
 
     let total_frames = exporter_base.total_frames(fps);
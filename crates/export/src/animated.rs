@@ -0,0 +1,186 @@
+use cap_enc_animated::{AnimatedFormat, AnimatedQuality, AnimationFrames};
+use cap_project::XY;
+use cap_rendering::ProjectUniforms;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use tracing::{info, trace};
+
+use crate::ExporterBase;
+use crate::gif::{feed_frames, output_path_with_extension};
+
+/// Animated images are short loops for docs and chats, so they default to
+/// half of 1080p at 15 fps rather than the video defaults.
+pub const DEFAULT_FPS: u32 = 15;
+pub const DEFAULT_RESOLUTION_BASE: XY<u32> = XY { x: 960, y: 540 };
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Type)]
+pub struct WebpExportSettings {
+    pub fps: u32,
+    pub resolution_base: XY<u32>,
+    /// Lossy quality from 1-100 (default: 80)
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// Encode every pixel exactly instead of lossy (default: false)
+    #[serde(default)]
+    pub lossless: bool,
+    /// Times to play the animation, 0 for forever (default: 0)
+    #[serde(default)]
+    pub loop_count: Option<u16>,
+    /// Lower the quality until the file fits in this many bytes
+    #[serde(default)]
+    #[specta(type = Option<f64>)]
+    pub max_file_size: Option<u64>,
+}
+
+impl Default for WebpExportSettings {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            resolution_base: DEFAULT_RESOLUTION_BASE,
+            quality: None,
+            lossless: false,
+            loop_count: None,
+            max_file_size: None,
+        }
+    }
+}
+
+impl WebpExportSettings {
+    pub async fn export(
+        self,
+        base: ExporterBase,
+        on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<PathBuf, String> {
+        export_animated(
+            base,
+            AnimatedFormat::WebP,
+            self.fps,
+            self.resolution_base,
+            AnimatedQuality {
+                quality: self.quality.unwrap_or(80),
+                lossless: self.lossless,
+                loop_count: self.loop_count.unwrap_or(0),
+                max_file_size: self.max_file_size,
+            },
+            on_progress,
+        )
+        .await
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Type)]
+pub struct ApngExportSettings {
+    pub fps: u32,
+    pub resolution_base: XY<u32>,
+    /// Palette quality from 1-100, ignored when lossless (default: 80)
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// Keep full RGBA colour instead of a 256-colour palette (default: false)
+    #[serde(default)]
+    pub lossless: bool,
+    /// Times to play the animation, 0 for forever (default: 0)
+    #[serde(default)]
+    pub loop_count: Option<u16>,
+    /// Lower the quality until the file fits in this many bytes
+    #[serde(default)]
+    #[specta(type = Option<f64>)]
+    pub max_file_size: Option<u64>,
+}
+
+impl Default for ApngExportSettings {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            resolution_base: DEFAULT_RESOLUTION_BASE,
+            quality: None,
+            lossless: false,
+            loop_count: None,
+            max_file_size: None,
+        }
+    }
+}
+
+impl ApngExportSettings {
+    pub async fn export(
+        self,
+        base: ExporterBase,
+        on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<PathBuf, String> {
+        export_animated(
+            base,
+            AnimatedFormat::Apng,
+            self.fps,
+            self.resolution_base,
+            AnimatedQuality {
+                quality: self.quality.unwrap_or(80),
+                lossless: self.lossless,
+                loop_count: self.loop_count.unwrap_or(0),
+                max_file_size: self.max_file_size,
+            },
+            on_progress,
+        )
+        .await
+    }
+}
+
+async fn export_animated(
+    base: ExporterBase,
+    format: AnimatedFormat,
+    fps: u32,
+    resolution_base: XY<u32>,
+    quality: AnimatedQuality,
+    on_progress: impl FnMut(u32) -> bool + Send + 'static,
+) -> Result<PathBuf, String> {
+    let (width, height) = ProjectUniforms::get_output_size(
+        base.render_constants.options(),
+        &base.project_config,
+        resolution_base,
+    );
+
+    // APNG files conventionally keep the .png extension.
+    let (name, extension) = match format {
+        AnimatedFormat::WebP => ("WebP", "webp"),
+        AnimatedFormat::Apng => ("APNG", "png"),
+    };
+    let output_path = output_path_with_extension(&base, extension)?;
+
+    trace!("Collecting {name} frames for '{}'", output_path.display());
+
+    let frames = AnimationFrames::new(width, height, fps)
+        .map_err(|e| format!("Failed to create {name} encoder: {e}"))?;
+
+    let frames = feed_frames(
+        &base,
+        fps,
+        resolution_base,
+        on_progress,
+        frames,
+        move |frames, frame| {
+            frames
+                .add_frame(&frame.data, frame.padded_bytes_per_row as usize)
+                .map_err(|e| format!("Failed to add frame to {name}: {e}"))
+        },
+    )
+    .await?;
+
+    let encoded =
+        tokio::task::spawn_blocking(move || cap_enc_animated::encode(&frames, format, &quality))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to encode {name}: {e}"))?;
+
+    if quality.max_file_size.is_some() {
+        info!(
+            "Encoded {name} at quality {}{} ({} bytes)",
+            encoded.quality,
+            if encoded.lossless { ", lossless" } else { "" },
+            encoded.data.len()
+        );
+    }
+
+    std::fs::write(&output_path, &encoded.data)
+        .map_err(|e| format!("Failed to write {}: {e}", output_path.display()))?;
+
+    Ok(output_path)
+}
@@ -0,0 +1,441 @@
+//! Audio shared by the MP4, MOV and stems exporters: loudness normalisation
+//! of the final mix and separate per-source tracks.
+
+use cap_editor::{
+    AudioRenderer, AudioSegment, MusicTracks, SegmentMedia, get_audio_segments,
+    get_audio_segments_for_sources, load_music_tracks_uncached,
+};
+use cap_project::ProjectConfiguration;
+use ebur128::{EbuR128, Mode};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tracing::{info, warn};
+
+use crate::ExporterBase;
+use crate::mp4::{audio_frame_budget, silent_audio_frame};
+
+/// True-peak ceiling used when normalising without an explicit limit.
+pub const DEFAULT_TRUE_PEAK_LIMIT: f32 = -1.0;
+
+/// Samples fed to the loudness meter per render call.
+const MEASURE_CHUNK_SAMPLES: usize = 4096;
+
+#[derive(Serialize, Deserialize, Type, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExportAudioSettings {
+    /// Normalise the mix to this integrated loudness in LUFS, e.g. -16
+    #[serde(default)]
+    pub loudness_target: Option<f32>,
+    /// True-peak ceiling in dBTP when normalising (default: -1)
+    #[serde(default)]
+    pub true_peak_limit: Option<f32>,
+    /// Add mic, system audio and music as separate tagged tracks after the mix
+    #[serde(default)]
+    pub separate_tracks: bool,
+}
+
+/// A single audio source rendered on its own.
+#[derive(Serialize, Deserialize, Type, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioStem {
+    Mic,
+    SystemAudio,
+    Music,
+}
+
+impl AudioStem {
+    pub const ALL: [Self; 3] = [Self::Mic, Self::SystemAudio, Self::Music];
+
+    /// File-name friendly identifier.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mic => "mic",
+            Self::SystemAudio => "system-audio",
+            Self::Music => "music",
+        }
+    }
+
+    /// Track title shown by players and editors.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Mic => "Microphone",
+            Self::SystemAudio => "System Audio",
+            Self::Music => "Music",
+        }
+    }
+}
+
+/// Integrated loudness and true peak of a rendered mix.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Loudness {
+    pub integrated_lufs: f64,
+    pub true_peak_dbtp: f64,
+}
+
+/// Linear gain taking `loudness` to `target_lufs` without pushing the true
+/// peak over `peak_limit_dbtp`. The gain is static, so a mix whose peaks
+/// leave too little headroom ends up quieter than the target rather than
+/// being compressed. Silent mixes are left alone.
+pub(crate) fn normalisation_gain(
+    loudness: Loudness,
+    target_lufs: f64,
+    peak_limit_dbtp: f64,
+) -> f32 {
+    if !loudness.integrated_lufs.is_finite() {
+        return 1.0;
+    }
+
+    let loudness_gain_db = target_lufs - loudness.integrated_lufs;
+    let peak_headroom_db = peak_limit_dbtp - loudness.true_peak_dbtp;
+    let gain_db = loudness_gain_db.min(peak_headroom_db);
+
+    if gain_db < loudness_gain_db {
+        warn!(
+            target_lufs,
+            reached_lufs = loudness.integrated_lufs + gain_db,
+            "True-peak limit kept the mix below the loudness target"
+        );
+    }
+
+    10f64.powf(gain_db / 20.0) as f32
+}
+
+struct TrackData {
+    /// `None` for the mix.
+    stem: Option<AudioStem>,
+    segments: Vec<AudioSegment>,
+    music: MusicTracks,
+}
+
+impl TrackData {
+    fn renderer(&self) -> AudioRenderer {
+        AudioRenderer::new(self.segments.clone()).with_music(self.music.clone())
+    }
+}
+
+/// Decoded audio for each track of an export, gathered before the encoder
+/// thread starts. Renderers aren't `Send`, so they are built on that thread
+/// with [`ExportAudioSources::into_audio`].
+pub(crate) struct ExportAudioSources {
+    tracks: Vec<TrackData>,
+    /// The full mix, kept for measuring loudness when normalising.
+    loudness_reference: Option<TrackData>,
+    settings: ExportAudioSettings,
+    project: ProjectConfiguration,
+    duration_secs: f64,
+}
+
+impl ExportAudioSources {
+    /// Loads the mix (when `include_mix`) followed by each stem that has
+    /// something to play (when `include_stems`).
+    pub(crate) async fn load(
+        base: &ExporterBase,
+        settings: ExportAudioSettings,
+        include_mix: bool,
+        include_stems: bool,
+    ) -> Self {
+        let project = base.project_config.clone();
+        let music = load_music_tracks_uncached(&project, &base.project_path);
+        let recording_muted = project.audio.mute;
+
+        let mut mix = None;
+        if include_mix || settings.loudness_target.is_some() {
+            let segments = get_audio_segments(&base.segments).await;
+            let has_recording_audio = segments.first().filter(|_| !recording_muted).is_some();
+            if has_recording_audio || !music.is_empty() {
+                mix = Some(TrackData {
+                    stem: None,
+                    segments,
+                    music: music.clone(),
+                });
+            }
+        }
+
+        let mut stems = Vec::new();
+        if include_stems {
+            for stem in AudioStem::ALL {
+                if let Some(track) = load_stem(&base.segments, stem, &music, recording_muted).await
+                {
+                    stems.push(track);
+                }
+            }
+        }
+
+        let (tracks, loudness_reference) = match mix {
+            Some(mix) if include_mix => {
+                let mut tracks = vec![mix];
+                tracks.extend(stems);
+                (tracks, None)
+            }
+            mix => (stems, mix),
+        };
+
+        let duration_secs = base.duration();
+
+        Self {
+            tracks,
+            loudness_reference,
+            settings,
+            project,
+            duration_secs,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    pub(crate) fn track_count(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Stems in track order, `None` for the mix.
+    pub(crate) fn stems(&self) -> Vec<Option<AudioStem>> {
+        self.tracks.iter().map(|track| track.stem).collect()
+    }
+
+    /// Track titles to tag streams with. A lone mix stays untitled.
+    pub(crate) fn titles(&self) -> Vec<Option<&'static str>> {
+        let titled = self.tracks.len() > 1;
+        self.tracks
+            .iter()
+            .map(|track| match track.stem {
+                Some(stem) => Some(stem.title()),
+                None => titled.then_some("Mix"),
+            })
+            .collect()
+    }
+
+    /// Builds a renderer per track, first measuring the mix when normalising
+    /// so every track gets the same gain. Renders the whole mix once, so call
+    /// it off the async runtime.
+    pub(crate) fn into_audio(self, fps: u32) -> Result<ExportAudio, String> {
+        let gain = match self.settings.loudness_target {
+            Some(target) => {
+                let reference = self
+                    .loudness_reference
+                    .as_ref()
+                    .or_else(|| self.tracks.first().filter(|track| track.stem.is_none()));
+                match reference {
+                    Some(reference) => {
+                        let loudness = measure_loudness(
+                            reference.renderer(),
+                            &self.project,
+                            (self.duration_secs * AudioRenderer::SAMPLE_RATE as f64).ceil()
+                                as usize,
+                        )?;
+                        let gain = normalisation_gain(
+                            loudness,
+                            target as f64,
+                            self.settings
+                                .true_peak_limit
+                                .unwrap_or(DEFAULT_TRUE_PEAK_LIMIT)
+                                as f64,
+                        );
+                        info!(
+                            integrated_lufs = loudness.integrated_lufs,
+                            true_peak_dbtp = loudness.true_peak_dbtp,
+                            target_lufs = target,
+                            gain_db = 20.0 * (gain as f64).log10(),
+                            "Normalising export audio"
+                        );
+                        gain
+                    }
+                    None => 1.0,
+                }
+            }
+            None => 1.0,
+        };
+
+        let mut renderers = self
+            .tracks
+            .iter()
+            .map(|track| track.renderer().with_output_gain(gain))
+            .collect::<Vec<_>>();
+        for renderer in &mut renderers {
+            renderer.set_playhead(0.0, &self.project);
+        }
+
+        Ok(ExportAudio {
+            renderers,
+            project: self.project,
+            fps: u64::from(fps),
+            sample_cursor: 0,
+        })
+    }
+}
+
+async fn load_stem(
+    segments: &[SegmentMedia],
+    stem: AudioStem,
+    music: &MusicTracks,
+    recording_muted: bool,
+) -> Option<TrackData> {
+    let (include_mic, include_system_audio) = match stem {
+        AudioStem::Mic => (true, false),
+        AudioStem::SystemAudio => (false, true),
+        AudioStem::Music => (false, false),
+    };
+
+    let segments =
+        get_audio_segments_for_sources(segments, include_mic, include_system_audio).await;
+    let music = match stem {
+        AudioStem::Music if !music.is_empty() => music.clone(),
+        AudioStem::Music => return None,
+        AudioStem::Mic | AudioStem::SystemAudio => {
+            if recording_muted || segments.iter().all(|s| s.tracks.is_empty()) {
+                return None;
+            }
+            MusicTracks::new()
+        }
+    };
+
+    Some(TrackData {
+        stem: Some(stem),
+        segments,
+        music,
+    })
+}
+
+fn measure_loudness(
+    mut renderer: AudioRenderer,
+    project: &ProjectConfiguration,
+    total_samples: usize,
+) -> Result<Loudness, String> {
+    let channels = u32::from(AudioRenderer::CHANNELS);
+    let mut meter = EbuR128::new(
+        channels,
+        AudioRenderer::SAMPLE_RATE,
+        Mode::I | Mode::TRUE_PEAK,
+    )
+    .map_err(|e| format!("Failed to create loudness meter: {e}"))?;
+
+    renderer.set_playhead(0.0, project);
+    let mut rendered = 0;
+    while rendered < total_samples {
+        let samples = MEASURE_CHUNK_SAMPLES.min(total_samples - rendered);
+        let Some((written, data)) = renderer.render_frame_raw(samples, project) else {
+            break;
+        };
+        if written == 0 {
+            break;
+        }
+
+        meter
+            .add_frames_f32(&data)
+            .map_err(|e| format!("Failed to measure loudness: {e}"))?;
+        rendered += written;
+    }
+
+    let integrated_lufs = meter
+        .loudness_global()
+        .map_err(|e| format!("Failed to measure loudness: {e}"))?;
+    let mut true_peak = 0.0f64;
+    for channel in 0..channels {
+        true_peak = true_peak.max(
+            meter
+                .true_peak(channel)
+                .map_err(|e| format!("Failed to measure true peak: {e}"))?,
+        );
+    }
+
+    Ok(Loudness {
+        integrated_lufs,
+        true_peak_dbtp: 20.0 * true_peak.log10(),
+    })
+}
+
+/// Per-track renderers that hand out audio in step with the video frames.
+pub(crate) struct ExportAudio {
+    renderers: Vec<AudioRenderer>,
+    project: ProjectConfiguration,
+    fps: u64,
+    sample_cursor: u64,
+}
+
+impl ExportAudio {
+    /// Audio for output video frame `frame_number`, one frame per track in
+    /// track order. Empty when the frame is already covered.
+    pub(crate) fn render_frames(&mut self, frame_number: u32) -> Vec<ffmpeg::frame::Audio> {
+        let sample_rate = u64::from(AudioRenderer::SAMPLE_RATE);
+        let Some((pts, samples)) = audio_frame_budget(
+            u64::from(frame_number),
+            sample_rate,
+            self.fps,
+            self.sample_cursor,
+        ) else {
+            return Vec::new();
+        };
+        self.sample_cursor = pts as u64 + samples as u64;
+
+        self.renderers
+            .iter_mut()
+            .map(|renderer| {
+                let mut frame = renderer
+                    .render_frame(samples, &self.project)
+                    .unwrap_or_else(|| silent_audio_frame(samples));
+                frame.set_pts(Some(pts));
+                frame
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gain_db(gain: f32) -> f64 {
+        20.0 * (gain as f64).log10()
+    }
+
+    #[test]
+    fn quiet_mix_is_raised_to_the_target() {
+        let gain = normalisation_gain(
+            Loudness {
+                integrated_lufs: -26.0,
+                true_peak_dbtp: -12.0,
+            },
+            -16.0,
+            -1.0,
+        );
+        assert!((gain_db(gain) - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn true_peak_limit_caps_the_gain() {
+        let gain = normalisation_gain(
+            Loudness {
+                integrated_lufs: -26.0,
+                true_peak_dbtp: -4.0,
+            },
+            -16.0,
+            -1.0,
+        );
+        assert!((gain_db(gain) - 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn loud_mix_is_turned_down() {
+        let gain = normalisation_gain(
+            Loudness {
+                integrated_lufs: -9.0,
+                true_peak_dbtp: 0.0,
+            },
+            -16.0,
+            -1.0,
+        );
+        assert!((gain_db(gain) + 7.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn silent_mix_is_left_alone() {
+        let gain = normalisation_gain(
+            Loudness {
+                integrated_lufs: f64::NEG_INFINITY,
+                true_peak_dbtp: f64::NEG_INFINITY,
+            },
+            -16.0,
+            -1.0,
+        );
+        assert_eq!(gain, 1.0);
+    }
+}
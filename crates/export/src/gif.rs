@@ -37,29 +37,15 @@ impl GifExportSettings {
     pub async fn export(
         self,
         base: ExporterBase,
-        mut on_progress: impl FnMut(u32) -> bool + Send + 'static,
+        on_progress: impl FnMut(u32) -> bool + Send + 'static,
     ) -> Result<PathBuf, String> {
-        let meta = &base.studio_meta;
-
-        let (tx_image_data, mut video_rx) = tokio::sync::mpsc::channel::<(RenderedFrame, u32)>(4);
-
-        let fps = self.fps;
-
         let output_size = ProjectUniforms::get_output_size(
-            &base.render_constants.options,
+            base.render_constants.options(),
             &base.project_config,
             self.resolution_base,
         );
 
-        // Ensure the output path has .gif extension
-        let mut gif_output_path = base.output_path.clone();
-        if gif_output_path.extension() != Some(std::ffi::OsStr::new("gif")) {
-            gif_output_path.set_extension("gif");
-        }
-
-        if let Some(parent) = gif_output_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-        }
+        let gif_output_path = output_path_with_extension(&base, "gif")?;
 
         trace!(
             "Creating GIF encoder at path '{}'",
@@ -75,51 +61,91 @@ impl GifExportSettings {
             })
             .unwrap_or_default();
 
-        let mut gif_encoder = cap_enc_gif::GifEncoderWrapper::new_with_quality(
+        let gif_encoder = cap_enc_gif::GifEncoderWrapper::new_with_quality(
             &gif_output_path,
             output_size.0,
             output_size.1,
-            fps,
+            self.fps,
             quality,
         )
         .map_err(|e| format!("Failed to create GIF encoder: {e}"))?;
 
-        let encoder_thread = tokio::task::spawn_blocking(move || {
-            let mut frame_count = 0;
+        let gif_encoder = feed_frames(
+            &base,
+            self.fps,
+            self.resolution_base,
+            on_progress,
+            gif_encoder,
+            |gif_encoder, frame| {
+                gif_encoder
+                    .add_frame(&frame.data, frame.padded_bytes_per_row as usize)
+                    .map_err(|e| format!("Failed to add frame to GIF: {e}"))
+            },
+        )
+        .await?;
 
-            while let Some((frame, _frame_number)) = video_rx.blocking_recv() {
-                if !(on_progress)(frame_count) {
-                    return Err(ExportError::Other("Export cancelled".to_string()));
-                }
+        tokio::task::spawn_blocking(move || gif_encoder.finish())
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to finish GIF: {e}"))?;
 
-                if let Err(e) =
-                    gif_encoder.add_frame(&frame.data, frame.padded_bytes_per_row as usize)
-                {
-                    return Err(ExportError::Other(format!(
-                        "Failed to add frame to GIF: {e}"
-                    )));
-                }
+        Ok(gif_output_path)
+    }
+}
 
-                frame_count += 1;
-            }
+/// The export's output path with `extension`, with its directory created.
+pub(crate) fn output_path_with_extension(
+    base: &ExporterBase,
+    extension: &str,
+) -> Result<PathBuf, String> {
+    let mut output_path = base.output_path.clone();
+    if output_path.extension() != Some(std::ffi::OsStr::new(extension)) {
+        output_path.set_extension(extension);
+    }
+
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
 
-            if let Err(e) = gif_encoder.finish() {
-                return Err(ExportError::Other(format!("Failed to finish GIF: {e}")));
+    Ok(output_path)
+}
+
+/// Renders the project and passes each frame to `add_frame` on a blocking
+/// thread. This is the frame feed shared by every animated image format;
+/// `encoder` is handed back once the last frame is in.
+pub(crate) async fn feed_frames<E: Send + 'static>(
+    base: &ExporterBase,
+    fps: u32,
+    resolution_base: XY<u32>,
+    mut on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    mut encoder: E,
+    mut add_frame: impl FnMut(&mut E, &RenderedFrame) -> Result<(), String> + Send + 'static,
+) -> Result<E, String> {
+    let (tx_image_data, mut video_rx) = tokio::sync::mpsc::channel::<(RenderedFrame, u32)>(4);
+
+    let encoder_thread = tokio::task::spawn_blocking(move || {
+        let mut frame_count = 0;
+
+        while let Some((frame, _frame_number)) = video_rx.blocking_recv() {
+            if !(on_progress)(frame_count) {
+                return Err(ExportError::Other("Export cancelled".to_string()));
             }
 
-            Ok(gif_output_path)
-        })
-        .then(|f| async {
-            f.map_err(|e| e.to_string())
-                .and_then(|v| v.map_err(|v| v.to_string()))
-        });
+            add_frame(&mut encoder, &frame).map_err(ExportError::Other)?;
 
-        let render_video_task = cap_rendering::render_video_to_channel(
-            &base.render_constants,
-            &base.project_config,
+            frame_count += 1;
+        }
+
+        Ok(encoder)
+    })
+    .then(|f| async {
+        f.map_err(|e| e.to_string())
+            .and_then(|v| v.map_err(|v| v.to_string()))
+    });
+
+    let render_video_task = base
+        .render_video_to_channel(
             tx_image_data,
-            &base.recording_meta,
-            meta,
             base.segments
                 .iter()
                 .map(|s| RenderSegment {
@@ -130,14 +156,12 @@ impl GifExportSettings {
                 })
                 .collect(),
             fps,
-            self.resolution_base,
-            &base.recordings,
+            resolution_base,
         )
         .then(|f| async { f.map_err(|v| v.to_string()) });
 
-        let (output_path, _) =
-            tokio::try_join!(encoder_thread, render_video_task).map_err(|e| e.to_string())?;
+    let (encoder, _) =
+        tokio::try_join!(encoder_thread, render_video_task).map_err(|e| e.to_string())?;
 
-        Ok(output_path)
-    }
+    Ok(encoder)
 }
@@ -1,15 +1,19 @@
+pub mod animated;
+pub mod audio;
 pub mod gif;
 pub mod mov;
 pub mod mp4;
 pub mod preview;
 pub mod settings;
+pub mod shortcuts;
+pub mod stems;
+pub mod thumbnails;
 
 use cap_editor::SegmentMedia;
-use cap_project::{
-    BackgroundSource, ProjectConfiguration, RecordingMeta, StudioRecordingMeta,
-    TimelineConfiguration, TimelineSegment,
+use cap_project::{BackgroundSource, ProjectConfiguration, RecordingMeta, StudioRecordingMeta};
+use cap_rendering::{
+    CpuRenderConstants, ProjectRecordingsMeta, RenderOptions, RenderVideoConstants,
 };
-use cap_rendering::{ProjectRecordingsMeta, RenderVideoConstants};
 use std::{path::PathBuf, sync::Arc};
 
 #[derive(thiserror::Error, Debug)]
@@ -54,11 +58,22 @@ pub enum ExporterBuildError {
     IO(PathBuf, std::io::Error),
 }
 
+/// Which backend rasterizes frames during export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Renderer {
+    /// The wgpu compositor.
+    #[default]
+    Gpu,
+    /// The Skia raster compositor, for hosts without a usable GPU.
+    Cpu,
+}
+
 pub struct ExporterBuilder {
     project_path: PathBuf,
     config: Option<ProjectConfiguration>,
     output_path: Option<PathBuf>,
     force_ffmpeg_decoder: bool,
+    renderer: Renderer,
 }
 
 impl ExporterBuilder {
@@ -77,6 +92,11 @@ impl ExporterBuilder {
         self
     }
 
+    pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
     pub async fn build(self) -> Result<ExporterBase, ExporterBuildError> {
         type Error = ExporterBuildError;
 
@@ -105,47 +125,32 @@ impl ExporterBuilder {
         // Desktop exports already carry a timeline by export time, so this only fires for un-edited
         // projects and changes nothing for them.
         if project_config.timeline.is_none() {
-            let segments: Vec<TimelineSegment> = recordings
-                .segments
-                .iter()
-                .enumerate()
-                .filter_map(|(i, segment)| {
-                    let duration = segment.duration();
-                    (duration > 0.0).then_some(TimelineSegment {
-                        recording_clip: i as u32,
-                        start: 0.0,
-                        end: duration,
-                        timescale: 1.0,
-                        name: None,
-                        speed_audio_mode: None,
-                    })
-                })
-                .collect();
-            if !segments.is_empty() {
-                project_config.timeline = Some(TimelineConfiguration {
-                    segments,
-                    transitions: Vec::new(),
-                    zoom_segments: Vec::new(),
-                    scene_segments: Vec::new(),
-                    mask_segments: Vec::new(),
-                    text_segments: Vec::new(),
-                    caption_segments: Vec::new(),
-                    keyboard_segments: Vec::new(),
-                    audio_segments: Vec::new(),
-                    camera3d_segments: Vec::new(),
-                });
-            }
+            project_config.timeline = recordings.default_timeline();
         }
 
-        let render_constants = Arc::new(
-            RenderVideoConstants::new(
-                &recordings.segments,
-                recording_meta.clone(),
-                studio_meta.clone(),
-            )
-            .await
-            .map_err(Error::RendererSetup)?,
-        );
+        let render_constants = match self.renderer {
+            Renderer::Gpu => RenderBackend::Gpu(Arc::new(
+                RenderVideoConstants::new(
+                    &recordings.segments,
+                    recording_meta.clone(),
+                    studio_meta.clone(),
+                )
+                .await
+                .map_err(Error::RendererSetup)?,
+            )),
+            Renderer::Cpu => {
+                let constants = CpuRenderConstants::new(
+                    &recordings.segments,
+                    recording_meta.clone(),
+                    studio_meta.clone(),
+                )
+                .map_err(Error::RendererSetup)?;
+                constants
+                    .ensure_supported(&project_config)
+                    .map_err(Error::RendererSetup)?;
+                RenderBackend::Cpu(Arc::new(constants))
+            }
+        };
 
         let segments =
             cap_editor::create_segments(&recording_meta, studio_meta, self.force_ffmpeg_decoder)
@@ -157,6 +162,9 @@ impl ExporterBuilder {
         for segment in &segments {
             segment.audio.get().await.map_err(Error::MediaLoad)?;
             segment.system_audio.get().await.map_err(Error::MediaLoad)?;
+            for mic in &segment.additional_mics {
+                mic.audio.get().await.map_err(Error::MediaLoad)?;
+            }
         }
 
         let output_path = self
@@ -214,27 +222,44 @@ pub fn make_cursor_only_project(mut project_config: ProjectConfiguration) -> Pro
     project_config
 }
 
+pub(crate) enum RenderBackend {
+    Gpu(Arc<RenderVideoConstants>),
+    Cpu(Arc<CpuRenderConstants>),
+}
+
+impl RenderBackend {
+    pub(crate) fn options(&self) -> &RenderOptions {
+        match self {
+            Self::Gpu(constants) => &constants.options,
+            Self::Cpu(constants) => &constants.options,
+        }
+    }
+}
+
 pub struct ExporterBase {
     project_path: PathBuf,
     recording_meta: RecordingMeta,
     project_config: ProjectConfiguration,
     studio_meta: StudioRecordingMeta,
     recordings: Arc<ProjectRecordingsMeta>,
-    render_constants: Arc<RenderVideoConstants>,
+    render_constants: RenderBackend,
     segments: Vec<SegmentMedia>,
     output_path: PathBuf,
 }
 
 impl ExporterBase {
     pub fn total_frames(&self, fps: u32) -> u32 {
-        let duration = cap_rendering::get_duration(
+        (fps as f64 * self.duration()).ceil() as u32
+    }
+
+    /// Output duration in seconds.
+    pub fn duration(&self) -> f64 {
+        cap_rendering::get_duration(
             &self.recordings,
             &self.recording_meta,
             &self.studio_meta,
             &self.project_config,
-        );
-
-        (fps as f64 * duration).ceil() as u32
+        )
     }
 
     pub fn builder(project_path: PathBuf) -> ExporterBuilder {
@@ -243,6 +268,47 @@ impl ExporterBase {
             config: None,
             output_path: None,
             force_ffmpeg_decoder: false,
+            renderer: Renderer::default(),
+        }
+    }
+
+    /// Renders RGBA frames with whichever backend the exporter was built with.
+    pub(crate) async fn render_video_to_channel(
+        &self,
+        sender: tokio::sync::mpsc::Sender<(cap_rendering::RenderedFrame, u32)>,
+        render_segments: Vec<cap_rendering::RenderSegment>,
+        fps: u32,
+        resolution_base: cap_project::XY<u32>,
+    ) -> Result<(), cap_rendering::RenderingError> {
+        match &self.render_constants {
+            RenderBackend::Gpu(constants) => {
+                cap_rendering::render_video_to_channel(
+                    constants,
+                    &self.project_config,
+                    sender,
+                    &self.recording_meta,
+                    &self.studio_meta,
+                    render_segments,
+                    fps,
+                    resolution_base,
+                    &self.recordings,
+                )
+                .await
+            }
+            RenderBackend::Cpu(constants) => {
+                cap_rendering::render_video_to_channel_cpu(
+                    constants,
+                    &self.project_config,
+                    sender,
+                    &self.recording_meta,
+                    &self.studio_meta,
+                    render_segments,
+                    fps,
+                    resolution_base,
+                    &self.recordings,
+                )
+                .await
+            }
         }
     }
 }
@@ -1,4 +1,10 @@
-use cap_enc_ffmpeg::{mov::MOVFile, prores::ProResEncoder};
+use cap_editor::AudioRenderer;
+use cap_enc_ffmpeg::{
+    AudioEncoder, AudioTrack,
+    lossless::{LosslessAudioEncoder, LosslessAudioFormat},
+    mov::MOVFile,
+    prores::ProResEncoder,
+};
 use cap_media_info::{RawVideoFormat, VideoInfo};
 use cap_project::XY;
 use cap_rendering::{ProjectUniforms, RenderSegment, RenderedFrame};
@@ -7,7 +13,10 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::{path::PathBuf, time::Duration};
 
-use crate::{ExportError, ExporterBase};
+use crate::{
+    ExportError, ExporterBase,
+    audio::{ExportAudioSettings, ExportAudioSources},
+};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Type)]
 pub struct MovExportSettings {
@@ -15,6 +24,10 @@ pub struct MovExportSettings {
     pub resolution_base: XY<u32>,
     #[serde(default)]
     pub cursor_only: bool,
+    /// MOV exports are silent unless normalisation or separate tracks are
+    /// requested; tracks are written as 24-bit PCM.
+    #[serde(default)]
+    pub audio: ExportAudioSettings,
 }
 
 impl MovExportSettings {
@@ -23,13 +36,11 @@ impl MovExportSettings {
         base: ExporterBase,
         mut on_progress: impl FnMut(u32) -> bool + Send + 'static,
     ) -> Result<PathBuf, String> {
-        let meta = &base.studio_meta;
-
         let (tx_image_data, mut video_rx) = tokio::sync::mpsc::channel::<(RenderedFrame, u32)>(4);
         let fps = self.fps;
 
         let output_size = ProjectUniforms::get_output_size(
-            &base.render_constants.options,
+            base.render_constants.options(),
             &base.project_config,
             self.resolution_base,
         );
@@ -46,10 +57,48 @@ impl MovExportSettings {
         let video_info =
             VideoInfo::from_raw(RawVideoFormat::Rgba, output_size.0, output_size.1, fps);
 
+        let wants_audio = self.audio.separate_tracks || self.audio.loudness_target.is_some();
+        let audio_sources = if wants_audio {
+            Some(
+                ExportAudioSources::load(&base, self.audio, true, self.audio.separate_tracks).await,
+            )
+        } else {
+            None
+        };
+
         let encoder_thread = tokio::task::spawn_blocking(move || {
-            let mut mov_encoder = MOVFile::init(mov_output_path.clone(), |output| {
-                ProResEncoder::builder(video_info).build(output)
-            })
+            let (mut audio, audio_tracks) = match audio_sources {
+                Some(sources) if !sources.is_empty() => {
+                    let tracks = sources
+                        .titles()
+                        .into_iter()
+                        .map(|title| {
+                            let track = AudioTrack::new(|o| {
+                                LosslessAudioEncoder::init(
+                                    AudioRenderer::info(),
+                                    LosslessAudioFormat::Wav,
+                                    o,
+                                )
+                                .map(|v| v.boxed())
+                                .map_err(Into::into)
+                            });
+                            match title {
+                                Some(title) => track.with_title(title),
+                                None => track,
+                            }
+                        })
+                        .collect();
+                    let audio = sources.into_audio(fps).map_err(ExportError::Other)?;
+                    (Some(audio), tracks)
+                }
+                _ => (None, Vec::new()),
+            };
+
+            let mut mov_encoder = MOVFile::init_with_audio_tracks(
+                mov_output_path.clone(),
+                |output| ProResEncoder::builder(video_info).build(output),
+                audio_tracks,
+            )
             .map_err(|e| ExportError::Other(format!("Failed to create MOV encoder: {e}")))?;
 
             let mut reusable_frame = ffmpeg::frame::Video::new(
@@ -72,6 +121,13 @@ impl MovExportSettings {
                     .queue_video_frame(&mut reusable_frame, timestamp)
                     .map_err(|e| ExportError::Other(format!("Failed to encode MOV frame: {e}")))?;
 
+                if let Some(audio) = &mut audio {
+                    for (track, frame) in audio.render_frames(frame_number).into_iter().enumerate()
+                    {
+                        mov_encoder.queue_audio_track_frame(track, frame);
+                    }
+                }
+
                 frame_count += 1;
             }
 
@@ -86,26 +142,22 @@ impl MovExportSettings {
                 .and_then(|v| v.map_err(|v| v.to_string()))
         });
 
-        let render_video_task = cap_rendering::render_video_to_channel(
-            &base.render_constants,
-            &base.project_config,
-            tx_image_data,
-            &base.recording_meta,
-            meta,
-            base.segments
-                .iter()
-                .map(|s| RenderSegment {
-                    cursor: s.cursor.clone(),
-                    keyboard: s.keyboard.clone(),
-                    decoders: s.decoders.clone(),
-                    render_display: false,
-                })
-                .collect(),
-            fps,
-            self.resolution_base,
-            &base.recordings,
-        )
-        .then(|f| async { f.map_err(|v| v.to_string()) });
+        let render_video_task = base
+            .render_video_to_channel(
+                tx_image_data,
+                base.segments
+                    .iter()
+                    .map(|s| RenderSegment {
+                        cursor: s.cursor.clone(),
+                        keyboard: s.keyboard.clone(),
+                        decoders: s.decoders.clone(),
+                        render_display: false,
+                    })
+                    .collect(),
+                fps,
+                self.resolution_base,
+            )
+            .then(|f| async { f.map_err(|v| v.to_string()) });
 
         let (output_path, _) =
             tokio::try_join!(encoder_thread, render_video_task).map_err(|e| e.to_string())?;
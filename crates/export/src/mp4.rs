@@ -1,6 +1,7 @@
 use crate::ExporterBase;
-use cap_editor::{AudioRenderer, get_audio_segments, load_music_tracks_uncached};
-use cap_enc_ffmpeg::{AudioEncoder, aac::AACEncoder, h264::H264Encoder, mp4::*};
+use crate::audio::{ExportAudioSettings, ExportAudioSources};
+use cap_editor::AudioRenderer;
+use cap_enc_ffmpeg::{AudioEncoder, AudioTrack, aac::AACEncoder, h264::H264Encoder, mp4::*};
 use cap_media_info::{RawVideoFormat, VideoInfo};
 use cap_project::XY;
 use cap_rendering::{
@@ -48,9 +49,48 @@ impl ExportCompression {
     }
 }
 
+/// Share of a size target left for MP4 container overhead.
+const TARGET_SIZE_CONTAINER_OVERHEAD: f64 = 0.02;
+/// Size targets that leave less than this for video are rejected rather than
+/// producing an unwatchable file.
+const MIN_TARGET_VIDEO_BITRATE: usize = 100_000;
+/// Encodes tried before giving up on a size target.
+const MAX_TARGET_SIZE_ATTEMPTS: u32 = 3;
+
+#[derive(Clone, Copy, Debug)]
+struct SizeTarget {
+    bytes: u64,
+    /// Applied to the computed bitrate; lowered after an attempt overshoots.
+    scale: f64,
+}
+
+impl SizeTarget {
+    /// Video bitrate that fits `duration_secs` of video plus `audio_bitrate`
+    /// audio within the target.
+    fn video_bitrate(&self, duration_secs: f64, audio_bitrate: usize) -> Result<usize, String> {
+        let available_bits = self.bytes as f64 * 8.0 * (1.0 - TARGET_SIZE_CONTAINER_OVERHEAD);
+        let total_bitrate = available_bits / duration_secs.max(0.001);
+        let video_bitrate = (total_bitrate - audio_bitrate as f64) * self.scale;
+
+        if video_bitrate < MIN_TARGET_VIDEO_BITRATE as f64 {
+            let needed_bytes = (MIN_TARGET_VIDEO_BITRATE + audio_bitrate) as f64 * duration_secs
+                / 8.0
+                / (1.0 - TARGET_SIZE_CONTAINER_OVERHEAD);
+            return Err(format!(
+                "Target size of {} bytes is too small for a {duration_secs:.1}s export; at least {} bytes are needed",
+                self.bytes,
+                needed_bytes.ceil() as u64
+            ));
+        }
+
+        Ok(video_bitrate as usize)
+    }
+}
+
 #[derive(Clone, Default)]
 struct ExportNv12Mode {
     stop_after_frames_sent: Option<u32>,
+    target_size: Option<SizeTarget>,
     record_first_queued_ms_since_pipeline: Option<Arc<AtomicU64>>,
     nv12_render_startup_breakdown_ms:
         Option<Arc<Mutex<Option<cap_rendering::Nv12RenderStartupBreakdownMs>>>>,
@@ -72,6 +112,14 @@ pub struct Mp4ExportSettings {
     pub force_ffmpeg_decoder: bool,
     #[serde(default)]
     pub optimize_filesize: bool,
+    /// Fit the file within this many bytes by deriving the bitrate from the
+    /// duration, re-encoding if the result overshoots. Takes precedence over
+    /// `compression`, `custom_bpp` and CRF encoding.
+    #[serde(default)]
+    #[specta(type = Option<f64>)]
+    pub target_file_size: Option<u64>,
+    #[serde(default)]
+    pub audio: ExportAudioSettings,
 }
 
 impl Mp4ExportSettings {
@@ -93,7 +141,7 @@ impl Mp4ExportSettings {
         let fps = self.fps;
 
         let output_size = ProjectUniforms::get_output_size(
-            &base.render_constants.options,
+            base.render_constants.options(),
             &base.project_config,
             self.resolution_base,
         );
@@ -103,14 +151,87 @@ impl Mp4ExportSettings {
             height = output_size.1,
             "Exporting with NV12 pipeline (GPU when possible, CPU fallback otherwise)"
         );
-        self.export_nv12(
-            base,
-            output_size,
-            fps,
-            on_progress,
-            ExportNv12Mode::default(),
-        )
-        .await
+
+        match self.target_file_size {
+            Some(target) => {
+                self.export_to_target_size(&base, output_size, fps, target, on_progress)
+                    .await
+            }
+            None => {
+                self.export_nv12(
+                    &base,
+                    output_size,
+                    fps,
+                    on_progress,
+                    ExportNv12Mode::default(),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn export_to_target_size(
+        self,
+        base: &ExporterBase,
+        output_size: (u32, u32),
+        fps: u32,
+        target_bytes: u64,
+        on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<PathBuf, String> {
+        let on_progress = Arc::new(Mutex::new(on_progress));
+        let mut target = SizeTarget {
+            bytes: target_bytes,
+            scale: 1.0,
+        };
+        let mut last_size = 0;
+
+        for attempt in 1..=MAX_TARGET_SIZE_ATTEMPTS {
+            let on_progress = Arc::clone(&on_progress);
+            let output_path = self
+                .export_nv12(
+                    base,
+                    output_size,
+                    fps,
+                    move |frame| {
+                        on_progress
+                            .lock()
+                            .map(|mut on_progress| on_progress(frame))
+                            .unwrap_or(false)
+                    },
+                    ExportNv12Mode {
+                        target_size: Some(target),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+            last_size = std::fs::metadata(&output_path)
+                .map_err(|e| format!("Failed to read {}: {e}", output_path.display()))?
+                .len();
+
+            if last_size <= target_bytes {
+                info!(
+                    attempt,
+                    size = last_size,
+                    target = target_bytes,
+                    "Export fits target size"
+                );
+                return Ok(output_path);
+            }
+
+            warn!(
+                attempt,
+                size = last_size,
+                target = target_bytes,
+                "Export overshot target size, re-encoding at a lower bitrate"
+            );
+            // Aim slightly under so the next attempt doesn't land just over.
+            target.scale *= target_bytes as f64 / last_size as f64 * 0.97;
+        }
+
+        Err(format!(
+            "Could not fit the export in {target_bytes} bytes after {MAX_TARGET_SIZE_ATTEMPTS} attempts; the last was {last_size} bytes"
+        ))
     }
 
     pub async fn benchmark_first_frame_with_breakdown(
@@ -119,7 +240,7 @@ impl Mp4ExportSettings {
     ) -> Result<FirstFrameQueuedBenchmark, String> {
         let fps = self.fps;
         let output_size = ProjectUniforms::get_output_size(
-            &base.render_constants.options,
+            base.render_constants.options(),
             &base.project_config,
             self.resolution_base,
         );
@@ -128,7 +249,7 @@ impl Mp4ExportSettings {
         let breakdown = Arc::new(Mutex::new(None));
         let breakdown_enc = Arc::clone(&breakdown);
         self.export_nv12(
-            base,
+            &base,
             output_size,
             fps,
             |_| true,
@@ -136,6 +257,7 @@ impl Mp4ExportSettings {
                 stop_after_frames_sent: Some(1),
                 record_first_queued_ms_since_pipeline: Some(first_ms_enc),
                 nv12_render_startup_breakdown_ms: Some(breakdown_enc),
+                ..Default::default()
             },
         )
         .await?;
@@ -162,7 +284,7 @@ impl Mp4ExportSettings {
 
     async fn export_nv12(
         self,
-        base: ExporterBase,
+        base: &ExporterBase,
         output_size: (u32, u32),
         fps: u32,
         on_progress: impl FnMut(u32) -> bool + Send + 'static,
@@ -178,26 +300,51 @@ impl Mp4ExportSettings {
             VideoInfo::from_raw(RawVideoFormat::Nv12, output_size.0, output_size.1, fps);
         video_info.time_base = ffmpeg::Rational::new(1, fps as i32);
 
-        let audio_segments = get_audio_segments(&base.segments).await;
-        let music = load_music_tracks_uncached(&base.project_config, &base.project_path);
+        let audio_sources =
+            ExportAudioSources::load(base, self.audio, true, self.audio.separate_tracks).await;
+        let audio_titles = audio_sources.titles();
 
-        let has_recording_audio = audio_segments
-            .first()
-            .filter(|_| !base.project_config.audio.mute)
-            .is_some();
-        let has_audio = has_recording_audio || !music.is_empty();
+        let video_bitrate = mode
+            .target_size
+            .map(|target| {
+                let duration_secs = base.total_frames(fps) as f64 / fps as f64;
+                let audio_bitrate = AACEncoder::OUTPUT_BITRATE * audio_sources.track_count();
+                target.video_bitrate(duration_secs, audio_bitrate)
+            })
+            .transpose()?;
 
         let record_first_queued_ms = mode.record_first_queued_ms_since_pipeline;
         let nv12_render_startup_breakdown_ms = mode.nv12_render_startup_breakdown_ms;
 
-        let project_for_audio = base.project_config.clone();
         let pipeline_start_for_encoder = pipeline_start;
+        let encoder_output_path = output_path.clone();
         let encoder_thread = tokio::task::spawn_blocking(move || {
+            let mut audio = if audio_sources.is_empty() {
+                None
+            } else {
+                Some(audio_sources.into_audio(fps)?)
+            };
+
             trace!("Creating MP4File encoder (NV12 path)");
 
-            let mut encoder = MP4File::init(
+            let audio_tracks = audio_titles
+                .into_iter()
+                .map(|title| {
+                    let track = AudioTrack::new(|o| {
+                        AACEncoder::init(AudioRenderer::info(), o)
+                            .map(|v| v.boxed())
+                            .map_err(Into::into)
+                    });
+                    match title {
+                        Some(title) => track.with_title(title),
+                        None => track,
+                    }
+                })
+                .collect();
+
+            let mut encoder = MP4File::init_with_audio_tracks(
                 "output",
-                base.output_path.clone(),
+                encoder_output_path.clone(),
                 self.optimize_filesize,
                 |o| {
                     let builder = H264Encoder::builder(video_info)
@@ -205,31 +352,21 @@ impl Mp4ExportSettings {
                         .with_export_priority()
                         .with_export_settings()
                         .with_external_conversion();
-                    let builder = if self.optimize_filesize {
+                    let builder = if let Some(bitrate) = video_bitrate {
+                        builder.with_bitrate(bitrate)
+                    } else if self.optimize_filesize {
                         builder.with_crf(self.compression.crf_value())
                     } else {
                         builder
                     };
                     builder.build(o)
                 },
-                |o| {
-                    has_audio.then(|| {
-                        AACEncoder::init(AudioRenderer::info(), o)
-                            .map(|v| v.boxed())
-                            .map_err(Into::into)
-                    })
-                },
+                audio_tracks,
             )
             .map_err(|v| v.to_string())?;
 
             info!("Created MP4File encoder (NV12, external conversion, export settings)");
 
-            let mut audio_renderer = if has_audio {
-                Some(AudioRenderer::new(audio_segments).with_music(music))
-            } else {
-                None
-            };
-
             let mut reusable_frame = ffmpeg::frame::Video::new(
                 ffmpeg::format::Pixel::NV12,
                 output_size.0,
@@ -238,28 +375,12 @@ impl Mp4ExportSettings {
             let mut converted_frame: Option<ffmpeg::frame::Video> = None;
             let mut encoded_frames = 0u32;
             let encode_start = std::time::Instant::now();
-            let sample_rate = u64::from(AudioRenderer::SAMPLE_RATE);
-            let fps_u64 = u64::from(fps);
-            let mut audio_sample_cursor = 0u64;
 
             while let Ok(input) = frame_rx.recv() {
-                if encoded_frames == 0
-                    && let Some(audio) = &mut audio_renderer
-                {
-                    audio.set_playhead(0.0, &project_for_audio);
-                }
-
-                let audio_frame = audio_renderer.as_mut().and_then(|audio| {
-                    let n = u64::from(input.frame_number);
-                    let (pts, samples) =
-                        audio_frame_budget(n, sample_rate, fps_u64, audio_sample_cursor)?;
-                    audio_sample_cursor = pts as u64 + samples as u64;
-                    let mut frame = audio
-                        .render_frame(samples, &project_for_audio)
-                        .unwrap_or_else(|| silent_audio_frame(samples));
-                    frame.set_pts(Some(pts));
-                    Some(frame)
-                });
+                let audio_frames = audio
+                    .as_mut()
+                    .map(|audio| audio.render_frames(input.frame_number))
+                    .unwrap_or_default();
 
                 fill_nv12_frame_direct(
                     &mut reusable_frame,
@@ -276,8 +397,8 @@ impl Mp4ExportSettings {
                         Duration::MAX,
                     )
                     .map_err(|err| err.to_string())?;
-                if let Some(audio) = audio_frame {
-                    encoder.queue_audio_frame(audio);
+                for (track, frame) in audio_frames.into_iter().enumerate() {
+                    encoder.queue_audio_track_frame(track, frame);
                 }
                 encoded_frames += 1;
                 if encoded_frames == 1
@@ -311,7 +432,7 @@ impl Mp4ExportSettings {
                 return Err(format!("Audio encoding failed: {e}"));
             }
 
-            Ok::<_, String>(base.output_path)
+            Ok::<_, String>(encoder_output_path)
         })
         .then(|r| async { r.map_err(|e| e.to_string()).and_then(|v| v) });
 
@@ -460,7 +581,7 @@ fn nv12_from_rendered_frame(frame: Nv12RenderedFrame) -> ExportFrame {
 /// the stream stays gapless and strictly monotonic. The caller advances the
 /// cursor to `pts + samples`. Shared with the tests so they exercise this exact
 /// arithmetic rather than a re-implementation.
-fn audio_frame_budget(
+pub(crate) fn audio_frame_budget(
     frame_number: u64,
     sample_rate: u64,
     fps: u64,
@@ -473,7 +594,7 @@ fn audio_frame_budget(
     Some((cursor as i64, (end - cursor) as usize))
 }
 
-fn silent_audio_frame(samples: usize) -> ffmpeg::frame::Audio {
+pub(crate) fn silent_audio_frame(samples: usize) -> ffmpeg::frame::Audio {
     let mut frame = ffmpeg::frame::Audio::new(
         AudioRenderer::SAMPLE_FORMAT,
         samples,
@@ -609,7 +730,9 @@ fn save_screenshot_from_nv12(
 }
 
 use cap_project::{ProjectConfiguration, RecordingMeta, StudioRecordingMeta};
-use cap_rendering::{ProjectRecordingsMeta, RenderVideoConstants};
+use cap_rendering::ProjectRecordingsMeta;
+
+use crate::RenderBackend;
 
 const FRAME_RECEIVE_INITIAL_TIMEOUT_SECS: u64 = 120;
 const FRAME_RECEIVE_STEADY_TIMEOUT_SECS: u64 = 90;
@@ -617,7 +740,7 @@ const MAX_CONSECUTIVE_FRAME_TIMEOUTS: u32 = 3;
 
 #[allow(clippy::too_many_arguments)]
 async fn export_render_to_channel(
-    constants: &RenderVideoConstants,
+    constants: &RenderBackend,
     project: &ProjectConfiguration,
     sender: std::sync::mpsc::SyncSender<ExportFrame>,
     recording_meta: &RecordingMeta,
@@ -636,19 +759,41 @@ async fn export_render_to_channel(
     let screenshot_project_path = project_path;
 
     let render_result = {
-        let render_future = cap_rendering::render_video_to_channel_nv12(
-            constants,
-            project,
-            tx_image_data,
-            recording_meta,
-            meta,
-            render_segments,
-            fps,
-            resolution_base,
-            recordings,
-            stop_after_frames_sent,
-            startup_breakdown_ms,
-        );
+        let render_future = async {
+            match constants {
+                RenderBackend::Gpu(constants) => {
+                    cap_rendering::render_video_to_channel_nv12(
+                        constants,
+                        project,
+                        tx_image_data,
+                        recording_meta,
+                        meta,
+                        render_segments,
+                        fps,
+                        resolution_base,
+                        recordings,
+                        stop_after_frames_sent,
+                        startup_breakdown_ms,
+                    )
+                    .await
+                }
+                RenderBackend::Cpu(constants) => {
+                    cap_rendering::render_video_to_channel_cpu_nv12(
+                        constants,
+                        project,
+                        tx_image_data,
+                        recording_meta,
+                        meta,
+                        render_segments,
+                        fps,
+                        resolution_base,
+                        recordings,
+                        stop_after_frames_sent,
+                    )
+                    .await
+                }
+            }
+        };
 
         let forward_future = async {
             let mut first_frame_data: Option<FirstFrameNv12> = None;
@@ -748,6 +893,40 @@ async fn export_render_to_channel(
 mod tests {
     use super::*;
 
+    #[test]
+    fn size_target_leaves_room_for_audio_and_container() {
+        let target = SizeTarget {
+            bytes: 10 * 1024 * 1024,
+            scale: 1.0,
+        };
+        let video_bitrate = target
+            .video_bitrate(60.0, AACEncoder::OUTPUT_BITRATE)
+            .unwrap();
+        let total_bytes = (video_bitrate + AACEncoder::OUTPUT_BITRATE) as f64 * 60.0 / 8.0;
+        assert!(total_bytes < target.bytes as f64);
+        assert!(total_bytes > target.bytes as f64 * 0.95);
+
+        let scaled = SizeTarget {
+            scale: 0.5,
+            ..target
+        }
+        .video_bitrate(60.0, AACEncoder::OUTPUT_BITRATE)
+        .unwrap();
+        assert_eq!(scaled, video_bitrate / 2);
+    }
+
+    #[test]
+    fn size_target_too_small_for_duration_is_rejected() {
+        let target = SizeTarget {
+            bytes: 1024 * 1024,
+            scale: 1.0,
+        };
+        let err = target
+            .video_bitrate(600.0, AACEncoder::OUTPUT_BITRATE)
+            .unwrap_err();
+        assert!(err.contains("too small"), "{err}");
+    }
+
     fn sum_samples(sample_rate: u64, fps: u64, frames: u64) -> u64 {
         (0..frames)
             .map(|n| {
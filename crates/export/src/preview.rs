@@ -3,12 +3,13 @@ use std::path::PathBuf;
 use base64::{Engine, engine::general_purpose::STANDARD};
 use cap_project::{RecordingMeta, TimelineFrameMapping, XY};
 use cap_rendering::{
-    FrameRenderer, ProjectUniforms, RendererLayers, TransitionRenderInput, ZoomTransformTimeline,
+    FrameRenderer, ProjectUniforms, RenderVideoConstants, RenderedFrame, RendererLayers,
+    TransitionRenderInput, ZoomTransformTimeline,
 };
 use image::codecs::jpeg::JpegEncoder;
 use serde::{Deserialize, Serialize};
 
-use crate::{ExportError, ExporterBase, make_cursor_only_project};
+use crate::{ExportError, ExporterBase, RenderBackend, make_cursor_only_project};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ExportPreviewSettings {
@@ -58,6 +59,101 @@ async fn render_preview_with_base(
     frame_time: f64,
     settings: ExportPreviewSettings,
 ) -> Result<ExportPreviewResult, ExportError> {
+    let RenderBackend::Gpu(render_constants) = &exporter_base.render_constants else {
+        return Err(ExportError::Other(
+            "Export previews require the GPU renderer".to_string(),
+        ));
+    };
+
+    let render_start = std::time::Instant::now();
+
+    let mut frame_renderer = FrameRenderer::new(render_constants);
+    let mut layers = RendererLayers::new_with_options(
+        &render_constants.device,
+        &render_constants.queue,
+        render_constants.is_software_adapter,
+    );
+    let frame = render_frame_at(
+        &exporter_base,
+        render_constants,
+        &mut frame_renderer,
+        &mut layers,
+        frame_time,
+        settings.fps,
+        settings.resolution_base,
+        !settings.cursor_only,
+    )
+    .await?;
+
+    let frame_render_time_ms = render_start.elapsed().as_secs_f64() * 1000.0;
+    let width = frame.width;
+    let height = frame.height;
+
+    let rgb_data: Vec<u8> = frame
+        .data
+        .chunks(frame.padded_bytes_per_row as usize)
+        .flat_map(|row| {
+            row[0..(frame.width * 4) as usize]
+                .chunks(4)
+                .flat_map(|chunk| [chunk[0], chunk[1], chunk[2]])
+        })
+        .collect();
+
+    let mut jpeg_buffer = Vec::new();
+    {
+        let mut encoder = JpegEncoder::new_with_quality(
+            &mut jpeg_buffer,
+            bpp_to_jpeg_quality(settings.compression_bpp),
+        );
+        encoder
+            .encode(&rgb_data, width, height, image::ExtendedColorType::Rgb8)
+            .map_err(|e| ExportError::Other(format!("Failed to encode JPEG: {e}")))?;
+    }
+
+    let duration_seconds = cap_rendering::get_duration(
+        &exporter_base.recordings,
+        &exporter_base.recording_meta,
+        &exporter_base.studio_meta,
+        &exporter_base.project_config,
+    );
+    let fps_f64 = settings.fps as f64;
+    let total_frames = (duration_seconds * fps_f64).ceil() as u32;
+    let total_pixels = (settings.resolution_base.x * settings.resolution_base.y) as f64;
+    let estimated_size_mb = if settings.cursor_only {
+        let total_frames_f64 = (duration_seconds * fps_f64).ceil();
+        estimate_cursor_only_size_mb(total_pixels, total_frames_f64)
+    } else {
+        let effective_fps = ((fps_f64 - 30.0).max(0.0) * 0.6) + fps_f64.min(30.0);
+        let video_bitrate = total_pixels * settings.compression_bpp as f64 * effective_fps;
+        let audio_bitrate = 192_000.0;
+        let total_bitrate = video_bitrate + audio_bitrate;
+        let encoder_efficiency = 0.5;
+        (total_bitrate * encoder_efficiency * duration_seconds) / (8.0 * 1024.0 * 1024.0)
+    };
+
+    Ok(ExportPreviewResult {
+        jpeg_base64: STANDARD.encode(&jpeg_buffer),
+        estimated_size_mb,
+        actual_width: width,
+        actual_height: height,
+        frame_render_time_ms,
+        total_frames,
+    })
+}
+
+/// Renders the output frame at `frame_time` (seconds of edited output),
+/// crossfading through a clip transition when one spans that time.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn render_frame_at(
+    exporter_base: &ExporterBase,
+    render_constants: &RenderVideoConstants,
+    frame_renderer: &mut FrameRenderer<'_>,
+    layers: &mut RendererLayers,
+    frame_time: f64,
+    fps: u32,
+    resolution_base: XY<u32>,
+    render_display: bool,
+) -> Result<RenderedFrame, ExportError> {
     let transition_mapping = exporter_base
         .project_config
         .timeline
@@ -93,20 +189,18 @@ async fn render_preview_with_base(
         .iter()
         .find(|v| v.index == segment.recording_clip);
 
-    let render_start = std::time::Instant::now();
-
     let segment_frames = segment_media
         .decoders
         .get_frames(
             segment_time as f32,
             !exporter_base.project_config.camera.hide,
-            !settings.cursor_only,
+            render_display,
             clip_config.map(|v| v.offsets).unwrap_or_default(),
         )
         .await
         .ok_or_else(|| ExportError::Other("Failed to decode frame".to_string()))?;
 
-    let frame_number = (frame_time * settings.fps as f64).floor() as u32;
+    let frame_number = (frame_time * fps as f64).floor() as u32;
     let total_duration = cap_rendering::get_duration(
         &exporter_base.recordings,
         &exporter_base.recording_meta,
@@ -118,30 +212,23 @@ async fn render_preview_with_base(
         &exporter_base.project_config,
         &segment_media.cursor,
         total_duration,
-        exporter_base.render_constants.options.screen_size,
+        render_constants.options.screen_size,
         segment.recording_clip,
     );
-    zoom_timeline.ensure_precomputed_until((frame_number as f32 + 1.0) / settings.fps as f32);
+    zoom_timeline.ensure_precomputed_until((frame_number as f32 + 1.0) / fps as f32);
 
     let uniforms = ProjectUniforms::new(
-        &exporter_base.render_constants,
+        render_constants,
         &exporter_base.project_config,
         frame_number,
-        settings.fps,
-        settings.resolution_base,
+        fps,
+        resolution_base,
         &segment_media.cursor,
         &segment_frames,
         total_duration,
         &zoom_timeline,
     );
 
-    let mut frame_renderer = FrameRenderer::new(&exporter_base.render_constants);
-    let mut layers = RendererLayers::new_with_options(
-        &exporter_base.render_constants.device,
-        &exporter_base.render_constants.queue,
-        exporter_base.render_constants.is_software_adapter,
-    );
-
     let frame = if let Some((outgoing, kind, progress)) = transition_mapping {
         let outgoing_media = exporter_base
             .segments
@@ -161,7 +248,7 @@ async fn render_preview_with_base(
             .get_frames(
                 outgoing.source_time as f32,
                 !exporter_base.project_config.camera.hide,
-                !settings.cursor_only,
+                render_display,
                 outgoing_offsets,
             )
             .await
@@ -170,16 +257,16 @@ async fn render_preview_with_base(
             &exporter_base.project_config,
             &outgoing_media.cursor,
             total_duration,
-            exporter_base.render_constants.options.screen_size,
+            render_constants.options.screen_size,
             outgoing.segment.recording_clip,
         );
-        outgoing_zoom.ensure_precomputed_until((frame_number as f32 + 1.0) / settings.fps as f32);
+        outgoing_zoom.ensure_precomputed_until((frame_number as f32 + 1.0) / fps as f32);
         let outgoing_uniforms = ProjectUniforms::new(
-            &exporter_base.render_constants,
+            render_constants,
             &exporter_base.project_config,
             frame_number,
-            settings.fps,
-            settings.resolution_base,
+            fps,
+            resolution_base,
             &outgoing_media.cursor,
             &outgoing_frames,
             total_duration,
@@ -192,17 +279,17 @@ async fn render_preview_with_base(
                     segment_frames: outgoing_frames,
                     uniforms: outgoing_uniforms,
                     cursor: &outgoing_media.cursor,
-                    render_display: !settings.cursor_only,
+                    render_display,
                 },
                 TransitionRenderInput {
                     segment_frames,
                     uniforms,
                     cursor: &segment_media.cursor,
-                    render_display: !settings.cursor_only,
+                    render_display,
                 },
                 kind,
                 progress as f32,
-                &mut layers,
+                layers,
             )
             .await?
     } else {
@@ -211,61 +298,13 @@ async fn render_preview_with_base(
                 segment_frames,
                 uniforms,
                 &segment_media.cursor,
-                !settings.cursor_only,
-                &mut layers,
+                render_display,
+                layers,
             )
             .await?
     };
 
-    let frame_render_time_ms = render_start.elapsed().as_secs_f64() * 1000.0;
-    let width = frame.width;
-    let height = frame.height;
-
-    let rgb_data: Vec<u8> = frame
-        .data
-        .chunks(frame.padded_bytes_per_row as usize)
-        .flat_map(|row| {
-            row[0..(frame.width * 4) as usize]
-                .chunks(4)
-                .flat_map(|chunk| [chunk[0], chunk[1], chunk[2]])
-        })
-        .collect();
-
-    let mut jpeg_buffer = Vec::new();
-    {
-        let mut encoder = JpegEncoder::new_with_quality(
-            &mut jpeg_buffer,
-            bpp_to_jpeg_quality(settings.compression_bpp),
-        );
-        encoder
-            .encode(&rgb_data, width, height, image::ExtendedColorType::Rgb8)
-            .map_err(|e| ExportError::Other(format!("Failed to encode JPEG: {e}")))?;
-    }
-
-    let duration_seconds = total_duration;
-    let fps_f64 = settings.fps as f64;
-    let total_frames = (duration_seconds * fps_f64).ceil() as u32;
-    let total_pixels = (settings.resolution_base.x * settings.resolution_base.y) as f64;
-    let estimated_size_mb = if settings.cursor_only {
-        let total_frames_f64 = (duration_seconds * fps_f64).ceil();
-        estimate_cursor_only_size_mb(total_pixels, total_frames_f64)
-    } else {
-        let effective_fps = ((fps_f64 - 30.0).max(0.0) * 0.6) + fps_f64.min(30.0);
-        let video_bitrate = total_pixels * settings.compression_bpp as f64 * effective_fps;
-        let audio_bitrate = 192_000.0;
-        let total_bitrate = video_bitrate + audio_bitrate;
-        let encoder_efficiency = 0.5;
-        (total_bitrate * encoder_efficiency * duration_seconds) / (8.0 * 1024.0 * 1024.0)
-    };
-
-    Ok(ExportPreviewResult {
-        jpeg_base64: STANDARD.encode(&jpeg_buffer),
-        estimated_size_mb,
-        actual_width: width,
-        actual_height: height,
-        frame_render_time_ms,
-        total_frames,
-    })
+    Ok(frame)
 }
 
 fn estimate_cursor_only_size_mb(total_pixels: f64, total_frames: f64) -> f64 {
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
+use crate::animated::{ApngExportSettings, WebpExportSettings};
 use crate::gif::GifExportSettings;
 use crate::mov::MovExportSettings;
 use crate::mp4::Mp4ExportSettings;
+use crate::stems::StemsExportSettings;
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Type)]
 #[serde(tag = "format")]
@@ -14,6 +16,12 @@ pub enum ExportSettings {
     Gif(GifExportSettings),
     #[serde(alias = "mov")]
     Mov(MovExportSettings),
+    #[serde(alias = "webp")]
+    WebP(WebpExportSettings),
+    #[serde(alias = "apng")]
+    Apng(ApngExportSettings),
+    #[serde(alias = "stems")]
+    Stems(StemsExportSettings),
 }
 
 impl ExportSettings {
@@ -22,13 +30,16 @@ impl ExportSettings {
             Self::Mp4(s) => s.fps,
             Self::Gif(s) => s.fps,
             Self::Mov(s) => s.fps,
+            Self::WebP(s) => s.fps,
+            Self::Apng(s) => s.fps,
+            Self::Stems(_) => StemsExportSettings::PROGRESS_FPS,
         }
     }
 
     pub fn force_ffmpeg_decoder(&self) -> bool {
         match self {
             Self::Mp4(s) => s.force_ffmpeg_decoder,
-            Self::Gif(_) | Self::Mov(_) => false,
+            Self::Gif(_) | Self::Mov(_) | Self::WebP(_) | Self::Apng(_) | Self::Stems(_) => false,
         }
     }
 
@@ -0,0 +1,254 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use cap_project::{
+    KeyboardEvents, KeyboardSettings, KeyboardTrackSegment, RecordingMeta, RecordingMetaInner,
+    StudioRecordingMeta, group_key_events,
+};
+use serde::Serialize;
+
+use crate::thumbnails::{format_timestamp, format_vtt_time};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutSource {
+    /// The project's keyboard track, in output time.
+    Timeline,
+    /// Grouped straight from the recorded key presses, in recording time.
+    Recording,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Shortcut {
+    pub start: f64,
+    pub end: f64,
+    /// Text as the keystroke overlay shows it, e.g. `⌘⇧P` or a typed word.
+    pub text: String,
+    /// Recorded key names in the group, in press order.
+    pub keys: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutTrack {
+    pub source: ShortcutSource,
+    pub shortcuts: Vec<Shortcut>,
+}
+
+/// Loads the grouped key presses of a project. The keyboard track on the
+/// timeline is used when it has segments, so edits made in the editor carry
+/// over; otherwise the recorded presses are grouped with the project's
+/// keyboard settings, the same way the editor generates the track.
+pub fn load_shortcuts(project_path: &Path) -> Result<ShortcutTrack, String> {
+    let meta = RecordingMeta::load_for_project(project_path)
+        .map_err(|e| format!("Failed to load recording meta: {e}"))?;
+    let project = meta.project_config();
+    let settings = project
+        .keyboard
+        .as_ref()
+        .map(|keyboard| keyboard.settings.clone())
+        .unwrap_or_default();
+
+    if let Some(timeline) = &project.timeline
+        && !timeline.keyboard_segments.is_empty()
+    {
+        return Ok(ShortcutTrack {
+            source: ShortcutSource::Timeline,
+            shortcuts: to_shortcuts(&timeline.keyboard_segments, &settings),
+        });
+    }
+
+    let RecordingMetaInner::Studio(studio_meta) = &meta.inner else {
+        return Err("Only studio recordings capture key presses".to_string());
+    };
+
+    let mut events = KeyboardEvents { presses: vec![] };
+    if let StudioRecordingMeta::MultipleSegments { inner, .. } = studio_meta.as_ref() {
+        for segment in &inner.segments {
+            events
+                .presses
+                .extend(segment.keyboard_events(&meta).presses);
+        }
+    }
+    events
+        .presses
+        .sort_by(|a, b| a.time_ms.total_cmp(&b.time_ms));
+
+    let segments = group_key_events(
+        &events,
+        settings.grouping_threshold_ms,
+        settings.linger_duration as f64 * 1000.0,
+        settings.show_modifiers,
+        settings.show_special_keys,
+    );
+
+    Ok(ShortcutTrack {
+        source: ShortcutSource::Recording,
+        shortcuts: to_shortcuts(&segments, &settings),
+    })
+}
+
+fn to_shortcuts(segments: &[KeyboardTrackSegment], settings: &KeyboardSettings) -> Vec<Shortcut> {
+    let mut shortcuts: Vec<Shortcut> = segments
+        .iter()
+        .filter(|segment| !segment.display_text.trim().is_empty())
+        .map(|segment| Shortcut {
+            start: segment.start,
+            end: segment.end.max(segment.start),
+            text: if segment.uppercase_override.unwrap_or(settings.uppercase) {
+                segment.display_text.to_uppercase()
+            } else {
+                segment.display_text.clone()
+            },
+            keys: segment.keys.iter().map(|key| key.key.clone()).collect(),
+        })
+        .collect();
+    shortcuts.sort_by(|a, b| a.start.total_cmp(&b.start));
+    shortcuts
+}
+
+/// A WebVTT track with one cue per shortcut.
+pub fn to_webvtt(shortcuts: &[Shortcut]) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for shortcut in shortcuts {
+        let _ = write!(
+            vtt,
+            "\n{} --> {}\n{}\n",
+            format_vtt_time(shortcut.start),
+            format_vtt_time(shortcut.end),
+            escape_vtt(&shortcut.text)
+        );
+    }
+    vtt
+}
+
+/// An SRT track with one numbered cue per shortcut.
+pub fn to_srt(shortcuts: &[Shortcut]) -> String {
+    let mut srt = String::new();
+    for (i, shortcut) in shortcuts.iter().enumerate() {
+        if i > 0 {
+            srt.push('\n');
+        }
+        let _ = writeln!(
+            srt,
+            "{}\n{} --> {}\n{}",
+            i + 1,
+            format_vtt_time(shortcut.start).replace('.', ","),
+            format_vtt_time(shortcut.end).replace('.', ","),
+            shortcut.text
+        );
+    }
+    srt
+}
+
+/// A numbered list of steps, one per shortcut, each followed by its
+/// screenshot when `frames` has an entry for it. Frame paths are written as
+/// given, so pass them relative to the Markdown file.
+pub fn to_markdown(title: &str, shortcuts: &[Shortcut], frames: &[String]) -> String {
+    let mut markdown = format!("# {title}\n");
+    if shortcuts.is_empty() {
+        markdown.push_str("\nNo key presses were recorded.\n");
+        return markdown;
+    }
+
+    for (i, shortcut) in shortcuts.iter().enumerate() {
+        let step = i + 1;
+        let timestamp = format_timestamp(shortcut.start);
+        let _ = writeln!(
+            markdown,
+            "\n{step}. Press {} ({timestamp})",
+            markdown_code(&shortcut.text)
+        );
+        if let Some(frame) = frames.get(i) {
+            let _ = writeln!(markdown, "\n   ![Step {step} at {timestamp}]({frame})");
+        }
+    }
+    markdown
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn markdown_code(text: &str) -> String {
+    if text.contains('`') {
+        format!("`` {text} ``")
+    } else {
+        format!("`{text}`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcut(start: f64, end: f64, text: &str) -> Shortcut {
+        Shortcut {
+            start,
+            end,
+            text: text.to_string(),
+            keys: vec![],
+        }
+    }
+
+    #[test]
+    fn caption_tracks_have_a_cue_per_shortcut() {
+        let shortcuts = [shortcut(1.5, 2.3, "⌘C"), shortcut(62.0, 63.25, "a<b")];
+
+        assert_eq!(
+            to_webvtt(&shortcuts),
+            "WEBVTT\n\
+             \n00:00:01.500 --> 00:00:02.300\n⌘C\n\
+             \n00:01:02.000 --> 00:01:03.250\na&lt;b\n"
+        );
+        assert_eq!(
+            to_srt(&shortcuts),
+            "1\n00:00:01,500 --> 00:00:02,300\n⌘C\n\
+             \n2\n00:01:02,000 --> 00:01:03,250\na<b\n"
+        );
+    }
+
+    #[test]
+    fn markdown_steps_link_frames_when_present() {
+        let shortcuts = [shortcut(5.0, 6.0, "⌘S"), shortcut(75.0, 76.0, "`")];
+        let markdown = to_markdown("Demo", &shortcuts, &["steps/step-001.png".to_string()]);
+
+        assert_eq!(
+            markdown,
+            "# Demo\n\
+             \n1. Press `⌘S` (0:05)\n\
+             \n   ![Step 1 at 0:05](steps/step-001.png)\n\
+             \n2. Press `` ` `` (1:15)\n"
+        );
+    }
+
+    #[test]
+    fn timeline_segments_keep_overlay_casing() {
+        let segment = KeyboardTrackSegment {
+            id: "kb-1".to_string(),
+            start: 3.0,
+            end: 2.0,
+            display_text: "hello".to_string(),
+            keys: vec![],
+            fade_duration_override: None,
+            position_override: None,
+            color_override: None,
+            background_color_override: None,
+            font_size_override: None,
+            uppercase_override: Some(true),
+        };
+        let blank = KeyboardTrackSegment {
+            display_text: " ".to_string(),
+            ..segment.clone()
+        };
+
+        let shortcuts = to_shortcuts(&[segment, blank], &KeyboardSettings::default());
+
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].text, "HELLO");
+        assert_eq!(shortcuts[0].end, 3.0);
+    }
+}
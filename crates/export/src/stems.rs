@@ -0,0 +1,106 @@
+use cap_editor::AudioRenderer;
+use cap_enc_ffmpeg::{
+    audio_file::AudioFile,
+    lossless::{LosslessAudioEncoder, LosslessAudioFormat},
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::PathBuf;
+use tracing::info;
+
+use crate::{
+    ExporterBase,
+    audio::{ExportAudioSettings, ExportAudioSources},
+};
+
+#[derive(Serialize, Deserialize, Type, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StemFileFormat {
+    #[default]
+    Wav,
+    Flac,
+}
+
+impl From<StemFileFormat> for LosslessAudioFormat {
+    fn from(value: StemFileFormat) -> Self {
+        match value {
+            StemFileFormat::Wav => Self::Wav,
+            StemFileFormat::Flac => Self::Flac,
+        }
+    }
+}
+
+/// Writes mic, system audio and music to separate files, without video.
+#[derive(Serialize, Deserialize, Type, Clone, Copy, Debug, Default)]
+pub struct StemsExportSettings {
+    #[serde(default)]
+    pub file_format: StemFileFormat,
+    /// Normalisation measured on the full mix and applied to every stem, so
+    /// the stems still sum to the normalised mix. `separate_tracks` is ignored.
+    #[serde(default)]
+    pub audio: ExportAudioSettings,
+}
+
+impl StemsExportSettings {
+    /// Rate progress is reported at, in audio "frames" per second.
+    pub const PROGRESS_FPS: u32 = 30;
+
+    /// Writes `<stem>.wav` or `<stem>.flac` files into a directory named
+    /// after the output path, returning the directory.
+    pub async fn export(
+        self,
+        base: ExporterBase,
+        mut on_progress: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<PathBuf, String> {
+        let output_dir = base.output_path.with_extension("");
+        std::fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", output_dir.display()))?;
+
+        let sources = ExportAudioSources::load(&base, self.audio, false, true).await;
+        if sources.is_empty() {
+            return Err("Project has no audio to export".to_string());
+        }
+
+        let total_frames = base.total_frames(Self::PROGRESS_FPS);
+        let format = LosslessAudioFormat::from(self.file_format);
+        let stems = sources.stems();
+
+        tokio::task::spawn_blocking(move || {
+            let mut audio = sources.into_audio(Self::PROGRESS_FPS)?;
+
+            let mut files = Vec::with_capacity(stems.len());
+            for stem in stems.iter().flatten() {
+                let path = output_dir.join(format!("{}.{}", stem.name(), format.extension()));
+                let file = AudioFile::init(
+                    path.clone(),
+                    LosslessAudioEncoder::factory(AudioRenderer::info(), format),
+                )
+                .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+                files.push((path, file));
+            }
+
+            for frame_number in 0..total_frames {
+                if !on_progress(frame_number) {
+                    return Err("Export cancelled".to_string());
+                }
+
+                for (track, frame) in audio.render_frames(frame_number).into_iter().enumerate() {
+                    if let Some((_, file)) = files.get_mut(track) {
+                        file.queue_frame(frame);
+                    }
+                }
+            }
+
+            for (path, file) in &mut files {
+                file.finish()
+                    .map_err(|e| e.to_string())
+                    .and_then(|flushed| flushed.map_err(|e| e.to_string()))
+                    .map_err(|e| format!("Failed to finish {}: {e}", path.display()))?;
+                info!(path = %path.display(), "Wrote audio stem");
+            }
+
+            Ok(output_dir)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+}
@@ -0,0 +1,506 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use cap_project::XY;
+use cap_rendering::label::{LabelMask, LabelRasterizer};
+use cap_rendering::{FrameRenderer, RenderVideoConstants, RenderedFrame, RendererLayers};
+use image::{Rgba, RgbaImage, imageops};
+
+use crate::{ExportError, ExporterBase, RenderBackend, preview::render_frame_at};
+
+/// Frame rate used to derive frame numbers for time-based effects (zoom
+/// springs, fades); thumbnails only ever sample single frames.
+const THUMBNAIL_FPS: u32 = 30;
+
+/// Thumbnails are rendered at twice their final size and downscaled so text
+/// and cursor edges stay legible.
+const SUPERSAMPLE: u32 = 2;
+
+/// Side of the luma grid compared between scene-detection samples.
+const SCENE_GRID: u32 = 32;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ThumbnailSelection {
+    /// `count` frames from the middle of equal slices of the output.
+    Even { count: u32 },
+    /// The first frame plus every sampled frame whose average luma change from
+    /// the previous sample exceeds `threshold` (0..1), keeping at most
+    /// `max_count` of the strongest changes.
+    SceneChange {
+        threshold: f64,
+        max_count: u32,
+        sample_interval: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThumbnailOptions {
+    pub selection: ThumbnailSelection,
+    /// Width of each thumbnail in pixels; height follows the output aspect.
+    pub width: u32,
+}
+
+pub struct Thumbnail {
+    /// Output time the frame was rendered at, in seconds.
+    pub time: f64,
+    /// Span of the output this thumbnail stands for when scrubbing.
+    pub start: f64,
+    pub end: f64,
+    pub image: RgbaImage,
+}
+
+pub struct ThumbnailSet {
+    pub duration: f64,
+    pub thumbnails: Vec<Thumbnail>,
+}
+
+/// Renders thumbnails of the edited project, with every timeline effect
+/// applied, through the GPU frame renderer.
+pub async fn render_thumbnails(
+    project_path: PathBuf,
+    options: ThumbnailOptions,
+    force_ffmpeg_decoder: bool,
+) -> Result<ThumbnailSet, ExportError> {
+    if options.width == 0 {
+        return Err(ExportError::Other(
+            "Thumbnail width must be greater than zero".to_string(),
+        ));
+    }
+
+    let exporter_base = build_exporter(project_path, force_ffmpeg_decoder).await?;
+    let duration = output_duration(&exporter_base)?;
+    let mut render = ThumbnailRenderer::new(&exporter_base, options.width)?;
+
+    let thumbnails = match options.selection {
+        ThumbnailSelection::Even { count } => {
+            if count == 0 {
+                return Err(ExportError::Other(
+                    "Thumbnail count must be greater than zero".to_string(),
+                ));
+            }
+
+            let slice = duration / count as f64;
+            let mut thumbnails = Vec::with_capacity(count as usize);
+            for i in 0..count {
+                let start = slice * i as f64;
+                let time = start + slice / 2.0;
+                thumbnails.push(Thumbnail {
+                    time,
+                    start,
+                    end: (start + slice).min(duration),
+                    image: render.render(time).await?,
+                });
+            }
+            thumbnails
+        }
+        ThumbnailSelection::SceneChange {
+            threshold,
+            max_count,
+            sample_interval,
+        } => {
+            if max_count == 0 || sample_interval <= 0.0 {
+                return Err(ExportError::Other(
+                    "Scene detection needs a positive thumbnail count and sample interval"
+                        .to_string(),
+                ));
+            }
+
+            // (time, score, image) for the first frame and every cut.
+            let mut cuts: Vec<(f64, f64, RgbaImage)> = Vec::new();
+            let mut previous: Option<Vec<f32>> = None;
+            let mut time = 0.0;
+            while time < duration {
+                let image = render.render(time).await?;
+                let grid = luma_grid(&image);
+                let score = previous
+                    .as_ref()
+                    .map(|previous| luma_change(previous, &grid))
+                    .unwrap_or(f64::INFINITY);
+                if score >= threshold {
+                    cuts.push((time, score, image));
+                }
+                previous = Some(grid);
+                time += sample_interval;
+            }
+
+            select_cuts(&mut cuts, max_count as usize);
+
+            let ends: Vec<f64> = cuts
+                .iter()
+                .skip(1)
+                .map(|(time, ..)| *time)
+                .chain(std::iter::once(duration))
+                .collect();
+            cuts.into_iter()
+                .zip(ends)
+                .map(|((time, _, image), end)| Thumbnail {
+                    time,
+                    start: time,
+                    end,
+                    image,
+                })
+                .collect()
+        }
+    };
+
+    Ok(ThumbnailSet {
+        duration,
+        thumbnails,
+    })
+}
+
+struct ThumbnailRenderer<'a> {
+    exporter_base: &'a ExporterBase,
+    render_constants: &'a RenderVideoConstants,
+    frame_renderer: FrameRenderer<'a>,
+    layers: RendererLayers,
+    resolution_base: XY<u32>,
+    width: u32,
+}
+
+async fn build_exporter(
+    project_path: PathBuf,
+    force_ffmpeg_decoder: bool,
+) -> Result<ExporterBase, ExportError> {
+    ExporterBase::builder(project_path)
+        .with_force_ffmpeg_decoder(force_ffmpeg_decoder)
+        .build()
+        .await
+        .map_err(|e| ExportError::Other(format!("Exporter build error: {e}")))
+}
+
+fn output_duration(exporter_base: &ExporterBase) -> Result<f64, ExportError> {
+    let duration = cap_rendering::get_duration(
+        &exporter_base.recordings,
+        &exporter_base.recording_meta,
+        &exporter_base.studio_meta,
+        &exporter_base.project_config,
+    );
+    if duration <= 0.0 {
+        return Err(ExportError::Other("Project has no content".to_string()));
+    }
+    Ok(duration)
+}
+
+/// Renders single frames of the edited project at the given output times,
+/// each `width` pixels wide. Times are clamped to the output's duration.
+pub async fn render_frames_at(
+    project_path: PathBuf,
+    times: &[f64],
+    width: u32,
+    force_ffmpeg_decoder: bool,
+) -> Result<Vec<RgbaImage>, ExportError> {
+    if width == 0 {
+        return Err(ExportError::Other(
+            "Frame width must be greater than zero".to_string(),
+        ));
+    }
+
+    let exporter_base = build_exporter(project_path, force_ffmpeg_decoder).await?;
+    let duration = output_duration(&exporter_base)?;
+    let mut render = ThumbnailRenderer::new(&exporter_base, width)?;
+
+    // Stay a frame short of the end so the last time still has a frame.
+    let last_frame = (duration - 1.0 / THUMBNAIL_FPS as f64).max(0.0);
+    let mut images = Vec::with_capacity(times.len());
+    for &time in times {
+        images.push(render.render(time.clamp(0.0, last_frame)).await?);
+    }
+    Ok(images)
+}
+
+impl<'a> ThumbnailRenderer<'a> {
+    fn new(exporter_base: &'a ExporterBase, width: u32) -> Result<Self, ExportError> {
+        let RenderBackend::Gpu(render_constants) = &exporter_base.render_constants else {
+            return Err(ExportError::Other(
+                "Thumbnails require the GPU renderer".to_string(),
+            ));
+        };
+
+        Ok(Self {
+            exporter_base,
+            render_constants,
+            frame_renderer: FrameRenderer::new(render_constants),
+            layers: RendererLayers::new_with_options(
+                &render_constants.device,
+                &render_constants.queue,
+                render_constants.is_software_adapter,
+            ),
+            resolution_base: XY::new(width * SUPERSAMPLE, width * SUPERSAMPLE),
+            width,
+        })
+    }
+
+    async fn render(&mut self, time: f64) -> Result<RgbaImage, ExportError> {
+        let frame = render_frame_at(
+            self.exporter_base,
+            self.render_constants,
+            &mut self.frame_renderer,
+            &mut self.layers,
+            time,
+            THUMBNAIL_FPS,
+            self.resolution_base,
+            true,
+        )
+        .await?;
+
+        Ok(downscale(&frame_to_image(&frame)?, self.width))
+    }
+}
+
+/// Keeps the first sample and the `max_count - 1` strongest cuts, in time
+/// order.
+fn select_cuts<T>(cuts: &mut Vec<(f64, f64, T)>, max_count: usize) {
+    if cuts.len() <= max_count {
+        return;
+    }
+
+    let mut rest = cuts.split_off(1);
+    rest.sort_by(|a, b| b.1.total_cmp(&a.1));
+    rest.truncate(max_count.saturating_sub(1));
+    rest.sort_by(|a, b| a.0.total_cmp(&b.0));
+    cuts.extend(rest);
+    cuts.truncate(max_count);
+}
+
+/// Tiles thumbnails into a grid with each one's timestamp printed beneath it.
+pub fn contact_sheet(thumbnails: &[Thumbnail], columns: u32) -> RgbaImage {
+    const GAP: u32 = 12;
+    const LABEL_HEIGHT: u32 = 26;
+    const LABEL_FONT_SIZE: f32 = 15.0;
+    const BACKGROUND: Rgba<u8> = Rgba([24, 24, 27, 255]);
+    const TEXT: Rgba<u8> = Rgba([236, 236, 240, 255]);
+
+    let Some((tile_width, tile_height)) = tile_size(thumbnails) else {
+        return RgbaImage::from_pixel(1, 1, BACKGROUND);
+    };
+    let columns = columns.clamp(1, thumbnails.len() as u32);
+    let rows = (thumbnails.len() as u32).div_ceil(columns);
+    let cell_height = tile_height + LABEL_HEIGHT;
+
+    let mut sheet = RgbaImage::from_pixel(
+        GAP + columns * (tile_width + GAP),
+        GAP + rows * (cell_height + GAP),
+        BACKGROUND,
+    );
+
+    let mut rasterizer = LabelRasterizer::new();
+
+    for (i, thumbnail) in thumbnails.iter().enumerate() {
+        let x = GAP + (i as u32 % columns) * (tile_width + GAP);
+        let y = GAP + (i as u32 / columns) * (cell_height + GAP);
+        imageops::overlay(&mut sheet, &thumbnail.image, x as i64, y as i64);
+
+        let label = rasterizer.rasterize(&format_timestamp(thumbnail.time), LABEL_FONT_SIZE);
+        draw_label(
+            &mut sheet,
+            &label,
+            x,
+            y + tile_height + LABEL_HEIGHT.saturating_sub(label.height) / 2,
+            TEXT,
+        );
+    }
+
+    sheet
+}
+
+pub struct ThumbnailSprite {
+    pub image: RgbaImage,
+    /// WebVTT track mapping each span of the output to its tile in `image`.
+    pub vtt: String,
+}
+
+/// Packs thumbnails into a single sprite image plus a WebVTT track whose cues
+/// point at `sprite_url#xywh=…`, the format web players use for scrubbing
+/// previews.
+pub fn thumbnail_sprite(
+    thumbnails: &[Thumbnail],
+    columns: u32,
+    sprite_url: &str,
+) -> ThumbnailSprite {
+    let mut vtt = String::from("WEBVTT\n");
+
+    let Some((tile_width, tile_height)) = tile_size(thumbnails) else {
+        return ThumbnailSprite {
+            image: RgbaImage::new(1, 1),
+            vtt,
+        };
+    };
+    let columns = columns.clamp(1, thumbnails.len() as u32);
+    let rows = (thumbnails.len() as u32).div_ceil(columns);
+    let mut image = RgbaImage::new(columns * tile_width, rows * tile_height);
+
+    for (i, thumbnail) in thumbnails.iter().enumerate() {
+        let x = (i as u32 % columns) * tile_width;
+        let y = (i as u32 / columns) * tile_height;
+        imageops::overlay(&mut image, &thumbnail.image, x as i64, y as i64);
+
+        let _ = write!(
+            vtt,
+            "\n{} --> {}\n{sprite_url}#xywh={x},{y},{},{}\n",
+            format_vtt_time(thumbnail.start),
+            format_vtt_time(thumbnail.end),
+            thumbnail.image.width(),
+            thumbnail.image.height(),
+        );
+    }
+
+    ThumbnailSprite { image, vtt }
+}
+
+fn tile_size(thumbnails: &[Thumbnail]) -> Option<(u32, u32)> {
+    let width = thumbnails.iter().map(|t| t.image.width()).max()?;
+    let height = thumbnails.iter().map(|t| t.image.height()).max()?;
+    Some((width, height))
+}
+
+fn frame_to_image(frame: &RenderedFrame) -> Result<RgbaImage, ExportError> {
+    let row_bytes = (frame.width * 4) as usize;
+    let data = frame
+        .data
+        .chunks(frame.padded_bytes_per_row as usize)
+        .take(frame.height as usize)
+        .flat_map(|row| &row[..row_bytes])
+        .copied()
+        .collect();
+
+    RgbaImage::from_raw(frame.width, frame.height, data)
+        .ok_or_else(|| ExportError::Other("Rendered frame is truncated".to_string()))
+}
+
+fn downscale(image: &RgbaImage, width: u32) -> RgbaImage {
+    if image.width() <= width {
+        return image.clone();
+    }
+
+    let height =
+        ((image.height() as f64 * width as f64 / image.width() as f64).round() as u32).max(1);
+    imageops::resize(image, width, height, imageops::FilterType::Triangle)
+}
+
+fn luma_grid(image: &RgbaImage) -> Vec<f32> {
+    imageops::resize(
+        image,
+        SCENE_GRID,
+        SCENE_GRID,
+        imageops::FilterType::Triangle,
+    )
+    .pixels()
+    .map(|p| (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) / 255.0)
+    .collect()
+}
+
+/// Mean absolute luma difference between two grids, 0 (identical) to 1.
+fn luma_change(a: &[f32], b: &[f32]) -> f64 {
+    let total: f32 = a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum();
+    total as f64 / a.len().max(1) as f64
+}
+
+/// `M:SS` (or `H:MM:SS` past an hour), for contact-sheet labels.
+pub(crate) fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total / 60) % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
+
+pub(crate) fn format_vtt_time(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        (millis / 60_000) % 60,
+        (millis / 1000) % 60,
+        millis % 1000
+    )
+}
+
+/// Blends `color` into `image` through the coverage of `label`, with the
+/// label's top-left corner at `x`, `y`.
+fn draw_label(image: &mut RgbaImage, label: &LabelMask, x: u32, y: u32, color: Rgba<u8>) {
+    for label_y in 0..label.height {
+        for label_x in 0..label.width {
+            let (px, py) = (x + label_x, y + label_y);
+            let coverage = label.coverage_at(label_x, label_y) as u32;
+            if coverage == 0 || px >= image.width() || py >= image.height() {
+                continue;
+            }
+
+            let pixel = image.get_pixel_mut(px, py);
+            for (dst, src) in pixel.0.iter_mut().zip(color.0).take(3) {
+                *dst = ((src as u32 * coverage + *dst as u32 * (255 - coverage)) / 255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thumbnail(start: f64, end: f64) -> Thumbnail {
+        Thumbnail {
+            time: start,
+            start,
+            end,
+            image: RgbaImage::from_pixel(160, 90, Rgba([255, 0, 0, 255])),
+        }
+    }
+
+    #[test]
+    fn sprite_cues_cover_each_span_with_its_tile() {
+        let thumbnails = [
+            thumbnail(0.0, 5.0),
+            thumbnail(5.0, 10.0),
+            thumbnail(10.0, 65.25),
+        ];
+
+        let sprite = thumbnail_sprite(&thumbnails, 2, "sprite.png");
+
+        assert_eq!(sprite.image.dimensions(), (320, 180));
+        assert_eq!(
+            sprite.vtt,
+            "WEBVTT\n\
+             \n00:00:00.000 --> 00:00:05.000\nsprite.png#xywh=0,0,160,90\n\
+             \n00:00:05.000 --> 00:00:10.000\nsprite.png#xywh=160,0,160,90\n\
+             \n00:00:10.000 --> 00:01:05.250\nsprite.png#xywh=0,90,160,90\n"
+        );
+    }
+
+    #[test]
+    fn contact_sheet_fits_every_tile_and_label() {
+        let thumbnails: Vec<_> = (0..5)
+            .map(|i| thumbnail(i as f64, i as f64 + 1.0))
+            .collect();
+
+        let sheet = contact_sheet(&thumbnails, 4);
+
+        assert_eq!(sheet.width(), 12 + 4 * (160 + 12));
+        assert_eq!(sheet.height(), 12 + 2 * (90 + 26 + 12));
+    }
+
+    #[test]
+    fn timestamps_switch_to_hours_past_an_hour() {
+        assert_eq!(format_timestamp(7.9), "0:07");
+        assert_eq!(format_timestamp(754.0), "12:34");
+        assert_eq!(format_timestamp(3723.0), "1:02:03");
+    }
+
+    #[test]
+    fn scene_cuts_keep_the_first_frame_and_strongest_changes_in_order() {
+        let mut cuts = vec![
+            (0.0, f64::INFINITY, ()),
+            (2.0, 0.2, ()),
+            (4.0, 0.9, ()),
+            (6.0, 0.5, ()),
+        ];
+
+        select_cuts(&mut cuts, 3);
+
+        let times: Vec<f64> = cuts.iter().map(|(time, ..)| *time).collect();
+        assert_eq!(times, vec![0.0, 4.0, 6.0]);
+    }
+}
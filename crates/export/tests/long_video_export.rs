@@ -134,6 +134,8 @@ async fn run_export(project_path: PathBuf, fps: u32) -> Result<(PathBuf, Duratio
         custom_bpp: None,
         force_ffmpeg_decoder: false,
         optimize_filesize: false,
+        target_file_size: None,
+        audio: Default::default(),
     };
 
     let total_frames = exporter_base.total_frames(fps);
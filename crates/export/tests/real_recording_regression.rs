@@ -189,11 +189,13 @@ async fn export_sequence_render_matches_editor_reference_for_real_recording()
         let precomputed_cursor_timelines: Vec<Arc<PrecomputedCursorTimeline>> =
             reference_render_segments
                 .iter()
-                .map(|segment| {
+                .enumerate()
+                .map(|(clip, segment)| {
                     Arc::new(PrecomputedCursorTimeline::new(
                         &segment.cursor,
                         cursor_smoothing,
                         Some(click_spring),
+                        &project_config.cursor.clip_edits(clip as u32),
                     ))
                 })
                 .collect();
@@ -0,0 +1,184 @@
+use cap_project::XY;
+use image::{Rgba, RgbaImage};
+
+/// Density is accumulated on a grid this many times smaller than the output.
+const GRID_SCALE: u32 = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeatmapOptions {
+    /// Spread of each click as a fraction of the longer image side.
+    pub radius: f64,
+    /// Opacity of the hottest spot.
+    pub opacity: f32,
+    /// How much the background frame is darkened so the heat stands out.
+    pub dim: f32,
+}
+
+impl Default for HeatmapOptions {
+    fn default() -> Self {
+        Self {
+            radius: 0.03,
+            opacity: 0.75,
+            dim: 0.4,
+        }
+    }
+}
+
+/// Draws normalised click positions as a heatmap over `background`, which is
+/// resized to `size` when it differs. Without a background the heat is drawn
+/// over black.
+pub fn render_heatmap(
+    points: &[XY<f64>],
+    background: Option<&RgbaImage>,
+    size: (u32, u32),
+    options: HeatmapOptions,
+) -> RgbaImage {
+    let (width, height) = (size.0.max(1), size.1.max(1));
+
+    let mut image = match background {
+        Some(frame) if frame.dimensions() == (width, height) => frame.clone(),
+        Some(frame) => {
+            image::imageops::resize(frame, width, height, image::imageops::FilterType::Triangle)
+        }
+        None => RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255])),
+    };
+
+    let dim = 1.0 - options.dim.clamp(0.0, 1.0);
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = (*channel as f32 * dim) as u8;
+        }
+        pixel.0[3] = 255;
+    }
+
+    let density = density_grid(points, width, height, options.radius);
+    let max = density.values.iter().copied().fold(0.0f32, f32::max);
+    if max <= 0.0 {
+        return image;
+    }
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let value =
+            density.sample(x as f32 / GRID_SCALE as f32, y as f32 / GRID_SCALE as f32) / max;
+        if value < 0.02 {
+            continue;
+        }
+
+        let color = heat_color(value);
+        let alpha = options.opacity.clamp(0.0, 1.0) * value.sqrt();
+        for (channel, heat) in pixel.0[..3].iter_mut().zip(color) {
+            *channel = (*channel as f32 * (1.0 - alpha) + heat as f32 * alpha) as u8;
+        }
+    }
+
+    image
+}
+
+struct DensityGrid {
+    width: u32,
+    height: u32,
+    values: Vec<f32>,
+}
+
+impl DensityGrid {
+    /// Bilinear lookup in grid coordinates.
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let x = x.clamp(0.0, (self.width - 1) as f32);
+        let y = y.clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let at = |x: u32, y: u32| self.values[(y * self.width + x) as usize];
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+/// Sums a gaussian per point on a downscaled grid.
+fn density_grid(points: &[XY<f64>], width: u32, height: u32, radius: f64) -> DensityGrid {
+    let grid_width = width.div_ceil(GRID_SCALE).max(1);
+    let grid_height = height.div_ceil(GRID_SCALE).max(1);
+    let mut values = vec![0.0f32; (grid_width * grid_height) as usize];
+
+    let sigma = (radius * width.max(height) as f64 / GRID_SCALE as f64).max(1.0);
+    let reach = (sigma * 3.0).ceil() as i64;
+
+    for point in points {
+        let cx = point.x.clamp(0.0, 1.0) * width as f64 / GRID_SCALE as f64;
+        let cy = point.y.clamp(0.0, 1.0) * height as f64 / GRID_SCALE as f64;
+
+        let (gx, gy) = (cx as i64, cy as i64);
+        for y in (gy - reach).max(0)..=(gy + reach).min(grid_height as i64 - 1) {
+            for x in (gx - reach).max(0)..=(gx + reach).min(grid_width as i64 - 1) {
+                let dx = x as f64 + 0.5 - cx;
+                let dy = y as f64 + 0.5 - cy;
+                let weight = (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp();
+                values[(y as u32 * grid_width + x as u32) as usize] += weight as f32;
+            }
+        }
+    }
+
+    DensityGrid {
+        width: grid_width,
+        height: grid_height,
+        values,
+    }
+}
+
+/// Blue through cyan, green and yellow to red as `value` goes from 0 to 1.
+fn heat_color(value: f32) -> [u8; 3] {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 255.0],
+        [0.0, 255.0, 255.0],
+        [0.0, 255.0, 0.0],
+        [255.0, 255.0, 0.0],
+        [255.0, 0.0, 0.0],
+    ];
+
+    let scaled = value.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let t = scaled - index as f32;
+    let (from, to) = (STOPS[index], STOPS[index + 1]);
+
+    [0, 1, 2].map(|i| (from[i] + (to[i] - from[i]) * t) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hottest_pixel_is_under_the_clicks() {
+        let points = [XY::new(0.25, 0.5), XY::new(0.25, 0.5), XY::new(0.75, 0.5)];
+        let image = render_heatmap(&points, None, (400, 200), HeatmapOptions::default());
+
+        let [red, green, _, _] = image.get_pixel(100, 100).0;
+        assert!(red > 150 && red > green, "expected red, got {red}/{green}");
+        assert_ne!(image.get_pixel(300, 100).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn no_clicks_only_dims_the_background() {
+        let background = RgbaImage::from_pixel(8, 8, Rgba([200, 100, 50, 255]));
+        let image = render_heatmap(
+            &[],
+            Some(&background),
+            (8, 8),
+            HeatmapOptions {
+                dim: 0.5,
+                ..Default::default()
+            },
+        );
+        assert_eq!(image.get_pixel(3, 3).0, [100, 50, 25, 255]);
+    }
+
+    #[test]
+    fn heat_color_runs_blue_to_red() {
+        assert_eq!(heat_color(0.0), [0, 0, 255]);
+        assert_eq!(heat_color(0.5), [0, 255, 0]);
+        assert_eq!(heat_color(1.0), [255, 0, 0]);
+    }
+}
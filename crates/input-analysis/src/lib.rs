@@ -0,0 +1,619 @@
+//! Interaction analytics for studio recordings.
+//!
+//! Turns the cursor and keyboard events captured alongside every studio
+//! recording into clicks, dwell areas, typing bursts and idle spans, and
+//! renders the clicks as a heatmap over a frame of the recorded display.
+//!
+//! Times are seconds on the recording timeline, with segments laid back to
+//! back, so edits made in the editor don't change the analysis. Positions are
+//! normalised (0-1) over the recorded display.
+
+pub mod heatmap;
+
+use std::path::{Path, PathBuf};
+
+use cap_project::{
+    ClipOffsets, CursorEvents, KeyboardEvents, MultipleSegment, RecordingMeta, StudioRecordingMeta,
+    XY, is_modifier_key,
+};
+use cap_rendering::{ProjectRecordingsMeta, RecordingSegmentDecoders, SegmentVideoPaths};
+use image::RgbaImage;
+use serde::Serialize;
+
+pub use heatmap::{HeatmapOptions, render_heatmap};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AnalysisOptions {
+    /// Normalised distance the cursor can drift and still count as dwelling.
+    pub dwell_radius: f64,
+    /// Seconds the cursor must stay put to count as a dwell.
+    pub min_dwell: f64,
+    /// Longest pause between key presses within one typing burst, in seconds.
+    pub typing_gap: f64,
+    /// Fewest key presses that count as a typing burst.
+    pub min_typing_keys: usize,
+    /// Seconds without any input that count as an idle span.
+    pub min_idle: f64,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            dwell_radius: 0.02,
+            min_dwell: 1.0,
+            typing_gap: 1.0,
+            min_typing_keys: 3,
+            min_idle: 5.0,
+        }
+    }
+}
+
+/// Input captured during one recording segment.
+#[derive(Debug, Clone, Default)]
+pub struct SegmentInput {
+    pub cursor: CursorEvents,
+    pub keyboard: KeyboardEvents,
+    /// Length of the segment in seconds.
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Click {
+    pub time: f64,
+    pub segment: usize,
+    pub button: u8,
+    /// Where the cursor last was, or `None` when the segment has no moves.
+    pub position: Option<XY<f64>>,
+    pub modifiers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dwell {
+    pub start: f64,
+    pub end: f64,
+    pub duration: f64,
+    pub segment: usize,
+    /// Average cursor position while dwelling.
+    pub position: XY<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypingBurst {
+    pub start: f64,
+    pub end: f64,
+    pub duration: f64,
+    pub segment: usize,
+    pub key_count: usize,
+    pub keys_per_second: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleSpan {
+    pub start: f64,
+    pub end: f64,
+    pub duration: f64,
+    pub segment: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputSummary {
+    pub click_count: usize,
+    pub clicks_per_minute: f64,
+    pub key_count: usize,
+    pub typing_time: f64,
+    pub dwell_time: f64,
+    pub idle_time: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputAnalysis {
+    pub duration: f64,
+    pub summary: InputSummary,
+    pub clicks: Vec<Click>,
+    pub dwells: Vec<Dwell>,
+    pub typing_bursts: Vec<TypingBurst>,
+    pub idle_spans: Vec<IdleSpan>,
+}
+
+pub fn analyze(segments: &[SegmentInput], options: AnalysisOptions) -> InputAnalysis {
+    let mut clicks = Vec::new();
+    let mut dwells = Vec::new();
+    let mut typing_bursts = Vec::new();
+    let mut idle_spans = Vec::new();
+    let mut key_count = 0;
+    let mut offset = 0.0;
+
+    for (segment, input) in segments.iter().enumerate() {
+        let mut moves = input.cursor.moves.clone();
+        moves.sort_by(|a, b| a.time_ms.total_cmp(&b.time_ms));
+
+        for click in input.cursor.clicks.iter().filter(|click| click.down) {
+            let position = moves
+                .iter()
+                .rev()
+                .find(|m| m.time_ms <= click.time_ms)
+                .or_else(|| moves.first())
+                .map(|m| XY::new(m.x, m.y));
+            clicks.push(Click {
+                time: offset + click.time_ms / 1000.0,
+                segment,
+                button: click.cursor_num,
+                position,
+                modifiers: click.active_modifiers.clone(),
+            });
+        }
+
+        for (start, end, position) in find_dwells(&moves, input.duration, options) {
+            dwells.push(Dwell {
+                start: offset + start,
+                end: offset + end,
+                duration: end - start,
+                segment,
+                position,
+            });
+        }
+
+        let mut key_times = input
+            .keyboard
+            .presses
+            .iter()
+            .filter(|press| press.down && !is_modifier_key(&press.key))
+            .map(|press| press.time_ms / 1000.0)
+            .collect::<Vec<_>>();
+        key_times.sort_by(f64::total_cmp);
+        key_count += key_times.len();
+
+        for (start, end, count) in find_bursts(&key_times, options) {
+            let duration = end - start;
+            typing_bursts.push(TypingBurst {
+                start: offset + start,
+                end: offset + end,
+                duration,
+                segment,
+                key_count: count,
+                keys_per_second: if duration > 0.0 {
+                    count as f64 / duration
+                } else {
+                    0.0
+                },
+            });
+        }
+
+        let mut event_times = moves
+            .iter()
+            .map(|m| m.time_ms / 1000.0)
+            .chain(input.cursor.clicks.iter().map(|c| c.time_ms / 1000.0))
+            .chain(input.keyboard.presses.iter().map(|p| p.time_ms / 1000.0))
+            .collect::<Vec<_>>();
+        event_times.sort_by(f64::total_cmp);
+
+        for (start, end) in find_idle(&event_times, input.duration, options.min_idle) {
+            idle_spans.push(IdleSpan {
+                start: offset + start,
+                end: offset + end,
+                duration: end - start,
+                segment,
+            });
+        }
+
+        offset += input.duration;
+    }
+
+    clicks.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let duration = offset;
+    let summary = InputSummary {
+        click_count: clicks.len(),
+        clicks_per_minute: if duration > 0.0 {
+            clicks.len() as f64 / (duration / 60.0)
+        } else {
+            0.0
+        },
+        key_count,
+        typing_time: typing_bursts.iter().map(|b| b.duration).sum(),
+        dwell_time: dwells.iter().map(|d| d.duration).sum(),
+        idle_time: idle_spans.iter().map(|s| s.duration).sum(),
+    };
+
+    InputAnalysis {
+        duration,
+        summary,
+        clicks,
+        dwells,
+        typing_bursts,
+        idle_spans,
+    }
+}
+
+/// Spans where the cursor stays within `dwell_radius` of where it stopped.
+/// Moves are only recorded when the cursor moves, so a dwell lasts until the
+/// first move outside the radius, or the end of the segment.
+fn find_dwells(
+    moves: &[cap_project::CursorMoveEvent],
+    duration: f64,
+    options: AnalysisOptions,
+) -> Vec<(f64, f64, XY<f64>)> {
+    let mut dwells = Vec::new();
+    let mut i = 0;
+
+    while i < moves.len() {
+        let anchor = &moves[i];
+        let mut sum = XY::new(anchor.x, anchor.y);
+        let mut j = i + 1;
+        while j < moves.len()
+            && (moves[j].x - anchor.x).hypot(moves[j].y - anchor.y) <= options.dwell_radius
+        {
+            sum.x += moves[j].x;
+            sum.y += moves[j].y;
+            j += 1;
+        }
+
+        let start = anchor.time_ms / 1000.0;
+        let end = match moves.get(j) {
+            Some(next) => next.time_ms / 1000.0,
+            None => duration.max(start),
+        };
+
+        if end - start >= options.min_dwell {
+            let count = (j - i) as f64;
+            dwells.push((start, end, XY::new(sum.x / count, sum.y / count)));
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+
+    dwells
+}
+
+/// Groups sorted key-press times into `(start, end, count)` bursts.
+fn find_bursts(times: &[f64], options: AnalysisOptions) -> Vec<(f64, f64, usize)> {
+    let mut bursts = Vec::new();
+    let mut start = 0;
+
+    for i in 1..=times.len() {
+        if i < times.len() && times[i] - times[i - 1] <= options.typing_gap {
+            continue;
+        }
+
+        let count = i - start;
+        if count >= options.min_typing_keys.max(1) {
+            bursts.push((times[start], times[i - 1], count));
+        }
+        start = i;
+    }
+
+    bursts
+}
+
+/// Gaps of at least `min_idle` seconds between sorted event times, including
+/// before the first and after the last event of the segment.
+fn find_idle(times: &[f64], duration: f64, min_idle: f64) -> Vec<(f64, f64)> {
+    let mut spans = Vec::new();
+    let mut last = 0.0;
+
+    for &time in times.iter().chain(std::iter::once(&duration)) {
+        let time = time.min(duration).max(last);
+        if time - last >= min_idle {
+            spans.push((last, time));
+        }
+        last = time;
+    }
+
+    spans
+}
+
+/// Analysis of a project plus its click heatmap.
+pub struct ProjectAnalysis {
+    pub analysis: InputAnalysis,
+    pub heatmap: RgbaImage,
+    /// Recording time of the frame under the heatmap, when one was decoded.
+    pub frame_time: Option<f64>,
+}
+
+/// Loads the cursor and keyboard events of every segment of a studio
+/// recording and analyses them.
+pub async fn analyze_project(
+    project_path: &Path,
+    options: AnalysisOptions,
+    heatmap: HeatmapOptions,
+    force_ffmpeg_decoder: bool,
+) -> Result<ProjectAnalysis, String> {
+    let recording_meta = RecordingMeta::load_for_project(project_path)
+        .map_err(|e| format!("Failed to load recording metadata: {e}"))?;
+    let studio_meta = recording_meta
+        .studio_meta()
+        .ok_or_else(|| "Only studio recordings capture cursor and keyboard input".to_string())?;
+    let recordings = ProjectRecordingsMeta::new(&recording_meta.project_path, studio_meta)?;
+
+    let segments = studio_segments(studio_meta)
+        .iter()
+        .zip(&recordings.segments)
+        .map(|(segment, recording)| SegmentInput {
+            cursor: segment.cursor_events(&recording_meta),
+            keyboard: segment.keyboard_events(&recording_meta),
+            duration: recording.duration(),
+        })
+        .collect::<Vec<_>>();
+
+    let analysis = analyze(&segments, options);
+
+    let (frame_segment, frame_time) = representative_time(&analysis, &segments);
+    let background = if frame_segment < recordings.segments.len() {
+        decode_display_frame(
+            &recording_meta,
+            studio_meta,
+            frame_segment,
+            frame_time,
+            force_ffmpeg_decoder,
+        )
+        .await?
+    } else {
+        None
+    };
+
+    let size = recordings
+        .segments
+        .get(frame_segment)
+        .map(|s| (s.display.width, s.display.height))
+        .unwrap_or((1920, 1080));
+    let points = analysis
+        .clicks
+        .iter()
+        .filter_map(|click| click.position)
+        .collect::<Vec<_>>();
+
+    let frame_offset: f64 = segments[..frame_segment.min(segments.len())]
+        .iter()
+        .map(|s| s.duration)
+        .sum();
+    let frame_time = background.is_some().then_some(frame_offset + frame_time);
+    let heatmap = render_heatmap(&points, background.as_ref(), size, heatmap);
+
+    Ok(ProjectAnalysis {
+        analysis,
+        heatmap,
+        frame_time,
+    })
+}
+
+fn studio_segments(meta: &StudioRecordingMeta) -> Vec<MultipleSegment> {
+    match meta {
+        StudioRecordingMeta::SingleSegment { segment } => vec![segment.clone().into()],
+        StudioRecordingMeta::MultipleSegments { inner } => inner.segments.clone(),
+    }
+}
+
+/// Picks the segment with the most positioned clicks and its median click
+/// time, so the frame shows what was on screen while people clicked. Falls
+/// back to the middle of the first segment.
+fn representative_time(analysis: &InputAnalysis, segments: &[SegmentInput]) -> (usize, f64) {
+    let mut counts = vec![0usize; segments.len()];
+    for click in analysis.clicks.iter().filter(|c| c.position.is_some()) {
+        if let Some(count) = counts.get_mut(click.segment) {
+            *count += 1;
+        }
+    }
+
+    let busiest = counts
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+        .filter(|(_, count)| **count > 0)
+        .map(|(segment, _)| segment);
+
+    let Some(segment) = busiest else {
+        return (0, segments.first().map(|s| s.duration / 2.0).unwrap_or(0.0));
+    };
+
+    let offset: f64 = segments[..segment].iter().map(|s| s.duration).sum();
+    let times = analysis
+        .clicks
+        .iter()
+        .filter(|c| c.segment == segment && c.position.is_some())
+        .map(|c| c.time - offset)
+        .collect::<Vec<_>>();
+
+    (segment, times[times.len() / 2])
+}
+
+async fn decode_display_frame(
+    recording_meta: &RecordingMeta,
+    studio_meta: &StudioRecordingMeta,
+    segment: usize,
+    time: f64,
+    force_ffmpeg_decoder: bool,
+) -> Result<Option<RgbaImage>, String> {
+    let decoders = RecordingSegmentDecoders::new(
+        recording_meta,
+        studio_meta,
+        SegmentVideoPaths {
+            display: display_path(recording_meta, studio_meta, segment)?,
+            camera: None,
+        },
+        segment,
+        force_ffmpeg_decoder,
+    )
+    .await?;
+
+    let Some(frame) = decoders
+        .get_frames(time as f32, false, true, ClipOffsets::default())
+        .await
+        .and_then(|frames| frames.screen_frame)
+    else {
+        tracing::warn!(time, "No display frame for the heatmap background");
+        return Ok(None);
+    };
+
+    let (width, height) = (frame.width(), frame.height());
+    Ok(frame
+        .to_rgba()
+        .and_then(|rgba| RgbaImage::from_raw(width, height, rgba)))
+}
+
+fn display_path(
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    segment: usize,
+) -> Result<PathBuf, String> {
+    let path = match meta {
+        StudioRecordingMeta::SingleSegment { segment } => &segment.display.path,
+        StudioRecordingMeta::MultipleSegments { inner, .. } => {
+            &inner
+                .segments
+                .get(segment)
+                .ok_or_else(|| format!("Recording segment {segment} does not exist"))?
+                .display
+                .path
+        }
+    };
+
+    Ok(recording_meta.path(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cap_project::{CursorClickEvent, CursorMoveEvent, KeyPressEvent};
+
+    fn move_at(time: f64, x: f64, y: f64) -> CursorMoveEvent {
+        CursorMoveEvent {
+            active_modifiers: Vec::new(),
+            cursor_id: "0".to_string(),
+            time_ms: time * 1000.0,
+            x,
+            y,
+        }
+    }
+
+    fn click_at(time: f64) -> CursorClickEvent {
+        CursorClickEvent {
+            active_modifiers: Vec::new(),
+            cursor_num: 0,
+            cursor_id: "0".to_string(),
+            time_ms: time * 1000.0,
+            down: true,
+        }
+    }
+
+    fn key_at(time: f64, key: &str) -> KeyPressEvent {
+        KeyPressEvent {
+            key: key.to_string(),
+            key_code: key.to_string(),
+            time_ms: time * 1000.0,
+            down: true,
+        }
+    }
+
+    #[test]
+    fn clicks_take_the_last_cursor_position() {
+        let segment = SegmentInput {
+            cursor: CursorEvents {
+                moves: vec![move_at(0.5, 0.1, 0.1), move_at(1.5, 0.8, 0.6)],
+                clicks: vec![click_at(1.0), click_at(2.0)],
+            },
+            duration: 3.0,
+            ..Default::default()
+        };
+
+        let analysis = analyze(&[segment.clone(), segment], AnalysisOptions::default());
+
+        let positions = analysis
+            .clicks
+            .iter()
+            .map(|c| (c.time, c.segment, c.position.map(|p| (p.x, p.y))))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                (1.0, 0, Some((0.1, 0.1))),
+                (2.0, 0, Some((0.8, 0.6))),
+                (4.0, 1, Some((0.1, 0.1))),
+                (5.0, 1, Some((0.8, 0.6))),
+            ]
+        );
+        assert_eq!(analysis.summary.click_count, 4);
+    }
+
+    #[test]
+    fn dwell_lasts_until_the_cursor_leaves_the_radius() {
+        let segment = SegmentInput {
+            cursor: CursorEvents {
+                moves: vec![
+                    move_at(0.0, 0.5, 0.5),
+                    move_at(0.4, 0.505, 0.5),
+                    move_at(2.0, 0.9, 0.9),
+                    move_at(2.2, 0.1, 0.1),
+                ],
+                clicks: Vec::new(),
+            },
+            duration: 4.0,
+            ..Default::default()
+        };
+
+        let analysis = analyze(&[segment], AnalysisOptions::default());
+
+        let dwells = analysis
+            .dwells
+            .iter()
+            .map(|d| (d.start, d.end))
+            .collect::<Vec<_>>();
+        assert_eq!(dwells, vec![(0.0, 2.0), (2.2, 4.0)]);
+        assert!((analysis.dwells[0].position.x - 0.5025).abs() < 1e-9);
+    }
+
+    #[test]
+    fn typing_bursts_ignore_modifiers_and_split_on_pauses() {
+        let segment = SegmentInput {
+            keyboard: KeyboardEvents {
+                presses: vec![
+                    key_at(1.0, "H"),
+                    key_at(1.2, "LShift"),
+                    key_at(1.3, "I"),
+                    key_at(1.5, "Space"),
+                    key_at(5.0, "A"),
+                    key_at(5.2, "B"),
+                ],
+            },
+            duration: 6.0,
+            ..Default::default()
+        };
+
+        let analysis = analyze(&[segment], AnalysisOptions::default());
+
+        assert_eq!(analysis.typing_bursts.len(), 1);
+        let burst = &analysis.typing_bursts[0];
+        assert_eq!((burst.start, burst.end, burst.key_count), (1.0, 1.5, 3));
+        assert_eq!(analysis.summary.key_count, 5);
+    }
+
+    #[test]
+    fn idle_spans_cover_gaps_and_the_tail() {
+        let segment = SegmentInput {
+            cursor: CursorEvents {
+                moves: vec![move_at(1.0, 0.5, 0.5)],
+                clicks: vec![click_at(8.0)],
+            },
+            keyboard: KeyboardEvents {
+                presses: vec![key_at(9.0, "A")],
+            },
+            duration: 20.0,
+        };
+
+        let analysis = analyze(&[segment], AnalysisOptions::default());
+
+        let spans = analysis
+            .idle_spans
+            .iter()
+            .map(|s| (s.start, s.end))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(1.0, 8.0), (9.0, 20.0)]);
+        assert_eq!(analysis.summary.idle_time, 18.0);
+    }
+}
@@ -2,7 +2,7 @@ use std::{
     collections::BTreeMap,
     fmt,
     ops::{Add, Div, Mul, Sub, SubAssign},
-    path::Path,
+    path::{Path, PathBuf},
     sync::LazyLock,
 };
 
@@ -596,6 +596,53 @@ impl Default for Camera {
     }
 }
 
+/// Layout for one of a segment's `additional_videos` (a second camera or
+/// display), drawn over the composite like the camera bubble.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PictureInPictureLayer {
+    /// Matches `AdditionalVideoMeta::id` in the recording meta.
+    pub source_id: String,
+    pub hide: bool,
+    pub mirror: bool,
+    /// Normalized (0-1) center of the layer in output-frame space.
+    pub position: XY<f64>,
+    /// Percentage of the output's shorter side, like `Camera::size`.
+    pub size: f32,
+    pub shape: CameraShape,
+    /// Region of the source frame to show, in source pixels.
+    pub crop: Option<Crop>,
+    pub rounding: f32,
+    pub rounding_type: CornerStyle,
+    pub shadow: f32,
+}
+
+impl PictureInPictureLayer {
+    pub fn new(source_id: impl Into<String>) -> Self {
+        Self {
+            source_id: source_id.into(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for PictureInPictureLayer {
+    fn default() -> Self {
+        Self {
+            source_id: String::new(),
+            hide: false,
+            mirror: false,
+            position: XY::new(0.15, 0.8),
+            size: 25.0,
+            shape: CameraShape::Source,
+            crop: None,
+            rounding: 20.0,
+            rounding_type: CornerStyle::default(),
+            shadow: 50.0,
+        }
+    }
+}
+
 impl Default for ShadowConfiguration {
     fn default() -> Self {
         Self {
@@ -622,7 +669,11 @@ pub struct AudioConfiguration {
     pub improve: bool,
     pub mic_volume_db: f32,
     pub mic_stereo_mode: StereoMode,
+    pub mic_mute: bool,
     pub system_volume_db: f32,
+    /// Mix settings for a segment's `additional_mics`. Microphones without an
+    /// entry play at 0 dB in stereo.
+    pub additional_mics: Vec<AdditionalMicConfiguration>,
 }
 
 impl Default for AudioConfiguration {
@@ -632,11 +683,31 @@ impl Default for AudioConfiguration {
             improve: false,
             mic_volume_db: 0.0,
             mic_stereo_mode: StereoMode::default(),
+            mic_mute: false,
             system_volume_db: 0.0,
+            additional_mics: Vec::new(),
         }
     }
 }
 
+impl AudioConfiguration {
+    pub fn additional_mic(&self, source_id: &str) -> Option<&AdditionalMicConfiguration> {
+        self.additional_mics
+            .iter()
+            .find(|mic| mic.source_id == source_id)
+    }
+}
+
+#[derive(Type, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AdditionalMicConfiguration {
+    /// Matches `AdditionalAudioMeta::id` in the recording meta.
+    pub source_id: String,
+    pub volume_db: f32,
+    pub stereo_mode: StereoMode,
+    pub mute: bool,
+}
+
 #[derive(Type, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum CursorType {
@@ -752,6 +823,9 @@ pub struct CursorConfiguration {
     pub click_spring: Option<ClickSpringConfig>,
     #[serde(default)]
     pub stop_movement_in_last_seconds: Option<f32>,
+    /// Corrections to the recorded cursor path, applied before smoothing.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<CursorEdit>,
 }
 
 impl Default for CursorConfiguration {
@@ -776,6 +850,7 @@ impl Default for CursorConfiguration {
             base_rotation: 0.0,
             click_spring: None,
             stop_movement_in_last_seconds: None,
+            edits: vec![],
         };
 
         if let Some(preset) = animation_style.preset() {
@@ -803,6 +878,97 @@ impl CursorConfiguration {
     pub fn click_spring_config(&self) -> ClickSpringConfig {
         self.click_spring.unwrap_or_default()
     }
+
+    /// Edits that apply to the given recording clip, in the order they were added.
+    pub fn clip_edits(&self, clip: u32) -> Vec<CursorEdit> {
+        self.edits
+            .iter()
+            .filter(|edit| edit.clip == clip)
+            .cloned()
+            .collect()
+    }
+}
+
+/// A correction to the recorded cursor over `start..end`, in seconds of
+/// recording time within `clip`. Later edits win where ranges overlap.
+#[derive(Type, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorEdit {
+    #[serde(default)]
+    pub clip: u32,
+    pub start: f64,
+    pub end: f64,
+    #[serde(flatten)]
+    pub kind: CursorEditKind,
+}
+
+impl CursorEdit {
+    pub fn contains(&self, time_secs: f64) -> bool {
+        time_secs >= self.start && time_secs < self.end
+    }
+}
+
+#[derive(Type, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CursorEditKind {
+    /// Holds the cursor at a normalised display position.
+    Position {
+        position: XY<f64>,
+    },
+    /// Replaces the recorded path with smooth curves between the range
+    /// boundaries and every click inside it. `curvature` bows each curve
+    /// sideways by that fraction of its length; 0 gives straight lines.
+    Straighten {
+        #[serde(default)]
+        curvature: f64,
+    },
+    Hide,
+    /// Draws a different cursor image for the range.
+    Image {
+        image: CursorEditImage,
+    },
+}
+
+#[derive(Type, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "source")]
+pub enum CursorEditImage {
+    /// Another cursor captured in the same recording.
+    #[serde(rename_all = "camelCase")]
+    Recorded { cursor_id: String },
+    /// An image file relative to the project directory. `hotspot` is
+    /// normalised to the image size.
+    Custom { path: PathBuf, hotspot: XY<f64> },
+}
+
+impl CursorEditImage {
+    const CUSTOM_PREFIX: &str = "custom:";
+
+    /// Identifier the renderer uses in place of the recorded cursor id.
+    /// Custom images get an id that [`Self::parse_custom_id`] turns back into
+    /// their path and hotspot, so they can share the recorded cursor caches.
+    pub fn cursor_id(&self) -> String {
+        match self {
+            Self::Recorded { cursor_id } => cursor_id.clone(),
+            Self::Custom { path, hotspot } => format!(
+                "{}{},{}:{}",
+                Self::CUSTOM_PREFIX,
+                hotspot.x,
+                hotspot.y,
+                path.display()
+            ),
+        }
+    }
+
+    /// Returns the project-relative path and hotspot of a custom image id.
+    pub fn parse_custom_id(cursor_id: &str) -> Option<(PathBuf, XY<f64>)> {
+        let rest = cursor_id.strip_prefix(Self::CUSTOM_PREFIX)?;
+        let (hotspot, path) = rest.split_once(':')?;
+        let (x, y) = hotspot.split_once(',')?;
+        Some((
+            PathBuf::from(path),
+            XY::new(x.parse().ok()?, y.parse().ok()?),
+        ))
+    }
 }
 
 #[derive(Type, Serialize, Deserialize, Clone, Debug, Default)]
@@ -972,6 +1138,25 @@ pub struct MaskSegment {
 }
 
 impl MaskSegment {
+    /// Creates an enabled mask on track 0 with the editor's default effect settings.
+    pub fn new(mask_type: MaskKind, start: f64, end: f64, center: XY<f64>, size: XY<f64>) -> Self {
+        Self {
+            start,
+            end,
+            track: 0,
+            enabled: Self::default_enabled(),
+            mask_type,
+            center,
+            size,
+            feather: 0.0,
+            opacity: Self::default_opacity(),
+            pixelation: Self::default_pixelation(),
+            darkness: 0.0,
+            fade_duration: Self::default_fade_duration(),
+            keyframes: MaskKeyframes::default(),
+        }
+    }
+
     fn default_enabled() -> bool {
         true
     }
@@ -1084,6 +1269,36 @@ pub struct TextSegment {
 }
 
 impl TextSegment {
+    /// An overlay with the editor's default styling.
+    pub fn new(start: f64, end: f64, content: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            track: 0,
+            enabled: Self::default_enabled(),
+            content: content.into(),
+            center: Self::default_center(),
+            size: Self::default_size(),
+            font_family: Self::default_font_family(),
+            font_size: Self::default_font_size(),
+            font_weight: Self::default_font_weight(),
+            italic: false,
+            color: Self::default_color(),
+            fade_duration: Self::default_fade_duration(),
+            align: TextAlign::default(),
+            letter_spacing: 0.0,
+            line_height: Self::default_line_height(),
+            opacity: Self::default_opacity(),
+            shadow: 0.0,
+            animation_in: TextAnimation::default(),
+            animation_out: TextAnimation::default(),
+            animation_in_duration: Self::default_fade_duration(),
+            animation_out_duration: Self::default_fade_duration(),
+            layout: TextLayout::default(),
+            layout_transition: Self::default_layout_transition(),
+        }
+    }
+
     fn default_enabled() -> bool {
         true
     }
@@ -1167,7 +1382,7 @@ impl Default for SplitLayout {
     }
 }
 
-fn default_scene_transition() -> f64 {
+pub(crate) fn default_scene_transition() -> f64 {
     0.3
 }
 
@@ -1846,6 +2061,139 @@ impl TimelineConfiguration {
 
         segment_duration + self.held_duration()
     }
+
+    /// Gapless output time at which segment `index` starts, after the overlap
+    /// of its own incoming transition and every one before it. An index past
+    /// the last segment gives the end of the clip timeline.
+    fn segment_output_start(&self, index: usize) -> f64 {
+        let index = index.min(self.segments.len());
+        let clips: f64 = self.segments[..index]
+            .iter()
+            .map(TimelineSegment::duration)
+            .sum();
+        let overlap: f64 = (1..=index.min(self.segments.len().saturating_sub(1)))
+            .filter_map(|segment_index| self.effective_transition(segment_index))
+            .map(|transition| transition.duration)
+            .sum();
+        clips - overlap
+    }
+
+    /// Inserts a clip so it plays at position `index` (clamped to the end),
+    /// pushing everything after it later. Transitions keep pointing at the
+    /// same clips, and track items starting at or after the insertion point
+    /// move by however much longer the timeline got; items spanning it are
+    /// stretched over the new clip. `transition` is added on each side of the
+    /// new clip that has a neighbour, replacing whatever was there.
+    pub fn insert_segment(
+        &mut self,
+        index: usize,
+        segment: TimelineSegment,
+        transition: Option<(ClipTransitionType, f64)>,
+    ) {
+        let index = index.min(self.segments.len());
+        let at = effective_to_output(&self.hold_windows(), self.segment_output_start(index));
+        let duration_before = self.duration();
+
+        for existing in &mut self.transitions {
+            if existing.segment_index as usize >= index {
+                existing.segment_index += 1;
+            }
+        }
+        self.segments.insert(index, segment);
+
+        if let Some((kind, duration)) = transition {
+            for segment_index in [index, index + 1] {
+                if segment_index == 0 || segment_index >= self.segments.len() {
+                    continue;
+                }
+                self.transitions
+                    .retain(|existing| existing.segment_index as usize != segment_index);
+                self.transitions.push(ClipTransition {
+                    segment_index: segment_index as u32,
+                    kind,
+                    duration,
+                });
+            }
+            self.transitions
+                .sort_by_key(|transition| transition.segment_index);
+        }
+
+        let shift = self.duration() - duration_before;
+        self.shift_track_items(|start, end| {
+            if *start >= at {
+                *start += shift;
+                *end += shift;
+            } else if *end > at {
+                *end += shift;
+            }
+        });
+    }
+
+    /// Appends another project's timeline after this one. Its clips are
+    /// renumbered by `recording_clip_offset` (the number of recording
+    /// segments already in this project) and every track item moves back by
+    /// this timeline's length.
+    pub fn append(&mut self, mut other: TimelineConfiguration, recording_clip_offset: u32) {
+        let offset = self.duration();
+        let segment_offset = self.segments.len() as u32;
+
+        other.shift_track_items(|start, end| {
+            *start += offset;
+            *end += offset;
+        });
+        for segment in &mut other.segments {
+            segment.recording_clip += recording_clip_offset;
+        }
+        for transition in &mut other.transitions {
+            transition.segment_index += segment_offset;
+        }
+
+        self.segments.extend(other.segments);
+        self.transitions.extend(other.transitions);
+        self.zoom_segments.extend(other.zoom_segments);
+        self.scene_segments.extend(other.scene_segments);
+        self.mask_segments.extend(other.mask_segments);
+        self.text_segments.extend(other.text_segments);
+        self.caption_segments.extend(other.caption_segments);
+        self.keyboard_segments.extend(other.keyboard_segments);
+        self.audio_segments.extend(other.audio_segments);
+        self.camera3d_segments.extend(other.camera3d_segments);
+    }
+
+    /// Runs `shift` over the output-time range of every track item. Caption
+    /// words move along with their segment's start.
+    fn shift_track_items(&mut self, shift: impl Fn(&mut f64, &mut f64)) {
+        for item in &mut self.zoom_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.scene_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.mask_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.text_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.caption_segments {
+            let start = item.start;
+            shift(&mut item.start, &mut item.end);
+            let moved = (item.start - start) as f32;
+            for word in &mut item.words {
+                word.start += moved;
+                word.end += moved;
+            }
+        }
+        for item in &mut self.keyboard_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.audio_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+        for item in &mut self.camera3d_segments {
+            shift(&mut item.start, &mut item.end);
+        }
+    }
 }
 
 fn active_hold_window(windows: &[(f64, f64)], time: f64) -> Option<(f64, f64)> {
@@ -2236,6 +2584,10 @@ pub struct ProjectConfiguration {
     pub keyboard: Option<KeyboardData>,
     pub clips: Vec<ClipConfiguration>,
     pub annotations: Vec<Annotation>,
+    /// One entry per additional recorded video that should be shown. Sources
+    /// without an entry are recorded but not rendered.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub picture_in_picture: Vec<PictureInPictureLayer>,
     #[serde(skip_serializing)]
     pub hidden_text_segments: Vec<usize>,
     #[serde(default = "ProjectConfiguration::default_screen_motion_blur")]
@@ -2291,6 +2643,7 @@ impl Default for ProjectConfiguration {
             keyboard: Default::default(),
             clips: Default::default(),
             annotations: Default::default(),
+            picture_in_picture: Default::default(),
             hidden_text_segments: Default::default(),
             screen_motion_blur: Self::default_screen_motion_blur(),
             screen_movement_spring: Default::default(),
@@ -2321,7 +2674,31 @@ impl ProjectConfiguration {
         let project_path = project_path.as_ref();
         let config_path = project_path.join("project-config.json");
         let config_str = std::fs::read_to_string(&config_path)?;
-        let parsed_value = serde_json::from_str::<Value>(&config_str).ok();
+        let (config, migrated) = Self::parse(&config_str)?;
+
+        if migrated {
+            match config.write(project_path) {
+                Ok(_) => {
+                    eprintln!("Updated project-config.json migrated settings");
+                }
+                Err(error) => {
+                    eprintln!("Failed to migrate project-config.json: {error}");
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Parses the contents of a `project-config.json`, migrating old settings
+    /// in memory only.
+    pub fn from_json(config_str: &str) -> Result<Self, std::io::Error> {
+        Self::parse(config_str).map(|(config, _)| config)
+    }
+
+    /// Parses and migrates a config, also returning whether it was migrated.
+    fn parse(config_str: &str) -> Result<(Self, bool), std::io::Error> {
+        let parsed_value = serde_json::from_str::<Value>(config_str).ok();
         let missing_screen_motion_blur = parsed_value.as_ref().is_some_and(|value| {
             value
                 .as_object()
@@ -2331,7 +2708,7 @@ impl ProjectConfiguration {
             .as_ref()
             .map(camera_config_needs_migration)
             .unwrap_or(false);
-        let mut config: Self = serde_json::from_str(&config_str)
+        let mut config: Self = serde_json::from_str(config_str)
             .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
         let cursor_motion_blur = config.cursor.motion_blur.clamp(0.0, 1.0);
         let screen_motion_blur = config.screen_motion_blur.clamp(0.0, 1.0);
@@ -2384,34 +2761,31 @@ impl ProjectConfiguration {
             .validate()
             .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
 
-        if needs_camera_migration
+        let migrated = needs_camera_migration
             || needs_motion_blur_clamp
             || needs_screen_motion_blur_migration
-            || needs_text_size_migration
-        {
-            match config.write(project_path) {
-                Ok(_) => {
-                    eprintln!("Updated project-config.json migrated settings");
-                }
-                Err(error) => {
-                    eprintln!("Failed to migrate project-config.json: {error}");
-                }
-            }
-        }
+            || needs_text_size_migration;
 
-        Ok(config)
+        Ok((config, migrated))
     }
 
-    pub fn write(&self, project_path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+    /// Validates the config and serializes it as `project-config.json` holds it.
+    pub fn to_json(&self) -> Result<String, std::io::Error> {
         self.validate()
             .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
 
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn write(&self, project_path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        let json = self.to_json()?;
+
         let project_path = project_path.as_ref();
         let config_path = project_path.join("project-config.json");
         let temp_path =
             project_path.join(format!(".project-config-{}.json.tmp", uuid::Uuid::new_v4()));
 
-        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::write(&temp_path, json)?;
 
         if let Err(error) = std::fs::rename(&temp_path, &config_path) {
             let _ = std::fs::remove_file(&temp_path);
@@ -2897,6 +3271,109 @@ mod tests {
         );
     }
 
+    fn clip(recording_clip: u32, duration: f64) -> TimelineSegment {
+        TimelineSegment {
+            recording_clip,
+            timescale: 1.0,
+            start: 0.0,
+            end: duration,
+            name: None,
+            speed_audio_mode: None,
+        }
+    }
+
+    #[test]
+    fn inserting_an_intro_pushes_tracks_and_transitions_back() {
+        let mut timeline = timeline_with_transitions(vec![ClipTransition {
+            segment_index: 1,
+            kind: ClipTransitionType::CrossFade,
+            duration: 1.0,
+        }]);
+        timeline.zoom_segments.push(ZoomSegment {
+            start: 0.0,
+            end: 2.0,
+            amount: 1.5,
+            mode: ZoomMode::Auto,
+            glide_direction: GlideDirection::None,
+            glide_speed: 0.5,
+            instant_animation: false,
+            edge_snap_ratio: 0.25,
+        });
+
+        timeline.insert_segment(0, clip(2, 3.0), None);
+
+        assert_eq!(timeline.segments[0].recording_clip, 2);
+        assert_eq!(timeline.transitions[0].segment_index, 2);
+        assert_eq!(timeline.zoom_segments[0].start, 3.0);
+        assert_eq!(timeline.zoom_segments[0].end, 5.0);
+        assert_eq!(timeline.duration(), 12.0);
+    }
+
+    #[test]
+    fn inserted_clip_transitions_on_both_sides() {
+        let mut timeline = timeline_with_transitions(Vec::new());
+        timeline.scene_segments.push(SceneSegment {
+            start: 5.0,
+            end: 6.0,
+            mode: SceneMode::default(),
+            split_layout: None,
+            transition_in: 0.0,
+            transition_out: 0.0,
+        });
+
+        timeline.insert_segment(1, clip(2, 3.0), Some((ClipTransitionType::CrossFade, 0.5)));
+
+        let indices: Vec<u32> = timeline
+            .transitions
+            .iter()
+            .map(|transition| transition.segment_index)
+            .collect();
+        assert_eq!(indices, vec![1, 2]);
+        // 3s clip minus two 0.5s overlaps
+        assert_eq!(timeline.scene_segments[0].start, 7.0);
+        assert_eq!(timeline.duration(), 12.0);
+    }
+
+    #[test]
+    fn appended_timeline_follows_the_first() {
+        let mut timeline = timeline_with_transitions(Vec::new());
+        let mut other = timeline_with_transitions(vec![ClipTransition {
+            segment_index: 1,
+            kind: ClipTransitionType::CrossFade,
+            duration: 1.0,
+        }]);
+        other.caption_segments.push(CaptionTrackSegment {
+            id: "caption".to_string(),
+            start: 1.0,
+            end: 2.0,
+            text: "hello".to_string(),
+            words: vec![CaptionWord {
+                text: "hello".to_string(),
+                start: 1.0,
+                end: 1.5,
+            }],
+            fade_duration_override: None,
+            linger_duration_override: None,
+            position_override: None,
+            color_override: None,
+            background_color_override: None,
+            font_size_override: None,
+        });
+
+        timeline.append(other, 2);
+
+        let clips: Vec<u32> = timeline
+            .segments
+            .iter()
+            .map(|segment| segment.recording_clip)
+            .collect();
+        assert_eq!(clips, vec![0, 1, 2, 3]);
+        assert_eq!(timeline.transitions[0].segment_index, 3);
+        assert_eq!(timeline.caption_segments[0].start, 11.0);
+        assert_eq!(timeline.caption_segments[0].words[0].start, 11.0);
+        assert_eq!(timeline.duration(), 19.0);
+    }
+
     fn write_config_with_motion_blur_values(
         project_path: &std::path::Path,
         cursor_motion_blur: f64,
@@ -3324,4 +3801,41 @@ mod tests {
         assert_eq!(spring.damping, default_spring.damping);
         assert_eq!(spring.mass, default_spring.mass);
     }
+
+    #[test]
+    fn cursor_edits_parse_and_filter_by_clip() {
+        let cursor: CursorConfiguration = serde_json::from_value(serde_json::json!({
+            "edits": [
+                { "start": 1.0, "end": 2.0, "type": "position", "position": { "x": 0.5, "y": 0.25 } },
+                { "clip": 1, "start": 0.0, "end": 3.0, "type": "straighten" },
+                { "start": 4.0, "end": 5.0, "type": "hide" },
+                {
+                    "start": 6.0,
+                    "end": 7.0,
+                    "type": "image",
+                    "image": { "source": "custom", "path": "pointer.png", "hotspot": { "x": 0.1, "y": 0.2 } }
+                }
+            ]
+        }))
+        .unwrap();
+
+        let clip_zero = cursor.clip_edits(0);
+        assert_eq!(clip_zero.len(), 3);
+        assert!(matches!(
+            clip_zero[0].kind,
+            CursorEditKind::Position { position } if position.x == 0.5 && position.y == 0.25
+        ));
+        assert_eq!(
+            cursor.clip_edits(1)[0].kind,
+            CursorEditKind::Straighten { curvature: 0.0 }
+        );
+
+        let CursorEditKind::Image { image } = &clip_zero[2].kind else {
+            panic!("expected an image edit");
+        };
+        let (path, hotspot) = CursorEditImage::parse_custom_id(&image.cursor_id()).unwrap();
+        assert_eq!(path, PathBuf::from("pointer.png"));
+        assert_eq!((hotspot.x, hotspot.y), (0.1, 0.2));
+        assert!(CursorEditImage::parse_custom_id("pointer").is_none());
+    }
 }
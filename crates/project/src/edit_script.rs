@@ -0,0 +1,1106 @@
+//! Declarative edit scripts: a short, reviewable list of cuts, speed changes
+//! and overlays that compiles to a [`TimelineConfiguration`].
+//!
+//! Every time in a script refers to the original recording (segments laid end
+//! to end), not to the edited output, so edits don't shift each other and a
+//! script keeps meaning the same thing as lines are added or removed.
+//!
+//! ```yaml
+//! edits:
+//!   - cut 00:10-00:14
+//!   - speed 01:00-02:00 x2
+//!   - zoom at 00:30 for 3s on cursor
+//!   - text "Step 1" at 00:05
+//!   - scene camera-only 00:00-00:04
+//! ```
+//!
+//! Each edit may also be written as a map, e.g.
+//! `{ zoom: { at: "00:30", for: 3, amount: 2, on: { x: 0.2, y: 0.8 } } }`.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    SceneMode, SceneSegment, TextSegment, TimelineConfiguration, TimelineSegment, XY, ZoomMode,
+    ZoomSegment,
+};
+
+pub const MIN_EDIT_SPEED: f64 = 0.25;
+pub const MAX_EDIT_SPEED: f64 = 8.0;
+const DEFAULT_OVERLAY_DURATION: f64 = 3.0;
+const DEFAULT_ZOOM_AMOUNT: f64 = 1.5;
+const EPSILON: f64 = 1e-6;
+
+/// A time in seconds, written as `SS`, `MM:SS` or `HH:MM:SS` with optional
+/// fractional seconds, a plain number of seconds, or `3s` / `500ms`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Timecode(pub f64);
+
+impl Timecode {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let invalid = || format!("invalid time '{value}'");
+
+        let seconds = if let Some(ms) = value.strip_suffix("ms") {
+            ms.parse::<f64>().map_err(|_| invalid())? / 1000.0
+        } else if let Some(seconds) = value.strip_suffix('s') {
+            seconds.parse::<f64>().map_err(|_| invalid())?
+        } else {
+            let mut total = 0.0;
+            let parts: Vec<&str> = value.split(':').collect();
+            if parts.len() > 3 {
+                return Err(invalid());
+            }
+            for (index, part) in parts.iter().enumerate() {
+                let is_last = index + 1 == parts.len();
+                let part_value = if is_last {
+                    part.parse::<f64>().map_err(|_| invalid())?
+                } else {
+                    part.parse::<u32>().map_err(|_| invalid())? as f64
+                };
+                if index > 0 && part_value >= 60.0 {
+                    return Err(invalid());
+                }
+                total = total * 60.0 + part_value;
+            }
+            total
+        };
+
+        if seconds.is_finite() && seconds >= 0.0 {
+            Ok(Self(seconds))
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes = (self.0 / 60.0).floor();
+        write!(f, "{:02}:{:05.2}", minutes as u64, self.0 - minutes * 60.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timecode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Seconds(f64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Seconds(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(Self(seconds)),
+            Raw::Seconds(seconds) => {
+                Err(serde::de::Error::custom(format!("invalid time {seconds}")))
+            }
+            Raw::Text(text) => Self::parse(&text).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum EditOperation {
+    /// Remove a range of the recording.
+    Cut { from: Timecode, to: Timecode },
+    /// Play a range of the recording `factor` times as fast.
+    Speed {
+        from: Timecode,
+        to: Timecode,
+        factor: f64,
+    },
+    /// Zoom in for `for` seconds, following the cursor unless `on` gives a
+    /// normalized point.
+    Zoom {
+        at: Timecode,
+        #[serde(default, rename = "for")]
+        duration: Option<Timecode>,
+        #[serde(default)]
+        amount: Option<f64>,
+        #[serde(default)]
+        on: Option<XY<f32>>,
+    },
+    /// Show a text overlay.
+    Text {
+        content: String,
+        at: Timecode,
+        #[serde(default, rename = "for")]
+        duration: Option<Timecode>,
+    },
+    /// Switch layout (`camera-only`, `hide-camera`, `split-screen`,
+    /// `floating`, `default`) for a range.
+    Scene {
+        mode: String,
+        from: Timecode,
+        to: Timecode,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EditEntry {
+    Line(String),
+    Operation(EditOperation),
+}
+
+impl EditEntry {
+    pub fn operation(&self) -> Result<EditOperation, String> {
+        match self {
+            Self::Line(line) => parse_line(line),
+            Self::Operation(operation) => Ok(operation.clone()),
+        }
+    }
+}
+
+impl fmt::Display for EditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line(line) => write!(f, "{line}"),
+            Self::Operation(operation) => match serde_json::to_string(operation) {
+                Ok(json) => write!(f, "{json}"),
+                Err(_) => write!(f, "{operation:?}"),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EditScript {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    pub edits: Vec<EditEntry>,
+}
+
+fn parse_scene_mode(mode: &str) -> Result<SceneMode, String> {
+    match mode {
+        "default" => Ok(SceneMode::Default),
+        "camera-only" => Ok(SceneMode::CameraOnly),
+        "hide-camera" => Ok(SceneMode::HideCamera),
+        "split-screen" => Ok(SceneMode::SplitScreen),
+        "floating" => Ok(SceneMode::Floating),
+        _ => Err(format!(
+            "unknown scene '{mode}' (expected default, camera-only, hide-camera, split-screen or floating)"
+        )),
+    }
+}
+
+fn parse_range(range: &str) -> Result<(Timecode, Timecode), String> {
+    let (from, to) = range
+        .split_once('-')
+        .ok_or_else(|| format!("expected a range like 00:10-00:14, got '{range}'"))?;
+    Ok((Timecode::parse(from)?, Timecode::parse(to)?))
+}
+
+fn parse_factor(value: &str) -> Result<f64, String> {
+    value
+        .strip_prefix('x')
+        .or_else(|| value.strip_suffix('x'))
+        .and_then(|factor| factor.parse::<f64>().ok())
+        .ok_or_else(|| format!("expected a factor like x2, got '{value}'"))
+}
+
+/// Splits on whitespace, keeping double-quoted text (with `\"` escapes)
+/// together.
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => token.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err("unterminated quote".to_string()),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Reads `at <time>`, `for <duration>`, `x<amount>` and `on <target>`
+/// clauses, in any order.
+#[derive(Default)]
+struct Clauses {
+    at: Option<Timecode>,
+    duration: Option<Timecode>,
+    amount: Option<f64>,
+    on: Option<Option<XY<f32>>>,
+}
+
+fn parse_clauses(tokens: &[String]) -> Result<Clauses, String> {
+    let mut clauses = Clauses::default();
+    let mut tokens = tokens.iter();
+
+    while let Some(token) = tokens.next() {
+        let mut value = || {
+            tokens
+                .next()
+                .ok_or_else(|| format!("'{token}' needs a value"))
+        };
+        match token.as_str() {
+            "at" => clauses.at = Some(Timecode::parse(value()?)?),
+            "for" => clauses.duration = Some(Timecode::parse(value()?)?),
+            "on" => {
+                let target = value()?;
+                clauses.on = Some(if target == "cursor" {
+                    None
+                } else {
+                    let (x, y) = target
+                        .split_once(',')
+                        .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                        .ok_or_else(|| {
+                            format!("expected 'cursor' or a point like 0.25,0.75, got '{target}'")
+                        })?;
+                    Some(XY::new(x, y))
+                });
+            }
+            other if other.starts_with('x') => clauses.amount = Some(parse_factor(other)?),
+            other => return Err(format!("unexpected '{other}'")),
+        }
+    }
+
+    Ok(clauses)
+}
+
+pub fn parse_line(line: &str) -> Result<EditOperation, String> {
+    let tokens = tokenize(line)?;
+    let Some((command, rest)) = tokens.split_first() else {
+        return Err("empty edit".to_string());
+    };
+
+    match (command.as_str(), rest) {
+        ("cut", [range]) => {
+            let (from, to) = parse_range(range)?;
+            Ok(EditOperation::Cut { from, to })
+        }
+        ("speed", [range, factor]) => {
+            let (from, to) = parse_range(range)?;
+            Ok(EditOperation::Speed {
+                from,
+                to,
+                factor: parse_factor(factor)?,
+            })
+        }
+        ("zoom", clauses) => {
+            let clauses = parse_clauses(clauses)?;
+            Ok(EditOperation::Zoom {
+                at: clauses.at.ok_or("zoom needs 'at <time>'")?,
+                duration: clauses.duration,
+                amount: clauses.amount,
+                on: clauses.on.flatten(),
+            })
+        }
+        ("text", [content, clauses @ ..]) => {
+            let clauses = parse_clauses(clauses)?;
+            if clauses.amount.is_some() || clauses.on.is_some() {
+                return Err("text only takes 'at' and 'for'".to_string());
+            }
+            Ok(EditOperation::Text {
+                content: content.clone(),
+                at: clauses.at.ok_or("text needs 'at <time>'")?,
+                duration: clauses.duration,
+            })
+        }
+        ("scene", [mode, range]) => {
+            parse_scene_mode(mode)?;
+            let (from, to) = parse_range(range)?;
+            Ok(EditOperation::Scene {
+                mode: mode.clone(),
+                from,
+                to,
+            })
+        }
+        ("cut" | "speed" | "text" | "scene", _) => Err(format!(
+            "malformed {command}; expected e.g. {}",
+            match command.as_str() {
+                "cut" => "cut 00:10-00:14",
+                "speed" => "speed 01:00-02:00 x2",
+                "text" => "text \"Step 1\" at 00:05",
+                _ => "scene camera-only 00:00-00:04",
+            }
+        )),
+        _ => Err(format!(
+            "unknown edit '{command}' (expected cut, speed, zoom, text or scene)"
+        )),
+    }
+}
+
+/// A stretch of one recording segment that survives the cuts.
+#[derive(Clone, Copy, Debug)]
+struct Piece {
+    clip: u32,
+    start: f64,
+    end: f64,
+    timescale: f64,
+}
+
+/// The recording as pieces on a shared source-time axis.
+struct SourceTimeline {
+    offsets: Vec<f64>,
+    total: f64,
+    pieces: Vec<Piece>,
+}
+
+impl SourceTimeline {
+    fn new(recording_durations: &[f64]) -> Self {
+        let mut offsets = Vec::with_capacity(recording_durations.len());
+        let mut pieces = Vec::new();
+        let mut total = 0.0;
+        for (clip, &duration) in recording_durations.iter().enumerate() {
+            offsets.push(total);
+            if duration > 0.0 {
+                pieces.push(Piece {
+                    clip: clip as u32,
+                    start: 0.0,
+                    end: duration,
+                    timescale: 1.0,
+                });
+            }
+            total += duration.max(0.0);
+        }
+
+        Self {
+            offsets,
+            total,
+            pieces,
+        }
+    }
+
+    fn bounds(&self, piece: &Piece) -> (f64, f64) {
+        let offset = self.offsets[piece.clip as usize];
+        (offset + piece.start, offset + piece.end)
+    }
+
+    fn split_at(&mut self, time: f64) {
+        let Some(index) = self.pieces.iter().position(|piece| {
+            let (start, end) = self.bounds(piece);
+            start + EPSILON < time && time < end - EPSILON
+        }) else {
+            return;
+        };
+
+        let piece = self.pieces[index];
+        let local = time - self.offsets[piece.clip as usize];
+        self.pieces[index].end = local;
+        self.pieces.insert(
+            index + 1,
+            Piece {
+                start: local,
+                ..piece
+            },
+        );
+    }
+
+    fn within(&self, piece: &Piece, from: f64, to: f64) -> bool {
+        let (start, end) = self.bounds(piece);
+        start >= from - EPSILON && end <= to + EPSILON
+    }
+
+    fn cut(&mut self, from: f64, to: f64) {
+        self.split_at(from);
+        self.split_at(to);
+        let pieces = std::mem::take(&mut self.pieces);
+        self.pieces = pieces
+            .into_iter()
+            .filter(|piece| !self.within(piece, from, to))
+            .collect();
+    }
+
+    fn speed(&mut self, from: f64, to: f64, factor: f64) {
+        self.split_at(from);
+        self.split_at(to);
+        for index in 0..self.pieces.len() {
+            if self.within(&self.pieces[index], from, to) {
+                self.pieces[index].timescale = factor;
+            }
+        }
+    }
+
+    /// Output time of a source time, or `None` if it was cut. Times in a cut
+    /// resolve to where the cut closes when `snap` is set.
+    fn to_output(&self, time: f64, snap: bool) -> Option<f64> {
+        let mut output = 0.0;
+        for piece in &self.pieces {
+            let (start, end) = self.bounds(piece);
+            if time < start - EPSILON {
+                return snap.then_some(output);
+            }
+            if time <= end + EPSILON {
+                return Some(output + (time.min(end) - start).max(0.0) / piece.timescale);
+            }
+            output += (end - start) / piece.timescale;
+        }
+        snap.then_some(output)
+    }
+
+    /// The first source time at or after `time` that wasn't cut.
+    fn next_kept(&self, time: f64) -> f64 {
+        self.pieces
+            .iter()
+            .map(|piece| self.bounds(piece))
+            .find(|(_, end)| time <= end + EPSILON)
+            .map_or(time, |(start, _)| time.max(start))
+    }
+
+    fn output_duration(&self) -> f64 {
+        self.pieces
+            .iter()
+            .map(|piece| (piece.end - piece.start) / piece.timescale)
+            .sum()
+    }
+}
+
+impl EditScript {
+    /// Compiles the script against a recording whose segments last
+    /// `recording_durations` seconds. Only clips, zooms, scenes and text are
+    /// produced; see [`EditScript::apply`] to keep a project's other tracks.
+    pub fn compile(&self, recording_durations: &[f64]) -> Result<TimelineConfiguration, String> {
+        self.compile_source(recording_durations)
+            .map(|(_, timeline)| timeline)
+    }
+
+    /// Compiles the script and carries over the tracks it doesn't write
+    /// (masks, captions, keyboard, audio and 3D camera) from `existing`,
+    /// moved through the same cuts and speed changes. Items that end up
+    /// entirely inside a cut are dropped.
+    pub fn apply(
+        &self,
+        recording_durations: &[f64],
+        existing: &TimelineConfiguration,
+    ) -> Result<TimelineConfiguration, String> {
+        let (source, mut timeline) = self.compile_source(recording_durations)?;
+        let duration = timeline.duration();
+        let existing_duration = existing.duration();
+
+        // Existing items are in the old output time; take them back to the
+        // recording through the old timeline, then forward through the new one.
+        let to_source = |time: f64| -> f64 {
+            let mapped = if time < existing_duration {
+                existing.get_segment_time(time.max(0.0))
+            } else {
+                existing
+                    .segments
+                    .last()
+                    .map(|segment| (segment.end, segment))
+            };
+            mapped
+                .and_then(|(time, segment)| {
+                    let offset = source.offsets.get(segment.recording_clip as usize)?;
+                    Some(offset + time)
+                })
+                .unwrap_or(time)
+        };
+        let retime =
+            |time: f64| -> f64 { source.to_output(to_source(time), true).unwrap_or(duration) };
+        let retime_range = |start: &mut f64, end: &mut f64| -> bool {
+            let (old_start, old_end) = (*start, *end);
+            *start = retime(old_start);
+            *end = retime(old_end);
+            *end - *start > EPSILON
+        };
+
+        timeline.mask_segments = existing
+            .mask_segments
+            .iter()
+            .cloned()
+            .filter_map(|mut item| {
+                let old_start = item.start;
+                if !retime_range(&mut item.start, &mut item.end) {
+                    return None;
+                }
+                let relative = |time: f64| retime(old_start + time) - item.start;
+                for key in item
+                    .keyframes
+                    .position
+                    .iter_mut()
+                    .chain(item.keyframes.size.iter_mut())
+                {
+                    key.time = relative(key.time);
+                }
+                for key in &mut item.keyframes.intensity {
+                    key.time = relative(key.time);
+                }
+                Some(item)
+            })
+            .collect();
+
+        timeline.caption_segments = existing
+            .caption_segments
+            .iter()
+            .cloned()
+            .filter_map(|mut item| {
+                if !retime_range(&mut item.start, &mut item.end) {
+                    return None;
+                }
+                item.words.retain_mut(|word| {
+                    let (start, end) = (retime(word.start as f64), retime(word.end as f64));
+                    word.start = start as f32;
+                    word.end = end as f32;
+                    end - start > EPSILON
+                });
+                Some(item)
+            })
+            .collect();
+
+        timeline.keyboard_segments = existing
+            .keyboard_segments
+            .iter()
+            .cloned()
+            .filter_map(|mut item| {
+                let old_start = item.start;
+                if !retime_range(&mut item.start, &mut item.end) {
+                    return None;
+                }
+                for key in &mut item.keys {
+                    key.time_offset = retime(old_start + key.time_offset) - item.start;
+                }
+                Some(item)
+            })
+            .collect();
+
+        // Audio clips play continuously, so only where they start and stop
+        // moves; a lead-in that was cut is skipped in the file.
+        timeline.audio_segments = existing
+            .audio_segments
+            .iter()
+            .cloned()
+            .filter_map(|mut item| {
+                let old_start = item.start;
+                if !retime_range(&mut item.start, &mut item.end) {
+                    return None;
+                }
+                let start = to_source(old_start);
+                item.trim_start += source.next_kept(start) - start;
+                Some(item)
+            })
+            .collect();
+
+        timeline.camera3d_segments = existing
+            .camera3d_segments
+            .iter()
+            .cloned()
+            .filter_map(|mut item| {
+                let old_start = item.start;
+                if !retime_range(&mut item.start, &mut item.end) {
+                    return None;
+                }
+                for track in item.tracks.all_tracks_mut() {
+                    for key in track.iter_mut() {
+                        key.time = retime(old_start + key.time) - item.start;
+                    }
+                }
+                Some(item)
+            })
+            .collect();
+
+        Ok(timeline)
+    }
+
+    fn compile_source(
+        &self,
+        recording_durations: &[f64],
+    ) -> Result<(SourceTimeline, TimelineConfiguration), String> {
+        let operations = self
+            .edits
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                entry
+                    .operation()
+                    .map(|operation| (index, entry, operation))
+                    .map_err(|e| format!("edit {} ({entry}): {e}", index + 1))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut source = SourceTimeline::new(recording_durations);
+        let total = source.total;
+        let check_range = |from: Timecode, to: Timecode| -> Result<(f64, f64), String> {
+            if from.0 + EPSILON >= to.0 {
+                return Err(format!("range {from}-{to} is empty"));
+            }
+            if to.0 > total + EPSILON {
+                return Err(format!(
+                    "range ends at {to}, after the recording ({})",
+                    Timecode(total)
+                ));
+            }
+            Ok((from.0, to.0.min(total)))
+        };
+
+        for (index, entry, operation) in &operations {
+            let context = |e: String| format!("edit {} ({entry}): {e}", index + 1);
+            match operation {
+                EditOperation::Cut { from, to } => {
+                    let (from, to) = check_range(*from, *to).map_err(context)?;
+                    source.cut(from, to);
+                }
+                EditOperation::Speed { from, to, factor } => {
+                    let (from, to) = check_range(*from, *to).map_err(context)?;
+                    if !(MIN_EDIT_SPEED..=MAX_EDIT_SPEED).contains(factor) {
+                        return Err(context(format!(
+                            "speed must be between x{MIN_EDIT_SPEED} and x{MAX_EDIT_SPEED}"
+                        )));
+                    }
+                    source.speed(from, to, *factor);
+                }
+                _ => {}
+            }
+        }
+
+        if source.pieces.is_empty() {
+            return Err("the edits cut the entire recording".to_string());
+        }
+        let duration = source.output_duration();
+
+        let mut zoom_segments: Vec<ZoomSegment> = Vec::new();
+        let mut scene_segments: Vec<SceneSegment> = Vec::new();
+        let mut text_segments: Vec<TextSegment> = Vec::new();
+
+        for (index, entry, operation) in &operations {
+            let context = |e: String| format!("edit {} ({entry}): {e}", index + 1);
+            let point = |at: &Timecode| -> Result<f64, String> {
+                if at.0 > total + EPSILON {
+                    return Err(format!(
+                        "{at} is after the end of the recording ({})",
+                        Timecode(total)
+                    ));
+                }
+                source
+                    .to_output(at.0, false)
+                    .ok_or_else(|| format!("{at} falls inside a cut"))
+            };
+            let overlay = |at: &Timecode, length: &Option<Timecode>| {
+                let start = point(at)?;
+                let length = length.map_or(DEFAULT_OVERLAY_DURATION, |length| length.0);
+                let end = (start + length).min(duration);
+                if end - start <= EPSILON {
+                    return Err("overlay has no time left to show".to_string());
+                }
+                Ok((start, end))
+            };
+
+            match operation {
+                EditOperation::Cut { .. } | EditOperation::Speed { .. } => {}
+                EditOperation::Zoom {
+                    at,
+                    duration: length,
+                    amount,
+                    on,
+                } => {
+                    let (start, end) = overlay(at, length).map_err(context)?;
+                    let amount = amount.unwrap_or(DEFAULT_ZOOM_AMOUNT);
+                    if !amount.is_finite() || amount < 1.0 {
+                        return Err(context("zoom amount must be at least x1".to_string()));
+                    }
+                    if let Some(other) = zoom_segments
+                        .iter()
+                        .find(|other| start < other.end - EPSILON && other.start < end - EPSILON)
+                    {
+                        return Err(context(format!(
+                            "overlaps the zoom at {} in the output",
+                            Timecode(other.start)
+                        )));
+                    }
+                    zoom_segments.push(ZoomSegment {
+                        start,
+                        end,
+                        amount,
+                        mode: match on {
+                            Some(point) => ZoomMode::Manual {
+                                x: point.x.clamp(0.0, 1.0),
+                                y: point.y.clamp(0.0, 1.0),
+                            },
+                            None => ZoomMode::Auto,
+                        },
+                        glide_direction: Default::default(),
+                        glide_speed: 0.5,
+                        instant_animation: false,
+                        edge_snap_ratio: 0.25,
+                    });
+                }
+                EditOperation::Text {
+                    content,
+                    at,
+                    duration: length,
+                } => {
+                    let (start, end) = overlay(at, length).map_err(context)?;
+                    text_segments.push(TextSegment::new(start, end, content.clone()));
+                }
+                EditOperation::Scene { mode, from, to } => {
+                    let mode = parse_scene_mode(mode).map_err(context)?;
+                    let (from, to) = check_range(*from, *to).map_err(context)?;
+                    let start = source.to_output(from, true).unwrap_or(0.0);
+                    let end = source.to_output(to, true).unwrap_or(duration);
+                    if end - start <= EPSILON {
+                        return Err(context("the whole range is cut".to_string()));
+                    }
+                    if let Some(other) = scene_segments
+                        .iter()
+                        .find(|other| start < other.end - EPSILON && other.start < end - EPSILON)
+                    {
+                        return Err(context(format!(
+                            "overlaps the scene at {} in the output",
+                            Timecode(other.start)
+                        )));
+                    }
+                    scene_segments.push(SceneSegment {
+                        start,
+                        end,
+                        mode,
+                        split_layout: None,
+                        transition_in: crate::configuration::default_scene_transition(),
+                        transition_out: crate::configuration::default_scene_transition(),
+                    });
+                }
+            }
+        }
+
+        zoom_segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+        scene_segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+        text_segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        let timeline = TimelineConfiguration {
+            segments: source
+                .pieces
+                .iter()
+                .map(|piece| TimelineSegment {
+                    recording_clip: piece.clip,
+                    timescale: piece.timescale,
+                    start: piece.start,
+                    end: piece.end,
+                    name: None,
+                    speed_audio_mode: None,
+                })
+                .collect(),
+            transitions: Vec::new(),
+            zoom_segments,
+            scene_segments,
+            mask_segments: Vec::new(),
+            text_segments,
+            caption_segments: Vec::new(),
+            keyboard_segments: Vec::new(),
+            audio_segments: Vec::new(),
+            camera3d_segments: Vec::new(),
+        };
+
+        Ok((source, timeline))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(lines: &[&str]) -> EditScript {
+        EditScript {
+            version: None,
+            edits: lines
+                .iter()
+                .map(|line| EditEntry::Line(line.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parses_timecodes() {
+        assert_eq!(Timecode::parse("00:10").unwrap().0, 10.0);
+        assert_eq!(Timecode::parse("1:02:03.5").unwrap().0, 3723.5);
+        assert_eq!(Timecode::parse("3s").unwrap().0, 3.0);
+        assert_eq!(Timecode::parse("250ms").unwrap().0, 0.25);
+        assert!(Timecode::parse("00:75").is_err());
+        assert!(Timecode::parse("soon").is_err());
+    }
+
+    #[test]
+    fn parses_every_line_form() {
+        assert_eq!(
+            parse_line("cut 00:10-00:14").unwrap(),
+            EditOperation::Cut {
+                from: Timecode(10.0),
+                to: Timecode(14.0)
+            }
+        );
+        assert_eq!(
+            parse_line("zoom at 00:30 for 3s on 0.25,0.75 x2").unwrap(),
+            EditOperation::Zoom {
+                at: Timecode(30.0),
+                duration: Some(Timecode(3.0)),
+                amount: Some(2.0),
+                on: Some(XY::new(0.25, 0.75)),
+            }
+        );
+        assert_eq!(
+            parse_line(r#"text "Step \"1\"" at 00:05"#).unwrap(),
+            EditOperation::Text {
+                content: "Step \"1\"".to_string(),
+                at: Timecode(5.0),
+                duration: None,
+            }
+        );
+        assert!(parse_line("scene sideways 00:00-00:04").is_err());
+        assert!(parse_line("fade 00:00-00:04").is_err());
+    }
+
+    #[test]
+    fn cuts_and_speed_changes_split_the_recording() {
+        let timeline = script(&["cut 00:10-00:14", "speed 00:20-00:30 x2"])
+            .compile(&[40.0])
+            .unwrap();
+
+        let spans: Vec<_> = timeline
+            .segments
+            .iter()
+            .map(|s| (s.start, s.end, s.timescale))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0.0, 10.0, 1.0),
+                (14.0, 20.0, 1.0),
+                (20.0, 30.0, 2.0),
+                (30.0, 40.0, 1.0)
+            ]
+        );
+        assert_eq!(timeline.duration(), 31.0);
+    }
+
+    #[test]
+    fn overlays_are_placed_in_output_time() {
+        let timeline = script(&[
+            "cut 00:00-00:05",
+            "speed 00:10-00:20 x2",
+            "zoom at 00:25 for 3s on cursor",
+            r#"text "Step 1" at 00:06 for 2s"#,
+            "scene camera-only 00:02-00:08",
+        ])
+        .compile(&[20.0, 20.0])
+        .unwrap();
+
+        let zoom = &timeline.zoom_segments[0];
+        assert_eq!((zoom.start, zoom.end), (15.0, 18.0));
+        assert!(matches!(zoom.mode, ZoomMode::Auto));
+        let text = &timeline.text_segments[0];
+        assert_eq!(
+            (text.start, text.end, text.content.as_str()),
+            (1.0, 3.0, "Step 1")
+        );
+        let scene = &timeline.scene_segments[0];
+        assert_eq!((scene.start, scene.end), (0.0, 3.0));
+        assert_eq!(timeline.segments[2].recording_clip, 1);
+    }
+
+    #[test]
+    fn rejects_edits_outside_the_recording() {
+        let error = script(&["cut 00:30-01:10"]).compile(&[60.0]).unwrap_err();
+        assert!(error.starts_with("edit 1 (cut 00:30-01:10)"), "{error}");
+
+        let error = script(&["cut 00:10-00:20", "text \"Hi\" at 00:15"])
+            .compile(&[60.0])
+            .unwrap_err();
+        assert!(error.contains("falls inside a cut"), "{error}");
+
+        let error = script(&["cut 00:00-01:00"]).compile(&[60.0]).unwrap_err();
+        assert!(error.contains("entire recording"), "{error}");
+    }
+
+    #[test]
+    fn accepts_structured_entries() {
+        let script: EditScript = serde_json::from_str(
+            r#"{ "edits": [
+                "cut 00:01-00:02",
+                { "zoom": { "at": "00:04", "for": 2, "amount": 2, "on": { "x": 0.2, "y": 0.8 } } },
+                { "scene": { "mode": "hide-camera", "from": 0, "to": "00:03" } }
+            ] }"#,
+        )
+        .unwrap();
+        let timeline = script.compile(&[10.0]).unwrap();
+
+        assert_eq!(timeline.zoom_segments[0].start, 3.0);
+        assert!(matches!(
+            timeline.zoom_segments[0].mode,
+            ZoomMode::Manual { x, y } if x == 0.2 && y == 0.8
+        ));
+        assert_eq!(timeline.scene_segments[0].end, 2.0);
+    }
+
+    /// A 10s single-clip timeline with `tracks` merged in, retimed by
+    /// cutting 00:02-00:04.
+    fn apply_cut(tracks: serde_json::Value) -> TimelineConfiguration {
+        let mut existing = serde_json::json!({
+            "segments": [{ "recordingSegment": 0, "timescale": 1.0, "start": 0.0, "end": 10.0 }],
+            "zoomSegments": [],
+        });
+        existing
+            .as_object_mut()
+            .unwrap()
+            .extend(tracks.as_object().unwrap().clone());
+        let existing: TimelineConfiguration = serde_json::from_value(existing).unwrap();
+
+        script(&["cut 00:02-00:04"])
+            .apply(&[10.0], &existing)
+            .unwrap()
+    }
+
+    #[test]
+    fn apply_retimes_masks() {
+        let mask = |start: f64, end: f64| {
+            serde_json::json!({
+                "start": start,
+                "end": end,
+                "maskType": "sensitive",
+                "center": { "x": 0.5, "y": 0.5 },
+                "size": { "x": 0.25, "y": 0.25 },
+                "keyframes": {
+                    "position": [{ "time": 4.0, "x": 0.1, "y": 0.1 }],
+                    "intensity": [{ "time": 0.5, "value": 1.0 }]
+                }
+            })
+        };
+        let timeline = apply_cut(serde_json::json!({
+            "maskSegments": [mask(1.0, 6.0), mask(2.5, 3.5)]
+        }));
+
+        assert_eq!(timeline.mask_segments.len(), 1);
+        let mask = &timeline.mask_segments[0];
+        assert_eq!((mask.start, mask.end), (1.0, 4.0));
+        assert_eq!(mask.keyframes.position[0].time, 2.0);
+        assert_eq!(mask.keyframes.intensity[0].time, 0.5);
+    }
+
+    #[test]
+    fn apply_retimes_captions() {
+        let timeline = apply_cut(serde_json::json!({
+            "captionSegments": [{
+                "id": "c1",
+                "start": 1.0,
+                "end": 6.0,
+                "text": "one two three",
+                "words": [
+                    { "text": "one", "start": 1.0, "end": 1.5 },
+                    { "text": "two", "start": 2.5, "end": 3.0 },
+                    { "text": "three", "start": 5.0, "end": 6.0 }
+                ]
+            }]
+        }));
+
+        let caption = &timeline.caption_segments[0];
+        assert_eq!((caption.start, caption.end), (1.0, 4.0));
+        let words: Vec<_> = caption
+            .words
+            .iter()
+            .map(|word| (word.text.as_str(), word.start, word.end))
+            .collect();
+        assert_eq!(words, vec![("one", 1.0, 1.5), ("three", 3.0, 4.0)]);
+    }
+
+    #[test]
+    fn apply_retimes_keyboard_presses() {
+        let timeline = apply_cut(serde_json::json!({
+            "keyboardSegments": [{
+                "id": "k1",
+                "start": 3.0,
+                "end": 5.0,
+                "displayText": "ab",
+                "keys": [
+                    { "key": "a", "timeOffset": 0.0 },
+                    { "key": "b", "timeOffset": 1.5 }
+                ]
+            }]
+        }));
+
+        let keyboard = &timeline.keyboard_segments[0];
+        assert_eq!((keyboard.start, keyboard.end), (2.0, 3.0));
+        let offsets: Vec<_> = keyboard.keys.iter().map(|key| key.time_offset).collect();
+        assert_eq!(offsets, vec![0.0, 0.5]);
+    }
+
+    #[test]
+    fn apply_retimes_audio_clips() {
+        let timeline = apply_cut(serde_json::json!({
+            "audioSegments": [
+                { "start": 1.0, "end": 8.0, "path": "music.mp3" },
+                { "start": 3.0, "end": 8.0, "path": "voice.mp3", "trimStart": 0.5 },
+                { "start": 2.0, "end": 4.0, "path": "gone.mp3" }
+            ]
+        }));
+
+        let clips: Vec<_> = timeline
+            .audio_segments
+            .iter()
+            .map(|clip| (clip.path.as_str(), clip.start, clip.end, clip.trim_start))
+            .collect();
+        assert_eq!(
+            clips,
+            vec![("music.mp3", 1.0, 6.0, 0.0), ("voice.mp3", 2.0, 6.0, 1.5)]
+        );
+    }
+
+    #[test]
+    fn apply_retimes_camera3d_keyframes() {
+        let timeline = apply_cut(serde_json::json!({
+            "camera3dSegments": [{
+                "start": 0.0,
+                "end": 10.0,
+                "tracks": {
+                    "zoom": [{ "time": 1.0, "value": 1.0 }, { "time": 5.0, "value": 2.0 }]
+                }
+            }]
+        }));
+
+        let camera = &timeline.camera3d_segments[0];
+        assert_eq!((camera.start, camera.end), (0.0, 8.0));
+        let times: Vec<_> = camera.tracks.zoom.iter().map(|key| key.time).collect();
+        assert_eq!(times, vec![1.0, 3.0]);
+    }
+
+    #[test]
+    fn apply_keeps_tracks_through_the_previous_edits() {
+        // The old timeline already sped up the first half; its mask sits at
+        // 5s of the recording either way.
+        let existing: TimelineConfiguration = serde_json::from_value(serde_json::json!({
+            "segments": [
+                { "recordingSegment": 0, "timescale": 2.0, "start": 0.0, "end": 4.0 },
+                { "recordingSegment": 0, "timescale": 1.0, "start": 4.0, "end": 10.0 }
+            ],
+            "zoomSegments": [],
+            "maskSegments": [{
+                "start": 3.0,
+                "end": 4.0,
+                "maskType": "sensitive",
+                "center": { "x": 0.5, "y": 0.5 },
+                "size": { "x": 0.25, "y": 0.25 }
+            }]
+        }))
+        .unwrap();
+
+        let timeline = script(&["cut 00:00-00:01"])
+            .apply(&[10.0], &existing)
+            .unwrap();
+
+        let mask = &timeline.mask_segments[0];
+        assert_eq!((mask.start, mask.end), (4.0, 5.0));
+    }
+}
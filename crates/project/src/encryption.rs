@@ -0,0 +1,1104 @@
+//! Envelope encryption of a project's files at rest.
+//!
+//! A project is encrypted with its own random 256-bit data key, which is
+//! stored in `encryption.json` wrapped (AES-256-GCM) by a [`MasterKey`] that
+//! never touches the project. The apps find the master key in the keyfile
+//! named by [`KEY_FILE_ENV`] or in the OS keyring; see
+//! [`MasterKey::load_from_env_or_keyring`]. Every other file in the project is sealed on its
+//! own, so a recording that is still being written can seal each fragment as
+//! it completes and a crashed recording is left as a set of individually
+//! readable files.
+//!
+//! A sealed file starts with [`MAGIC`] and a random nonce prefix, followed by
+//! the plaintext in [`CHUNK_SIZE`] chunks, each encrypted with AES-256-GCM
+//! under a nonce made of the prefix, the chunk's index and a final-chunk flag.
+//! Every chunk is also authenticated with the file's path in the project, so
+//! reordering, truncating or editing a file, or swapping it for another sealed
+//! file of the project, makes it fail to open.
+//!
+//! Small files such as `recording-meta.json` are read and written in memory
+//! with [`ProjectKey::read`] and [`ProjectKey::write`]. Media tools need plain
+//! files, so readers of media open an encrypted project through a
+//! [`DecryptedProject`]: a copy of just the files they ask for, sealed back
+//! into the project by [`DecryptedProject::persist`] and deleted when dropped.
+//! That copy is plaintext for as long as it exists. It lives in a directory
+//! only the current user can open, on a memory-backed filesystem where the
+//! platform has one (`/dev/shm` on Linux), so it never reaches a disk there; a
+//! copy left behind by a process that was killed is deleted the next time any
+//! encrypted project is opened.
+//!
+//! Every file of an encrypted project is expected to be sealed. The only
+//! exception is a recording that crashed, which can leave the files it was
+//! still writing as plaintext; [`DecryptedProject::open_recovering`] accepts
+//! those, and only those its caller names.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+
+use aes_gcm::{
+    Aes256Gcm, Key, KeyInit, Nonce,
+    aead::{Aead, OsRng, Payload, rand_core::RngCore},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Name of the file holding a project's wrapped data key. Its presence marks
+/// a project as encrypted.
+pub const ENCRYPTION_FILE: &str = "encryption.json";
+/// First bytes of every sealed file.
+pub const MAGIC: &[u8; 8] = b"CAPENC\x00\x01";
+/// Plaintext bytes per encrypted chunk.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+/// Environment variable naming a keyfile to use instead of the OS keyring.
+pub const KEY_FILE_ENV: &str = "CAP_RECORDING_KEY_FILE";
+const KEYRING_SERVICE: &str = "so.cap.cli";
+const KEYRING_USER: &str = "recording-encryption-key";
+
+pub const KEY_LEN: usize = 32;
+const NONCE_PREFIX_LEN: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + NONCE_PREFIX_LEN;
+const TAG_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const ALGORITHM: &str = "AES-256-GCM";
+const ENCRYPTION_VERSION: u32 = 2;
+const SEAL_TMP_SUFFIX: &str = ".seal.tmp";
+const WORKSPACE_PREFIX: &str = "cap-decrypted-";
+
+fn keyring_entry() -> io::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| io::Error::other(format!("the OS keyring is unavailable: {e}")))
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// The key that wraps project data keys, loaded from a keyfile or a keyring.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MasterKey([u8; KEY_LEN]);
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MasterKey").field(&self.id()).finish()
+    }
+}
+
+impl MasterKey {
+    pub fn generate() -> Self {
+        let mut key = [0; KEY_LEN];
+        OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    /// Reads a key written as 32 raw bytes or 64 hex characters.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        if let Ok(key) = <[u8; KEY_LEN]>::try_from(bytes) {
+            return Ok(Self(key));
+        }
+
+        let hex = std::str::from_utf8(bytes)
+            .map(str::trim)
+            .map_err(|_| invalid_data("key must be 32 bytes or 64 hex characters"))?;
+        let mut key = [0; KEY_LEN];
+        hex::decode_to_slice(hex, &mut key)
+            .map_err(|_| invalid_data("key must be 32 bytes or 64 hex characters"))?;
+        Ok(Self(key))
+    }
+
+    pub fn from_keyfile(path: &Path) -> io::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// The key from the keyfile named by [`KEY_FILE_ENV`], or else the one in
+    /// the OS keyring, or `None` when neither has been set up.
+    pub fn load_from_env_or_keyring() -> io::Result<Option<Self>> {
+        if let Some(path) = std::env::var_os(KEY_FILE_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+        {
+            return Self::from_keyfile(&path)
+                .map(Some)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())));
+        }
+
+        match keyring_entry()?.get_password() {
+            Ok(value) => Self::from_bytes(value.as_bytes())
+                .map(Some)
+                .map_err(|e| io::Error::new(e.kind(), format!("OS keyring: {e}"))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(io::Error::other(format!(
+                "failed to read the encryption key from the OS keyring: {e}"
+            ))),
+        }
+    }
+
+    /// Stores the key in the OS keyring, where
+    /// [`MasterKey::load_from_env_or_keyring`] finds it.
+    pub fn store_in_keyring(&self) -> io::Result<()> {
+        keyring_entry()?.set_password(&self.to_hex()).map_err(|e| {
+            io::Error::other(format!(
+                "failed to store the encryption key in the OS keyring: {e}"
+            ))
+        })
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// A short, non-secret identifier for the key, recorded in each project it
+    /// encrypts so a wrong key can be told apart from a damaged project.
+    pub fn id(&self) -> String {
+        let digest = Sha256::new()
+            .chain_update(b"cap-project-master-key")
+            .chain_update(self.0)
+            .finalize();
+        hex::encode(&digest[..8])
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+/// Contents of `encryption.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionInfo {
+    pub version: u32,
+    pub algorithm: String,
+    /// [`MasterKey::id`] of the key that wraps the data key.
+    pub key_id: String,
+    pub nonce: String,
+    pub wrapped_key: String,
+}
+
+/// Whether `project_path` is an encrypted project.
+pub fn is_encrypted(project_path: &Path) -> bool {
+    project_path.join(ENCRYPTION_FILE).is_file()
+}
+
+/// Writes `contents` to `path`, sealed when there is a `key`, so files written
+/// while a project is being recorded are never on disk as plaintext.
+pub fn write_file(key: Option<&ProjectKey>, path: &Path, contents: &[u8]) -> io::Result<()> {
+    match key {
+        Some(key) => key.write(path, contents),
+        None => std::fs::write(path, contents),
+    }
+}
+
+/// Whether the file at `path` is sealed.
+pub fn is_sealed(path: &Path) -> io::Result<bool> {
+    let mut magic = [0; MAGIC.len()];
+    let mut file = File::open(path)?;
+    Ok(read_full(&mut file, &mut magic)? == MAGIC.len() && &magic == MAGIC)
+}
+
+/// A project's data key.
+#[derive(Clone)]
+pub struct ProjectKey {
+    cipher: Aes256Gcm,
+    project: PathBuf,
+}
+
+impl fmt::Debug for ProjectKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProjectKey")
+            .field("project", &self.project)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProjectKey {
+    /// Gives `project_path` a new data key wrapped by `master`.
+    pub fn create(project_path: &Path, master: &MasterKey) -> io::Result<Self> {
+        let info_path = project_path.join(ENCRYPTION_FILE);
+        if info_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} is already encrypted", project_path.display()),
+            ));
+        }
+
+        let mut data_key = [0; KEY_LEN];
+        OsRng.fill_bytes(&mut data_key);
+        let mut nonce = [0; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let wrapped_key = master
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), data_key.as_slice())
+            .map_err(|_| invalid_data("failed to wrap the project key"))?;
+
+        let info = EncryptionInfo {
+            version: ENCRYPTION_VERSION,
+            algorithm: ALGORITHM.to_string(),
+            key_id: master.id(),
+            nonce: hex::encode(nonce),
+            wrapped_key: hex::encode(wrapped_key),
+        };
+
+        std::fs::create_dir_all(project_path)?;
+        let temp_path = project_path.join(format!("{ENCRYPTION_FILE}.tmp"));
+        std::fs::write(&temp_path, serde_json::to_string_pretty(&info)?)?;
+        File::open(&temp_path)?.sync_all()?;
+        std::fs::rename(&temp_path, &info_path)?;
+
+        Ok(Self::from_data_key(project_path, &data_key))
+    }
+
+    /// Unwraps the data key of an encrypted project.
+    pub fn open(project_path: &Path, master: &MasterKey) -> io::Result<Self> {
+        let info: EncryptionInfo = serde_json::from_str(&std::fs::read_to_string(
+            project_path.join(ENCRYPTION_FILE),
+        )?)?;
+
+        if info.version != ENCRYPTION_VERSION || info.algorithm != ALGORITHM {
+            return Err(invalid_data(format!(
+                "unsupported project encryption: version {} {}",
+                info.version, info.algorithm
+            )));
+        }
+        if info.key_id != master.id() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "project is encrypted with key {}, not {}",
+                    info.key_id,
+                    master.id()
+                ),
+            ));
+        }
+
+        let nonce = hex::decode(&info.nonce).map_err(|_| invalid_data("invalid key nonce"))?;
+        let wrapped_key =
+            hex::decode(&info.wrapped_key).map_err(|_| invalid_data("invalid wrapped key"))?;
+        if nonce.len() != NONCE_LEN {
+            return Err(invalid_data("invalid key nonce"));
+        }
+
+        let data_key = master
+            .cipher()
+            .decrypt(Nonce::from_slice(&nonce), wrapped_key.as_slice())
+            .map_err(|_| invalid_data("failed to unwrap the project key"))?;
+        if data_key.len() != KEY_LEN {
+            return Err(invalid_data("invalid project key length"));
+        }
+
+        Ok(Self::from_data_key(project_path, &data_key))
+    }
+
+    fn from_data_key(project_path: &Path, data_key: &[u8]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(data_key)),
+            project: project_path.to_path_buf(),
+        }
+    }
+
+    /// `path` relative to the project, which is what a sealed file is bound to.
+    fn relative_path(&self, path: &Path) -> io::Result<PathBuf> {
+        if let Ok(relative) = path.strip_prefix(&self.project) {
+            return Ok(relative.to_path_buf());
+        }
+
+        // The project may have been named by another path to the same place.
+        let outside = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} is not in the project at {}",
+                    path.display(),
+                    self.project.display()
+                ),
+            )
+        };
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return Err(outside());
+        };
+        parent
+            .canonicalize()?
+            .join(name)
+            .strip_prefix(self.project.canonicalize()?)
+            .map(Path::to_path_buf)
+            .map_err(|_| outside())
+    }
+
+    /// Encrypts `reader` into `writer`, for the file at `relative` in the
+    /// project.
+    pub fn seal(&self, relative: &Path, reader: impl Read, writer: impl Write) -> io::Result<()> {
+        let mut reader = reader;
+        let mut writer = writer;
+
+        let mut header = [0; HEADER_LEN];
+        header[..MAGIC.len()].copy_from_slice(MAGIC);
+        OsRng.fill_bytes(&mut header[MAGIC.len()..]);
+        let aad = associated_data(&header, relative)?;
+        writer.write_all(&header)?;
+
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let mut next = vec![0; CHUNK_SIZE];
+        let mut len = read_full(&mut reader, &mut chunk)?;
+        let mut index = 0;
+
+        loop {
+            // Reading one chunk ahead tells us which chunk is the last.
+            let next_len = if len == CHUNK_SIZE {
+                read_full(&mut reader, &mut next)?
+            } else {
+                0
+            };
+            let last = next_len == 0;
+
+            let ciphertext = self
+                .cipher
+                .encrypt(
+                    &chunk_nonce(&header, index, last),
+                    Payload {
+                        msg: &chunk[..len],
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| invalid_data("encryption failed"))?;
+            writer.write_all(&ciphertext)?;
+
+            if last {
+                break;
+            }
+
+            std::mem::swap(&mut chunk, &mut next);
+            len = next_len;
+            index = index
+                .checked_add(1)
+                .ok_or_else(|| invalid_data("file too large to seal"))?;
+        }
+
+        writer.flush()
+    }
+
+    /// Decrypts a sealed `reader` into `writer`, failing if it was modified,
+    /// cut short or sealed for a file other than the one at `relative` in the
+    /// project.
+    pub fn unseal(&self, relative: &Path, reader: impl Read, writer: impl Write) -> io::Result<()> {
+        let mut reader = reader;
+        let mut writer = writer;
+
+        let mut header = [0; HEADER_LEN];
+        if read_full(&mut reader, &mut header)? != HEADER_LEN || &header[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a sealed file"));
+        }
+        let aad = associated_data(&header, relative)?;
+
+        let mut chunk = vec![0; CHUNK_SIZE + TAG_LEN];
+        let mut next = vec![0; CHUNK_SIZE + TAG_LEN];
+        let mut len = read_full(&mut reader, &mut chunk)?;
+        let mut index = 0;
+
+        loop {
+            let next_len = if len == chunk.len() {
+                read_full(&mut reader, &mut next)?
+            } else {
+                0
+            };
+            let last = next_len == 0;
+
+            let plaintext = self
+                .cipher
+                .decrypt(
+                    &chunk_nonce(&header, index, last),
+                    Payload {
+                        msg: &chunk[..len],
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| invalid_data("sealed file is corrupted, truncated or misplaced"))?;
+            writer.write_all(&plaintext)?;
+
+            if last {
+                break;
+            }
+
+            std::mem::swap(&mut chunk, &mut next);
+            len = next_len;
+            index = index
+                .checked_add(1)
+                .ok_or_else(|| invalid_data("sealed file is too large"))?;
+        }
+
+        writer.flush()
+    }
+
+    /// Reads the plaintext of the sealed file at `path` into memory.
+    pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let relative = self.relative_path(path)?;
+        let mut plaintext = Vec::new();
+        self.unseal(&relative, BufReader::new(File::open(path)?), &mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    /// Seals `contents` into the file at `path`, replacing it atomically.
+    pub fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let relative = self.relative_path(path)?;
+        write_atomically(path, |file| {
+            self.seal(&relative, contents, BufWriter::new(file))
+        })
+    }
+
+    /// Encrypts the file at `path` in place, through a temporary file that
+    /// replaces it once fully written, and returns its new size. A file that
+    /// is already sealed is left alone.
+    pub fn seal_file(&self, path: &Path) -> io::Result<u64> {
+        if !is_sealed(path)? {
+            self.seal_file_to(path, path)?;
+        }
+        Ok(std::fs::metadata(path)?.len())
+    }
+
+    /// Writes a sealed copy of `source` to `destination`, replacing it
+    /// atomically.
+    pub fn seal_file_to(&self, source: &Path, destination: &Path) -> io::Result<()> {
+        let relative = self.relative_path(destination)?;
+        write_atomically(destination, |file| {
+            self.seal(
+                &relative,
+                BufReader::new(File::open(source)?),
+                BufWriter::new(file),
+            )
+        })
+    }
+
+    /// Writes the plaintext of the sealed file at `source` to `destination`.
+    pub fn unseal_file_to(&self, source: &Path, destination: &Path) -> io::Result<()> {
+        let relative = self.relative_path(source)?;
+        let file = File::create(destination)?;
+        self.unseal(
+            &relative,
+            BufReader::new(File::open(source)?),
+            BufWriter::new(&file),
+        )?;
+        file.sync_all()
+    }
+
+    /// Seals every file in the project that is not sealed yet.
+    pub fn seal_project(&self, project_path: &Path) -> io::Result<()> {
+        for relative in project_files(project_path)? {
+            self.seal_file(&project_path.join(relative))?;
+        }
+        Ok(())
+    }
+}
+
+/// What every chunk of a sealed file is authenticated with besides its
+/// contents: the file's header and its path in the project, spelled the same
+/// on every platform.
+fn associated_data(header: &[u8; HEADER_LEN], relative: &Path) -> io::Result<Vec<u8>> {
+    let mut aad = header.to_vec();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => {
+                aad.push(b'/');
+                aad.extend_from_slice(name.as_encoded_bytes());
+            }
+            Component::CurDir => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is not a path in the project", relative.display()),
+                ));
+            }
+        }
+    }
+    Ok(aad)
+}
+
+fn chunk_nonce(
+    header: &[u8; HEADER_LEN],
+    index: u32,
+    last: bool,
+) -> Nonce<aes_gcm::aead::consts::U12> {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(&header[MAGIC.len()..]);
+    nonce[NONCE_PREFIX_LEN..NONCE_LEN - 1].copy_from_slice(&index.to_be_bytes());
+    nonce[NONCE_LEN - 1] = u8::from(last);
+    *Nonce::from_slice(&nonce)
+}
+
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn seal_tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(SEAL_TMP_SUFFIX);
+    PathBuf::from(tmp)
+}
+
+fn write_atomically(path: &Path, write: impl FnOnce(&File) -> io::Result<()>) -> io::Result<()> {
+    let temp_path = seal_tmp_path(path);
+    let result = File::create(&temp_path).and_then(|file| {
+        write(&file)?;
+        file.sync_all()
+    });
+
+    if let Err(error) = result.and_then(|()| std::fs::rename(&temp_path, path)) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// The project's files, relative to it, leaving out `encryption.json` and
+/// leftovers of interrupted seals.
+fn project_files(project_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(project_path.join(&dir))? {
+            let entry = entry?;
+            let relative = dir.join(entry.file_name());
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() {
+                dirs.push(relative);
+            } else if file_type.is_file()
+                && relative != Path::new(ENCRYPTION_FILE)
+                && !entry
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with(SEAL_TMP_SUFFIX)
+            {
+                files.push(relative);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn project_dirs(project_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut all = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(project_path.join(&dir))? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                let relative = dir.join(entry.file_name());
+                all.push(relative.clone());
+                dirs.push(relative);
+            }
+        }
+    }
+
+    Ok(all)
+}
+
+type FileStamp = (u64, Option<SystemTime>);
+
+fn file_stamp(path: &Path) -> io::Result<FileStamp> {
+    let metadata = std::fs::metadata(path)?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Where decrypted working copies are made: a memory-backed filesystem where
+/// there is one, so plaintext media never reaches a disk.
+fn workspace_root() -> PathBuf {
+    let shm = Path::new("/dev/shm");
+    if cfg!(target_os = "linux") && shm.is_dir() {
+        shm.to_path_buf()
+    } else {
+        std::env::temp_dir()
+    }
+}
+
+/// Deletes working copies whose process is gone, such as one that was killed
+/// before it could drop its [`DecryptedProject`].
+fn remove_abandoned_workspaces(root: &Path) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+
+    let workspaces = entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix(WORKSPACE_PREFIX))
+                .and_then(|rest| rest.split_once('-'))
+                .and_then(|(pid, _)| pid.parse::<u32>().ok())?;
+            Some((sysinfo::Pid::from_u32(pid), entry.path()))
+        })
+        .collect::<Vec<_>>();
+    if workspaces.is_empty() {
+        return;
+    }
+
+    let pids = workspaces.iter().map(|(pid, _)| *pid).collect::<Vec<_>>();
+    let mut system = sysinfo::System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&pids), true);
+
+    for (pid, path) in workspaces {
+        if system.process(pid).is_none() {
+            // Fails harmlessly on another user's copy, which we can't open.
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+fn create_workspace() -> io::Result<tempfile::TempDir> {
+    let root = workspace_root();
+    remove_abandoned_workspaces(&root);
+
+    let prefix = format!("{WORKSPACE_PREFIX}{}-", std::process::id());
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o700));
+    }
+    builder.tempdir_in(root)
+}
+
+/// A plaintext working copy of some of an encrypted project's files.
+pub struct DecryptedProject {
+    key: ProjectKey,
+    source: PathBuf,
+    path: PathBuf,
+    stamps: HashMap<PathBuf, FileStamp>,
+    _workspace: tempfile::TempDir,
+}
+
+impl DecryptedProject {
+    /// Decrypts the whole project at `project_path` into a new working copy.
+    pub fn open(project_path: &Path, master: &MasterKey) -> io::Result<Self> {
+        Self::open_only(project_path, master, |_| true)
+    }
+
+    /// Like [`DecryptedProject::open`], but only decrypts the files for which
+    /// `include` returns true, given their path relative to the project. The
+    /// project's directories are all created, so new files can be written
+    /// anywhere in the copy.
+    pub fn open_only(
+        project_path: &Path,
+        master: &MasterKey,
+        include: impl Fn(&Path) -> bool,
+    ) -> io::Result<Self> {
+        Self::open_with(project_path, master, include, |_| false)
+    }
+
+    /// Like [`DecryptedProject::open`], for a recording that stopped before it
+    /// could seal everything: a file for which `in_progress` returns true,
+    /// given its path relative to the project, is copied as it is when it is
+    /// not sealed. Any other file that is not sealed fails to open.
+    pub fn open_recovering(
+        project_path: &Path,
+        master: &MasterKey,
+        in_progress: impl Fn(&Path) -> bool,
+    ) -> io::Result<Self> {
+        Self::open_with(project_path, master, |_| true, in_progress)
+    }
+
+    fn open_with(
+        project_path: &Path,
+        master: &MasterKey,
+        include: impl Fn(&Path) -> bool,
+        in_progress: impl Fn(&Path) -> bool,
+    ) -> io::Result<Self> {
+        let key = ProjectKey::open(project_path, master)?;
+        let workspace = create_workspace()?;
+        // Some readers name things after the project directory.
+        let path = workspace
+            .path()
+            .join(project_path.file_name().unwrap_or(OsStr::new("project")));
+
+        std::fs::create_dir_all(&path)?;
+        for dir in project_dirs(project_path)? {
+            std::fs::create_dir_all(path.join(dir))?;
+        }
+
+        let mut stamps = HashMap::new();
+        for relative in project_files(project_path)?
+            .into_iter()
+            .filter(|relative| include(relative))
+        {
+            let source = project_path.join(&relative);
+            let destination = path.join(&relative);
+            let copied = if in_progress(&relative) && !is_sealed(&source)? {
+                std::fs::copy(&source, &destination).map(|_| ())
+            } else {
+                key.unseal_file_to(&source, &destination)
+            };
+            copied.map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", relative.display())))?;
+            stamps.insert(relative, file_stamp(&destination)?);
+        }
+
+        Ok(Self {
+            key,
+            source: project_path.to_path_buf(),
+            path,
+            stamps,
+            _workspace: workspace,
+        })
+    }
+
+    /// Where the decrypted project can be read from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The encrypted project this was opened from.
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Maps a path inside the working copy to the same path in the encrypted
+    /// project. Other paths are returned unchanged.
+    pub fn source_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.path)
+            .map(|relative| self.source.join(relative))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Seals files that were added or changed in the working copy back into
+    /// the project, along with any file there that is still plaintext, and
+    /// removes the ones that were deleted.
+    pub fn persist(&mut self) -> io::Result<()> {
+        let files: HashSet<PathBuf> = project_files(&self.path)?.into_iter().collect();
+
+        for relative in &files {
+            let working = self.path.join(relative);
+            let original = self.source.join(relative);
+            let stamp = file_stamp(&working)?;
+
+            let unchanged = self.stamps.get(relative) == Some(&stamp);
+            if unchanged && is_sealed(&original).unwrap_or(false) {
+                continue;
+            }
+
+            if let Some(parent) = original.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            self.key.seal_file_to(&working, &original)?;
+            self.stamps.insert(relative.clone(), stamp);
+        }
+
+        let removed: Vec<PathBuf> = self
+            .stamps
+            .keys()
+            .filter(|relative| !files.contains(*relative))
+            .cloned()
+            .collect();
+        for relative in removed {
+            match std::fs::remove_file(self.source.join(&relative)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+            self.stamps.remove(&relative);
+        }
+
+        let mut dirs = project_dirs(&self.source)?;
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in dirs {
+            if !self.path.join(&dir).exists() {
+                // Only empties; anything else in there was not ours to delete.
+                let _ = std::fs::remove_dir(self.source.join(dir));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key(byte: u8) -> ProjectKey {
+        ProjectKey::from_data_key(Path::new("Recording.cap"), &[byte; KEY_LEN])
+    }
+
+    fn sealed(key: &ProjectKey, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        key.seal(Path::new("a.bin"), data, &mut out).unwrap();
+        out
+    }
+
+    fn unsealed(key: &ProjectKey, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        key.unseal(Path::new("a.bin"), data, &mut out)?;
+        Ok(out)
+    }
+
+    fn encrypted_project() -> (tempfile::TempDir, PathBuf, MasterKey, ProjectKey) {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("Recording.cap");
+        let master = MasterKey::generate();
+        let key = ProjectKey::create(&project, &master).unwrap();
+        (dir, project, master, key)
+    }
+
+    #[test]
+    fn seal_round_trips_chunk_boundaries() {
+        let key = test_key(7);
+
+        for len in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE * 2,
+            CHUNK_SIZE * 2 + 5,
+        ] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let sealed = sealed(&key, &data);
+
+            assert!(sealed.starts_with(MAGIC));
+            assert_eq!(unsealed(&key, &sealed).unwrap(), data, "length {len}");
+        }
+    }
+
+    #[test]
+    fn unseal_rejects_tampering_and_truncation() {
+        let key = test_key(7);
+        let data = vec![42; CHUNK_SIZE * 2];
+        let sealed = sealed(&key, &data);
+
+        let mut flipped = sealed.clone();
+        flipped[HEADER_LEN + 10] ^= 1;
+        assert!(unsealed(&key, &flipped).is_err());
+
+        let truncated = &sealed[..HEADER_LEN + CHUNK_SIZE + TAG_LEN];
+        assert!(unsealed(&key, truncated).is_err());
+
+        let other = test_key(8);
+        assert!(unsealed(&other, &sealed).is_err());
+    }
+
+    #[test]
+    fn sealed_files_are_bound_to_their_path() {
+        let (_dir, project, _master, key) = encrypted_project();
+        std::fs::create_dir_all(project.join("display")).unwrap();
+        let first = project.join("display/segment_001.m4s");
+        let second = project.join("display/segment_002.m4s");
+        key.write(&first, b"one").unwrap();
+        key.write(&second, b"two").unwrap();
+
+        std::fs::rename(&second, &first).unwrap();
+        assert!(key.read(&first).is_err());
+
+        key.write(&second, b"two").unwrap();
+        assert_eq!(key.read(&second).unwrap(), b"two");
+        // The same file, named through a different path to the project.
+        let dotted = project.join(".").join("display/segment_002.m4s");
+        assert_eq!(key.read(&dotted).unwrap(), b"two");
+    }
+
+    #[test]
+    fn master_key_reads_raw_and_hex_keys() {
+        let key = MasterKey::generate();
+
+        assert_eq!(MasterKey::from_bytes(&key.0).unwrap(), key);
+        assert_eq!(
+            MasterKey::from_bytes(format!("{}\n", key.to_hex()).as_bytes()).unwrap(),
+            key
+        );
+        assert!(MasterKey::from_bytes(b"too short").is_err());
+    }
+
+    #[test]
+    fn project_key_only_opens_with_its_master_key() {
+        let (_dir, project, master, key) = encrypted_project();
+        std::fs::write(project.join("a.bin"), b"secret").unwrap();
+        key.seal_project(&project).unwrap();
+
+        let reopened = ProjectKey::open(&project, &master).unwrap();
+        let mut plaintext = Vec::new();
+        reopened
+            .unseal(
+                Path::new("a.bin"),
+                File::open(project.join("a.bin")).unwrap(),
+                &mut plaintext,
+            )
+            .unwrap();
+        assert_eq!(plaintext, b"secret");
+
+        let error = ProjectKey::open(&project, &MasterKey::generate()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(ProjectKey::create(&project, &master).is_err());
+    }
+
+    #[test]
+    fn seal_file_is_idempotent() {
+        let (_dir, project, _master, key) = encrypted_project();
+        let path = project.join("segment_001.m4s");
+        std::fs::write(&path, b"fragment").unwrap();
+
+        let size = key.seal_file(&path).unwrap();
+        let sealed = std::fs::read(&path).unwrap();
+        assert_eq!(key.seal_file(&path).unwrap(), size);
+        assert_eq!(std::fs::read(&path).unwrap(), sealed);
+        assert!(!seal_tmp_path(&path).exists());
+    }
+
+    #[test]
+    fn decrypted_project_persists_changes_sealed() {
+        let (_dir, project, master, key) = encrypted_project();
+        std::fs::create_dir_all(project.join("content/display")).unwrap();
+        std::fs::write(project.join("recording-meta.json"), b"{}").unwrap();
+        std::fs::write(project.join("content/display/segment_001.m4s"), b"one").unwrap();
+        key.seal_project(&project).unwrap();
+        // A fragment that was still being written when the recording stopped.
+        std::fs::write(project.join("content/display/segment_002.m4s"), b"two").unwrap();
+
+        assert!(DecryptedProject::open(&project, &master).is_err());
+        let mut decrypted = DecryptedProject::open_recovering(&project, &master, |relative| {
+            relative == Path::new("content/display/segment_002.m4s")
+        })
+        .unwrap();
+        let working = decrypted.path().to_path_buf();
+        assert_eq!(working.file_name(), project.file_name());
+        assert!(!working.join(ENCRYPTION_FILE).exists());
+        assert_eq!(
+            std::fs::read(working.join("content/display/segment_002.m4s")).unwrap(),
+            b"two"
+        );
+
+        std::fs::remove_dir_all(working.join("content/display")).unwrap();
+        std::fs::write(working.join("content/display.mp4"), b"remuxed").unwrap();
+        std::fs::write(working.join("recording-meta.json"), b"{\"done\":true}").unwrap();
+        decrypted.persist().unwrap();
+
+        assert!(!project.join("content/display").exists());
+        for (relative, expected) in [
+            ("content/display.mp4", b"remuxed".as_slice()),
+            ("recording-meta.json", b"{\"done\":true}".as_slice()),
+        ] {
+            let path = project.join(relative);
+            assert!(is_sealed(&path).unwrap(), "{relative}");
+            assert_eq!(key.read(&path).unwrap(), expected);
+        }
+
+        drop(decrypted);
+        assert!(!working.exists());
+    }
+
+    #[test]
+    fn partial_project_leaves_other_files_alone() {
+        let (_dir, project, master, key) = encrypted_project();
+        std::fs::create_dir_all(project.join("content")).unwrap();
+        std::fs::write(project.join("project-config.json"), b"{}").unwrap();
+        std::fs::write(project.join("content/display.mp4"), b"video").unwrap();
+        key.seal_project(&project).unwrap();
+
+        let mut decrypted = DecryptedProject::open_only(&project, &master, |relative| {
+            relative.components().count() == 1
+        })
+        .unwrap();
+        let working = decrypted.path().to_path_buf();
+        assert_eq!(
+            std::fs::read(working.join("project-config.json")).unwrap(),
+            b"{}"
+        );
+        assert!(working.join("content").is_dir());
+        assert!(!working.join("content/display.mp4").exists());
+
+        std::fs::write(working.join("project-config.json"), b"{\"edited\":true}").unwrap();
+        decrypted.persist().unwrap();
+
+        assert_eq!(
+            key.read(&project.join("content/display.mp4")).unwrap(),
+            b"video"
+        );
+    }
+
+    #[test]
+    fn recovery_only_accepts_the_files_it_names_unsealed() {
+        let (_dir, project, master, key) = encrypted_project();
+        std::fs::create_dir_all(project.join("content/display")).unwrap();
+        std::fs::write(project.join("content/display/segment_001.m4s"), b"one").unwrap();
+        key.seal_project(&project).unwrap();
+        // Plaintext where a sealed fragment should be.
+        std::fs::write(project.join("content/display/segment_001.m4s"), b"swapped").unwrap();
+        std::fs::write(project.join("content/display/segment_002.m4s"), b"two").unwrap();
+
+        let error = DecryptedProject::open_recovering(&project, &master, |relative| {
+            relative == Path::new("content/display/segment_002.m4s")
+        })
+        .err()
+        .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_and_write_stay_in_memory() {
+        let (_dir, project, _master, key) = encrypted_project();
+        let path = project.join("project-config.json");
+
+        key.write(&path, b"{\"a\":1}").unwrap();
+        assert!(is_sealed(&path).unwrap());
+        assert_eq!(key.read(&path).unwrap(), b"{\"a\":1}");
+
+        // Plaintext in an encrypted project is never trusted.
+        std::fs::write(&path, b"{}").unwrap();
+        assert!(key.read(&path).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn working_copy_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, project, master, key) = encrypted_project();
+        std::fs::write(project.join("recording-meta.json"), b"{}").unwrap();
+        key.seal_project(&project).unwrap();
+
+        let decrypted = DecryptedProject::open(&project, &master).unwrap();
+        let workspace = decrypted.path().parent().unwrap();
+        assert!(workspace.starts_with(workspace_root()));
+        let mode = std::fs::metadata(workspace).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn abandoned_working_copies_are_removed() {
+        let root = tempfile::tempdir().unwrap();
+        // No process has an id this large.
+        let abandoned = root
+            .path()
+            .join(format!("{WORKSPACE_PREFIX}4294967295-abc"));
+        let live = root
+            .path()
+            .join(format!("{WORKSPACE_PREFIX}{}-abc", std::process::id()));
+        let unrelated = root.path().join("other-4294967295-abc");
+        for dir in [&abandoned, &live, &unrelated] {
+            std::fs::create_dir_all(dir.join("Recording.cap")).unwrap();
+        }
+
+        remove_abandoned_workspaces(root.path());
+
+        assert!(!abandoned.exists());
+        assert!(live.exists());
+        assert!(unrelated.exists());
+    }
+}
@@ -53,12 +53,17 @@ impl KeyboardEvents {
     }
 
     pub fn write_to_file(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()?)
+            .map_err(|e| format!("Failed to write keyboard events file: {e}"))
+    }
+
+    /// The contents of a `keyboard.bin` holding these events.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
         let mut bytes = KEYBOARD_EVENTS_MAGIC.to_vec();
         let payload = bincode::serde::encode_to_vec(self, bincode::config::standard())
             .map_err(|e| format!("Failed to serialize keyboard events: {e}"))?;
         bytes.extend(payload);
-        std::fs::write(path, bytes)
-            .map_err(|e| format!("Failed to write keyboard events file: {e}"))
+        Ok(bytes)
     }
 }
 
@@ -84,7 +89,7 @@ const SPECIAL_KEY_SYMBOLS: &[(&str, &str)] = &[
     ("PageDown", "⇟"),
 ];
 
-fn is_modifier_key(key: &str) -> bool {
+pub fn is_modifier_key(key: &str) -> bool {
     MODIFIER_KEYS.contains(&key)
 }
 
@@ -1,5 +1,7 @@
 mod configuration;
 pub mod cursor;
+pub mod edit_script;
+pub mod encryption;
 pub mod keyboard;
 mod meta;
 
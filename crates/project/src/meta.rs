@@ -178,7 +178,13 @@ impl RecordingMeta {
 
     pub fn load_for_project(project_path: &Path) -> Result<Self, Box<dyn Error>> {
         let meta_path = project_path.join("recording-meta.json");
-        let mut meta: Self = serde_json::from_str(&std::fs::read_to_string(&meta_path)?)?;
+        Self::from_json_for_project(project_path, &std::fs::read_to_string(&meta_path)?)
+    }
+
+    /// Parses the contents of a project's `recording-meta.json`, for projects
+    /// whose files can't be read directly.
+    pub fn from_json_for_project(project_path: &Path, json: &str) -> Result<Self, Box<dyn Error>> {
+        let mut meta: Self = serde_json::from_str(json)?;
         meta.project_path = project_path.to_path_buf();
         meta.normalize_paths();
 
@@ -193,14 +199,28 @@ impl RecordingMeta {
     }
 
     pub fn project_config(&self) -> ProjectConfiguration {
-        let mut config = ProjectConfiguration::load(&self.project_path).unwrap_or_default();
+        let config = ProjectConfiguration::load(&self.project_path).unwrap_or_default();
 
         let captions_path = self.project_path.join("captions.json");
         debug!("Checking for captions at: {:?}", captions_path);
 
-        if let Ok(captions_str) = std::fs::read_to_string(&captions_path) {
+        let captions = std::fs::read_to_string(&captions_path).ok();
+        if captions.is_none() {
+            debug!("No captions.json found");
+        }
+
+        Self::with_captions(config, captions.as_deref())
+    }
+
+    /// Adds the contents of a project's `captions.json` to its configuration,
+    /// the way [`RecordingMeta::project_config`] does.
+    pub fn with_captions(
+        mut config: ProjectConfiguration,
+        captions_json: Option<&str>,
+    ) -> ProjectConfiguration {
+        if let Some(captions_str) = captions_json {
             debug!("Found captions.json, attempting to parse");
-            if let Ok(captions_data) = serde_json::from_str::<CaptionsData>(&captions_str) {
+            if let Ok(captions_data) = serde_json::from_str::<CaptionsData>(captions_str) {
                 info!(
                     "Successfully loaded captions with {} segments",
                     captions_data.segments.len()
@@ -209,8 +229,6 @@ impl RecordingMeta {
             } else {
                 warn!("Failed to parse captions.json");
             }
-        } else {
-            debug!("No captions.json found");
         }
 
         if let Some(ref captions) = config.captions {
@@ -401,6 +419,62 @@ impl StudioRecordingMeta {
             }
         }
     }
+
+    /// Converts a single-segment recording in place so segments can be
+    /// appended to it. The cursor images listed in the segment's cursor file
+    /// under `project_path` move to the project-wide cursor table, where the
+    /// renderer looks them up for multi-segment recordings.
+    pub fn ensure_multiple_segments(&mut self, project_path: &Path) -> &mut MultipleSegments {
+        if let Self::SingleSegment { segment } = self {
+            let cursors = single_segment_cursors(project_path, segment);
+            *self = Self::MultipleSegments {
+                inner: MultipleSegments {
+                    segments: vec![segment.clone().into()],
+                    cursors,
+                    status: Some(StudioRecordingStatus::Complete),
+                },
+            };
+        }
+
+        match self {
+            Self::MultipleSegments { inner } => inner,
+            Self::SingleSegment { .. } => unreachable!(),
+        }
+    }
+}
+
+fn single_segment_cursors(project_path: &Path, segment: &SingleSegment) -> Cursors {
+    let Some(cursor_path) = &segment.cursor else {
+        return Cursors::default();
+    };
+
+    let data = match crate::CursorData::load_from_file(&cursor_path.to_path(project_path)) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("Keeping no cursor images for converted recording: {e}");
+            return Cursors::default();
+        }
+    };
+
+    let cursors = data
+        .cursor_images
+        .0
+        .into_iter()
+        .filter_map(|(id, image)| {
+            let relative = image.path.strip_prefix(project_path).unwrap_or(&image.path);
+            let image_path = RelativePathBuf::from_path(relative).ok()?;
+            Some((
+                id,
+                CursorMeta {
+                    image_path,
+                    hotspot: image.hotspot,
+                    shape: None,
+                },
+            ))
+        })
+        .collect();
+
+    Cursors::Correct(cursors)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -482,6 +556,9 @@ impl MultipleSegments {
                     ))
                     | Some(cap_cursor_info::CursorShape::Windows(
                         cap_cursor_info::CursorShapeWindows::Arrow,
+                    ))
+                    | Some(cap_cursor_info::CursorShape::Linux(
+                        cap_cursor_info::CursorShapeLinux::Default,
                     )) => Some(id.clone()),
                     _ => None,
                 })
@@ -513,6 +590,10 @@ pub struct MultipleSegment {
     pub mic: Option<AudioMeta>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub system_audio: Option<AudioMeta>,
+    /// Further microphones recorded next to `mic`, each as its own track that
+    /// is mixed using `AudioConfiguration::additional_mics`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_mics: Vec<AdditionalAudioMeta>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[specta(type = Option<String>)]
     pub cursor: Option<RelativePathBuf>,
@@ -521,6 +602,77 @@ pub struct MultipleSegment {
     pub keyboard: Option<RelativePathBuf>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display_notch: Option<DisplayNotch>,
+    /// Extra cameras or displays captured alongside the main display, laid out
+    /// as picture-in-picture layers via `ProjectConfiguration::picture_in_picture`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_videos: Vec<AdditionalVideoMeta>,
+    /// Set when `display` is an external file imported as a clip (an intro
+    /// bumper, B-roll, a title card) rather than a Cap recording.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported: Option<ImportedMediaMeta>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AdditionalVideoKind {
+    Camera,
+    Display,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AdditionalVideoMeta {
+    /// Stable across segments (e.g. `camera-1`), so one layout entry applies to
+    /// every segment the source was recorded in.
+    pub id: String,
+    pub kind: AdditionalVideoKind,
+    #[serde(flatten)]
+    pub video: VideoMeta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AdditionalAudioMeta {
+    /// Stable across segments (e.g. `mic-2`), so one audio setting applies to
+    /// every segment the microphone was recorded in.
+    pub id: String,
+    #[serde(flatten)]
+    pub audio: AudioMeta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportedMediaKind {
+    Video,
+    Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedMediaMeta {
+    pub kind: ImportedMediaKind,
+    /// File name the clip was imported from, for display in the editor.
+    pub source_name: String,
+    /// How long a still image is shown. Videos play for their own length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+}
+
+impl From<SingleSegment> for MultipleSegment {
+    fn from(segment: SingleSegment) -> Self {
+        Self {
+            display: segment.display,
+            camera: segment.camera,
+            mic: segment.audio,
+            system_audio: None,
+            additional_mics: Vec::new(),
+            cursor: segment.cursor,
+            keyboard: None,
+            display_notch: None,
+            additional_videos: Vec::new(),
+            imported: None,
+        }
+    }
 }
 
 impl MultipleSegment {
@@ -599,9 +751,34 @@ impl MultipleSegment {
             value = value.max(system_audio.start_time?);
         }
 
+        for additional in &self.additional_videos {
+            if let Some(start_time) = additional.video.start_time {
+                value = value.max(start_time);
+            }
+        }
+
+        for additional in &self.additional_mics {
+            if let Some(start_time) = additional.audio.start_time {
+                value = value.max(start_time);
+            }
+        }
+
         Some(value)
     }
 
+    /// What to add to the clip's `mic` offset to get an additional
+    /// microphone's offset. The microphones share a clock, so they only differ
+    /// by when each one's first sample arrived.
+    pub fn additional_mic_offset(&self, additional: &AdditionalAudioMeta) -> f32 {
+        match (
+            self.mic.as_ref().and_then(|mic| mic.start_time),
+            additional.audio.start_time,
+        ) {
+            (Some(mic_start), Some(start)) => (mic_start - start) as f32,
+            _ => 0.0,
+        }
+    }
+
     pub fn calculate_audio_offsets(&self) -> crate::ClipOffsets {
         self.calculate_audio_offsets_with_calibration(None)
     }
@@ -702,6 +879,75 @@ mod test {
         );
     }
 
+    #[test]
+    fn single_segment_converts_in_place() {
+        let mut meta: RecordingMeta = serde_json::from_str(
+            r#"{
+              "pretty_name": "Cap",
+              "display": { "path": "content/display.mp4", "fps": 30 },
+              "audio": { "path": "content/audio-input.ogg" },
+              "segments": [{ "start": 0.0, "end": 1.0 }]
+            }"#,
+        )
+        .unwrap();
+        let super::RecordingMetaInner::Studio(studio) = &mut meta.inner else {
+            panic!("expected a studio recording");
+        };
+
+        let inner = studio.ensure_multiple_segments(std::path::Path::new("/nonexistent"));
+
+        assert_eq!(inner.segments.len(), 1);
+        assert_eq!(
+            inner.segments[0].display.path.as_str(),
+            "content/display.mp4"
+        );
+        assert_eq!(
+            inner.segments[0].mic.as_ref().unwrap().path.as_str(),
+            "content/audio-input.ogg"
+        );
+        assert!(inner.segments[0].imported.is_none());
+    }
+
+    #[test]
+    fn single_segment_conversion_keeps_cursor_images() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("cursor.json"),
+            r#"{
+              "clicks": [],
+              "moves": [],
+              "cursor_images": {
+                "0": { "path": "content/cursors/cursor_0.png", "hotspot": { "x": 0.25, "y": 0.5 } }
+              }
+            }"#,
+        )
+        .unwrap();
+        let mut meta: RecordingMeta = serde_json::from_str(
+            r#"{
+              "pretty_name": "Cap",
+              "display": { "path": "content/display.mp4", "fps": 30 },
+              "cursor": "cursor.json"
+            }"#,
+        )
+        .unwrap();
+        let super::RecordingMetaInner::Studio(studio) = &mut meta.inner else {
+            panic!("expected a studio recording");
+        };
+
+        let inner = studio.ensure_multiple_segments(dir.path());
+
+        let super::Cursors::Correct(cursors) = &inner.cursors else {
+            panic!("expected converted cursors");
+        };
+        let cursor = &cursors["0"];
+        assert_eq!(cursor.image_path.as_str(), "content/cursors/cursor_0.png");
+        assert_eq!(cursor.hotspot.x, 0.25);
+        assert_eq!(
+            inner.segments[0].cursor.as_ref().unwrap().as_str(),
+            "cursor.json"
+        );
+    }
+
     #[test]
     fn multi_segment() {
         test_meta_deserialize(
@@ -794,9 +1040,12 @@ mod test {
                 camera: None,
                 mic: mic_start.map(|s| audio(Some(s))),
                 system_audio: system_start.map(|s| audio(Some(s))),
+                additional_mics: Vec::new(),
                 cursor: None,
                 keyboard: None,
                 display_notch: None,
+                additional_videos: Vec::new(),
+                imported: None,
             }
         }
 
@@ -832,6 +1081,54 @@ mod test {
             assert!((offsets.mic - (0.6586015 - 0.5559852) as f32).abs() < 1e-6);
             assert_eq!(offsets.system_audio, 0.0);
         }
+
+        #[test]
+        fn late_additional_video_moves_the_anchor() {
+            let mut segment = segment(0.5, Some(0.5), None);
+            segment.additional_videos.push(crate::AdditionalVideoMeta {
+                id: "camera-1".to_string(),
+                kind: crate::AdditionalVideoKind::Camera,
+                video: video(Some(0.8)),
+            });
+
+            assert_eq!(segment.latest_start_time(), Some(0.8));
+            assert!((segment.calculate_audio_offsets().mic - 0.3).abs() < 1e-6);
+        }
+
+        #[test]
+        fn additional_mics_align_to_the_main_mic() {
+            let mut segment = segment(0.5, Some(0.5), None);
+            segment.additional_mics.push(crate::AdditionalAudioMeta {
+                id: "mic-2".to_string(),
+                audio: audio(Some(0.6)),
+            });
+
+            assert_eq!(segment.latest_start_time(), Some(0.6));
+            let offsets = segment.calculate_audio_offsets();
+            assert!((offsets.mic - 0.1).abs() < 1e-6);
+            // It started with the video, so none of it is skipped.
+            let additional_offset =
+                offsets.mic + segment.additional_mic_offset(&segment.additional_mics[0]);
+            assert!(additional_offset.abs() < 1e-6);
+        }
+
+        #[test]
+        fn additional_videos_flatten_their_video_meta() {
+            let segment: MultipleSegment = serde_json::from_str(
+                r#"{
+                  "display": { "path": "display.mp4", "fps": 30 },
+                  "additional_videos": [
+                    { "id": "display-1", "kind": "display", "path": "display-1.mp4", "fps": 60 }
+                  ]
+                }"#,
+            )
+            .unwrap();
+
+            let additional = &segment.additional_videos[0];
+            assert_eq!(additional.kind, crate::AdditionalVideoKind::Display);
+            assert_eq!(additional.video.path.as_str(), "display-1.mp4");
+            assert_eq!(additional.video.fps, 60);
+        }
     }
 }
 
@@ -15,7 +15,7 @@ use cap_enc_ffmpeg::h264::H264EncoderBuilder;
 use cap_enc_ffmpeg::h264::H264Preset;
 #[cfg(windows)]
 use cap_enc_ffmpeg::h264::H264Preset;
-use cap_enc_ffmpeg::segmented_stream::SegmentCompletedEvent;
+use cap_enc_ffmpeg::segmented_stream::{FragmentSealer, SegmentCompletedEvent};
 use cap_timestamp::Timestamps;
 use std::path::PathBuf;
 
@@ -65,6 +65,8 @@ pub trait MakeCapturePipeline: ScreenCaptureFormat + std::fmt::Debug + 'static {
         shared_pause_state: Option<SharedPauseState>,
         output_size: Option<(u32, u32)>,
         quality: StudioQuality,
+        live_stream: Option<LiveStream>,
+        fragment_sealer: Option<FragmentSealer>,
         #[cfg(windows)] encoder_preferences: EncoderPreferences,
     ) -> anyhow::Result<OutputPipeline>
     where
@@ -76,6 +78,7 @@ pub trait MakeCapturePipeline: ScreenCaptureFormat + std::fmt::Debug + 'static {
         output_size: (u32, u32),
         start_time: Timestamps,
         segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
+        live_stream: Option<LiveStream>,
     ) -> anyhow::Result<OutputPipeline>
     where
         Self: Sized;
@@ -94,6 +97,8 @@ impl MakeCapturePipeline for screen_capture::CMSampleBufferCapture {
         shared_pause_state: Option<SharedPauseState>,
         output_size: Option<(u32, u32)>,
         quality: StudioQuality,
+        live_stream: Option<LiveStream>,
+        fragment_sealer: Option<FragmentSealer>,
     ) -> anyhow::Result<OutputPipeline> {
         let ultra = quality == StudioQuality::Ultra;
         let compatibility = quality == StudioQuality::Compatibility;
@@ -159,11 +164,13 @@ impl MakeCapturePipeline for screen_capture::CMSampleBufferCapture {
                 OutputPipeline::builder(fragments_dir)
                     .with_video::<screen_capture::VideoSource>(screen_capture)
                     .with_timestamps(start_time)
+                    .with_live_stream(live_stream)
                     .build::<OutOfProcessFragmentedM4SMuxer>(OutOfProcessFragmentedM4SMuxerConfig {
                         preset,
                         bpp,
                         output_size,
                         shared_pause_state,
+                        fragment_sealer,
                         ..Default::default()
                     })
                     .await
@@ -171,11 +178,13 @@ impl MakeCapturePipeline for screen_capture::CMSampleBufferCapture {
                 OutputPipeline::builder(fragments_dir)
                     .with_video::<screen_capture::VideoSource>(screen_capture)
                     .with_timestamps(start_time)
+                    .with_live_stream(live_stream)
                     .build::<MacOSFragmentedM4SMuxer>(MacOSFragmentedM4SMuxerConfig {
                         preset,
                         bpp,
                         output_size,
                         shared_pause_state,
+                        fragment_sealer,
                         ..Default::default()
                     })
                     .await
@@ -189,6 +198,7 @@ impl MakeCapturePipeline for screen_capture::CMSampleBufferCapture {
             OutputPipeline::builder(output_path.clone())
                 .with_video::<screen_capture::VideoSource>(screen_capture)
                 .with_timestamps(start_time)
+                .with_live_stream(live_stream)
                 .build::<AVFoundationMp4Muxer>(AVFoundationMp4MuxerConfig {
                     output_height: output_size.map(|(_, h)| h),
                     instant_mode: false,
@@ -205,10 +215,12 @@ impl MakeCapturePipeline for screen_capture::CMSampleBufferCapture {
         output_size: (u32, u32),
         start_time: Timestamps,
         segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
+        live_stream: Option<LiveStream>,
     ) -> anyhow::Result<OutputPipeline> {
         OutputPipeline::builder(segments_dir)
             .with_video::<screen_capture::VideoSource>(screen_capture)
             .with_timestamps(start_time)
+            .with_live_stream(live_stream)
             .build::<MacOSFragmentedM4SMuxer>(MacOSFragmentedM4SMuxerConfig {
                 bpp: H264EncoderBuilder::INSTANT_MODE_BPP,
                 output_size: Some(output_size),
@@ -231,6 +243,8 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
         shared_pause_state: Option<SharedPauseState>,
         output_size: Option<(u32, u32)>,
         quality: StudioQuality,
+        live_stream: Option<LiveStream>,
+        fragment_sealer: Option<FragmentSealer>,
         encoder_preferences: EncoderPreferences,
     ) -> anyhow::Result<OutputPipeline> {
         let ultra = quality == StudioQuality::Ultra;
@@ -283,6 +297,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
                 OutputPipeline::builder(fragments_dir)
                     .with_video::<screen_capture::VideoSource>(screen_capture)
                     .with_timestamps(start_time)
+                    .with_live_stream(live_stream)
                     .build::<WindowsOOPFragmentedM4SMuxer>(WindowsOOPFragmentedM4SMuxerConfig {
                         segment_duration: std::time::Duration::from_secs(2),
                         preset,
@@ -290,6 +305,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
                         output_size,
                         shared_pause_state,
                         disk_space_callback: None,
+                        fragment_sealer,
                         segment_tx: None,
                         ..Default::default()
                     })
@@ -298,6 +314,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
                 OutputPipeline::builder(fragments_dir)
                     .with_video::<screen_capture::VideoSource>(screen_capture)
                     .with_timestamps(start_time)
+                    .with_live_stream(live_stream)
                     .build::<WindowsFragmentedM4SMuxer>(WindowsFragmentedM4SMuxerConfig {
                         segment_duration: std::time::Duration::from_secs(2),
                         preset,
@@ -305,6 +322,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
                         output_size,
                         shared_pause_state,
                         disk_space_callback: None,
+                        fragment_sealer,
                         segment_tx: None,
                     })
                     .await
@@ -316,6 +334,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
             OutputPipeline::builder(output_path.clone())
                 .with_video::<screen_capture::VideoSource>(screen_capture)
                 .with_timestamps(start_time)
+                .with_live_stream(live_stream)
                 .build::<WindowsMuxer>(WindowsMuxerConfig {
                     pixel_format: screen_capture::Direct3DCapture::PIXEL_FORMAT.as_dxgi(),
                     d3d_device,
@@ -339,10 +358,12 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
         output_size: (u32, u32),
         start_time: Timestamps,
         segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
+        live_stream: Option<LiveStream>,
     ) -> anyhow::Result<OutputPipeline> {
         OutputPipeline::builder(segments_dir)
             .with_video::<screen_capture::VideoSource>(screen_capture)
             .with_timestamps(start_time)
+            .with_live_stream(live_stream)
             .build::<WindowsFragmentedM4SMuxer>(WindowsFragmentedM4SMuxerConfig {
                 segment_duration: std::time::Duration::from_secs(2),
                 preset: H264Preset::Ultrafast,
@@ -350,6 +371,7 @@ impl MakeCapturePipeline for screen_capture::Direct3DCapture {
                 output_size: Some(output_size),
                 shared_pause_state: None,
                 disk_space_callback: None,
+                fragment_sealer: None,
                 segment_tx,
             })
             .await
@@ -367,6 +389,8 @@ impl MakeCapturePipeline for screen_capture::X11Capture {
         shared_pause_state: Option<SharedPauseState>,
         output_size: Option<(u32, u32)>,
         quality: StudioQuality,
+        live_stream: Option<LiveStream>,
+        fragment_sealer: Option<FragmentSealer>,
     ) -> anyhow::Result<OutputPipeline> {
         let fragments_dir = output_path
             .parent()
@@ -377,6 +401,7 @@ impl MakeCapturePipeline for screen_capture::X11Capture {
         OutputPipeline::builder(fragments_dir)
             .with_video::<screen_capture::VideoSource>(screen_capture)
             .with_timestamps(start_time)
+            .with_live_stream(live_stream)
             .build::<crate::ffmpeg::SegmentedVideoMuxer>(crate::ffmpeg::SegmentedVideoMuxerConfig {
                 segment_duration: std::time::Duration::from_secs(2),
                 preset: if ultra {
@@ -386,6 +411,8 @@ impl MakeCapturePipeline for screen_capture::X11Capture {
                 },
                 output_size,
                 shared_pause_state,
+                fragment_sealer,
+                segment_tx: None,
             })
             .await
     }
@@ -395,16 +422,20 @@ impl MakeCapturePipeline for screen_capture::X11Capture {
         segments_dir: PathBuf,
         output_size: (u32, u32),
         start_time: Timestamps,
-        _segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
+        segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
+        live_stream: Option<LiveStream>,
     ) -> anyhow::Result<OutputPipeline> {
         OutputPipeline::builder(segments_dir)
             .with_video::<screen_capture::VideoSource>(screen_capture)
             .with_timestamps(start_time)
+            .with_live_stream(live_stream)
             .build::<crate::ffmpeg::SegmentedVideoMuxer>(crate::ffmpeg::SegmentedVideoMuxerConfig {
                 segment_duration: std::time::Duration::from_secs(2),
                 preset: H264Preset::Ultrafast,
                 output_size: Some(output_size),
                 shared_pause_state: None,
+                fragment_sealer: None,
+                segment_tx,
             })
             .await
     }
@@ -430,7 +461,9 @@ pub fn target_to_display_and_crop(
 
     let crop_bounds = match target {
         ScreenCaptureTarget::Display { .. } => None,
-        ScreenCaptureTarget::Window { id } => {
+        // A followed window starts out cropped exactly like a window capture;
+        // the capturer moves the crop from there.
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let window = Window::from_id(id).ok_or_else(|| anyhow!("Window not found"))?;
 
             #[cfg(target_os = "macos")]
@@ -549,8 +582,9 @@ pub fn target_to_display_and_crop(
 /// Locates the recording display's physical notch within the frames this target
 /// will produce.
 ///
-/// Window captures get `None`: they record the window's own surface rather than
-/// a screen region, and the window moves, so there is no stable position.
+/// Window and follow-window captures get `None`: they record the window's own
+/// surface rather than a screen region, and the window moves, so there is no
+/// stable position.
 /// Area captures also return `None` when they contain only part of the notch,
 /// because `DisplayNotch` cannot encode a cropped source shape.
 pub fn resolve_display_notch(target: &ScreenCaptureTarget) -> Option<cap_project::DisplayNotch> {
@@ -567,7 +601,9 @@ pub fn resolve_display_notch(target: &ScreenCaptureTarget) -> Option<cap_project
             let display_size = display.logical_size()?;
             resolve_area_display_notch(notch, display_size, *bounds)
         }
-        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::CameraOnly => None,
+        ScreenCaptureTarget::Window { .. }
+        | ScreenCaptureTarget::FollowWindow { .. }
+        | ScreenCaptureTarget::CameraOnly => None,
     }
 }
 
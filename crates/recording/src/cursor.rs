@@ -2,6 +2,7 @@ use cap_cursor_capture::CursorCropBounds;
 use cap_cursor_info::CursorShape;
 use cap_project::{
     CursorClickEvent, CursorEvents, CursorMoveEvent, KeyPressEvent, KeyboardEvents, XY,
+    encryption::{self, ProjectKey},
 };
 use cap_timestamp::Timestamps;
 use futures::{FutureExt, future::Shared};
@@ -42,6 +43,8 @@ pub struct CursorActor {
 pub struct IncrementalCaptureOutputs {
     pub cursor: Option<PathBuf>,
     pub keyboard: Option<PathBuf>,
+    /// Seals these outputs and the cursor images as they are written.
+    pub encryption: Option<ProjectKey>,
 }
 
 impl CursorActor {
@@ -69,13 +72,18 @@ fn prefers_wayland_portal_cursor() -> bool {
             .is_ok_and(|session| session.eq_ignore_ascii_case("wayland"))
 }
 
-fn flush_cursor_data(output_path: &Path, moves: &[CursorMoveEvent], clicks: &[CursorClickEvent]) {
+fn flush_cursor_data(
+    output_path: &Path,
+    moves: &[CursorMoveEvent],
+    clicks: &[CursorClickEvent],
+    key: Option<&ProjectKey>,
+) {
     let events = CursorEvents {
         clicks: clicks.to_vec(),
         moves: moves.to_vec(),
     };
     if let Ok(json) = serde_json::to_string_pretty(&events)
-        && let Err(e) = std::fs::write(output_path, json)
+        && let Err(e) = encryption::write_file(key, output_path, json.as_bytes())
     {
         tracing::error!(
             "Failed to write cursor data to {}: {}",
@@ -85,11 +93,14 @@ fn flush_cursor_data(output_path: &Path, moves: &[CursorMoveEvent], clicks: &[Cu
     }
 }
 
-fn flush_keyboard_data(output_path: &Path, presses: &[KeyPressEvent]) {
+fn flush_keyboard_data(output_path: &Path, presses: &[KeyPressEvent], key: Option<&ProjectKey>) {
     let events = KeyboardEvents {
         presses: presses.to_vec(),
     };
-    if let Err(e) = events.write_to_file(output_path) {
+    let written = events.to_bytes().and_then(|bytes| {
+        encryption::write_file(key, output_path, &bytes).map_err(|e| e.to_string())
+    });
+    if let Err(e) = written {
         tracing::error!(
             "Failed to write keyboard data to {}: {}",
             output_path.display(),
@@ -260,6 +271,7 @@ pub fn spawn_cursor_recorder(
             keyboard_presses: vec![],
         };
 
+        let key = incremental_outputs.encryption.as_ref();
         let mut last_flush = Instant::now();
         let flush_interval = Duration::from_secs(CURSOR_FLUSH_INTERVAL_SECS);
         let mut last_cursor_id: Option<String> = None;
@@ -303,8 +315,16 @@ pub fn spawn_cursor_recorder(
 
                     if let Ok(image) = image::load_from_memory(&data.image) {
                         let rgba_image = image.into_rgba8();
-
-                        if let Err(e) = rgba_image.save(&cursor_path) {
+                        let mut png = std::io::Cursor::new(Vec::new());
+                        let saved = rgba_image
+                            .write_to(&mut png, image::ImageFormat::Png)
+                            .map_err(|e| e.to_string())
+                            .and_then(|()| {
+                                encryption::write_file(key, &cursor_path, png.get_ref())
+                                    .map_err(|e| e.to_string())
+                            });
+
+                        if let Err(e) = saved {
                             error!("Failed to save cursor image: {}", e);
                         } else {
                             info!("Saved cursor {cursor_id} image to: {:?}", file_name);
@@ -402,10 +422,10 @@ pub fn spawn_cursor_recorder(
 
             if last_flush.elapsed() >= flush_interval {
                 if let Some(ref path) = incremental_outputs.cursor {
-                    flush_cursor_data(path, &response.moves, &response.clicks);
+                    flush_cursor_data(path, &response.moves, &response.clicks, key);
                 }
                 if let Some(ref kb_path) = incremental_outputs.keyboard {
-                    flush_keyboard_data(kb_path, &response.keyboard_presses);
+                    flush_keyboard_data(kb_path, &response.keyboard_presses, key);
                 }
                 last_flush = Instant::now();
             }
@@ -414,11 +434,11 @@ pub fn spawn_cursor_recorder(
         info!("cursor recorder done");
 
         if let Some(ref path) = incremental_outputs.cursor {
-            flush_cursor_data(path, &response.moves, &response.clicks);
+            flush_cursor_data(path, &response.moves, &response.clicks, key);
         }
 
         if let Some(ref kb_path) = incremental_outputs.keyboard {
-            flush_keyboard_data(kb_path, &response.keyboard_presses);
+            flush_keyboard_data(kb_path, &response.keyboard_presses, key);
         }
 
         let _ = tx.send(response);
@@ -474,44 +494,23 @@ fn get_cursor_data() -> Option<CursorData> {
 
 #[cfg(target_os = "linux")]
 fn get_x11_cursor_data() -> Option<CursorData> {
-    use x11rb::protocol::xfixes::ConnectionExt as _;
+    let cursor = cap_cursor_capture::XFixesCursor::current()?;
 
-    let (conn, _) = x11rb::connect(None).ok()?;
-    conn.xfixes_query_version(5, 0).ok()?.reply().ok()?;
-    let cursor = conn.xfixes_get_cursor_image().ok()?.reply().ok()?;
-
-    let width = u32::from(cursor.width);
-    let height = u32::from(cursor.height);
-    if width == 0 || height == 0 {
-        return None;
-    }
-
-    let pixel_count = usize::from(cursor.width).checked_mul(usize::from(cursor.height))?;
-    if cursor.cursor_image.len() != pixel_count {
-        return None;
-    }
-
-    let mut rgba = Vec::with_capacity(pixel_count.checked_mul(4)?);
-    for pixel in cursor.cursor_image {
-        rgba.push(((pixel >> 16) & 0xff) as u8);
-        rgba.push(((pixel >> 8) & 0xff) as u8);
-        rgba.push((pixel & 0xff) as u8);
-        rgba.push(((pixel >> 24) & 0xff) as u8);
-    }
-
-    let image = image::RgbaImage::from_raw(width, height, rgba)?;
+    let image = image::RgbaImage::from_raw(
+        u32::from(cursor.width),
+        u32::from(cursor.height),
+        cursor.rgba(),
+    )?;
     let mut bytes = std::io::Cursor::new(Vec::new());
     image::DynamicImage::ImageRgba8(image)
         .write_to(&mut bytes, image::ImageFormat::Png)
         .ok()?;
 
+    let (hotspot_x, hotspot_y) = cursor.normalized_hotspot();
     Some(CursorData {
         image: bytes.into_inner(),
-        hotspot: XY::new(
-            f64::from(cursor.xhot) / f64::from(width),
-            f64::from(cursor.yhot) / f64::from(height),
-        ),
-        shape: None,
+        hotspot: XY::new(hotspot_x, hotspot_y),
+        shape: cursor.shape().map(Into::into),
     })
 }
 
@@ -864,3 +863,58 @@ fn get_cursor_data() -> Option<CursorData> {
         })
     }
 }
+
+/// Tracks when the mouse last moved or a mouse button or key was last pressed, so callers can end a
+/// recording after a stretch of inactivity. The polling thread stops when this is dropped.
+pub struct InputActivity {
+    last_activity: std::sync::Arc<std::sync::Mutex<Instant>>,
+    _stop: DropGuard,
+}
+
+impl InputActivity {
+    pub fn spawn() -> Self {
+        use device_query::{DeviceQuery, DeviceState};
+        use std::{
+            sync::{Arc, Mutex},
+            time::Duration,
+        };
+
+        let last_activity = Arc::new(Mutex::new(Instant::now()));
+        let stop_token = CancellationToken::new();
+
+        let thread_activity = last_activity.clone();
+        let thread_stop = stop_token.child_token();
+        std::thread::spawn(move || {
+            let device_state = DeviceState::new();
+            let mut last_mouse = device_state.get_mouse();
+            let mut last_keys = device_state.get_keys();
+
+            while !thread_stop.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(100));
+
+                let mouse = device_state.get_mouse();
+                let keys = device_state.get_keys();
+                if mouse.coords != last_mouse.coords
+                    || mouse.button_pressed != last_mouse.button_pressed
+                    || keys != last_keys
+                {
+                    *thread_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+                }
+                last_mouse = mouse;
+                last_keys = keys;
+            }
+        });
+
+        Self {
+            last_activity,
+            _stop: stop_token.drop_guard(),
+        }
+    }
+
+    pub fn idle_for(&self) -> std::time::Duration {
+        self.last_activity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+}
@@ -8,7 +8,13 @@ use crate::{
     feeds::microphone::MicrophoneFeedLock,
     output_pipeline::{self, OutputPipeline},
     resolution_limits::ensure_even,
-    sources::screen_capture::{ScreenCaptureConfig, ScreenCaptureTarget},
+    sources::screen_capture::{
+        ScreenCaptureConfig, ScreenCaptureTarget, app_audio::AppAudioFilter,
+    },
+    video_filter::{
+        FilteredVideoSource, FilteredVideoSourceConfig, VideoFilterChain, VideoFilterConfig,
+        VideoFilters,
+    },
 };
 use anyhow::Context as _;
 use cap_media_info::VideoInfo;
@@ -315,7 +321,15 @@ async fn create_pipeline(
     system_audio_source: Option<crate::sources::screen_capture::SystemAudioSourceConfig>,
     max_output_size: Option<u32>,
     start_time: Timestamps,
+    live_stream: Option<output_pipeline::LiveStream>,
+    screen_filters: Vec<VideoFilterConfig>,
 ) -> anyhow::Result<Pipeline> {
+    let screen_info = if screen_filters.is_empty() {
+        screen_info
+    } else {
+        VideoFilterChain::new(&screen_filters).output_info(screen_info)
+    };
+
     let output_resolution = max_output_size
         .map(|max_output_size| {
             clamp_size(
@@ -343,20 +357,41 @@ async fn create_pipeline(
 
     let segment_tx_for_video = segment_channel.as_ref().map(|(tx, _)| tx.clone());
 
-    let video = ScreenCaptureMethod::make_instant_segmented_video_pipeline(
-        screen_capture,
-        segments_dir.clone(),
-        output_resolution,
-        start_time,
-        segment_tx_for_video,
-    )
-    .await?;
+    let video = if screen_filters.is_empty() {
+        ScreenCaptureMethod::make_instant_segmented_video_pipeline(
+            screen_capture,
+            segments_dir.clone(),
+            output_resolution,
+            start_time,
+            segment_tx_for_video,
+            live_stream.clone(),
+        )
+        .await?
+    } else {
+        // Filters work on frames in system memory, so a filtered screen is
+        // encoded with ffmpeg on every platform.
+        OutputPipeline::builder(segments_dir.clone())
+            .with_video::<FilteredVideoSource<crate::sources::screen_capture::VideoSource>>(
+                FilteredVideoSourceConfig::new(screen_capture, screen_filters),
+            )
+            .with_timestamps(start_time)
+            .with_live_stream(live_stream.clone())
+            .build::<crate::ffmpeg::SegmentedVideoMuxer>(crate::ffmpeg::SegmentedVideoMuxerConfig {
+                segment_duration: std::time::Duration::from_secs(2),
+                output_size: Some(output_resolution),
+                segment_tx: segment_tx_for_video,
+                ..Default::default()
+            })
+            .await
+            .context("filtered screen pipeline setup")?
+    };
 
     let has_audio = mic_feed.is_some() || system_audio_source.is_some();
     let audio = if has_audio {
         let audio_dir = content_dir.join("audio");
-        let mut builder =
-            output_pipeline::OutputPipeline::builder(audio_dir.clone()).with_timestamps(start_time);
+        let mut builder = output_pipeline::OutputPipeline::builder(audio_dir.clone())
+            .with_timestamps(start_time)
+            .with_live_stream(live_stream);
 
         if let Some(sys_audio) = system_audio_source {
             builder = builder
@@ -411,12 +446,15 @@ pub struct ActorBuilder {
     output_path: PathBuf,
     capture_target: ScreenCaptureTarget,
     system_audio: bool,
+    app_audio: Option<AppAudioFilter>,
     mic_feed: Option<Arc<MicrophoneFeedLock>>,
     camera_feed: Option<Arc<crate::feeds::camera::CameraFeedLock>>,
     max_output_size: Option<u32>,
     max_fps: u32,
     #[cfg(target_os = "macos")]
     excluded_windows: Vec<scap_targets::WindowId>,
+    live_stream: Option<output_pipeline::LiveStream>,
+    video_filters: VideoFilters,
 }
 
 impl ActorBuilder {
@@ -425,12 +463,15 @@ impl ActorBuilder {
             output_path: output,
             capture_target,
             system_audio: false,
+            app_audio: None,
             mic_feed: None,
             camera_feed: None,
             max_output_size: None,
             max_fps: crate::defaults::DEFAULT_INSTANT_MODE_FPS,
             #[cfg(target_os = "macos")]
             excluded_windows: Vec::new(),
+            live_stream: None,
+            video_filters: VideoFilters::default(),
         }
     }
 
@@ -439,6 +480,13 @@ impl ActorBuilder {
         self
     }
 
+    /// Records system audio from only the applications `app_audio` selects.
+    /// Linux only; this also enables system audio.
+    pub fn with_app_audio(mut self, app_audio: AppAudioFilter) -> Self {
+        self.app_audio = Some(app_audio);
+        self
+    }
+
     pub fn with_mic_feed(mut self, mic_feed: Arc<MicrophoneFeedLock>) -> Self {
         self.mic_feed = Some(mic_feed);
         self
@@ -452,6 +500,18 @@ impl ActorBuilder {
         self
     }
 
+    /// Streams the screen and mixed audio to `live_stream` while recording.
+    pub fn with_live_stream(mut self, live_stream: output_pipeline::LiveStream) -> Self {
+        self.live_stream = Some(live_stream);
+        self
+    }
+
+    /// Burns `filters` into the recording, e.g. a compliance watermark.
+    pub fn with_video_filters(mut self, filters: VideoFilters) -> Self {
+        self.video_filters = filters;
+        self
+    }
+
     pub fn with_max_output_size(mut self, max_output_size: u32) -> Self {
         self.max_output_size = Some(max_output_size);
         self
@@ -476,13 +536,20 @@ impl ActorBuilder {
             self.output_path,
             RecordingBaseInputs {
                 capture_target: self.capture_target,
-                capture_system_audio: self.system_audio,
+                capture_system_audio: self.system_audio || self.app_audio.is_some(),
+                app_audio: self.app_audio,
                 mic_feed: self.mic_feed,
+                additional_mics: Vec::new(),
                 camera_feed: self.camera_feed,
                 #[cfg(target_os = "macos")]
                 shareable_content,
                 #[cfg(target_os = "macos")]
                 excluded_windows: self.excluded_windows,
+                additional_videos: Vec::new(),
+                live_stream: self.live_stream,
+                media_input: None,
+                video_filters: self.video_filters,
+                encryption: None,
             },
             self.max_output_size,
             self.max_fps,
@@ -523,21 +590,32 @@ pub async fn spawn_instant_recording_actor(
                 })?;
 
                 let output_path = content_dir.join("output.mp4");
+                let camera_filters = inputs.video_filters.camera.clone();
+                let video_info =
+                    VideoFilterChain::new(&camera_filters).output_info(*camera_feed.video_info());
 
-                let mut builder = OutputPipeline::builder(output_path.clone())
-                    .with_video::<crate::sources::Camera>(camera_feed.clone())
-                    .with_timestamps(timestamps);
+                let mut builder =
+                    OutputPipeline::builder(output_path.clone()).with_timestamps(timestamps);
 
                 if let Some(mic_feed) = inputs.mic_feed.clone() {
                     builder = builder.with_audio_source::<crate::sources::Microphone>(mic_feed);
                 }
 
-                let cam_pipeline = builder
-                    .build::<output_pipeline::Mp4Muxer>(())
-                    .await
-                    .context("camera-only pipeline setup")?;
+                let cam_pipeline = if camera_filters.is_empty() {
+                    builder
+                        .with_video::<crate::sources::Camera>(camera_feed.clone())
+                        .build::<output_pipeline::Mp4Muxer>(())
+                        .await
+                } else {
+                    builder
+                        .with_video::<FilteredVideoSource<crate::sources::Camera>>(
+                            FilteredVideoSourceConfig::new(camera_feed.clone(), camera_filters),
+                        )
+                        .build::<output_pipeline::Mp4Muxer>(())
+                        .await
+                }
+                .context("camera-only pipeline setup")?;
 
-                let video_info = *camera_feed.video_info();
                 (
                     Pipeline {
                         video: cam_pipeline,
@@ -560,6 +638,7 @@ pub async fn spawn_instant_recording_actor(
                         failed to initialize. Try reconnecting your camera or selecting a different one."
                     )
                 })?;
+                inputs.video_filters.ensure_camera_supported()?;
 
                 let output_path = content_dir.join("output.mp4");
 
@@ -649,7 +728,9 @@ pub async fn spawn_instant_recording_actor(
                 inputs.excluded_windows,
             )
             .await
-            .context("screen capture init")?;
+            .context("screen capture init")?
+            .with_follow_window(inputs.capture_target.follow_window())
+            .with_app_audio(inputs.app_audio.clone());
 
             debug!("screen capture: {screen_source:#?}");
 
@@ -664,6 +745,8 @@ pub async fn spawn_instant_recording_actor(
                 system_audio_source,
                 max_output_size,
                 timestamps,
+                inputs.live_stream.clone(),
+                inputs.video_filters.screen.clone(),
             )
             .await?;
 
@@ -7,15 +7,18 @@ pub mod feeds;
 pub mod fragmentation;
 pub mod instant_recording;
 pub mod memory_profiling;
+pub mod metrics;
 mod output_pipeline;
 pub mod output_validation;
 pub mod recovery;
+pub mod replay;
 mod resolution_limits;
 pub mod screenshot;
 pub mod sources;
 pub mod studio_recording;
 pub mod sync_calibration;
 pub mod track_heal;
+pub mod video_filter;
 
 pub use resolution_limits::{H264_MAX_DIMENSION, calculate_gpu_compatible_size};
 
@@ -50,6 +53,9 @@ pub enum RecordingMode {
     #[default]
     Instant,
     Screenshot,
+    /// An instant recording kept as a rolling buffer of the last few minutes, saved on demand with
+    /// [`replay::save_replay`].
+    Replay,
 }
 
 #[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
@@ -76,12 +82,37 @@ pub struct RecordingOptions {
 pub struct RecordingBaseInputs {
     pub capture_target: ScreenCaptureTarget,
     pub capture_system_audio: bool,
+    /// Limits the system audio to some applications. Linux only.
+    pub app_audio: Option<sources::screen_capture::app_audio::AppAudioFilter>,
     pub mic_feed: Option<Arc<MicrophoneFeedLock>>,
+    /// Studio mode only. Microphones recorded as their own tracks next to
+    /// `mic_feed`, on the same clock; ignored when there is no `mic_feed`.
+    pub additional_mics: Vec<Arc<MicrophoneFeedLock>>,
     pub camera_feed: Option<Arc<CameraFeedLock>>,
     #[cfg(target_os = "macos")]
     pub shareable_content: Option<SendableShareableContent>,
     #[cfg(target_os = "macos")]
     pub excluded_windows: Vec<scap_targets::WindowId>,
+    /// Studio mode only; instant recordings ignore these.
+    pub additional_videos: Vec<AdditionalVideoInput>,
+    /// Streams the screen and one audio track next to the local recording.
+    pub live_stream: Option<LiveStream>,
+    /// Studio mode only. Replaces the screen capture, and the system audio
+    /// with the input's audio track, when set.
+    pub media_input: Option<sources::MediaInputFeed>,
+    /// Filters run on the screen and camera frames before they are encoded.
+    pub video_filters: video_filter::VideoFilters,
+    /// Studio mode only. Seals the project's media with this key: fragments
+    /// as they complete, everything else when the recording stops.
+    pub encryption: Option<cap_project::encryption::ProjectKey>,
+}
+
+/// An extra video source recorded into each studio segment next to the main
+/// capture target, for picture-in-picture layouts.
+#[derive(Clone)]
+pub enum AdditionalVideoInput {
+    Camera(Arc<CameraFeedLock>),
+    Display(ScreenCaptureTarget),
 }
 
 #[cfg(target_os = "macos")]
@@ -0,0 +1,120 @@
+//! Process-wide recording counters for external monitoring, such as the CLI's
+//! Prometheus endpoint. Counters only ever increase and cover every pipeline
+//! in the process, so exporters should treat them as monotonic totals.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+static METRICS: RecordingMetrics = RecordingMetrics::new();
+
+pub(crate) fn metrics() -> &'static RecordingMetrics {
+    &METRICS
+}
+
+pub(crate) struct RecordingMetrics {
+    frames_captured: AtomicU64,
+    frames_encoded: AtomicU64,
+    encode_time_ns: AtomicU64,
+    mixer_stalls: AtomicU64,
+    frames_dropped: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl RecordingMetrics {
+    const fn new() -> Self {
+        Self {
+            frames_captured: AtomicU64::new(0),
+            frames_encoded: AtomicU64::new(0),
+            encode_time_ns: AtomicU64::new(0),
+            mixer_stalls: AtomicU64::new(0),
+            frames_dropped: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub(crate) fn record_frame_captured(&self) {
+        self.frames_captured.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_frame_encoded(&self, elapsed: Duration) {
+        self.frames_encoded.fetch_add(1, Ordering::Relaxed);
+        self.encode_time_ns.fetch_add(
+            u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(crate) fn record_frame_dropped(&self, source: &'static str) {
+        let mut dropped = self
+            .frames_dropped
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *dropped.entry(source).or_default() += 1;
+    }
+
+    pub(crate) fn record_mixer_stall(&self) {
+        self.mixer_stalls.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    /// Video frames that reached an encoder.
+    pub frames_captured: u64,
+    /// Video frames the encoder accepted, with the time it spent on them.
+    pub frames_encoded: u64,
+    pub encode_time: Duration,
+    /// Audio mixer stalls waiting on a laggard source.
+    pub mixer_stalls: u64,
+    /// Frames a source dropped because its pipeline stayed full, by source.
+    pub frames_dropped: BTreeMap<&'static str, u64>,
+}
+
+impl MetricsSnapshot {
+    pub fn total_frames_dropped(&self) -> u64 {
+        self.frames_dropped.values().sum()
+    }
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+    let metrics = metrics();
+    MetricsSnapshot {
+        frames_captured: metrics.frames_captured.load(Ordering::Relaxed),
+        frames_encoded: metrics.frames_encoded.load(Ordering::Relaxed),
+        encode_time: Duration::from_nanos(metrics.encode_time_ns.load(Ordering::Relaxed)),
+        mixer_stalls: metrics.mixer_stalls.load(Ordering::Relaxed),
+        frames_dropped: metrics
+            .frames_dropped
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_accumulates_counters() {
+        let before = snapshot();
+
+        metrics().record_frame_captured();
+        metrics().record_frame_encoded(Duration::from_millis(4));
+        metrics().record_frame_dropped("metrics-test");
+        metrics().record_frame_dropped("metrics-test");
+        metrics().record_mixer_stall();
+
+        let after = snapshot();
+        assert!(after.frames_captured > before.frames_captured);
+        assert!(after.frames_encoded > before.frames_encoded);
+        assert!(after.encode_time >= before.encode_time + Duration::from_millis(4));
+        assert!(after.mixer_stalls > before.mixer_stalls);
+        assert_eq!(after.frames_dropped.get("metrics-test"), Some(&2));
+        assert!(after.total_frames_dropped() >= before.total_frames_dropped() + 2);
+    }
+}
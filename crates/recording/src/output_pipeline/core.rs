@@ -326,7 +326,12 @@ pub(crate) fn spawn_blocking_thread_timeout_cleanup(
     rx
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
 pub enum PipelineHealthEvent {
     FrameDropRateHigh {
         source: String,
@@ -372,12 +377,22 @@ pub enum PipelineHealthEvent {
     CaptureTargetLost {
         target: String,
     },
+    StreamConnected {
+        url: String,
+    },
+    StreamDisconnected {
+        reason: String,
+    },
+    StreamReconnecting {
+        attempt: u32,
+        delay_ms: u64,
+    },
 }
 
 pub type HealthSender = tokio::sync::mpsc::Sender<PipelineHealthEvent>;
 pub type HealthReceiver = tokio::sync::mpsc::Receiver<PipelineHealthEvent>;
 
-fn new_health_channel() -> (HealthSender, HealthReceiver) {
+pub(crate) fn new_health_channel() -> (HealthSender, HealthReceiver) {
     tokio::sync::mpsc::channel(HEALTH_CHANNEL_CAPACITY)
 }
 
@@ -545,6 +560,7 @@ pub(crate) fn send_with_stall_budget_futures<T>(
                             waited_ms,
                         },
                     );
+                    crate::metrics::metrics().record_frame_dropped(source);
                     return StallSendOutcome::StalledAndDropped { waited_ms };
                 }
                 frame = Some(err.into_inner());
@@ -575,6 +591,7 @@ pub(crate) fn send_with_stall_budget_flume<T>(
                     waited_ms,
                 },
             );
+            crate::metrics::metrics().record_frame_dropped(source);
             StallSendOutcome::StalledAndDropped { waited_ms }
         }
         Err(flume::SendTimeoutError::Disconnected(_)) => StallSendOutcome::Disconnected,
@@ -1494,6 +1511,7 @@ impl OutputPipeline {
             timestamps,
             master_clock: None,
             audio_anchor: AudioAnchor::FirstFrame,
+            live_stream: None,
         }
     }
 }
@@ -1577,6 +1595,7 @@ pub struct OutputPipelineBuilder<TVideo> {
     timestamps: Timestamps,
     master_clock: Option<Arc<MasterClock>>,
     audio_anchor: AudioAnchor,
+    live_stream: Option<LiveStream>,
 }
 
 pub struct NoVideo;
@@ -1622,6 +1641,12 @@ impl<THasVideo> OutputPipelineBuilder<THasVideo> {
         self.audio_anchor = anchor;
         self
     }
+
+    /// Also sends every frame handed to the muxer to `live_stream`.
+    pub fn with_live_stream(mut self, live_stream: Option<LiveStream>) -> Self {
+        self.live_stream = live_stream;
+        self
+    }
 }
 
 impl OutputPipelineBuilder<NoVideo> {
@@ -1636,6 +1661,7 @@ impl OutputPipelineBuilder<NoVideo> {
             timestamps: self.timestamps,
             master_clock: self.master_clock,
             audio_anchor: self.audio_anchor,
+            live_stream: self.live_stream,
         }
     }
 }
@@ -1702,6 +1728,7 @@ impl<TVideo: VideoSource> OutputPipelineBuilder<HasVideo<TVideo>> {
             path,
             master_clock,
             audio_anchor,
+            live_stream,
             ..
         } = self;
 
@@ -1762,6 +1789,7 @@ impl<TVideo: VideoSource> OutputPipelineBuilder<HasVideo<TVideo>> {
             master_clock.clone(),
             video_info,
             video_start_gate.clone(),
+            live_stream.clone(),
         );
 
         let audio_gap_summary = Arc::new(OnceLock::new());
@@ -1781,6 +1809,7 @@ impl<TVideo: VideoSource> OutputPipelineBuilder<HasVideo<TVideo>> {
             build_ctx.stop_signal,
             audio_gap_summary.clone(),
             audio_anchor,
+            live_stream,
         )
         .await?;
 
@@ -1811,6 +1840,7 @@ impl OutputPipelineBuilder<NoVideo> {
             path,
             master_clock,
             audio_anchor,
+            live_stream,
             ..
         } = self;
 
@@ -1867,6 +1897,7 @@ impl OutputPipelineBuilder<NoVideo> {
             build_ctx.stop_signal,
             audio_gap_summary.clone(),
             audio_anchor,
+            live_stream,
         )
         .await?;
 
@@ -1939,6 +1970,7 @@ async fn finish_build(
     stop_signal: PipelineStopSignal,
     gap_summary_slot: Arc<OnceLock<AudioGapSummary>>,
     audio_anchor: AudioAnchor,
+    live_stream: Option<LiveStream>,
 ) -> anyhow::Result<()> {
     if let Some(audio) = audio {
         audio.configure(
@@ -1952,6 +1984,7 @@ async fn finish_build(
             video_start_gate,
             gap_summary_slot,
             audio_anchor,
+            live_stream,
         );
     }
 
@@ -2099,8 +2132,13 @@ fn spawn_video_encoder<TMutex: VideoMuxer<VideoFrame = TVideo::Frame>, TVideo: V
     master_clock: Arc<MasterClock>,
     video_info: VideoInfo,
     video_start_gate: Option<VideoStartGate>,
+    live_stream: Option<LiveStream>,
 ) {
     let frame_duration_ns = estimate_video_frame_duration_ns(&video_info);
+    if let Some(live_stream) = &live_stream {
+        live_stream.default_health_sender(setup_ctx.health_tx());
+        live_stream.attach_video(video_info);
+    }
     setup_ctx.tasks().spawn("capture-video", {
         let stop_token = stop_token.clone();
         async move {
@@ -2143,6 +2181,7 @@ fn spawn_video_encoder<TMutex: VideoMuxer<VideoFrame = TVideo::Frame>, TVideo: V
                     }
 
                     frame_count += 1;
+                    crate::metrics::metrics().record_frame_captured();
 
                     let timestamp = frame.timestamp();
 
@@ -2224,9 +2263,16 @@ fn spawn_video_encoder<TMutex: VideoMuxer<VideoFrame = TVideo::Frame>, TVideo: V
                         );
                     }
 
-                    if let Err(e) = muxer.lock().await.send_video_frame(frame, duration) {
+                    if let Some(live_stream) = &live_stream {
+                        live_stream.send_video(&frame, duration);
+                    }
+
+                    let mut muxer_guard = muxer.lock().await;
+                    let encode_start = Instant::now();
+                    if let Err(e) = muxer_guard.send_video_frame(frame, duration) {
                         return Err(video_mux_send_error(frame_count, e));
                     }
+                    crate::metrics::metrics().record_frame_encoded(encode_start.elapsed());
                 }
 
                 info!("mux-video stream ended (rx closed)");
@@ -2398,8 +2444,12 @@ impl PreparedAudioSources {
         video_start_gate: Option<VideoStartGate>,
         gap_summary_slot: Arc<OnceLock<AudioGapSummary>>,
         audio_anchor: AudioAnchor,
+        live_stream: Option<LiveStream>,
     ) {
         let audio_info = self.audio_info;
+        if let Some(live_stream) = &live_stream {
+            live_stream.attach_audio(audio_info);
+        }
         let has_wireless_source = self.has_wireless_source;
         let health_tx = setup_ctx.health_tx().clone();
         let master_clock = setup_ctx.master_clock().clone();
@@ -2444,6 +2494,7 @@ impl PreparedAudioSources {
                                     observed_at: Instant::now(),
                                     timestamps,
                                     anchor: audio_anchor,
+                                    live_stream: live_stream.as_ref(),
                                 },
                                 AudioFrameProcessState {
                                     timestamp_generator: &mut timestamp_generator,
@@ -2506,6 +2557,7 @@ impl PreparedAudioSources {
                                     observed_at: Instant::now(),
                                     timestamps,
                                     anchor: audio_anchor,
+                                    live_stream: live_stream.as_ref(),
                                 },
                                 AudioFrameProcessState {
                                     timestamp_generator: &mut timestamp_generator,
@@ -2703,6 +2755,7 @@ struct AudioFrameProcessContext<'a, TMutex: AudioMuxer> {
     observed_at: Instant,
     timestamps: Timestamps,
     anchor: AudioAnchor,
+    live_stream: Option<&'a LiveStream>,
 }
 
 struct AudioFrameProcessState<'a> {
@@ -2963,6 +3016,10 @@ async fn process_audio_frame<TMutex: AudioMuxer>(
         );
     }
 
+    if let Some(live_stream) = ctx.live_stream {
+        live_stream.send_audio(&frame.inner, timestamp);
+    }
+
     if let Err(e) = ctx.muxer.lock().await.send_audio_frame(frame, timestamp) {
         if ctx.has_video {
             warn!(
@@ -3383,6 +3440,12 @@ pub trait AudioSource: Send + 'static {
 
 pub trait VideoFrame: Send + 'static {
     fn timestamp(&self) -> Timestamp;
+
+    /// Copies the frame into system memory for consumers that encode it on
+    /// their own, like a [`LiveStream`]. `None` if the frame can't be read back.
+    fn to_ffmpeg(&self) -> Option<ffmpeg::frame::Video> {
+        None
+    }
 }
 
 pub trait Muxer: Send + 'static {
@@ -5741,6 +5804,7 @@ mod tests {
                         observed_at,
                         timestamps: self.timestamps,
                         anchor: self.anchor,
+                        live_stream: None,
                     },
                     AudioFrameProcessState {
                         timestamp_generator: &mut self.timestamp_generator,
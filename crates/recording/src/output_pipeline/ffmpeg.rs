@@ -13,12 +13,14 @@ use cap_enc_ffmpeg::{
     ogg::*,
     opus::OpusEncoder,
     segmented_audio::SegmentedAudioEncoder,
-    segmented_stream::{SegmentCompletedEvent, SegmentedVideoEncoder, SegmentedVideoEncoderConfig},
+    segmented_stream::{
+        FragmentSealer, SegmentCompletedEvent, SegmentedVideoEncoder, SegmentedVideoEncoderConfig,
+    },
 };
 use cap_media_info::{AudioInfo, VideoInfo};
 use cap_timestamp::Timestamp;
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
         atomic::AtomicBool,
@@ -39,6 +41,10 @@ impl VideoFrame for FFmpegVideoFrame {
     fn timestamp(&self) -> Timestamp {
         self.timestamp
     }
+
+    fn to_ffmpeg(&self) -> Option<ffmpeg::frame::Video> {
+        Some(self.inner.clone())
+    }
 }
 
 pub struct Mp4Muxer {
@@ -133,13 +139,33 @@ impl AudioMuxer for Mp4Muxer {
     }
 }
 
-pub struct OggMuxer(OggFile);
+/// Seals an audio file once its muxer has finished writing it and closed it,
+/// which Windows needs before the file can be replaced. One that fails to seal
+/// is left for the recording to seal when it stops.
+fn seal_finished_audio(sealer: &FragmentSealer, path: &Path) {
+    if let Err(e) = sealer.seal(path) {
+        warn!("Failed to seal {}: {e}", path.display());
+    }
+}
+
+pub struct OggMuxer {
+    /// `None` once the file has been closed to be sealed.
+    file: Option<OggFile>,
+    path: PathBuf,
+    fragment_sealer: Option<FragmentSealer>,
+}
+
+#[derive(Default)]
+pub struct OggMuxerConfig {
+    /// Seals the file once it is finished.
+    pub fragment_sealer: Option<FragmentSealer>,
+}
 
 impl Muxer for OggMuxer {
-    type Config = ();
+    type Config = OggMuxerConfig;
 
     async fn setup(
-        _: Self::Config,
+        config: Self::Config,
         output_path: PathBuf,
         _: Option<VideoInfo>,
         audio_config: Option<AudioInfo>,
@@ -152,34 +178,53 @@ impl Muxer for OggMuxer {
         let audio_config =
             audio_config.ok_or_else(|| anyhow!("No audio configuration provided"))?;
 
-        Ok(Self(
-            OggFile::init(output_path, |o| OpusEncoder::init(audio_config, o))
-                .map_err(|e| anyhow!("Failed to initialize Opus encoder: {e}"))?,
-        ))
+        Ok(Self {
+            path: output_path.with_extension("ogg"),
+            file: Some(
+                OggFile::init(output_path, |o| OpusEncoder::init(audio_config, o))
+                    .map_err(|e| anyhow!("Failed to initialize Opus encoder: {e}"))?,
+            ),
+            fragment_sealer: config.fragment_sealer,
+        })
     }
 
     fn finish(&mut self, _: Duration) -> anyhow::Result<anyhow::Result<()>> {
-        self.0
-            .finish()
-            .map_err(Into::into)
-            .map(|r| r.map_err(Into::into))
+        let Some(file) = &mut self.file else {
+            return Err(anyhow!("Already finished"));
+        };
+        let result = file.finish()?;
+        if result.is_ok()
+            && let Some(sealer) = &self.fragment_sealer
+        {
+            self.file = None;
+            seal_finished_audio(sealer, &self.path);
+        }
+        Ok(result.map_err(Into::into))
     }
 }
 
 impl AudioMuxer for OggMuxer {
     fn send_audio_frame(&mut self, frame: AudioFrame, timestamp: Duration) -> anyhow::Result<()> {
-        Ok(self.0.queue_frame(frame.inner, timestamp)?)
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        Ok(file.queue_frame(frame.inner, timestamp)?)
     }
 }
 
 pub struct FragmentedAudioMuxer {
-    encoder: FragmentedAudioFile,
+    /// `None` once the file has been closed to be sealed.
+    encoder: Option<FragmentedAudioFile>,
+    path: PathBuf,
     pause: Option<SharedPauseState>,
+    fragment_sealer: Option<FragmentSealer>,
 }
 
 #[derive(Default)]
 pub struct FragmentedAudioMuxerConfig {
     pub shared_pause_state: Option<SharedPauseState>,
+    /// Seals the file once it is finished.
+    pub fragment_sealer: Option<FragmentSealer>,
 }
 
 impl Muxer for FragmentedAudioMuxer {
@@ -200,15 +245,31 @@ impl Muxer for FragmentedAudioMuxer {
             audio_config.ok_or_else(|| anyhow!("No audio configuration provided"))?;
 
         Ok(Self {
-            encoder: FragmentedAudioFile::init(output_path, audio_config)
-                .map_err(|e| anyhow!("Failed to initialize fragmented audio encoder: {e}"))?,
+            path: output_path.with_extension("m4a"),
+            encoder: Some(
+                FragmentedAudioFile::init(output_path, audio_config)
+                    .map_err(|e| anyhow!("Failed to initialize fragmented audio encoder: {e}"))?,
+            ),
             pause: config.shared_pause_state,
+            fragment_sealer: config.fragment_sealer,
         })
     }
 
     fn finish(&mut self, timestamp: Duration) -> anyhow::Result<anyhow::Result<()>> {
-        match self.encoder.finish_with_timestamp(timestamp) {
-            Ok(result) => Ok(result.map_err(Into::into)),
+        let Some(encoder) = &mut self.encoder else {
+            return Ok(Ok(()));
+        };
+
+        match encoder.finish_with_timestamp(timestamp) {
+            Ok(result) => {
+                if result.is_ok()
+                    && let Some(sealer) = &self.fragment_sealer
+                {
+                    self.encoder = None;
+                    seal_finished_audio(sealer, &self.path);
+                }
+                Ok(result.map_err(Into::into))
+            }
             Err(FragmentedAudioFinishError::AlreadyFinished) => Ok(Ok(())),
             Err(FragmentedAudioFinishError::WriteTrailerFailed(error)) => Ok(Err(anyhow!(error))),
         }
@@ -226,7 +287,10 @@ impl AudioMuxer for FragmentedAudioMuxer {
             timestamp
         };
 
-        Ok(self.encoder.queue_frame(frame.inner, adjusted_timestamp)?)
+        let Some(encoder) = &mut self.encoder else {
+            return Ok(());
+        };
+        Ok(encoder.queue_frame(frame.inner, adjusted_timestamp)?)
     }
 }
 
@@ -418,6 +482,8 @@ pub struct SegmentedVideoMuxer {
     pause: SharedPauseState,
     frame_drops: FrameDropTracker,
     started: bool,
+    fragment_sealer: Option<FragmentSealer>,
+    segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
 }
 
 pub struct SegmentedVideoMuxerConfig {
@@ -425,6 +491,9 @@ pub struct SegmentedVideoMuxerConfig {
     pub preset: H264Preset,
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
+    pub fragment_sealer: Option<FragmentSealer>,
+    /// Notified as each segment completes, e.g. to upload it while recording.
+    pub segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
 }
 
 impl Default for SegmentedVideoMuxerConfig {
@@ -434,6 +503,8 @@ impl Default for SegmentedVideoMuxerConfig {
             preset: H264Preset::Ultrafast,
             output_size: None,
             shared_pause_state: None,
+            fragment_sealer: None,
+            segment_tx: None,
         }
     }
 }
@@ -472,6 +543,8 @@ impl Muxer for SegmentedVideoMuxer {
             pause,
             frame_drops: FrameDropTracker::new(),
             started: false,
+            fragment_sealer: config.fragment_sealer,
+            segment_tx: config.segment_tx,
         })
     }
 
@@ -583,8 +656,14 @@ impl SegmentedVideoMuxer {
         };
 
         let slow_threshold_ms = frame_timing_log_threshold_ms(&self.video_config);
-        let encoder =
+        let mut encoder =
             SegmentedVideoEncoder::init(self.base_path.clone(), self.video_config, encoder_config)?;
+        if let Some(sealer) = &self.fragment_sealer {
+            encoder.set_fragment_sealer(sealer.clone());
+        }
+        if let Some(tx) = &self.segment_tx {
+            encoder.set_segment_callback(tx.clone());
+        }
         let encoder = Arc::new(Mutex::new(encoder));
         let encoder_clone = encoder.clone();
 
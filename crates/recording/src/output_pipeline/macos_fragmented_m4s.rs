@@ -10,7 +10,8 @@ use crate::{
 use anyhow::{Context, anyhow};
 use cap_enc_ffmpeg::h264::{H264EncoderBuilder, H264Preset};
 use cap_enc_ffmpeg::segmented_stream::{
-    DiskSpaceCallback, SegmentCompletedEvent, SegmentedVideoEncoder, SegmentedVideoEncoderConfig,
+    DiskSpaceCallback, FragmentSealer, SegmentCompletedEvent, SegmentedVideoEncoder,
+    SegmentedVideoEncoderConfig,
 };
 use cap_media_info::{AudioInfo, VideoInfo};
 use std::{
@@ -165,6 +166,7 @@ pub struct MacOSFragmentedM4SMuxer {
     frame_drops: FrameDropTracker,
     started: bool,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     health_tx: SharedHealthSender,
 }
@@ -176,6 +178,7 @@ pub struct MacOSFragmentedM4SMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
     pub segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
 }
 
@@ -188,6 +191,7 @@ impl Default for MacOSFragmentedM4SMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
         }
     }
@@ -229,6 +233,7 @@ impl Muxer for MacOSFragmentedM4SMuxer {
             frame_drops: FrameDropTracker::new(SharedHealthSender::new(), "muxer:macos-fragmented"),
             started: false,
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             segment_tx: config.segment_tx,
             health_tx: SharedHealthSender::new(),
         })
@@ -299,6 +304,9 @@ impl MacOSFragmentedM4SMuxer {
         if let Some(callback) = &self.disk_space_callback {
             encoder.set_disk_space_callback(callback.clone());
         }
+        if let Some(sealer) = &self.fragment_sealer {
+            encoder.set_fragment_sealer(sealer.clone());
+        }
         if let Some(tx) = &self.segment_tx {
             encoder.set_segment_callback(tx.clone());
         }
@@ -497,6 +505,7 @@ pub struct MacOSFragmentedM4SCameraMuxer {
     frame_drops: FrameDropTracker,
     started: bool,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     health_tx: SharedHealthSender,
 }
@@ -508,6 +517,7 @@ pub struct MacOSFragmentedM4SCameraMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
     pub segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
 }
 
@@ -520,6 +530,7 @@ impl Default for MacOSFragmentedM4SCameraMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
         }
     }
@@ -565,6 +576,7 @@ impl Muxer for MacOSFragmentedM4SCameraMuxer {
             ),
             started: false,
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             segment_tx: config.segment_tx,
             health_tx: SharedHealthSender::new(),
         })
@@ -637,6 +649,9 @@ impl MacOSFragmentedM4SCameraMuxer {
         if let Some(callback) = &self.disk_space_callback {
             encoder.set_disk_space_callback(callback.clone());
         }
+        if let Some(sealer) = &self.fragment_sealer {
+            encoder.set_fragment_sealer(sealer.clone());
+        }
         if let Some(tx) = &self.segment_tx {
             encoder.set_segment_callback(tx.clone());
         }
@@ -4,14 +4,16 @@ pub mod ffmpeg;
 #[cfg(target_os = "macos")]
 mod macos_fragmented_m4s;
 #[cfg(target_os = "macos")]
-mod macos_frame_convert;
+pub(crate) mod macos_frame_convert;
 #[cfg(target_os = "macos")]
 mod oop_fragmented_m4s;
 pub mod oop_muxer;
+mod stream;
 
 pub use async_camera::*;
 pub use core::*;
 pub use ffmpeg::*;
+pub use stream::*;
 
 #[cfg(target_os = "linux")]
 #[derive(Clone)]
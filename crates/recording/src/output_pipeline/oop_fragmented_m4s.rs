@@ -16,7 +16,7 @@ use anyhow::{Context, anyhow};
 use cap_enc_ffmpeg::fragment_manifest::FragmentManifestTracker;
 use cap_enc_ffmpeg::h264::{H264EncoderBuilder, H264Preset};
 use cap_enc_ffmpeg::h264_packet::EncodePacketError;
-use cap_enc_ffmpeg::segmented_stream::{DiskSpaceCallback, SegmentCompletedEvent};
+use cap_enc_ffmpeg::segmented_stream::{DiskSpaceCallback, FragmentSealer, SegmentCompletedEvent};
 use cap_media_info::{AudioInfo, VideoInfo};
 use std::{
     path::PathBuf,
@@ -118,6 +118,7 @@ pub struct OutOfProcessFragmentedM4SMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
     pub segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     pub max_respawns: u32,
 }
@@ -131,6 +132,7 @@ impl Default for OutOfProcessFragmentedM4SMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
             max_respawns: DEFAULT_MAX_RESPAWNS,
         }
@@ -149,6 +151,7 @@ pub struct OutOfProcessFragmentedM4SMuxer {
     frame_drops: FrameDropTracker,
     started: bool,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     health_tx: SharedHealthSender,
     max_respawns: u32,
@@ -190,6 +193,7 @@ impl Muxer for OutOfProcessFragmentedM4SMuxer {
             frame_drops: FrameDropTracker::new(SharedHealthSender::new(), "muxer:macos-oop"),
             started: false,
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             segment_tx: config.segment_tx,
             health_tx: SharedHealthSender::new(),
             max_respawns: config.max_respawns,
@@ -274,6 +278,7 @@ impl OutOfProcessFragmentedM4SMuxer {
         let bpp = self.bpp;
         let output_size = self.output_size;
         let disk_space_callback = self.disk_space_callback.clone();
+        let fragment_sealer = self.fragment_sealer.clone();
         let segment_tx = self.segment_tx.clone();
         let health_tx = self.health_tx.clone();
         let max_respawns = self.max_respawns;
@@ -337,6 +342,9 @@ impl OutOfProcessFragmentedM4SMuxer {
                     &video_config,
                     segment_duration,
                 );
+                if let Some(sealer) = fragment_sealer.clone() {
+                    tracker.set_fragment_sealer(sealer);
+                }
                 if let Some(tx) = segment_tx.clone() {
                     tracker.set_segment_callback(tx);
                 }
@@ -13,7 +13,7 @@ use anyhow::{Context, anyhow};
 use cap_enc_ffmpeg::fragment_manifest::FragmentManifestTracker;
 use cap_enc_ffmpeg::h264::{H264EncoderBuilder, H264Preset};
 use cap_enc_ffmpeg::h264_packet::EncodePacketError;
-use cap_enc_ffmpeg::segmented_stream::{DiskSpaceCallback, SegmentCompletedEvent};
+use cap_enc_ffmpeg::segmented_stream::{DiskSpaceCallback, FragmentSealer, SegmentCompletedEvent};
 use cap_media_info::{AudioInfo, VideoInfo};
 use std::{
     path::PathBuf,
@@ -121,6 +121,7 @@ pub struct WindowsOOPFragmentedM4SMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
     pub segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     pub max_respawns: u32,
 }
@@ -134,6 +135,7 @@ impl Default for WindowsOOPFragmentedM4SMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
             max_respawns: DEFAULT_MAX_RESPAWNS,
         }
@@ -151,6 +153,7 @@ pub struct WindowsOOPFragmentedM4SMuxer {
     pause: SharedPauseState,
     frame_drops: FrameDropTracker,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx: Option<std::sync::mpsc::Sender<SegmentCompletedEvent>>,
     health_tx: SharedHealthSender,
     max_respawns: u32,
@@ -192,6 +195,7 @@ impl Muxer for WindowsOOPFragmentedM4SMuxer {
             pause,
             frame_drops: FrameDropTracker::new(SharedHealthSender::new(), "muxer:windows-oop"),
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             segment_tx: config.segment_tx,
             health_tx: SharedHealthSender::new(),
             max_respawns: config.max_respawns,
@@ -265,6 +269,7 @@ impl WindowsOOPFragmentedM4SMuxer {
         let bpp = self.bpp;
         let output_size = self.output_size;
         let disk_space_callback = self.disk_space_callback.clone();
+        let fragment_sealer = self.fragment_sealer.clone();
         let segment_tx = self.segment_tx.clone();
         let health_tx = self.health_tx.clone();
         let max_respawns = self.max_respawns;
@@ -328,6 +333,9 @@ impl WindowsOOPFragmentedM4SMuxer {
                     &video_config,
                     segment_duration,
                 );
+                if let Some(sealer) = fragment_sealer.clone() {
+                    tracker.set_fragment_sealer(sealer);
+                }
                 if let Some(tx) = segment_tx.clone() {
                     tracker.set_segment_callback(tx);
                 }
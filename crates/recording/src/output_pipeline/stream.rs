@@ -0,0 +1,578 @@
+use crate::{
+    TaskPool,
+    output_pipeline::{
+        AudioFrame, AudioMuxer, HealthSender, Muxer, PipelineHealthEvent, SharedHealthSender,
+        VideoFrame, VideoMuxer,
+    },
+};
+use anyhow::{Context, anyhow};
+use cap_enc_ffmpeg::{
+    aac::AACEncoder,
+    h264::{H264Encoder, H264Preset},
+};
+use cap_media_info::{AudioInfo, VideoInfo};
+use std::{
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError, SyncSender, TrySendError, sync_channel},
+    },
+    time::{Duration, Instant},
+};
+use tracing::*;
+
+/// Frames buffered ahead of the network. A stalled connection drops frames
+/// past this instead of holding up the recording.
+const STREAM_CHANNEL_CAPACITY: usize = 64;
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// How long the first connect waits for an audio track to be attached after
+/// the video track, so audio pipelines built right after the screen pipeline
+/// make it into the stream.
+const AUDIO_ATTACH_GRACE: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a network read or write may block before the connection is
+/// treated as dropped, so a peer that stops reading triggers a reconnect
+/// instead of stalling the worker.
+const STREAM_IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamProtocol {
+    /// FLV over `rtmp://` or `rtmps://`.
+    Rtmp,
+    /// MPEG-TS over `srt://`.
+    Srt,
+}
+
+impl StreamProtocol {
+    pub fn from_url(url: &str) -> anyhow::Result<Self> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| anyhow!("Stream URL '{url}' has no scheme"))?;
+
+        if rest.is_empty() {
+            return Err(anyhow!("Stream URL '{url}' has no host"));
+        }
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "rtmp" | "rtmps" => Ok(Self::Rtmp),
+            "srt" => Ok(Self::Srt),
+            _ => Err(anyhow!(
+                "Unsupported stream URL scheme '{scheme}', expected rtmp, rtmps or srt"
+            )),
+        }
+    }
+
+    fn format_name(self) -> &'static str {
+        match self {
+            Self::Rtmp => "flv",
+            Self::Srt => "mpegts",
+        }
+    }
+}
+
+enum StreamInput {
+    Video(ffmpeg::frame::Video, Duration),
+    Audio(ffmpeg::frame::Audio, Duration),
+}
+
+#[derive(Default)]
+struct StreamTracks {
+    video: Option<(VideoInfo, Instant)>,
+    audio: Option<AudioInfo>,
+}
+
+/// A live RTMP/SRT output fed with the frames of one or more recording
+/// pipelines. Cloning shares the connection; it is flushed and closed once
+/// every clone has been dropped.
+///
+/// Encoding and network writes run on a dedicated thread, so a slow or lost
+/// connection drops stream frames rather than stalling the local recording.
+/// Lost connections are retried with exponential backoff and reported as
+/// `Stream*` [`PipelineHealthEvent`]s.
+#[derive(Clone)]
+pub struct LiveStream {
+    inner: Arc<LiveStreamInner>,
+}
+
+struct LiveStreamInner {
+    url: String,
+    protocol: StreamProtocol,
+    tx: SyncSender<StreamInput>,
+    tracks: Arc<Mutex<StreamTracks>>,
+    accepting: Arc<AtomicBool>,
+    health: SharedHealthSender,
+}
+
+impl LiveStream {
+    pub fn new(url: impl Into<String>) -> anyhow::Result<Self> {
+        let url = url.into();
+        let protocol = StreamProtocol::from_url(&url)?;
+
+        let (tx, rx) = sync_channel(STREAM_CHANNEL_CAPACITY);
+        let tracks = Arc::new(Mutex::new(StreamTracks::default()));
+        let accepting = Arc::new(AtomicBool::new(true));
+        let health = SharedHealthSender::new();
+
+        std::thread::Builder::new()
+            .name("live-stream".to_string())
+            .spawn({
+                let worker = StreamWorker {
+                    url: url.clone(),
+                    protocol,
+                    tracks: tracks.clone(),
+                    accepting: accepting.clone(),
+                    health: health.clone(),
+                };
+                move || worker.run(rx)
+            })
+            .context("spawn live stream thread")?;
+
+        Ok(Self {
+            inner: Arc::new(LiveStreamInner {
+                url,
+                protocol,
+                tx,
+                tracks,
+                accepting,
+                health,
+            }),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.inner.url
+    }
+
+    pub fn protocol(&self) -> StreamProtocol {
+        self.inner.protocol
+    }
+
+    /// Routes the connection events to `tx`. Otherwise they go to the health
+    /// channel of the first pipeline that feeds the stream.
+    pub fn set_health_sender(&self, tx: HealthSender) {
+        self.inner.health.set(tx);
+    }
+
+    pub(crate) fn default_health_sender(&self, tx: &HealthSender) {
+        if self.inner.health.get().is_none() {
+            self.inner.health.set(tx.clone());
+        }
+    }
+
+    pub(crate) fn attach_video(&self, info: VideoInfo) {
+        if let Ok(mut tracks) = self.inner.tracks.lock() {
+            let attached_at = tracks.video.map(|(_, at)| at).unwrap_or_else(Instant::now);
+            tracks.video = Some((info, attached_at));
+        }
+    }
+
+    pub(crate) fn attach_audio(&self, info: AudioInfo) {
+        if let Ok(mut tracks) = self.inner.tracks.lock() {
+            if tracks.audio.is_some_and(|current| current != info) {
+                warn!("Live stream already has an audio track, ignoring another");
+                return;
+            }
+            tracks.audio = Some(info);
+        }
+    }
+
+    pub(crate) fn send_video(&self, frame: &impl VideoFrame, timestamp: Duration) {
+        if !self.inner.accepting.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(frame) = frame.to_ffmpeg() {
+            self.send(StreamInput::Video(frame, timestamp));
+        }
+    }
+
+    pub(crate) fn send_audio(&self, frame: &ffmpeg::frame::Audio, timestamp: Duration) {
+        if !self.inner.accepting.load(Ordering::Relaxed) {
+            return;
+        }
+        self.send(StreamInput::Audio(frame.clone(), timestamp));
+    }
+
+    fn send(&self, input: StreamInput) {
+        match self.inner.tx.try_send(input) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => trace!("Live stream backlogged, dropping frame"),
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+struct StreamWorker {
+    url: String,
+    protocol: StreamProtocol,
+    tracks: Arc<Mutex<StreamTracks>>,
+    accepting: Arc<AtomicBool>,
+    health: SharedHealthSender,
+}
+
+enum WorkerState {
+    Waiting,
+    Live(Box<StreamSession>),
+    Backoff { attempt: u32, retry_at: Instant },
+}
+
+impl StreamWorker {
+    fn run(self, rx: Receiver<StreamInput>) {
+        let mut state = WorkerState::Waiting;
+        let mut video_clock = TrackClock::default();
+        let mut audio_clock = TrackClock::default();
+
+        loop {
+            let input = match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(input) => Some(input),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            // Segments restart their timestamps at zero, so keep each track
+            // moving forward across them before anything is dropped.
+            let input = input.map(|input| match input {
+                StreamInput::Video(frame, ts) => StreamInput::Video(frame, video_clock.next(ts)),
+                StreamInput::Audio(frame, ts) => StreamInput::Audio(frame, audio_clock.next(ts)),
+            });
+
+            state = match state {
+                WorkerState::Waiting => match self.ready_tracks() {
+                    Some(tracks) if matches!(input, Some(StreamInput::Video(..))) => {
+                        self.connect(tracks, 0)
+                    }
+                    _ => WorkerState::Waiting,
+                },
+                WorkerState::Backoff { attempt, retry_at } if Instant::now() >= retry_at => {
+                    match self.ready_tracks() {
+                        Some(tracks) => self.connect(tracks, attempt),
+                        None => WorkerState::Backoff { attempt, retry_at },
+                    }
+                }
+                state => state,
+            };
+
+            if let Some(input) = input
+                && let WorkerState::Live(session) = &mut state
+                && let Err(e) = session.write(input)
+            {
+                warn!(url = %self.url, "Live stream write failed: {e:#}");
+                self.health.emit(PipelineHealthEvent::StreamDisconnected {
+                    reason: format!("{e:#}"),
+                });
+                state = self.backoff(1);
+            }
+        }
+
+        if let WorkerState::Live(mut session) = state {
+            match session.finish() {
+                Ok(()) => info!(url = %self.url, "Live stream finished"),
+                Err(e) => warn!(url = %self.url, "Live stream finish failed: {e:#}"),
+            }
+        }
+    }
+
+    /// Track infos once the stream can connect: video must be attached, and
+    /// audio too unless the grace period for it has passed.
+    fn ready_tracks(&self) -> Option<(VideoInfo, Option<AudioInfo>)> {
+        let tracks = self.tracks.lock().ok()?;
+        let (video, attached_at) = tracks.video?;
+        if tracks.audio.is_none() && attached_at.elapsed() < AUDIO_ATTACH_GRACE {
+            return None;
+        }
+        Some((video, tracks.audio))
+    }
+
+    fn connect(&self, (video, audio): (VideoInfo, Option<AudioInfo>), attempt: u32) -> WorkerState {
+        match StreamSession::connect(&self.url, self.protocol, video, audio) {
+            Ok(session) => {
+                info!(url = %self.url, attempt, "Live stream connected");
+                self.accepting.store(true, Ordering::Relaxed);
+                self.health.emit(PipelineHealthEvent::StreamConnected {
+                    url: self.url.clone(),
+                });
+                WorkerState::Live(Box::new(session))
+            }
+            Err(e) => {
+                warn!(url = %self.url, attempt, "Live stream connect failed: {e:#}");
+                if attempt == 0 {
+                    self.health.emit(PipelineHealthEvent::StreamDisconnected {
+                        reason: format!("{e:#}"),
+                    });
+                }
+                self.backoff(attempt + 1)
+            }
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> WorkerState {
+        let delay = reconnect_delay(attempt);
+        self.accepting.store(false, Ordering::Relaxed);
+        self.health.emit(PipelineHealthEvent::StreamReconnecting {
+            attempt,
+            delay_ms: delay.as_millis() as u64,
+        });
+        WorkerState::Backoff {
+            attempt,
+            retry_at: Instant::now() + delay,
+        }
+    }
+}
+
+/// Delay before reconnect `attempt` (1-based): doubling from
+/// [`RECONNECT_INITIAL_DELAY`] up to [`RECONNECT_MAX_DELAY`].
+fn reconnect_delay(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    RECONNECT_INITIAL_DELAY
+        .saturating_mul(factor)
+        .min(RECONNECT_MAX_DELAY)
+}
+
+/// Keeps one track's timestamps increasing when its pipeline is replaced and
+/// starts counting from zero again.
+#[derive(Default)]
+struct TrackClock {
+    offset: Duration,
+    last: Option<Duration>,
+}
+
+impl TrackClock {
+    const RESTART_STEP: Duration = Duration::from_millis(1);
+
+    fn next(&mut self, timestamp: Duration) -> Duration {
+        let mut adjusted = timestamp + self.offset;
+        if let Some(last) = self.last
+            && adjusted < last
+        {
+            self.offset = (last + Self::RESTART_STEP).saturating_sub(timestamp);
+            adjusted = timestamp + self.offset;
+        }
+        self.last = Some(adjusted);
+        adjusted
+    }
+}
+
+struct StreamSession {
+    output: ffmpeg::format::context::Output,
+    video: H264Encoder,
+    audio: Option<AACEncoder>,
+    /// First timestamp written on this connection. Every connection starts
+    /// its own timeline at zero.
+    base: Option<Duration>,
+}
+
+impl StreamSession {
+    fn connect(
+        url: &str,
+        protocol: StreamProtocol,
+        video_info: VideoInfo,
+        audio_info: Option<AudioInfo>,
+    ) -> anyhow::Result<Self> {
+        let mut output = open_output(url, protocol, STREAM_IO_TIMEOUT)?;
+
+        let video = H264Encoder::builder(video_info)
+            .with_preset(H264Preset::Ultrafast)
+            .build(&mut output)
+            .context("video encoder")?;
+
+        let audio = audio_info
+            .map(|info| AACEncoder::init(info, &mut output))
+            .transpose()
+            .context("audio encoder")?;
+
+        output.write_header().context("write_header")?;
+
+        Ok(Self {
+            output,
+            video,
+            audio,
+            base: None,
+        })
+    }
+
+    fn write(&mut self, input: StreamInput) -> anyhow::Result<()> {
+        match input {
+            StreamInput::Video(frame, timestamp) => {
+                // Open on a video frame so the stream starts with a keyframe.
+                let base = *self.base.get_or_insert(timestamp);
+                let Some(timestamp) = timestamp.checked_sub(base) else {
+                    return Ok(());
+                };
+                self.video.queue_frame(frame, timestamp, &mut self.output)?;
+            }
+            StreamInput::Audio(frame, timestamp) => {
+                let (Some(audio), Some(base)) = (self.audio.as_mut(), self.base) else {
+                    return Ok(());
+                };
+                let Some(timestamp) = timestamp.checked_sub(base) else {
+                    return Ok(());
+                };
+                audio.send_frame(frame, timestamp, &mut self.output)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let video_result = self.video.flush(&mut self.output);
+        let audio_result = self
+            .audio
+            .as_mut()
+            .map(|audio| audio.flush(&mut self.output))
+            .unwrap_or(Ok(()));
+
+        self.output.write_trailer().context("write_trailer")?;
+
+        video_result.context("flush video")?;
+        audio_result.context("flush audio")?;
+        Ok(())
+    }
+}
+
+/// Muxer that only feeds a [`LiveStream`], for pipelines that stream without
+/// writing a local file. The output path is ignored.
+pub struct StreamMuxer<TFrame> {
+    stream: LiveStream,
+    _frame: PhantomData<fn(TFrame)>,
+}
+
+impl<TFrame: VideoFrame> Muxer for StreamMuxer<TFrame> {
+    type Config = LiveStream;
+
+    async fn setup(
+        stream: Self::Config,
+        _: PathBuf,
+        video_config: Option<VideoInfo>,
+        audio_config: Option<AudioInfo>,
+        _: Arc<AtomicBool>,
+        _: &mut TaskPool,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        if let Some(video_config) = video_config {
+            stream.attach_video(video_config);
+        }
+        if let Some(audio_config) = audio_config {
+            stream.attach_audio(audio_config);
+        }
+
+        Ok(Self {
+            stream,
+            _frame: PhantomData,
+        })
+    }
+
+    fn finish(&mut self, _: Duration) -> anyhow::Result<anyhow::Result<()>> {
+        Ok(Ok(()))
+    }
+
+    fn set_health_sender(&mut self, tx: HealthSender) {
+        self.stream.default_health_sender(&tx);
+    }
+}
+
+impl<TFrame: VideoFrame> VideoMuxer for StreamMuxer<TFrame> {
+    type VideoFrame = TFrame;
+
+    fn send_video_frame(
+        &mut self,
+        frame: Self::VideoFrame,
+        timestamp: Duration,
+    ) -> anyhow::Result<()> {
+        self.stream.send_video(&frame, timestamp);
+        Ok(())
+    }
+}
+
+impl<TFrame: VideoFrame> AudioMuxer for StreamMuxer<TFrame> {
+    fn send_audio_frame(&mut self, frame: AudioFrame, timestamp: Duration) -> anyhow::Result<()> {
+        self.stream.send_audio(&frame.inner, timestamp);
+        Ok(())
+    }
+}
+
+fn open_output(
+    url: &str,
+    protocol: StreamProtocol,
+    io_timeout: Duration,
+) -> anyhow::Result<ffmpeg::format::context::Output> {
+    let mut options = ffmpeg::Dictionary::new();
+    options.set("rw_timeout", &io_timeout.as_micros().to_string());
+
+    ffmpeg::format::output_as_with(url, protocol.format_name(), options)
+        .with_context(|| format!("open {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_follows_url_scheme() {
+        assert_eq!(
+            StreamProtocol::from_url("rtmp://live.example.com/app/key").unwrap(),
+            StreamProtocol::Rtmp
+        );
+        assert_eq!(
+            StreamProtocol::from_url("RTMPS://live.example.com:443/app").unwrap(),
+            StreamProtocol::Rtmp
+        );
+        assert_eq!(
+            StreamProtocol::from_url("srt://127.0.0.1:9000?mode=caller").unwrap(),
+            StreamProtocol::Srt
+        );
+        assert!(StreamProtocol::from_url("https://example.com/live").is_err());
+        assert!(StreamProtocol::from_url("rtmp://").is_err());
+        assert!(StreamProtocol::from_url("live.example.com").is_err());
+    }
+
+    #[test]
+    fn reconnect_delay_doubles_up_to_cap() {
+        let delays: Vec<u64> = (1..=7).map(|a| reconnect_delay(a).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(reconnect_delay(100), RECONNECT_MAX_DELAY);
+    }
+
+    #[test]
+    fn track_clock_continues_across_restarts() {
+        let mut clock = TrackClock::default();
+        let ms = Duration::from_millis;
+
+        assert_eq!(clock.next(ms(0)), ms(0));
+        assert_eq!(clock.next(ms(500)), ms(500));
+        // A new segment starts again from zero.
+        assert_eq!(clock.next(ms(0)), ms(501));
+        assert_eq!(clock.next(ms(100)), ms(601));
+    }
+
+    #[test]
+    fn connect_gives_up_when_the_server_stops_responding() {
+        ffmpeg::init().unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accept the connection and hold it open without reading or answering.
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            let _socket = listener.accept();
+            let _ = done_rx.recv();
+        });
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let result = open_output(
+                &format!("rtmp://127.0.0.1:{port}/live/key"),
+                StreamProtocol::Rtmp,
+                Duration::from_millis(500),
+            );
+            let _ = tx.send(result.is_err());
+        });
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(10)), Ok(true));
+        drop(done_tx);
+    }
+}
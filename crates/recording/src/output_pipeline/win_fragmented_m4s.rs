@@ -10,7 +10,7 @@ use crate::{
 use anyhow::{Context, anyhow};
 use cap_enc_ffmpeg::h264::{H264EncoderBuilder, H264Preset};
 use cap_enc_ffmpeg::segmented_stream::{
-    DiskSpaceCallback, SegmentedVideoEncoder, SegmentedVideoEncoderConfig,
+    DiskSpaceCallback, FragmentSealer, SegmentedVideoEncoder, SegmentedVideoEncoderConfig,
 };
 use cap_media_info::{AudioInfo, Pixel, VideoInfo};
 use std::{
@@ -209,6 +209,7 @@ pub struct WindowsFragmentedM4SMuxer {
     frame_drops: FrameDropTracker,
     started: bool,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     segment_tx:
         Option<std::sync::mpsc::Sender<cap_enc_ffmpeg::segmented_stream::SegmentCompletedEvent>>,
     health_tx: SharedHealthSender,
@@ -221,6 +222,7 @@ pub struct WindowsFragmentedM4SMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
     pub segment_tx:
         Option<std::sync::mpsc::Sender<cap_enc_ffmpeg::segmented_stream::SegmentCompletedEvent>>,
 }
@@ -234,6 +236,7 @@ impl Default for WindowsFragmentedM4SMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
             segment_tx: None,
         }
     }
@@ -278,6 +281,7 @@ impl Muxer for WindowsFragmentedM4SMuxer {
             ),
             started: false,
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             segment_tx: config.segment_tx,
             health_tx: SharedHealthSender::new(),
         };
@@ -340,6 +344,9 @@ impl WindowsFragmentedM4SMuxer {
         if let Some(callback) = &self.disk_space_callback {
             encoder.set_disk_space_callback(callback.clone());
         }
+        if let Some(sealer) = &self.fragment_sealer {
+            encoder.set_fragment_sealer(sealer.clone());
+        }
         if let Some(tx) = self.segment_tx.take() {
             encoder.set_segment_callback(tx);
         }
@@ -639,6 +646,7 @@ pub struct WindowsFragmentedM4SCameraMuxer {
     frame_drops: FrameDropTracker,
     started: bool,
     disk_space_callback: Option<DiskSpaceCallback>,
+    fragment_sealer: Option<FragmentSealer>,
     health_tx: SharedHealthSender,
 }
 
@@ -649,6 +657,7 @@ pub struct WindowsFragmentedM4SCameraMuxerConfig {
     pub output_size: Option<(u32, u32)>,
     pub shared_pause_state: Option<SharedPauseState>,
     pub disk_space_callback: Option<DiskSpaceCallback>,
+    pub fragment_sealer: Option<FragmentSealer>,
 }
 
 impl Default for WindowsFragmentedM4SCameraMuxerConfig {
@@ -660,6 +669,7 @@ impl Default for WindowsFragmentedM4SCameraMuxerConfig {
             output_size: None,
             shared_pause_state: None,
             disk_space_callback: None,
+            fragment_sealer: None,
         }
     }
 }
@@ -704,6 +714,7 @@ impl Muxer for WindowsFragmentedM4SCameraMuxer {
             ),
             started: false,
             disk_space_callback: config.disk_space_callback,
+            fragment_sealer: config.fragment_sealer,
             health_tx: SharedHealthSender::new(),
         };
 
@@ -807,6 +818,9 @@ impl WindowsFragmentedM4SCameraMuxer {
         if let Some(callback) = &self.disk_space_callback {
             encoder.set_disk_space_callback(callback.clone());
         }
+        if let Some(sealer) = &self.fragment_sealer {
+            encoder.set_fragment_sealer(sealer.clone());
+        }
         let encoder = Arc::new(Mutex::new(encoder));
         let encoder_clone = encoder.clone();
 
@@ -1,4 +1,6 @@
 use std::{
+    collections::HashSet,
+    ffi::OsString,
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -13,6 +15,7 @@ use cap_project::{
     AudioMeta, Cursors, MultipleSegment, MultipleSegments, ProjectConfiguration, RecordingMeta,
     RecordingMetaInner, StudioRecordingMeta, StudioRecordingStatus, TimelineConfiguration,
     TimelineSegment, VideoMeta,
+    encryption::{self, DecryptedProject, MasterKey},
 };
 use relative_path::RelativePathBuf;
 use tracing::{debug, warn};
@@ -31,6 +34,45 @@ pub struct IncompleteRecording {
     pub meta: RecordingMeta,
     pub recoverable_segments: Vec<RecoverableSegment>,
     pub estimated_duration: Duration,
+    /// The key of an encrypted project, which is recovered in a decrypted copy
+    /// and sealed again.
+    pub master_key: Option<MasterKey>,
+}
+
+impl IncompleteRecording {
+    /// The same recording with every path under `from` moved under `to`.
+    fn rebased(&self, from: &Path, to: &Path) -> Self {
+        let rebase = |path: &PathBuf| {
+            path.strip_prefix(from)
+                .map(|relative| to.join(relative))
+                .unwrap_or_else(|_| path.clone())
+        };
+        let rebase_all = |paths: &Vec<PathBuf>| paths.iter().map(rebase).collect::<Vec<_>>();
+
+        let mut meta = self.meta.clone();
+        meta.project_path = rebase(&meta.project_path);
+
+        Self {
+            project_path: rebase(&self.project_path),
+            meta,
+            recoverable_segments: self
+                .recoverable_segments
+                .iter()
+                .map(|segment| RecoverableSegment {
+                    index: segment.index,
+                    display_fragments: rebase_all(&segment.display_fragments),
+                    display_init_segment: segment.display_init_segment.as_ref().map(rebase),
+                    camera_fragments: segment.camera_fragments.as_ref().map(rebase_all),
+                    camera_init_segment: segment.camera_init_segment.as_ref().map(rebase),
+                    mic_fragments: segment.mic_fragments.as_ref().map(rebase_all),
+                    system_audio_fragments: segment.system_audio_fragments.as_ref().map(rebase_all),
+                    cursor_path: segment.cursor_path.as_ref().map(rebase),
+                })
+                .collect(),
+            estimated_duration: self.estimated_duration,
+            master_key: self.master_key.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -112,6 +154,15 @@ const EXPORT_SEEK_PROBE_SAMPLE_COUNT: usize = 8;
 
 impl RecoveryManager {
     pub fn inspect_recording(project_path: &Path) -> Option<IncompleteRecording> {
+        Self::inspect_recording_with_key(project_path, None)
+    }
+
+    /// Like [`RecoveryManager::inspect_recording`], opening an encrypted
+    /// project with `master_key`.
+    pub fn inspect_recording_with_key(
+        project_path: &Path,
+        master_key: Option<&MasterKey>,
+    ) -> Option<IncompleteRecording> {
         if !project_path.is_dir() {
             return None;
         }
@@ -120,12 +171,19 @@ impl RecoveryManager {
             return None;
         }
 
-        let meta = RecordingMeta::load_for_project(project_path).ok()?;
-
-        Self::analyze_incomplete(project_path, &meta)
+        Self::analyze_project(project_path, master_key, Self::analyze_incomplete)
     }
 
     pub fn find_incomplete_single(project_path: &Path) -> Option<IncompleteRecording> {
+        Self::find_incomplete_single_with_key(project_path, None)
+    }
+
+    /// Like [`RecoveryManager::find_incomplete_single`], opening an encrypted
+    /// project with `master_key`.
+    pub fn find_incomplete_single_with_key(
+        project_path: &Path,
+        master_key: Option<&MasterKey>,
+    ) -> Option<IncompleteRecording> {
         if !project_path.is_dir() {
             return None;
         }
@@ -134,19 +192,29 @@ impl RecoveryManager {
             return None;
         }
 
-        let meta = RecordingMeta::load_for_project(project_path).ok()?;
-
-        if let Some(studio_meta) = meta.studio_meta()
-            && Self::should_check_for_recovery(&studio_meta.status())
-        {
-            Self::analyze_incomplete(project_path, &meta)
-        } else {
-            None
-        }
+        Self::analyze_project(project_path, master_key, |path, meta| {
+            if let Some(studio_meta) = meta.studio_meta()
+                && Self::should_check_for_recovery(&studio_meta.status())
+            {
+                Self::analyze_incomplete(path, meta)
+            } else {
+                None
+            }
+        })
     }
 
     pub fn remux_if_needed(project_path: &Path) -> Result<bool, RecoveryError> {
-        let Some(incomplete) = Self::find_incomplete_single(project_path) else {
+        Self::remux_if_needed_with_key(project_path, None)
+    }
+
+    /// Like [`RecoveryManager::remux_if_needed`], opening an encrypted project
+    /// with `master_key`.
+    pub fn remux_if_needed_with_key(
+        project_path: &Path,
+        master_key: Option<&MasterKey>,
+    ) -> Result<bool, RecoveryError> {
+        let Some(incomplete) = Self::find_incomplete_single_with_key(project_path, master_key)
+        else {
             return Ok(false);
         };
 
@@ -159,6 +227,15 @@ impl RecoveryManager {
     }
 
     pub fn find_incomplete(recordings_dir: &Path) -> Vec<IncompleteRecording> {
+        Self::find_incomplete_with_key(recordings_dir, None)
+    }
+
+    /// Like [`RecoveryManager::find_incomplete`], opening encrypted projects
+    /// with `master_key`.
+    pub fn find_incomplete_with_key(
+        recordings_dir: &Path,
+        master_key: Option<&MasterKey>,
+    ) -> Vec<IncompleteRecording> {
         let mut incomplete = Vec::new();
 
         let Ok(entries) = std::fs::read_dir(recordings_dir) else {
@@ -175,27 +252,152 @@ impl RecoveryManager {
                 continue;
             }
 
-            let Ok(meta) = RecordingMeta::load_for_project(&path) else {
-                continue;
-            };
+            let recording = Self::analyze_project(&path, master_key, |path, meta| {
+                let studio_meta = meta.studio_meta()?;
+                if !Self::should_check_for_recovery(&studio_meta.status()) {
+                    return None;
+                }
 
-            if let Some(studio_meta) = meta.studio_meta()
-                && Self::should_check_for_recovery(&studio_meta.status())
-            {
-                match Self::analyze_incomplete(&path, &meta) {
-                    Some(incomplete_recording) => {
-                        incomplete.push(incomplete_recording);
-                    }
-                    None => {
-                        Self::mark_unrecoverable(&path, &meta);
-                    }
+                let recording = Self::analyze_incomplete(path, meta);
+                if recording.is_none() {
+                    Self::mark_unrecoverable(path, meta);
                 }
+                recording
+            });
+
+            if let Some(incomplete_recording) = recording {
+                incomplete.push(incomplete_recording);
             }
         }
 
         incomplete
     }
 
+    /// Runs `analyze` on the project at `project_path`. An encrypted project is
+    /// analyzed in a decrypted copy, whose changes are sealed back into it, and
+    /// the recording found is pointed back at the project.
+    fn analyze_project(
+        project_path: &Path,
+        master_key: Option<&MasterKey>,
+        analyze: impl FnOnce(&Path, &RecordingMeta) -> Option<IncompleteRecording>,
+    ) -> Option<IncompleteRecording> {
+        if !encryption::is_encrypted(project_path) {
+            let meta = RecordingMeta::load_for_project(project_path).ok()?;
+            return analyze(project_path, &meta);
+        }
+
+        let Some(master_key) = master_key else {
+            warn!(
+                "Recording at {:?} is encrypted and can't be checked for recovery without its key",
+                project_path
+            );
+            return None;
+        };
+
+        let mut decrypted = match Self::open_recovering(project_path, master_key) {
+            Ok(decrypted) => decrypted,
+            Err(e) => {
+                warn!("Failed to decrypt recording at {:?}: {e}", project_path);
+                return None;
+            }
+        };
+
+        let meta = RecordingMeta::load_for_project(decrypted.path()).ok()?;
+        let recording = analyze(decrypted.path(), &meta);
+
+        // Analysis renames fragments left as `.tmp` files and can mark the
+        // recording as failed.
+        if let Err(e) = decrypted.persist() {
+            warn!("Failed to seal recording at {:?}: {e}", project_path);
+        }
+
+        recording.map(|recording| IncompleteRecording {
+            master_key: Some(master_key.clone()),
+            ..recording.rebased(decrypted.path(), project_path)
+        })
+    }
+
+    /// Opens an encrypted recording that stopped before it could seal the
+    /// files it was still writing.
+    fn open_recovering(
+        project_path: &Path,
+        master_key: &MasterKey,
+    ) -> std::io::Result<DecryptedProject> {
+        let in_progress = Self::in_progress_files(project_path);
+        DecryptedProject::open_recovering(project_path, master_key, |relative| {
+            in_progress.contains(relative)
+        })
+    }
+
+    /// The files, relative to the project, that a crashed encrypted recording
+    /// may have left unsealed: those its last segment was still writing. That
+    /// is each track's newest fragment, and the audio files, which are sealed
+    /// once their segment stops.
+    fn in_progress_files(project_path: &Path) -> HashSet<PathBuf> {
+        let segments = Path::new("content").join("segments");
+        let Some(last_segment) = std::fs::read_dir(project_path.join(&segments))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let index = name
+                    .to_str()?
+                    .strip_prefix("segment-")?
+                    .parse::<u32>()
+                    .ok()?;
+                Some((index, name))
+            })
+            .max_by_key(|(index, _)| *index)
+            .map(|(_, name)| segments.join(name))
+        else {
+            return HashSet::new();
+        };
+
+        let mut files = HashSet::new();
+        for entry in std::fs::read_dir(project_path.join(&last_segment))
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let path = entry.path();
+            let relative = last_segment.join(entry.file_name());
+            if path.is_dir() {
+                if let Some(fragment) = Self::newest_fragment(&path) {
+                    files.insert(relative.join(fragment));
+                }
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "m4a" || extension == "ogg")
+            {
+                files.insert(relative);
+            }
+        }
+
+        files
+    }
+
+    /// The highest-numbered `segment_NNN.m4s` fragment in `dir`, whether or
+    /// not it is still a `.tmp` file.
+    fn newest_fragment(dir: &Path) -> Option<OsString> {
+        std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let index = name
+                    .to_str()?
+                    .strip_prefix("segment_")?
+                    .split('.')
+                    .next()?
+                    .parse::<u32>()
+                    .ok()?;
+                Some((index, name))
+            })
+            .max_by_key(|(index, _)| *index)
+            .map(|(_, name)| name)
+    }
+
     fn should_check_for_recovery(status: &StudioRecordingStatus) -> bool {
         match status {
             StudioRecordingStatus::InProgress | StudioRecordingStatus::NeedsRemux => true,
@@ -313,6 +515,7 @@ impl RecoveryManager {
             meta: meta.clone(),
             recoverable_segments,
             estimated_duration: total_duration,
+            master_key: None,
         })
     }
 
@@ -795,6 +998,10 @@ impl RecoveryManager {
             return Err(RecoveryError::NoRecoverableSegments);
         }
 
+        if let Some(master_key) = &recording.master_key {
+            return Self::finalize_encrypted(recording, master_key, purpose);
+        }
+
         for segment in &recording.recoverable_segments {
             let segment_dir = recording
                 .project_path
@@ -1064,6 +1271,26 @@ impl RecoveryManager {
         })
     }
 
+    /// Runs the same finalization in a decrypted copy of the project, then
+    /// seals the result into it. The project is left as it was on failure.
+    fn finalize_encrypted(
+        recording: &IncompleteRecording,
+        master_key: &MasterKey,
+        purpose: RecoveryPurpose,
+    ) -> Result<RecoveredRecording, RecoveryError> {
+        let mut decrypted = Self::open_recovering(&recording.project_path, master_key)?;
+        let working = IncompleteRecording {
+            master_key: None,
+            ..recording.rebased(&recording.project_path, decrypted.path())
+        };
+
+        let mut recovered = Self::finalize_with_purpose(&working, purpose)?;
+        decrypted.persist()?;
+
+        recovered.project_path = recording.project_path.clone();
+        Ok(recovered)
+    }
+
     pub fn finalize_to_progressive_mp4(
         fragmented_dir: &Path,
         output: &Path,
@@ -1404,6 +1631,17 @@ impl RecoveryManager {
                             None
                         }
                     },
+                    additional_mics: original_segment
+                        .map(|s| {
+                            s.additional_mics
+                                .iter()
+                                .filter(|mic| {
+                                    mic.audio.path.to_path(&recording.project_path).exists()
+                                })
+                                .cloned()
+                                .collect()
+                        })
+                        .unwrap_or_default(),
                     system_audio: {
                         let file_size = std::fs::metadata(&system_audio_path)
                             .map(|m| m.len())
@@ -1444,6 +1682,18 @@ impl RecoveryManager {
                         None
                     },
                     display_notch: original_segment.and_then(|s| s.display_notch),
+                    additional_videos: original_segment
+                        .map(|s| {
+                            s.additional_videos
+                                .iter()
+                                .filter(|video| {
+                                    video.video.path.to_path(&recording.project_path).exists()
+                                })
+                                .cloned()
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    imported: None,
                 }
             })
             .collect();
@@ -0,0 +1,421 @@
+//! Rolling "replay buffer" recordings.
+//!
+//! A replay buffer is an instant recording whose segmented output is pruned while it records, so
+//! only the last few minutes stay on disk. [`save_replay`] turns the tail of that ring into a normal
+//! instant `.cap` project, reusing the recovery concatenation code to build `content/output.mp4`.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use cap_enc_ffmpeg::{
+    remux::{self, RemuxError},
+    segmented_stream::PrunedSegments,
+};
+use tokio_util::sync::{CancellationToken, DropGuard};
+use tracing::{debug, warn};
+
+use crate::{
+    fragmentation::atomic_write_json,
+    recovery::{RecoveryError, RecoveryManager},
+};
+
+/// Directory inside a replay project that the ring of segments is recorded into.
+pub const BUFFER_DIR: &str = "replay";
+
+/// How much footage a replay buffer keeps when no length is given.
+pub const DEFAULT_LENGTH: Duration = Duration::from_secs(5 * 60);
+
+const INIT_SEGMENT: &str = "init.mp4";
+const MANIFEST: &str = "manifest.json";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid replay manifest {0}: {1}")]
+    Manifest(String, serde_json::Error),
+    #[error("The replay buffer has no complete segments yet")]
+    Empty,
+    #[error("{0}")]
+    Recovery(#[from] RecoveryError),
+    #[error("Failed to rebase replay timestamps: {0}")]
+    Rebase(RemuxError),
+}
+
+/// Deletes segments that have fallen out of the replay window while the recording runs. The pruning
+/// thread stops when this is dropped.
+pub struct ReplayBuffer {
+    _stop: DropGuard,
+}
+
+impl ReplayBuffer {
+    /// Keeps `length` worth of complete segments in the instant recording at `buffer_dir`.
+    pub fn spawn(buffer_dir: PathBuf, length: Duration) -> Self {
+        let stop_token = CancellationToken::new();
+
+        let thread_stop = stop_token.child_token();
+        std::thread::spawn(move || {
+            while !thread_stop.is_cancelled() {
+                std::thread::sleep(PRUNE_INTERVAL);
+
+                match prune(&buffer_dir, length) {
+                    Ok(0) => {}
+                    Ok(removed) => debug!("Pruned {removed} replay buffer segments"),
+                    Err(e) => warn!("Failed to prune replay buffer: {e}"),
+                }
+            }
+        });
+
+        Self {
+            _stop: stop_token.drop_guard(),
+        }
+    }
+}
+
+/// A replay written out by [`save_replay`].
+#[derive(Debug, Clone)]
+pub struct SavedReplay {
+    pub project_path: PathBuf,
+    pub duration: Duration,
+    pub fps: Option<u32>,
+}
+
+/// Deletes the video segments older than the newest `length` of footage, and the audio segments
+/// that end before them. Returns how many files were removed.
+///
+/// Each track records what it pruned in a sidecar next to its manifest, which the muxers read to
+/// drop those entries, so the manifest stays as short as the window.
+pub fn prune(buffer_dir: &Path, length: Duration) -> Result<usize, ReplayError> {
+    let display_dir = buffer_dir.join("content/display");
+    let audio_dir = buffer_dir.join("content/audio");
+
+    let Some(video) = read_timeline(&display_dir)? else {
+        return Ok(0);
+    };
+    let retained = select_last(&video, Some(length), |name| display_dir.join(name).exists());
+    let Some(window_start) = video.get(retained.start).map(|segment| segment.start) else {
+        return Ok(0);
+    };
+
+    let mut removed = remove_segments(&display_dir, &video[..retained.start])?;
+
+    if let Some(audio) = read_timeline(&audio_dir)? {
+        let stale = audio
+            .iter()
+            .take_while(|segment| segment.end() <= window_start)
+            .count();
+        removed += remove_segments(&audio_dir, &audio[..stale])?;
+    }
+
+    Ok(removed)
+}
+
+/// Writes the newest `last` of the replay buffer at `buffer_dir` (all of it when `None`) to an
+/// instant project at `project_path`, producing `content/output.mp4`.
+pub fn save_replay(
+    buffer_dir: &Path,
+    last: Option<Duration>,
+    project_path: &Path,
+) -> Result<SavedReplay, ReplayError> {
+    let display_dir = buffer_dir.join("content/display");
+    let audio_dir = buffer_dir.join("content/audio");
+
+    let video = read_timeline(&display_dir)?.unwrap_or_default();
+    let selected = select_last(&video, last, |name| display_dir.join(name).exists());
+    let video = &video[selected];
+    let (Some(first), Some(newest)) = (video.first(), video.last()) else {
+        return Err(ReplayError::Empty);
+    };
+    let (start, end) = (first.start, newest.end());
+
+    let content_dir = project_path.join("content");
+    let staged_display = content_dir.join("display");
+    let staged_audio = content_dir.join("audio");
+
+    stage_segments(&display_dir, &staged_display, video)?;
+    if let Some(audio) = read_timeline(&audio_dir)? {
+        let audio: Vec<Segment> = audio
+            .into_iter()
+            .filter(|segment| segment.end() > start && segment.start < end)
+            .collect();
+        if !audio.is_empty() {
+            stage_segments(&audio_dir, &staged_audio, &audio)?;
+        }
+    }
+
+    let output = content_dir.join("output.mp4");
+    let joined = content_dir.join("replay.mp4");
+    let result = RecoveryManager::finalize_instant_output(&staged_display, &staged_audio, &joined)
+        .map_err(ReplayError::from)
+        .and_then(|_| remux::rebase_to_video_start(&joined, &output).map_err(ReplayError::Rebase));
+
+    let _ = std::fs::remove_file(&joined);
+    for dir in [&staged_display, &staged_audio] {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    result?;
+
+    Ok(SavedReplay {
+        project_path: project_path.to_path_buf(),
+        duration: end - start,
+        fps: remux::get_video_fps(&output),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    path: String,
+    index: u32,
+    start: Duration,
+    duration: Duration,
+    entry: serde_json::Value,
+}
+
+impl Segment {
+    fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+}
+
+/// Reads the complete segments listed in `dir`'s manifest, placed on one timeline by summing their
+/// durations. The timeline starts where the pruned segments ended, so the remaining ones stay
+/// aligned with the other track.
+fn read_timeline(dir: &Path) -> Result<Option<Vec<Segment>>, ReplayError> {
+    let manifest_path = dir.join(MANIFEST);
+    let content = match std::fs::read_to_string(&manifest_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let manifest: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| manifest_error(&manifest_path, e))?;
+
+    Ok(Some(timeline(&manifest, PrunedSegments::read(dir))))
+}
+
+fn timeline(manifest: &serde_json::Value, pruned: Option<PrunedSegments>) -> Vec<Segment> {
+    let mut start = pruned
+        .map(|pruned| pruned.end)
+        .filter(|end| end.is_finite() && *end >= 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or_default();
+    manifest
+        .get("segments")
+        .and_then(|segments| segments.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.get("is_complete").and_then(|c| c.as_bool()) == Some(true))
+        .filter_map(|entry| {
+            let path = entry.get("path")?.as_str()?.to_string();
+            let index = entry
+                .get("index")
+                .and_then(|i| i.as_u64())
+                .and_then(|i| u32::try_from(i).ok())?;
+            if pruned.is_some_and(|pruned| pruned.contains(index)) {
+                return None;
+            }
+            let duration = entry
+                .get("duration")
+                .and_then(|d| d.as_f64())
+                .filter(|d| d.is_finite() && *d >= 0.0)
+                .map(Duration::from_secs_f64)
+                .unwrap_or_default();
+            let segment = Segment {
+                path,
+                index,
+                start,
+                duration,
+                entry: entry.clone(),
+            };
+            start += duration;
+            Some(segment)
+        })
+        .collect()
+}
+
+/// The newest run of segments still on disk that covers at least `last`, or every one when `None`.
+fn select_last(
+    segments: &[Segment],
+    last: Option<Duration>,
+    exists: impl Fn(&str) -> bool,
+) -> std::ops::Range<usize> {
+    let mut first = segments.len();
+    let mut covered = Duration::ZERO;
+    while first > 0 && last.is_none_or(|last| covered < last) && exists(&segments[first - 1].path) {
+        first -= 1;
+        covered += segments[first].duration;
+    }
+    first..segments.len()
+}
+
+fn manifest_error(path: &Path, error: serde_json::Error) -> ReplayError {
+    ReplayError::Manifest(path.display().to_string(), error)
+}
+
+/// Records `segments` as pruned in `dir`, then removes them. The sidecar is written first so a
+/// manifest written in between never lists a segment that is already gone.
+fn remove_segments(dir: &Path, segments: &[Segment]) -> Result<usize, ReplayError> {
+    let Some(newest) = segments.last() else {
+        return Ok(0);
+    };
+    PrunedSegments {
+        last_index: newest.index,
+        end: newest.end().as_secs_f64(),
+    }
+    .write(dir)?;
+
+    let mut removed = 0;
+    for segment in segments {
+        match std::fs::remove_file(dir.join(&segment.path)) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove replay segment {}: {e}", segment.path),
+        }
+    }
+    Ok(removed)
+}
+
+/// Links (or copies, across filesystems) the init segment and `segments` from `source` into
+/// `target`, with a manifest listing only those segments. Linking first means the pruner can delete
+/// the originals while the replay is still being assembled.
+fn stage_segments(source: &Path, target: &Path, segments: &[Segment]) -> Result<(), ReplayError> {
+    std::fs::create_dir_all(target)?;
+    link_or_copy(&source.join(INIT_SEGMENT), &target.join(INIT_SEGMENT))?;
+
+    let mut entries = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match link_or_copy(&source.join(&segment.path), &target.join(&segment.path)) {
+            Ok(()) => entries.push(segment.entry.clone()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                warn!(
+                    "Replay segment {} was pruned before it could be saved",
+                    segment.path
+                );
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(source.join(MANIFEST))?)
+            .map_err(|e| manifest_error(&source.join(MANIFEST), e))?;
+    manifest["segments"] = serde_json::Value::Array(entries);
+    manifest["is_complete"] = serde_json::Value::Bool(true);
+    atomic_write_json(&target.join(MANIFEST), &manifest)?;
+
+    Ok(())
+}
+
+fn link_or_copy(source: &Path, target: &Path) -> std::io::Result<()> {
+    if target.exists() {
+        std::fs::remove_file(target)?;
+    }
+    std::fs::hard_link(source, target).or_else(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            return Err(e);
+        }
+        std::fs::copy(source, target).map(|_| ())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(durations: &[f64]) -> serde_json::Value {
+        let mut segments: Vec<serde_json::Value> = durations
+            .iter()
+            .enumerate()
+            .map(|(index, duration)| {
+                serde_json::json!({
+                    "path": format!("segment_{index:03}.m4s"),
+                    "index": index,
+                    "duration": duration,
+                    "is_complete": true,
+                })
+            })
+            .collect();
+        segments.push(serde_json::json!({
+            "path": format!("segment_{:03}.m4s", durations.len()),
+            "index": durations.len(),
+            "duration": 0.0,
+            "is_complete": false,
+        }));
+        serde_json::json!({ "type": "m4s_segments", "segments": segments })
+    }
+
+    #[test]
+    fn timeline_places_complete_segments_end_to_end() {
+        let segments = timeline(&manifest(&[2.0, 2.0, 1.5]), None);
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[2].start, Duration::from_secs(4));
+        assert_eq!(segments[2].end(), Duration::from_secs_f64(5.5));
+    }
+
+    #[test]
+    fn timeline_resumes_after_pruned_segments() {
+        let pruned = PrunedSegments {
+            last_index: 1,
+            end: 4.0,
+        };
+        let segments = timeline(&manifest(&[2.0, 2.0, 1.5]), Some(pruned));
+
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].index, 2);
+        assert_eq!(segments[0].start, Duration::from_secs(4));
+        assert_eq!(segments[0].end(), Duration::from_secs_f64(5.5));
+    }
+
+    #[test]
+    fn prune_records_removed_segments_so_later_ones_keep_their_place() {
+        let buffer = tempfile::tempdir().unwrap();
+        let display_dir = buffer.path().join("content/display");
+        std::fs::create_dir_all(&display_dir).unwrap();
+        let manifest = manifest(&[2.0; 5]);
+        std::fs::write(display_dir.join(MANIFEST), manifest.to_string()).unwrap();
+        for index in 0..=5 {
+            std::fs::write(display_dir.join(format!("segment_{index:03}.m4s")), b"m4s").unwrap();
+        }
+
+        assert_eq!(prune(buffer.path(), Duration::from_secs(3)).unwrap(), 3);
+
+        assert!(!display_dir.join("segment_002.m4s").exists());
+        assert!(display_dir.join("segment_003.m4s").exists());
+        assert_eq!(
+            PrunedSegments::read(&display_dir),
+            Some(PrunedSegments {
+                last_index: 2,
+                end: 6.0,
+            })
+        );
+
+        let video = read_timeline(&display_dir).unwrap().unwrap();
+        assert_eq!(video.len(), 2);
+        assert_eq!(video[0].start, Duration::from_secs(6));
+
+        assert_eq!(prune(buffer.path(), Duration::from_secs(3)).unwrap(), 0);
+    }
+
+    #[test]
+    fn select_last_covers_the_window_with_segments_on_disk() {
+        let segments = timeline(&manifest(&[2.0; 10]), None);
+
+        let range = select_last(&segments, Some(Duration::from_secs(5)), |_| true);
+        assert_eq!(range, 7..10);
+
+        let range = select_last(&segments, None, |path| path != "segment_003.m4s");
+        assert_eq!(range, 4..10);
+
+        let range = select_last(&segments, Some(Duration::from_secs(60)), |_| true);
+        assert_eq!(range, 0..10);
+
+        assert_eq!(
+            select_last(&[], Some(Duration::from_secs(5)), |_| true),
+            0..0
+        );
+    }
+}
@@ -180,7 +180,7 @@ fn apply_window_rounded_corners(image: RgbImage, target: &ScreenCaptureTarget) -
 
 fn window_corner_radius_px(target: &ScreenCaptureTarget) -> f32 {
     match target {
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let scale = scap_targets::Window::from_id(id)
                 .and_then(|w| w.display())
                 .and_then(|d| {
@@ -260,7 +260,7 @@ fn try_fast_capture(target: &ScreenCaptureTarget) -> Option<DynamicImage> {
             }
             unsafe { core_graphics::image::CGImage::from_ptr(image) }
         }
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             use core_graphics::display::CGRectNull;
 
             let window = scap_targets::Window::from_id(id)?;
@@ -349,7 +349,10 @@ fn try_fast_capture(target: &ScreenCaptureTarget) -> Option<DynamicImage> {
     let cf_data: CFData = cg_image.data();
     let data = cf_data.bytes();
 
-    let is_window = matches!(target, ScreenCaptureTarget::Window { .. });
+    let is_window = matches!(
+        target,
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. }
+    );
 
     let result = if is_window {
         let rgba = rgba_from_raw(data, width, height, bytes_per_row, ChannelOrder::Bgra)?;
@@ -702,7 +705,7 @@ fn capture_screenshot_fallback(target: ScreenCaptureTarget) -> anyhow::Result<Rg
             debug!("Windows GDI display capture");
             Ok(image)
         }
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let window = scap_targets::Window::from_id(&id).ok_or_else(unsupported_error)?;
             let bounds = window
                 .raw_handle()
@@ -762,14 +765,17 @@ fn try_fast_capture(target: &ScreenCaptureTarget) -> Option<DynamicImage> {
     }
 
     let start = std::time::Instant::now();
-    let is_window = matches!(target, ScreenCaptureTarget::Window { .. });
+    let is_window = matches!(
+        target,
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. }
+    );
 
     let item = match target.clone() {
         ScreenCaptureTarget::Display { id } => {
             let display = scap_targets::Display::from_id(&id)?;
             display.raw_handle().try_as_capture_item().ok()?
         }
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let window = scap_targets::Window::from_id(&id)?;
             window.raw_handle().try_as_capture_item().ok()?
         }
@@ -874,7 +880,7 @@ pub async fn capture_screenshot(target: ScreenCaptureTarget) -> anyhow::Result<D
                     )
                     .ok_or_else(|| anyhow!("Failed to get content filter"))?
             }
-            ScreenCaptureTarget::Window { id } => {
+            ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
                 let window = scap_targets::Window::from_id(&id)
                     .ok_or_else(|| anyhow!("Window not found"))?;
 
@@ -911,10 +917,12 @@ pub async fn capture_screenshot(target: ScreenCaptureTarget) -> anyhow::Result<D
                 .and_then(|d| d.physical_size())
                 .map(|s| s.width())
                 .unwrap_or(1920.0),
-            ScreenCaptureTarget::Window { id } => scap_targets::Window::from_id(&id)
-                .and_then(|w| w.physical_size())
-                .map(|s| s.width())
-                .unwrap_or(1920.0),
+            ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
+                scap_targets::Window::from_id(&id)
+                    .and_then(|w| w.physical_size())
+                    .map(|s| s.width())
+                    .unwrap_or(1920.0)
+            }
             ScreenCaptureTarget::Area { screen, .. } => scap_targets::Display::from_id(&screen)
                 .and_then(|d| d.physical_size())
                 .map(|s| s.width())
@@ -929,10 +937,12 @@ pub async fn capture_screenshot(target: ScreenCaptureTarget) -> anyhow::Result<D
                 .and_then(|d| d.physical_size())
                 .map(|s| s.height())
                 .unwrap_or(1080.0),
-            ScreenCaptureTarget::Window { id } => scap_targets::Window::from_id(&id)
-                .and_then(|w| w.physical_size())
-                .map(|s| s.height())
-                .unwrap_or(1080.0),
+            ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
+                scap_targets::Window::from_id(&id)
+                    .and_then(|w| w.physical_size())
+                    .map(|s| s.height())
+                    .unwrap_or(1080.0)
+            }
             ScreenCaptureTarget::Area { screen, .. } => scap_targets::Display::from_id(&screen)
                 .and_then(|d| d.physical_size())
                 .map(|s| s.height())
@@ -990,7 +1000,7 @@ pub async fn capture_screenshot(target: ScreenCaptureTarget) -> anyhow::Result<D
                     .try_as_capture_item()
                     .map_err(|e| anyhow!("Failed to get capture item: {e:?}"))?
             }
-            ScreenCaptureTarget::Window { id } => {
+            ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
                 let window = scap_targets::Window::from_id(&id)
                     .ok_or_else(|| anyhow!("Window not found"))?;
                 window
@@ -1101,7 +1111,10 @@ pub async fn capture_screenshot(target: ScreenCaptureTarget) -> anyhow::Result<D
 }
 
 fn finalize_screenshot(image: RgbImage, target: &ScreenCaptureTarget) -> DynamicImage {
-    if matches!(target, ScreenCaptureTarget::Window { .. }) {
+    if matches!(
+        target,
+        ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. }
+    ) {
         DynamicImage::ImageRgba8(apply_window_rounded_corners(image, target))
     } else {
         DynamicImage::ImageRgb8(image)
@@ -1229,6 +1242,7 @@ fn capture_screenshot_x11_blocking(target: &ScreenCaptureTarget) -> anyhow::Resu
         height,
         fps: 1,
         show_cursor: false,
+        follow_window: None,
     };
     let mut grabber = X11Grabber::new(&config)?;
     let frame = grabber.grab()?;
@@ -1260,7 +1274,7 @@ fn linux_capture_geometry(
             )?;
             Ok((display_name, x, y, width, height))
         }
-        ScreenCaptureTarget::Window { id } => {
+        ScreenCaptureTarget::Window { id } | ScreenCaptureTarget::FollowWindow { id } => {
             let window =
                 scap_targets::Window::from_id(id).ok_or_else(|| anyhow!("Window not found"))?;
             let display = window
@@ -251,6 +251,7 @@ impl MixerBufferingTracker {
                 {
                     self.emitted_for_current_stall = true;
                     self.total_stall_events += 1;
+                    crate::metrics::metrics().record_mixer_stall();
                     self.last_stall_emit_at = Some(now);
                     warn!(
                         source = source,
@@ -0,0 +1,606 @@
+use crate::output_pipeline::{AudioFrame, AudioSource, FFmpegVideoFrame, SetupCtx, VideoSource};
+use anyhow::{Context, anyhow};
+use cap_media_info::{AudioInfo, VideoInfo};
+use cap_timestamp::Timestamp;
+use futures::{FutureExt, SinkExt, channel::mpsc, future::BoxFuture};
+use std::{
+    ffi::CString,
+    path::{Path, PathBuf},
+    ptr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use tracing::*;
+
+const VIDEO_CHANNEL_CAPACITY: usize = 8;
+const AUDIO_CHANNEL_CAPACITY: usize = 64;
+/// How long decoding waits for the audio pipeline to attach after the video
+/// pipeline, so the first audio frames aren't dropped.
+const AUDIO_ATTACH_GRACE: Duration = Duration::from_secs(1);
+const ATTACH_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const DEFAULT_FPS: u32 = 30;
+
+/// Where a [`MediaInputFeed`] reads its frames from. A path of `-` reads
+/// from stdin.
+#[derive(Debug, Clone)]
+pub enum MediaInput {
+    /// Any container ffmpeg can demux, including named pipes.
+    File(PathBuf),
+    /// Numbered images matching a printf-style pattern like `frame-%05d.png`.
+    ImageSequence { pattern: PathBuf, fps: u32 },
+    /// Headerless frames of a fixed size and pixel format, e.g. piped from a
+    /// headless browser.
+    RawVideo {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+        pixel_format: String,
+        fps: u32,
+    },
+}
+
+impl MediaInput {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::File(path) => path,
+            Self::ImageSequence { pattern, .. } => pattern,
+            Self::RawVideo { path, .. } => path,
+        }
+    }
+
+    fn url(&self) -> String {
+        let path = self.path();
+        if path == Path::new("-") {
+            "pipe:0".to_string()
+        } else {
+            path.to_string_lossy().into_owned()
+        }
+    }
+
+    fn open(&self) -> anyhow::Result<ffmpeg::format::context::Input> {
+        let url = self.url();
+
+        match self {
+            Self::File(_) => {
+                ffmpeg::format::input(&url).with_context(|| format!("Failed to open '{url}'"))
+            }
+            Self::ImageSequence { fps, .. } => {
+                let mut options = ffmpeg::Dictionary::new();
+                options.set("framerate", &fps.to_string());
+                open_input_with_format(&url, "image2", options)
+            }
+            Self::RawVideo {
+                width,
+                height,
+                pixel_format,
+                fps,
+                ..
+            } => {
+                let mut options = ffmpeg::Dictionary::new();
+                options.set("video_size", &format!("{width}x{height}"));
+                options.set("pixel_format", pixel_format);
+                options.set("framerate", &fps.to_string());
+                open_input_with_format(&url, "rawvideo", options)
+            }
+        }
+    }
+}
+
+fn open_input_with_format(
+    url: &str,
+    format_name: &str,
+    options: ffmpeg::Dictionary,
+) -> anyhow::Result<ffmpeg::format::context::Input> {
+    let format_cstr = CString::new(format_name)?;
+    let url_cstr = CString::new(url).map_err(|_| anyhow!("Invalid input path '{url}'"))?;
+
+    unsafe {
+        let input_format = ffmpeg::ffi::av_find_input_format(format_cstr.as_ptr());
+        if input_format.is_null() {
+            return Err(anyhow!("ffmpeg has no '{format_name}' demuxer"));
+        }
+
+        let mut ps = ptr::null_mut();
+        let mut opts = options.disown();
+
+        let ret =
+            ffmpeg::ffi::avformat_open_input(&mut ps, url_cstr.as_ptr(), input_format, &mut opts);
+
+        ffmpeg::Dictionary::own(opts);
+
+        if ret < 0 {
+            return Err(anyhow!(
+                "Failed to open '{url}' as {format_name}: {}",
+                ffmpeg::Error::from(ret)
+            ));
+        }
+
+        let ret = ffmpeg::ffi::avformat_find_stream_info(ps, ptr::null_mut());
+        if ret < 0 {
+            ffmpeg::ffi::avformat_close_input(&mut ps);
+            return Err(anyhow!(
+                "Failed to read stream info from '{url}': {}",
+                ffmpeg::Error::from(ret)
+            ));
+        }
+
+        Ok(ffmpeg::format::context::Input::wrap(ps))
+    }
+}
+
+struct Decoding {
+    input: ffmpeg::format::context::Input,
+    video_index: usize,
+    video: ffmpeg::decoder::Video,
+    video_time_base: ffmpeg::Rational,
+    audio: Option<(usize, ffmpeg::decoder::Audio, ffmpeg::Rational)>,
+}
+
+struct Shared {
+    video_tx: Mutex<Option<flume::Sender<FFmpegVideoFrame>>>,
+    audio_tx: Mutex<Option<flume::Sender<AudioFrame>>>,
+    anchor: Mutex<Option<Instant>>,
+    stop: AtomicBool,
+    finished: watch::Sender<bool>,
+}
+
+struct Handle {
+    description: String,
+    video_info: VideoInfo,
+    audio_info: Option<AudioInfo>,
+    decoding: Mutex<Option<Decoding>>,
+    shared: Arc<Shared>,
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A media input decoded once and fed to recording pipelines in real time.
+///
+/// The input is opened and probed up front since stdin and pipes can't be
+/// reopened. Decoding starts when the first video pipeline attaches and is
+/// paced against that pipeline's [`MasterClock`](cap_timestamp::MasterClock),
+/// so each frame is delivered when it would have been captured. Pipelines
+/// built for later segments take over the feed; frames decoded while nothing
+/// is attached, e.g. during a pause, are dropped like a live capture would.
+#[derive(Clone)]
+pub struct MediaInputFeed {
+    inner: Arc<Handle>,
+}
+
+impl MediaInputFeed {
+    pub fn open(input: MediaInput) -> anyhow::Result<Self> {
+        let input_ctx = input.open()?;
+
+        let video_stream = input_ctx
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .ok_or_else(|| anyhow!("'{}' has no video stream", input.path().display()))?;
+        let video_index = video_stream.index();
+        let video_time_base = video_stream.time_base();
+        let video = ffmpeg::codec::context::Context::from_parameters(video_stream.parameters())?
+            .decoder()
+            .video()
+            .context("Failed to open video decoder")?;
+
+        let rate = video_stream.avg_frame_rate();
+        let rate = if rate.numerator() > 0 && rate.denominator() > 0 {
+            rate
+        } else {
+            video_stream.rate()
+        };
+        let fps = if rate.numerator() > 0 && rate.denominator() > 0 {
+            (f64::from(rate).round() as u32).max(1)
+        } else {
+            DEFAULT_FPS
+        };
+
+        let video_info =
+            VideoInfo::from_raw_ffmpeg(video.format(), video.width(), video.height(), fps);
+
+        let audio = match input_ctx.streams().best(ffmpeg::media::Type::Audio) {
+            Some(stream) => {
+                let decoder =
+                    ffmpeg::codec::context::Context::from_parameters(stream.parameters())?
+                        .decoder()
+                        .audio()
+                        .context("Failed to open audio decoder")?;
+                Some((stream.index(), decoder, stream.time_base()))
+            }
+            None => None,
+        };
+
+        let audio_info = match &audio {
+            Some((_, decoder, _)) => Some(AudioInfo::from_decoder(decoder)?),
+            None => None,
+        };
+
+        info!(
+            input = %input.path().display(),
+            width = video_info.width,
+            height = video_info.height,
+            fps,
+            has_audio = audio_info.is_some(),
+            "Opened media input"
+        );
+
+        let (finished, _) = watch::channel(false);
+
+        Ok(Self {
+            inner: Arc::new(Handle {
+                description: input.path().display().to_string(),
+                video_info,
+                audio_info,
+                decoding: Mutex::new(Some(Decoding {
+                    input: input_ctx,
+                    video_index,
+                    video,
+                    video_time_base,
+                    audio,
+                })),
+                shared: Arc::new(Shared {
+                    video_tx: Mutex::new(None),
+                    audio_tx: Mutex::new(None),
+                    anchor: Mutex::new(None),
+                    stop: AtomicBool::new(false),
+                    finished,
+                }),
+            }),
+        })
+    }
+
+    pub fn video_info(&self) -> VideoInfo {
+        self.inner.video_info
+    }
+
+    pub fn audio_info(&self) -> Option<AudioInfo> {
+        self.inner.audio_info
+    }
+
+    pub fn is_finished(&self) -> bool {
+        *self.inner.shared.finished.borrow()
+    }
+
+    /// Resolves once the whole input has been decoded and delivered.
+    pub async fn wait_finished(&self) {
+        let mut rx = self.inner.shared.finished.subscribe();
+        let _ = rx.wait_for(|finished| *finished).await;
+    }
+
+    fn attach_video(&self, anchor: Instant) -> flume::Receiver<FFmpegVideoFrame> {
+        let (tx, rx) = flume::bounded(VIDEO_CHANNEL_CAPACITY);
+        self.inner
+            .shared
+            .anchor
+            .lock()
+            .unwrap()
+            .get_or_insert(anchor);
+        *self.inner.shared.video_tx.lock().unwrap() = Some(tx);
+        self.start_decoding();
+        rx
+    }
+
+    fn attach_audio(&self) -> flume::Receiver<AudioFrame> {
+        let (tx, rx) = flume::bounded(AUDIO_CHANNEL_CAPACITY);
+        *self.inner.shared.audio_tx.lock().unwrap() = Some(tx);
+        rx
+    }
+
+    fn detach_video(&self) {
+        self.inner.shared.video_tx.lock().unwrap().take();
+    }
+
+    fn detach_audio(&self) {
+        self.inner.shared.audio_tx.lock().unwrap().take();
+    }
+
+    fn start_decoding(&self) {
+        let Some(decoding) = self.inner.decoding.lock().unwrap().take() else {
+            return;
+        };
+
+        let shared = self.inner.shared.clone();
+        let description = self.inner.description.clone();
+        let fps = self.inner.video_info.fps();
+
+        let spawned = std::thread::Builder::new()
+            .name("media-input".to_string())
+            .spawn(move || {
+                if let Err(e) = run_decoder(decoding, &shared, fps) {
+                    error!(input = %description, "Media input failed: {e:#}");
+                } else {
+                    info!(input = %description, "Media input reached end of stream");
+                }
+
+                shared.video_tx.lock().unwrap().take();
+                shared.audio_tx.lock().unwrap().take();
+                shared.finished.send_replace(true);
+            });
+
+        if let Err(e) = spawned {
+            error!("Failed to spawn media input thread: {e}");
+            self.inner.shared.finished.send_replace(true);
+        }
+    }
+}
+
+/// Position of a decoded track in seconds. Uses frame timestamps when the
+/// demuxer provides them, and counts frames or samples otherwise.
+#[derive(Default)]
+struct TrackPosition {
+    next: f64,
+}
+
+impl TrackPosition {
+    fn advance(&mut self, pts: Option<i64>, time_base: ffmpeg::Rational, length: f64) -> f64 {
+        let position = match pts {
+            Some(pts) if time_base.denominator() != 0 => {
+                pts as f64 * f64::from(time_base.numerator()) / f64::from(time_base.denominator())
+            }
+            _ => self.next,
+        };
+        self.next = position + length;
+        position
+    }
+}
+
+struct Pacer<'a> {
+    shared: &'a Shared,
+    anchor: Instant,
+    origin: Option<f64>,
+}
+
+impl Pacer<'_> {
+    /// Sleeps until `position` is due and returns its capture timestamp.
+    fn wait(&mut self, position: f64) -> Option<Timestamp> {
+        let origin = *self.origin.get_or_insert(position);
+        let offset = Duration::from_secs_f64((position - origin).max(0.0));
+        let due = self.anchor + offset;
+
+        loop {
+            if self.shared.stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            let now = Instant::now();
+            if due <= now {
+                return Some(Timestamp::Instant(due));
+            }
+            std::thread::sleep((due - now).min(Duration::from_millis(100)));
+        }
+    }
+}
+
+fn run_decoder(mut decoding: Decoding, shared: &Shared, fps: u32) -> anyhow::Result<()> {
+    let wait_started = Instant::now();
+    while decoding.audio.is_some()
+        && shared.audio_tx.lock().unwrap().is_none()
+        && wait_started.elapsed() < AUDIO_ATTACH_GRACE
+    {
+        if shared.stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        std::thread::sleep(ATTACH_POLL_INTERVAL);
+    }
+
+    let anchor = shared.anchor.lock().unwrap().unwrap_or_else(Instant::now);
+    let mut pacer = Pacer {
+        shared,
+        anchor,
+        origin: None,
+    };
+    let frame_length = 1.0 / f64::from(fps.max(1));
+    let mut video_position = TrackPosition::default();
+    let mut audio_position = TrackPosition::default();
+
+    let mut emit_video = |decoder: &mut ffmpeg::decoder::Video, pacer: &mut Pacer| -> bool {
+        let mut frame = ffmpeg::frame::Video::empty();
+        while decoder.receive_frame(&mut frame).is_ok() {
+            let position =
+                video_position.advance(frame.timestamp(), decoding.video_time_base, frame_length);
+            let Some(timestamp) = pacer.wait(position) else {
+                return false;
+            };
+
+            let mut video_tx = shared.video_tx.lock().unwrap();
+            if let Some(tx) = video_tx.as_ref()
+                && let Err(flume::TrySendError::Disconnected(_)) = tx.try_send(FFmpegVideoFrame {
+                    inner: std::mem::replace(&mut frame, ffmpeg::frame::Video::empty()),
+                    timestamp,
+                })
+            {
+                video_tx.take();
+            }
+        }
+        true
+    };
+
+    let mut emit_audio = |decoder: &mut ffmpeg::decoder::Audio,
+                          time_base: ffmpeg::Rational,
+                          pacer: &mut Pacer|
+     -> bool {
+        let mut frame = ffmpeg::frame::Audio::empty();
+        while decoder.receive_frame(&mut frame).is_ok() {
+            let length = frame.samples() as f64 / f64::from(frame.rate().max(1));
+            let position = audio_position.advance(frame.timestamp(), time_base, length);
+            let Some(timestamp) = pacer.wait(position) else {
+                return false;
+            };
+
+            let mut audio_tx = shared.audio_tx.lock().unwrap();
+            if let Some(tx) = audio_tx.as_ref()
+                && let Err(flume::TrySendError::Disconnected(_)) = tx.try_send(AudioFrame::new(
+                    std::mem::replace(&mut frame, ffmpeg::frame::Audio::empty()),
+                    timestamp,
+                ))
+            {
+                audio_tx.take();
+            }
+        }
+        true
+    };
+
+    for (stream, packet) in decoding.input.packets() {
+        let index = stream.index();
+
+        if index == decoding.video_index {
+            decoding
+                .video
+                .send_packet(&packet)
+                .context("Failed to decode video packet")?;
+            if !emit_video(&mut decoding.video, &mut pacer) {
+                return Ok(());
+            }
+        } else if let Some((audio_index, decoder, time_base)) = &mut decoding.audio
+            && index == *audio_index
+        {
+            decoder
+                .send_packet(&packet)
+                .context("Failed to decode audio packet")?;
+            if !emit_audio(decoder, *time_base, &mut pacer) {
+                return Ok(());
+            }
+        }
+    }
+
+    decoding.video.send_eof()?;
+    emit_video(&mut decoding.video, &mut pacer);
+    if let Some((_, decoder, time_base)) = &mut decoding.audio {
+        decoder.send_eof()?;
+        emit_audio(decoder, *time_base, &mut pacer);
+    }
+
+    Ok(())
+}
+
+pub struct MediaVideoSourceConfig {
+    feed: MediaInputFeed,
+}
+
+impl MediaVideoSourceConfig {
+    pub fn new(feed: MediaInputFeed) -> Self {
+        Self { feed }
+    }
+}
+
+/// Video from a [`MediaInputFeed`].
+pub struct MediaVideoSource {
+    feed: MediaInputFeed,
+}
+
+impl VideoSource for MediaVideoSource {
+    type Config = MediaVideoSourceConfig;
+    type Frame = FFmpegVideoFrame;
+
+    async fn setup(
+        config: Self::Config,
+        mut video_tx: mpsc::Sender<Self::Frame>,
+        ctx: &mut SetupCtx,
+    ) -> anyhow::Result<Self> {
+        let rx = config.feed.attach_video(ctx.master_clock().start_instant());
+
+        tokio::spawn(async move {
+            while let Ok(frame) = rx.recv_async().await {
+                let _ = video_tx.send(frame).await;
+            }
+        });
+
+        Ok(Self { feed: config.feed })
+    }
+
+    fn video_info(&self) -> VideoInfo {
+        self.feed.video_info()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, anyhow::Result<()>> {
+        self.feed.detach_video();
+        async { Ok(()) }.boxed()
+    }
+}
+
+pub struct MediaAudioSourceConfig {
+    feed: MediaInputFeed,
+}
+
+impl MediaAudioSourceConfig {
+    pub fn new(feed: MediaInputFeed) -> Self {
+        Self { feed }
+    }
+}
+
+/// Audio from a [`MediaInputFeed`]. Only valid for feeds with an audio track.
+pub struct MediaAudioSource {
+    feed: MediaInputFeed,
+    info: AudioInfo,
+}
+
+impl AudioSource for MediaAudioSource {
+    type Config = MediaAudioSourceConfig;
+
+    fn setup(
+        config: Self::Config,
+        mut tx: mpsc::Sender<AudioFrame>,
+        _: &mut SetupCtx,
+    ) -> impl Future<Output = anyhow::Result<Self>> + 'static {
+        let feed = config.feed;
+
+        async move {
+            let info = feed
+                .audio_info()
+                .ok_or_else(|| anyhow!("Media input has no audio track"))?;
+            let rx = feed.attach_audio();
+
+            tokio::spawn(async move {
+                while let Ok(frame) = rx.recv_async().await {
+                    let _ = tx.send(frame).await;
+                }
+            });
+
+            Ok(Self { feed, info })
+        }
+    }
+
+    fn audio_info(&self) -> AudioInfo {
+        self.info
+    }
+
+    async fn stop(&mut self) -> anyhow::Result<()> {
+        self.feed.detach_audio();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_reads_from_stdin() {
+        assert_eq!(MediaInput::File("-".into()).url(), "pipe:0");
+        assert_eq!(
+            MediaInput::ImageSequence {
+                pattern: "frames/%04d.png".into(),
+                fps: 30
+            }
+            .url(),
+            "frames/%04d.png"
+        );
+    }
+
+    #[test]
+    fn track_position_prefers_timestamps() {
+        let mut position = TrackPosition::default();
+        let time_base = ffmpeg::Rational::new(1, 1000);
+
+        assert_eq!(position.advance(None, time_base, 0.5), 0.0);
+        assert_eq!(position.advance(None, time_base, 0.5), 0.5);
+        assert_eq!(position.advance(Some(2000), time_base, 0.5), 2.0);
+        assert_eq!(position.advance(None, time_base, 0.5), 2.5);
+    }
+}
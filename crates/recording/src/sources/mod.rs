@@ -1,10 +1,12 @@
 pub mod audio_mixer;
 pub mod camera;
+pub mod media_input;
 pub mod microphone;
 pub mod native_camera;
 pub mod screen_capture;
 
 pub use camera::*;
+pub use media_input::*;
 pub use microphone::*;
 pub use native_camera::*;
 pub use screen_capture::*;
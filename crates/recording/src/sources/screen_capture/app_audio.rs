@@ -0,0 +1,222 @@
+//! Per-application system audio on Linux.
+//!
+//! PipeWire exposes every application that is playing sound as a
+//! `Stream/Output/Audio` node. [`list_audio_apps`] reads those nodes from
+//! `pw-dump`, and an [`AppAudioFilter`] picks which of them a recording's
+//! system audio track should contain, so notifications and music from other
+//! applications stay out of it.
+
+use serde::Serialize;
+
+const STREAM_MEDIA_CLASS: &str = "Stream/Output/Audio";
+
+/// An application stream that is currently playing audio.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioApp {
+    /// PipeWire node id of the stream. An application can have several.
+    pub node_id: u32,
+    /// The application's name, e.g. `Firefox`.
+    pub name: String,
+    /// The executable name, e.g. `firefox`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// What the stream is playing, when the application names it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_name: Option<String>,
+}
+
+/// Which applications' audio is recorded as system audio. Applications are
+/// matched by name or executable (case-insensitively) or by PID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppAudioFilter {
+    /// Record only these applications.
+    Include(Vec<String>),
+    /// Record every application except these.
+    Exclude(Vec<String>),
+}
+
+impl AppAudioFilter {
+    pub fn captures(&self, app: &AudioApp) -> bool {
+        match self {
+            Self::Include(apps) => apps.iter().any(|pattern| app.matches(pattern)),
+            Self::Exclude(apps) => !apps.iter().any(|pattern| app.matches(pattern)),
+        }
+    }
+}
+
+impl AudioApp {
+    fn matches(&self, pattern: &str) -> bool {
+        if let Ok(pid) = pattern.parse::<u32>() {
+            return self.pid == Some(pid);
+        }
+
+        self.name.eq_ignore_ascii_case(pattern)
+            || self
+                .binary
+                .as_deref()
+                .is_some_and(|binary| binary.eq_ignore_ascii_case(pattern))
+    }
+}
+
+/// Lists the application streams currently connected to PipeWire.
+#[cfg(target_os = "linux")]
+pub fn list_audio_apps() -> anyhow::Result<Vec<AudioApp>> {
+    use anyhow::Context as _;
+
+    let output = std::process::Command::new("pw-dump")
+        .output()
+        .context("run pw-dump (per-application audio requires PipeWire)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "pw-dump failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    parse_pw_dump(&output.stdout)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_audio_apps() -> anyhow::Result<Vec<AudioApp>> {
+    anyhow::bail!("Per-application audio capture is only supported on Linux")
+}
+
+/// Reads the application audio streams out of `pw-dump`'s JSON.
+pub fn parse_pw_dump(json: &[u8]) -> anyhow::Result<Vec<AudioApp>> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_slice(json).map_err(|e| anyhow::anyhow!("Invalid pw-dump output: {e}"))?;
+
+    Ok(objects
+        .iter()
+        .filter(|object| {
+            object.get("type").and_then(|t| t.as_str()) == Some("PipeWire:Interface:Node")
+        })
+        .filter_map(|object| {
+            let props = object.get("info")?.get("props")?;
+            let prop = |key: &str| {
+                props
+                    .get(key)
+                    .and_then(|value| value.as_str())
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+            };
+            if prop("media.class").as_deref() != Some(STREAM_MEDIA_CLASS) {
+                return None;
+            }
+
+            // PipeWire properties are strings, but pw-dump prints numeric ones as numbers.
+            let pid = props.get("application.process.id").and_then(|pid| {
+                pid.as_u64()
+                    .or_else(|| pid.as_str()?.parse().ok())
+                    .and_then(|pid| u32::try_from(pid).ok())
+            });
+
+            Some(AudioApp {
+                node_id: u32::try_from(object.get("id")?.as_u64()?).ok()?,
+                name: prop("application.name").or_else(|| prop("node.name"))?,
+                binary: prop("application.process.binary"),
+                pid,
+                media_name: prop("media.name"),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PW_DUMP: &str = r#"[
+        {
+            "id": 31,
+            "type": "PipeWire:Interface:Node",
+            "info": { "props": { "media.class": "Audio/Sink", "node.name": "alsa_output.pci" } }
+        },
+        {
+            "id": 87,
+            "type": "PipeWire:Interface:Node",
+            "info": {
+                "props": {
+                    "media.class": "Stream/Output/Audio",
+                    "application.name": "Firefox",
+                    "application.process.binary": "firefox",
+                    "application.process.id": 4242,
+                    "media.name": "AudioStream",
+                    "node.name": "Firefox"
+                }
+            }
+        },
+        {
+            "id": 92,
+            "type": "PipeWire:Interface:Node",
+            "info": {
+                "props": {
+                    "media.class": "Stream/Output/Audio",
+                    "application.process.id": "5151",
+                    "node.name": "pw-play"
+                }
+            }
+        },
+        {
+            "id": 95,
+            "type": "PipeWire:Interface:Node",
+            "info": { "props": { "media.class": "Stream/Input/Audio", "node.name": "recorder" } }
+        },
+        { "id": 96, "type": "PipeWire:Interface:Link", "info": {} }
+    ]"#;
+
+    fn apps() -> Vec<AudioApp> {
+        parse_pw_dump(PW_DUMP.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn pw_dump_lists_only_output_streams() {
+        assert_eq!(
+            apps(),
+            vec![
+                AudioApp {
+                    node_id: 87,
+                    name: "Firefox".to_string(),
+                    binary: Some("firefox".to_string()),
+                    pid: Some(4242),
+                    media_name: Some("AudioStream".to_string()),
+                },
+                AudioApp {
+                    node_id: 92,
+                    name: "pw-play".to_string(),
+                    binary: None,
+                    pid: Some(5151),
+                    media_name: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_match_name_binary_or_pid() {
+        let apps = apps();
+        let captured = |filter: AppAudioFilter| {
+            apps.iter()
+                .filter(|app| filter.captures(app))
+                .map(|app| app.node_id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            captured(AppAudioFilter::Include(vec!["FIREFOX".to_string()])),
+            vec![87]
+        );
+        assert_eq!(
+            captured(AppAudioFilter::Include(vec!["5151".to_string()])),
+            vec![92]
+        );
+        assert_eq!(
+            captured(AppAudioFilter::Exclude(vec!["firefox".to_string()])),
+            vec![92]
+        );
+        assert!(captured(AppAudioFilter::Include(vec!["slack".to_string()])).is_empty());
+    }
+}
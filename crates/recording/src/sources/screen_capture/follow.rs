@@ -0,0 +1,202 @@
+//! Tracks a window for [`super::ScreenCaptureTarget::FollowWindow`] captures.
+//!
+//! The platform capturers record the display under the window and crop every
+//! frame to wherever the window is at that moment. [`WindowFollower`] polls
+//! the window's position off the capture thread so frame callbacks only read a
+//! cached value, and [`letterbox`] places the (possibly resized) crop inside
+//! the fixed output size chosen when the recording started.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use scap_targets::{DisplayId, Window, WindowId, bounds::*};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// How often the followed window's position is refreshed. Faster than most
+/// recording frame rates would only repeat identical lookups.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Where a followed window currently is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowedWindow {
+    /// The display the window is (mostly) on.
+    pub display: DisplayId,
+    /// The window's bounds relative to `display`.
+    pub bounds: LogicalBounds,
+}
+
+impl FollowedWindow {
+    pub fn locate(id: &WindowId) -> Option<Self> {
+        let window = Window::from_id(id)?;
+
+        Some(Self {
+            display: window.display()?.id(),
+            bounds: window.display_relative_logical_bounds()?,
+        })
+    }
+
+    /// The window's bounds in its display's physical pixels, clamped to the
+    /// display.
+    #[cfg(any(windows, target_os = "linux"))]
+    pub fn physical_bounds(&self) -> Option<PhysicalBounds> {
+        let display = scap_targets::Display::from_id(&self.display)?;
+
+        super::logical_area_to_physical_bounds(
+            self.bounds,
+            display.logical_size()?,
+            display.physical_size()?,
+        )
+    }
+}
+
+/// Keeps the position of a window up to date on a background thread, which
+/// stops when this is dropped.
+pub struct WindowFollower {
+    current: Arc<Mutex<Option<FollowedWindow>>>,
+    _stop: DropGuard,
+}
+
+impl WindowFollower {
+    pub fn spawn(id: WindowId) -> Self {
+        let current = Arc::new(Mutex::new(FollowedWindow::locate(&id)));
+        let stop_token = CancellationToken::new();
+
+        let thread_current = current.clone();
+        let thread_stop = stop_token.child_token();
+        std::thread::spawn(move || {
+            while !thread_stop.is_cancelled() {
+                std::thread::sleep(POLL_INTERVAL);
+
+                // A window that is briefly unavailable (minimised, mid-space
+                // switch) keeps its last position rather than jumping.
+                if let Some(window) = FollowedWindow::locate(&id)
+                    && let Ok(mut current) = thread_current.lock()
+                {
+                    *current = Some(window);
+                }
+            }
+        });
+
+        Self {
+            current,
+            _stop: stop_token.drop_guard(),
+        }
+    }
+
+    /// The most recently seen position, or `None` if the window has not been
+    /// found since the follower started.
+    pub fn current(&self) -> Option<FollowedWindow> {
+        self.current.lock().ok().and_then(|current| current.clone())
+    }
+}
+
+/// The area of an output frame that a letterboxed source occupies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Letterbox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Fits a `source`-sized image inside `output` without changing its aspect
+/// ratio, centred with bars on the remaining sides. Offsets and sizes are even
+/// so they line up with chroma-subsampled pixel formats.
+pub fn letterbox(source: (u32, u32), output: (u32, u32)) -> Letterbox {
+    let (source_width, source_height) = source;
+    let (output_width, output_height) = output;
+
+    if source_width == 0 || source_height == 0 {
+        return Letterbox {
+            x: 0,
+            y: 0,
+            width: output_width,
+            height: output_height,
+        };
+    }
+
+    let scale = (output_width as f64 / source_width as f64)
+        .min(output_height as f64 / source_height as f64);
+    let fit = |source: u32, output: u32| {
+        let scaled = (source as f64 * scale).round() as u32;
+        (scaled - scaled % 2).clamp(2.min(output), output)
+    };
+    let width = fit(source_width, output_width);
+    let height = fit(source_height, output_height);
+    let offset = |size: u32, output: u32| {
+        let offset = (output - size) / 2;
+        offset - offset % 2
+    };
+
+    Letterbox {
+        x: offset(width, output_width),
+        y: offset(height, output_height),
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_fills_output_with_matching_aspect() {
+        assert_eq!(
+            letterbox((800, 600), (1600, 1200)),
+            Letterbox {
+                x: 0,
+                y: 0,
+                width: 1600,
+                height: 1200,
+            }
+        );
+    }
+
+    #[test]
+    fn letterbox_centres_mismatched_aspect() {
+        assert_eq!(
+            letterbox((1000, 400), (1000, 800)),
+            Letterbox {
+                x: 0,
+                y: 200,
+                width: 1000,
+                height: 400,
+            }
+        );
+        assert_eq!(
+            letterbox((300, 600), (1000, 600)),
+            Letterbox {
+                x: 350,
+                y: 0,
+                width: 300,
+                height: 600,
+            }
+        );
+    }
+
+    #[test]
+    fn letterbox_keeps_even_geometry() {
+        let fitted = letterbox((333, 101), (640, 480));
+
+        assert_eq!(fitted.width, 640);
+        assert_eq!(fitted.height % 2, 0);
+        assert_eq!(fitted.y % 2, 0);
+        assert!(fitted.y + fitted.height <= 480);
+    }
+
+    #[test]
+    fn letterbox_uses_whole_output_for_empty_source() {
+        assert_eq!(
+            letterbox((0, 0), (640, 480)),
+            Letterbox {
+                x: 0,
+                y: 0,
+                width: 640,
+                height: 480,
+            }
+        );
+    }
+}
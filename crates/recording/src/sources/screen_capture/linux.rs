@@ -1,3 +1,4 @@
+use super::app_audio::{self, AppAudioFilter};
 use super::*;
 use crate::feeds::microphone::{self, MicrophoneFeed, MicrophoneFeedLock};
 use crate::ffmpeg::FFmpegVideoFrame;
@@ -16,6 +17,7 @@ use kameo::Actor as _;
 use pipewire as pw;
 use pw::{properties::properties, spa};
 use std::{
+    collections::HashSet,
     os::fd::OwnedFd,
     process::Command,
     sync::{
@@ -67,6 +69,9 @@ pub(crate) struct X11InputConfig {
     pub height: u32,
     pub fps: u32,
     pub show_cursor: bool,
+    /// Moves the capture region with this window, letterboxing it into the
+    /// starting `width`x`height`.
+    pub follow_window: Option<WindowId>,
 }
 
 struct WaylandInputConfig {
@@ -92,7 +97,7 @@ impl ScreenCaptureConfig<X11Capture> {
         &self,
     ) -> anyhow::Result<(VideoSourceConfig, Option<SystemAudioSourceConfig>)> {
         let system_audio = if self.system_audio {
-            Some(create_system_audio_source_config().await?)
+            Some(create_system_audio_source_config(self.app_audio.clone()).await?)
         } else {
             None
         };
@@ -160,6 +165,7 @@ impl ScreenCaptureConfig<X11Capture> {
                     height,
                     fps: self.config.fps,
                     show_cursor: self.config.show_cursor,
+                    follow_window: self.config.follow_window.clone(),
                 }),
             },
             system_audio,
@@ -821,11 +827,13 @@ pub struct SystemAudioSourceConfig {
     feed_lock: Arc<MicrophoneFeedLock>,
     device_name: String,
     restore_source: Option<String>,
+    app_audio: Option<AppAudioRouting>,
 }
 
 pub struct SystemAudioSource {
     inner: crate::sources::Microphone,
     restore_source: Option<String>,
+    _app_audio: Option<AppAudioRouting>,
 }
 
 impl AudioSource for SystemAudioSource {
@@ -841,6 +849,7 @@ impl AudioSource for SystemAudioSource {
     {
         let device_name = config.device_name.clone();
         let restore_source = config.restore_source;
+        let app_audio = config.app_audio;
         let setup = <crate::sources::Microphone as AudioSource>::setup(config.feed_lock, tx, ctx);
         async move {
             let inner = setup
@@ -850,6 +859,7 @@ impl AudioSource for SystemAudioSource {
             Ok(Self {
                 inner,
                 restore_source,
+                _app_audio: app_audio,
             })
         }
     }
@@ -871,8 +881,14 @@ impl AudioSource for SystemAudioSource {
     }
 }
 
-async fn create_system_audio_source_config() -> anyhow::Result<SystemAudioSourceConfig> {
-    let selected = select_system_audio_monitor()?;
+async fn create_system_audio_source_config(
+    app_audio: Option<AppAudioFilter>,
+) -> anyhow::Result<SystemAudioSourceConfig> {
+    let app_audio = app_audio.map(AppAudioRouting::start).transpose()?;
+    let selected = match &app_audio {
+        Some(routing) => select_app_audio_monitor(routing)?,
+        None => select_system_audio_monitor()?,
+    };
 
     let (error_tx, _error_rx) = flume::bounded(16);
     let feed = MicrophoneFeed::spawn(MicrophoneFeed::new(error_tx));
@@ -901,6 +917,7 @@ async fn create_system_audio_source_config() -> anyhow::Result<SystemAudioSource
         feed_lock: Arc::new(lock),
         device_name: selected.device_name,
         restore_source: selected.restore_source,
+        app_audio,
     })
 }
 
@@ -987,18 +1004,164 @@ fn select_pactl_monitor_source(
         return Ok(None);
     };
 
+    select_pactl_source(device_name, &source).map(Some)
+}
+
+/// Records `source` through the PulseAudio input `device_name`, which reads
+/// the default source, by making it the default until the recording stops.
+fn select_pactl_source(
+    device_name: String,
+    source: &str,
+) -> anyhow::Result<SelectedSystemAudioInput> {
     let previous_source = pactl_default_source();
-    let restore_source = if previous_source.as_deref() == Some(source.as_str()) {
+    let restore_source = if previous_source.as_deref() == Some(source) {
         None
     } else {
-        set_pactl_default_source(&source)?;
+        set_pactl_default_source(source)?;
         previous_source
     };
 
-    Ok(Some(SelectedSystemAudioInput {
+    Ok(SelectedSystemAudioInput {
         device_name,
         restore_source,
-    }))
+    })
+}
+
+fn select_app_audio_monitor(routing: &AppAudioRouting) -> anyhow::Result<SelectedSystemAudioInput> {
+    let available = MicrophoneFeed::list().keys().cloned().collect::<Vec<_>>();
+    let Some(device_name) = pulse_cpal_device_name(&available) else {
+        bail!(
+            "Per-application audio needs a PulseAudio input to read {}. \
+            Available input devices: {available:?}",
+            routing.monitor_source()
+        );
+    };
+
+    select_pactl_source(device_name, &routing.monitor_source())
+}
+
+const APP_AUDIO_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Routes the streams of selected applications into a private null sink,
+/// whose monitor is then recorded as system audio. Streams are linked to the
+/// sink in addition to their existing output, so they stay audible, and
+/// applications that start playing later are picked up while recording.
+struct AppAudioRouting {
+    sink: String,
+    module: String,
+    _stop: tokio_util::sync::DropGuard,
+}
+
+impl AppAudioRouting {
+    fn start(filter: AppAudioFilter) -> anyhow::Result<Self> {
+        let sink = format!("cap-app-audio-{}", std::process::id());
+        let output = Command::new("pactl")
+            .args([
+                "load-module",
+                "module-null-sink",
+                &format!("sink_name={sink}"),
+                "sink_properties=device.description=Cap-App-Audio",
+            ])
+            .output()
+            .context("run pactl load-module")?;
+        if !output.status.success() {
+            bail!(
+                "pactl could not create the application audio sink: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let stop_token = CancellationToken::new();
+        let routing = Self {
+            sink,
+            module: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            _stop: stop_token.clone().drop_guard(),
+        };
+
+        // The first pass runs before recording starts so its audio is there
+        // from the first frame, and surfaces a missing pw-dump or pw-link.
+        let mut linked = HashSet::new();
+        link_streams(&routing.sink, &filter, &mut linked)?;
+        if linked.is_empty() {
+            tracing::warn!(
+                ?filter,
+                "No application playing audio matches the filter yet; waiting for one to start"
+            );
+        }
+
+        let sink = routing.sink.clone();
+        let thread_stop = stop_token.child_token();
+        std::thread::spawn(move || {
+            while !thread_stop.is_cancelled() {
+                std::thread::sleep(APP_AUDIO_POLL_INTERVAL);
+
+                if let Err(error) = link_streams(&sink, &filter, &mut linked) {
+                    tracing::debug!(error = %error, "Failed to update application audio links");
+                }
+            }
+        });
+
+        Ok(routing)
+    }
+
+    fn monitor_source(&self) -> String {
+        format!("{}.monitor", self.sink)
+    }
+}
+
+/// Links every stream `filter` selects that is not in `linked` yet to `sink`,
+/// and forgets streams that have gone away. A stream that fails to link is
+/// still remembered so it is not retried (and warned about) on every poll.
+fn link_streams(
+    sink: &str,
+    filter: &AppAudioFilter,
+    linked: &mut HashSet<u32>,
+) -> anyhow::Result<()> {
+    let own_pid = std::process::id();
+    let apps = app_audio::list_audio_apps()?;
+    linked.retain(|node_id| apps.iter().any(|app| app.node_id == *node_id));
+
+    for app in apps
+        .iter()
+        .filter(|app| app.pid != Some(own_pid) && filter.captures(app))
+    {
+        if !linked.insert(app.node_id) {
+            continue;
+        }
+
+        let output = Command::new("pw-link")
+            .args([app.node_id.to_string().as_str(), sink])
+            .output()
+            .context("run pw-link")?;
+        if output.status.success() {
+            tracing::info!(app = %app.name, node_id = app.node_id, "Recording application audio");
+        } else {
+            tracing::warn!(
+                app = %app.name,
+                node_id = app.node_id,
+                error = %String::from_utf8_lossy(&output.stderr).trim(),
+                "Failed to link application audio"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+impl Drop for AppAudioRouting {
+    fn drop(&mut self) {
+        match Command::new("pactl")
+            .args(["unload-module", &self.module])
+            .status()
+        {
+            Ok(status) if status.success() => {}
+            result => tracing::warn!(
+                module = %self.module,
+                ?result,
+                "Failed to remove the application audio sink"
+            ),
+        }
+    }
 }
 
 fn pulse_cpal_device_name(available_devices: &[String]) -> Option<String> {
@@ -1125,6 +1288,71 @@ impl FrameScaler {
     }
 }
 
+/// Letterboxes frames of a followed window, whose size can change, into the
+/// fixed output size.
+struct FollowLetterbox {
+    output: VideoInfo,
+    scaler: Option<FrameScaler>,
+}
+
+impl FollowLetterbox {
+    fn new(output: VideoInfo) -> Self {
+        Self {
+            output,
+            scaler: None,
+        }
+    }
+
+    fn apply(&mut self, frame: ffmpeg::frame::Video) -> anyhow::Result<ffmpeg::frame::Video> {
+        if frame.width() == self.output.width && frame.height() == self.output.height {
+            return Ok(frame);
+        }
+
+        let fitted = follow::letterbox(
+            (frame.width(), frame.height()),
+            (self.output.width, self.output.height),
+        );
+        let fitted_info = VideoInfo {
+            width: fitted.width,
+            height: fitted.height,
+            ..self.output
+        };
+        if self.scaler.is_none() {
+            self.scaler = Some(FrameScaler::new(
+                frame.format(),
+                frame.width(),
+                frame.height(),
+                fitted_info,
+            )?);
+        }
+        let scaled = self
+            .scaler
+            .as_mut()
+            .expect("scaler initialized")
+            .scale(&frame, fitted_info)?;
+
+        let mut output = ffmpeg::frame::Video::new(
+            self.output.pixel_format,
+            self.output.width,
+            self.output.height,
+        );
+        let dst_stride = output.stride(0);
+        let src_stride = scaled.stride(0);
+        let row_bytes = fitted.width as usize * 4;
+        let dst = output.data_mut(0);
+        dst.fill(0);
+        for row in 0..fitted.height as usize {
+            let src_start = row * src_stride;
+            let dst_start = (fitted.y as usize + row) * dst_stride + fitted.x as usize * 4;
+            dst[dst_start..dst_start + row_bytes]
+                .copy_from_slice(&scaled.data(0)[src_start..src_start + row_bytes]);
+        }
+        output.set_pts(frame.pts());
+
+        Ok(output)
+    }
+}
+
 fn capture_x11(
     _video_info: VideoInfo,
     input_config: X11InputConfig,
@@ -1133,6 +1361,18 @@ fn capture_x11(
     health_tx: output_pipeline::HealthSender,
 ) -> anyhow::Result<()> {
     let mut grabber = X11Grabber::new(&input_config)?;
+    let follower = input_config
+        .follow_window
+        .clone()
+        .map(follow::WindowFollower::spawn);
+    let mut letterbox = follower.is_some().then(|| {
+        FollowLetterbox::new(VideoInfo::from_raw_ffmpeg(
+            ffmpeg::format::Pixel::BGRZ,
+            input_config.width,
+            input_config.height,
+            input_config.fps.max(1),
+        ))
+    });
     let frame_interval = Duration::from_secs_f64(1.0 / f64::from(input_config.fps.max(1)));
     let started = Instant::now();
     let mut next_capture = Instant::now();
@@ -1140,7 +1380,13 @@ fn capture_x11(
     let mut dropped = 0u64;
 
     while !stop_token.is_cancelled() {
-        let mut frame = match grabber.grab() {
+        if let Some(window) = follower.as_ref().and_then(|follower| follower.current())
+            && let Err(error) = grabber.follow(&window)
+        {
+            tracing::trace!(error = %error, "Keeping X11 capture region for followed window");
+        }
+
+        let frame = match grabber.grab() {
             Ok(frame) => frame,
             Err(error) => {
                 // X11 servers can transiently fail GetImage (e.g. while the
@@ -1150,6 +1396,18 @@ fn capture_x11(
                 continue;
             }
         };
+        let mut frame = match letterbox.as_mut() {
+            Some(letterbox) => match letterbox.apply(frame) {
+                Ok(frame) => frame,
+                Err(error) => {
+                    tracing::warn!(error = %error, "X11 follow-window letterbox failed");
+                    dropped += 1;
+                    std::thread::sleep(frame_interval);
+                    continue;
+                }
+            },
+            None => frame,
+        };
         frame.set_pts(Some(started.elapsed().as_micros() as i64));
 
         let timestamp = Timestamp::Instant(Instant::now());
@@ -1245,17 +1503,7 @@ impl X11Grabber {
             visual.blue_mask,
         )?;
 
-        let x = i16::try_from(config.x)
-            .map_err(|_| anyhow!("X11 capture x offset {} out of range", config.x))?;
-        let y = i16::try_from(config.y)
-            .map_err(|_| anyhow!("X11 capture y offset {} out of range", config.y))?;
-        let width = u16::try_from(config.width)
-            .map_err(|_| anyhow!("X11 capture width {} out of range", config.width))?;
-        let height = u16::try_from(config.height)
-            .map_err(|_| anyhow!("X11 capture height {} out of range", config.height))?;
-        if width == 0 || height == 0 {
-            bail!("X11 capture size must be non-zero");
-        }
+        let (x, y, width, height) = x11_region(config.x, config.y, config.width, config.height)?;
 
         // xfixes is needed to fetch the cursor image; only probe it when asked
         // to draw the cursor, and degrade gracefully if it is unavailable.
@@ -1288,6 +1536,51 @@ impl X11Grabber {
         })
     }
 
+    /// Move the capture region onto a followed window's current bounds.
+    fn follow(&mut self, window: &follow::FollowedWindow) -> anyhow::Result<()> {
+        let display =
+            Display::from_id(&window.display).ok_or_else(|| anyhow!("Display not found"))?;
+        let display_position = display
+            .raw_handle()
+            .physical_position()
+            .ok_or_else(|| anyhow!("Display position unavailable"))?;
+        let display_size = display
+            .physical_size()
+            .ok_or_else(|| anyhow!("Display size unavailable"))?;
+        let bounds = window
+            .physical_bounds()
+            .ok_or_else(|| anyhow!("Window bounds unavailable"))?;
+
+        let (x, y, width, height) = x11_capture_rect(
+            display_position.x(),
+            display_position.y(),
+            display_size.width(),
+            display_size.height(),
+            Some((
+                bounds.position().x(),
+                bounds.position().y(),
+                bounds.size().width(),
+                bounds.size().height(),
+            )),
+        )?;
+        let (x, y, width, height) = x11_region(x, y, width, height)?;
+
+        if (width, height) != (self.width, self.height) {
+            self.output = VideoInfo {
+                width: u32::from(width),
+                height: u32::from(height),
+                ..self.output
+            };
+            self.scaler = None;
+        }
+        self.x = x;
+        self.y = y;
+        self.width = width;
+        self.height = height;
+
+        Ok(())
+    }
+
     /// Capture one frame of the configured region as a BGRZ video frame.
     pub(crate) fn grab(&mut self) -> anyhow::Result<ffmpeg::frame::Video> {
         let reply = self
@@ -1425,6 +1718,20 @@ impl X11Grabber {
     }
 }
 
+fn x11_region(x: i32, y: i32, width: u32, height: u32) -> anyhow::Result<(i16, i16, u16, u16)> {
+    let x = i16::try_from(x).map_err(|_| anyhow!("X11 capture x offset {x} out of range"))?;
+    let y = i16::try_from(y).map_err(|_| anyhow!("X11 capture y offset {y} out of range"))?;
+    let width =
+        u16::try_from(width).map_err(|_| anyhow!("X11 capture width {width} out of range"))?;
+    let height =
+        u16::try_from(height).map_err(|_| anyhow!("X11 capture height {height} out of range"))?;
+    if width == 0 || height == 0 {
+        bail!("X11 capture size must be non-zero");
+    }
+
+    Ok((x, y, width, height))
+}
+
 /// Map an X11 32-bit TrueColor visual (byte order + RGB masks) to the matching
 /// packed FFmpeg pixel format. The overwhelmingly common desktop case
 /// (depth 24/32, little-endian, BGRX) resolves to BGRZ.
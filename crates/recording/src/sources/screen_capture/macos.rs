@@ -150,6 +150,25 @@ impl output_pipeline::VideoFrame for VideoFrame {
     fn timestamp(&self) -> Timestamp {
         self.timestamp
     }
+
+    fn to_ffmpeg(&self) -> Option<ffmpeg::frame::Video> {
+        use crate::output_pipeline::macos_frame_convert::fill_frame_from_sample_buf;
+
+        let image_buf = self.sample_buf.image_buf()?;
+        let pixel_format = match image_buf.pixel_format() {
+            cv::PixelFormat::_420V => ffmpeg::format::Pixel::NV12,
+            cv::PixelFormat::_32_BGRA => ffmpeg::format::Pixel::BGRA,
+            cv::PixelFormat::_2VUY => ffmpeg::format::Pixel::UYVY422,
+            _ => return None,
+        };
+        let mut frame = ffmpeg::frame::Video::new(
+            pixel_format,
+            image_buf.width() as u32,
+            image_buf.height() as u32,
+        );
+        fill_frame_from_sample_buf(&self.sample_buf, &mut frame).ok()?;
+        Some(frame)
+    }
 }
 
 impl ScreenCaptureConfig<CMSampleBufferCapture> {
@@ -573,6 +592,26 @@ impl Capturer {
 
         Ok(())
     }
+
+    /// Moves the running stream to `content_filter`, when given, and applies
+    /// `config`.
+    async fn update(
+        &self,
+        content_filter: Option<&sc::ContentFilter>,
+        config: &sc::StreamCfg,
+    ) -> anyhow::Result<()> {
+        if let Some(content_filter) = content_filter {
+            self.capturer
+                .update_content_filter(content_filter)
+                .await
+                .map_err(|err| anyhow!(format!("{err}")))?;
+        }
+
+        self.capturer
+            .update_config(config)
+            .await
+            .map_err(|err| anyhow!(format!("{err}")))
+    }
 }
 
 pub struct VideoSourceConfig {
@@ -644,6 +683,8 @@ impl output_pipeline::VideoSource for VideoSource {
         }
         let active_capturer: Arc<Mutex<Option<Capturer>>> = Arc::new(Mutex::new(None));
         let active_capturer_for_stop = active_capturer.clone();
+        let active_capturer_for_follow = active_capturer.clone();
+        let follow_params = rebuild_params.clone();
         let original_capturer = capturer.clone();
         ctx.tasks().spawn("screen-capture-monitor", async move {
             let mut restart_count = 0u32;
@@ -772,6 +813,74 @@ impl output_pipeline::VideoSource for VideoSource {
             }
         });
 
+        if let Some(window_id) = follow_params.config.follow_window.clone() {
+            let follower = follow::WindowFollower::spawn(window_id);
+            let follow_cancel = cancel_token.clone();
+            let original_capturer = capturer.clone();
+
+            // Moves the stream's source rect (and display, when the window
+            // changes screen) after the window. The output size is fixed, so
+            // ScreenCaptureKit letterboxes the window as it is resized.
+            ctx.tasks()
+                .spawn("screen-capture-follow-window", async move {
+                    let mut applied =
+                        follow_params
+                            .config
+                            .crop_bounds
+                            .map(|bounds| follow::FollowedWindow {
+                                display: follow_params.display_id.clone(),
+                                bounds,
+                            });
+
+                    loop {
+                        select! {
+                            _ = follow_cancel.cancelled() => break Ok(()),
+                            _ = tokio::time::sleep(follow::POLL_INTERVAL) => {}
+                        }
+
+                        let Some(window) = follower.current() else {
+                            continue;
+                        };
+                        if applied.as_ref() == Some(&window) {
+                            continue;
+                        }
+
+                        let content_filter = if applied
+                            .as_ref()
+                            .is_none_or(|applied| applied.display != window.display)
+                        {
+                            match display_content_filter(
+                                &window.display,
+                                &follow_params.excluded_windows,
+                            )
+                            .await
+                            {
+                                Ok(content_filter) => Some(content_filter),
+                                Err(e) => {
+                                    debug!(error = %e, "Failed to follow window onto its display");
+                                    continue;
+                                }
+                            }
+                        } else {
+                            None
+                        };
+
+                        let target = active_capturer_for_follow
+                            .lock()
+                            .ok()
+                            .and_then(|guard| guard.clone())
+                            .unwrap_or_else(|| original_capturer.clone());
+                        let config = rebuild_stream_cfg(&follow_params, Some(window.bounds));
+                        match target.update(content_filter.as_deref(), &config).await {
+                            Ok(()) => applied = Some(window),
+                            Err(e) => {
+                                debug!(error = %e, "Failed to move capture to followed window")
+                            }
+                        }
+                    }
+                });
+        }
+
         let stats_health_tx = ctx.health_tx().clone();
         ChannelVideoSource::setup(inner, video_tx, ctx)
             .await
@@ -923,19 +1032,23 @@ struct CapturerRebuildParams {
 unsafe impl Send for CapturerRebuildParams {}
 unsafe impl Sync for CapturerRebuildParams {}
 
-async fn rebuild_capturer(params: &CapturerRebuildParams) -> anyhow::Result<Capturer> {
+/// Builds a content filter for `display_id` from freshly fetched shareable content.
+async fn display_content_filter(
+    display_id: &DisplayId,
+    excluded_windows: &[WindowId],
+) -> anyhow::Result<arc::R<sc::ContentFilter>> {
     let shareable_content = sc::ShareableContent::current()
         .await
         .map_err(|e| anyhow!("Failed to get shareable content during restart: {e}"))?;
 
-    let display = Display::from_id(&params.display_id)
-        .ok_or_else(|| anyhow!("Display not found during restart: {:?}", params.display_id))?;
+    let display = Display::from_id(display_id)
+        .ok_or_else(|| anyhow!("Display not found during restart: {:?}", display_id))?;
 
-    let excluded_sc_windows = if params.excluded_windows.is_empty() {
+    let excluded_sc_windows = if excluded_windows.is_empty() {
         Vec::new()
     } else {
         let mut collected = Vec::new();
-        for window_id in &params.excluded_windows {
+        for window_id in excluded_windows {
             let Some(window) = Window::from_id(window_id) else {
                 warn!(%window_id, "Excluded screenshot window id no longer resolves");
                 continue;
@@ -952,18 +1065,25 @@ async fn rebuild_capturer(params: &CapturerRebuildParams) -> anyhow::Result<Capt
             }
         }
         info!(
-            configured_excluded_windows = params.excluded_windows.len(),
+            configured_excluded_windows = excluded_windows.len(),
             mapped_excluded_windows = collected.len(),
             "Mapped screenshot ScreenCaptureKit excluded windows"
         );
         collected
     };
 
-    let content_filter = display
+    display
         .raw_handle()
         .as_content_filter_excluding_windows(shareable_content, excluded_sc_windows)
-        .ok_or_else(|| anyhow!("Failed to create content filter during restart"))?;
+        .ok_or_else(|| anyhow!("Failed to create content filter during restart"))
+}
 
+/// The stream configuration for a rebuilt or updated capturer, limited to
+/// `crop_bounds` of the display when set.
+fn rebuild_stream_cfg(
+    params: &CapturerRebuildParams,
+    crop_bounds: Option<CropBounds>,
+) -> arc::R<sc::StreamCfg> {
     let size = PhysicalSize::new(
         params.video_info.width as f64,
         params.video_info.height as f64,
@@ -985,7 +1105,7 @@ async fn rebuild_capturer(params: &CapturerRebuildParams) -> anyhow::Result<Capt
     settings.set_pixel_format(cv::PixelFormat::_420V);
     settings.set_color_space_name(cg::color_space::names::srgb());
 
-    if let Some(crop_bounds) = params.config.crop_bounds {
+    if let Some(crop_bounds) = crop_bounds {
         settings.set_src_rect(cg::Rect::new(
             crop_bounds.position().x(),
             crop_bounds.position().y(),
@@ -994,6 +1114,30 @@ async fn rebuild_capturer(params: &CapturerRebuildParams) -> anyhow::Result<Capt
         ));
     }
 
+    settings
+}
+
+async fn rebuild_capturer(params: &CapturerRebuildParams) -> anyhow::Result<Capturer> {
+    // A followed window may have moved, or changed display, since the capture
+    // started.
+    let followed = params
+        .config
+        .follow_window
+        .as_ref()
+        .and_then(follow::FollowedWindow::locate);
+    let display_id = followed
+        .as_ref()
+        .map_or(&params.display_id, |window| &window.display);
+
+    let content_filter = display_content_filter(display_id, &params.excluded_windows).await?;
+    let settings = rebuild_stream_cfg(
+        params,
+        followed
+            .as_ref()
+            .map(|window| window.bounds)
+            .or(params.config.crop_bounds),
+    );
+
     let expected_width = params.video_info.width as usize;
     let expected_height = params.video_info.height as usize;
 
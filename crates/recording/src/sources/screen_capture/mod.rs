@@ -8,7 +8,9 @@ use specta::Type;
 use std::time::SystemTime;
 use tracing::*;
 
+pub mod app_audio;
 pub mod cadence;
+pub mod follow;
 
 #[cfg(target_os = "windows")]
 mod windows;
@@ -69,6 +71,11 @@ pub enum ScreenCaptureTarget {
         screen: DisplayId,
         bounds: LogicalBounds,
     },
+    /// Records the display under a window, cropped to the window as it moves. The output keeps the
+    /// window's starting size and letterboxes it when the window is resized.
+    FollowWindow {
+        id: WindowId,
+    },
     CameraOnly,
 }
 
@@ -84,7 +91,10 @@ pub enum LinuxCaptureSource {
 impl LinuxCaptureSource {
     pub fn from_target(target: &ScreenCaptureTarget) -> Self {
         match target {
-            ScreenCaptureTarget::Window { .. } => Self::Window,
+            // Portal window streams already track the window as it moves.
+            ScreenCaptureTarget::Window { .. } | ScreenCaptureTarget::FollowWindow { .. } => {
+                Self::Window
+            }
             ScreenCaptureTarget::Area { .. } => Self::Area,
             ScreenCaptureTarget::Display { .. } | ScreenCaptureTarget::CameraOnly => Self::Display,
         }
@@ -95,7 +105,9 @@ impl ScreenCaptureTarget {
     pub fn display(&self) -> Option<Display> {
         match self {
             Self::Display { id } => Display::from_id(id),
-            Self::Window { id } => Window::from_id(id).and_then(|w| w.display()),
+            Self::Window { id } | Self::FollowWindow { id } => {
+                Window::from_id(id).and_then(|w| w.display())
+            }
             Self::Area { screen, .. } => Display::from_id(screen),
             Self::CameraOnly => None,
         }
@@ -103,7 +115,15 @@ impl ScreenCaptureTarget {
 
     pub fn window(&self) -> Option<WindowId> {
         match self {
-            Self::Window { id } => Some(id.clone()),
+            Self::Window { id } | Self::FollowWindow { id } => Some(id.clone()),
+            _ => None,
+        }
+    }
+
+    /// The window whose movement the capture crop tracks, for [`Self::FollowWindow`].
+    pub fn follow_window(&self) -> Option<WindowId> {
+        match self {
+            Self::FollowWindow { id } => Some(id.clone()),
             _ => None,
         }
     }
@@ -141,7 +161,7 @@ impl ScreenCaptureTarget {
                     )));
                 }
             }
-            Self::Window { id } => {
+            Self::Window { id } | Self::FollowWindow { id } => {
                 let window = Window::from_id(id)?;
 
                 #[cfg(target_os = "macos")]
@@ -236,7 +256,9 @@ impl ScreenCaptureTarget {
     pub fn physical_size(&self) -> Option<PhysicalSize> {
         match self {
             Self::Display { id } => Display::from_id(id).and_then(|d| d.physical_size()),
-            Self::Window { id } => Window::from_id(id).and_then(|w| w.physical_size()),
+            Self::Window { id } | Self::FollowWindow { id } => {
+                Window::from_id(id).and_then(|w| w.physical_size())
+            }
             Self::Area { bounds, .. } => {
                 let display = self.display()?;
 
@@ -270,7 +292,9 @@ impl ScreenCaptureTarget {
     pub fn title(&self) -> Option<String> {
         match self {
             Self::Display { id } => Display::from_id(id).and_then(|d| d.name()),
-            Self::Window { id } => Window::from_id(id).and_then(|w| w.name()),
+            Self::Window { id } | Self::FollowWindow { id } => {
+                Window::from_id(id).and_then(|w| w.name())
+            }
             Self::Area { screen, .. } => Display::from_id(screen).and_then(|d| d.name()),
             Self::CameraOnly => Some("Camera".to_string()),
         }
@@ -281,6 +305,7 @@ impl ScreenCaptureTarget {
             ScreenCaptureTarget::Display { .. } => "Display",
             ScreenCaptureTarget::Window { .. } => "Window",
             ScreenCaptureTarget::Area { .. } => "Area",
+            ScreenCaptureTarget::FollowWindow { .. } => "FollowWindow",
             ScreenCaptureTarget::CameraOnly => "Camera",
         }
     }
@@ -291,6 +316,8 @@ pub struct ScreenCaptureConfig<TCaptureFormat: ScreenCaptureFormat> {
     video_info: VideoInfo,
     start_time: SystemTime,
     pub system_audio: bool,
+    /// Limits system audio to some applications' streams. Linux only.
+    pub app_audio: Option<app_audio::AppAudioFilter>,
     _phantom: std::marker::PhantomData<TCaptureFormat>,
     #[cfg(windows)]
     d3d_device: ::windows::Win32::Graphics::Direct3D11::ID3D11Device,
@@ -359,6 +386,7 @@ impl<TCaptureFormat: ScreenCaptureFormat> Clone for ScreenCaptureConfig<TCapture
             video_info: self.video_info,
             start_time: self.start_time,
             system_audio: self.system_audio,
+            app_audio: self.app_audio.clone(),
             _phantom: std::marker::PhantomData,
             #[cfg(windows)]
             d3d_device: self.d3d_device.clone(),
@@ -376,6 +404,7 @@ pub struct Config {
     crop_bounds: Option<CropBounds>,
     fps: u32,
     show_cursor: bool,
+    follow_window: Option<WindowId>,
     #[cfg(target_os = "linux")]
     linux_source: LinuxCaptureSource,
 }
@@ -447,6 +476,10 @@ impl Config {
     pub fn fps(&self) -> u32 {
         self.fps
     }
+
+    pub fn follow_window(&self) -> Option<&WindowId> {
+        self.follow_window.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -529,6 +562,7 @@ impl<TCaptureFormat: ScreenCaptureFormat> ScreenCaptureConfig<TCaptureFormat> {
                 crop_bounds,
                 fps,
                 show_cursor,
+                follow_window: None,
                 #[cfg(target_os = "linux")]
                 linux_source,
             },
@@ -540,6 +574,7 @@ impl<TCaptureFormat: ScreenCaptureFormat> ScreenCaptureConfig<TCaptureFormat> {
             ),
             start_time,
             system_audio,
+            app_audio: None,
             _phantom: std::marker::PhantomData,
             #[cfg(windows)]
             d3d_device,
@@ -550,6 +585,20 @@ impl<TCaptureFormat: ScreenCaptureFormat> ScreenCaptureConfig<TCaptureFormat> {
         })
     }
 
+    /// Makes `crop_bounds` the window's starting position and moves the crop
+    /// with the window for the rest of the capture.
+    pub fn with_follow_window(mut self, follow_window: Option<WindowId>) -> Self {
+        self.config.follow_window = follow_window;
+        self
+    }
+
+    /// Records only the audio of the applications `app_audio` selects as
+    /// system audio, when `system_audio` is enabled.
+    pub fn with_app_audio(mut self, app_audio: Option<app_audio::AppAudioFilter>) -> Self {
+        self.app_audio = app_audio;
+        self
+    }
+
     #[cfg(windows)]
     pub fn d3d_device(&self) -> &::windows::Win32::Graphics::Direct3D11::ID3D11Device {
         &self.d3d_device
@@ -1,6 +1,8 @@
 use crate::{
     AudioFrame, SetupCtx, output_pipeline,
-    screen_capture::{ScreenCaptureConfig, ScreenCaptureFormat, cadence::FrameCadenceGate},
+    screen_capture::{
+        ScreenCaptureConfig, ScreenCaptureFormat, cadence::FrameCadenceGate, follow::WindowFollower,
+    },
 };
 use ::windows::Win32::Graphics::Direct3D11::{
     D3D11_BIND_SHADER_RESOURCE, D3D11_BOX, D3D11_SUBRESOURCE_DATA, D3D11_TEXTURE2D_DESC,
@@ -16,7 +18,7 @@ use futures::{
     channel::{mpsc, oneshot},
 };
 use scap_ffmpeg::*;
-use scap_targets::{Display, DisplayId};
+use scap_targets::{Display, DisplayId, WindowId};
 use std::{
     sync::{
         Arc, Mutex,
@@ -173,6 +175,8 @@ struct FrameScalerState {
     context: ffmpeg::software::scaling::Context,
     source_width: u32,
     source_height: u32,
+    output_width: u32,
+    output_height: u32,
 }
 
 unsafe impl Send for FrameScalerState {}
@@ -201,55 +205,89 @@ impl WindowsFrameScaler {
         }
     }
 
+    fn ffmpeg_pixel(&self) -> ffmpeg::format::Pixel {
+        match self.pixel_format {
+            scap_direct3d::PixelFormat::R8G8B8A8Unorm => ffmpeg::format::Pixel::RGBA,
+            scap_direct3d::PixelFormat::B8G8R8A8Unorm => ffmpeg::format::Pixel::BGRA,
+        }
+    }
+
     fn scale_frame(&mut self, frame: &scap_direct3d::Frame) -> Option<ScreenFrame> {
         let src_width = frame.width();
         let src_height = frame.height();
 
-        let needs_reinit = self
-            .state
-            .as_ref()
-            .is_none_or(|s| s.source_width != src_width || s.source_height != src_height);
+        let src_frame = self.read_region(frame, 0, 0, src_width, src_height)?;
+        let dst_frame = self.scale(&src_frame, self.target_width, self.target_height)?;
 
-        if needs_reinit {
-            let src_pixel = match self.pixel_format {
-                scap_direct3d::PixelFormat::R8G8B8A8Unorm => ffmpeg::format::Pixel::RGBA,
-                scap_direct3d::PixelFormat::B8G8R8A8Unorm => ffmpeg::format::Pixel::BGRA,
-            };
+        let dst_row_length = (self.target_width * 4) as usize;
+        let mut pixel_data = vec![0u8; dst_row_length * self.target_height as usize];
+        copy_rows(&dst_frame, &mut pixel_data, dst_row_length, 0, 0);
 
-            let context = ffmpeg::software::scaling::Context::get(
-                src_pixel,
-                src_width,
-                src_height,
-                src_pixel,
-                self.target_width,
-                self.target_height,
-                ffmpeg::software::scaling::Flags::BILINEAR,
-            )
-            .ok()?;
+        self.texture_frame(pixel_data)
+    }
 
-            self.state = Some(FrameScalerState {
-                context,
-                source_width: src_width,
-                source_height: src_height,
-            });
+    /// Crops `bounds` out of `frame` and letterboxes it into the target size,
+    /// for captures that follow a moving window across the display.
+    fn crop_frame(
+        &mut self,
+        frame: &scap_direct3d::Frame,
+        bounds: scap_targets::bounds::PhysicalBounds,
+    ) -> Option<ScreenFrame> {
+        let frame_width = frame.width();
+        let frame_height = frame.height();
+        if frame_width < 2 || frame_height < 2 {
+            return None;
         }
 
+        let left = (bounds.position().x().max(0.0) as u32).min(frame_width - 2);
+        let top = (bounds.position().y().max(0.0) as u32).min(frame_height - 2);
+        let width = cap_media_info::ensure_even(
+            (bounds.size().width().max(0.0) as u32).min(frame_width - left),
+        );
+        let height = cap_media_info::ensure_even(
+            (bounds.size().height().max(0.0) as u32).min(frame_height - top),
+        );
+
+        let fitted =
+            super::follow::letterbox((width, height), (self.target_width, self.target_height));
+
+        let src_frame = self.read_region(frame, left, top, width, height)?;
+        let dst_frame = self.scale(&src_frame, fitted.width, fitted.height)?;
+
+        let dst_row_length = (self.target_width * 4) as usize;
+        let mut pixel_data = vec![0u8; dst_row_length * self.target_height as usize];
+        copy_rows(
+            &dst_frame,
+            &mut pixel_data,
+            dst_row_length,
+            fitted.x as usize * 4,
+            fitted.y as usize,
+        );
+
+        self.texture_frame(pixel_data)
+    }
+
+    /// Copies a region of the captured frame into an ffmpeg frame.
+    fn read_region(
+        &self,
+        frame: &scap_direct3d::Frame,
+        left: u32,
+        top: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<ffmpeg::frame::Video> {
         let buffer = frame.as_buffer().ok()?;
         let src_data = buffer.data();
         let src_stride = buffer.stride() as usize;
-        let row_length = (src_width * 4) as usize;
+        let row_length = (width * 4) as usize;
+        let row_offset = (left * 4) as usize;
 
-        let src_pixel = match self.pixel_format {
-            scap_direct3d::PixelFormat::R8G8B8A8Unorm => ffmpeg::format::Pixel::RGBA,
-            scap_direct3d::PixelFormat::B8G8R8A8Unorm => ffmpeg::format::Pixel::BGRA,
-        };
-
-        let mut src_frame = ffmpeg::frame::Video::new(src_pixel, src_width, src_height);
+        let mut src_frame = ffmpeg::frame::Video::new(self.ffmpeg_pixel(), width, height);
         let ff_stride = src_frame.stride(0);
         let ff_data = src_frame.data_mut(0);
 
-        for row in 0..src_height as usize {
-            let s_start = row * src_stride;
+        for row in 0..height as usize {
+            let s_start = (top as usize + row) * src_stride + row_offset;
             let d_start = row * ff_stride;
             let copy_len = row_length.min(
                 src_data
@@ -263,34 +301,58 @@ impl WindowsFrameScaler {
             }
         }
 
-        drop(buffer);
+        Some(src_frame)
+    }
 
-        let state = self.state.as_mut()?;
-        let mut dst_frame =
-            ffmpeg::frame::Video::new(src_pixel, self.target_width, self.target_height);
-        state.context.run(&src_frame, &mut dst_frame).ok()?;
+    fn scale(
+        &mut self,
+        src_frame: &ffmpeg::frame::Video,
+        output_width: u32,
+        output_height: u32,
+    ) -> Option<ffmpeg::frame::Video> {
+        let src_width = src_frame.width();
+        let src_height = src_frame.height();
+        let src_pixel = self.ffmpeg_pixel();
+
+        let needs_reinit = self.state.as_ref().is_none_or(|s| {
+            s.source_width != src_width
+                || s.source_height != src_height
+                || s.output_width != output_width
+                || s.output_height != output_height
+        });
 
-        let dst_stride = dst_frame.stride(0);
-        let dst_row_length = (self.target_width * 4) as usize;
-        let total_bytes = dst_row_length * self.target_height as usize;
-        let mut pixel_data = vec![0u8; total_bytes];
-        let dst_data = dst_frame.data(0);
-
-        for row in 0..self.target_height as usize {
-            let s_start = row * dst_stride;
-            let d_start = row * dst_row_length;
-            let copy_len = dst_row_length.min(
-                dst_data
-                    .len()
-                    .saturating_sub(s_start)
-                    .min(pixel_data.len().saturating_sub(d_start)),
-            );
-            if copy_len > 0 {
-                pixel_data[d_start..d_start + copy_len]
-                    .copy_from_slice(&dst_data[s_start..s_start + copy_len]);
-            }
+        if needs_reinit {
+            let context = ffmpeg::software::scaling::Context::get(
+                src_pixel,
+                src_width,
+                src_height,
+                src_pixel,
+                output_width,
+                output_height,
+                ffmpeg::software::scaling::Flags::BILINEAR,
+            )
+            .ok()?;
+
+            self.state = Some(FrameScalerState {
+                context,
+                source_width: src_width,
+                source_height: src_height,
+                output_width,
+                output_height,
+            });
         }
 
+        let state = self.state.as_mut()?;
+        let mut dst_frame = ffmpeg::frame::Video::new(src_pixel, output_width, output_height);
+        state.context.run(src_frame, &mut dst_frame).ok()?;
+
+        Some(dst_frame)
+    }
+
+    /// Uploads target-sized pixel rows into a new texture.
+    fn texture_frame(&self, pixel_data: Vec<u8>) -> Option<ScreenFrame> {
+        let dst_row_length = self.target_width * 4;
+
         let dxgi_format = match self.pixel_format {
             scap_direct3d::PixelFormat::R8G8B8A8Unorm => {
                 ::windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_R8G8B8A8_UNORM
@@ -318,7 +380,7 @@ impl WindowsFrameScaler {
 
         let subresource_data = D3D11_SUBRESOURCE_DATA {
             pSysMem: pixel_data.as_ptr() as *const _,
-            SysMemPitch: dst_row_length as u32,
+            SysMemPitch: dst_row_length,
             SysMemSlicePitch: 0,
         };
 
@@ -340,6 +402,35 @@ impl WindowsFrameScaler {
     }
 }
 
+/// Copies the rows of a packed 4-byte-per-pixel frame into `pixel_data`, whose
+/// rows are `row_length` bytes, starting `x_offset` bytes into row `y_offset`.
+fn copy_rows(
+    frame: &ffmpeg::frame::Video,
+    pixel_data: &mut [u8],
+    row_length: usize,
+    x_offset: usize,
+    y_offset: usize,
+) {
+    let src_stride = frame.stride(0);
+    let src_row_length = (frame.width() * 4) as usize;
+    let src_data = frame.data(0);
+
+    for row in 0..frame.height() as usize {
+        let s_start = row * src_stride;
+        let d_start = (y_offset + row) * row_length + x_offset;
+        let copy_len = src_row_length.min(row_length.saturating_sub(x_offset)).min(
+            src_data
+                .len()
+                .saturating_sub(s_start)
+                .min(pixel_data.len().saturating_sub(d_start)),
+        );
+        if copy_len > 0 {
+            pixel_data[d_start..d_start + copy_len]
+                .copy_from_slice(&src_data[s_start..s_start + copy_len]);
+        }
+    }
+}
+
 pub struct VideoFrame {
     pub frame: ScreenFrame,
     pub timestamp: Timestamp,
@@ -349,15 +440,25 @@ impl output_pipeline::VideoFrame for VideoFrame {
     fn timestamp(&self) -> Timestamp {
         self.timestamp
     }
+
+    fn to_ffmpeg(&self) -> Option<ffmpeg::frame::Video> {
+        self.frame.as_ffmpeg().ok()
+    }
 }
 
 impl ScreenCaptureConfig<Direct3DCapture> {
     pub async fn to_sources(
         &self,
     ) -> anyhow::Result<(VideoSourceConfig, Option<SystemAudioSourceConfig>)> {
+        // A followed window is cropped per frame, so the whole display is captured.
+        let crop_bounds = self
+            .config
+            .crop_bounds
+            .filter(|_| self.config.follow_window.is_none());
+
         let mut settings = scap_direct3d::Settings {
             pixel_format: Direct3DCapture::PIXEL_FORMAT,
-            crop: self.config.crop_bounds.map(|b| {
+            crop: crop_bounds.map(|b| {
                 let position = b.position();
                 let size = b.size().map(|v| (v / 2.0).floor() * 2.0);
 
@@ -401,6 +502,7 @@ impl ScreenCaptureConfig<Direct3DCapture> {
                 display_id: self.config.display.clone(),
                 settings,
                 d3d_device: self.d3d_device.clone(),
+                follow_window: self.config.follow_window.clone(),
             },
             self.system_audio.then_some(SystemAudioSourceConfig),
         ))
@@ -418,6 +520,7 @@ pub struct VideoSourceConfig {
     display_id: DisplayId,
     settings: scap_direct3d::Settings,
     pub d3d_device: ID3D11Device,
+    follow_window: Option<WindowId>,
 }
 pub struct VideoSource {
     video_info: VideoInfo,
@@ -429,8 +532,14 @@ enum VideoControl {
     Stop(oneshot::Sender<anyhow::Result<()>>),
     Restart,
     RecreateDevice,
+    /// Recreates the capturer on another display, which a followed window has
+    /// moved onto.
+    SwitchDisplay(DisplayId),
 }
 
+/// How often a follow-window capture checks whether the window changed display.
+const FOLLOW_DISPLAY_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
 const MAX_CAPTURE_RESTARTS: u32 = 3;
 const RESTART_DELAY: Duration = Duration::from_secs(1);
 
@@ -477,6 +586,7 @@ struct CreateCapturerParams<'a> {
     scaling_logged: Arc<AtomicBool>,
     scaled_frame_count: Arc<AtomicU32>,
     stall_health_tx: output_pipeline::HealthSender,
+    follower: Option<Arc<WindowFollower>>,
 }
 
 fn create_d3d_capturer(
@@ -504,6 +614,8 @@ fn create_d3d_capturer(
             let scaling_logged = params.scaling_logged.clone();
             let scaled_frame_count = params.scaled_frame_count.clone();
             let stall_health_tx = params.stall_health_tx.clone();
+            let follower = params.follower.clone();
+            let display_id = params.display_id.clone();
             move |frame| {
                 let capture_time = frame.inner().SystemRelativeTime()?;
 
@@ -525,51 +637,72 @@ fn create_d3d_capturer(
                 let frame_width = frame.width();
                 let frame_height = frame.height();
 
-                let screen_frame =
-                    if frame_width != expected_width || frame_height != expected_height {
-                        let Ok(mut scaler_guard) = frame_scaler.lock() else {
+                let screen_frame = if let Some(follower) = &follower {
+                    // Frames from the previous display are skipped until the
+                    // capture thread switches to the window's new one.
+                    let Some(bounds) = follower
+                        .current()
+                        .filter(|window| window.display == display_id)
+                        .and_then(|window| window.physical_bounds())
+                    else {
+                        return Ok(());
+                    };
+
+                    let cropped = frame_scaler
+                        .lock()
+                        .ok()
+                        .and_then(|mut scaler| scaler.crop_frame(&frame, bounds));
+                    match cropped {
+                        Some(cropped) => cropped,
+                        None => {
                             video_drop_counter.fetch_add(1, atomic::Ordering::Relaxed);
                             return Ok(());
-                        };
-
-                        if !scaling_logged.load(atomic::Ordering::Relaxed) {
-                            info!(
-                                expected_width,
-                                expected_height,
-                                frame_width,
-                                frame_height,
-                                "Display resolution changed, scaling frames to match original dimensions"
-                            );
-                            scaling_logged.store(true, atomic::Ordering::Relaxed);
                         }
+                    }
+                } else if frame_width != expected_width || frame_height != expected_height {
+                    let Ok(mut scaler_guard) = frame_scaler.lock() else {
+                        video_drop_counter.fetch_add(1, atomic::Ordering::Relaxed);
+                        return Ok(());
+                    };
 
-                        match scaler_guard.scale_frame(&frame) {
-                            Some(scaled) => {
-                                let count =
-                                    scaled_frame_count.fetch_add(1, atomic::Ordering::Relaxed) + 1;
-                                if count.is_multiple_of(300) {
-                                    debug!(scaled_frames = count, "Scaling frames");
-                                }
-                                scaled
-                            }
-                            None => {
-                                video_drop_counter.fetch_add(1, atomic::Ordering::Relaxed);
-                                return Ok(());
+                    if !scaling_logged.load(atomic::Ordering::Relaxed) {
+                        info!(
+                            expected_width,
+                            expected_height,
+                            frame_width,
+                            frame_height,
+                            "Display resolution changed, scaling frames to match original dimensions"
+                        );
+                        scaling_logged.store(true, atomic::Ordering::Relaxed);
+                    }
+
+                    match scaler_guard.scale_frame(&frame) {
+                        Some(scaled) => {
+                            let count =
+                                scaled_frame_count.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+                            if count.is_multiple_of(300) {
+                                debug!(scaled_frames = count, "Scaling frames");
                             }
+                            scaled
                         }
-                    } else {
-                        if scaling_logged.swap(false, atomic::Ordering::Relaxed) {
-                            let count = scaled_frame_count.swap(0, atomic::Ordering::Relaxed);
-                            info!(
-                                scaled_frames = count,
-                                "Display dimensions restored, resuming direct capture"
-                            );
-                            if let Ok(mut guard) = frame_scaler.lock() {
-                                guard.state = None;
-                            }
+                        None => {
+                            video_drop_counter.fetch_add(1, atomic::Ordering::Relaxed);
+                            return Ok(());
                         }
-                        ScreenFrame::Captured(frame)
-                    };
+                    }
+                } else {
+                    if scaling_logged.swap(false, atomic::Ordering::Relaxed) {
+                        let count = scaled_frame_count.swap(0, atomic::Ordering::Relaxed);
+                        info!(
+                            scaled_frames = count,
+                            "Display dimensions restored, resuming direct capture"
+                        );
+                        if let Ok(mut guard) = frame_scaler.lock() {
+                            guard.state = None;
+                        }
+                    }
+                    ScreenFrame::Captured(frame)
+                };
 
                 match output_pipeline::send_with_stall_budget_futures(
                     &mut tx,
@@ -622,6 +755,7 @@ impl output_pipeline::VideoSource for VideoSource {
             display_id,
             settings,
             d3d_device,
+            follow_window,
         }: Self::Config,
         video_tx: mpsc::Sender<Self::Frame>,
         ctx: &mut output_pipeline::SetupCtx,
@@ -646,6 +780,9 @@ impl output_pipeline::VideoSource for VideoSource {
         )));
         let scaling_logged: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
         let scaled_frame_count: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
+        let follower = follow_window.map(|id| Arc::new(WindowFollower::spawn(id)));
+        // Cancelled when the capture thread exits, ending the display watcher.
+        let follow_stop = CancellationToken::new();
 
         // Belt-and-braces alongside WGC's MinUpdateInterval: on systems where
         // that API is unsupported (or silently ineffective) the gate keeps
@@ -666,8 +803,12 @@ impl output_pipeline::VideoSource for VideoSource {
             let scaling_logged = scaling_logged.clone();
             let scaled_frame_count = scaled_frame_count.clone();
             let stats_health_tx = stats_health_tx.clone();
+            let follower = follower.clone();
+            let follow_stop = follow_stop.clone();
+            let mut display_id = display_id.clone();
             move || {
                 cap_mediafoundation_utils::thread_init();
+                let _follow_stop = follow_stop.drop_guard();
 
                 let video_frame_counter: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
                 let video_drop_counter: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
@@ -693,6 +834,7 @@ impl output_pipeline::VideoSource for VideoSource {
                             scaling_logged: scaling_logged.clone(),
                             scaled_frame_count: scaled_frame_count.clone(),
                             stall_health_tx: stats_health_tx.clone(),
+                            follower: follower.clone(),
                         }
                     };
                 }
@@ -845,6 +987,33 @@ impl output_pipeline::VideoSource for VideoSource {
                                 }
                             }
                         }
+                        Ok(VideoControl::SwitchDisplay(new_display_id)) => {
+                            info!(
+                                from = %display_id,
+                                to = %new_display_id,
+                                "Followed window moved display, switching Windows screen capture"
+                            );
+                            if let Some(mut old) = capturer.take() {
+                                let _ = old.stop();
+                                drop(old);
+                            }
+                            display_id = new_display_id;
+
+                            match create_d3d_capturer(&build_params!(&d3d_device), &error_tx)
+                                .and_then(|mut new_cap| {
+                                    new_cap.start()?;
+                                    Ok(new_cap)
+                                }) {
+                                Ok(new_cap) => capturer = Some(new_cap),
+                                Err(e) => {
+                                    warn!(error = %e, "Failed to switch capture display");
+                                    let _ = error_tx.try_send(CaptureClosureEvent {
+                                        kind: CaptureClosureKind::Transient,
+                                        message: format!("switch_display_failed: {e}"),
+                                    });
+                                }
+                            }
+                        }
                         Ok(VideoControl::Restart) => {
                             info!("Restarting Windows screen capture");
                             output_pipeline::emit_health(
@@ -900,6 +1069,35 @@ impl output_pipeline::VideoSource for VideoSource {
             }
         });
 
+        if let Some(follower) = follower {
+            let switch_ctrl_tx = ctrl_tx.clone();
+            let mut current_display = display_id.clone();
+            ctx.tasks().spawn(
+                "d3d-follow-window",
+                async move {
+                    loop {
+                        tokio::time::sleep(FOLLOW_DISPLAY_CHECK_INTERVAL).await;
+                        let Some(window) = follower.current() else {
+                            continue;
+                        };
+                        if window.display == current_display {
+                            continue;
+                        }
+
+                        current_display = window.display;
+                        if switch_ctrl_tx
+                            .try_send(VideoControl::SwitchDisplay(current_display.clone()))
+                            .is_err()
+                        {
+                            warn!("Failed to ask the capture thread to switch display");
+                        }
+                    }
+                }
+                .with_cancellation_token_owned(follow_stop)
+                .map(|_| Ok(())),
+            );
+        }
+
         let monitor_health_tx = ctx.health_tx().clone();
         ctx.tasks().spawn("d3d-capture", async move {
             let mut restart_count = 0u32;
@@ -6,20 +6,24 @@ use crate::output_pipeline::{
     MacOSFragmentedM4SCameraMuxerConfig,
 };
 use crate::{
-    ActorError, H264_MAX_DIMENSION, MediaError, RecordingBaseInputs, RecordingError,
-    SharedPauseState, calculate_gpu_compatible_size,
+    ActorError, AdditionalVideoInput, H264_MAX_DIMENSION, MediaError, RecordingBaseInputs,
+    RecordingError, SharedPauseState, calculate_gpu_compatible_size,
     capture_pipeline::{
         MakeCapturePipeline, ScreenCaptureMethod, Stop, target_to_display_and_crop,
     },
     cursor::{CursorActor, Cursors, IncrementalCaptureOutputs, spawn_cursor_recorder},
     feeds::{camera::CameraFeedLock, microphone::MicrophoneFeedLock},
-    ffmpeg::{FragmentedAudioMuxer, FragmentedAudioMuxerConfig, OggMuxer},
+    ffmpeg::{FragmentedAudioMuxer, FragmentedAudioMuxerConfig, OggMuxer, OggMuxerConfig},
     output_pipeline::{
-        AudioAnchor, AudioGapSummary, DoneFut, FinishedOutputPipeline, OutputPipeline,
-        PipelineDoneError,
+        AudioAnchor, AudioGapSummary, DoneFut, FinishedOutputPipeline, HealthReceiver,
+        HealthSender, LiveStream, OutputPipeline, PipelineDoneError, VideoSource, emit_health,
+        new_health_channel,
+    },
+    screen_capture::{ScreenCaptureConfig, app_audio::AppAudioFilter},
+    sources::{self, MediaInputFeed, audio_mixer::AudioMixer, screen_capture},
+    video_filter::{
+        FilteredVideoSource, FilteredVideoSourceConfig, VideoFilterConfig, VideoFilters,
     },
-    screen_capture::ScreenCaptureConfig,
-    sources::{self, screen_capture},
 };
 
 #[cfg(windows)]
@@ -28,12 +32,14 @@ use crate::output_pipeline::{
     WindowsFragmentedM4SCameraMuxerConfig,
 };
 use anyhow::{Context as _, anyhow, bail};
+use cap_enc_ffmpeg::segmented_stream::FragmentSealer;
 use cap_media_info::VideoInfo;
 use cap_project::{
-    CursorEvents, KeyboardEvents, MultipleSegment, MultipleSegments, Platform, RecordingMeta,
-    RecordingMetaInner, StudioRecordingMeta, StudioRecordingStatus,
+    AdditionalVideoKind, CursorEvents, KeyboardEvents, MultipleSegment, MultipleSegments, Platform,
+    RecordingMeta, RecordingMetaInner, StudioRecordingMeta, StudioRecordingStatus,
+    encryption::{self, ProjectKey},
 };
-use cap_timestamp::{Timestamp, Timestamps};
+use cap_timestamp::{MasterClock, Timestamp, Timestamps};
 use futures::{FutureExt, StreamExt, future::OptionFuture, stream::FuturesUnordered};
 use kameo::{Actor as _, prelude::*};
 use relative_path::RelativePathBuf;
@@ -88,6 +94,7 @@ pub struct ActorHandle {
     actor_ref: kameo::actor::ActorRef<Actor>,
     pub capture_target: screen_capture::ScreenCaptureTarget,
     done_fut: DoneFut,
+    health_rx: Arc<std::sync::Mutex<Option<HealthReceiver>>>,
     // pub bounds: Bounds,
 }
 
@@ -117,27 +124,27 @@ impl Actor {
 
         let segment_stop_time = current_time_f64();
 
+        let key = self.segment_factory.base_inputs.encryption.as_ref();
         let cursors = if let Some(cursor) = pipeline.cursor.as_mut()
             && let Ok(res) = cursor.actor.rx.clone().await
         {
             if let Some(output_path) = cursor.output_path.as_ref() {
-                std::fs::write(
-                    output_path,
-                    serde_json::to_string_pretty(&CursorEvents {
-                        clicks: res.clicks,
-                        moves: res.moves,
-                    })?,
-                )?;
+                let json = serde_json::to_string_pretty(&CursorEvents {
+                    clicks: res.clicks,
+                    moves: res.moves,
+                })?;
+                encryption::write_file(key, output_path, json.as_bytes())?;
             }
 
             if !res.keyboard_presses.is_empty()
                 && let Some(keyboard_output_path) = cursor.keyboard_output_path.as_ref()
             {
-                KeyboardEvents {
+                let bytes = KeyboardEvents {
                     presses: res.keyboard_presses,
                 }
-                .write_to_file(keyboard_output_path)
+                .to_bytes()
                 .map_err(anyhow::Error::msg)?;
+                encryption::write_file(key, keyboard_output_path, &bytes)?;
             }
 
             (res.cursors, res.next_cursor_id)
@@ -197,9 +204,15 @@ impl Message<Stop> for Actor {
             cursors,
             self.segment_factory.fragmented,
             self.display_notch,
+            self.segment_factory.base_inputs.encryption.as_ref(),
         )
         .await?;
 
+        if let Some(key) = &self.segment_factory.base_inputs.encryption {
+            key.seal_project(&recording.project_path)
+                .context("encrypt recording")?;
+        }
+
         self.notify_completion_ok();
 
         Ok(recording)
@@ -362,9 +375,11 @@ struct Pipeline {
     // sources
     pub screen: OutputPipeline,
     pub microphone: Option<OutputPipeline>,
+    pub additional_mics: Vec<AdditionalMicTrack<OutputPipeline>>,
     pub camera: Option<OutputPipeline>,
     pub system_audio: Option<OutputPipeline>,
     pub cursor: Option<CursorPipeline>,
+    pub additional: Vec<AdditionalVideoTrack<OutputPipeline>>,
     pub track_failures: SharedTrackFailures,
     pub watcher_task: Option<JoinHandle<()>>,
 }
@@ -374,19 +389,39 @@ struct FinishedPipeline {
     // sources
     pub screen: FinishedOutputPipeline,
     pub microphone: Option<FinishedOutputPipeline>,
+    pub additional_mics: Vec<AdditionalMicTrack<FinishedOutputPipeline>>,
     pub camera: Option<FinishedOutputPipeline>,
     pub system_audio: Option<FinishedOutputPipeline>,
     pub cursor: Option<CursorPipeline>,
+    pub additional: Vec<AdditionalVideoTrack<FinishedOutputPipeline>>,
     pub track_failures: Vec<TrackFailureRecord>,
 }
 
+/// A picture-in-picture source's pipeline, tagged with the id it is stored
+/// under in the segment meta.
+struct AdditionalVideoTrack<P> {
+    id: String,
+    kind: AdditionalVideoKind,
+    pipeline: P,
+}
+
+/// An extra microphone's pipeline, tagged with the id it is stored under in
+/// the segment meta.
+struct AdditionalMicTrack<P> {
+    id: String,
+    device_id: String,
+    pipeline: P,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 enum RecordingTrackKind {
     Display,
     Microphone,
+    AdditionalMicrophone,
     Camera,
     SystemAudio,
+    AdditionalVideo,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
@@ -513,20 +548,30 @@ fn build_recording_failure_diagnostics(
 fn write_recording_failure_diagnostics(
     recording_dir: &Path,
     diagnostics: &RecordingFailureDiagnostics,
+    key: Option<&ProjectKey>,
 ) -> Result<(), RecordingError> {
-    std::fs::write(
-        recording_dir.join("recording-diagnostics.json"),
-        serde_json::to_string_pretty(diagnostics)?,
+    encryption::write_file(
+        key,
+        &recording_dir.join("recording-diagnostics.json"),
+        serde_json::to_string_pretty(diagnostics)?.as_bytes(),
     )?;
     Ok(())
 }
 
 impl Pipeline {
     pub async fn stop(mut self) -> anyhow::Result<FinishedPipeline> {
-        let (microphone, camera, system_audio) = futures::join!(
+        let (microphone, additional_mics, camera, system_audio, additional) = futures::join!(
             OptionFuture::from(self.microphone.map(|s| s.stop())),
+            futures::future::join_all(self.additional_mics.into_iter().map(|track| async move {
+                let result = track.pipeline.stop().await;
+                (track.id, track.device_id, result)
+            })),
             OptionFuture::from(self.camera.map(|s| s.stop())),
-            OptionFuture::from(self.system_audio.map(|s| s.stop()))
+            OptionFuture::from(self.system_audio.map(|s| s.stop())),
+            futures::future::join_all(self.additional.into_iter().map(|track| async move {
+                let result = track.pipeline.stop().await;
+                (track.id, track.kind, result)
+            }))
         );
 
         let screen = self.screen.stop().await;
@@ -549,6 +594,21 @@ impl Pipeline {
                 microphone.transpose(),
                 &self.track_failures,
             ),
+            additional_mics: additional_mics
+                .into_iter()
+                .filter_map(|(id, device_id, result)| {
+                    finalize_optional_track(
+                        RecordingTrackKind::AdditionalMicrophone,
+                        result.map(Some),
+                        &self.track_failures,
+                    )
+                    .map(|pipeline| AdditionalMicTrack {
+                        id,
+                        device_id,
+                        pipeline,
+                    })
+                })
+                .collect(),
             camera: finalize_optional_track(
                 RecordingTrackKind::Camera,
                 camera.transpose(),
@@ -560,10 +620,52 @@ impl Pipeline {
                 &self.track_failures,
             ),
             cursor: self.cursor,
+            additional: additional
+                .into_iter()
+                .filter_map(|(id, kind, result)| {
+                    finalize_optional_track(
+                        RecordingTrackKind::AdditionalVideo,
+                        result.map(Some),
+                        &self.track_failures,
+                    )
+                    .map(|pipeline| AdditionalVideoTrack {
+                        id,
+                        kind,
+                        pipeline,
+                    })
+                })
+                .collect(),
             track_failures: take_track_failures(&self.track_failures),
         })
     }
 
+    /// Forwards each track's health events into `health_tx`, which outlives
+    /// the segment.
+    fn forward_health(&mut self, health_tx: &HealthSender) {
+        let pipelines = std::iter::once(&mut self.screen)
+            .chain(self.microphone.as_mut())
+            .chain(
+                self.additional_mics
+                    .iter_mut()
+                    .map(|track| &mut track.pipeline),
+            )
+            .chain(self.camera.as_mut())
+            .chain(self.system_audio.as_mut())
+            .chain(self.additional.iter_mut().map(|track| &mut track.pipeline));
+
+        for pipeline in pipelines {
+            let Some(mut rx) = pipeline.take_health_rx() else {
+                continue;
+            };
+            let health_tx = health_tx.clone();
+            tokio::spawn(async move {
+                while let Some(event) = rx.recv().await {
+                    emit_health(&health_tx, event);
+                }
+            });
+        }
+    }
+
     fn spawn_watcher(
         &mut self,
         completion_tx: watch::Sender<Option<Result<(), PipelineDoneError>>>,
@@ -589,6 +691,19 @@ impl Pipeline {
             }));
         }
 
+        for track in &self.additional_mics {
+            futures.push(Box::pin({
+                let done_fut = track.pipeline.done_fut();
+                async move {
+                    (
+                        RecordingTrackKind::AdditionalMicrophone,
+                        false,
+                        done_fut.await,
+                    )
+                }
+            }));
+        }
+
         if let Some(ref camera) = self.camera {
             futures.push(Box::pin({
                 let done_fut = camera.done_fut();
@@ -603,11 +718,28 @@ impl Pipeline {
             }));
         }
 
+        for track in &self.additional {
+            futures.push(Box::pin({
+                let done_fut = track.pipeline.done_fut();
+                async move { (RecordingTrackKind::AdditionalVideo, false, done_fut.await) }
+            }));
+        }
+
         // Ensure non-video pipelines stop promptly when the video pipeline completes
         {
             let mic_cancel = self.microphone.as_ref().map(|p| p.cancel_token());
             let cam_cancel = self.camera.as_ref().map(|p| p.cancel_token());
             let sys_cancel = self.system_audio.as_ref().map(|p| p.cancel_token());
+            let additional_cancel = self
+                .additional_mics
+                .iter()
+                .map(|track| track.pipeline.cancel_token())
+                .chain(
+                    self.additional
+                        .iter()
+                        .map(|track| track.pipeline.cancel_token()),
+                )
+                .collect::<Vec<_>>();
 
             let screen_done = self.screen.done_fut();
             tokio::spawn(async move {
@@ -622,6 +754,9 @@ impl Pipeline {
                 if let Some(token) = sys_cancel.as_ref() {
                     token.cancel();
                 }
+                for token in &additional_cancel {
+                    token.cancel();
+                }
             });
         }
 
@@ -663,6 +798,15 @@ impl ActorHandle {
         self.done_fut.clone()
     }
 
+    /// Health events from every track of every segment. Can only be taken
+    /// once across all clones of the handle.
+    pub fn take_health_rx(&self) -> Option<HealthReceiver> {
+        self.health_rx
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
     pub async fn pause(&self) -> anyhow::Result<()> {
         Ok(self.actor_ref.ask(Pause).await?)
     }
@@ -707,7 +851,9 @@ pub struct ActorBuilder {
     output_path: PathBuf,
     capture_target: screen_capture::ScreenCaptureTarget,
     system_audio: bool,
+    app_audio: Option<AppAudioFilter>,
     mic_feed: Option<Arc<MicrophoneFeedLock>>,
+    additional_mics: Vec<Arc<MicrophoneFeedLock>>,
     camera_feed: Option<Arc<CameraFeedLock>>,
     custom_cursor: bool,
     keyboard_capture: bool,
@@ -717,6 +863,11 @@ pub struct ActorBuilder {
     quality: crate::StudioQuality,
     #[cfg(target_os = "macos")]
     excluded_windows: Vec<scap_targets::WindowId>,
+    additional_videos: Vec<AdditionalVideoInput>,
+    live_stream: Option<LiveStream>,
+    media_input: Option<MediaInputFeed>,
+    video_filters: VideoFilters,
+    encryption: Option<ProjectKey>,
 }
 
 impl ActorBuilder {
@@ -725,7 +876,9 @@ impl ActorBuilder {
             output_path: output,
             capture_target,
             system_audio: false,
+            app_audio: None,
             mic_feed: None,
+            additional_mics: Vec::new(),
             camera_feed: None,
             custom_cursor: false,
             keyboard_capture: true,
@@ -735,6 +888,11 @@ impl ActorBuilder {
             quality: crate::StudioQuality::Balanced,
             #[cfg(target_os = "macos")]
             excluded_windows: Vec::new(),
+            additional_videos: Vec::new(),
+            live_stream: None,
+            media_input: None,
+            video_filters: VideoFilters::default(),
+            encryption: None,
         }
     }
 
@@ -743,16 +901,66 @@ impl ActorBuilder {
         self
     }
 
+    /// Records system audio from only the applications `app_audio` selects.
+    /// Linux only; this also enables system audio.
+    pub fn with_app_audio(mut self, app_audio: AppAudioFilter) -> Self {
+        self.app_audio = Some(app_audio);
+        self
+    }
+
     pub fn with_mic_feed(mut self, mic_feed: Arc<MicrophoneFeedLock>) -> Self {
         self.mic_feed = Some(mic_feed);
         self
     }
 
+    /// Records another microphone as its own track, on the same clock as the
+    /// main mic. Can be called more than once; ignored without a mic feed.
+    pub fn with_additional_mic_feed(mut self, mic_feed: Arc<MicrophoneFeedLock>) -> Self {
+        self.additional_mics.push(mic_feed);
+        self
+    }
+
     pub fn with_camera_feed(mut self, camera_feed: Arc<CameraFeedLock>) -> Self {
         self.camera_feed = Some(camera_feed);
         self
     }
 
+    /// Records another camera or display into every segment as a
+    /// picture-in-picture source. Can be called more than once.
+    pub fn with_additional_video(mut self, input: AdditionalVideoInput) -> Self {
+        self.additional_videos.push(input);
+        self
+    }
+
+    /// Streams the display of every segment, with the microphone or else the
+    /// system audio, to `live_stream` while recording.
+    pub fn with_live_stream(mut self, live_stream: LiveStream) -> Self {
+        self.live_stream = Some(live_stream);
+        self
+    }
+
+    /// Records `media_input` in place of the capture target. Segments are
+    /// cursor-less and its audio track becomes the system audio track.
+    pub fn with_media_input(mut self, media_input: MediaInputFeed) -> Self {
+        self.media_input = Some(media_input);
+        self
+    }
+
+    /// Runs the screen and camera frames through `filters` before they are
+    /// encoded.
+    pub fn with_video_filters(mut self, filters: VideoFilters) -> Self {
+        self.video_filters = filters;
+        self
+    }
+
+    /// Encrypts everything the recording writes with `key`, which must have
+    /// been created for the output project. Encrypted recordings are always
+    /// fragmented, so each fragment can be sealed as soon as it is complete.
+    pub fn with_encryption(mut self, key: ProjectKey) -> Self {
+        self.encryption = Some(key);
+        self
+    }
+
     pub fn with_custom_cursor(mut self, custom_cursor: bool) -> Self {
         self.custom_cursor = custom_cursor;
         self
@@ -793,21 +1001,29 @@ impl ActorBuilder {
         self,
         #[cfg(target_os = "macos")] shareable_content: Option<SendableShareableContent>,
     ) -> anyhow::Result<ActorHandle> {
+        let encrypted = self.encryption.is_some();
         spawn_studio_recording_actor(
             self.output_path,
             RecordingBaseInputs {
                 capture_target: self.capture_target,
-                capture_system_audio: self.system_audio,
+                capture_system_audio: self.system_audio || self.app_audio.is_some(),
+                app_audio: self.app_audio,
                 mic_feed: self.mic_feed,
+                additional_mics: self.additional_mics,
                 camera_feed: self.camera_feed,
                 #[cfg(target_os = "macos")]
                 shareable_content,
                 #[cfg(target_os = "macos")]
                 excluded_windows: self.excluded_windows,
+                additional_videos: self.additional_videos,
+                live_stream: self.live_stream,
+                media_input: self.media_input,
+                video_filters: self.video_filters,
+                encryption: self.encryption,
             },
             self.custom_cursor,
             self.keyboard_capture,
-            self.fragmented,
+            self.fragmented || encrypted,
             self.use_oop_muxer,
             self.max_fps,
             self.quality,
@@ -839,6 +1055,7 @@ async fn spawn_studio_recording_actor(
 
     let (completion_tx, completion_rx) =
         watch::channel::<Option<Result<(), PipelineDoneError>>>(None);
+    let (health_tx, health_rx) = new_health_channel();
 
     if let Some(camera_feed) = &base_inputs.camera_feed {
         debug!("camera device info: {:#?}", camera_feed.camera_info());
@@ -860,10 +1077,11 @@ async fn spawn_studio_recording_actor(
         max_fps,
         quality,
         completion_tx.clone(),
+        health_tx,
     );
 
     if fragmented {
-        write_in_progress_meta(&recording_dir)?;
+        write_in_progress_meta(&recording_dir, base_inputs.encryption.as_ref())?;
     }
 
     let index = 0;
@@ -898,6 +1116,7 @@ async fn spawn_studio_recording_actor(
         actor_ref,
         capture_target: base_inputs.capture_target,
         done_fut,
+        health_rx: Arc::new(std::sync::Mutex::new(Some(health_rx))),
     })
 }
 
@@ -926,6 +1145,7 @@ async fn stop_recording(
     cursors: Cursors,
     fragmented: bool,
     display_notch: Option<cap_project::DisplayNotch>,
+    key: Option<&ProjectKey>,
 ) -> Result<CompletedRecording, RecordingError> {
     use cap_project::*;
     use cap_timestamp::{AUDIO_OUTPUT_FRAMES, DEFAULT_SAMPLE_RATE};
@@ -1068,6 +1288,22 @@ async fn stop_recording(
                         device_id: s.mic_device_id.clone(),
                         gap_summary: to_project_gap_summary(mic.audio_gap_summary),
                     }),
+                    additional_mics: s
+                        .pipeline
+                        .additional_mics
+                        .iter()
+                        .map(|track| AdditionalAudioMeta {
+                            id: track.id.clone(),
+                            audio: AudioMeta {
+                                path: make_relative(&track.pipeline.path),
+                                start_time: Some(to_start_time(track.pipeline.first_timestamp)),
+                                device_id: Some(track.device_id.clone()),
+                                gap_summary: to_project_gap_summary(
+                                    track.pipeline.audio_gap_summary,
+                                ),
+                            },
+                        })
+                        .collect(),
                     system_audio: s.pipeline.system_audio.map(|audio| {
                         let raw_sys_start = to_start_time(audio.first_timestamp);
                         let sys_start_time = if let Some(mic_start) = mic_start_time {
@@ -1103,6 +1339,26 @@ async fn stop_recording(
                             .map(make_relative)
                     }),
                     display_notch,
+                    additional_videos: s
+                        .pipeline
+                        .additional
+                        .iter()
+                        .map(|track| AdditionalVideoMeta {
+                            id: track.id.clone(),
+                            kind: track.kind,
+                            video: VideoMeta {
+                                path: make_relative(&track.pipeline.path),
+                                fps: track
+                                    .pipeline
+                                    .video_info
+                                    .map(|v| v.fps())
+                                    .unwrap_or(DEFAULT_FPS),
+                                start_time: Some(to_start_time(track.pipeline.first_timestamp)),
+                                device_id: None,
+                            },
+                        })
+                        .collect(),
+                    imported: None,
                 },
                 diagnostics,
                 duration: display_media_duration,
@@ -1184,7 +1440,7 @@ async fn stop_recording(
         },
     };
 
-    persist_final_recording_meta(&recording_dir, &meta);
+    persist_final_recording_meta(&recording_dir, &meta, key);
 
     let mut project_config = cap_project::ProjectConfiguration::default();
     if !timeline_segments.is_empty() {
@@ -1204,12 +1460,17 @@ async fn stop_recording(
     if let Some(clips) = clip_configs {
         project_config.clips = clips;
     }
-    project_config
-        .write(&recording_dir)
-        .map_err(RecordingError::from)?;
+    match key {
+        Some(key) => key.write(
+            &recording_dir.join("project-config.json"),
+            project_config.to_json()?.as_bytes(),
+        ),
+        None => project_config.write(&recording_dir),
+    }
+    .map_err(RecordingError::from)?;
 
     if let Some(diagnostics) = build_recording_failure_diagnostics(&segment_failure_diagnostics)
-        && let Err(error) = write_recording_failure_diagnostics(&recording_dir, &diagnostics)
+        && let Err(error) = write_recording_failure_diagnostics(&recording_dir, &diagnostics, key)
     {
         warn!(
             error = %error,
@@ -1239,6 +1500,7 @@ struct SegmentPipelineFactory {
     quality: crate::StudioQuality,
     index: u32,
     completion_tx: watch::Sender<Option<Result<(), PipelineDoneError>>>,
+    health_tx: HealthSender,
     #[cfg(windows)]
     encoder_preferences: crate::capture_pipeline::EncoderPreferences,
 }
@@ -1256,6 +1518,7 @@ impl SegmentPipelineFactory {
         max_fps: u32,
         quality: crate::StudioQuality,
         completion_tx: watch::Sender<Option<Result<(), PipelineDoneError>>>,
+        health_tx: HealthSender,
     ) -> Self {
         Self {
             segments_dir,
@@ -1269,6 +1532,7 @@ impl SegmentPipelineFactory {
             quality,
             index: 0,
             completion_tx,
+            health_tx,
             #[cfg(windows)]
             encoder_preferences: crate::capture_pipeline::EncoderPreferences::new(),
         }
@@ -1301,6 +1565,7 @@ impl SegmentPipelineFactory {
 
         self.index += 1;
 
+        pipeline.forward_health(&self.health_tx);
         pipeline.spawn_watcher(self.completion_tx.clone());
 
         Ok(pipeline)
@@ -1399,6 +1664,14 @@ async fn create_segment_pipeline(
     #[cfg(not(target_os = "macos"))]
     let segment_fragmented = fragmented;
 
+    // An unfragmented track is only complete once its segment stops, so it
+    // would stay plaintext until then.
+    if base_inputs.encryption.is_some() && !segment_fragmented {
+        return Err(anyhow!(
+            "Encrypted recordings must be fragmented, which recordings with a camera aren't on macOS"
+        ));
+    }
+
     let shared_pause_state = if segment_fragmented {
         Some(SharedPauseState::new(Arc::new(
             std::sync::atomic::AtomicBool::new(false),
@@ -1407,12 +1680,85 @@ async fn create_segment_pipeline(
         None
     };
 
-    let camera_only = matches!(
-        base_inputs.capture_target,
-        screen_capture::ScreenCaptureTarget::CameraOnly
-    );
+    // Fragments and init segments are sealed as soon as they're complete,
+    // audio files as soon as their muxer finishes, and manifests and cursor
+    // data as they are written; the rest of the segment, which is only
+    // finalised when it stops, is sealed then.
+    let fragment_sealer = base_inputs.encryption.clone().map(|key| {
+        let write_key = key.clone();
+        FragmentSealer::new(
+            move |path: &Path| key.seal_file(path),
+            move |path: &Path, contents: &[u8]| write_key.write(path, contents),
+        )
+    });
 
-    let (screen, system_audio, cursor_display) = if camera_only {
+    // Cursor events are stored relative to fixed crop bounds, which a followed
+    // window does not have, so its cursor is drawn into the video instead.
+    let custom_cursor_capture =
+        custom_cursor_capture && base_inputs.capture_target.follow_window().is_none();
+
+    let media_input = base_inputs.media_input.clone();
+    let camera_only = media_input.is_none()
+        && matches!(
+            base_inputs.capture_target,
+            screen_capture::ScreenCaptureTarget::CameraOnly
+        );
+
+    let screen_filters = base_inputs.video_filters.screen.clone();
+
+    let (screen, system_audio, cursor_display) = if let Some(media_input) = &media_input
+        && !screen_filters.is_empty()
+    {
+        let screen = create_filtered_video_pipeline::<sources::MediaVideoSource>(
+            sources::MediaVideoSourceConfig::new(media_input.clone()),
+            screen_filters,
+            &dir,
+            "display",
+            segment_fragmented,
+            shared_pause_state.clone(),
+            start_time,
+            base_inputs.live_stream.clone(),
+            fragment_sealer.clone(),
+        )
+        .instrument(error_span!("screen-out"))
+        .await
+        .context("media input screen pipeline setup")?;
+
+        (screen, None, None)
+    } else if let Some(media_input) = &media_input {
+        let builder = if segment_fragmented {
+            OutputPipeline::builder(dir.join("display"))
+        } else {
+            OutputPipeline::builder(screen_output_path.clone())
+        }
+        .with_video::<sources::MediaVideoSource>(sources::MediaVideoSourceConfig::new(
+            media_input.clone(),
+        ))
+        .with_timestamps(start_time)
+        .with_live_stream(base_inputs.live_stream.clone());
+
+        let screen = if segment_fragmented {
+            builder
+                .build::<crate::ffmpeg::SegmentedVideoMuxer>(
+                    crate::ffmpeg::SegmentedVideoMuxerConfig {
+                        segment_duration: Duration::from_secs(2),
+                        shared_pause_state: shared_pause_state.clone(),
+                        fragment_sealer: fragment_sealer.clone(),
+                        ..Default::default()
+                    },
+                )
+                .instrument(error_span!("screen-out"))
+                .await
+        } else {
+            builder
+                .build::<crate::ffmpeg::Mp4Muxer>(())
+                .instrument(error_span!("screen-out"))
+                .await
+        }
+        .context("media input screen pipeline setup")?;
+
+        (screen, None, None)
+    } else if camera_only {
         #[cfg(target_os = "linux")]
         {
             let camera_feed = base_inputs.camera_feed.clone().ok_or_else(|| {
@@ -1424,30 +1770,48 @@ async fn create_segment_pipeline(
                 )
             })?;
 
-            let builder = if segment_fragmented {
-                OutputPipeline::builder(dir.join("display"))
-            } else {
-                OutputPipeline::builder(screen_output_path.clone())
-            }
-            .with_video::<sources::Camera>(camera_feed)
-            .with_timestamps(start_time);
-
-            let screen = if segment_fragmented {
-                builder
-                    .build::<crate::ffmpeg::SegmentedVideoMuxer>(
-                        crate::ffmpeg::SegmentedVideoMuxerConfig {
-                            segment_duration: Duration::from_secs(2),
-                            shared_pause_state: shared_pause_state.clone(),
-                            ..Default::default()
-                        },
-                    )
-                    .instrument(error_span!("screen-out"))
-                    .await
+            let camera_filters = base_inputs.video_filters.camera.clone();
+            let screen = if !camera_filters.is_empty() {
+                create_filtered_video_pipeline::<sources::Camera>(
+                    camera_feed,
+                    camera_filters,
+                    &dir,
+                    "display",
+                    segment_fragmented,
+                    shared_pause_state.clone(),
+                    start_time,
+                    None,
+                    fragment_sealer.clone(),
+                )
+                .instrument(error_span!("screen-out"))
+                .await
             } else {
-                builder
-                    .build::<crate::ffmpeg::Mp4Muxer>(())
-                    .instrument(error_span!("screen-out"))
-                    .await
+                let builder = if segment_fragmented {
+                    OutputPipeline::builder(dir.join("display"))
+                } else {
+                    OutputPipeline::builder(screen_output_path.clone())
+                }
+                .with_video::<sources::Camera>(camera_feed)
+                .with_timestamps(start_time);
+
+                if segment_fragmented {
+                    builder
+                        .build::<crate::ffmpeg::SegmentedVideoMuxer>(
+                            crate::ffmpeg::SegmentedVideoMuxerConfig {
+                                segment_duration: Duration::from_secs(2),
+                                shared_pause_state: shared_pause_state.clone(),
+                                fragment_sealer: fragment_sealer.clone(),
+                                ..Default::default()
+                            },
+                        )
+                        .instrument(error_span!("screen-out"))
+                        .await
+                } else {
+                    builder
+                        .build::<crate::ffmpeg::Mp4Muxer>(())
+                        .instrument(error_span!("screen-out"))
+                        .await
+                }
             }
             .context("camera-only screen pipeline setup")?;
 
@@ -1464,6 +1828,7 @@ async fn create_segment_pipeline(
                     failed to initialize. Try reconnecting your camera or selecting a different one."
                 )
             })?;
+            base_inputs.video_filters.ensure_camera_supported()?;
 
             #[cfg(target_os = "macos")]
             let screen = OutputPipeline::builder(screen_output_path.clone())
@@ -1525,7 +1890,9 @@ async fn create_segment_pipeline(
             base_inputs.excluded_windows.clone(),
         )
         .await
-        .context("screen capture init")?;
+        .context("screen capture init")?
+        .with_follow_window(capture_target.follow_window())
+        .with_app_audio(base_inputs.app_audio.clone());
 
         let screen_info = screen_config.info();
         let output_size = calculate_gpu_compatible_size(
@@ -1536,20 +1903,45 @@ async fn create_segment_pipeline(
 
         let (capture_source, system_audio) = screen_config.to_sources().await?;
 
-        let screen = ScreenCaptureMethod::make_studio_mode_pipeline(
-            capture_source,
-            screen_output_path.clone(),
-            start_time,
-            segment_fragmented,
-            use_oop_muxer,
-            shared_pause_state.clone(),
-            output_size,
-            quality,
-            #[cfg(windows)]
-            encoder_preferences.clone(),
-        )
-        .instrument(error_span!("screen-out"))
-        .await
+        let screen = if screen_filters.is_empty() {
+            ScreenCaptureMethod::make_studio_mode_pipeline(
+                capture_source,
+                screen_output_path.clone(),
+                start_time,
+                segment_fragmented,
+                use_oop_muxer,
+                shared_pause_state.clone(),
+                output_size,
+                quality,
+                base_inputs.live_stream.clone(),
+                fragment_sealer.clone(),
+                #[cfg(windows)]
+                encoder_preferences.clone(),
+            )
+            .instrument(error_span!("screen-out"))
+            .await
+        } else {
+            // The encoder limit becomes the last filter, since the ffmpeg
+            // MP4 muxer can't scale on its own.
+            let mut screen_filters = screen_filters;
+            if let Some((width, height)) = output_size {
+                screen_filters.push(VideoFilterConfig::Downscale { width, height });
+            }
+
+            create_filtered_video_pipeline::<screen_capture::VideoSource>(
+                capture_source,
+                screen_filters,
+                &dir,
+                "display",
+                segment_fragmented,
+                shared_pause_state.clone(),
+                start_time,
+                base_inputs.live_stream.clone(),
+                fragment_sealer.clone(),
+            )
+            .instrument(error_span!("screen-out"))
+            .await
+        }
         .context("screen pipeline setup")?;
 
         (screen, system_audio, Some(display))
@@ -1559,6 +1951,8 @@ async fn create_segment_pipeline(
     let camera = if camera_only {
         None
     } else if let Some(camera_feed) = base_inputs.camera_feed {
+        base_inputs.video_filters.ensure_camera_supported()?;
+
         let pipeline = if segment_fragmented {
             let fragments_dir = dir.join("camera");
             OutputPipeline::builder(fragments_dir)
@@ -1566,6 +1960,7 @@ async fn create_segment_pipeline(
                 .with_timestamps(start_time)
                 .build::<MacOSFragmentedM4SCameraMuxer>(MacOSFragmentedM4SCameraMuxerConfig {
                     shared_pause_state: shared_pause_state.clone(),
+                    fragment_sealer: fragment_sealer.clone(),
                     ..Default::default()
                 })
                 .instrument(error_span!("camera-out"))
@@ -1590,6 +1985,8 @@ async fn create_segment_pipeline(
     let camera = if camera_only {
         None
     } else if let Some(camera_feed) = base_inputs.camera_feed {
+        base_inputs.video_filters.ensure_camera_supported()?;
+
         let pipeline = if segment_fragmented {
             let fragments_dir = dir.join("camera");
             OutputPipeline::builder(fragments_dir)
@@ -1597,6 +1994,7 @@ async fn create_segment_pipeline(
                 .with_timestamps(start_time)
                 .build::<WindowsFragmentedM4SCameraMuxer>(WindowsFragmentedM4SCameraMuxerConfig {
                     shared_pause_state: shared_pause_state.clone(),
+                    fragment_sealer: fragment_sealer.clone(),
                     ..Default::default()
                 })
                 .instrument(error_span!("camera-out"))
@@ -1621,7 +2019,22 @@ async fn create_segment_pipeline(
     let camera = if camera_only {
         None
     } else if let Some(camera_feed) = base_inputs.camera_feed {
-        let pipeline = if segment_fragmented {
+        let camera_filters = base_inputs.video_filters.camera.clone();
+        let pipeline = if !camera_filters.is_empty() {
+            create_filtered_video_pipeline::<sources::Camera>(
+                camera_feed,
+                camera_filters,
+                &dir,
+                "camera",
+                segment_fragmented,
+                shared_pause_state.clone(),
+                start_time,
+                None,
+                fragment_sealer.clone(),
+            )
+            .instrument(error_span!("camera-out"))
+            .await
+        } else if segment_fragmented {
             OutputPipeline::builder(dir.join("camera"))
                 .with_video::<sources::Camera>(camera_feed)
                 .with_timestamps(start_time)
@@ -1629,6 +2042,7 @@ async fn create_segment_pipeline(
                     crate::ffmpeg::SegmentedVideoMuxerConfig {
                         segment_duration: Duration::from_secs(2),
                         shared_pause_state: shared_pause_state.clone(),
+                        fragment_sealer: fragment_sealer.clone(),
                         ..Default::default()
                     },
                 )
@@ -1647,14 +2061,68 @@ async fn create_segment_pipeline(
         None
     };
 
+    let additional = if camera_only {
+        Vec::new()
+    } else {
+        create_additional_video_pipelines(
+            &dir,
+            &base_inputs.additional_videos,
+            start_time,
+            max_fps,
+            quality,
+            #[cfg(windows)]
+            &encoder_preferences,
+            #[cfg(target_os = "macos")]
+            base_inputs.shareable_content.clone(),
+        )
+        .await?
+    };
+
+    // A stream carries a single audio track: the microphone when there is
+    // one, otherwise the system audio.
+    let stream_system_audio = base_inputs.mic_feed.is_none();
+
+    // Every microphone shares one clock so their tracks stay aligned with
+    // each other without any syncing in the editor.
+    let mic_clock = MasterClock::new(start_time, AudioMixer::INFO.rate() as u32);
+
+    let additional_mics = if base_inputs.mic_feed.is_some() {
+        let mut additional_mics = Vec::with_capacity(base_inputs.additional_mics.len());
+        for (i, mic_feed) in base_inputs.additional_mics.iter().enumerate() {
+            let id = format!("mic-{}", i + 2);
+            let device_id = mic_feed.device_name().to_string();
+            let pipeline = OutputPipeline::builder(dir.join(format!("audio-input-{id}.ogg")))
+                .with_audio_source::<sources::Microphone>(mic_feed.clone())
+                .with_timestamps(start_time)
+                .with_master_clock(mic_clock.clone())
+                .build::<OggMuxer>(OggMuxerConfig {
+                    fragment_sealer: fragment_sealer.clone(),
+                })
+                .instrument(error_span!("additional-mic-out", id = %id))
+                .await
+                .with_context(|| format!("{id} pipeline setup"))?;
+            additional_mics.push(AdditionalMicTrack {
+                id,
+                device_id,
+                pipeline,
+            });
+        }
+        additional_mics
+    } else {
+        Vec::new()
+    };
+
     let microphone = if let Some(mic_feed) = base_inputs.mic_feed {
         let pipeline = if segment_fragmented {
             let output_path = dir.join("audio-input.m4a");
             OutputPipeline::builder(output_path)
                 .with_audio_source::<sources::Microphone>(mic_feed)
                 .with_timestamps(start_time)
+                .with_master_clock(mic_clock.clone())
+                .with_live_stream(base_inputs.live_stream.clone())
                 .build::<FragmentedAudioMuxer>(FragmentedAudioMuxerConfig {
                     shared_pause_state: shared_pause_state.clone(),
+                    fragment_sealer: fragment_sealer.clone(),
                 })
                 .instrument(error_span!("mic-out"))
                 .await
@@ -1662,7 +2130,11 @@ async fn create_segment_pipeline(
             OutputPipeline::builder(dir.join("audio-input.ogg"))
                 .with_audio_source::<sources::Microphone>(mic_feed)
                 .with_timestamps(start_time)
-                .build::<OggMuxer>(())
+                .with_master_clock(mic_clock)
+                .with_live_stream(base_inputs.live_stream.clone())
+                .build::<OggMuxer>(OggMuxerConfig {
+                    fragment_sealer: fragment_sealer.clone(),
+                })
                 .instrument(error_span!("mic-out"))
                 .await
         };
@@ -1671,7 +2143,41 @@ async fn create_segment_pipeline(
         None
     };
 
-    let system_audio = if let Some(system_audio_source) = system_audio {
+    let media_audio = media_input
+        .clone()
+        .filter(|media_input| media_input.audio_info().is_some());
+
+    let system_audio = if let Some(media_input) = media_audio {
+        let live_stream = base_inputs
+            .live_stream
+            .clone()
+            .filter(|_| stream_system_audio);
+        let config = sources::MediaAudioSourceConfig::new(media_input);
+
+        let pipeline = if segment_fragmented {
+            OutputPipeline::builder(dir.join("system_audio.m4a"))
+                .with_audio_source::<sources::MediaAudioSource>(config)
+                .with_timestamps(start_time)
+                .with_live_stream(live_stream)
+                .build::<FragmentedAudioMuxer>(FragmentedAudioMuxerConfig {
+                    shared_pause_state: shared_pause_state.clone(),
+                    fragment_sealer: fragment_sealer.clone(),
+                })
+                .instrument(error_span!("system-audio-out"))
+                .await
+        } else {
+            OutputPipeline::builder(dir.join("system_audio.ogg"))
+                .with_audio_source::<sources::MediaAudioSource>(config)
+                .with_timestamps(start_time)
+                .with_live_stream(live_stream)
+                .build::<OggMuxer>(OggMuxerConfig {
+                    fragment_sealer: fragment_sealer.clone(),
+                })
+                .instrument(error_span!("system-audio-out"))
+                .await
+        };
+        Some(pipeline.context("media input audio pipeline setup")?)
+    } else if let Some(system_audio_source) = system_audio {
         // System audio is intermittent (WASAPI loopback only delivers while
         // sound plays), so its first packet is not a "source ready" marker:
         // anchor the track at the recording epoch. This keeps a late first
@@ -1683,8 +2189,15 @@ async fn create_segment_pipeline(
                 .with_audio_source::<screen_capture::SystemAudioSource>(system_audio_source)
                 .with_timestamps(start_time)
                 .with_audio_anchor(AudioAnchor::PipelineEpoch)
+                .with_live_stream(
+                    base_inputs
+                        .live_stream
+                        .clone()
+                        .filter(|_| stream_system_audio),
+                )
                 .build::<FragmentedAudioMuxer>(FragmentedAudioMuxerConfig {
                     shared_pause_state: shared_pause_state.clone(),
+                    fragment_sealer: fragment_sealer.clone(),
                 })
                 .instrument(error_span!("system-audio-out"))
                 .await
@@ -1693,7 +2206,15 @@ async fn create_segment_pipeline(
                 .with_audio_source::<screen_capture::SystemAudioSource>(system_audio_source)
                 .with_timestamps(start_time)
                 .with_audio_anchor(AudioAnchor::PipelineEpoch)
-                .build::<OggMuxer>(())
+                .with_live_stream(
+                    base_inputs
+                        .live_stream
+                        .clone()
+                        .filter(|_| stream_system_audio),
+                )
+                .build::<OggMuxer>(OggMuxerConfig {
+                    fragment_sealer: fragment_sealer.clone(),
+                })
                 .instrument(error_span!("system-audio-out"))
                 .await
         };
@@ -1702,7 +2223,8 @@ async fn create_segment_pipeline(
         None
     };
 
-    let cursor = if camera_only {
+    let cursor_encryption = base_inputs.encryption.clone();
+    let cursor = if camera_only || media_input.is_some() {
         None
     } else {
         (custom_cursor_capture || keyboard_capture)
@@ -1737,6 +2259,7 @@ async fn create_segment_pipeline(
                     IncrementalCaptureOutputs {
                         cursor: incremental_output,
                         keyboard: keyboard_incremental_output,
+                        encryption: cursor_encryption,
                     },
                 );
 
@@ -1755,14 +2278,205 @@ async fn create_segment_pipeline(
         start_time,
         screen,
         microphone,
+        additional_mics,
         camera,
         cursor,
         system_audio,
+        additional,
         track_failures: Arc::new(std::sync::Mutex::new(Vec::new())),
         watcher_task: None,
     })
 }
 
+/// Records `config`'s frames through `filters` into the segment's `name`
+/// track: a directory of fragments when `fragmented`, `<name>.mp4` otherwise.
+/// Filtered frames are in system memory, so this uses the ffmpeg muxers on
+/// every platform.
+#[allow(clippy::too_many_arguments)]
+async fn create_filtered_video_pipeline<S: VideoSource>(
+    config: S::Config,
+    filters: Vec<VideoFilterConfig>,
+    dir: &Path,
+    name: &str,
+    fragmented: bool,
+    shared_pause_state: Option<SharedPauseState>,
+    start_time: Timestamps,
+    live_stream: Option<LiveStream>,
+    fragment_sealer: Option<FragmentSealer>,
+) -> anyhow::Result<OutputPipeline>
+where
+    S::Config: Send,
+{
+    let builder = if fragmented {
+        OutputPipeline::builder(dir.join(name))
+    } else {
+        OutputPipeline::builder(dir.join(format!("{name}.mp4")))
+    }
+    .with_video::<FilteredVideoSource<S>>(FilteredVideoSourceConfig::new(config, filters))
+    .with_timestamps(start_time)
+    .with_live_stream(live_stream);
+
+    if fragmented {
+        builder
+            .build::<crate::ffmpeg::SegmentedVideoMuxer>(crate::ffmpeg::SegmentedVideoMuxerConfig {
+                segment_duration: Duration::from_secs(2),
+                shared_pause_state,
+                fragment_sealer,
+                ..Default::default()
+            })
+            .await
+    } else {
+        builder.build::<crate::ffmpeg::Mp4Muxer>(()).await
+    }
+}
+
+/// Extra sources are always written as progressive MP4s (`camera-1.mp4`,
+/// `display-1.mp4`, ...) next to the segment's main tracks.
+#[allow(clippy::too_many_arguments)]
+async fn create_additional_video_pipelines(
+    dir: &Path,
+    inputs: &[AdditionalVideoInput],
+    start_time: Timestamps,
+    max_fps: u32,
+    #[cfg_attr(target_os = "linux", allow(unused_variables))] quality: crate::StudioQuality,
+    #[cfg(windows)] encoder_preferences: &crate::capture_pipeline::EncoderPreferences,
+    #[cfg(target_os = "macos")] shareable_content: Option<SendableShareableContent>,
+) -> anyhow::Result<Vec<AdditionalVideoTrack<OutputPipeline>>> {
+    let mut tracks = Vec::with_capacity(inputs.len());
+    let (mut cameras, mut displays) = (0, 0);
+
+    for input in inputs {
+        let (id, kind) = match input {
+            AdditionalVideoInput::Camera(_) => {
+                cameras += 1;
+                (format!("camera-{cameras}"), AdditionalVideoKind::Camera)
+            }
+            AdditionalVideoInput::Display(_) => {
+                displays += 1;
+                (format!("display-{displays}"), AdditionalVideoKind::Display)
+            }
+        };
+        let output_path = dir.join(format!("{id}.mp4"));
+
+        let pipeline = match input {
+            AdditionalVideoInput::Camera(camera_feed) => {
+                #[cfg(target_os = "macos")]
+                {
+                    OutputPipeline::builder(output_path)
+                        .with_video::<sources::NativeCamera>(camera_feed.clone())
+                        .with_timestamps(start_time)
+                        .build::<AVFoundationCameraMuxer>(AVFoundationCameraMuxerConfig {
+                            compatibility_quality: matches!(
+                                quality,
+                                crate::StudioQuality::Compatibility
+                            ),
+                            ..Default::default()
+                        })
+                        .instrument(error_span!("additional-out", id = %id))
+                        .await
+                }
+
+                #[cfg(windows)]
+                {
+                    OutputPipeline::builder(output_path)
+                        .with_video::<sources::NativeCamera>(camera_feed.clone())
+                        .with_timestamps(start_time)
+                        .build::<WindowsCameraMuxer>(WindowsCameraMuxerConfig {
+                            encoder_preferences: encoder_preferences.clone(),
+                            ..Default::default()
+                        })
+                        .instrument(error_span!("additional-out", id = %id))
+                        .await
+                }
+
+                #[cfg(target_os = "linux")]
+                {
+                    OutputPipeline::builder(output_path)
+                        .with_video::<sources::Camera>(camera_feed.clone())
+                        .with_timestamps(start_time)
+                        .build::<crate::ffmpeg::Mp4Muxer>(())
+                        .instrument(error_span!("additional-out", id = %id))
+                        .await
+                }
+            }
+            AdditionalVideoInput::Display(target) => {
+                let (display, crop) =
+                    target_to_display_and_crop(target).context("target_display_crop")?;
+
+                let screen_config = ScreenCaptureConfig::<ScreenCaptureMethod>::init(
+                    display,
+                    crop,
+                    true,
+                    max_fps,
+                    None,
+                    start_time.system_time(),
+                    false,
+                    #[cfg(target_os = "linux")]
+                    sources::screen_capture::LinuxCaptureSource::from_target(target),
+                    #[cfg(windows)]
+                    crate::capture_pipeline::create_d3d_device()
+                        .context("D3D11 device creation failed")?,
+                    #[cfg(target_os = "macos")]
+                    shareable_content
+                        .clone()
+                        .ok_or_else(|| anyhow!("Missing shareable content"))?,
+                    #[cfg(target_os = "macos")]
+                    Vec::new(),
+                )
+                .await
+                .with_context(|| format!("{id} screen capture init"))?
+                .with_follow_window(target.follow_window());
+
+                let (capture_source, _) = screen_config.to_sources().await?;
+
+                // The Linux studio screen pipeline always writes fragments, so
+                // mux extra displays directly instead.
+                #[cfg(target_os = "linux")]
+                {
+                    OutputPipeline::builder(output_path)
+                        .with_video::<screen_capture::VideoSource>(capture_source)
+                        .with_timestamps(start_time)
+                        .build::<crate::ffmpeg::Mp4Muxer>(())
+                        .instrument(error_span!("additional-out", id = %id))
+                        .await
+                }
+
+                #[cfg(not(target_os = "linux"))]
+                {
+                    let screen_info = screen_config.info();
+                    let output_size = calculate_gpu_compatible_size(
+                        screen_info.width,
+                        screen_info.height,
+                        H264_MAX_DIMENSION,
+                    );
+
+                    ScreenCaptureMethod::make_studio_mode_pipeline(
+                        capture_source,
+                        output_path,
+                        start_time,
+                        false,
+                        false,
+                        None,
+                        output_size,
+                        quality,
+                        None,
+                        None,
+                        #[cfg(windows)]
+                        encoder_preferences.clone(),
+                    )
+                    .instrument(error_span!("additional-out", id = %id))
+                    .await
+                }
+            }
+        }
+        .with_context(|| format!("{id} pipeline setup"))?;
+
+        tracks.push(AdditionalVideoTrack { id, kind, pipeline });
+    }
+
+    Ok(tracks)
+}
+
 fn ensure_dir(path: &PathBuf) -> Result<PathBuf, MediaError> {
     std::fs::create_dir_all(path)?;
     Ok(path.clone())
@@ -1775,7 +2489,11 @@ fn current_time_f64() -> f64 {
         .as_secs_f64()
 }
 
-fn persist_final_recording_meta(recording_dir: &Path, studio_meta: &StudioRecordingMeta) {
+fn persist_final_recording_meta(
+    recording_dir: &Path,
+    studio_meta: &StudioRecordingMeta,
+    key: Option<&ProjectKey>,
+) {
     use chrono::Local;
 
     let pretty_name = Local::now().format("Cap %Y-%m-%d at %H.%M.%S").to_string();
@@ -1788,7 +2506,7 @@ fn persist_final_recording_meta(recording_dir: &Path, studio_meta: &StudioRecord
         upload: None,
     };
 
-    if let Err(err) = recording_meta.save_for_project() {
+    if let Err(err) = save_recording_meta(&recording_meta, key) {
         warn!(
             error = ?err,
             path = %recording_dir.join("recording-meta.json").display(),
@@ -1797,7 +2515,7 @@ fn persist_final_recording_meta(recording_dir: &Path, studio_meta: &StudioRecord
     }
 }
 
-fn write_in_progress_meta(recording_dir: &Path) -> anyhow::Result<()> {
+fn write_in_progress_meta(recording_dir: &Path, key: Option<&ProjectKey>) -> anyhow::Result<()> {
     use chrono::Local;
 
     let pretty_name = Local::now().format("Cap %Y-%m-%d at %H.%M.%S").to_string();
@@ -1817,8 +2535,19 @@ fn write_in_progress_meta(recording_dir: &Path) -> anyhow::Result<()> {
         upload: None,
     };
 
-    meta.save_for_project()
-        .map_err(|e| anyhow!("Failed to save in-progress meta: {:?}", e))
+    save_recording_meta(&meta, key).context("Failed to save in-progress meta")
+}
+
+/// Saves `meta` to its project's `recording-meta.json`, sealed when there is a
+/// `key` so a recording that crashes never leaves it plaintext.
+fn save_recording_meta(meta: &RecordingMeta, key: Option<&ProjectKey>) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(meta)?;
+    encryption::write_file(
+        key,
+        &meta.project_path.join("recording-meta.json"),
+        json.as_bytes(),
+    )?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -2008,9 +2737,11 @@ mod tests {
                     None,
                     0,
                 )),
+                additional_mics: Vec::new(),
                 camera: None,
                 system_audio: None,
                 cursor: None,
+                additional: Vec::new(),
                 track_failures: Vec::new(),
             },
             camera_device_id: None,
@@ -2023,6 +2754,7 @@ mod tests {
             Default::default(),
             false,
             None,
+            None,
         )
         .await
         .expect("recording should stop");
@@ -2202,9 +2934,11 @@ mod tests {
                     1,
                 ),
                 microphone: None,
+                additional_mics: Vec::new(),
                 camera: None,
                 system_audio: None,
                 cursor: None,
+                additional: Vec::new(),
                 track_failures: vec![TrackFailureRecord {
                     track: RecordingTrackKind::Microphone,
                     stage: TrackFailureStage::Runtime,
@@ -2221,6 +2955,7 @@ mod tests {
             Default::default(),
             false,
             None,
+            None,
         )
         .await
         .expect("diagnostics sidecar failure should not abort stop_recording");
@@ -2274,9 +3009,11 @@ mod tests {
             start_time: timestamps,
             screen,
             microphone: Some(microphone),
+            additional_mics: Vec::new(),
             camera: None,
             system_audio: None,
             cursor: None,
+            additional: Vec::new(),
             track_failures: Arc::new(std::sync::Mutex::new(Vec::new())),
             watcher_task: None,
         };
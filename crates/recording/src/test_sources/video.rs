@@ -118,7 +118,9 @@ impl VideoSource for TestPatternVideoSource {
     }
 }
 
-fn generate_video_frame(
+/// Draws frame `frame_number` of `pattern` in `info`'s size and pixel format,
+/// exactly as [`TestPatternVideoSource`] emits it.
+pub fn generate_video_frame(
     info: &VideoInfo,
     pattern: TestPattern,
     frame_number: u64,
@@ -0,0 +1,839 @@
+//! User-level video filters applied between capture and encode.
+//!
+//! A [`VideoFilterChain`] runs each captured frame through a list of
+//! [`VideoFilter`]s on the CPU: privacy blur of fixed rectangles, watermark
+//! and timestamp burn-in, and downscaling. [`FilteredVideoSource`] wraps any
+//! [`VideoSource`] whose frames can be read back and feeds the filtered frames
+//! to the ffmpeg muxers, so the filters end up in the recording itself. That
+//! matters for instant recordings, which have no editor step to add them in.
+
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context as _;
+use cap_media_info::VideoInfo;
+use cap_rendering::label::{LabelMask, LabelRasterizer};
+use cap_timestamp::Timestamps;
+use ffmpeg::format::Pixel;
+use futures::{SinkExt, StreamExt, channel::mpsc, future::BoxFuture};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ffmpeg::FFmpegVideoFrame,
+    output_pipeline::{SetupCtx, VideoFrame, VideoSource},
+};
+
+/// Pixel format frames are converted to when they don't already use a packed
+/// 32-bit format the filters can draw on.
+const WORKING_FORMAT: Pixel = Pixel::BGRA;
+
+const DEFAULT_BLUR_BLOCK_SIZE: u32 = 16;
+
+/// A frame on its way through a [`VideoFilterChain`].
+pub struct FilterFrame {
+    /// Always in a packed 32-bit format such as BGRA.
+    pub video: ffmpeg::frame::Video,
+    /// How far into the recording the frame was captured.
+    pub elapsed: Duration,
+    /// When the frame was captured.
+    pub wall_clock: SystemTime,
+}
+
+pub trait VideoFilter: Send + 'static {
+    /// The size of the frames this filter produces from `input`-sized ones.
+    fn output_size(&self, input: (u32, u32)) -> (u32, u32) {
+        input
+    }
+
+    fn apply(&mut self, frame: &mut FilterFrame) -> anyhow::Result<()>;
+}
+
+/// An area of a frame, in the pixels of the frames the filter receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Which corner of the frame a text overlay is drawn in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl FromStr for OverlayPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            other => Err(format!(
+                "Unknown position '{other}' (expected top-left, top-right, bottom-left or bottom-right)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OverlayPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        })
+    }
+}
+
+/// One filter of a source's chain, as configured by the user.
+///
+/// Also parses from the command-line form: `blur=X,Y,W,H`, `watermark=TEXT`,
+/// `timestamp` and `downscale=WxH`. Overlays take an optional corner, as in
+/// `watermark:top-left=TEXT` or `timestamp:bottom-left`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum VideoFilterConfig {
+    /// Pixelates an area so whatever is in it can't be read.
+    Blur { area: FilterRect },
+    /// Burns fixed text into every frame.
+    Watermark {
+        text: String,
+        #[serde(default)]
+        position: OverlayPosition,
+    },
+    /// Burns the local wall-clock time each frame was captured at into it.
+    Timestamp {
+        #[serde(default = "default_timestamp_position")]
+        position: OverlayPosition,
+    },
+    /// Scales frames down to fit within `width`x`height`, keeping their
+    /// aspect ratio. Smaller frames are left alone.
+    Downscale { width: u32, height: u32 },
+}
+
+fn default_timestamp_position() -> OverlayPosition {
+    OverlayPosition::BottomLeft
+}
+
+impl VideoFilterConfig {
+    pub fn build(&self) -> Box<dyn VideoFilter> {
+        match self {
+            Self::Blur { area } => Box::new(BlurFilter::new(*area)),
+            Self::Watermark { text, position } => Box::new(TextOverlayFilter::new(
+                OverlayText::Fixed(text.clone()),
+                *position,
+            )),
+            Self::Timestamp { position } => {
+                Box::new(TextOverlayFilter::new(OverlayText::WallClock, *position))
+            }
+            Self::Downscale { width, height } => Box::new(DownscaleFilter::new(*width, *height)),
+        }
+    }
+}
+
+impl FromStr for VideoFilterConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (head, value) = match s.split_once('=') {
+            Some((head, value)) => (head, Some(value)),
+            None => (s, None),
+        };
+        let (kind, position) = match head.split_once(':') {
+            Some((kind, position)) => (kind, Some(position.parse::<OverlayPosition>()?)),
+            None => (head, None),
+        };
+        if position.is_some() && !matches!(kind, "watermark" | "timestamp") {
+            return Err(format!("The {kind} filter doesn't take a position"));
+        }
+
+        match (kind, value) {
+            ("blur", Some(value)) => {
+                let numbers = value
+                    .split(',')
+                    .map(|n| n.trim().parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()
+                    .filter(|numbers| numbers.len() == 4)
+                    .ok_or_else(|| format!("Invalid blur area '{value}' (expected X,Y,W,H)"))?;
+                if numbers[2] == 0 || numbers[3] == 0 {
+                    return Err("A blur area must have a non-zero width and height".to_string());
+                }
+                Ok(Self::Blur {
+                    area: FilterRect {
+                        x: numbers[0],
+                        y: numbers[1],
+                        width: numbers[2],
+                        height: numbers[3],
+                    },
+                })
+            }
+            ("watermark", Some(text)) if !text.trim().is_empty() => Ok(Self::Watermark {
+                text: text.to_string(),
+                position: position.unwrap_or_default(),
+            }),
+            ("watermark", _) => Err("A watermark needs some text, e.g. watermark=TEXT".to_string()),
+            ("timestamp", None) => Ok(Self::Timestamp {
+                position: position.unwrap_or_else(default_timestamp_position),
+            }),
+            ("downscale", Some(value)) => {
+                let (width, height) = value
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse::<u32>().ok()?, h.parse::<u32>().ok()?)))
+                    .filter(|(w, h)| *w >= 2 && *h >= 2)
+                    .ok_or_else(|| {
+                        format!("Invalid downscale size '{value}' (expected WxH, e.g. 1280x720)")
+                    })?;
+                Ok(Self::Downscale { width, height })
+            }
+            ("blur" | "downscale", None) => Err(format!("The {kind} filter needs a value")),
+            ("timestamp", Some(_)) => Err("The timestamp filter doesn't take a value".to_string()),
+            (other, _) => Err(format!(
+                "Unknown filter '{other}' (expected blur, watermark, timestamp or downscale)"
+            )),
+        }
+    }
+}
+
+/// The filters applied to each kind of source.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoFilters {
+    #[serde(default)]
+    pub screen: Vec<VideoFilterConfig>,
+    #[serde(default)]
+    pub camera: Vec<VideoFilterConfig>,
+}
+
+impl VideoFilters {
+    /// Cameras on macOS and Windows hand over frames that stay on the GPU or
+    /// in the capture framework's buffers, which the filters can't draw on.
+    #[cfg(any(target_os = "macos", windows))]
+    pub(crate) fn ensure_camera_supported(&self) -> anyhow::Result<()> {
+        if !self.camera.is_empty() {
+            anyhow::bail!("Camera video filters are only supported on Linux");
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs frames through a list of filters, converting them to a format the
+/// filters can draw on first.
+pub struct VideoFilterChain {
+    filters: Vec<Box<dyn VideoFilter>>,
+    converter: Option<Scaler>,
+}
+
+impl VideoFilterChain {
+    pub fn new(filters: &[VideoFilterConfig]) -> Self {
+        Self {
+            filters: filters.iter().map(VideoFilterConfig::build).collect(),
+            converter: None,
+        }
+    }
+
+    /// The format and size of the frames this chain produces from `input`.
+    /// An empty chain passes frames through untouched.
+    pub fn output_info(&self, input: VideoInfo) -> VideoInfo {
+        if self.filters.is_empty() {
+            return input;
+        }
+
+        let (width, height) = self
+            .filters
+            .iter()
+            .fold((input.width, input.height), |size, filter| {
+                filter.output_size(size)
+            });
+
+        VideoInfo {
+            pixel_format: working_format(input.pixel_format),
+            width,
+            height,
+            ..input
+        }
+    }
+
+    pub fn apply(
+        &mut self,
+        video: ffmpeg::frame::Video,
+        elapsed: Duration,
+        wall_clock: SystemTime,
+    ) -> anyhow::Result<ffmpeg::frame::Video> {
+        if self.filters.is_empty() {
+            return Ok(video);
+        }
+
+        let video = if is_packed_32(video.format()) {
+            video
+        } else {
+            let (width, height) = (video.width(), video.height());
+            Scaler::get(&mut self.converter, &video, WORKING_FORMAT, width, height)?.run(&video)?
+        };
+
+        let mut frame = FilterFrame {
+            video,
+            elapsed,
+            wall_clock,
+        };
+        for filter in &mut self.filters {
+            filter.apply(&mut frame)?;
+        }
+
+        Ok(frame.video)
+    }
+}
+
+fn is_packed_32(format: Pixel) -> bool {
+    matches!(
+        format,
+        Pixel::BGRA
+            | Pixel::RGBA
+            | Pixel::ARGB
+            | Pixel::ABGR
+            | Pixel::BGRZ
+            | Pixel::RGBZ
+            | Pixel::ZRGB
+            | Pixel::ZBGR
+    )
+}
+
+fn working_format(format: Pixel) -> Pixel {
+    if is_packed_32(format) {
+        format
+    } else {
+        WORKING_FORMAT
+    }
+}
+
+/// Byte offsets of the colour channels in a packed 32-bit pixel, skipping
+/// alpha or padding.
+fn color_channels(format: Pixel) -> [usize; 3] {
+    match format {
+        Pixel::ARGB | Pixel::ABGR | Pixel::ZRGB | Pixel::ZBGR => [1, 2, 3],
+        _ => [0, 1, 2],
+    }
+}
+
+/// Pixelates a rectangle of each frame.
+pub struct BlurFilter {
+    area: FilterRect,
+    block_size: u32,
+}
+
+impl BlurFilter {
+    pub fn new(area: FilterRect) -> Self {
+        Self {
+            area,
+            block_size: DEFAULT_BLUR_BLOCK_SIZE,
+        }
+    }
+}
+
+impl VideoFilter for BlurFilter {
+    fn apply(&mut self, frame: &mut FilterFrame) -> anyhow::Result<()> {
+        let video = &mut frame.video;
+        let (frame_width, frame_height) = (video.width(), video.height());
+        let x0 = self.area.x.min(frame_width);
+        let y0 = self.area.y.min(frame_height);
+        let x1 = self.area.x.saturating_add(self.area.width).min(frame_width);
+        let y1 = self
+            .area
+            .y
+            .saturating_add(self.area.height)
+            .min(frame_height);
+
+        let stride = video.stride(0);
+        let data = video.data_mut(0);
+
+        for block_y in (y0..y1).step_by(self.block_size as usize) {
+            let block_y1 = (block_y + self.block_size).min(y1);
+            for block_x in (x0..x1).step_by(self.block_size as usize) {
+                let block_x1 = (block_x + self.block_size).min(x1);
+                let pixels = ((block_x1 - block_x) * (block_y1 - block_y)) as u64;
+
+                let mut sums = [0u64; 4];
+                for y in block_y..block_y1 {
+                    for x in block_x..block_x1 {
+                        let offset = y as usize * stride + x as usize * 4;
+                        for (sum, value) in sums.iter_mut().zip(&data[offset..offset + 4]) {
+                            *sum += *value as u64;
+                        }
+                    }
+                }
+
+                let average = sums.map(|sum| (sum / pixels) as u8);
+                for y in block_y..block_y1 {
+                    for x in block_x..block_x1 {
+                        let offset = y as usize * stride + x as usize * 4;
+                        data[offset..offset + 4].copy_from_slice(&average);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a [`TextOverlayFilter`] draws.
+pub enum OverlayText {
+    Fixed(String),
+    /// The local time the frame was captured at.
+    WallClock,
+}
+
+impl OverlayText {
+    fn render(&self, frame: &FilterFrame) -> String {
+        match self {
+            Self::Fixed(text) => text.clone(),
+            Self::WallClock => chrono::DateTime::<chrono::Local>::from(frame.wall_clock)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        }
+    }
+}
+
+/// Draws white text on a translucent dark box in a corner of each frame,
+/// sized relative to the frame height.
+pub struct TextOverlayFilter {
+    text: OverlayText,
+    position: OverlayPosition,
+    rasterizer: Option<LabelRasterizer>,
+    label: Option<(String, u32, LabelMask)>,
+}
+
+impl TextOverlayFilter {
+    pub fn new(text: OverlayText, position: OverlayPosition) -> Self {
+        Self {
+            text,
+            position,
+            rasterizer: None,
+            label: None,
+        }
+    }
+
+    fn label(&mut self, text: String, font_size: u32) -> &LabelMask {
+        let stale = self
+            .label
+            .as_ref()
+            .is_none_or(|(cached, size, _)| *cached != text || *size != font_size);
+        if stale {
+            // Loading the system fonts is slow, so it waits for the first
+            // frame rather than pipeline setup.
+            let mask = self
+                .rasterizer
+                .get_or_insert_with(LabelRasterizer::new)
+                .rasterize(&text, font_size as f32);
+            self.label = Some((text, font_size, mask));
+        }
+
+        &self.label.as_ref().expect("label was just cached").2
+    }
+}
+
+impl VideoFilter for TextOverlayFilter {
+    fn apply(&mut self, frame: &mut FilterFrame) -> anyhow::Result<()> {
+        let (frame_width, frame_height) = (frame.video.width(), frame.video.height());
+        let font_size = (frame_height / 36).max(12);
+        let padding = font_size / 3;
+        let margin = font_size / 2;
+
+        let text = self.text.render(frame);
+        let position = self.position;
+        let channels = color_channels(frame.video.format());
+        let label = self.label(text, font_size);
+
+        let box_width = (label.width + padding * 2).min(frame_width);
+        let box_height = (label.height + padding * 2).min(frame_height);
+        let free_x = frame_width - box_width;
+        let free_y = frame_height - box_height;
+        let (box_x, box_y) = match position {
+            OverlayPosition::TopLeft => (margin.min(free_x), margin.min(free_y)),
+            OverlayPosition::TopRight => (free_x.saturating_sub(margin), margin.min(free_y)),
+            OverlayPosition::BottomLeft => (margin.min(free_x), free_y.saturating_sub(margin)),
+            OverlayPosition::BottomRight => {
+                (free_x.saturating_sub(margin), free_y.saturating_sub(margin))
+            }
+        };
+
+        let stride = frame.video.stride(0);
+        let data = frame.video.data_mut(0);
+        for y in 0..box_height {
+            for x in 0..box_width {
+                let offset = (box_y + y) as usize * stride + (box_x + x) as usize * 4;
+                let coverage = match (x.checked_sub(padding), y.checked_sub(padding)) {
+                    (Some(label_x), Some(label_y))
+                        if label_x < label.width && label_y < label.height =>
+                    {
+                        label.coverage_at(label_x, label_y) as u32
+                    }
+                    _ => 0,
+                };
+
+                for channel in channels {
+                    let darkened = data[offset + channel] as u32 / 2;
+                    data[offset + channel] = (darkened + (255 - darkened) * coverage / 255) as u8;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Scales frames down to fit within a maximum size.
+pub struct DownscaleFilter {
+    max_size: (u32, u32),
+    scaler: Option<Scaler>,
+}
+
+impl DownscaleFilter {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            max_size: (width, height),
+            scaler: None,
+        }
+    }
+}
+
+impl VideoFilter for DownscaleFilter {
+    fn output_size(&self, input: (u32, u32)) -> (u32, u32) {
+        downscaled_size(input, self.max_size)
+    }
+
+    fn apply(&mut self, frame: &mut FilterFrame) -> anyhow::Result<()> {
+        let input = (frame.video.width(), frame.video.height());
+        let (width, height) = self.output_size(input);
+        if (width, height) == input {
+            return Ok(());
+        }
+
+        let format = frame.video.format();
+        frame.video = Scaler::get(&mut self.scaler, &frame.video, format, width, height)?
+            .run(&frame.video)?;
+
+        Ok(())
+    }
+}
+
+/// The largest even size within `max` with `input`'s aspect ratio, or
+/// `input` if it already fits.
+fn downscaled_size(input: (u32, u32), max: (u32, u32)) -> (u32, u32) {
+    let (width, height) = input;
+    if (width <= max.0 && height <= max.1) || width == 0 || height == 0 {
+        return input;
+    }
+
+    let scale = (max.0 as f64 / width as f64).min(max.1 as f64 / height as f64);
+    let even = |size: u32| {
+        let scaled = (size as f64 * scale).round() as u32;
+        (scaled - scaled % 2).max(2)
+    };
+
+    (even(width), even(height))
+}
+
+/// A software scaler that is rebuilt whenever the frames it gets change
+/// format or size.
+struct Scaler {
+    context: ffmpeg::software::scaling::Context,
+    input: (Pixel, u32, u32),
+    output: (Pixel, u32, u32),
+}
+
+// The scaling context is only ever used by the thread that owns the chain.
+unsafe impl Send for Scaler {}
+
+impl Scaler {
+    fn get<'a>(
+        slot: &'a mut Option<Self>,
+        frame: &ffmpeg::frame::Video,
+        format: Pixel,
+        width: u32,
+        height: u32,
+    ) -> anyhow::Result<&'a mut Self> {
+        let input = (frame.format(), frame.width(), frame.height());
+        let output = (format, width, height);
+        if slot
+            .as_ref()
+            .is_none_or(|scaler| scaler.input != input || scaler.output != output)
+        {
+            let context = ffmpeg::software::scaling::Context::get(
+                input.0,
+                input.1,
+                input.2,
+                output.0,
+                output.1,
+                output.2,
+                ffmpeg::software::scaling::Flags::BILINEAR,
+            )
+            .context("create filter scaler")?;
+            *slot = Some(Self {
+                context,
+                input,
+                output,
+            });
+        }
+
+        Ok(slot.as_mut().expect("scaler was just created"))
+    }
+
+    fn run(&mut self, input: &ffmpeg::frame::Video) -> anyhow::Result<ffmpeg::frame::Video> {
+        let mut output = ffmpeg::frame::Video::empty();
+        self.context.run(input, &mut output)?;
+        output.set_pts(input.pts());
+        Ok(output)
+    }
+}
+
+pub struct FilteredVideoSourceConfig<C> {
+    pub inner: C,
+    pub filters: Vec<VideoFilterConfig>,
+}
+
+impl<C> FilteredVideoSourceConfig<C> {
+    pub fn new(inner: C, filters: Vec<VideoFilterConfig>) -> Self {
+        Self { inner, filters }
+    }
+}
+
+/// Runs the frames of another source through a [`VideoFilterChain`] on a
+/// dedicated thread. The inner source's frames must support
+/// [`VideoFrame::to_ffmpeg`].
+pub struct FilteredVideoSource<S> {
+    inner: S,
+    video_info: VideoInfo,
+}
+
+impl<S: VideoSource> VideoSource for FilteredVideoSource<S>
+where
+    S::Config: Send,
+{
+    type Config = FilteredVideoSourceConfig<S::Config>;
+    type Frame = FFmpegVideoFrame;
+
+    async fn setup(
+        config: Self::Config,
+        mut video_tx: mpsc::Sender<Self::Frame>,
+        ctx: &mut SetupCtx,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        let (inner_tx, mut inner_rx) = mpsc::channel::<S::Frame>(8);
+        let inner = S::setup(config.inner, inner_tx, ctx).await?;
+
+        let mut chain = VideoFilterChain::new(&config.filters);
+        let video_info = chain.output_info(inner.video_info());
+        let timestamps: Timestamps = ctx.master_clock().timestamps();
+        let stop_token = ctx.stop_token();
+
+        ctx.tasks().spawn_thread("video-filters", move || {
+            futures::executor::block_on(stop_token.run_until_cancelled(async {
+                while let Some(frame) = inner_rx.next().await {
+                    let timestamp = frame.timestamp();
+                    let video = frame.to_ffmpeg().ok_or_else(|| {
+                        anyhow::anyhow!("Video filters need frames that can be read back")
+                    })?;
+                    let elapsed = timestamp
+                        .checked_duration_since(timestamps)
+                        .unwrap_or_default();
+
+                    let inner = chain.apply(video, elapsed, timestamps.system_time() + elapsed)?;
+                    if video_tx
+                        .send(FFmpegVideoFrame { inner, timestamp })
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+
+                Ok(())
+            }))
+            .unwrap_or(Ok(()))
+        });
+
+        Ok(Self { inner, video_info })
+    }
+
+    fn video_info(&self) -> VideoInfo {
+        self.video_info
+    }
+
+    fn start(&mut self) -> BoxFuture<'_, anyhow::Result<()>> {
+        self.inner.start()
+    }
+
+    fn stop(&mut self) -> BoxFuture<'_, anyhow::Result<()>> {
+        self.inner.stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_frame(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> FilterFrame {
+        let mut video = ffmpeg::frame::Video::new(Pixel::BGRA, width, height);
+        let stride = video.stride(0);
+        let data = video.data_mut(0);
+        for y in 0..height {
+            for x in 0..width {
+                let offset = y as usize * stride + x as usize * 4;
+                data[offset..offset + 4].copy_from_slice(&pixel(x, y));
+            }
+        }
+
+        FilterFrame {
+            video,
+            elapsed: Duration::ZERO,
+            wall_clock: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    fn pixel_at(frame: &FilterFrame, x: u32, y: u32) -> [u8; 4] {
+        let offset = y as usize * frame.video.stride(0) + x as usize * 4;
+        frame.video.data(0)[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn parses_filter_specs() {
+        assert_eq!(
+            "blur=10,20,300,40".parse(),
+            Ok(VideoFilterConfig::Blur {
+                area: FilterRect {
+                    x: 10,
+                    y: 20,
+                    width: 300,
+                    height: 40,
+                },
+            })
+        );
+        assert_eq!(
+            "watermark=ACME, internal".parse(),
+            Ok(VideoFilterConfig::Watermark {
+                text: "ACME, internal".to_string(),
+                position: OverlayPosition::BottomRight,
+            })
+        );
+        assert_eq!(
+            "watermark:top-left=a=b".parse(),
+            Ok(VideoFilterConfig::Watermark {
+                text: "a=b".to_string(),
+                position: OverlayPosition::TopLeft,
+            })
+        );
+        assert_eq!(
+            "timestamp".parse(),
+            Ok(VideoFilterConfig::Timestamp {
+                position: OverlayPosition::BottomLeft,
+            })
+        );
+        assert_eq!(
+            "downscale=1280x720".parse(),
+            Ok(VideoFilterConfig::Downscale {
+                width: 1280,
+                height: 720,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_filter_specs() {
+        for spec in [
+            "blur=1,2,3",
+            "blur=0,0,0,10",
+            "watermark=",
+            "watermark:middle=x",
+            "timestamp=now",
+            "downscale=1280",
+            "downscale:top-left=1280x720",
+            "sharpen",
+        ] {
+            assert!(
+                spec.parse::<VideoFilterConfig>().is_err(),
+                "{spec} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn downscale_fits_within_max_size() {
+        assert_eq!(downscaled_size((3840, 2160), (1280, 720)), (1280, 720));
+        assert_eq!(downscaled_size((1000, 1000), (1280, 720)), (720, 720));
+        assert_eq!(downscaled_size((1001, 333), (500, 500)), (500, 166));
+        assert_eq!(downscaled_size((640, 480), (1280, 720)), (640, 480));
+    }
+
+    #[test]
+    fn chain_reports_filtered_output_info() {
+        let chain = VideoFilterChain::new(&[
+            VideoFilterConfig::Downscale {
+                width: 1280,
+                height: 720,
+            },
+            VideoFilterConfig::Timestamp {
+                position: OverlayPosition::TopLeft,
+            },
+        ]);
+
+        let input = VideoInfo::from_raw_ffmpeg(Pixel::NV12, 2560, 1440, 30);
+        let info = chain.output_info(input);
+        assert_eq!((info.width, info.height), (1280, 720));
+        assert_eq!(info.pixel_format, Pixel::BGRA);
+        assert_eq!(info.fps(), 30);
+
+        let unfiltered = VideoFilterChain::new(&[]).output_info(input);
+        assert_eq!(unfiltered.pixel_format, Pixel::NV12);
+        assert_eq!((unfiltered.width, unfiltered.height), (2560, 1440));
+    }
+
+    #[test]
+    fn blur_averages_only_inside_the_area() {
+        let mut frame = solid_frame(32, 32, |x, _| if x % 2 == 0 { [0; 4] } else { [200; 4] });
+        let mut blur = BlurFilter::new(FilterRect {
+            x: 0,
+            y: 0,
+            width: 16,
+            height: 16,
+        });
+
+        blur.apply(&mut frame).unwrap();
+
+        assert_eq!(pixel_at(&frame, 0, 0), [100; 4]);
+        assert_eq!(pixel_at(&frame, 15, 15), [100; 4]);
+        assert_eq!(pixel_at(&frame, 16, 0), [0; 4]);
+        assert_eq!(pixel_at(&frame, 17, 0), [200; 4]);
+        assert_eq!(pixel_at(&frame, 1, 16), [200; 4]);
+    }
+
+    #[test]
+    fn blur_clamps_area_to_the_frame() {
+        let mut frame = solid_frame(8, 8, |_, _| [50; 4]);
+        let mut blur = BlurFilter::new(FilterRect {
+            x: 4,
+            y: 4,
+            width: 100,
+            height: 100,
+        });
+
+        blur.apply(&mut frame).unwrap();
+
+        assert_eq!(pixel_at(&frame, 7, 7), [50; 4]);
+    }
+}
@@ -137,6 +137,9 @@ impl TestRecording {
                         cursor: None,
                         keyboard: None,
                         display_notch: None,
+                        additional_videos: Vec::new(),
+                        additional_mics: Vec::new(),
+                        imported: None,
                     }],
                     cursors: Cursors::default(),
                     status: Some(status),
@@ -1327,6 +1330,111 @@ fn recover_after_simulated_crash_produces_playable_mp4_with_preserved_duration()
     );
 }
 
+#[test]
+fn recover_encrypted_recording_after_simulated_crash() {
+    use cap_project::encryption::{self, DecryptedProject, MasterKey, ProjectKey};
+
+    test_utils::init_tracing();
+
+    let recording = TestRecording::new().unwrap();
+    let display_dir = recording.create_display_dir(0).unwrap();
+    write_synthetic_fragments(&display_dir, 90, Duration::from_secs(1));
+    recording
+        .write_recording_meta(StudioRecordingStatus::InProgress)
+        .unwrap();
+
+    let master_key = MasterKey::generate();
+    ProjectKey::create(recording.path(), &master_key)
+        .unwrap()
+        .seal_project(recording.path())
+        .unwrap();
+
+    assert!(
+        RecoveryManager::inspect_recording(recording.path()).is_none(),
+        "an encrypted recording can't be read without its key"
+    );
+
+    let incomplete =
+        RecoveryManager::inspect_recording_with_key(recording.path(), Some(&master_key))
+            .expect("inspect should find recoverable fragments with the key");
+    assert_eq!(incomplete.project_path, recording.path());
+    assert!(
+        incomplete.recoverable_segments[0]
+            .display_fragments
+            .iter()
+            .all(|fragment| fragment.starts_with(recording.path()))
+    );
+
+    let recovered = RecoveryManager::recover(&incomplete).expect("recovery should succeed");
+    assert_eq!(recovered.project_path, recording.path());
+
+    let display_mp4 = recording
+        .path()
+        .join("content/segments/segment-0/display.mp4");
+    assert!(encryption::is_sealed(&display_mp4).unwrap());
+    assert!(encryption::is_sealed(&recording.path().join("recording-meta.json")).unwrap());
+
+    let decrypted = DecryptedProject::open(recording.path(), &master_key).unwrap();
+    let meta = RecordingMeta::load_for_project(decrypted.path()).unwrap();
+    assert!(matches!(
+        meta.studio_meta().unwrap().status(),
+        StudioRecordingStatus::Complete
+    ));
+    assert!(
+        probe_video_can_decode(
+            &decrypted
+                .path()
+                .join("content/segments/segment-0/display.mp4")
+        )
+        .unwrap_or(false),
+        "recovered display.mp4 must be decodable"
+    );
+}
+
+#[test]
+fn encrypted_recovery_only_accepts_the_newest_fragment_unsealed() {
+    use cap_project::encryption::{MasterKey, ProjectKey};
+
+    test_utils::init_tracing();
+
+    let recording = TestRecording::new().unwrap();
+    let display_dir = recording.create_display_dir(0).unwrap();
+    write_synthetic_fragments(&display_dir, 90, Duration::from_secs(1));
+    recording
+        .write_recording_meta(StudioRecordingStatus::InProgress)
+        .unwrap();
+
+    let mut fragments: Vec<_> = std::fs::read_dir(&display_dir)
+        .unwrap()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "m4s"))
+        .collect();
+    fragments.sort();
+    let newest = fragments.last().unwrap().clone();
+    let newest_plaintext = std::fs::read(&newest).unwrap();
+    let first_plaintext = std::fs::read(&fragments[0]).unwrap();
+
+    let master_key = MasterKey::generate();
+    ProjectKey::create(recording.path(), &master_key)
+        .unwrap()
+        .seal_project(recording.path())
+        .unwrap();
+
+    // The fragment still being written when the recording crashed.
+    std::fs::write(&newest, &newest_plaintext).unwrap();
+    assert!(
+        RecoveryManager::inspect_recording_with_key(recording.path(), Some(&master_key)).is_some(),
+        "the newest fragment may be left unsealed by a crash"
+    );
+
+    std::fs::write(&fragments[0], &first_plaintext).unwrap();
+    assert!(
+        RecoveryManager::inspect_recording_with_key(recording.path(), Some(&master_key)).is_none(),
+        "an earlier fragment left unsealed must not be trusted"
+    );
+}
+
 #[test]
 fn recover_preserves_fragments_when_progressive_mp4_validation_fails() {
     test_utils::init_tracing();
@@ -0,0 +1,430 @@
+//! Finds sensitive text in a studio recording and turns it into blur masks.
+//!
+//! The output timeline is sampled at a fixed interval, each sampled display frame
+//! is run through the platform OCR engine, and any text matching the
+//! [`RedactionList`] becomes a [`MaskKind::Sensitive`](cap_project::MaskKind)
+//! segment, keyframed to follow the text while it moves.
+
+pub mod ocr;
+pub mod patterns;
+mod tracking;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use cap_project::{
+    ClipOffsets, MaskSegment, ProjectConfiguration, RecordingMeta, StudioRecordingMeta, XY,
+};
+use cap_rendering::{
+    ProjectRecordingsMeta, ProjectUniforms, RecordingSegmentDecoders, RenderOptions,
+    SegmentRecordings, SegmentVideoPaths,
+};
+use serde::Serialize;
+
+pub use ocr::OcrRect;
+pub use patterns::{RedactionList, RedactionMatcher, RedactionPattern, RedactionRule};
+pub use tracking::{Detection, TrackingOptions, masks_from_detections};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScanOptions {
+    /// Seconds of output time between OCR samples.
+    pub interval: f64,
+    /// UV padding added around each detected box.
+    pub padding: f64,
+    pub force_ffmpeg_decoder: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            interval: 0.5,
+            padding: 0.004,
+            force_ffmpeg_decoder: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionFinding {
+    /// Output timeline time of the sampled frame, in seconds.
+    pub time: f64,
+    pub rule: String,
+    /// The matched text with its middle masked, safe to print in logs.
+    pub preview: String,
+    /// Matched region in output-frame UV space.
+    pub bounds: OcrRect,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionReport {
+    pub engine: &'static str,
+    pub frames_scanned: u32,
+    pub duration: f64,
+    pub findings: Vec<RedactionFinding>,
+    pub masks: Vec<MaskSegment>,
+}
+
+/// Scans the project's output timeline and proposes masks for every match.
+///
+/// Detection uses the project's crop and padding to place masks, but not zoom
+/// segments: masks are frame-space overlays, so text inside a zoomed span is
+/// located as if the zoom were not applied.
+pub async fn scan_project(
+    project_path: &Path,
+    list: &RedactionList,
+    options: ScanOptions,
+) -> Result<RedactionReport, String> {
+    if !options.interval.is_finite() || options.interval <= 0.0 {
+        return Err("Sample interval must be greater than zero".to_string());
+    }
+
+    let matcher = RedactionMatcher::new(list)?;
+    if matcher.is_empty() {
+        return Err("Redaction list has no rules".to_string());
+    }
+
+    let recording_meta = RecordingMeta::load_for_project(project_path)
+        .map_err(|e| format!("Failed to load recording metadata: {e}"))?;
+    let studio_meta = recording_meta
+        .studio_meta()
+        .ok_or_else(|| "Only studio recordings can be redacted".to_string())?;
+    let recordings = ProjectRecordingsMeta::new(&recording_meta.project_path, studio_meta)?;
+
+    let mut project = recording_meta.project_config();
+    if project.timeline.is_none() {
+        project.timeline = recordings.default_timeline();
+    }
+    let timeline = project
+        .timeline
+        .clone()
+        .ok_or_else(|| "Project has no recorded media to scan".to_string())?;
+    let duration = timeline.duration();
+
+    let mut decoders = HashMap::<u32, RecordingSegmentDecoders>::new();
+    let mut findings = Vec::new();
+    let mut detections = Vec::new();
+    let mut frames_scanned = 0;
+    let mut sample = 0u32;
+
+    loop {
+        let time = f64::from(sample) * options.interval;
+        if time >= duration {
+            break;
+        }
+        sample += 1;
+
+        let Some((segment_time, segment)) = timeline.get_segment_time(time) else {
+            continue;
+        };
+        let clip = segment.recording_clip;
+
+        let Some(recording) = recordings.segments.get(clip as usize) else {
+            continue;
+        };
+
+        if !decoders.contains_key(&clip) {
+            let decoder = RecordingSegmentDecoders::new(
+                &recording_meta,
+                studio_meta,
+                SegmentVideoPaths {
+                    display: display_path(&recording_meta, studio_meta, clip)?,
+                    camera: None,
+                },
+                clip as usize,
+                options.force_ffmpeg_decoder,
+            )
+            .await?;
+            decoders.insert(clip, decoder);
+        }
+        let decoder = &decoders[&clip];
+
+        let Some(frame) = decoder
+            .get_frames(segment_time as f32, false, true, ClipOffsets::default())
+            .await
+            .and_then(|frames| frames.screen_frame)
+        else {
+            tracing::warn!(time, "No display frame to scan");
+            continue;
+        };
+
+        let (width, height) = (frame.width(), frame.height());
+        let Some(rgba) = frame.to_rgba() else {
+            tracing::warn!(time, "Failed to convert display frame for OCR");
+            continue;
+        };
+
+        let lines = tokio::task::spawn_blocking(move || ocr::recognize_rgba(&rgba, width, height))
+            .await
+            .map_err(|e| format!("OCR task failed: {e}"))??;
+        frames_scanned += 1;
+
+        let placement = DisplayPlacement::new(&project, recording);
+
+        for line in &lines {
+            for found in matcher.find(&line.text) {
+                let bounds = placement.to_output(line.bounds_for_range(&found.range));
+                findings.push(RedactionFinding {
+                    time,
+                    rule: found.rule.clone(),
+                    preview: preview(&line.text[found.range.clone()]),
+                    bounds,
+                });
+                detections.push(Detection {
+                    time,
+                    rule: found.rule,
+                    bounds,
+                });
+            }
+        }
+    }
+
+    let masks = masks_from_detections(
+        &detections,
+        TrackingOptions {
+            interval: options.interval,
+            padding: options.padding,
+            duration,
+        },
+    );
+
+    Ok(RedactionReport {
+        engine: ocr::engine_name(),
+        frames_scanned,
+        duration,
+        findings,
+        masks,
+    })
+}
+
+/// Adds `masks` to the project's saved editor configuration.
+pub fn write_masks(project_path: &Path, masks: Vec<MaskSegment>) -> Result<usize, String> {
+    let recording_meta = RecordingMeta::load_for_project(project_path)
+        .map_err(|e| format!("Failed to load recording metadata: {e}"))?;
+    let studio_meta = recording_meta
+        .studio_meta()
+        .ok_or_else(|| "Only studio recordings can be redacted".to_string())?;
+    let recordings = ProjectRecordingsMeta::new(&recording_meta.project_path, studio_meta)?;
+
+    let mut project = match ProjectConfiguration::load(project_path) {
+        Ok(project) => project,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ProjectConfiguration::default(),
+        Err(e) => return Err(format!("Failed to load project config: {e}")),
+    };
+
+    let added = apply_masks(&mut project, &recordings, masks)?;
+    project
+        .write(project_path)
+        .map_err(|e| format!("Failed to write project config: {e}"))?;
+
+    Ok(added)
+}
+
+/// Appends `masks` to the project's timeline, creating the timeline if needed.
+///
+/// Masks go on fresh lanes after the existing ones so they're easy to review
+/// (and delete) as a group in the editor. Returns the number of masks added.
+pub fn apply_masks(
+    project: &mut ProjectConfiguration,
+    recordings: &ProjectRecordingsMeta,
+    masks: Vec<MaskSegment>,
+) -> Result<usize, String> {
+    if project.timeline.is_none() {
+        project.timeline = recordings.default_timeline();
+    }
+    let timeline = project
+        .timeline
+        .as_mut()
+        .ok_or_else(|| "Project has no recorded media".to_string())?;
+
+    let first_lane = timeline
+        .mask_segments
+        .iter()
+        .map(|mask| mask.track + 1)
+        .max()
+        .unwrap_or(0);
+    let mut lane_ends: Vec<f64> = Vec::new();
+    let count = masks.len();
+
+    for mut mask in masks {
+        let lane = match lane_ends.iter().position(|end| *end <= mask.start) {
+            Some(lane) => lane,
+            None => {
+                lane_ends.push(0.0);
+                lane_ends.len() - 1
+            }
+        };
+        lane_ends[lane] = mask.end;
+        mask.track = first_lane + lane as u32;
+        timeline.mask_segments.push(mask);
+    }
+
+    Ok(count)
+}
+
+fn display_path(
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    clip: u32,
+) -> Result<PathBuf, String> {
+    let path = match meta {
+        StudioRecordingMeta::SingleSegment { segment } => &segment.display.path,
+        StudioRecordingMeta::MultipleSegments { inner, .. } => {
+            &inner
+                .segments
+                .get(clip as usize)
+                .ok_or_else(|| format!("Recording segment {clip} does not exist"))?
+                .display
+                .path
+        }
+    };
+
+    Ok(recording_meta.path(path))
+}
+
+/// Where the (cropped) display lands in the unzoomed output frame.
+struct DisplayPlacement {
+    screen: XY<f64>,
+    crop_position: XY<f64>,
+    crop_size: XY<f64>,
+    offset: XY<f64>,
+    size: XY<f64>,
+    output: XY<f64>,
+}
+
+impl DisplayPlacement {
+    fn new(project: &ProjectConfiguration, recording: &SegmentRecordings) -> Self {
+        let screen_size = XY::new(recording.display.width, recording.display.height);
+        let options = RenderOptions {
+            camera_size: recording
+                .camera
+                .as_ref()
+                .map(|camera| XY::new(camera.width, camera.height)),
+            screen_size,
+            preserve_screen_alpha: false,
+        };
+
+        let crop = ProjectUniforms::get_crop(&options, project);
+        let (output_width, output_height) =
+            ProjectUniforms::get_output_size(&options, project, screen_size);
+        let offset = ProjectUniforms::display_offset(&options, project, screen_size).coord;
+        let size = ProjectUniforms::display_size(&options, project, screen_size).coord;
+
+        Self {
+            screen: XY::new(f64::from(screen_size.x), f64::from(screen_size.y)),
+            crop_position: XY::new(f64::from(crop.position.x), f64::from(crop.position.y)),
+            crop_size: XY::new(f64::from(crop.size.x.max(1)), f64::from(crop.size.y.max(1))),
+            offset,
+            size,
+            output: XY::new(
+                f64::from(output_width.max(1)),
+                f64::from(output_height.max(1)),
+            ),
+        }
+    }
+
+    /// Maps a rect normalized to the raw display frame into output-frame UV.
+    fn to_output(&self, rect: OcrRect) -> OcrRect {
+        let map_x = |x: f64| {
+            let cropped = (x * self.screen.x - self.crop_position.x) / self.crop_size.x;
+            (self.offset.x + cropped * self.size.x) / self.output.x
+        };
+        let map_y = |y: f64| {
+            let cropped = (y * self.screen.y - self.crop_position.y) / self.crop_size.y;
+            (self.offset.y + cropped * self.size.y) / self.output.y
+        };
+
+        let left = map_x(rect.x).clamp(0.0, 1.0);
+        let top = map_y(rect.y).clamp(0.0, 1.0);
+        let right = map_x(rect.x + rect.width).clamp(0.0, 1.0);
+        let bottom = map_y(rect.y + rect.height).clamp(0.0, 1.0);
+
+        OcrRect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+}
+
+fn preview(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    if chars.len() <= 4 {
+        return "*".repeat(chars.len());
+    }
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i < 2 || i >= chars.len() - 2 {
+                *c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cap_project::{MaskKind, TimelineConfiguration};
+
+    fn mask(start: f64, end: f64) -> MaskSegment {
+        MaskSegment::new(
+            MaskKind::Sensitive,
+            start,
+            end,
+            XY::new(0.5, 0.5),
+            XY::new(0.1, 0.1),
+        )
+    }
+
+    #[test]
+    fn preview_hides_the_middle_of_matches() {
+        assert_eq!(preview("me@cap.so"), "me*****so");
+        assert_eq!(preview("1234"), "****");
+    }
+
+    #[test]
+    fn applied_masks_use_non_overlapping_lanes_after_existing_ones() {
+        let mut existing = mask(0.0, 1.0);
+        existing.track = 2;
+
+        let mut project = ProjectConfiguration::default();
+        project.timeline = Some(TimelineConfiguration {
+            segments: Vec::new(),
+            transitions: Vec::new(),
+            zoom_segments: Vec::new(),
+            scene_segments: Vec::new(),
+            mask_segments: vec![existing],
+            text_segments: Vec::new(),
+            caption_segments: Vec::new(),
+            keyboard_segments: Vec::new(),
+            audio_segments: Vec::new(),
+            camera3d_segments: Vec::new(),
+        });
+
+        let recordings = ProjectRecordingsMeta {
+            segments: Vec::new(),
+        };
+        let added = apply_masks(
+            &mut project,
+            &recordings,
+            vec![mask(0.0, 2.0), mask(1.0, 3.0), mask(2.5, 4.0)],
+        )
+        .unwrap();
+
+        let tracks = project.timeline.unwrap().mask_segments[1..]
+            .iter()
+            .map(|m| m.track)
+            .collect::<Vec<_>>();
+        assert_eq!(added, 3);
+        assert_eq!(tracks, vec![3, 4, 3]);
+    }
+}
@@ -0,0 +1,454 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+/// Rectangle in normalized image coordinates, `(0, 0)` at the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl OcrRect {
+    pub fn union(self, other: Self) -> Self {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+
+        Self {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        }
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrWord {
+    /// Byte range of the word within [`OcrLine::text`].
+    pub range: Range<usize>,
+    pub bounds: OcrRect,
+}
+
+#[derive(Debug, Clone)]
+pub struct OcrLine {
+    pub text: String,
+    pub bounds: OcrRect,
+    /// Per-word boxes when the engine reports them. Empty for engines that only
+    /// return line-level boxes.
+    pub words: Vec<OcrWord>,
+}
+
+impl OcrLine {
+    /// Bounds covering `range` of the line's text.
+    ///
+    /// Uses word boxes when available, otherwise assumes glyphs are evenly spaced
+    /// across the line box, which is close enough for monospaced and UI text.
+    pub fn bounds_for_range(&self, range: &Range<usize>) -> OcrRect {
+        let from_words = self
+            .words
+            .iter()
+            .filter(|word| word.range.start < range.end && range.start < word.range.end)
+            .map(|word| word.bounds)
+            .reduce(OcrRect::union);
+
+        if let Some(bounds) = from_words {
+            return bounds;
+        }
+
+        let total = self.text.chars().count().max(1) as f64;
+        let start = self.text[..range.start.min(self.text.len())]
+            .chars()
+            .count() as f64;
+        let end = self.text[..range.end.min(self.text.len())].chars().count() as f64;
+
+        OcrRect {
+            x: self.bounds.x + self.bounds.width * (start / total),
+            y: self.bounds.y,
+            width: self.bounds.width * ((end - start) / total),
+            height: self.bounds.height,
+        }
+    }
+}
+
+/// Runs the platform OCR engine over a tightly packed RGBA image.
+///
+/// Blocking; call from `spawn_blocking`.
+pub fn recognize_rgba(rgba: &[u8], width: u32, height: u32) -> Result<Vec<OcrLine>, String> {
+    if width == 0 || height == 0 {
+        return Ok(Vec::new());
+    }
+
+    recognize(rgba, width, height)
+}
+
+pub fn engine_name() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macos-vision"
+    } else if cfg!(target_os = "windows") {
+        "windows-media-ocr"
+    } else {
+        "tesseract"
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn rgba_to_bgra(rgba: &[u8]) -> Vec<u8> {
+    let mut bgra = rgba.to_vec();
+    for pixel in bgra.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    bgra
+}
+
+#[cfg(target_os = "macos")]
+fn recognize(rgba: &[u8], width: u32, height: u32) -> Result<Vec<OcrLine>, String> {
+    cidre::objc::ar_pool(|| {
+        use cidre::{cv, ns, vn};
+        use std::ffi::c_void;
+
+        extern "C" fn release_pixel_buffer_data(
+            release_ref_con: *mut c_void,
+            _base_address: *const *const c_void,
+        ) {
+            if !release_ref_con.is_null() {
+                unsafe {
+                    drop(Box::from_raw(release_ref_con.cast::<Vec<u8>>()));
+                }
+            }
+        }
+
+        let width_px = width as usize;
+        let height_px = height as usize;
+        let mut data = Box::new(rgba_to_bgra(rgba));
+        let base_address = data.as_mut_ptr().cast::<c_void>();
+        let release_ref_con = Box::into_raw(data).cast::<c_void>();
+
+        let pixel_buffer = match cv::PixelBuf::with_bytes(
+            width_px,
+            height_px,
+            base_address,
+            width_px * 4,
+            release_pixel_buffer_data,
+            release_ref_con,
+            cv::PixelFormat::_32_BGRA,
+            None,
+        ) {
+            Ok(pixel_buffer) => pixel_buffer,
+            Err(e) => {
+                unsafe {
+                    drop(Box::from_raw(release_ref_con.cast::<Vec<u8>>()));
+                }
+                return Err(format!("Failed to create OCR image: {e}"));
+            }
+        };
+
+        let mut request = vn::RecognizeTextRequest::new();
+        request.set_recognition_level(vn::RequestTextRecognitionLevel::Accurate);
+        // Language correction "fixes" tokens and emails into dictionary words.
+        request.set_uses_lang_correction(false);
+
+        if cidre::version!(macos = 13.0) {
+            request.set_revision(vn::RecognizeTextRequest::REVISION_3);
+        } else {
+            request.set_revision(vn::RecognizeTextRequest::REVISION_2);
+        }
+
+        let handler = vn::ImageRequestHandler::with_cv_pixel_buf(&pixel_buffer, None)
+            .ok_or_else(|| "Failed to initialize OCR image handler".to_string())?;
+        let requests = ns::Array::<vn::Request>::from_slice(&[&request]);
+        handler
+            .perform(&requests)
+            .map_err(|e| format!("macOS OCR failed: {e}"))?;
+
+        let observations = request.results().unwrap_or_else(ns::Array::new);
+        let mut lines = Vec::new();
+
+        for observation in observations.iter() {
+            let candidates = observation.top_candidates(1);
+            let Some(candidate) = candidates.first() else {
+                continue;
+            };
+            let text = candidate.string().to_string();
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            // Vision uses a bottom-left origin.
+            let rect = observation.bounding_box();
+            lines.push(OcrLine {
+                text,
+                bounds: OcrRect {
+                    x: rect.origin.x,
+                    y: 1.0 - rect.origin.y - rect.size.height,
+                    width: rect.size.width,
+                    height: rect.size.height,
+                },
+                words: Vec::new(),
+            });
+        }
+
+        Ok(lines)
+    })
+}
+
+#[cfg(target_os = "windows")]
+struct WindowsRuntimeGuard;
+
+#[cfg(target_os = "windows")]
+impl Drop for WindowsRuntimeGuard {
+    fn drop(&mut self) {
+        unsafe {
+            windows::Win32::System::WinRT::RoUninitialize();
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn recognize(rgba: &[u8], width: u32, height: u32) -> Result<Vec<OcrLine>, String> {
+    use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::DataWriter;
+    use windows::Win32::System::WinRT::{RO_INIT_MULTITHREADED, RoInitialize};
+
+    unsafe { RoInitialize(RO_INIT_MULTITHREADED) }
+        .map_err(|e| format!("Windows OCR runtime failed: {e}"))?;
+    let _runtime = WindowsRuntimeGuard;
+
+    let err = |e: windows::core::Error| format!("Windows OCR failed: {e}");
+
+    let max_dimension = OcrEngine::MaxImageDimension().map_err(err)?;
+    if width > max_dimension || height > max_dimension {
+        return Err(format!(
+            "Frame is {width}x{height} but Windows OCR supports up to {max_dimension}px per side"
+        ));
+    }
+
+    let writer = DataWriter::new().map_err(err)?;
+    writer.WriteBytes(&rgba_to_bgra(rgba)).map_err(err)?;
+    let buffer = writer.DetachBuffer().map_err(err)?;
+    let bitmap = SoftwareBitmap::CreateCopyWithAlphaFromBuffer(
+        &buffer,
+        BitmapPixelFormat::Bgra8,
+        width as i32,
+        height as i32,
+        BitmapAlphaMode::Premultiplied,
+    )
+    .map_err(err)?;
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .map_err(|e| format!("Windows OCR is not available: {e}"))?;
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .map_err(err)?
+        .get()
+        .map_err(err)?;
+
+    let width_f = f64::from(width);
+    let height_f = f64::from(height);
+    let ocr_lines = result.Lines().map_err(err)?;
+    let mut lines = Vec::new();
+
+    for index in 0..ocr_lines.Size().map_err(err)? {
+        let ocr_words = ocr_lines
+            .GetAt(index)
+            .and_then(|l| l.Words())
+            .map_err(err)?;
+        let mut text = String::new();
+        let mut words = Vec::new();
+
+        for word_index in 0..ocr_words.Size().map_err(err)? {
+            let word = ocr_words.GetAt(word_index).map_err(err)?;
+            let word_text = word.Text().map_err(err)?.to_string_lossy();
+            let rect = word.BoundingRect().map_err(err)?;
+
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            let start = text.len();
+            text.push_str(&word_text);
+
+            words.push(OcrWord {
+                range: start..text.len(),
+                bounds: OcrRect {
+                    x: f64::from(rect.X) / width_f,
+                    y: f64::from(rect.Y) / height_f,
+                    width: f64::from(rect.Width) / width_f,
+                    height: f64::from(rect.Height) / height_f,
+                },
+            });
+        }
+
+        let Some(bounds) = words.iter().map(|w| w.bounds).reduce(OcrRect::union) else {
+            continue;
+        };
+
+        lines.push(OcrLine {
+            text,
+            bounds,
+            words,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// Linux has no system OCR service, so shell out to `tesseract` when it's installed.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn recognize(rgba: &[u8], width: u32, height: u32) -> Result<Vec<OcrLine>, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let image = image::RgbaImage::from_raw(width, height, rgba.to_vec())
+        .ok_or_else(|| "Frame buffer does not match its dimensions".to_string())?;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode OCR image: {e}"))?;
+
+    let mut child = Command::new("tesseract")
+        .args(["stdin", "stdout", "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("OCR on Linux requires the `tesseract` command: {e}"))?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| "Failed to open tesseract stdin".to_string())?
+        .write_all(&png)
+        .map_err(|e| format!("Failed to send frame to tesseract: {e}"))?;
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("tesseract failed: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("tesseract exited with {}", output.status));
+    }
+
+    Ok(parse_tesseract_tsv(
+        &String::from_utf8_lossy(&output.stdout),
+        width,
+        height,
+    ))
+}
+
+/// Groups word rows (level 5) of `tesseract ... tsv` output into lines.
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn parse_tesseract_tsv(tsv: &str, width: u32, height: u32) -> Vec<OcrLine> {
+    let width = f64::from(width.max(1));
+    let height = f64::from(height.max(1));
+    let mut lines: Vec<((u32, u32, u32, u32), OcrLine)> = Vec::new();
+
+    for row in tsv.lines().skip(1) {
+        let columns = row.split('\t').collect::<Vec<_>>();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+
+        let text = columns[11].trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let number = |i: usize| columns[i].parse::<u32>().unwrap_or_default();
+        let key = (number(1), number(2), number(3), number(4));
+        let bounds = OcrRect {
+            x: f64::from(number(6)) / width,
+            y: f64::from(number(7)) / height,
+            width: f64::from(number(8)) / width,
+            height: f64::from(number(9)) / height,
+        };
+
+        if lines.last().is_none_or(|(last_key, _)| *last_key != key) {
+            lines.push((
+                key,
+                OcrLine {
+                    text: String::new(),
+                    bounds,
+                    words: Vec::new(),
+                },
+            ));
+        }
+        let Some((_, line)) = lines.last_mut() else {
+            continue;
+        };
+
+        if !line.text.is_empty() {
+            line.text.push(' ');
+        }
+        let start = line.text.len();
+        line.text.push_str(text);
+        line.bounds = line.bounds.union(bounds);
+        line.words.push(OcrWord {
+            range: start..line.text.len(),
+            bounds,
+        });
+    }
+
+    lines.into_iter().map(|(_, line)| line).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tesseract_words_are_grouped_into_lines() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+            4\t1\t1\t1\t1\t0\t10\t10\t180\t20\t-1\t\n\
+            5\t1\t1\t1\t1\t1\t10\t10\t40\t20\t96\tmail\n\
+            5\t1\t1\t1\t1\t2\t60\t10\t130\t20\t91\tme@cap.so\n\
+            5\t1\t1\t1\t2\t1\t10\t50\t50\t20\t90\tnext\n";
+
+        let lines = parse_tesseract_tsv(tsv, 200, 100);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "mail me@cap.so");
+        assert_eq!(lines[0].words[1].range, 5..14);
+        assert!((lines[0].bounds.width - 0.9).abs() < 1e-9);
+        assert_eq!(lines[1].text, "next");
+    }
+
+    #[test]
+    fn range_bounds_prefer_word_boxes() {
+        let lines = parse_tesseract_tsv(
+            "header\n5\t1\t1\t1\t1\t1\t0\t0\t50\t10\t90\tkey\n5\t1\t1\t1\t1\t2\t100\t0\t100\t10\t90\tsecret\n",
+            200,
+            10,
+        );
+
+        let bounds = lines[0].bounds_for_range(&(4..10));
+        assert!((bounds.x - 0.5).abs() < 1e-9);
+        assert!((bounds.width - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn range_bounds_interpolate_without_word_boxes() {
+        let line = OcrLine {
+            text: "abcdefghij".to_string(),
+            bounds: OcrRect {
+                x: 0.0,
+                y: 0.2,
+                width: 1.0,
+                height: 0.1,
+            },
+            words: Vec::new(),
+        };
+
+        let bounds = line.bounds_for_range(&(5..10));
+        assert!((bounds.x - 0.5).abs() < 1e-9);
+        assert!((bounds.width - 0.5).abs() < 1e-9);
+        assert_eq!(bounds.y, 0.2);
+    }
+}
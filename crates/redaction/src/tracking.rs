@@ -0,0 +1,228 @@
+use cap_project::{MaskKeyframes, MaskKind, MaskSegment, MaskVectorKeyframe, XY};
+
+use crate::ocr::OcrRect;
+
+/// A single pattern match on one sampled frame, in output-frame UV space.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub time: f64,
+    pub rule: String,
+    pub bounds: OcrRect,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TrackingOptions {
+    /// Seconds between sampled frames.
+    pub interval: f64,
+    /// UV padding added on every side of a detected box.
+    pub padding: f64,
+    /// Output duration, used to clamp the last mask.
+    pub duration: f64,
+}
+
+struct Track {
+    rule: String,
+    samples: Vec<(f64, OcrRect)>,
+}
+
+impl Track {
+    fn last(&self) -> (f64, OcrRect) {
+        *self.samples.last().unwrap()
+    }
+}
+
+/// Joins detections of the same rule across consecutive samples into tracks,
+/// so scrolling or moving text gets one keyframed mask instead of a flicker of
+/// short ones.
+pub fn masks_from_detections(
+    detections: &[Detection],
+    options: TrackingOptions,
+) -> Vec<MaskSegment> {
+    let mut sorted = detections.to_vec();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let max_gap = options.interval * 1.5;
+    let mut tracks: Vec<Track> = Vec::new();
+
+    for detection in sorted {
+        let (cx, cy) = detection.bounds.center();
+
+        let best = tracks
+            .iter_mut()
+            .filter(|track| track.rule == detection.rule)
+            .filter(|track| {
+                let (time, _) = track.last();
+                time < detection.time && detection.time - time <= max_gap
+            })
+            .map(|track| {
+                let (_, rect) = track.last();
+                let (tx, ty) = rect.center();
+                let distance = ((tx - cx).powi(2) + (ty - cy).powi(2)).sqrt();
+                let reach = rect.width.max(rect.height).max(detection.bounds.width);
+                (track, distance, reach)
+            })
+            .filter(|(_, distance, reach)| distance <= reach)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(track, ..)| track);
+
+        match best {
+            Some(track) => track.samples.push((detection.time, detection.bounds)),
+            None => tracks.push(Track {
+                rule: detection.rule,
+                samples: vec![(detection.time, detection.bounds)],
+            }),
+        }
+    }
+
+    tracks
+        .into_iter()
+        .map(|track| track_to_mask(&track, options))
+        .collect()
+}
+
+fn padded(rect: OcrRect, padding: f64) -> (XY<f64>, XY<f64>) {
+    let (cx, cy) = rect.center();
+    (
+        XY::new(cx, cy),
+        XY::new(rect.width + padding * 2.0, rect.height + padding * 2.0),
+    )
+}
+
+fn track_to_mask(track: &Track, options: TrackingOptions) -> MaskSegment {
+    let half = options.interval / 2.0;
+    let (first_time, first_rect) = track.samples[0];
+    let (last_time, _) = track.last();
+
+    let start = (first_time - half).max(0.0);
+    let end = if options.duration > 0.0 {
+        (last_time + half).min(options.duration)
+    } else {
+        last_time + half
+    };
+
+    let (center, size) = padded(first_rect, options.padding);
+    let mut mask = MaskSegment::new(MaskKind::Sensitive, start, end.max(start), center, size);
+
+    if track.samples.len() > 1 {
+        let mut keyframes = MaskKeyframes::default();
+        for &(time, rect) in &track.samples {
+            let (center, size) = padded(rect, options.padding);
+            keyframes.position.push(MaskVectorKeyframe {
+                time: time - start,
+                x: center.x,
+                y: center.y,
+            });
+            keyframes.size.push(MaskVectorKeyframe {
+                time: time - start,
+                x: size.x,
+                y: size.y,
+            });
+        }
+        mask.keyframes = keyframes;
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64) -> OcrRect {
+        OcrRect {
+            x,
+            y,
+            width: 0.2,
+            height: 0.04,
+        }
+    }
+
+    fn detection(time: f64, rule: &str, bounds: OcrRect) -> Detection {
+        Detection {
+            time,
+            rule: rule.to_string(),
+            bounds,
+        }
+    }
+
+    const OPTIONS: TrackingOptions = TrackingOptions {
+        interval: 0.5,
+        padding: 0.0,
+        duration: 10.0,
+    };
+
+    #[test]
+    fn static_text_becomes_one_mask_spanning_samples() {
+        let masks = masks_from_detections(
+            &[
+                detection(1.0, "email", rect(0.1, 0.1)),
+                detection(1.5, "email", rect(0.1, 0.1)),
+                detection(2.0, "email", rect(0.1, 0.1)),
+            ],
+            OPTIONS,
+        );
+
+        assert_eq!(masks.len(), 1);
+        assert_eq!(masks[0].start, 0.75);
+        assert_eq!(masks[0].end, 2.25);
+        assert!(matches!(masks[0].mask_type, MaskKind::Sensitive));
+        assert!((masks[0].center.x - 0.2).abs() < 1e-9);
+        assert_eq!(masks[0].keyframes.position.len(), 3);
+        assert_eq!(masks[0].keyframes.position[0].time, 0.25);
+    }
+
+    #[test]
+    fn scrolling_text_is_keyframed() {
+        let masks = masks_from_detections(
+            &[
+                detection(0.0, "apiKey", rect(0.1, 0.5)),
+                detection(0.5, "apiKey", rect(0.1, 0.47)),
+                detection(1.0, "apiKey", rect(0.1, 0.44)),
+            ],
+            OPTIONS,
+        );
+
+        assert_eq!(masks.len(), 1);
+        let ys = masks[0]
+            .keyframes
+            .position
+            .iter()
+            .map(|k| (k.y * 100.0).round() / 100.0)
+            .collect::<Vec<_>>();
+        assert_eq!(ys, vec![0.52, 0.49, 0.46]);
+    }
+
+    #[test]
+    fn gaps_rules_and_distance_split_tracks() {
+        let masks = masks_from_detections(
+            &[
+                detection(0.0, "email", rect(0.1, 0.1)),
+                detection(3.0, "email", rect(0.1, 0.1)),
+                detection(3.0, "creditCard", rect(0.1, 0.1)),
+                detection(3.5, "email", rect(0.7, 0.8)),
+            ],
+            OPTIONS,
+        );
+
+        assert_eq!(masks.len(), 4);
+        assert!(masks.iter().all(|m| m.keyframes.position.is_empty()));
+    }
+
+    #[test]
+    fn masks_are_clamped_to_the_output() {
+        let masks = masks_from_detections(
+            &[
+                detection(0.0, "email", rect(0.1, 0.1)),
+                detection(10.0, "email", rect(0.5, 0.5)),
+            ],
+            TrackingOptions {
+                padding: 0.01,
+                ..OPTIONS
+            },
+        );
+
+        assert_eq!(masks[0].start, 0.0);
+        assert_eq!(masks[1].end, 10.0);
+        assert!((masks[1].size.x - 0.22).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,610 @@
+//! CPU raster compositor mirroring `cap_rendering`'s wgpu layer stack.
+//!
+//! `cap_rendering` resolves a frame's uniforms into a [`RasterFrame`] — a
+//! flat list of layers in GPU draw order — and this module draws it with
+//! Skia's raster backend, using SkSL ports of the same shaders (see
+//! [`sksl`]). Blend modes follow the wgpu pipelines: straight-alpha
+//! `ALPHA_BLENDING` and premultiplied blending both become `SrcOver` on
+//! premultiplied shader output, and `REPLACE` passes become `Src`.
+//!
+//! Pixels are raw RGBA8 with no color space attached, matching the GPU's
+//! non-sRGB `Rgba8Unorm` targets, so no conversion ever happens in between.
+
+mod sksl;
+
+use skia_safe::{
+    AlphaType, BlendMode, ClipOp, ColorType, IRect, Image, ImageInfo, Paint, SamplingOptions,
+    Shader, Surface, TileMode, images, runtime_effect::ChildPtr, surfaces,
+};
+
+use crate::SkiaRenderingError;
+use sksl::{Effects, Uniforms};
+
+/// An RGBA8 image the compositor can sample. Cheap to clone.
+#[derive(Clone)]
+pub struct RasterImage {
+    image: Image,
+}
+
+impl RasterImage {
+    /// Wraps tightly packed RGBA8 pixels. Bytes are used as-is, exactly as
+    /// the GPU path uploads them to an `Rgba8Unorm` texture.
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Option<Self> {
+        if width == 0 || height == 0 || rgba.len() < (width * height * 4) as usize {
+            return None;
+        }
+        let info = raw_image_info(width, height);
+        let image = images::raster_from_data(
+            &info,
+            skia_safe::Data::new_copy(&rgba[..(width * height * 4) as usize]),
+            width as usize * 4,
+        )?;
+        Some(Self { image })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width() as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height() as u32
+    }
+
+    fn size(&self) -> [f32; 2] {
+        [self.image.width() as f32, self.image.height() as f32]
+    }
+
+    fn shader(&self) -> Option<Shader> {
+        image_shader(&self.image)
+    }
+}
+
+/// Mirror of `cap_rendering::CompositeVideoFrameUniforms` without padding.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CardUniforms {
+    pub crop_bounds: [f32; 4],
+    pub target_bounds: [f32; 4],
+    pub output_size: [f32; 2],
+    pub frame_size: [f32; 2],
+    pub motion_blur_vector: [f32; 2],
+    pub motion_blur_zoom_center: [f32; 2],
+    pub motion_blur_params: [f32; 4],
+    pub target_size: [f32; 2],
+    pub rounding_px: f32,
+    pub rounding_type: f32,
+    pub mirror_x: f32,
+    pub shadow: f32,
+    pub shadow_size: f32,
+    pub shadow_opacity: f32,
+    pub shadow_blur: f32,
+    pub opacity: f32,
+    pub border_enabled: f32,
+    pub border_width: f32,
+    pub preserve_source_alpha: f32,
+    pub border_color: [f32; 4],
+    pub corner_radii: [f32; 4],
+    pub grade: ColorGradeParams,
+}
+
+/// `(exposure, contrast, saturation, temperature)`, `(tint, fade,
+/// split_tone, vignette)` and `(grain, seed, active, full-frame vignette)`,
+/// as in `cap_rendering::ColorGradeUniformParams`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ColorGradeParams {
+    pub color_adjust_a: [f32; 4],
+    pub color_adjust_b: [f32; 4],
+    pub grain_params: [f32; 4],
+}
+
+/// Mirror of `cap_rendering`'s cursor uniforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CursorParams {
+    pub position_size: [f32; 4],
+    pub output_size: [f32; 4],
+    pub screen_bounds: [f32; 4],
+    pub motion_vector_strength: [f32; 4],
+    pub rotation_params: [f32; 4],
+    pub grade: ColorGradeParams,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GradientParams {
+    pub start: [f32; 4],
+    pub end: [f32; 4],
+    pub angle: f32,
+    pub noise_intensity: f32,
+    pub noise_scale: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+    Pixelate,
+    Highlight,
+    Blur,
+}
+
+/// A mask rectangle in output UV space, as in `cap_rendering::PreparedMask`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaskParams {
+    pub center: [f32; 2],
+    pub size: [f32; 2],
+    pub feather: f32,
+    pub opacity: f32,
+    pub effect_size: f32,
+    pub darkness: f32,
+    pub mode: MaskMode,
+}
+
+/// Focus blur parameters in the layout of camera3d-blur.wgsl. The pass
+/// component of `params0` is filled in by the compositor.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FocusBlurParams {
+    pub params0: [f32; 4],
+    pub params1: [f32; 4],
+    pub params2: [f32; 4],
+    pub bokeh: bool,
+}
+
+/// One rasterized glyph, already tinted and premultiplied, at its integer
+/// output position.
+#[derive(Clone)]
+pub struct GlyphSprite {
+    pub left: i32,
+    pub top: i32,
+    pub image: RasterImage,
+}
+
+/// A pixel rectangle `[x, y, width, height]`, like a wgpu scissor rect.
+pub type ClipRect = [u32; 4];
+
+/// One step of the GPU layer stack.
+pub enum RasterLayer {
+    Gradient(GradientParams),
+    /// Background image; `inset` is the cover crop (`x_width`, `y_height`)
+    /// in image UV.
+    Image {
+        image: RasterImage,
+        inset: [f32; 2],
+    },
+    /// Separable background blur, `strength` in `0..=1`.
+    BackgroundBlur {
+        strength: f32,
+    },
+    /// Full-frame grade over everything drawn so far.
+    ColorGrade(ColorGradeParams),
+    /// A composite-video-frame card: display, camera, frame chrome, notch.
+    Card {
+        image: RasterImage,
+        uniforms: CardUniforms,
+    },
+    Cursor {
+        image: RasterImage,
+        params: CursorParams,
+    },
+    /// Draws `content` onto a transparent plane and warps it over the frame
+    /// through the inverse homography rows of camera3d.wgsl.
+    Warp3D {
+        content: Vec<RasterLayer>,
+        inv_rows: [[f32; 4]; 3],
+    },
+    FocusBlur(FocusBlurParams),
+    Mask(MaskParams),
+    /// Caption/keyboard background squircle, `rect` as `[x, y, w, h]`.
+    RoundedRect {
+        rect: [f32; 4],
+        color: [f32; 4],
+        radius: f32,
+        clip: ClipRect,
+    },
+    Glyphs {
+        glyphs: Vec<GlyphSprite>,
+        clip: ClipRect,
+    },
+}
+
+/// A frame's layers in draw order.
+pub struct RasterFrame {
+    pub width: u32,
+    pub height: u32,
+    pub layers: Vec<RasterLayer>,
+}
+
+/// Draws [`RasterFrame`]s on the CPU. Shaders are compiled once here, so
+/// keep one compositor per render loop.
+pub struct CpuCompositor {
+    effects: Effects,
+}
+
+impl CpuCompositor {
+    pub fn new() -> Result<Self, SkiaRenderingError> {
+        Ok(Self {
+            effects: Effects::compile().map_err(SkiaRenderingError::ShaderCompilationFailed)?,
+        })
+    }
+
+    /// Renders the frame and returns tightly packed RGBA8 pixels.
+    pub fn render(&self, frame: &RasterFrame) -> Result<Vec<u8>, SkiaRenderingError> {
+        let (width, height) = (frame.width, frame.height);
+        let mut surface = new_surface(width, height)?;
+        surface.canvas().clear(skia_safe::Color::TRANSPARENT);
+
+        for layer in &frame.layers {
+            self.draw_layer(&mut surface, layer)?;
+        }
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        if !surface.read_pixels(
+            &raw_image_info(width, height),
+            &mut pixels,
+            width as usize * 4,
+            (0, 0),
+        ) {
+            return Err(SkiaRenderingError::ReadPixelsFailed);
+        }
+        Ok(pixels)
+    }
+
+    fn draw_layer(
+        &self,
+        surface: &mut Surface,
+        layer: &RasterLayer,
+    ) -> Result<(), SkiaRenderingError> {
+        let (width, height) = (surface.width() as u32, surface.height() as u32);
+        let output_size = [width as f32, height as f32];
+        let effects = &self.effects;
+
+        match layer {
+            RasterLayer::Gradient(params) => {
+                let uniforms = Uniforms::new(&effects.gradient)
+                    .set("output_size", &output_size)
+                    .set("start_color", &params.start)
+                    .set("end_color", &params.end)
+                    .set("angle", &[params.angle])
+                    .set("noise_intensity", &[params.noise_intensity])
+                    .set("noise_scale", &[params.noise_scale]);
+                let shader = make_shader(&effects.gradient, uniforms, vec![])?;
+                fill(surface, shader, BlendMode::Src, None);
+            }
+            RasterLayer::Image { image, inset } => {
+                let uniforms = Uniforms::new(&effects.image_background)
+                    .set("tex_size", &image.size())
+                    .set("output_size", &output_size)
+                    .set("x_width", &[inset[0]])
+                    .set("y_height", &[inset[1]]);
+                let shader =
+                    make_shader(&effects.image_background, uniforms, vec![image.shader()])?;
+                fill(surface, shader, BlendMode::Src, None);
+            }
+            RasterLayer::BackgroundBlur { strength } => {
+                // Horizontal then vertical; snapshotting stands in for the
+                // GPU's ping-pong textures.
+                for direction in [0.0, 1.0] {
+                    let source_image = surface.image_snapshot();
+                    let uniforms = Uniforms::new(&effects.background_blur)
+                        .set("output_size", &output_size)
+                        .set("blur_strength", &[*strength])
+                        .set("direction", &[direction]);
+                    let shader = make_shader(
+                        &effects.background_blur,
+                        uniforms,
+                        vec![image_shader(&source_image)],
+                    )?;
+                    fill(surface, shader, BlendMode::Src, None);
+                }
+            }
+            RasterLayer::ColorGrade(grade) => {
+                let source_image = surface.image_snapshot();
+                let uniforms = grade_uniforms(Uniforms::new(&effects.color_grade), grade)
+                    .set("output_size", &output_size);
+                let shader = make_shader(
+                    &effects.color_grade,
+                    uniforms,
+                    vec![image_shader(&source_image)],
+                )?;
+                fill(surface, shader, BlendMode::Src, None);
+            }
+            RasterLayer::Card { image, uniforms: u } => {
+                let uniforms = grade_uniforms(Uniforms::new(&effects.card), &u.grade)
+                    .set("tex_size", &image.size())
+                    .set("crop_bounds", &u.crop_bounds)
+                    .set("target_bounds", &u.target_bounds)
+                    .set("output_size", &u.output_size)
+                    .set("frame_size", &u.frame_size)
+                    .set("motion_blur_vector", &u.motion_blur_vector)
+                    .set("motion_blur_zoom_center", &u.motion_blur_zoom_center)
+                    .set("motion_blur_params", &u.motion_blur_params)
+                    .set("target_size", &u.target_size)
+                    .set("rounding_px", &[u.rounding_px])
+                    .set("rounding_type", &[u.rounding_type])
+                    .set("mirror_x", &[u.mirror_x])
+                    .set("shadow", &[u.shadow])
+                    .set("shadow_size", &[u.shadow_size])
+                    .set("shadow_opacity", &[u.shadow_opacity])
+                    .set("shadow_blur", &[u.shadow_blur])
+                    .set("opacity", &[u.opacity])
+                    .set("border_enabled", &[u.border_enabled])
+                    .set("border_width", &[u.border_width])
+                    .set("preserve_source_alpha", &[u.preserve_source_alpha])
+                    .set("border_color", &u.border_color)
+                    .set("corner_radii", &u.corner_radii);
+                let shader = make_shader(&effects.card, uniforms, vec![image.shader()])?;
+                fill(surface, shader, BlendMode::SrcOver, None);
+            }
+            RasterLayer::Cursor { image, params } => {
+                let uniforms = grade_uniforms(Uniforms::new(&effects.cursor), &params.grade)
+                    .set("tex_size", &image.size())
+                    .set("position_size", &params.position_size)
+                    .set("output_size", &params.output_size)
+                    .set("screen_bounds", &params.screen_bounds)
+                    .set("motion_vector_strength", &params.motion_vector_strength)
+                    .set("rotation_params", &params.rotation_params);
+                let shader = make_shader(&effects.cursor, uniforms, vec![image.shader()])?;
+                fill(surface, shader, BlendMode::SrcOver, None);
+            }
+            RasterLayer::Warp3D { content, inv_rows } => {
+                let mut plane = new_surface(width, height)?;
+                plane.canvas().clear(skia_safe::Color::TRANSPARENT);
+                for layer in content {
+                    self.draw_layer(&mut plane, layer)?;
+                }
+                let plane_image = plane.image_snapshot();
+                let uniforms = Uniforms::new(&effects.camera3d)
+                    .set("output_size", &output_size)
+                    .set("inv_row0", &inv_rows[0])
+                    .set("inv_row1", &inv_rows[1])
+                    .set("inv_row2", &inv_rows[2]);
+                let shader = make_shader(
+                    &effects.camera3d,
+                    uniforms,
+                    vec![image_shader(&plane_image)],
+                )?;
+                fill(surface, shader, BlendMode::SrcOver, None);
+            }
+            RasterLayer::FocusBlur(params) => {
+                let passes: &[f32] = if params.bokeh { &[2.0] } else { &[0.0, 1.0] };
+                for &pass in passes {
+                    let source_image = surface.image_snapshot();
+                    let mut params0 = params.params0;
+                    params0[0] = pass;
+                    let uniforms = Uniforms::new(&effects.camera3d_blur)
+                        .set("params0", &params0)
+                        .set("params1", &params.params1)
+                        .set("params2", &params.params2);
+                    let shader = make_shader(
+                        &effects.camera3d_blur,
+                        uniforms,
+                        vec![image_shader(&source_image)],
+                    )?;
+                    fill(surface, shader, BlendMode::Src, None);
+                }
+            }
+            RasterLayer::Mask(mask) => self.draw_mask(surface, mask)?,
+            RasterLayer::RoundedRect {
+                rect,
+                color,
+                radius,
+                clip,
+            } => {
+                let uniforms = Uniforms::new(&effects.rounded_rect)
+                    .set("rect", rect)
+                    .set("color", color)
+                    .set("radius", &[*radius]);
+                let shader = make_shader(&effects.rounded_rect, uniforms, vec![])?;
+                fill(surface, shader, BlendMode::SrcOver, Some(*clip));
+            }
+            RasterLayer::Glyphs { glyphs, clip } => {
+                let canvas = surface.canvas();
+                canvas.save();
+                canvas.clip_irect(clip_irect(*clip), ClipOp::Intersect);
+                let paint = Paint::default();
+                for glyph in glyphs {
+                    canvas.draw_image(
+                        &glyph.image.image,
+                        (glyph.left as f32, glyph.top as f32),
+                        Some(&paint),
+                    );
+                }
+                canvas.restore();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_mask(
+        &self,
+        surface: &mut Surface,
+        mask: &MaskParams,
+    ) -> Result<(), SkiaRenderingError> {
+        let (width, height) = (surface.width() as u32, surface.height() as u32);
+        let effect = &self.effects.mask;
+        let uniforms = |mode: f32| {
+            Uniforms::new(effect)
+                .set("output_size", &[width as f32, height as f32])
+                .set("rect_center", &mask.center)
+                .set("rect_size", &mask.size)
+                .set("feather", &[mask.feather])
+                .set("opacity", &[mask.opacity])
+                .set("effect_size", &[mask.effect_size])
+                .set("darkness", &[mask.darkness])
+                .set("mode", &[mode])
+        };
+
+        let source_image = surface.image_snapshot();
+        match mask.mode {
+            MaskMode::Pixelate | MaskMode::Highlight => {
+                let mode = if mask.mode == MaskMode::Pixelate {
+                    0.0
+                } else {
+                    1.0
+                };
+                let shader =
+                    make_shader(effect, uniforms(mode), vec![image_shader(&source_image)])?;
+                fill(surface, shader, BlendMode::Src, None);
+            }
+            MaskMode::Blur => {
+                let mut spare = new_surface(width, height)?;
+                let shader = make_shader(effect, uniforms(2.0), vec![image_shader(&source_image)])?;
+                fill(&mut spare, shader, BlendMode::Src, None);
+
+                let blurred = spare.image_snapshot();
+                let shader = make_shader(effect, uniforms(3.0), vec![image_shader(&blurred)])?;
+                let blender = self
+                    .effects
+                    .mask_blender
+                    .make_blender(skia_safe::Data::new_empty(), &[])
+                    .ok_or_else(|| {
+                        SkiaRenderingError::ShaderCompilationFailed("mask blender".to_string())
+                    })?;
+                let mut paint = Paint::default();
+                paint.set_shader(shader);
+                paint.set_blender(blender);
+                surface.canvas().draw_paint(&paint);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn grade_uniforms<'a>(uniforms: Uniforms<'a>, grade: &ColorGradeParams) -> Uniforms<'a> {
+    uniforms
+        .set("color_adjust_a", &grade.color_adjust_a)
+        .set("color_adjust_b", &grade.color_adjust_b)
+        .set("grain_params", &grade.grain_params)
+}
+
+fn raw_image_info(width: u32, height: u32) -> ImageInfo {
+    ImageInfo::new(
+        (width as i32, height as i32),
+        ColorType::RGBA8888,
+        AlphaType::Premul,
+        None,
+    )
+}
+
+fn new_surface(width: u32, height: u32) -> Result<Surface, SkiaRenderingError> {
+    if width == 0 || height == 0 {
+        return Err(SkiaRenderingError::InvalidDimensions(format!(
+            "{width}x{height}"
+        )));
+    }
+    surfaces::raster(&raw_image_info(width, height), None, None).ok_or_else(|| {
+        SkiaRenderingError::SurfaceCreationFailed(format!("raster {width}x{height}"))
+    })
+}
+
+/// Clamp-to-edge, linearly filtered sampling: the GPU layers' sampler.
+fn image_shader(image: &Image) -> Option<Shader> {
+    image.to_raw_shader(
+        (TileMode::Clamp, TileMode::Clamp),
+        SamplingOptions::new(skia_safe::FilterMode::Linear, skia_safe::MipmapMode::None),
+        None,
+    )
+}
+
+fn make_shader(
+    effect: &skia_safe::RuntimeEffect,
+    uniforms: Uniforms<'_>,
+    children: Vec<Option<Shader>>,
+) -> Result<Shader, SkiaRenderingError> {
+    let children = children
+        .into_iter()
+        .map(|child| child.map(ChildPtr::Shader))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            SkiaRenderingError::ShaderCompilationFailed("missing image shader".to_string())
+        })?;
+    effect
+        .make_shader(uniforms.finish(), &children, None)
+        .ok_or_else(|| {
+            SkiaRenderingError::ShaderCompilationFailed("uniform layout mismatch".to_string())
+        })
+}
+
+fn clip_irect(clip: ClipRect) -> IRect {
+    IRect::from_xywh(
+        clip[0] as i32,
+        clip[1] as i32,
+        clip[2] as i32,
+        clip[3] as i32,
+    )
+}
+
+fn fill(surface: &mut Surface, shader: Shader, blend: BlendMode, clip: Option<ClipRect>) {
+    let canvas = surface.canvas();
+    canvas.save();
+    if let Some(clip) = clip {
+        canvas.clip_irect(clip_irect(clip), ClipOp::Intersect);
+    }
+    let mut paint = Paint::default();
+    paint.set_shader(shader);
+    paint.set_blend_mode(blend);
+    canvas.draw_paint(&paint);
+    canvas.restore();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(pixels: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * width + x) * 4) as usize;
+        [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+    }
+
+    #[test]
+    fn shaders_compile() {
+        CpuCompositor::new().unwrap();
+    }
+
+    #[test]
+    fn solid_gradient_fills_frame() {
+        let compositor = CpuCompositor::new().unwrap();
+        let frame = RasterFrame {
+            width: 8,
+            height: 8,
+            layers: vec![RasterLayer::Gradient(GradientParams {
+                start: [1.0, 0.0, 0.0, 1.0],
+                end: [1.0, 0.0, 0.0, 1.0],
+                ..Default::default()
+            })],
+        };
+        let pixels = compositor.render(&frame).unwrap();
+        assert_eq!(pixel(&pixels, 8, 3, 5), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn card_samples_source_inside_target() {
+        let compositor = CpuCompositor::new().unwrap();
+        let image = RasterImage::from_rgba(4, 4, &[0, 0, 255, 255].repeat(16)).unwrap();
+        let frame = RasterFrame {
+            width: 16,
+            height: 16,
+            layers: vec![
+                RasterLayer::Gradient(GradientParams {
+                    start: [0.0, 0.0, 0.0, 1.0],
+                    end: [0.0, 0.0, 0.0, 1.0],
+                    ..Default::default()
+                }),
+                RasterLayer::Card {
+                    image,
+                    uniforms: CardUniforms {
+                        crop_bounds: [0.0, 0.0, 4.0, 4.0],
+                        target_bounds: [4.0, 4.0, 12.0, 12.0],
+                        output_size: [16.0, 16.0],
+                        frame_size: [4.0, 4.0],
+                        motion_blur_zoom_center: [0.5, 0.5],
+                        target_size: [8.0, 8.0],
+                        opacity: 1.0,
+                        corner_radii: [1.0; 4],
+                        ..Default::default()
+                    },
+                },
+            ],
+        };
+        let pixels = compositor.render(&frame).unwrap();
+        assert_eq!(pixel(&pixels, 16, 8, 8), [0, 0, 255, 255]);
+        assert_eq!(pixel(&pixels, 16, 1, 1), [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,952 @@
+//! SkSL ports of the wgpu shaders in `crates/rendering/src/shaders`.
+//!
+//! Each effect is a line-for-line translation of its WGSL counterpart so the
+//! raster backend produces the same pixels as the GPU compositor. The
+//! differences are forced by SkSL's ES2 profile: loops need constant bounds
+//! (early `break` instead), there are no derivatives (`fwidth` becomes a
+//! forward difference of the same function one pixel over), and WGSL's
+//! `smoothstep` with reversed edges is spelled out as `ss`.
+
+use skia_safe::{Data, RuntimeEffect};
+
+/// Helpers shared by every effect. Must be appended after the uniform
+/// declarations of the effect using them.
+const COMMON: &str = r#"
+float ss(float e0, float e1, float x) {
+    float t = clamp((x - e0) / (e1 - e0), 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+
+float grain_hash(float2 p) {
+    float3 p3 = fract(float3(p.x, p.y, p.x) * 0.1031);
+    p3 += dot(p3, p3.yzx + 33.33);
+    return fract((p3.x + p3.y) * p3.z);
+}
+"#;
+
+/// `apply_color_grade` from composite-video-frame.wgsl, cursor.wgsl and
+/// color-grade.wgsl, on straight-alpha color. Expects `color_adjust_a`,
+/// `color_adjust_b` and `grain_params` uniforms.
+const GRADE: &str = r#"
+float3 grade_rgb(float3 color, float2 vig_uv, float2 frag_pos) {
+    float exposure = color_adjust_a.x;
+    float contrast = color_adjust_a.y;
+    float saturation = color_adjust_a.z;
+    float temperature = color_adjust_a.w;
+    float tint = color_adjust_b.x;
+    float fade = color_adjust_b.y;
+    float split_tone = color_adjust_b.z;
+    float vignette = color_adjust_b.w;
+    float grain = grain_params.x;
+
+    float3 rgb = color * exp2(exposure);
+    rgb = rgb * float3(
+        1.0 + 0.10 * temperature + 0.04 * tint,
+        1.0 - 0.07 * tint,
+        1.0 - 0.10 * temperature + 0.04 * tint);
+    rgb = (rgb - 0.5) * (1.0 + contrast) + 0.5;
+
+    float luma = dot(clamp(rgb, 0.0, 1.0), float3(0.2126, 0.7152, 0.0722));
+    rgb = mix(float3(luma), rgb, 1.0 + saturation);
+
+    float shadow_w = 1.0 - ss(0.2, 0.65, luma);
+    float highlight_w = ss(0.35, 0.8, luma);
+    rgb += split_tone * (
+        shadow_w * float3(-0.06, 0.02, 0.08) +
+        highlight_w * float3(0.08, 0.02, -0.06));
+
+    rgb = rgb * (1.0 - 0.18 * fade) + 0.09 * fade;
+
+    if (vignette > 0.0) {
+        float r = length((vig_uv - 0.5) * 2.0);
+        rgb = rgb * (1.0 - vignette * 0.65 * ss(0.5, 1.5, r));
+    }
+
+    if (grain > 0.0) {
+        float seed = grain_params.y;
+        float grain_noise = grain_hash(frag_pos + float2(seed * 17.0, seed * 29.0));
+        float graded_luma = dot(clamp(rgb, 0.0, 1.0), float3(0.2126, 0.7152, 0.0722));
+        float response = 0.25 + 0.75 * (1.0 - abs(2.0 * graded_luma - 1.0));
+        rgb += (grain_noise - 0.5) * grain * 0.35 * response;
+    }
+
+    return clamp(rgb, 0.0, 1.0);
+}
+"#;
+
+/// composite-video-frame.wgsl. Returns premultiplied color for `SrcOver`,
+/// which equals the GPU pipeline's straight-alpha `ALPHA_BLENDING`.
+const CARD: &str = r#"
+uniform shader frame;
+uniform float2 tex_size;
+uniform float4 crop_bounds;
+uniform float4 target_bounds;
+uniform float2 output_size;
+uniform float2 frame_size;
+uniform float2 motion_blur_vector;
+uniform float2 motion_blur_zoom_center;
+uniform float4 motion_blur_params;
+uniform float2 target_size;
+uniform float rounding_px;
+uniform float rounding_type;
+uniform float mirror_x;
+uniform float shadow;
+uniform float shadow_size;
+uniform float shadow_opacity;
+uniform float shadow_blur;
+uniform float opacity;
+uniform float border_enabled;
+uniform float border_width;
+uniform float preserve_source_alpha;
+uniform float4 border_color;
+uniform float4 corner_radii;
+uniform float4 color_adjust_a;
+uniform float4 color_adjust_b;
+uniform float4 grain_params;
+"#;
+
+const CARD_MAIN: &str = r#"
+const float MAX_ZOOM_RAY_UV = 0.10;
+
+float interleaved_noise(float2 p) {
+    return fract(sin(dot(p, float2(12.9898, 78.233))) * 43758.5453);
+}
+
+float superellipse_norm(float2 p, float power) {
+    float x = pow(abs(p.x), power);
+    float y = pow(abs(p.y), power);
+    return pow(x + y, 1.0 / power);
+}
+
+float rounded_corner_norm(float2 p) {
+    if (rounding_type < 0.5) {
+        return length(p);
+    }
+    return superellipse_norm(p, 4.0);
+}
+
+float corner_radius_for(float2 p) {
+    float multiplier = p.y < 0.0
+        ? (p.x < 0.0 ? corner_radii.x : corner_radii.y)
+        : (p.x < 0.0 ? corner_radii.z : corner_radii.w);
+    return rounding_px * multiplier;
+}
+
+float sdf_rounded_rect(float2 p, float2 b, float r) {
+    float2 q = abs(p) - b + r;
+    float2 outside = max(q, 0.0);
+    return rounded_corner_norm(outside) + min(max(q.x, q.y), 0.0) - r;
+}
+
+float coverage_from_distance(float dist_px, float aa_width) {
+    return clamp(1.0 - ss(-aa_width, aa_width, dist_px), 0.0, 1.0);
+}
+
+float rounded_rect_coverage(float2 p, float2 b, float r) {
+    float dist_px = sdf_rounded_rect(p, b, r);
+    float dx = sdf_rounded_rect(p + float2(1.0, 0.0), b, r) - dist_px;
+    float dy = sdf_rounded_rect(p + float2(0.0, 1.0), b, r) - dist_px;
+    float aa_width = max(abs(dx) + abs(dy), 1.0);
+
+    if (dist_px <= -aa_width) {
+        return 1.0;
+    }
+    if (dist_px >= aa_width) {
+        return 0.0;
+    }
+
+    float o = 0.25;
+    float coverage = coverage_from_distance(sdf_rounded_rect(p + float2(-o, -o), b, r), aa_width);
+    coverage += coverage_from_distance(sdf_rounded_rect(p + float2(o, -o), b, r), aa_width);
+    coverage += coverage_from_distance(sdf_rounded_rect(p + float2(-o, o), b, r), aa_width);
+    coverage += coverage_from_distance(sdf_rounded_rect(p + float2(o, o), b, r), aa_width);
+    return coverage * 0.25;
+}
+
+float4 apply_color_grade(float4 color, float2 target_uv, float2 frag_pos) {
+    if (grain_params.z < 0.5) {
+        return color;
+    }
+    float2 vig_uv = grain_params.w > 0.5 ? frag_pos / output_size : target_uv;
+    return float4(grade_rgb(color.rgb, vig_uv, frag_pos), color.a);
+}
+
+float4 composite_source_over(float4 fg, float4 bg) {
+    float alpha = fg.a + bg.a * (1.0 - fg.a);
+    if (alpha <= 0.0001) {
+        return float4(0.0);
+    }
+    float3 color = (fg.rgb * fg.a + bg.rgb * bg.a * (1.0 - fg.a)) / alpha;
+    return float4(color, alpha);
+}
+
+float4 tex(float2 uv) {
+    return frame.eval(uv * tex_size);
+}
+
+float4 sample_texture(float2 uv, float4 crop_uv) {
+    if (uv.x >= 0.0 && uv.x <= 1.0 && uv.y >= 0.0 && uv.y <= 1.0) {
+        float2 sample_uv = uv;
+        if (mirror_x != 0.0) {
+            sample_uv.x = 1.0 - sample_uv.x;
+        }
+
+        float2 crop_size = crop_uv.zw - crop_uv.xy;
+        float2 texel = 1.0 / frame_size;
+        float2 safe_min = crop_uv.xy + texel;
+        float2 safe_max = crop_uv.zw - texel;
+        float2 cropped_uv = clamp(sample_uv * crop_size + crop_uv.xy, safe_min, safe_max);
+
+        float2 source_size = frame_size * crop_size;
+        float2 scale_ratio = source_size / target_size;
+        bool is_downscaling = max(scale_ratio.x, scale_ratio.y) > 1.1;
+        float upscale_ratio = max(target_size.x / source_size.x, target_size.y / source_size.y);
+        bool is_upscaling = upscale_ratio > 1.05;
+
+        float4 center_sample = tex(cropped_uv);
+        float3 center_color = center_sample.rgb;
+        float out_alpha = preserve_source_alpha > 0.5 ? center_sample.a : 1.0;
+
+        if (is_downscaling || is_upscaling) {
+            float2 offset_x = float2(texel.x, 0.0);
+            float2 offset_y = float2(0.0, texel.y);
+            float3 left = tex(clamp(cropped_uv - offset_x, safe_min, safe_max)).rgb;
+            float3 right = tex(clamp(cropped_uv + offset_x, safe_min, safe_max)).rgb;
+            float3 top = tex(clamp(cropped_uv - offset_y, safe_min, safe_max)).rgb;
+            float3 bottom = tex(clamp(cropped_uv + offset_y, safe_min, safe_max)).rgb;
+            float3 blurred = (left + right + top + bottom) * 0.25;
+            float sharpness = is_downscaling
+                ? min(scale_ratio.x * 0.3, 0.7)
+                : min((upscale_ratio - 1.0) * 0.25, 0.45);
+            float3 sharpened = center_color + (center_color - blurred) * sharpness;
+            return float4(clamp(sharpened, 0.0, 1.0), out_alpha);
+        }
+
+        return float4(center_color, out_alpha);
+    }
+
+    return float4(0.0);
+}
+
+float4 apply_rounded_corners(float4 current_color, float2 target_uv) {
+    float2 centered_uv = (target_uv - 0.5) * target_size;
+    float coverage = rounded_rect_coverage(
+        centered_uv, target_size * 0.5, corner_radius_for(centered_uv));
+    return float4(current_color.rgb, current_color.a * coverage);
+}
+
+float4 card(float2 p) {
+    float2 center = (target_bounds.xy + target_bounds.zw) * 0.5;
+    float2 size = (target_bounds.zw - target_bounds.xy) * 0.5;
+    float2 rel = p - center;
+    float dist = sdf_rounded_rect(rel, size, corner_radius_for(rel));
+
+    float min_frame_size = min(size.x, size.y);
+    bool shadow_enabled = shadow > 0.0;
+    float shadow_strength = shadow / 100.0;
+    float s_size = shadow_enabled
+        ? shadow_strength * (shadow_size / 100.0) * min_frame_size
+        : shadow_strength * min_frame_size;
+    float s_opacity = shadow_enabled
+        ? shadow_strength * (shadow_opacity / 100.0)
+        : shadow_strength * 0.18;
+    float s_blur = shadow_enabled
+        ? shadow_strength * (shadow_blur / 100.0) * min_frame_size
+        : shadow_strength * min_frame_size * 0.5;
+    // A zero-width shadow divides by zero inside `ss`; its opacity is zero
+    // anyway, so skip it rather than risk a NaN.
+    float s_alpha = s_opacity > 0.0 ? ss(s_size + s_blur, -s_blur, abs(dist)) * s_opacity : 0.0;
+    float4 shadow_color = float4(0.0, 0.0, 0.0, s_alpha);
+
+    float2 target_uv = (p - target_bounds.xy) / target_size;
+    float4 crop_uv = float4(crop_bounds.xy / frame_size, crop_bounds.zw / frame_size);
+    float edge_padding = max(2.0, border_width + 2.0);
+    float2 edge_padding_uv = edge_padding / target_size;
+
+    float blur_mode = motion_blur_params.x;
+    float blur_strength = motion_blur_params.y;
+    bool blur_active = blur_mode >= 0.5 && blur_strength >= 0.001;
+    float2 blur_reach_uv = float2(0.0);
+    if (blur_active) {
+        blur_reach_uv = blur_mode < 1.5 ? abs(motion_blur_vector) : float2(MAX_ZOOM_RAY_UV);
+    }
+
+    if (target_uv.x < -edge_padding_uv.x - blur_reach_uv.x ||
+        target_uv.x > 1.0 + edge_padding_uv.x + blur_reach_uv.x ||
+        target_uv.y < -edge_padding_uv.y - blur_reach_uv.y ||
+        target_uv.y > 1.0 + edge_padding_uv.y + blur_reach_uv.y) {
+        return shadow_color;
+    }
+
+    if (border_enabled > 0.0) {
+        float outer = rounded_rect_coverage(
+            rel, size + border_width, corner_radius_for(rel) + border_width);
+        float inner = rounded_rect_coverage(rel, size, corner_radius_for(rel));
+        float border_coverage = clamp(outer - inner, 0.0, 1.0);
+        if (border_coverage > 0.001) {
+            return composite_source_over(
+                float4(border_color.rgb, border_coverage * border_color.a), shadow_color);
+        }
+    }
+
+    float shape_coverage = rounded_rect_coverage(rel, size, corner_radius_for(rel));
+    if (shape_coverage <= 0.001 && !blur_active) {
+        return shadow_color;
+    }
+
+    float4 base_color = sample_texture(clamp(target_uv, 0.0, 1.0), crop_uv);
+    base_color.a = base_color.a * shape_coverage * opacity;
+
+    if (!blur_active) {
+        return composite_source_over(apply_color_grade(base_color, target_uv, p), shadow_color);
+    }
+
+    if (blur_mode < 1.5) {
+        float2 velocity_uv = motion_blur_vector;
+        if (length(velocity_uv) < 1e-5) {
+            return composite_source_over(apply_color_grade(base_color, target_uv, p), shadow_color);
+        }
+
+        float3 accum = float3(0.0);
+        float alpha_sum = 0.0;
+        for (int i = 0; i <= 20; i++) {
+            float2 tap_uv = target_uv + velocity_uv * (float(i) / 20.0);
+            float4 tap = apply_rounded_corners(sample_texture(tap_uv, crop_uv), tap_uv);
+            accum += tap.rgb * tap.a;
+            alpha_sum += tap.a;
+        }
+
+        float out_alpha = (alpha_sum / 21.0) * opacity;
+        if (out_alpha <= 0.0001 || alpha_sum <= 0.0001) {
+            return shadow_color;
+        }
+        return composite_source_over(
+            apply_color_grade(float4(accum / alpha_sum, out_alpha), target_uv, p), shadow_color);
+    }
+
+    float zoom_amount = motion_blur_params.z;
+    float2 dir = motion_blur_zoom_center - target_uv;
+    float center_dist = length(dir);
+    if (center_dist < 1e-4 || zoom_amount < 1e-4) {
+        return composite_source_over(apply_color_grade(base_color, target_uv, p), shadow_color);
+    }
+
+    float2 scaled_dir = dir / center_dist * min(center_dist * min(zoom_amount, 1.0), MAX_ZOOM_RAY_UV);
+    float dither = interleaved_noise(p);
+
+    float3 accum = float3(0.0);
+    float alpha_sum = 0.0;
+    float weight_sum = 0.0;
+    for (int i = 0; i < 13; i++) {
+        float percent = (float(i) + dither) / 13.0;
+        float weight = 4.0 * (percent - percent * percent);
+        float2 tap_uv = target_uv + scaled_dir * percent;
+        float4 tap = apply_rounded_corners(sample_texture(tap_uv, crop_uv), tap_uv);
+        accum += tap.rgb * tap.a * weight;
+        alpha_sum += tap.a * weight;
+        weight_sum += weight;
+    }
+
+    if (weight_sum <= 0.0001 || alpha_sum <= 0.0001) {
+        return shadow_color;
+    }
+    float out_alpha = (alpha_sum / weight_sum) * opacity;
+    if (out_alpha <= 0.0001) {
+        return shadow_color;
+    }
+    return composite_source_over(
+        apply_color_grade(float4(accum / alpha_sum, out_alpha), target_uv, p), shadow_color);
+}
+
+half4 main(float2 p) {
+    float4 c = card(p);
+    return half4(c.rgb * c.a, c.a);
+}
+"#;
+
+/// cursor.wgsl. The vertex stage's rotated quad is inverted per pixel; the
+/// texture is treated as premultiplied exactly like the GPU pipeline's
+/// `One, OneMinusSrcAlpha` blend.
+const CURSOR: &str = r#"
+uniform shader cursor;
+uniform float2 tex_size;
+uniform float4 position_size;
+uniform float4 output_size;
+uniform float4 screen_bounds;
+uniform float4 motion_vector_strength;
+uniform float4 rotation_params;
+uniform float4 color_adjust_a;
+uniform float4 color_adjust_b;
+uniform float4 grain_params;
+"#;
+
+const CURSOR_MAIN: &str = r#"
+const float MAX_ROTATION_RADIANS = 0.34906584;
+const float MAX_CURSOR_BLUR_UV = 4.0;
+
+float2 cursor_velocity_uv() {
+    float2 motion_vec = motion_vector_strength.xy;
+    float blur_strength = motion_vector_strength.z;
+    float2 cursor_size = position_size.zw;
+    if (length(motion_vec) < 0.5 || blur_strength < 0.001 || cursor_size.x <= 0.0 || cursor_size.y <= 0.0) {
+        return float2(0.0);
+    }
+    float2 raw_velocity_uv = motion_vec / cursor_size;
+    float raw_vel_len = length(raw_velocity_uv);
+    if (raw_vel_len < 0.005) {
+        return float2(0.0);
+    }
+    return raw_velocity_uv * min(1.0, MAX_CURSOR_BLUR_UV / raw_vel_len);
+}
+
+float4 sample_cursor(float2 uv) {
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        return float4(0.0);
+    }
+    return cursor.eval(uv * tex_size);
+}
+
+float screen_bounds_mask(float2 frag_pos) {
+    float4 b = screen_bounds;
+    float inside = min(min(frag_pos.x - b.x, b.z - frag_pos.x), min(frag_pos.y - b.y, b.w - frag_pos.y));
+    return clamp(inside + 0.5, 0.0, 1.0);
+}
+
+float4 apply_color_grade(float4 color, float2 frag_pos) {
+    if (grain_params.z < 0.5 || color.a < 0.001) {
+        return color;
+    }
+    float3 rgb = grade_rgb(color.rgb / color.a, frag_pos / output_size.xy, frag_pos);
+    return float4(rgb * color.a, color.a);
+}
+
+half4 main(float2 p) {
+    float2 cursor_size = position_size.zw;
+    if (cursor_size.x <= 0.0 || cursor_size.y <= 0.0) {
+        return half4(0.0);
+    }
+
+    float angle = clamp(rotation_params.z, -MAX_ROTATION_RADIANS, MAX_ROTATION_RADIANS) + rotation_params.y;
+    float c = cos(angle);
+    float s = sin(angle);
+    float2 q = (p - position_size.xy) / cursor_size;
+    float2 uv = float2(c * q.x - s * q.y, s * q.x + c * q.y);
+
+    float2 velocity_uv = cursor_velocity_uv();
+    float2 uv_min = min(float2(0.0), -velocity_uv);
+    float2 uv_max = max(float2(1.0), 1.0 - velocity_uv);
+    if (uv.x < uv_min.x || uv.x > uv_max.x || uv.y < uv_min.y || uv.y > uv_max.y) {
+        return half4(0.0);
+    }
+
+    float blur_strength = motion_vector_strength.z;
+    float opacity = motion_vector_strength.w * screen_bounds_mask(p);
+    float4 color = sample_cursor(uv);
+
+    if (length(velocity_uv) < 0.005 || blur_strength < 0.001) {
+        return half4(apply_color_grade(color * opacity, p));
+    }
+
+    for (int i = 1; i <= 20; i++) {
+        color += sample_cursor(uv + velocity_uv * (float(i) / 20.0));
+    }
+    color /= 21.0;
+    return half4(apply_color_grade(color * opacity, p));
+}
+"#;
+
+/// gradient-or-color.wgsl, written with `Src`.
+const GRADIENT: &str = r#"
+uniform float2 output_size;
+uniform float4 start_color;
+uniform float4 end_color;
+uniform float angle;
+uniform float noise_intensity;
+uniform float noise_scale;
+"#;
+
+const GRADIENT_MAIN: &str = r#"
+float value_noise(float2 p) {
+    float2 i = floor(p);
+    float2 f = fract(p);
+    float2 u_interp = f * f * (3.0 - 2.0 * f);
+    return mix(
+        mix(grain_hash(i), grain_hash(i + float2(1.0, 0.0)), u_interp.x),
+        mix(grain_hash(i + float2(0.0, 1.0)), grain_hash(i + float2(1.0, 1.0)), u_interp.x),
+        u_interp.y);
+}
+
+float fbm(float2 p) {
+    float value = 0.0;
+    float amplitude = 0.5;
+    float2 coord = p;
+    for (int i = 0; i < 4; i++) {
+        value += amplitude * value_noise(coord);
+        coord *= 2.0;
+        amplitude *= 0.5;
+    }
+    return value;
+}
+
+float overlay_channel(float base, float blend) {
+    if (base < 0.5) {
+        return 2.0 * base * blend;
+    }
+    return 1.0 - 2.0 * (1.0 - base) * (1.0 - blend);
+}
+
+half4 main(float2 p) {
+    float2 uv = p / output_size;
+    float angle_rad = radians(angle + 270.0);
+    float2 dir = float2(cos(angle_rad), sin(angle_rad));
+    float t = clamp(dot(uv - 0.5, dir) + 0.5, 0.0, 1.0);
+    float4 color = mix(start_color, end_color, t);
+
+    if (noise_intensity > 0.0) {
+        float freq = 0.3 + ((100.0 - noise_scale) / 100.0) * 1.2;
+        float n = fbm(uv * freq * 600.0);
+        float3 blended = float3(
+            overlay_channel(color.r, n),
+            overlay_channel(color.g, n),
+            overlay_channel(color.b, n));
+        float intensity = (noise_intensity / 100.0) * 0.25;
+        color = float4(mix(color.rgb, blended, intensity), color.a);
+    }
+
+    return half4(color);
+}
+"#;
+
+/// image-background.wgsl, written with `Src`.
+const IMAGE_BACKGROUND: &str = r#"
+uniform shader image;
+uniform float2 tex_size;
+uniform float2 output_size;
+uniform float x_width;
+uniform float y_height;
+"#;
+
+const IMAGE_BACKGROUND_MAIN: &str = r#"
+half4 main(float2 p) {
+    float2 uv = p / output_size;
+    float2 image_uv = float2(
+        x_width + (0.5 - x_width) * 2.0 * uv.x,
+        y_height + (0.5 - y_height) * 2.0 * uv.y);
+    return half4(image.eval(image_uv * tex_size));
+}
+"#;
+
+/// One pass of background-blur.wgsl, written with `Src`.
+const BACKGROUND_BLUR: &str = r#"
+uniform shader source;
+uniform float2 output_size;
+uniform float blur_strength;
+uniform float direction;
+"#;
+
+const BACKGROUND_BLUR_MAIN: &str = r#"
+half4 main(float2 p) {
+    float4 color = source.eval(p);
+    if (blur_strength <= 0.001) {
+        return half4(color);
+    }
+
+    float sigma = blur_strength * output_size.y * 0.025;
+    float radius = sigma * 3.0;
+    float taps = min(24.0, ceil(radius));
+    float spacing = radius / taps;
+    float2 step_px = direction > 0.5 ? float2(0.0, spacing) : float2(spacing, 0.0);
+
+    float total_weight = 1.0;
+    float inv_sigma2 = 1.0 / (2.0 * sigma * sigma);
+    int n = int(taps);
+
+    for (int i = 1; i <= 24; i++) {
+        if (i > n) {
+            break;
+        }
+        float dist = float(i) * spacing;
+        float weight = exp(-dist * dist * inv_sigma2);
+        float2 offset = step_px * float(i);
+        color += source.eval(p + offset) * weight;
+        color += source.eval(p - offset) * weight;
+        total_weight += 2.0 * weight;
+    }
+
+    return half4(color / total_weight);
+}
+"#;
+
+/// color-grade.wgsl, written with `Src`.
+const COLOR_GRADE: &str = r#"
+uniform shader source;
+uniform float2 output_size;
+uniform float4 color_adjust_a;
+uniform float4 color_adjust_b;
+uniform float4 grain_params;
+"#;
+
+const COLOR_GRADE_MAIN: &str = r#"
+half4 main(float2 p) {
+    float4 color = source.eval(p);
+    return half4(grade_rgb(color.rgb, p / output_size, p), color.a);
+}
+"#;
+
+/// mask.wgsl. Pixelate, highlight and the horizontal blur pass are written
+/// with `Src`; the vertical blur pass goes through [`MASK_BLENDER`].
+const MASK: &str = r#"
+uniform shader source;
+uniform float2 output_size;
+uniform float2 rect_center;
+uniform float2 rect_size;
+uniform float feather;
+uniform float opacity;
+uniform float effect_size;
+uniform float darkness;
+uniform float mode;
+"#;
+
+const MASK_MAIN: &str = r#"
+float rect_mask(float2 uv) {
+    float2 delta = abs(uv - rect_center) - rect_size * 0.5;
+    float sdf = length(max(delta, 0.0)) + min(max(delta.x, delta.y), 0.0);
+    float edge = max(feather, 1e-4);
+    return clamp(ss(0.0, edge, -sdf), 0.0, 1.0);
+}
+
+float4 pixelate_sample(float2 uv) {
+    float2 cell = max(effect_size, 1.0) / output_size;
+    float2 snapped = floor(uv / cell) * cell + cell * 0.5;
+    float2 coord = clamp(floor(snapped * output_size), float2(0.0), output_size - 1.0);
+    return source.eval(coord + 0.5);
+}
+
+float4 blur_sample(float2 uv, float2 direction) {
+    float2 step_uv = direction * max(effect_size, 1.0) / (output_size * 12.0);
+    float4 color = float4(0.0);
+    float weight_sum = 0.0;
+    for (int index = -12; index <= 12; index++) {
+        float dist_px = float(index) / 4.0;
+        float weight = exp(-0.5 * dist_px * dist_px);
+        color += source.eval((uv + float(index) * step_uv) * output_size) * weight;
+        weight_sum += weight;
+    }
+    return color / weight_sum;
+}
+
+bool horizontal_blur_support(float2 uv) {
+    float2 delta = abs(uv - rect_center);
+    float2 half_size = rect_size * 0.5;
+    return delta.x <= half_size.x && delta.y <= half_size.y + effect_size / output_size.y;
+}
+
+half4 main(float2 p) {
+    float2 uv = p / output_size;
+    float4 base = source.eval(p);
+    float mask = rect_mask(uv);
+
+    if (mode < 0.5) {
+        float4 effect = float4(pixelate_sample(uv).rgb, base.a);
+        return half4(mix(base, effect, mask));
+    }
+
+    if (mode < 1.5) {
+        float dim = clamp(darkness * opacity, 0.0, 1.0);
+        float4 outside = float4(base.rgb * (1.0 - dim), base.a);
+        return half4(mix(outside, base, mask));
+    }
+
+    if (mode < 2.5) {
+        if (!horizontal_blur_support(uv)) {
+            return half4(base);
+        }
+        return half4(blur_sample(uv, float2(1.0, 0.0)).rgb, base.a);
+    }
+
+    // Vertical pass: `discard` becomes a fully transparent source.
+    if (mask <= 0.0) {
+        return half4(0.0);
+    }
+    float4 blurred = blur_sample(uv, float2(0.0, 1.0));
+    return half4(blurred.rgb * mask, mask);
+}
+"#;
+
+/// The vertical mask-blur pass blends color with `SrcAlpha,
+/// OneMinusSrcAlpha` and keeps the destination alpha (`Zero, One`).
+const MASK_BLENDER: &str = r#"
+half4 main(half4 src, half4 dst) {
+    return half4(src.rgb + dst.rgb * (1.0 - src.a), dst.a);
+}
+"#;
+
+/// camera3d.wgsl, composited with `SrcOver` (premultiplied content).
+const CAMERA3D: &str = r#"
+uniform shader content;
+uniform float2 output_size;
+uniform float4 inv_row0;
+uniform float4 inv_row1;
+uniform float4 inv_row2;
+"#;
+
+const CAMERA3D_MAIN: &str = r#"
+float3 project(float2 p) {
+    float2 screen_uv = p / output_size;
+    float3 ndc = float3(screen_uv.x * 2.0 - 1.0, 1.0 - screen_uv.y * 2.0, 1.0);
+    float3 q = float3(dot(inv_row0.xyz, ndc), dot(inv_row1.xyz, ndc), dot(inv_row2.xyz, ndc));
+    float denom = abs(q.z) <= 1e-6 ? 1e-6 : q.z;
+    float2 plane = q.xy / denom;
+    float2 half_extents = float2(inv_row1.w, inv_row2.w);
+    float2 content_uv = float2(
+        (plane.x / half_extents.x + 1.0) * 0.5,
+        (1.0 - plane.y / half_extents.y) * 0.5);
+    return float3(content_uv, q.z);
+}
+
+half4 main(float2 p) {
+    float3 projected = project(p);
+    float2 content_uv = projected.xy;
+    float in_front = projected.z > 1e-6 ? 1.0 : 0.0;
+
+    float2 dx = project(p + float2(1.0, 0.0)).xy - content_uv;
+    float2 dy = project(p + float2(0.0, 1.0)).xy - content_uv;
+    float2 width = max(abs(dx) + abs(dy), float2(1e-6));
+    float2 edge = float2(ss(0.0, width.x, content_uv.x), ss(0.0, width.y, content_uv.y))
+        * float2(ss(0.0, width.x, 1.0 - content_uv.x), ss(0.0, width.y, 1.0 - content_uv.y));
+    float coverage = edge.x * edge.y * in_front;
+
+    float4 color = content.eval(clamp(content_uv, 0.0, 1.0) * output_size);
+    return half4(color * coverage);
+}
+"#;
+
+/// One pass of camera3d-blur.wgsl, written with `Src`.
+const CAMERA3D_BLUR: &str = r#"
+uniform shader source;
+uniform float4 params0;
+uniform float4 params1;
+uniform float4 params2;
+"#;
+
+const CAMERA3D_BLUR_MAIN: &str = r#"
+float blur_amount(float2 gl_uv) {
+    float mode = params0.y;
+    float strength = params0.z;
+    float falloff = params0.w;
+    float2 resolution = params2.yz;
+    float aspect = resolution.x / max(resolution.y, 1.0);
+
+    float widen = 1.0 + falloff * 3.0;
+    float curve = mix(2.0, 0.7, falloff);
+
+    if (mode == 2.0) {
+        float2 dir = float2(cos(params1.w), sin(params1.w));
+        float2 diff = gl_uv - 0.5;
+        diff.x = diff.x * aspect;
+        float projected = dot(diff, dir);
+        float reach = aspect * 0.5 + 0.5;
+        float threshold = mix(-reach, reach, params2.x);
+        float d = max(projected - threshold, 0.0);
+        return pow(ss(0.0, 0.7 * widen, d), curve) * strength;
+    }
+
+    float2 diff = gl_uv - params1.xy;
+    diff.x = diff.x * aspect;
+
+    float dist = 0.0;
+    if (mode == 3.0) {
+        float2 perp = float2(sin(params1.w), cos(params1.w));
+        dist = max(abs(dot(diff, perp)) - params1.z * 0.5, 0.0);
+    } else {
+        dist = length(diff);
+    }
+
+    float edge = params1.z * 0.5;
+    return pow(ss(0.0, (edge + 0.35) * widen, dist - edge), curve) * strength;
+}
+
+float4 gaussian(float2 gl_uv, float2 p, bool horizontal) {
+    float coc = blur_amount(gl_uv);
+    float4 center = source.eval(p);
+    if (coc < 0.5) {
+        return center;
+    }
+
+    float2 resolution = params2.yz;
+    float radius_cap = min(resolution.y * (40.0 / 1080.0), 160.0);
+    int radius = int(min(coc, radius_cap));
+    float sigma = coc * 0.5;
+    float inv_sigma2 = 1.0 / (2.0 * sigma * sigma);
+
+    float4 color = float4(0.0);
+    float total = 0.0;
+    for (int i = -160; i <= 160; i++) {
+        if (i < -radius) {
+            continue;
+        }
+        if (i > radius) {
+            break;
+        }
+        float fi = float(i);
+        float weight = exp(-fi * fi * inv_sigma2);
+        float2 offset = horizontal ? float2(fi, 0.0) : float2(0.0, fi);
+        color += source.eval(p + offset) * weight;
+        total += weight;
+    }
+    return color / max(total, 1e-6);
+}
+
+float4 bokeh(float2 gl_uv, float2 p) {
+    float coc = blur_amount(gl_uv);
+    float4 center = source.eval(p);
+    if (coc < 0.5) {
+        return center;
+    }
+
+    float2 resolution = params2.yz;
+    float aspect = resolution.x / max(resolution.y, 1.0);
+    float ring_step = coc / 3.0;
+
+    float4 acc = center;
+    float wsum = 1.0;
+    for (int ring = 1; ring <= 3; ring++) {
+        int ring_samples = ring * 5;
+        float r = float(ring) * ring_step;
+        float ring_weight = mix(1.0, float(ring) / 3.0, 0.3);
+        for (int j = 0; j < 15; j++) {
+            if (j >= ring_samples) {
+                break;
+            }
+            float a = 6.28318530718 * float(j) / float(ring_samples);
+            float2 offset_uv = float2(cos(a) / aspect, sin(a)) * r / max(resolution.y, 1.0);
+            float2 tap = p + float2(offset_uv.x, -offset_uv.y) * resolution;
+            float4 s = source.eval(tap);
+            float luma = dot(s.rgb, float3(0.299, 0.587, 0.114));
+            float gain = 1.0 + ss(0.7, 1.0, luma) * 1.5;
+            acc += s * ring_weight * gain;
+            wsum += ring_weight * gain;
+        }
+    }
+    return acc / wsum;
+}
+
+half4 main(float2 p) {
+    float2 resolution = params2.yz;
+    float2 screen_uv = p / resolution;
+    float2 gl_uv = float2(screen_uv.x, 1.0 - screen_uv.y);
+    if (params0.x == 2.0) {
+        return half4(bokeh(gl_uv, p));
+    }
+    return half4(gaussian(gl_uv, p, params0.x == 0.0));
+}
+"#;
+
+/// caption_bg.wgsl (also used by the keyboard overlay), composited with
+/// `SrcOver`.
+const ROUNDED_RECT: &str = r#"
+uniform float4 rect;
+uniform float4 color;
+uniform float radius;
+"#;
+
+const ROUNDED_RECT_MAIN: &str = r#"
+float squircle_sdf(float2 p, float2 half_size, float r) {
+    float2 q = abs(p) - (half_size - r);
+    if (q.x <= 0.0 && q.y <= 0.0) {
+        return max(q.x, q.y) - r;
+    }
+    float2 corner = max(q, 0.0);
+    return pow(pow(corner.x, 4.0) + pow(corner.y, 4.0), 0.25) - r;
+}
+
+half4 main(float2 p) {
+    float2 half_size = rect.zw * 0.5;
+    float dist_px = squircle_sdf(p - (rect.xy + half_size), half_size, radius);
+    float alpha = color.a * (1.0 - ss(-1.5, 1.5, dist_px));
+    return half4(color.rgb * alpha, alpha);
+}
+"#;
+
+pub(super) struct Effects {
+    pub card: RuntimeEffect,
+    pub cursor: RuntimeEffect,
+    pub gradient: RuntimeEffect,
+    pub image_background: RuntimeEffect,
+    pub background_blur: RuntimeEffect,
+    pub color_grade: RuntimeEffect,
+    pub mask: RuntimeEffect,
+    pub mask_blender: RuntimeEffect,
+    pub camera3d: RuntimeEffect,
+    pub camera3d_blur: RuntimeEffect,
+    pub rounded_rect: RuntimeEffect,
+}
+
+impl Effects {
+    pub fn compile() -> Result<Self, String> {
+        let shader = |name: &str, parts: &[&str]| {
+            RuntimeEffect::make_for_shader(parts.concat(), None)
+                .map_err(|err| format!("{name}: {err}"))
+        };
+
+        Ok(Self {
+            card: shader("card", &[CARD, COMMON, GRADE, CARD_MAIN])?,
+            cursor: shader("cursor", &[CURSOR, COMMON, GRADE, CURSOR_MAIN])?,
+            gradient: shader("gradient", &[GRADIENT, COMMON, GRADIENT_MAIN])?,
+            image_background: shader(
+                "image background",
+                &[IMAGE_BACKGROUND, IMAGE_BACKGROUND_MAIN],
+            )?,
+            background_blur: shader("background blur", &[BACKGROUND_BLUR, BACKGROUND_BLUR_MAIN])?,
+            color_grade: shader(
+                "color grade",
+                &[COLOR_GRADE, COMMON, GRADE, COLOR_GRADE_MAIN],
+            )?,
+            mask: shader("mask", &[MASK, COMMON, MASK_MAIN])?,
+            mask_blender: RuntimeEffect::make_for_blender(MASK_BLENDER, None)
+                .map_err(|err| format!("mask blender: {err}"))?,
+            camera3d: shader("camera3d", &[CAMERA3D, COMMON, CAMERA3D_MAIN])?,
+            camera3d_blur: shader(
+                "camera3d blur",
+                &[CAMERA3D_BLUR, COMMON, CAMERA3D_BLUR_MAIN],
+            )?,
+            rounded_rect: shader("rounded rect", &[ROUNDED_RECT, COMMON, ROUNDED_RECT_MAIN])?,
+        })
+    }
+}
+
+/// Packs uniforms by name into the layout the effect reports, so the Rust
+/// side never has to mirror SkSL's packing rules.
+pub(super) struct Uniforms<'a> {
+    effect: &'a RuntimeEffect,
+    bytes: Vec<u8>,
+}
+
+impl<'a> Uniforms<'a> {
+    pub fn new(effect: &'a RuntimeEffect) -> Self {
+        Self {
+            effect,
+            bytes: vec![0; effect.uniform_size()],
+        }
+    }
+
+    pub fn set(mut self, name: &str, values: &[f32]) -> Self {
+        let Some(uniform) = self.effect.find_uniform(name) else {
+            debug_assert!(false, "unknown uniform {name}");
+            return self;
+        };
+        let offset = uniform.offset();
+        let len = (values.len() * 4).min(uniform.size_in_bytes());
+        for (i, chunk) in self.bytes[offset..offset + len]
+            .chunks_exact_mut(4)
+            .enumerate()
+        {
+            chunk.copy_from_slice(&values[i].to_ne_bytes());
+        }
+        self
+    }
+
+    pub fn finish(self) -> Data {
+        Data::new_copy(&self.bytes)
+    }
+}
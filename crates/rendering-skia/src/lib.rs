@@ -1,8 +1,10 @@
 use thiserror::Error;
 
+pub mod compositor;
 pub mod context;
 pub mod layers;
 
+pub use compositor::{CpuCompositor, RasterFrame, RasterImage, RasterLayer};
 pub use context::SkiaRenderContext;
 pub use layers::{BackgroundLayer, LayerStack};
 
@@ -17,6 +19,9 @@ pub enum SkiaRenderingError {
     #[error("Failed to read pixels from surface")]
     ReadPixelsFailed,
 
+    #[error("Failed to compile shader: {0}")]
+    ShaderCompilationFailed(String),
+
     #[error("Invalid dimensions: {0}")]
     InvalidDimensions(String),
 
@@ -0,0 +1,1332 @@
+//! Software counterpart of [`RendererLayers`] for machines without a usable GPU.
+//!
+//! Frames are described as a [`RasterFrame`] in exactly the order
+//! [`RendererLayers::render`] draws them, and drawn by the Skia compositor in
+//! `cap-rendering-skia`, whose shaders are ports of the WGSL ones. Layout
+//! (uniforms, text shaping, cursor motion, chrome rasterization) is shared
+//! with the GPU path, so the two only differ by rasterization rounding.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use cap_project::{
+    ClipTransitionType, CursorEvents, CursorType, FrameStyle, FrameTheme, ProjectConfiguration,
+    RecordingMeta, StudioRecordingMeta, TimelineFrameMapping, XY,
+};
+use cap_rendering_skia::compositor::{
+    CardUniforms, ColorGradeParams, CursorParams, FocusBlurParams, GlyphSprite, GradientParams,
+    MaskMode, MaskParams,
+};
+use cap_rendering_skia::{CpuCompositor, RasterFrame, RasterImage, RasterLayer};
+use glyphon::cosmic_text::SwashContent;
+use glyphon::{Buffer, FontSystem, SwashCache};
+use tokio::sync::mpsc;
+
+use crate::composite_frame::{ColorGradeUniformParams, CompositeVideoFrameUniforms};
+use crate::frame_pipeline::{NV12BufferPool, Nv12RenderedFrame};
+use crate::layers::{
+    Background, CaptionBox, CursorSprite, GradientOrColorUniforms, MAX_BACKGROUND_DIMENSION,
+    TextPlacement, camera3d_passes, circle_cursor_image, cursor_uniforms, decode_background_rgba,
+    image_cover_inset, layout_caption, layout_keyboard, layout_texts, load_cursor_image,
+    uniforms_for_source_frame,
+};
+use crate::{
+    DECODE_MAX_RETRIES_INITIAL, DECODE_MAX_RETRIES_STEADY, DecodedFrame, DecodedSegmentFrames,
+    MAX_INITIAL_CONSECUTIVE_FAILURES, MaskRenderMode, PrecomputedCursorTimeline,
+    ProjectRecordingsMeta, ProjectUniforms, RenderOptions, RenderSegment, RenderedFrame,
+    RendererLayers, RenderingError, SegmentRecordings, ZoomTransformTimeline,
+    blur_mode_from_config, decode_segment_frames_with_retry, decode_timeline_source_frames,
+    frame_chrome, get_duration, notch_shape, rgba_frame_to_nv12, spring_mass_damper,
+};
+
+/// What the CPU renderer needs from [`RenderVideoConstants`](crate::RenderVideoConstants),
+/// without a wgpu device.
+pub struct CpuRenderConstants {
+    pub options: RenderOptions,
+    pub meta: StudioRecordingMeta,
+    pub recording_meta: RecordingMeta,
+}
+
+impl CpuRenderConstants {
+    pub fn new(
+        segments: &[SegmentRecordings],
+        recording_meta: RecordingMeta,
+        meta: StudioRecordingMeta,
+    ) -> Result<Self, RenderingError> {
+        let first_segment = segments.first().ok_or(RenderingError::NoSegments)?;
+
+        let options = RenderOptions {
+            screen_size: XY::new(first_segment.display.width, first_segment.display.height),
+            camera_size: first_segment
+                .camera
+                .as_ref()
+                .map(|c| XY::new(c.width, c.height)),
+            preserve_screen_alpha: false,
+        };
+
+        Ok(Self {
+            options,
+            meta,
+            recording_meta,
+        })
+    }
+
+    /// Fails for projects that use an effect the CPU renderer can't draw, so
+    /// an export never silently differs from the GPU one.
+    pub fn ensure_supported(&self, project: &ProjectConfiguration) -> Result<(), RenderingError> {
+        match unsupported_effect(project, &self.options) {
+            Some(effect) => Err(RenderingError::CpuUnsupported(effect)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Camera background blur needs the segmentation model the GPU blur runs on,
+/// which the CPU renderer doesn't have.
+fn unsupported_effect(
+    project: &ProjectConfiguration,
+    options: &RenderOptions,
+) -> Option<&'static str> {
+    (options.camera_size.is_some()
+        && !project.camera.hide
+        && blur_mode_from_config(&project.camera.background_blur).is_some())
+    .then_some("camera background blur")
+}
+
+type ChromeKey = (FrameStyle, FrameTheme, String, String, (u32, u32));
+
+/// Holds what [`RendererLayers`] keeps in textures between frames: decoded
+/// sources that may be reused when no new frame arrives, and rasterized
+/// assets.
+pub(crate) struct CpuFrameRenderer {
+    compositor: CpuCompositor,
+    font_system: FontSystem,
+    swash_cache: SwashCache,
+    background: Option<(String, Option<RasterImage>)>,
+    chrome: Option<(ChromeKey, Option<RasterImage>)>,
+    notch: Option<((u32, u32), Option<RasterImage>)>,
+    cursors: HashMap<(String, bool), Option<(RasterImage, XY<f64>)>>,
+    circle_cursor: Option<(RasterImage, XY<f64>)>,
+    cursor: Option<(RasterImage, CursorParams)>,
+    display: Option<(f32, RasterImage)>,
+    camera: Option<(f32, RasterImage)>,
+    camera_only: Option<(f32, RasterImage)>,
+}
+
+impl CpuFrameRenderer {
+    pub fn new() -> Result<Self, RenderingError> {
+        Ok(Self {
+            compositor: CpuCompositor::new()?,
+            font_system: crate::layers::new_font_system(),
+            swash_cache: SwashCache::new(),
+            background: None,
+            chrome: None,
+            notch: None,
+            cursors: HashMap::new(),
+            circle_cursor: None,
+            cursor: None,
+            display: None,
+            camera: None,
+            camera_only: None,
+        })
+    }
+
+    pub fn render(
+        &mut self,
+        constants: &CpuRenderConstants,
+        uniforms: &ProjectUniforms,
+        segment_frames: &DecodedSegmentFrames,
+        cursor: &CursorEvents,
+        render_display: bool,
+    ) -> Result<RenderedFrame, RenderingError> {
+        let frame =
+            self.raster_frame(constants, uniforms, segment_frames, cursor, render_display)?;
+        let data = self.compositor.render(&frame)?;
+
+        Ok(RenderedFrame {
+            data: Arc::new(data),
+            width: frame.width,
+            height: frame.height,
+            padded_bytes_per_row: frame.width * 4,
+            frame_number: uniforms.frame_number,
+            target_time_ns: (uniforms.frame_number as u64 * 1_000_000_000)
+                / uniforms.frame_rate.max(1) as u64,
+        })
+    }
+
+    fn raster_frame(
+        &mut self,
+        constants: &CpuRenderConstants,
+        uniforms: &ProjectUniforms,
+        segment_frames: &DecodedSegmentFrames,
+        cursor: &CursorEvents,
+        render_display: bool,
+    ) -> Result<RasterFrame, RenderingError> {
+        let options = &constants.options;
+        let (width, height) = uniforms.output_size;
+        let mut layers = vec![self.background_layer(uniforms, options)];
+
+        if uniforms.project.background.blur > 0.0 {
+            layers.push(RasterLayer::BackgroundBlur {
+                strength: uniforms.project.background.blur as f32 / 100.0,
+            });
+        }
+        if uniforms.screen_color_grade.is_active() {
+            layers.push(RasterLayer::ColorGrade(grade_params(
+                &uniforms.screen_color_grade,
+            )));
+        }
+
+        let mut display_card = None;
+        let mut chrome_card = None;
+        let mut notch_card = None;
+        if render_display {
+            chrome_card = self.chrome_card(uniforms);
+            notch_card = self.notch_card(uniforms);
+            display_card = Some(self.display_card(uniforms, segment_frames, options)?);
+        }
+
+        self.update_cursor(constants, uniforms, segment_frames, cursor);
+
+        let camera_frame = if segment_frames.segment_has_camera {
+            options.camera_size.and_then(|_| {
+                segment_frames
+                    .camera_frame
+                    .as_ref()
+                    .map(|frame| (frame, segment_frames.recording_time))
+            })
+        } else {
+            None
+        };
+        let (camera_uniforms, camera_only_uniforms) = if segment_frames.segment_has_camera {
+            (uniforms.camera, uniforms.camera_only)
+        } else {
+            (None, None)
+        };
+        let camera_card = prepare_camera_card(&mut self.camera, camera_uniforms, camera_frame);
+        let camera_only_card =
+            prepare_camera_card(&mut self.camera_only, camera_only_uniforms, camera_frame);
+
+        if (camera_card.is_some() || camera_only_card.is_some())
+            && let Some(effect) = unsupported_effect(&uniforms.project, options)
+        {
+            return Err(RenderingError::CpuUnsupported(effect));
+        }
+
+        let should_render_screen = render_display
+            && uniforms.scene.should_render_screen()
+            && uniforms.display.opacity > 0.001
+            && self.display.is_some();
+        let should_render_cursor = if render_display {
+            uniforms.scene.should_render_screen()
+        } else {
+            true
+        };
+
+        let mut content = Vec::new();
+        if should_render_screen && let Some(card) = chrome_card {
+            content.push(card);
+        }
+        if should_render_screen && let Some(card) = display_card.flatten() {
+            content.push(card);
+        }
+        if should_render_cursor && let Some((image, params)) = &self.cursor {
+            content.push(RasterLayer::Cursor {
+                image: image.clone(),
+                params: params.clone(),
+            });
+        }
+        if should_render_screen && let Some(card) = notch_card {
+            content.push(card);
+        }
+        if render_display && uniforms.scene.should_render_screen() {
+            for (pip_uniforms, frame) in RendererLayers::pip_frames(uniforms, segment_frames) {
+                if let Some(image) = frame_image(frame) {
+                    content.push(RasterLayer::Card {
+                        image,
+                        uniforms: card_uniforms(&pip_uniforms),
+                    });
+                }
+            }
+        }
+        if uniforms.scene.is_transitioning_camera_only()
+            && let Some(card) = camera_only_card
+        {
+            content.push(card);
+        }
+        if uniforms.scene.should_render_camera()
+            && uniforms.scene.regular_camera_transition_opacity() > 0.01
+            && let Some(card) = camera_card
+        {
+            content.push(card);
+        }
+
+        let camera3d = camera3d_passes(uniforms);
+        match camera3d.warp {
+            Some(warp) => layers.push(RasterLayer::Warp3D {
+                content,
+                inv_rows: [warp.inv_row0, warp.inv_row1, warp.inv_row2],
+            }),
+            None => layers.extend(content),
+        }
+        if let Some((kind, h, _)) = camera3d.blur {
+            layers.push(RasterLayer::FocusBlur(FocusBlurParams {
+                params0: h.params0,
+                params1: h.params1,
+                params2: h.params2,
+                bokeh: kind == crate::layers::Camera3DBlurKind::Bokeh,
+            }));
+        }
+
+        layers.extend(uniforms.masks.iter().map(|mask| {
+            RasterLayer::Mask(MaskParams {
+                center: [mask.center.x, mask.center.y],
+                size: [mask.size.x, mask.size.y],
+                feather: mask.feather,
+                opacity: mask.opacity,
+                effect_size: mask.effect_size,
+                darkness: mask.darkness,
+                mode: match mask.mode {
+                    MaskRenderMode::Pixelate => MaskMode::Pixelate,
+                    MaskRenderMode::Highlight => MaskMode::Highlight,
+                    MaskRenderMode::Blur => MaskMode::Blur,
+                },
+            })
+        }));
+
+        let output_size = XY::new(width, height);
+        let full_frame = [0, 0, width, height];
+
+        if !uniforms.texts.is_empty() {
+            let (buffers, placements) =
+                layout_texts(&mut self.font_system, uniforms.output_size, &uniforms.texts);
+            layers.push(RasterLayer::Glyphs {
+                glyphs: self.glyph_sprites(&buffers, &placements, uniforms.output_size),
+                clip: full_frame,
+            });
+        }
+
+        let caption = layout_caption(&mut self.font_system, uniforms, output_size);
+        let caption_layout = caption.as_ref().map(|caption| caption.layout);
+
+        if let Some(keyboard) =
+            layout_keyboard(&mut self.font_system, uniforms, output_size, caption_layout)
+            && let Some(scissor) = keyboard.scissor
+        {
+            layers.push(rounded_rect(keyboard.background, scissor));
+            layers.push(RasterLayer::Glyphs {
+                glyphs: self.glyph_sprites(
+                    std::slice::from_ref(&keyboard.buffer),
+                    &[keyboard.placement],
+                    uniforms.output_size,
+                ),
+                clip: scissor,
+            });
+        }
+
+        if let Some(caption) = caption
+            && let Some(scissor) = caption.scissor
+        {
+            layers.push(rounded_rect(caption.background, scissor));
+            if let Some((pill, pill_scissor)) = caption.highlight {
+                layers.push(rounded_rect(pill, pill_scissor));
+            }
+            layers.push(RasterLayer::Glyphs {
+                glyphs: self.glyph_sprites(
+                    std::slice::from_ref(&caption.buffer),
+                    &caption.placements,
+                    uniforms.output_size,
+                ),
+                clip: scissor,
+            });
+        }
+
+        Ok(RasterFrame {
+            width,
+            height,
+            layers,
+        })
+    }
+
+    fn background_layer(
+        &mut self,
+        uniforms: &ProjectUniforms,
+        options: &RenderOptions,
+    ) -> RasterLayer {
+        let background = Background::from_source(
+            uniforms.project.background.source.clone(),
+            options.preserve_screen_alpha,
+        );
+
+        let Background::Image { path } = background else {
+            return gradient_layer(background);
+        };
+
+        if self.background.as_ref().map(|(cached, _)| cached) != Some(&path) {
+            let image = match decode_background_rgba(&path, MAX_BACKGROUND_DIMENSION) {
+                Ok((rgba, width, height)) => RasterImage::from_rgba(width, height, &rgba),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load background image '{}': {}. Falling back to solid color.",
+                        path,
+                        e
+                    );
+                    None
+                }
+            };
+            self.background = Some((path, image));
+        }
+
+        match self
+            .background
+            .as_ref()
+            .and_then(|(_, image)| image.clone())
+        {
+            Some(image) => {
+                let (x_width, y_height) =
+                    image_cover_inset(uniforms.output_size, image.width(), image.height());
+                RasterLayer::Image {
+                    image,
+                    inset: [x_width, y_height],
+                }
+            }
+            None => gradient_layer(Background::Color([1.0, 1.0, 1.0, 1.0])),
+        }
+    }
+
+    fn chrome_card(&mut self, uniforms: &ProjectUniforms) -> Option<RasterLayer> {
+        let chrome = uniforms.frame_chrome.as_ref()?;
+        if chrome.composite.opacity <= 0.001 {
+            return None;
+        }
+
+        let (tex_w, tex_h) =
+            frame_chrome::chrome_texture_size(chrome.raster_size.x, chrome.raster_size.y);
+        let key = (
+            chrome.style,
+            chrome.theme,
+            chrome.url.clone(),
+            chrome.title.clone(),
+            (tex_w, tex_h),
+        );
+
+        if self.chrome.as_ref().map(|(cached, _)| cached) != Some(&key) {
+            let scale = tex_w as f64 / chrome.raster_size.x.max(1.0);
+            let image = frame_chrome::rasterize_chrome(
+                chrome.style,
+                chrome.theme,
+                &chrome.url,
+                &chrome.title,
+                tex_w,
+                tex_h,
+                chrome.content_height * scale,
+            )
+            .and_then(|rgba| RasterImage::from_rgba(tex_w, tex_h, &rgba));
+            self.chrome = Some((key, image));
+        }
+
+        let image = self.chrome.as_ref()?.1.clone()?;
+        let mut composite = chrome.composite;
+        composite.frame_size = [tex_w as f32, tex_h as f32];
+        composite.crop_bounds = [0.0, 0.0, tex_w as f32, tex_h as f32];
+
+        Some(RasterLayer::Card {
+            image,
+            uniforms: card_uniforms(&composite),
+        })
+    }
+
+    fn notch_card(&mut self, uniforms: &ProjectUniforms) -> Option<RasterLayer> {
+        let notch = uniforms.notch?;
+        if notch.composite.opacity <= 0.001 {
+            return None;
+        }
+
+        let (tex_w, tex_h) = notch_shape::texture_size(notch.raster_size[0], notch.raster_size[1]);
+        if self.notch.as_ref().map(|(cached, _)| *cached) != Some((tex_w, tex_h)) {
+            let image = notch_shape::rasterize(tex_w, tex_h)
+                .and_then(|rgba| RasterImage::from_rgba(tex_w, tex_h, &rgba));
+            self.notch = Some(((tex_w, tex_h), image));
+        }
+
+        let image = self.notch.as_ref()?.1.clone()?;
+        let mut composite = notch.composite;
+        composite.frame_size = [tex_w as f32, tex_h as f32];
+        composite.crop_bounds = [
+            notch.source_crop[0] * tex_w as f32,
+            notch.source_crop[1] * tex_h as f32,
+            notch.source_crop[2] * tex_w as f32,
+            notch.source_crop[3] * tex_h as f32,
+        ];
+
+        Some(RasterLayer::Card {
+            image,
+            uniforms: card_uniforms(&composite),
+        })
+    }
+
+    /// Like `DisplayLayer::prepare_with_encoder`, a missing or unconvertible
+    /// screen frame is an error; a repeated recording time reuses the last
+    /// frame.
+    fn display_card(
+        &mut self,
+        uniforms: &ProjectUniforms,
+        segment_frames: &DecodedSegmentFrames,
+        options: &RenderOptions,
+    ) -> Result<Option<RasterLayer>, RenderingError> {
+        let upload_failed = || RenderingError::DisplayFrameUploadFailed {
+            frame_number: uniforms.frame_number,
+            recording_time: segment_frames.recording_time,
+        };
+        let screen_frame = segment_frames
+            .screen_frame
+            .as_ref()
+            .ok_or_else(upload_failed)?;
+
+        let recording_time = segment_frames.recording_time;
+        let is_same_frame = self
+            .display
+            .as_ref()
+            .is_some_and(|(last, _)| (last - recording_time).abs() < 0.001);
+        if !is_same_frame {
+            let image = frame_image(screen_frame).ok_or_else(upload_failed)?;
+            self.display = Some((recording_time, image));
+        }
+
+        let display_uniforms = uniforms_for_source_frame(
+            uniforms.display,
+            options.screen_size,
+            XY::new(screen_frame.width(), screen_frame.height()),
+        );
+
+        Ok(self.display.as_ref().map(|(_, image)| RasterLayer::Card {
+            image: image.clone(),
+            uniforms: card_uniforms(&display_uniforms),
+        }))
+    }
+
+    /// Mirrors `CursorLayer::prepare`: a hidden cursor clears the sprite, a
+    /// frame without cursor data keeps the previous one.
+    fn update_cursor(
+        &mut self,
+        constants: &CpuRenderConstants,
+        uniforms: &ProjectUniforms,
+        segment_frames: &DecodedSegmentFrames,
+        cursor: &CursorEvents,
+    ) {
+        if uniforms.project.cursor.hide {
+            self.cursor = None;
+            return;
+        }
+
+        let Some(interpolated_cursor) = &uniforms.interpolated_cursor else {
+            self.cursor = None;
+            return;
+        };
+
+        let cursor_uv = &interpolated_cursor.position.coord;
+        if !cursor_uv.x.is_finite() || !cursor_uv.y.is_finite() {
+            self.cursor = None;
+            return;
+        }
+
+        let use_svg = uniforms.project.cursor.use_svg;
+        let sprite = if *uniforms.project.cursor.cursor_type() == CursorType::Circle {
+            self.circle_cursor
+                .get_or_insert_with(|| {
+                    let image = circle_cursor_image();
+                    (
+                        RasterImage::from_rgba(image.width, image.height, &image.rgba)
+                            .expect("circle cursor image is valid"),
+                        image.hotspot,
+                    )
+                })
+                .clone()
+        } else {
+            let key = (interpolated_cursor.cursor_id.clone(), use_svg);
+            let entry = self.cursors.entry(key).or_insert_with(|| {
+                load_cursor_image(
+                    &constants.recording_meta,
+                    &constants.meta,
+                    &interpolated_cursor.cursor_id,
+                    use_svg,
+                )
+                .and_then(|image| {
+                    RasterImage::from_rgba(image.width, image.height, &image.rgba)
+                        .map(|raster| (raster, image.hotspot))
+                })
+            });
+            let Some(sprite) = entry.clone() else {
+                tracing::error!("Cursor {:?} not found!", interpolated_cursor.cursor_id);
+                return;
+            };
+            sprite
+        };
+
+        let (image, hotspot) = sprite;
+        let cursor_uniforms = cursor_uniforms(
+            uniforms,
+            &constants.options,
+            cursor,
+            segment_frames.recording_time,
+            uniforms.resolution_base,
+            &uniforms.zoom,
+            CursorSprite {
+                size: XY::new(image.width(), image.height()),
+                hotspot,
+            },
+        );
+
+        self.cursor = Some((
+            image,
+            CursorParams {
+                position_size: cursor_uniforms.position_size,
+                output_size: cursor_uniforms.output_size,
+                screen_bounds: cursor_uniforms.screen_bounds,
+                motion_vector_strength: cursor_uniforms.motion_vector_strength,
+                rotation_params: cursor_uniforms.rotation_params,
+                grade: ColorGradeParams {
+                    color_adjust_a: cursor_uniforms.color_adjust_a,
+                    color_adjust_b: cursor_uniforms.color_adjust_b,
+                    grain_params: cursor_uniforms.grain_params,
+                },
+            },
+        ));
+    }
+
+    /// Rasterizes shaped text the way glyphon's atlas renderer places it:
+    /// whole-pixel glyph positions clipped to each area's bounds and the
+    /// output, colors converted from sRGB (glyphon's default color mode).
+    fn glyph_sprites(
+        &mut self,
+        buffers: &[Buffer],
+        placements: &[TextPlacement],
+        output_size: (u32, u32),
+    ) -> Vec<GlyphSprite> {
+        let mut sprites = Vec::new();
+
+        for placement in placements {
+            let bounds = placement.bounds;
+            let min_x = bounds.left.max(0);
+            let min_y = bounds.top.max(0);
+            let max_x = bounds.right.min(output_size.0 as i32);
+            let max_y = bounds.bottom.min(output_size.1 as i32);
+
+            for run in buffers[placement.buffer].layout_runs() {
+                for glyph in run.glyphs {
+                    let physical = glyph.physical((placement.left, placement.top), placement.scale);
+                    let Some(image) = self
+                        .swash_cache
+                        .get_image(&mut self.font_system, physical.cache_key)
+                        .as_ref()
+                    else {
+                        continue;
+                    };
+
+                    let (glyph_w, glyph_h) = (image.placement.width, image.placement.height);
+                    if glyph_w == 0 || glyph_h == 0 {
+                        continue;
+                    }
+                    let x = physical.x + image.placement.left;
+                    let y = (run.line_y * placement.scale).round() as i32 + physical.y
+                        - image.placement.top;
+
+                    let (x0, y0) = (x.max(min_x), y.max(min_y));
+                    let (x1, y1) = (
+                        (x + glyph_w as i32).min(max_x),
+                        (y + glyph_h as i32).min(max_y),
+                    );
+                    if x0 >= x1 || y0 >= y1 {
+                        continue;
+                    }
+
+                    let color = glyph.color_opt.unwrap_or(placement.default_color);
+                    let tint = [
+                        srgb_to_linear(color.r()),
+                        srgb_to_linear(color.g()),
+                        srgb_to_linear(color.b()),
+                        color.a() as f32 / 255.0,
+                    ];
+
+                    let (w, h) = ((x1 - x0) as u32, (y1 - y0) as u32);
+                    let mut rgba = Vec::with_capacity((w * h * 4) as usize);
+                    for row in y0..y1 {
+                        for col in x0..x1 {
+                            let index = ((row - y) as u32 * glyph_w + (col - x) as u32) as usize;
+                            let premultiplied = match image.content {
+                                SwashContent::Mask => {
+                                    let alpha = tint[3] * image.data[index] as f32 / 255.0;
+                                    [tint[0] * alpha, tint[1] * alpha, tint[2] * alpha, alpha]
+                                }
+                                SwashContent::Color | SwashContent::SubpixelMask => {
+                                    let texel = &image.data[index * 4..index * 4 + 4];
+                                    let alpha = texel[3] as f32 / 255.0;
+                                    [
+                                        srgb_to_linear(texel[0]) * alpha,
+                                        srgb_to_linear(texel[1]) * alpha,
+                                        srgb_to_linear(texel[2]) * alpha,
+                                        alpha,
+                                    ]
+                                }
+                            };
+                            rgba.extend(
+                                premultiplied.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8),
+                            );
+                        }
+                    }
+
+                    if let Some(image) = RasterImage::from_rgba(w, h, &rgba) {
+                        sprites.push(GlyphSprite {
+                            left: x0,
+                            top: y0,
+                            image,
+                        });
+                    }
+                }
+            }
+        }
+
+        sprites
+    }
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let c = value as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn frame_image(frame: &DecodedFrame) -> Option<RasterImage> {
+    let rgba = frame.to_rgba()?;
+    RasterImage::from_rgba(frame.width(), frame.height(), &rgba)
+}
+
+/// Mirrors `CameraLayer::prepare_with_encoder`: no uniforms hides the layer,
+/// and a frame without new camera data keeps showing the previous one.
+fn prepare_camera_card(
+    last: &mut Option<(f32, RasterImage)>,
+    uniforms: Option<CompositeVideoFrameUniforms>,
+    frame: Option<(&DecodedFrame, f32)>,
+) -> Option<RasterLayer> {
+    let uniforms = uniforms?;
+
+    if let Some((frame, recording_time)) = frame {
+        let is_same_frame = last
+            .as_ref()
+            .is_some_and(|(time, _)| (time - recording_time).abs() < 0.001);
+        if !is_same_frame && let Some(image) = frame_image(frame) {
+            *last = Some((recording_time, image));
+        }
+    }
+
+    let (_, image) = last.as_ref()?;
+    Some(RasterLayer::Card {
+        image: image.clone(),
+        uniforms: card_uniforms(&uniforms),
+    })
+}
+
+fn card_uniforms(uniforms: &CompositeVideoFrameUniforms) -> CardUniforms {
+    CardUniforms {
+        crop_bounds: uniforms.crop_bounds,
+        target_bounds: uniforms.target_bounds,
+        output_size: uniforms.output_size,
+        frame_size: uniforms.frame_size,
+        motion_blur_vector: uniforms.motion_blur_vector,
+        motion_blur_zoom_center: uniforms.motion_blur_zoom_center,
+        motion_blur_params: uniforms.motion_blur_params,
+        target_size: uniforms.target_size,
+        rounding_px: uniforms.rounding_px,
+        rounding_type: uniforms.rounding_type,
+        mirror_x: uniforms.mirror_x,
+        shadow: uniforms.shadow,
+        shadow_size: uniforms.shadow_size,
+        shadow_opacity: uniforms.shadow_opacity,
+        shadow_blur: uniforms.shadow_blur,
+        opacity: uniforms.opacity,
+        border_enabled: uniforms.border_enabled,
+        border_width: uniforms.border_width,
+        preserve_source_alpha: uniforms.preserve_source_alpha,
+        border_color: uniforms.border_color,
+        corner_radii: uniforms.corner_radii,
+        grade: ColorGradeParams {
+            color_adjust_a: uniforms.color_adjust_a,
+            color_adjust_b: uniforms.color_adjust_b,
+            grain_params: uniforms.grain_params,
+        },
+    }
+}
+
+fn grade_params(params: &ColorGradeUniformParams) -> ColorGradeParams {
+    ColorGradeParams {
+        color_adjust_a: params.color_adjust_a,
+        color_adjust_b: params.color_adjust_b,
+        grain_params: params.grain_params,
+    }
+}
+
+fn gradient_layer(background: Background) -> RasterLayer {
+    let uniforms = GradientOrColorUniforms::from(background);
+    RasterLayer::Gradient(GradientParams {
+        start: uniforms.start,
+        end: uniforms.end,
+        angle: uniforms.angle,
+        noise_intensity: uniforms.noise_intensity,
+        noise_scale: uniforms.noise_scale,
+    })
+}
+
+fn rounded_rect(rect: CaptionBox, clip: [u32; 4]) -> RasterLayer {
+    RasterLayer::RoundedRect {
+        rect: rect.rect,
+        color: rect.color,
+        radius: rect.radius,
+        clip,
+    }
+}
+
+/// Blends two rendered frames like transition.wgsl.
+fn blend_transition(
+    outgoing: &RenderedFrame,
+    incoming: &mut RenderedFrame,
+    kind: ClipTransitionType,
+    progress: f32,
+    opaque: bool,
+) {
+    if outgoing.width != incoming.width || outgoing.height != incoming.height {
+        return;
+    }
+
+    let progress = progress.clamp(0.0, 1.0);
+    let (outgoing_weight, incoming_weight, black_alpha) = match kind {
+        ClipTransitionType::CrossFade => (1.0 - progress, progress, 0.0),
+        ClipTransitionType::FadeThroughBlack => {
+            let outgoing_weight = (1.0 - progress * 2.0).max(0.0);
+            let incoming_weight = (progress * 2.0 - 1.0).max(0.0);
+            let black_weight = 1.0 - outgoing_weight - incoming_weight;
+            (
+                outgoing_weight,
+                incoming_weight,
+                if opaque { black_weight * 255.0 } else { 0.0 },
+            )
+        }
+    };
+
+    let blended = Arc::make_mut(&mut incoming.data);
+    for (i, (dst, src)) in blended.iter_mut().zip(outgoing.data.iter()).enumerate() {
+        let mut value = *src as f32 * outgoing_weight + *dst as f32 * incoming_weight;
+        if i % 4 == 3 {
+            value += black_alpha;
+        }
+        *dst = value.round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// CPU counterpart of [`render_video_to_channel`](crate::render_video_to_channel).
+#[allow(clippy::too_many_arguments)]
+pub async fn render_video_to_channel_cpu(
+    constants: &CpuRenderConstants,
+    project: &ProjectConfiguration,
+    sender: mpsc::Sender<(RenderedFrame, u32)>,
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    render_segments: Vec<RenderSegment>,
+    fps: u32,
+    resolution_base: XY<u32>,
+    recordings: &ProjectRecordingsMeta,
+) -> Result<(), RenderingError> {
+    render_frames(
+        constants,
+        project,
+        sender,
+        recording_meta,
+        meta,
+        render_segments,
+        fps,
+        resolution_base,
+        recordings,
+        None,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn render_frames(
+    constants: &CpuRenderConstants,
+    project: &ProjectConfiguration,
+    sender: mpsc::Sender<(RenderedFrame, u32)>,
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    render_segments: Vec<RenderSegment>,
+    fps: u32,
+    resolution_base: XY<u32>,
+    recordings: &ProjectRecordingsMeta,
+    stop_after_frames_sent: Option<u32>,
+) -> Result<(), RenderingError> {
+    ffmpeg::init().unwrap();
+
+    let start_time = Instant::now();
+
+    let duration = get_duration(recordings, recording_meta, meta, project);
+
+    let total_frames = (fps as f64 * duration).ceil() as u32;
+
+    let cursor_smoothing =
+        (!project.cursor.raw).then_some(spring_mass_damper::SpringMassDamperSimulationConfig {
+            tension: project.cursor.tension,
+            mass: project.cursor.mass,
+            friction: project.cursor.friction,
+        });
+
+    let click_spring = project.cursor.click_spring_config();
+
+    let precomputed_cursor_timelines: Vec<PrecomputedCursorTimeline> = render_segments
+        .iter()
+        .enumerate()
+        .map(|(clip, segment)| {
+            PrecomputedCursorTimeline::new(
+                &segment.cursor,
+                cursor_smoothing,
+                Some(click_spring),
+                &project.cursor.clip_edits(clip as u32),
+            )
+        })
+        .collect();
+
+    let mut zoom_timelines: Vec<ZoomTransformTimeline> = render_segments
+        .iter()
+        .enumerate()
+        .map(|(recording_clip, segment)| {
+            ZoomTransformTimeline::from_project_for_clip(
+                project,
+                &segment.cursor,
+                duration,
+                constants.options.screen_size,
+                recording_clip as u32,
+            )
+        })
+        .collect();
+    let mut outgoing_zoom_timelines: Vec<ZoomTransformTimeline> = render_segments
+        .iter()
+        .enumerate()
+        .map(|(recording_clip, segment)| {
+            ZoomTransformTimeline::from_project_for_outgoing_clip(
+                project,
+                &segment.cursor,
+                duration,
+                constants.options.screen_size,
+                recording_clip as u32,
+            )
+        })
+        .collect();
+
+    let uniforms_for = |frame_number: u32,
+                        clip: usize,
+                        segment_frames: &DecodedSegmentFrames,
+                        zoom_timeline: &ZoomTransformTimeline| {
+        let precomputed_cursor = &precomputed_cursor_timelines[clip];
+        ProjectUniforms::new_inner(
+            &constants.options,
+            &constants.meta,
+            project,
+            frame_number,
+            fps,
+            resolution_base,
+            &render_segments[clip].cursor,
+            segment_frames,
+            duration,
+            zoom_timeline,
+            &|time: f32| precomputed_cursor.interpolate(time),
+        )
+    };
+
+    let mut renderer = CpuFrameRenderer::new()?;
+    let needs_camera = !project.camera.hide;
+    let mut last_successful_frame: Option<RenderedFrame> = None;
+    let mut consecutive_failures = 0u32;
+    let mut frames_sent = 0u32;
+    const MAX_CONSECUTIVE_FAILURES: u32 = 200;
+
+    for current_frame_number in 0..total_frames {
+        let frame_time = current_frame_number as f64 / fps as f64;
+        let transition_mapping = project.timeline.as_ref().and_then(|timeline| {
+            if timeline.transitions.is_empty() {
+                return None;
+            }
+            match timeline.get_frame_mapping(frame_time) {
+                Some(TimelineFrameMapping::Transition {
+                    outgoing,
+                    kind,
+                    progress,
+                    ..
+                }) => Some((outgoing, kind, progress)),
+                _ => None,
+            }
+        });
+
+        let Some((segment_time, segment)) = project.get_segment_time(frame_time) else {
+            break;
+        };
+
+        let clip_config = project
+            .clips
+            .iter()
+            .find(|v| v.index == segment.recording_clip);
+
+        let render_segment = &render_segments[segment.recording_clip as usize];
+        let is_initial_frame = current_frame_number == 0 || last_successful_frame.is_none();
+        let segment_clip_index = segment.recording_clip as usize;
+
+        let zoom_until = (current_frame_number as f32 + 1.0) / fps as f32;
+        zoom_timelines[segment_clip_index].ensure_precomputed_until(zoom_until);
+        outgoing_zoom_timelines[segment_clip_index].ensure_precomputed_until(zoom_until);
+
+        let segment_frames = decode_segment_frames_with_retry(
+            &render_segment.decoders,
+            segment_time,
+            needs_camera,
+            render_segment.render_display,
+            clip_config.map(|v| v.offsets).unwrap_or_default(),
+            current_frame_number,
+            is_initial_frame,
+            fps,
+        )
+        .await;
+
+        if let Some(segment_frames) = segment_frames {
+            consecutive_failures = 0;
+
+            let uniforms = uniforms_for(
+                current_frame_number,
+                segment_clip_index,
+                &segment_frames,
+                &zoom_timelines[segment_clip_index],
+            );
+
+            let outgoing = match transition_mapping {
+                Some((outgoing, kind, progress)) => decode_timeline_source_frames(
+                    project,
+                    &render_segments,
+                    outgoing,
+                    needs_camera,
+                    current_frame_number,
+                    is_initial_frame,
+                    fps,
+                )
+                .await
+                .map(|frames| {
+                    (
+                        outgoing.segment.recording_clip as usize,
+                        frames,
+                        kind,
+                        progress,
+                    )
+                }),
+                None => None,
+            };
+            if transition_mapping.is_some() && outgoing.is_none() {
+                tracing::warn!(
+                    frame_number = current_frame_number,
+                    "Outgoing transition frame decode failed; rendering incoming frame"
+                );
+            }
+
+            let render_result = match outgoing {
+                Some((outgoing_clip, outgoing_frames, kind, progress)) => {
+                    let outgoing_render_segment = &render_segments[outgoing_clip];
+                    outgoing_zoom_timelines[outgoing_clip].ensure_precomputed_until(zoom_until);
+                    let outgoing_uniforms = uniforms_for(
+                        current_frame_number,
+                        outgoing_clip,
+                        &outgoing_frames,
+                        &outgoing_zoom_timelines[outgoing_clip],
+                    );
+
+                    renderer
+                        .render(
+                            constants,
+                            &outgoing_uniforms,
+                            &outgoing_frames,
+                            &outgoing_render_segment.cursor,
+                            outgoing_render_segment.render_display,
+                        )
+                        .and_then(|outgoing_frame| {
+                            let mut frame = renderer.render(
+                                constants,
+                                &uniforms,
+                                &segment_frames,
+                                &render_segment.cursor,
+                                render_segment.render_display,
+                            )?;
+                            blend_transition(
+                                &outgoing_frame,
+                                &mut frame,
+                                kind,
+                                progress as f32,
+                                outgoing_render_segment.render_display
+                                    || render_segment.render_display,
+                            );
+                            Ok(frame)
+                        })
+                }
+                None => renderer.render(
+                    constants,
+                    &uniforms,
+                    &segment_frames,
+                    &render_segment.cursor,
+                    render_segment.render_display,
+                ),
+            };
+
+            match render_result {
+                Ok(frame) if frame.width > 0 && frame.height > 0 => {
+                    last_successful_frame = Some(frame.clone());
+                    sender.send((frame, current_frame_number)).await?;
+                    frames_sent += 1;
+                    if stop_after_frames_sent.is_some_and(|limit| frames_sent >= limit) {
+                        break;
+                    }
+                }
+                Ok(_) => {
+                    tracing::warn!(
+                        frame_number = current_frame_number,
+                        "Rendered frame has zero dimensions"
+                    );
+                    if let Some(ref last_frame) = last_successful_frame {
+                        let mut fallback = last_frame.clone();
+                        fallback.frame_number = current_frame_number;
+                        fallback.target_time_ns =
+                            (current_frame_number as u64 * 1_000_000_000) / fps as u64;
+                        sender.send((fallback, current_frame_number)).await?;
+                        frames_sent += 1;
+                        if stop_after_frames_sent.is_some_and(|limit| frames_sent >= limit) {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(
+                        frame_number = current_frame_number,
+                        error = %e,
+                        "Frame rendering failed"
+                    );
+                    if let Some(ref last_frame) = last_successful_frame {
+                        let mut fallback = last_frame.clone();
+                        fallback.frame_number = current_frame_number;
+                        fallback.target_time_ns =
+                            (current_frame_number as u64 * 1_000_000_000) / fps as u64;
+                        sender.send((fallback, current_frame_number)).await?;
+                        frames_sent += 1;
+                        if stop_after_frames_sent.is_some_and(|limit| frames_sent >= limit) {
+                            break;
+                        }
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        } else {
+            consecutive_failures += 1;
+
+            if last_successful_frame.is_none()
+                && consecutive_failures >= MAX_INITIAL_CONSECUTIVE_FAILURES
+            {
+                tracing::error!(
+                    frame_number = current_frame_number,
+                    consecutive_failures = consecutive_failures,
+                    max_retries = DECODE_MAX_RETRIES_INITIAL,
+                    "No initial frame could be decoded - aborting export"
+                );
+                return Err(RenderingError::FrameDecodeFailed {
+                    frame_number: current_frame_number,
+                    consecutive_failures,
+                });
+            }
+
+            if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                tracing::error!(
+                    frame_number = current_frame_number,
+                    consecutive_failures = consecutive_failures,
+                    "Too many consecutive frame failures - aborting export"
+                );
+                return Err(RenderingError::FrameDecodeFailed {
+                    frame_number: current_frame_number,
+                    consecutive_failures,
+                });
+            }
+
+            if let Some(ref last_frame) = last_successful_frame {
+                tracing::warn!(
+                    frame_number = current_frame_number,
+                    segment_time = segment_time,
+                    consecutive_failures = consecutive_failures,
+                    max_retries = if is_initial_frame {
+                        DECODE_MAX_RETRIES_INITIAL
+                    } else {
+                        DECODE_MAX_RETRIES_STEADY
+                    },
+                    "Frame decode failed after retries - using previous frame"
+                );
+                let mut fallback = last_frame.clone();
+                fallback.frame_number = current_frame_number;
+                fallback.target_time_ns =
+                    (current_frame_number as u64 * 1_000_000_000) / fps as u64;
+                sender.send((fallback, current_frame_number)).await?;
+                frames_sent += 1;
+                if stop_after_frames_sent.is_some_and(|limit| frames_sent >= limit) {
+                    break;
+                }
+            } else {
+                tracing::error!(
+                    frame_number = current_frame_number,
+                    segment_time = segment_time,
+                    "First frame decode failed after retries - cannot continue"
+                );
+            }
+        }
+    }
+
+    tracing::info!(
+        frames = total_frames,
+        elapsed_secs = format!("{:.2}", start_time.elapsed().as_secs_f32()),
+        "CPU render complete"
+    );
+
+    Ok(())
+}
+
+/// [`render_video_to_channel_cpu`] with frames converted to NV12 for the
+/// encoders that take the GPU renderer's NV12 output.
+#[allow(clippy::too_many_arguments)]
+pub async fn render_video_to_channel_cpu_nv12(
+    constants: &CpuRenderConstants,
+    project: &ProjectConfiguration,
+    sender: mpsc::Sender<(Nv12RenderedFrame, u32)>,
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    render_segments: Vec<RenderSegment>,
+    fps: u32,
+    resolution_base: XY<u32>,
+    recordings: &ProjectRecordingsMeta,
+    stop_after_frames_sent: Option<u32>,
+) -> Result<(), RenderingError> {
+    let (rgba_tx, mut rgba_rx) = mpsc::channel::<(RenderedFrame, u32)>(4);
+
+    let render = render_frames(
+        constants,
+        project,
+        rgba_tx,
+        recording_meta,
+        meta,
+        render_segments,
+        fps,
+        resolution_base,
+        recordings,
+        stop_after_frames_sent,
+    );
+    let convert = async {
+        let pool = NV12BufferPool::new(6);
+        while let Some((frame, frame_number)) = rgba_rx.recv().await {
+            sender
+                .send((rgba_frame_to_nv12(&frame, &pool), frame_number))
+                .await?;
+        }
+        Ok::<_, RenderingError>(())
+    };
+
+    let (render_result, convert_result) = tokio::join!(render, convert);
+    render_result?;
+    convert_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_frame(width: u32, height: u32, rgba: [u8; 4]) -> RenderedFrame {
+        RenderedFrame {
+            data: Arc::new(rgba.repeat((width * height) as usize)),
+            width,
+            height,
+            padded_bytes_per_row: width * 4,
+            frame_number: 0,
+            target_time_ns: 0,
+        }
+    }
+
+    #[test]
+    fn camera_background_blur_is_rejected_instead_of_dropped() {
+        let options = RenderOptions {
+            screen_size: XY::new(1920, 1080),
+            camera_size: Some(XY::new(1280, 720)),
+            preserve_screen_alpha: false,
+        };
+        let mut project = ProjectConfiguration::default();
+        assert_eq!(unsupported_effect(&project, &options), None);
+
+        project.camera.background_blur.mode = cap_project::BackgroundBlurMode::Heavy;
+        assert_eq!(
+            unsupported_effect(&project, &options),
+            Some("camera background blur")
+        );
+
+        project.camera.hide = true;
+        assert_eq!(unsupported_effect(&project, &options), None);
+
+        project.camera.hide = false;
+        let screen_only = RenderOptions {
+            camera_size: None,
+            ..options
+        };
+        assert_eq!(unsupported_effect(&project, &screen_only), None);
+    }
+
+    #[test]
+    fn cross_fade_mixes_frames() {
+        let outgoing = solid_frame(2, 2, [200, 0, 0, 255]);
+        let mut incoming = solid_frame(2, 2, [0, 100, 0, 255]);
+        blend_transition(
+            &outgoing,
+            &mut incoming,
+            ClipTransitionType::CrossFade,
+            0.25,
+            true,
+        );
+        assert_eq!(&incoming.data[..4], &[150, 25, 0, 255]);
+    }
+
+    #[test]
+    fn fade_through_black_is_opaque_black_at_midpoint() {
+        let outgoing = solid_frame(2, 2, [200, 50, 0, 255]);
+        let mut incoming = solid_frame(2, 2, [0, 100, 0, 255]);
+        blend_transition(
+            &outgoing,
+            &mut incoming,
+            ClipTransitionType::FadeThroughBlack,
+            0.5,
+            true,
+        );
+        assert_eq!(&incoming.data[..4], &[0, 0, 0, 255]);
+
+        let mut transparent = solid_frame(2, 2, [0, 100, 0, 255]);
+        blend_transition(
+            &outgoing,
+            &mut transparent,
+            ClipTransitionType::FadeThroughBlack,
+            0.5,
+            false,
+        );
+        assert_eq!(&transparent.data[..4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn srgb_conversion_matches_endpoints() {
+        assert_eq!(srgb_to_linear(0), 0.0);
+        assert_eq!(srgb_to_linear(255), 1.0);
+        assert!((srgb_to_linear(128) - 0.2158).abs() < 0.001);
+    }
+}
@@ -1,6 +1,9 @@
 use std::borrow::Cow;
 
-use cap_project::{ClickSpringConfig, CursorClickEvent, CursorEvents, CursorMoveEvent, XY};
+use cap_project::{
+    ClickSpringConfig, CursorClickEvent, CursorEdit, CursorEditKind, CursorEvents, CursorMoveEvent,
+    XY,
+};
 
 use crate::{
     Coord, RawDisplayUVSpace,
@@ -317,19 +320,37 @@ pub struct PrecomputedCursorTimeline {
     timeline: Vec<SmoothedCursorEvent>,
     raw_cursor: CursorEvents,
     has_smoothing: bool,
+    /// Hide and image edits, which apply on top of the interpolated position.
+    overlay_edits: Vec<CursorEdit>,
 }
 
 impl PrecomputedCursorTimeline {
+    /// `edits` are the cursor edits for this segment's clip. Path edits are
+    /// baked into the cursor moves before shake filtering and smoothing.
     pub fn new(
         cursor: &CursorEvents,
         smoothing: Option<SpringMassDamperSimulationConfig>,
         click_spring: Option<ClickSpringConfig>,
+        edits: &[CursorEdit],
     ) -> Self {
+        let cursor = apply_path_edits(cursor, edits);
+        let overlay_edits = edits
+            .iter()
+            .filter(|edit| {
+                matches!(
+                    edit.kind,
+                    CursorEditKind::Hide | CursorEditKind::Image { .. }
+                )
+            })
+            .cloned()
+            .collect();
+
         if cursor.moves.is_empty() || smoothing.is_none() {
             return Self {
                 timeline: vec![],
-                raw_cursor: cursor.clone(),
+                raw_cursor: cursor,
                 has_smoothing: false,
+                overlay_edits,
             };
         }
 
@@ -337,7 +358,7 @@ impl PrecomputedCursorTimeline {
         let filtered_moves = filter_cursor_shake(&cursor.moves);
         let prepared_moves = decimate_cursor_moves(filtered_moves.as_ref());
         let timeline = build_smoothed_timeline(
-            cursor,
+            &cursor,
             prepared_moves.as_ref(),
             smoothing_config,
             click_spring,
@@ -345,19 +366,142 @@ impl PrecomputedCursorTimeline {
 
         Self {
             timeline,
-            raw_cursor: cursor.clone(),
+            raw_cursor: cursor,
             has_smoothing: true,
+            overlay_edits,
         }
     }
 
+    /// Returns `None` when there is no cursor data or a hide edit covers `time_secs`.
     pub fn interpolate(&self, time_secs: f32) -> Option<InterpolatedCursorPosition> {
         let time_ms = (time_secs * 1000.0) as f64;
-        if self.has_smoothing {
+        let mut interpolated = if self.has_smoothing {
             interpolate_timeline(&self.timeline, time_ms)
         } else {
             interpolate_raw_cursor(&self.raw_cursor, time_ms)
+        }?;
+
+        let overlay = self
+            .overlay_edits
+            .iter()
+            .rev()
+            .find(|edit| edit.contains(time_secs as f64));
+        match overlay.map(|edit| &edit.kind) {
+            Some(CursorEditKind::Hide) => return None,
+            Some(CursorEditKind::Image { image }) => interpolated.cursor_id = image.cursor_id(),
+            _ => {}
+        }
+
+        Some(interpolated)
+    }
+}
+
+/// Rewrites the recorded moves for position and straighten edits, in order,
+/// so later edits apply on top of earlier ones.
+fn apply_path_edits(cursor: &CursorEvents, edits: &[CursorEdit]) -> CursorEvents {
+    let mut cursor = cursor.clone();
+    for edit in edits {
+        apply_path_edit(&mut cursor, edit);
+    }
+    cursor
+}
+
+fn apply_path_edit(cursor: &mut CursorEvents, edit: &CursorEdit) {
+    let start_ms = edit.start * 1000.0;
+    let end_ms = edit.end * 1000.0;
+    if cursor.moves.is_empty() || !start_ms.is_finite() || !end_ms.is_finite() || end_ms <= start_ms
+    {
+        return;
+    }
+
+    let moves = &cursor.moves;
+    let at = |time_ms: f64| {
+        let (x, y) = position_at_time(moves, time_ms);
+        (time_ms, XY::new(x, y))
+    };
+
+    let samples = match &edit.kind {
+        CursorEditKind::Position { position } => {
+            // Pin the recorded position just after the range so the cursor
+            // returns to its path instead of holding the override until the
+            // next recorded move.
+            let (_, resume) = at(end_ms);
+            vec![
+                (start_ms, *position),
+                (end_ms, *position),
+                (end_ms + 1.0, resume),
+            ]
+        }
+        CursorEditKind::Straighten { curvature } => {
+            let mut anchors = vec![at(start_ms)];
+            anchors.extend(
+                cursor
+                    .clicks
+                    .iter()
+                    .filter(|click| {
+                        click.down && click.time_ms > start_ms && click.time_ms < end_ms
+                    })
+                    .map(|click| at(click.time_ms)),
+            );
+            anchors.push(at(end_ms));
+            bezier_path(&anchors, *curvature)
+        }
+        CursorEditKind::Hide | CursorEditKind::Image { .. } => return,
+    };
+
+    let Some(&(last_time, _)) = samples.last() else {
+        return;
+    };
+
+    let first = moves.partition_point(|m| m.time_ms < start_ms);
+    let after = moves.partition_point(|m| m.time_ms <= last_time);
+    let template = &moves[first.saturating_sub(1).min(moves.len() - 1)];
+    let replacement: Vec<_> = samples
+        .into_iter()
+        .map(|(time_ms, position)| CursorMoveEvent {
+            active_modifiers: template.active_modifiers.clone(),
+            cursor_id: template.cursor_id.clone(),
+            time_ms,
+            x: position.x,
+            y: position.y,
+        })
+        .collect();
+
+    cursor.moves.splice(first..after, replacement);
+}
+
+/// Samples cubic bezier curves between consecutive `(time_ms, position)`
+/// anchors at the simulation rate, easing in and out of each anchor.
+fn bezier_path(anchors: &[(f64, XY<f64>)], curvature: f64) -> Vec<(f64, XY<f64>)> {
+    let mut samples = vec![];
+
+    for pair in anchors.windows(2) {
+        let ((t0, p0), (t1, p1)) = (pair[0], pair[1]);
+        let delta = p1 - p0;
+        // Perpendicular to the chord with the chord's length, so the bow
+        // scales with the distance travelled.
+        let normal = XY::new(-delta.y, delta.x) * curvature;
+        let c0 = p0 + delta * (1.0 / 3.0) + normal;
+        let c1 = p0 + delta * (2.0 / 3.0) + normal;
+
+        let steps = ((t1 - t0) / SIMULATION_STEP_MS).ceil().max(1.0) as usize;
+        for step in 0..steps {
+            let u = step as f64 / steps as f64;
+            let s = u * u * (3.0 - 2.0 * u);
+            let inv = 1.0 - s;
+            let point = p0 * (inv * inv * inv)
+                + c0 * (3.0 * inv * inv * s)
+                + c1 * (3.0 * inv * s * s)
+                + p1 * (s * s * s);
+            samples.push((t0 + (t1 - t0) * u, point));
         }
     }
+
+    if let Some(&last) = anchors.last() {
+        samples.push(last);
+    }
+
+    samples
 }
 
 fn build_smoothed_timeline(
@@ -830,4 +974,112 @@ mod tests {
             prev = pos;
         }
     }
+
+    fn cursor_edit(start: f64, end: f64, kind: CursorEditKind) -> CursorEdit {
+        CursorEdit {
+            clip: 0,
+            start,
+            end,
+            kind,
+        }
+    }
+
+    fn jittery_cursor() -> CursorEvents {
+        let moves = (0..=100)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 0.02 } else { -0.02 };
+                cursor_move(f64::from(i) * 10.0, f64::from(i) / 100.0, 0.5 + jitter)
+            })
+            .collect();
+        CursorEvents {
+            moves,
+            clicks: vec![],
+        }
+    }
+
+    #[test]
+    fn position_edit_holds_cursor_then_resumes_path() {
+        let edits = [cursor_edit(
+            0.2,
+            0.4,
+            CursorEditKind::Position {
+                position: XY::new(0.9, 0.1),
+            },
+        )];
+        let timeline = PrecomputedCursorTimeline::new(&jittery_cursor(), None, None, &edits);
+
+        let held = timeline.interpolate(0.3).unwrap();
+        assert_eq!((held.position.coord.x, held.position.coord.y), (0.9, 0.1));
+
+        let after = timeline.interpolate(0.6).unwrap();
+        assert!((after.position.coord.x - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn straighten_edit_removes_jitter_and_passes_through_clicks() {
+        let mut cursor = jittery_cursor();
+        cursor.clicks = vec![click_event(500.0, true), click_event(550.0, false)];
+        let edits = [cursor_edit(
+            0.1,
+            0.9,
+            CursorEditKind::Straighten { curvature: 0.0 },
+        )];
+        let timeline = PrecomputedCursorTimeline::new(&cursor, None, None, &edits);
+
+        let anchor_y = 0.52;
+        for t_ms in (120..880).step_by(10) {
+            let position = timeline.interpolate(t_ms as f32 / 1000.0).unwrap().position;
+            assert!(
+                (position.coord.y - anchor_y).abs() < 0.001,
+                "jitter left at t={t_ms}ms: y={}",
+                position.coord.y
+            );
+        }
+
+        let at_click = timeline.interpolate(0.5).unwrap().position;
+        assert!((at_click.coord.x - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn curvature_bows_straightened_path() {
+        let cursor = CursorEvents {
+            moves: vec![cursor_move(0.0, 0.1, 0.5), cursor_move(1000.0, 0.9, 0.5)],
+            clicks: vec![],
+        };
+        let edits = [cursor_edit(
+            0.0,
+            1.0,
+            CursorEditKind::Straighten { curvature: 0.25 },
+        )];
+        let timeline = PrecomputedCursorTimeline::new(&cursor, None, None, &edits);
+
+        let midpoint = timeline.interpolate(0.5).unwrap().position;
+        assert!(
+            midpoint.coord.y > 0.6,
+            "expected a bow, got {}",
+            midpoint.coord.y
+        );
+    }
+
+    #[test]
+    fn hide_and_image_edits_apply_to_their_range() {
+        let edits = [
+            cursor_edit(0.2, 0.4, CursorEditKind::Hide),
+            cursor_edit(
+                0.6,
+                0.8,
+                CursorEditKind::Image {
+                    image: cap_project::CursorEditImage::Recorded {
+                        cursor_id: "text".into(),
+                    },
+                },
+            ),
+        ];
+        let timeline = PrecomputedCursorTimeline::new(&jittery_cursor(), None, None, &edits);
+
+        assert!(timeline.interpolate(0.3).is_none());
+        assert_eq!(timeline.interpolate(0.5).unwrap().cursor_id, "primary");
+        assert_eq!(timeline.interpolate(0.7).unwrap().cursor_id, "text");
+        assert_eq!(timeline.interpolate(0.9).unwrap().cursor_id, "primary");
+    }
 }
@@ -16,6 +16,9 @@ mod frame_converter;
 mod media_foundation;
 #[cfg(target_os = "macos")]
 pub mod multi_position;
+mod still;
+
+pub use still::is_still_image;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DecoderType {
@@ -25,6 +28,7 @@ pub enum DecoderType {
     MediaFoundation,
     FFmpegHardware,
     FFmpegSoftware,
+    Still,
 }
 
 impl DecoderType {
@@ -36,6 +40,7 @@ impl DecoderType {
             DecoderType::MediaFoundation => true,
             DecoderType::FFmpegHardware => true,
             DecoderType::FFmpegSoftware => false,
+            DecoderType::Still => false,
         }
     }
 }
@@ -49,6 +54,7 @@ impl fmt::Display for DecoderType {
             DecoderType::MediaFoundation => write!(f, "MediaFoundation (hardware)"),
             DecoderType::FFmpegHardware => write!(f, "FFmpeg (hardware)"),
             DecoderType::FFmpegSoftware => write!(f, "FFmpeg (software)"),
+            DecoderType::Still => write!(f, "Still image"),
         }
     }
 }
@@ -435,6 +441,55 @@ impl DecodedFrame {
     pub fn uv_stride(&self) -> u32 {
         self.uv_stride
     }
+
+    /// Converts the frame to tightly packed RGBA on the CPU.
+    /// Returns `None` when the frame's planes are missing or truncated.
+    pub fn to_rgba(&self) -> Option<Vec<u8>> {
+        let row_bytes = (self.width as usize).checked_mul(4)?;
+        let len = row_bytes.checked_mul(self.height as usize)?;
+
+        match self.format {
+            PixelFormat::Rgba => {
+                let stride = self.y_stride as usize;
+                if stride == row_bytes {
+                    return self.data.get(..len).map(<[u8]>::to_vec);
+                }
+                let mut rgba = Vec::with_capacity(len);
+                for row in 0..self.height as usize {
+                    let start = row * stride;
+                    rgba.extend_from_slice(self.data.get(start..start + row_bytes)?);
+                }
+                Some(rgba)
+            }
+            PixelFormat::Nv12 => {
+                let mut rgba = vec![0u8; len];
+                crate::cpu_yuv::nv12_to_rgba(
+                    self.y_plane()?,
+                    self.uv_plane()?,
+                    self.width,
+                    self.height,
+                    self.y_stride,
+                    self.uv_stride,
+                    &mut rgba,
+                );
+                Some(rgba)
+            }
+            PixelFormat::Yuv420p => {
+                let mut rgba = vec![0u8; len];
+                crate::cpu_yuv::yuv420p_to_rgba(
+                    self.y_plane()?,
+                    self.u_plane()?,
+                    self.v_plane()?,
+                    self.width,
+                    self.height,
+                    self.y_stride,
+                    self.uv_stride,
+                    &mut rgba,
+                );
+                Some(rgba)
+            }
+        }
+    }
 }
 
 pub enum VideoDecoderMessage {
@@ -630,6 +685,32 @@ pub async fn spawn_decoder(
     let path_display = path.display().to_string();
     let timeout_duration = Duration::from_secs(30);
 
+    if is_still_image(&path) {
+        let (ready_tx, ready_rx) = oneshot::channel::<Result<DecoderInitResult, String>>();
+        let (tx, rx) = mpsc::channel();
+
+        still::StillImageDecoder::spawn(name, path, rx, ready_tx);
+
+        return match tokio::time::timeout(timeout_duration, ready_rx).await {
+            Ok(Ok(Ok(init_result))) => Ok(AsyncVideoDecoderHandle {
+                sender: tx,
+                offset,
+                status: DecoderStatus {
+                    decoder_type: init_result.decoder_type,
+                    video_width: init_result.width,
+                    video_height: init_result.height,
+                    fallback_reason: None,
+                },
+                max_fallback_distance: DEFAULT_MAX_FALLBACK_DISTANCE,
+            }),
+            Ok(Ok(Err(e))) => Err(format!("'{name}' still image decoder / {e}")),
+            Ok(Err(e)) => Err(format!("'{name}' still image decoder channel closed: {e}")),
+            Err(_) => Err(format!(
+                "'{name}' still image decoder timed out after 30s initializing: {path_display}"
+            )),
+        };
+    }
+
     #[cfg(target_os = "macos")]
     {
         if force_ffmpeg {
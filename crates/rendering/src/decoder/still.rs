@@ -0,0 +1,71 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc},
+};
+
+use tokio::sync::oneshot;
+use tracing::info;
+
+use super::{DecodedFrame, DecoderInitResult, DecoderType, VideoDecoderMessage};
+
+const STILL_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+pub fn is_still_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            STILL_IMAGE_EXTENSIONS
+                .iter()
+                .any(|still| ext.eq_ignore_ascii_case(still))
+        })
+}
+
+/// Serves an imported image (a title card, a slide) as a video that shows
+/// the same frame at every timestamp. The image is decoded once up front.
+pub struct StillImageDecoder;
+
+impl StillImageDecoder {
+    pub fn spawn(
+        name: &'static str,
+        path: PathBuf,
+        rx: mpsc::Receiver<VideoDecoderMessage>,
+        ready_tx: oneshot::Sender<Result<DecoderInitResult, String>>,
+    ) {
+        std::thread::spawn(move || {
+            let image = match image::open(&path) {
+                Ok(image) => image.to_rgba8(),
+                Err(e) => {
+                    let _ = ready_tx.send(Err(format!("Failed to open image: {e}")));
+                    return;
+                }
+            };
+
+            let (width, height) = image.dimensions();
+            let frame = DecodedFrame::new_with_arc(Arc::new(image.into_raw()), width, height);
+
+            info!(name, width, height, "Still image decoder ready");
+            let _ = ready_tx.send(Ok(DecoderInitResult {
+                width,
+                height,
+                decoder_type: DecoderType::Still,
+            }));
+
+            while let Ok(VideoDecoderMessage::GetFrame(_, _, sender)) = rx.recv() {
+                let _ = sender.send(frame.clone());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_image_extensions() {
+        assert!(is_still_image(Path::new("title.PNG")));
+        assert!(is_still_image(Path::new("card.jpeg")));
+        assert!(!is_still_image(Path::new("display.mp4")));
+        assert!(!is_still_image(Path::new("display")));
+    }
+}
@@ -0,0 +1,111 @@
+//! Single-line text rasterized on the CPU, for burning labels such as
+//! watermarks and timestamps into frames outside the render pipeline.
+
+use glyphon::cosmic_text::SwashContent;
+use glyphon::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping, SwashCache};
+
+/// Coverage of a rasterized line of text, one byte per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelMask {
+    pub width: u32,
+    pub height: u32,
+    pub coverage: Vec<u8>,
+}
+
+impl LabelMask {
+    pub fn coverage_at(&self, x: u32, y: u32) -> u8 {
+        self.coverage[(y * self.width + x) as usize]
+    }
+}
+
+pub struct LabelRasterizer {
+    font_system: FontSystem,
+    swash_cache: SwashCache,
+}
+
+impl Default for LabelRasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LabelRasterizer {
+    pub fn new() -> Self {
+        Self {
+            font_system: crate::layers::new_font_system(),
+            swash_cache: SwashCache::new(),
+        }
+    }
+
+    /// Shapes `text` in the system sans-serif font at `font_size` pixels and
+    /// returns its coverage mask, sized to the laid-out line.
+    pub fn rasterize(&mut self, text: &str, font_size: f32) -> LabelMask {
+        let metrics = Metrics::new(font_size, font_size * 1.2);
+        let mut buffer = Buffer::new(&mut self.font_system, metrics);
+        buffer.set_size(&mut self.font_system, None, None);
+        buffer.set_text(
+            &mut self.font_system,
+            text,
+            &Attrs::new().family(Family::SansSerif),
+            Shaping::Advanced,
+        );
+        buffer.shape_until_scroll(&mut self.font_system, false);
+
+        let (mut width, mut height) = (0.0f32, 0.0f32);
+        for run in buffer.layout_runs() {
+            width = width.max(run.line_w);
+            height = height.max(run.line_top + run.line_height);
+        }
+
+        let mut mask = LabelMask {
+            width: width.ceil() as u32,
+            height: height.ceil() as u32,
+            coverage: Vec::new(),
+        };
+        mask.coverage = vec![0; (mask.width * mask.height) as usize];
+
+        for run in buffer.layout_runs() {
+            for glyph in run.glyphs {
+                let physical = glyph.physical((0.0, 0.0), 1.0);
+                let Some(image) = self
+                    .swash_cache
+                    .get_image(&mut self.font_system, physical.cache_key)
+                    .as_ref()
+                else {
+                    continue;
+                };
+
+                let (glyph_w, glyph_h) = (image.placement.width, image.placement.height);
+                let x = physical.x + image.placement.left;
+                let y = run.line_y.round() as i32 + physical.y - image.placement.top;
+
+                for row in 0..glyph_h as i32 {
+                    for col in 0..glyph_w as i32 {
+                        let (mask_x, mask_y) = (x + col, y + row);
+                        if mask_x < 0
+                            || mask_y < 0
+                            || mask_x >= mask.width as i32
+                            || mask_y >= mask.height as i32
+                        {
+                            continue;
+                        }
+
+                        let index = (row as u32 * glyph_w + col as u32) as usize;
+                        let coverage = match image.content {
+                            SwashContent::Mask => image.data[index],
+                            SwashContent::Color | SwashContent::SubpixelMask => {
+                                image.data[index * 4 + 3]
+                            }
+                        };
+
+                        let target = &mut mask.coverage
+                            [(mask_y as u32 * mask.width + mask_x as u32) as usize];
+                        *target = (*target).max(coverage);
+                    }
+                }
+            }
+        }
+
+        mask
+    }
+}
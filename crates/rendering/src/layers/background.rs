@@ -10,7 +10,7 @@ use wgpu::{include_wgsl, util::DeviceExt};
 
 use crate::{ProjectUniforms, RenderVideoConstants, RenderingError, create_shader_render_pipeline};
 
-const MAX_BACKGROUND_DIMENSION: u32 = 2560;
+pub(crate) const MAX_BACKGROUND_DIMENSION: u32 = 2560;
 
 const DEFAULT_BACKGROUND_CACHE_CAPACITY: usize = 8;
 
@@ -29,7 +29,7 @@ pub fn clean_background_path(path: &str) -> Option<String> {
         .then_some(clean_path)
 }
 
-fn decode_background_rgba(
+pub(crate) fn decode_background_rgba(
     path: &str,
     max_dimension: u32,
 ) -> Result<(Vec<u8>, u32, u32), image::ImageError> {
@@ -57,6 +57,30 @@ fn decode_background_rgba(
     Ok((img.to_rgba8().into_raw(), width, height))
 }
 
+/// The UV inset (`x_width`, `y_height`) that crops an image of
+/// `width`×`height` so it covers `output_size` without stretching.
+pub(crate) fn image_cover_inset(output_size: (u32, u32), width: u32, height: u32) -> (f32, f32) {
+    let output_ar = output_size.1 as f32 / output_size.0 as f32;
+    let image_ar = height as f32 / width as f32;
+
+    let y_height = if output_ar < image_ar {
+        ((image_ar - output_ar) / 2.0) / image_ar
+    } else {
+        0.0
+    };
+
+    let x_width = if output_ar > image_ar {
+        let output_ar = 1.0 / output_ar;
+        let image_ar = 1.0 / image_ar;
+
+        ((image_ar - output_ar) / 2.0) / image_ar
+    } else {
+        0.0
+    };
+
+    (x_width, y_height)
+}
+
 struct CachedBackgroundTexture {
     texture: wgpu::Texture,
     last_used: u64,
@@ -392,24 +416,11 @@ impl BackgroundLayer {
                             }
                         };
 
-                        let output_ar =
-                            uniforms.output_size.1 as f32 / uniforms.output_size.0 as f32;
-                        let image_ar = texture.height() as f32 / texture.width() as f32;
-
-                        let y_height = if output_ar < image_ar {
-                            ((image_ar - output_ar) / 2.0) / image_ar
-                        } else {
-                            0.0
-                        };
-
-                        let x_width = if output_ar > image_ar {
-                            let output_ar = 1.0 / output_ar;
-                            let image_ar = 1.0 / image_ar;
-
-                            ((image_ar - output_ar) / 2.0) / image_ar
-                        } else {
-                            0.0
-                        };
+                        let (x_width, y_height) = image_cover_inset(
+                            uniforms.output_size,
+                            texture.width(),
+                            texture.height(),
+                        );
 
                         let image_uniforms = ImageBackgroundUniforms {
                             output_size: [
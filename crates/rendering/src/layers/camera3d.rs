@@ -77,79 +77,29 @@ impl Camera3DLayer {
     }
 
     pub fn prepare(&mut self, queue: &wgpu::Queue, uniforms: &ProjectUniforms) {
-        self.warp_active = false;
-        self.blur_kind = None;
+        let passes = camera3d_passes(uniforms);
 
-        let Some(frame) = uniforms.camera3d else {
-            return;
-        };
-        let (out_w, out_h) = (uniforms.output_size.0.max(1), uniforms.output_size.1.max(1));
-        let aspect = out_w as f64 / out_h as f64;
-
-        if let Some(pose) = frame.pose
-            && let Some(homography) =
-                camera3d_inverse_homography(&pose, aspect, uniforms.camera3d_zoom.as_ref())
+        self.warp_active = passes.warp.is_some();
+        if let Some(warp_uniforms) = passes.warp
+            && self.cached_warp_uniforms.as_ref() != Some(&warp_uniforms)
         {
-            let rows = homography.inverse_rows;
-            let (hx, hy) = homography.half_extents;
-            let warp_uniforms = Camera3DUniforms {
-                inv_row0: [rows[0][0], rows[0][1], rows[0][2], 0.0],
-                inv_row1: [rows[1][0], rows[1][1], rows[1][2], hx],
-                inv_row2: [rows[2][0], rows[2][1], rows[2][2], hy],
-            };
-
-            if self.cached_warp_uniforms.as_ref() != Some(&warp_uniforms) {
-                queue.write_buffer(
-                    &self.warp_uniforms_buffer,
-                    0,
-                    bytemuck::cast_slice(&[warp_uniforms]),
-                );
-                self.cached_warp_uniforms = Some(warp_uniforms);
-            }
-            self.warp_active = true;
+            queue.write_buffer(
+                &self.warp_uniforms_buffer,
+                0,
+                bytemuck::cast_slice(&[warp_uniforms]),
+            );
+            self.cached_warp_uniforms = Some(warp_uniforms);
         }
 
-        if let Some(blur) = frame.blur {
-            // Strength is authored at 1080p output height; scale so preview
-            // and export produce the same look.
-            let strength_px = (blur.strength * out_h as f64 / CAMERA3D_BLUR_BASE_HEIGHT) as f32;
-            let mode = match blur.mode {
-                Camera3DBlurMode::None => return,
-                Camera3DBlurMode::Radial => 1.0,
-                Camera3DBlurMode::Directional => 2.0,
-                Camera3DBlurMode::TiltShift => 3.0,
-            };
-            let params = |pass: f32| Camera3DBlurUniforms {
-                params0: [pass, mode, strength_px, blur.falloff as f32],
-                params1: [
-                    blur.focus_x as f32,
-                    blur.focus_y as f32,
-                    blur.focus_size as f32,
-                    blur.angle as f32,
-                ],
-                params2: [blur.dir_position as f32, out_w as f32, out_h as f32, 0.0],
-            };
-            let (h, v) = if blur.bokeh {
-                // Bokeh is single-pass; only the "V" slot is used.
-                (params(2.0), params(2.0))
-            } else {
-                (params(0.0), params(1.0))
-            };
-
-            if self.cached_blur_uniforms.as_ref() != Some(&(h, v)) {
-                if !blur.bokeh {
-                    // Bokeh never reads the H buffer (lib.rs only runs the V
-                    // pass for it), so skip the redundant upload.
-                    queue.write_buffer(&self.blur_h_buffer, 0, bytemuck::cast_slice(&[h]));
-                }
-                queue.write_buffer(&self.blur_v_buffer, 0, bytemuck::cast_slice(&[v]));
-                self.cached_blur_uniforms = Some((h, v));
+        self.blur_kind = passes.blur.map(|(kind, ..)| kind);
+        if let Some((kind, h, v)) = passes.blur
+            && self.cached_blur_uniforms.as_ref() != Some(&(h, v))
+        {
+            if kind == Camera3DBlurKind::Gaussian {
+                queue.write_buffer(&self.blur_h_buffer, 0, bytemuck::cast_slice(&[h]));
             }
-            self.blur_kind = Some(if blur.bokeh {
-                Camera3DBlurKind::Bokeh
-            } else {
-                Camera3DBlurKind::Gaussian
-            });
+            queue.write_buffer(&self.blur_v_buffer, 0, bytemuck::cast_slice(&[v]));
+            self.cached_blur_uniforms = Some((h, v));
         }
     }
 
@@ -212,20 +162,82 @@ impl Camera3DLayer {
     }
 }
 
+/// The warp and focus-blur passes a frame needs, shared by [`Camera3DLayer`]
+/// and the CPU renderer.
+pub(crate) struct Camera3DPasses {
+    pub warp: Option<Camera3DUniforms>,
+    /// Blur kind with its horizontal and vertical pass uniforms (both the
+    /// single bokeh pass when the kind is `Bokeh`).
+    pub blur: Option<(Camera3DBlurKind, Camera3DBlurUniforms, Camera3DBlurUniforms)>,
+}
+
+pub(crate) fn camera3d_passes(uniforms: &ProjectUniforms) -> Camera3DPasses {
+    let mut passes = Camera3DPasses {
+        warp: None,
+        blur: None,
+    };
+
+    let Some(frame) = uniforms.camera3d else {
+        return passes;
+    };
+    let (out_w, out_h) = (uniforms.output_size.0.max(1), uniforms.output_size.1.max(1));
+    let aspect = out_w as f64 / out_h as f64;
+
+    if let Some(pose) = frame.pose
+        && let Some(homography) =
+            camera3d_inverse_homography(&pose, aspect, uniforms.camera3d_zoom.as_ref())
+    {
+        let rows = homography.inverse_rows;
+        let (hx, hy) = homography.half_extents;
+        passes.warp = Some(Camera3DUniforms {
+            inv_row0: [rows[0][0], rows[0][1], rows[0][2], 0.0],
+            inv_row1: [rows[1][0], rows[1][1], rows[1][2], hx],
+            inv_row2: [rows[2][0], rows[2][1], rows[2][2], hy],
+        });
+    }
+
+    if let Some(blur) = frame.blur {
+        let strength_px = (blur.strength * out_h as f64 / CAMERA3D_BLUR_BASE_HEIGHT) as f32;
+        let mode = match blur.mode {
+            Camera3DBlurMode::None => return passes,
+            Camera3DBlurMode::Radial => 1.0,
+            Camera3DBlurMode::Directional => 2.0,
+            Camera3DBlurMode::TiltShift => 3.0,
+        };
+        let params = |pass: f32| Camera3DBlurUniforms {
+            params0: [pass, mode, strength_px, blur.falloff as f32],
+            params1: [
+                blur.focus_x as f32,
+                blur.focus_y as f32,
+                blur.focus_size as f32,
+                blur.angle as f32,
+            ],
+            params2: [blur.dir_position as f32, out_w as f32, out_h as f32, 0.0],
+        };
+        passes.blur = Some(if blur.bokeh {
+            (Camera3DBlurKind::Bokeh, params(2.0), params(2.0))
+        } else {
+            (Camera3DBlurKind::Gaussian, params(0.0), params(1.0))
+        });
+    }
+
+    passes
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable, Default, PartialEq)]
-struct Camera3DUniforms {
-    inv_row0: [f32; 4],
-    inv_row1: [f32; 4],
-    inv_row2: [f32; 4],
+pub(crate) struct Camera3DUniforms {
+    pub inv_row0: [f32; 4],
+    pub inv_row1: [f32; 4],
+    pub inv_row2: [f32; 4],
 }
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable, Default, PartialEq)]
-struct Camera3DBlurUniforms {
-    params0: [f32; 4],
-    params1: [f32; 4],
-    params2: [f32; 4],
+pub(crate) struct Camera3DBlurUniforms {
+    pub params0: [f32; 4],
+    pub params1: [f32; 4],
+    pub params2: [f32; 4],
 }
 
 fn make_bind_group_layout(device: &wgpu::Device, label: &str) -> wgpu::BindGroupLayout {
@@ -3,11 +3,12 @@ use cap_project::XY;
 use glyphon::cosmic_text::LayoutRunIter;
 use glyphon::{
     Attrs, Buffer, Cache, Color, Family, FontSystem, Metrics, Resolution, Shaping, SwashCache,
-    TextArea, TextAtlas, TextBounds, TextRenderer, Viewport, Weight,
+    TextAtlas, TextBounds, TextRenderer, Viewport, Weight,
 };
 use log::warn;
 use wgpu::{Device, Queue, include_wgsl, util::DeviceExt};
 
+use super::TextPlacement;
 use crate::{DecodedSegmentFrames, ProjectUniforms, RenderVideoConstants, parse_color_component};
 
 #[derive(Debug, Clone)]
@@ -57,6 +58,18 @@ struct CaptionBackgroundUniforms {
     _padding2: [f32; 4],
 }
 
+impl From<CaptionBox> for CaptionBackgroundUniforms {
+    fn from(value: CaptionBox) -> Self {
+        Self {
+            rect: value.rect,
+            color: value.color,
+            radius: value.radius,
+            _padding: [0.0; 3],
+            _padding2: [0.0; 4],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CaptionPosition {
     TopLeft,
@@ -268,6 +281,577 @@ fn calculate_word_highlight(
     0.0
 }
 
+/// A rounded caption box: the background card or the active-word pill.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CaptionBox {
+    pub rect: [f32; 4],
+    pub color: [f32; 4],
+    pub radius: f32,
+}
+
+/// One frame's caption, laid out but not yet drawn.
+pub(crate) struct CaptionFrame {
+    /// Active segment text, start and effective end.
+    pub segment: (String, f32, f32),
+    pub buffer: Buffer,
+    /// Outline passes then the text itself, all drawing `buffer`.
+    pub placements: Vec<TextPlacement>,
+    pub background: CaptionBox,
+    /// The active-word pill and its scissor rect.
+    pub highlight: Option<(CaptionBox, [u32; 4])>,
+    /// Scissor around the caption box; `None` when it falls off-frame and
+    /// nothing should be drawn.
+    pub scissor: Option<[u32; 4]>,
+    pub layout: CaptionOverlayLayout,
+}
+
+/// Lays out the caption active at the current frame, if any.
+pub(crate) fn layout_caption(
+    font_system: &mut FontSystem,
+    uniforms: &ProjectUniforms,
+    output_size: XY<u32>,
+) -> Option<CaptionFrame> {
+    let Some(caption_data) = &uniforms.project.captions else {
+        return None;
+    };
+
+    if !caption_data.settings.enabled {
+        return None;
+    }
+
+    let timeline = match &uniforms.project.timeline {
+        Some(t) => t,
+        None => {
+            return None;
+        }
+    };
+
+    if timeline.caption_segments.is_empty() {
+        return None;
+    }
+
+    let current_time = uniforms.frame_number as f64 / uniforms.frame_rate as f64;
+    let default_fade = caption_data.settings.fade_duration;
+    let word_transition_duration = caption_data.settings.word_transition_duration;
+
+    let Some(active) =
+        find_active_caption_segment(current_time, &timeline.caption_segments, default_fade)
+    else {
+        return None;
+    };
+
+    let segment_fade = active
+        .segment
+        .fade_duration_override
+        .unwrap_or(default_fade) as f64;
+
+    let effective_end = caption_segment_effective_end(active.segment);
+
+    let joined_caption_text = active
+        .segment
+        .text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let uppercase = caption_data.settings.uppercase;
+    let caption_text = if uppercase {
+        joined_caption_text.to_uppercase()
+    } else {
+        joined_caption_text
+    };
+    let caption_words: Vec<CaptionWord> = active
+        .segment
+        .words
+        .iter()
+        .map(|w| CaptionWord {
+            text: w.text.clone(),
+            start: w.start,
+            end: w.end,
+        })
+        .collect();
+
+    let fade_opacity = calculate_caption_fade(
+        current_time,
+        active.segment.start,
+        effective_end,
+        segment_fade,
+    ) * uniforms.takeover_overlay_fade();
+    if fade_opacity <= 0.0 {
+        return None;
+    }
+
+    let animation = CaptionAnimation::from_str(&caption_data.settings.animation);
+
+    let bounce_offset = if animation == CaptionAnimation::Bounce {
+        calculate_caption_bounce(
+            current_time,
+            active.segment.start,
+            effective_end,
+            segment_fade,
+        )
+    } else {
+        0.0
+    };
+
+    let pop_scale = if animation == CaptionAnimation::Pop {
+        calculate_caption_pop_scale(
+            current_time,
+            active.segment.start,
+            effective_end,
+            segment_fade,
+        )
+    } else {
+        1.0
+    };
+
+    let active_word_highlight_enabled = caption_data.settings.active_word_highlight;
+    let use_pill_highlight = active_word_highlight_enabled
+        && !caption_words.is_empty()
+        && caption_data.settings.highlight_style == "pill";
+    let use_color_highlight =
+        active_word_highlight_enabled && !caption_words.is_empty() && !use_pill_highlight;
+
+    let active_word_byte_range = if use_pill_highlight {
+        find_active_word_index(current_time as f32, &caption_words)
+            .and_then(|idx| word_byte_range(&caption_text, &caption_words, idx, uppercase))
+    } else {
+        None
+    };
+
+    let (width, height) = (output_size.x, output_size.y);
+
+    let position = active
+        .segment
+        .position_override
+        .as_deref()
+        .map(CaptionPosition::from_str)
+        .unwrap_or_else(|| CaptionPosition::from_str(&caption_data.settings.position));
+    let margin = width as f32 * 0.05;
+
+    let base_color = [
+        parse_color_component(&caption_data.settings.color, 0),
+        parse_color_component(&caption_data.settings.color, 1),
+        parse_color_component(&caption_data.settings.color, 2),
+    ];
+
+    let highlight_color_rgb = [
+        parse_color_component(&caption_data.settings.highlight_color, 0),
+        parse_color_component(&caption_data.settings.highlight_color, 1),
+        parse_color_component(&caption_data.settings.highlight_color, 2),
+    ];
+
+    let outline_color_rgb = [
+        parse_color_component(&caption_data.settings.outline_color, 0),
+        parse_color_component(&caption_data.settings.outline_color, 1),
+        parse_color_component(&caption_data.settings.outline_color, 2),
+    ];
+
+    let background_color_rgb = [
+        parse_color_component(&caption_data.settings.background_color, 0),
+        parse_color_component(&caption_data.settings.background_color, 1),
+        parse_color_component(&caption_data.settings.background_color, 2),
+    ];
+
+    let background_alpha =
+        ((caption_data.settings.background_opacity as f32 / 100.0) * fade_opacity).clamp(0.0, 1.0);
+
+    let font_size = caption_data.settings.size as f32 * (height as f32 / 1080.0);
+    let metrics = Metrics::new(font_size, font_size * 1.2);
+
+    let mut updated_buffer = Buffer::new(font_system, metrics);
+    let wrap_width = (width as f32 - margin * 2.0).max(font_size);
+    updated_buffer.set_size(font_system, Some(wrap_width), None);
+    updated_buffer.set_wrap(font_system, glyphon::Wrap::None);
+
+    let font_family = match caption_data.settings.font.as_str() {
+        "System Serif" => Family::Serif,
+        "System Monospace" => Family::Monospace,
+        _ => Family::SansSerif,
+    };
+
+    let weight = if caption_data.settings.font_weight >= 700 {
+        Weight::BOLD
+    } else if caption_data.settings.font_weight >= 500 {
+        Weight::MEDIUM
+    } else {
+        Weight::NORMAL
+    };
+
+    let base_alpha = (fade_opacity * BASE_TEXT_OPACITY).clamp(0.0, 1.0);
+    let highlight_alpha = fade_opacity.clamp(0.0, 1.0);
+
+    if use_color_highlight {
+        let mut rich_text: Vec<(&str, Attrs)> = Vec::new();
+        let full_text = caption_text.as_str();
+        let mut last_end = 0usize;
+
+        for (idx, word) in caption_words.iter().enumerate() {
+            let needle = if uppercase {
+                word.text.to_uppercase()
+            } else {
+                word.text.clone()
+            };
+            if let Some(start_pos) = full_text.get(last_end..).and_then(|s| s.find(&needle)) {
+                let abs_start = last_end + start_pos;
+
+                if abs_start > last_end {
+                    let space = &full_text[last_end..abs_start];
+                    rich_text.push((
+                        space,
+                        Attrs::new()
+                            .family(font_family)
+                            .weight(weight)
+                            .color(Color::rgba(
+                                (base_color[0] * 255.0) as u8,
+                                (base_color[1] * 255.0) as u8,
+                                (base_color[2] * 255.0) as u8,
+                                (base_alpha * 255.0) as u8,
+                            )),
+                    ));
+                }
+
+                let word_highlight = calculate_word_highlight(
+                    current_time as f32,
+                    word,
+                    idx,
+                    &caption_words,
+                    word_transition_duration,
+                );
+
+                let blended_color = [
+                    base_color[0] + (highlight_color_rgb[0] - base_color[0]) * word_highlight,
+                    base_color[1] + (highlight_color_rgb[1] - base_color[1]) * word_highlight,
+                    base_color[2] + (highlight_color_rgb[2] - base_color[2]) * word_highlight,
+                ];
+
+                let blended_alpha = base_alpha + (highlight_alpha - base_alpha) * word_highlight;
+
+                let word_end = abs_start + needle.len();
+                rich_text.push((
+                    &full_text[abs_start..word_end],
+                    Attrs::new()
+                        .family(font_family)
+                        .weight(weight)
+                        .color(Color::rgba(
+                            (blended_color[0] * 255.0) as u8,
+                            (blended_color[1] * 255.0) as u8,
+                            (blended_color[2] * 255.0) as u8,
+                            (blended_alpha * 255.0) as u8,
+                        )),
+                ));
+                last_end = word_end;
+            }
+        }
+
+        if last_end < full_text.len() {
+            rich_text.push((
+                &full_text[last_end..],
+                Attrs::new()
+                    .family(font_family)
+                    .weight(weight)
+                    .color(Color::rgba(
+                        (base_color[0] * 255.0) as u8,
+                        (base_color[1] * 255.0) as u8,
+                        (base_color[2] * 255.0) as u8,
+                        (base_alpha * 255.0) as u8,
+                    )),
+            ));
+        }
+
+        updated_buffer.set_rich_text(
+            font_system,
+            rich_text,
+            &Attrs::new().family(font_family).weight(weight),
+            Shaping::Advanced,
+            None,
+        );
+    } else {
+        let color = Color::rgba(
+            (base_color[0] * 255.0) as u8,
+            (base_color[1] * 255.0) as u8,
+            (base_color[2] * 255.0) as u8,
+            (highlight_alpha * 255.0) as u8,
+        );
+        let attrs = Attrs::new().family(font_family).weight(weight).color(color);
+        updated_buffer.set_text(
+            font_system,
+            caption_text.as_str(),
+            &attrs,
+            Shaping::Advanced,
+        );
+    }
+
+    let mut layout_width: f32 = 0.0;
+    let mut layout_height: f32 = 0.0;
+    let mut highlight_extent: Option<(f32, f32, f32, f32)> = None;
+    for run in LayoutRunIter::new(&updated_buffer) {
+        layout_width = layout_width.max(run.line_w);
+        layout_height = layout_height.max(run.line_top + run.line_height);
+
+        if let Some((word_start, word_end)) = active_word_byte_range {
+            for glyph in run.glyphs.iter() {
+                if glyph.start < word_end && glyph.end > word_start {
+                    match highlight_extent {
+                        Some((
+                            ref mut min_x,
+                            ref mut max_x,
+                            ref mut line_top,
+                            ref mut line_height,
+                        )) => {
+                            *min_x = min_x.min(glyph.x);
+                            *max_x = max_x.max(glyph.x + glyph.w);
+                            *line_top = run.line_top;
+                            *line_height = run.line_height;
+                        }
+                        None => {
+                            highlight_extent =
+                                Some((glyph.x, glyph.x + glyph.w, run.line_top, run.line_height));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if layout_height == 0.0 {
+        layout_height = font_size * 1.2;
+        layout_width = layout_width.max(font_size);
+    }
+
+    let available_width = (width as f32 - margin * 2.0).max(1.0);
+    let initial_padding = font_size * 0.5;
+    let fit_scale = if layout_width + initial_padding * 2.0 > available_width {
+        (available_width / (layout_width + initial_padding * 2.0)).clamp(0.35, 1.0)
+    } else {
+        1.0
+    };
+    let effective_font_size = font_size * fit_scale;
+    let padding = effective_font_size * 0.5;
+    let corner_radius = effective_font_size * 0.55;
+    let text_width = (layout_width * fit_scale).min(available_width);
+    let text_height = layout_height * fit_scale;
+    let box_width = (text_width + padding * 2.0).min(available_width).max(1.0);
+    let box_height = (text_height + padding * 2.0).min(height as f32).max(1.0);
+
+    let background_left = if position == CaptionPosition::Manual {
+        caption_data
+            .settings
+            .manual_position
+            .map(|manual_position| {
+                (manual_position.x.clamp(0.0, 1.0) * width as f32 - box_width / 2.0)
+                    .clamp(0.0, (width as f32 - box_width).max(0.0))
+            })
+            .unwrap_or_else(|| ((width as f32 - box_width) / 2.0).max(0.0))
+    } else {
+        match position {
+            CaptionPosition::TopLeft | CaptionPosition::BottomLeft => margin,
+            CaptionPosition::TopRight | CaptionPosition::BottomRight => {
+                (width as f32 - margin - box_width).max(0.0)
+            }
+            CaptionPosition::TopCenter | CaptionPosition::BottomCenter => {
+                ((width as f32 - box_width) / 2.0).max(0.0)
+            }
+            CaptionPosition::Manual => ((width as f32 - box_width) / 2.0).max(0.0),
+        }
+    };
+
+    let center_y = if position == CaptionPosition::Manual {
+        caption_data
+            .settings
+            .manual_position
+            .map(|manual_position| manual_position.y.clamp(0.0, 1.0) * height as f32)
+            .unwrap_or_else(|| height as f32 * CaptionPosition::BottomCenter.y_factor())
+    } else {
+        height as f32 * position.y_factor()
+    };
+    let base_background_top =
+        (center_y - box_height / 2.0).clamp(0.0, (height as f32 - box_height).max(0.0));
+    let background_top = (base_background_top + bounce_offset as f32)
+        .clamp(0.0, (height as f32 - box_height).max(0.0));
+
+    let anim_scale = pop_scale as f32;
+    let box_center_x = background_left + box_width / 2.0;
+    let box_center_y = background_top + box_height / 2.0;
+    let draw_box_width = box_width * anim_scale;
+    let draw_box_height = box_height * anim_scale;
+    let draw_box_left = box_center_x - draw_box_width / 2.0;
+    let draw_box_top = box_center_y - draw_box_height / 2.0;
+    let render_scale = fit_scale * anim_scale;
+    let draw_text_width = text_width * anim_scale;
+    let draw_text_height = text_height * anim_scale;
+
+    let text_left = draw_box_left + padding * anim_scale;
+    let text_top = draw_box_top + padding * anim_scale;
+
+    let bounds = TextBounds {
+        left: (text_left - 2.0).floor() as i32,
+        top: (text_top - 2.0).floor() as i32,
+        right: (text_left + draw_text_width + 2.0).ceil() as i32,
+        bottom: (text_top + draw_text_height + 2.0).ceil() as i32,
+    };
+
+    let outline_color = Color::rgba(
+        (outline_color_rgb[0] * 255.0) as u8,
+        (outline_color_rgb[1] * 255.0) as u8,
+        (outline_color_rgb[2] * 255.0) as u8,
+        (fade_opacity * 255.0) as u8,
+    );
+
+    let mut placements = Vec::new();
+    let placement = |left: f32, top: f32, default_color: Color| TextPlacement {
+        buffer: 0,
+        left,
+        top,
+        scale: render_scale,
+        bounds,
+        default_color,
+    };
+
+    if caption_data.settings.outline {
+        let outline_thickness = 1.2 * render_scale;
+        let outline_offsets = [
+            (-outline_thickness, -outline_thickness),
+            (0.0, -outline_thickness),
+            (outline_thickness, -outline_thickness),
+            (-outline_thickness, 0.0),
+            (outline_thickness, 0.0),
+            (-outline_thickness, outline_thickness),
+            (0.0, outline_thickness),
+            (outline_thickness, outline_thickness),
+            (-outline_thickness * 0.7, -outline_thickness * 0.7),
+            (outline_thickness * 0.7, -outline_thickness * 0.7),
+            (-outline_thickness * 0.7, outline_thickness * 0.7),
+            (outline_thickness * 0.7, outline_thickness * 0.7),
+        ];
+
+        for (offset_x, offset_y) in outline_offsets.iter() {
+            placements.push(placement(
+                text_left + offset_x,
+                text_top + offset_y,
+                outline_color,
+            ));
+        }
+    }
+
+    let default_color = Color::rgba(
+        (base_color[0] * 255.0) as u8,
+        (base_color[1] * 255.0) as u8,
+        (base_color[2] * 255.0) as u8,
+        (base_alpha * 255.0) as u8,
+    );
+
+    placements.push(placement(text_left, text_top, default_color));
+
+    let rect = [
+        draw_box_left.max(0.0),
+        draw_box_top.max(0.0),
+        draw_box_width,
+        draw_box_height,
+    ];
+
+    let background = CaptionBox {
+        rect,
+        color: [
+            background_color_rgb[0],
+            background_color_rgb[1],
+            background_color_rgb[2],
+            background_alpha,
+        ],
+        radius: (corner_radius * anim_scale)
+            .min(draw_box_width / 2.0)
+            .min(draw_box_height / 2.0),
+    };
+
+    let mut highlight = None;
+    if let Some((min_x, max_x, line_top, line_height)) = highlight_extent {
+        if max_x > min_x {
+            let pill_pad_x = effective_font_size * 0.28 * anim_scale;
+            let pill_pad_y = effective_font_size * 0.12 * anim_scale;
+            let pill_left = (text_left + min_x * render_scale - pill_pad_x).max(0.0);
+            let pill_top = (text_top + line_top * render_scale - pill_pad_y).max(0.0);
+            let pill_width = ((max_x - min_x) * render_scale + pill_pad_x * 2.0)
+                .min((width as f32 - pill_left).max(0.0))
+                .max(1.0);
+            let pill_height = (line_height * render_scale + pill_pad_y * 2.0)
+                .min((height as f32 - pill_top).max(0.0))
+                .max(1.0);
+            let pill_radius = (pill_height * 0.4).min(pill_width / 2.0);
+
+            let pill = CaptionBox {
+                rect: [pill_left, pill_top, pill_width, pill_height],
+                color: [
+                    highlight_color_rgb[0],
+                    highlight_color_rgb[1],
+                    highlight_color_rgb[2],
+                    fade_opacity,
+                ],
+                radius: pill_radius,
+            };
+
+            let pill_scissor_pad = 3.0;
+            let pill_scissor_x = (pill_left - pill_scissor_pad).max(0.0).floor() as u32;
+            let pill_scissor_y = (pill_top - pill_scissor_pad).max(0.0).floor() as u32;
+            let pill_max_width = width.saturating_sub(pill_scissor_x);
+            let pill_max_height = height.saturating_sub(pill_scissor_y);
+
+            if pill_max_width > 0 && pill_max_height > 0 {
+                let pill_scissor_width = (pill_width + pill_scissor_pad * 2.0)
+                    .ceil()
+                    .max(1.0)
+                    .min(pill_max_width as f32) as u32;
+                let pill_scissor_height = (pill_height + pill_scissor_pad * 2.0)
+                    .ceil()
+                    .max(1.0)
+                    .min(pill_max_height as f32) as u32;
+
+                highlight = Some((
+                    pill,
+                    [
+                        pill_scissor_x,
+                        pill_scissor_y,
+                        pill_scissor_width,
+                        pill_scissor_height,
+                    ],
+                ));
+            }
+        }
+    }
+
+    let scissor_padding = 4.0;
+    let scissor_x = (draw_box_left - scissor_padding).max(0.0).floor() as u32;
+    let scissor_y = (draw_box_top - scissor_padding).max(0.0).floor() as u32;
+    let max_width = width.saturating_sub(scissor_x);
+    let max_height = height.saturating_sub(scissor_y);
+
+    let scissor = (max_width > 0 && max_height > 0)
+        .then(|| {
+            let scissor_width = (draw_box_width + scissor_padding * 2.0)
+                .ceil()
+                .max(1.0)
+                .min(max_width as f32) as u32;
+            let scissor_height = (draw_box_height + scissor_padding * 2.0)
+                .ceil()
+                .max(1.0)
+                .min(max_height as f32) as u32;
+            [scissor_x, scissor_y, scissor_width, scissor_height]
+        })
+        .filter(|scissor| scissor[2] > 0 && scissor[3] > 0);
+
+    Some(CaptionFrame {
+        segment: (
+            active.segment.text.clone(),
+            active.segment.start as f32,
+            effective_end as f32,
+        ),
+        buffer: updated_buffer,
+        placements,
+        background,
+        highlight: scissor.and(highlight),
+        scissor,
+        layout: CaptionOverlayLayout { rect, position },
+    })
+}
+
 pub struct CaptionsLayer {
     _settings_buffer: wgpu::Buffer,
     font_system: FontSystem,
@@ -460,465 +1044,33 @@ impl CaptionsLayer {
         self.has_highlight = false;
         self.output_size = (output_size.x, output_size.y);
 
-        let Some(caption_data) = &uniforms.project.captions else {
+        let Some(frame) = layout_caption(&mut self.font_system, uniforms, output_size) else {
             self.current_text = None;
             return;
         };
 
-        if !caption_data.settings.enabled {
-            self.current_text = None;
-            return;
-        }
+        let (text, start, end) = frame.segment;
+        self.update_caption(Some(text), start, end);
 
-        let timeline = match &uniforms.project.timeline {
-            Some(t) => t,
-            None => {
-                self.current_text = None;
-                return;
-            }
-        };
-
-        if timeline.caption_segments.is_empty() {
-            self.current_text = None;
-            return;
-        }
-
-        let current_time = uniforms.frame_number as f64 / uniforms.frame_rate as f64;
-        let default_fade = caption_data.settings.fade_duration;
-        let word_transition_duration = caption_data.settings.word_transition_duration;
-
-        let Some(active) =
-            find_active_caption_segment(current_time, &timeline.caption_segments, default_fade)
-        else {
-            self.current_text = None;
-            return;
-        };
-
-        let segment_fade = active
-            .segment
-            .fade_duration_override
-            .unwrap_or(default_fade) as f64;
-
-        let effective_end = caption_segment_effective_end(active.segment);
-
-        self.update_caption(
-            Some(active.segment.text.clone()),
-            active.segment.start as f32,
-            effective_end as f32,
-        );
-
-        let raw_caption_text = self.current_text.clone().unwrap_or_default();
-        let joined_caption_text = raw_caption_text
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join(" ");
-        let uppercase = caption_data.settings.uppercase;
-        let caption_text = if uppercase {
-            joined_caption_text.to_uppercase()
-        } else {
-            joined_caption_text
-        };
-        let caption_words: Vec<CaptionWord> = active
-            .segment
-            .words
-            .iter()
-            .map(|w| CaptionWord {
-                text: w.text.clone(),
-                start: w.start,
-                end: w.end,
-            })
-            .collect();
-
-        let fade_opacity = calculate_caption_fade(
-            current_time,
-            active.segment.start,
-            effective_end,
-            segment_fade,
-        ) * uniforms.takeover_overlay_fade();
-        if fade_opacity <= 0.0 {
-            self.current_text = None;
-            return;
-        }
-
-        let animation = CaptionAnimation::from_str(&caption_data.settings.animation);
-
-        let bounce_offset = if animation == CaptionAnimation::Bounce {
-            calculate_caption_bounce(
-                current_time,
-                active.segment.start,
-                effective_end,
-                segment_fade,
-            )
-        } else {
-            0.0
-        };
-
-        let pop_scale = if animation == CaptionAnimation::Pop {
-            calculate_caption_pop_scale(
-                current_time,
-                active.segment.start,
-                effective_end,
-                segment_fade,
-            )
-        } else {
-            1.0
-        };
-
-        let active_word_highlight_enabled = caption_data.settings.active_word_highlight;
-        let use_pill_highlight = active_word_highlight_enabled
-            && !caption_words.is_empty()
-            && caption_data.settings.highlight_style == "pill";
-        let use_color_highlight =
-            active_word_highlight_enabled && !caption_words.is_empty() && !use_pill_highlight;
-
-        let active_word_byte_range = if use_pill_highlight {
-            find_active_word_index(current_time as f32, &caption_words)
-                .and_then(|idx| word_byte_range(&caption_text, &caption_words, idx, uppercase))
-        } else {
-            None
-        };
-
-        let (width, height) = (output_size.x, output_size.y);
-        let device = &constants.device;
         let queue = &constants.queue;
-
-        let position = active
-            .segment
-            .position_override
-            .as_deref()
-            .map(CaptionPosition::from_str)
-            .unwrap_or_else(|| CaptionPosition::from_str(&caption_data.settings.position));
-        let margin = width as f32 * 0.05;
-
-        let base_color = [
-            parse_color_component(&caption_data.settings.color, 0),
-            parse_color_component(&caption_data.settings.color, 1),
-            parse_color_component(&caption_data.settings.color, 2),
-        ];
-
-        let highlight_color_rgb = [
-            parse_color_component(&caption_data.settings.highlight_color, 0),
-            parse_color_component(&caption_data.settings.highlight_color, 1),
-            parse_color_component(&caption_data.settings.highlight_color, 2),
-        ];
-
-        let outline_color_rgb = [
-            parse_color_component(&caption_data.settings.outline_color, 0),
-            parse_color_component(&caption_data.settings.outline_color, 1),
-            parse_color_component(&caption_data.settings.outline_color, 2),
-        ];
-
-        let background_color_rgb = [
-            parse_color_component(&caption_data.settings.background_color, 0),
-            parse_color_component(&caption_data.settings.background_color, 1),
-            parse_color_component(&caption_data.settings.background_color, 2),
-        ];
-
-        let background_alpha = ((caption_data.settings.background_opacity as f32 / 100.0)
-            * fade_opacity)
-            .clamp(0.0, 1.0);
-
-        let font_size = caption_data.settings.size as f32 * (height as f32 / 1080.0);
-        let metrics = Metrics::new(font_size, font_size * 1.2);
-
-        let mut updated_buffer = Buffer::new(&mut self.font_system, metrics);
-        let wrap_width = (width as f32 - margin * 2.0).max(font_size);
-        updated_buffer.set_size(&mut self.font_system, Some(wrap_width), None);
-        updated_buffer.set_wrap(&mut self.font_system, glyphon::Wrap::None);
-
-        let font_family = match caption_data.settings.font.as_str() {
-            "System Serif" => Family::Serif,
-            "System Monospace" => Family::Monospace,
-            _ => Family::SansSerif,
-        };
-
-        let weight = if caption_data.settings.font_weight >= 700 {
-            Weight::BOLD
-        } else if caption_data.settings.font_weight >= 500 {
-            Weight::MEDIUM
-        } else {
-            Weight::NORMAL
-        };
-
-        let base_alpha = (fade_opacity * BASE_TEXT_OPACITY).clamp(0.0, 1.0);
-        let highlight_alpha = fade_opacity.clamp(0.0, 1.0);
-
-        if use_color_highlight {
-            let mut rich_text: Vec<(&str, Attrs)> = Vec::new();
-            let full_text = caption_text.as_str();
-            let mut last_end = 0usize;
-
-            for (idx, word) in caption_words.iter().enumerate() {
-                let needle = if uppercase {
-                    word.text.to_uppercase()
-                } else {
-                    word.text.clone()
-                };
-                if let Some(start_pos) = full_text.get(last_end..).and_then(|s| s.find(&needle)) {
-                    let abs_start = last_end + start_pos;
-
-                    if abs_start > last_end {
-                        let space = &full_text[last_end..abs_start];
-                        rich_text.push((
-                            space,
-                            Attrs::new()
-                                .family(font_family)
-                                .weight(weight)
-                                .color(Color::rgba(
-                                    (base_color[0] * 255.0) as u8,
-                                    (base_color[1] * 255.0) as u8,
-                                    (base_color[2] * 255.0) as u8,
-                                    (base_alpha * 255.0) as u8,
-                                )),
-                        ));
-                    }
-
-                    let word_highlight = calculate_word_highlight(
-                        current_time as f32,
-                        word,
-                        idx,
-                        &caption_words,
-                        word_transition_duration,
-                    );
-
-                    let blended_color = [
-                        base_color[0] + (highlight_color_rgb[0] - base_color[0]) * word_highlight,
-                        base_color[1] + (highlight_color_rgb[1] - base_color[1]) * word_highlight,
-                        base_color[2] + (highlight_color_rgb[2] - base_color[2]) * word_highlight,
-                    ];
-
-                    let blended_alpha =
-                        base_alpha + (highlight_alpha - base_alpha) * word_highlight;
-
-                    let word_end = abs_start + needle.len();
-                    rich_text.push((
-                        &full_text[abs_start..word_end],
-                        Attrs::new()
-                            .family(font_family)
-                            .weight(weight)
-                            .color(Color::rgba(
-                                (blended_color[0] * 255.0) as u8,
-                                (blended_color[1] * 255.0) as u8,
-                                (blended_color[2] * 255.0) as u8,
-                                (blended_alpha * 255.0) as u8,
-                            )),
-                    ));
-                    last_end = word_end;
-                }
-            }
-
-            if last_end < full_text.len() {
-                rich_text.push((
-                    &full_text[last_end..],
-                    Attrs::new()
-                        .family(font_family)
-                        .weight(weight)
-                        .color(Color::rgba(
-                            (base_color[0] * 255.0) as u8,
-                            (base_color[1] * 255.0) as u8,
-                            (base_color[2] * 255.0) as u8,
-                            (base_alpha * 255.0) as u8,
-                        )),
-                ));
-            }
-
-            updated_buffer.set_rich_text(
-                &mut self.font_system,
-                rich_text,
-                &Attrs::new().family(font_family).weight(weight),
-                Shaping::Advanced,
-                None,
-            );
-        } else {
-            let color = Color::rgba(
-                (base_color[0] * 255.0) as u8,
-                (base_color[1] * 255.0) as u8,
-                (base_color[2] * 255.0) as u8,
-                (highlight_alpha * 255.0) as u8,
-            );
-            let attrs = Attrs::new().family(font_family).weight(weight).color(color);
-            updated_buffer.set_text(
-                &mut self.font_system,
-                caption_text.as_str(),
-                &attrs,
-                Shaping::Advanced,
-            );
-        }
-
-        let mut layout_width: f32 = 0.0;
-        let mut layout_height: f32 = 0.0;
-        let mut highlight_extent: Option<(f32, f32, f32, f32)> = None;
-        for run in LayoutRunIter::new(&updated_buffer) {
-            layout_width = layout_width.max(run.line_w);
-            layout_height = layout_height.max(run.line_top + run.line_height);
-
-            if let Some((word_start, word_end)) = active_word_byte_range {
-                for glyph in run.glyphs.iter() {
-                    if glyph.start < word_end && glyph.end > word_start {
-                        match highlight_extent {
-                            Some((
-                                ref mut min_x,
-                                ref mut max_x,
-                                ref mut line_top,
-                                ref mut line_height,
-                            )) => {
-                                *min_x = min_x.min(glyph.x);
-                                *max_x = max_x.max(glyph.x + glyph.w);
-                                *line_top = run.line_top;
-                                *line_height = run.line_height;
-                            }
-                            None => {
-                                highlight_extent = Some((
-                                    glyph.x,
-                                    glyph.x + glyph.w,
-                                    run.line_top,
-                                    run.line_height,
-                                ));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        if layout_height == 0.0 {
-            layout_height = font_size * 1.2;
-            layout_width = layout_width.max(font_size);
-        }
-
-        let available_width = (width as f32 - margin * 2.0).max(1.0);
-        let initial_padding = font_size * 0.5;
-        let fit_scale = if layout_width + initial_padding * 2.0 > available_width {
-            (available_width / (layout_width + initial_padding * 2.0)).clamp(0.35, 1.0)
-        } else {
-            1.0
-        };
-        let effective_font_size = font_size * fit_scale;
-        let padding = effective_font_size * 0.5;
-        let corner_radius = effective_font_size * 0.55;
-        let text_width = (layout_width * fit_scale).min(available_width);
-        let text_height = layout_height * fit_scale;
-        let box_width = (text_width + padding * 2.0).min(available_width).max(1.0);
-        let box_height = (text_height + padding * 2.0).min(height as f32).max(1.0);
-
-        let background_left = if position == CaptionPosition::Manual {
-            caption_data
-                .settings
-                .manual_position
-                .map(|manual_position| {
-                    (manual_position.x.clamp(0.0, 1.0) * width as f32 - box_width / 2.0)
-                        .clamp(0.0, (width as f32 - box_width).max(0.0))
-                })
-                .unwrap_or_else(|| ((width as f32 - box_width) / 2.0).max(0.0))
-        } else {
-            match position {
-                CaptionPosition::TopLeft | CaptionPosition::BottomLeft => margin,
-                CaptionPosition::TopRight | CaptionPosition::BottomRight => {
-                    (width as f32 - margin - box_width).max(0.0)
-                }
-                CaptionPosition::TopCenter | CaptionPosition::BottomCenter => {
-                    ((width as f32 - box_width) / 2.0).max(0.0)
-                }
-                CaptionPosition::Manual => ((width as f32 - box_width) / 2.0).max(0.0),
-            }
-        };
-
-        let center_y = if position == CaptionPosition::Manual {
-            caption_data
-                .settings
-                .manual_position
-                .map(|manual_position| manual_position.y.clamp(0.0, 1.0) * height as f32)
-                .unwrap_or_else(|| height as f32 * CaptionPosition::BottomCenter.y_factor())
-        } else {
-            height as f32 * position.y_factor()
-        };
-        let base_background_top =
-            (center_y - box_height / 2.0).clamp(0.0, (height as f32 - box_height).max(0.0));
-        let background_top = (base_background_top + bounce_offset as f32)
-            .clamp(0.0, (height as f32 - box_height).max(0.0));
-
-        let anim_scale = pop_scale as f32;
-        let box_center_x = background_left + box_width / 2.0;
-        let box_center_y = background_top + box_height / 2.0;
-        let draw_box_width = box_width * anim_scale;
-        let draw_box_height = box_height * anim_scale;
-        let draw_box_left = box_center_x - draw_box_width / 2.0;
-        let draw_box_top = box_center_y - draw_box_height / 2.0;
-        let render_scale = fit_scale * anim_scale;
-        let draw_text_width = text_width * anim_scale;
-        let draw_text_height = text_height * anim_scale;
-
-        let text_left = draw_box_left + padding * anim_scale;
-        let text_top = draw_box_top + padding * anim_scale;
-
-        let bounds = TextBounds {
-            left: (text_left - 2.0).floor() as i32,
-            top: (text_top - 2.0).floor() as i32,
-            right: (text_left + draw_text_width + 2.0).ceil() as i32,
-            bottom: (text_top + draw_text_height + 2.0).ceil() as i32,
-        };
-
-        self.text_buffer = updated_buffer;
-        self.viewport.update(queue, Resolution { width, height });
-
-        let mut text_areas = Vec::new();
-
-        let outline_color = Color::rgba(
-            (outline_color_rgb[0] * 255.0) as u8,
-            (outline_color_rgb[1] * 255.0) as u8,
-            (outline_color_rgb[2] * 255.0) as u8,
-            (fade_opacity * 255.0) as u8,
-        );
-
-        if caption_data.settings.outline {
-            let outline_thickness = 1.2 * render_scale;
-            let outline_offsets = [
-                (-outline_thickness, -outline_thickness),
-                (0.0, -outline_thickness),
-                (outline_thickness, -outline_thickness),
-                (-outline_thickness, 0.0),
-                (outline_thickness, 0.0),
-                (-outline_thickness, outline_thickness),
-                (0.0, outline_thickness),
-                (outline_thickness, outline_thickness),
-                (-outline_thickness * 0.7, -outline_thickness * 0.7),
-                (outline_thickness * 0.7, -outline_thickness * 0.7),
-                (-outline_thickness * 0.7, outline_thickness * 0.7),
-                (outline_thickness * 0.7, outline_thickness * 0.7),
-            ];
-
-            for (offset_x, offset_y) in outline_offsets.iter() {
-                text_areas.push(TextArea {
-                    buffer: &self.text_buffer,
-                    left: text_left + offset_x,
-                    top: text_top + offset_y,
-                    scale: render_scale,
-                    bounds,
-                    default_color: outline_color,
-                    custom_glyphs: &[],
-                });
-            }
-        }
-
-        let default_color = Color::rgba(
-            (base_color[0] * 255.0) as u8,
-            (base_color[1] * 255.0) as u8,
-            (base_color[2] * 255.0) as u8,
-            (base_alpha * 255.0) as u8,
+        self.text_buffer = frame.buffer;
+        self.viewport.update(
+            queue,
+            Resolution {
+                width: output_size.x,
+                height: output_size.y,
+            },
         );
 
-        text_areas.push(TextArea {
-            buffer: &self.text_buffer,
-            left: text_left,
-            top: text_top,
-            scale: render_scale,
-            bounds,
-            default_color,
-            custom_glyphs: &[],
-        });
+        let buffers = std::slice::from_ref(&self.text_buffer);
+        let text_areas = frame
+            .placements
+            .iter()
+            .map(|placement| placement.to_text_area(buffers))
+            .collect::<Vec<_>>();
 
         match self.text_renderer.prepare(
-            device,
+            &constants.device,
             queue,
             &mut self.font_system,
             &mut self.text_atlas,
@@ -930,128 +1082,26 @@ impl CaptionsLayer {
             Err(e) => warn!("Error preparing text: {e:?}"),
         }
 
-        let rect = [
-            draw_box_left.max(0.0),
-            draw_box_top.max(0.0),
-            draw_box_width,
-            draw_box_height,
-        ];
-
-        self.active_layout = Some(CaptionOverlayLayout { rect, position });
-
-        let background_uniforms = CaptionBackgroundUniforms {
-            rect,
-            color: [
-                background_color_rgb[0],
-                background_color_rgb[1],
-                background_color_rgb[2],
-                background_alpha,
-            ],
-            radius: (corner_radius * anim_scale)
-                .min(draw_box_width / 2.0)
-                .min(draw_box_height / 2.0),
-            _padding: [0.0; 3],
-            _padding2: [0.0; 4],
-        };
+        self.active_layout = Some(frame.layout);
 
         queue.write_buffer(
             &self.background_uniform_buffer,
             0,
-            bytemuck::bytes_of(&background_uniforms),
+            bytemuck::bytes_of(&CaptionBackgroundUniforms::from(frame.background)),
         );
 
-        if let Some((min_x, max_x, line_top, line_height)) = highlight_extent {
-            if max_x > min_x {
-                let pill_pad_x = effective_font_size * 0.28 * anim_scale;
-                let pill_pad_y = effective_font_size * 0.12 * anim_scale;
-                let pill_left = (text_left + min_x * render_scale - pill_pad_x).max(0.0);
-                let pill_top = (text_top + line_top * render_scale - pill_pad_y).max(0.0);
-                let pill_width = ((max_x - min_x) * render_scale + pill_pad_x * 2.0)
-                    .min((width as f32 - pill_left).max(0.0))
-                    .max(1.0);
-                let pill_height = (line_height * render_scale + pill_pad_y * 2.0)
-                    .min((height as f32 - pill_top).max(0.0))
-                    .max(1.0);
-                let pill_radius = (pill_height * 0.4).min(pill_width / 2.0);
-
-                let pill_uniforms = CaptionBackgroundUniforms {
-                    rect: [pill_left, pill_top, pill_width, pill_height],
-                    color: [
-                        highlight_color_rgb[0],
-                        highlight_color_rgb[1],
-                        highlight_color_rgb[2],
-                        fade_opacity,
-                    ],
-                    radius: pill_radius,
-                    _padding: [0.0; 3],
-                    _padding2: [0.0; 4],
-                };
-                queue.write_buffer(
-                    &self.highlight_uniform_buffer,
-                    0,
-                    bytemuck::bytes_of(&pill_uniforms),
-                );
-
-                let pill_scissor_pad = 3.0;
-                let pill_scissor_x = (pill_left - pill_scissor_pad).max(0.0).floor() as u32;
-                let pill_scissor_y = (pill_top - pill_scissor_pad).max(0.0).floor() as u32;
-                let pill_max_width = width.saturating_sub(pill_scissor_x);
-                let pill_max_height = height.saturating_sub(pill_scissor_y);
-
-                if pill_max_width > 0 && pill_max_height > 0 {
-                    let pill_scissor_width = (pill_width + pill_scissor_pad * 2.0)
-                        .ceil()
-                        .max(1.0)
-                        .min(pill_max_width as f32)
-                        as u32;
-                    let pill_scissor_height = (pill_height + pill_scissor_pad * 2.0)
-                        .ceil()
-                        .max(1.0)
-                        .min(pill_max_height as f32)
-                        as u32;
-
-                    self.highlight_scissor = Some([
-                        pill_scissor_x,
-                        pill_scissor_y,
-                        pill_scissor_width,
-                        pill_scissor_height,
-                    ]);
-                    self.has_highlight = true;
-                }
-            }
-        }
-
-        let scissor_padding = 4.0;
-        let scissor_x = (draw_box_left - scissor_padding).max(0.0).floor() as u32;
-        let scissor_y = (draw_box_top - scissor_padding).max(0.0).floor() as u32;
-        let max_width = width.saturating_sub(scissor_x);
-        let max_height = height.saturating_sub(scissor_y);
-
-        if max_width == 0 || max_height == 0 {
-            self.has_caption = false;
-            self.has_highlight = false;
-            self.highlight_scissor = None;
-            return;
-        }
-
-        let scissor_width = (draw_box_width + scissor_padding * 2.0)
-            .ceil()
-            .max(1.0)
-            .min(max_width as f32) as u32;
-        let scissor_height = (draw_box_height + scissor_padding * 2.0)
-            .ceil()
-            .max(1.0)
-            .min(max_height as f32) as u32;
-
-        if scissor_width == 0 || scissor_height == 0 {
-            self.has_caption = false;
-            self.has_highlight = false;
-            self.highlight_scissor = None;
-            return;
+        if let Some((pill, pill_scissor)) = frame.highlight {
+            queue.write_buffer(
+                &self.highlight_uniform_buffer,
+                0,
+                bytemuck::bytes_of(&CaptionBackgroundUniforms::from(pill)),
+            );
+            self.highlight_scissor = Some(pill_scissor);
+            self.has_highlight = true;
         }
 
-        self.background_scissor = Some([scissor_x, scissor_y, scissor_width, scissor_height]);
-        self.has_caption = true;
+        self.background_scissor = frame.scissor;
+        self.has_caption = frame.scissor.is_some();
     }
 
     pub fn has_content(&self) -> bool {
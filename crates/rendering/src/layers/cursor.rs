@@ -7,7 +7,7 @@ use tracing::error;
 use wgpu::{BindGroup, FilterMode, include_wgsl, util::DeviceExt};
 
 use crate::{
-    Coord, DecodedSegmentFrames, FrameSpace, ProjectUniforms, RenderVideoConstants,
+    Coord, DecodedSegmentFrames, FrameSpace, ProjectUniforms, RenderOptions, RenderVideoConstants,
     STANDARD_CURSOR_HEIGHT, composite_frame::ColorGradeUniformParams, zoom::InterpolatedZoom,
 };
 
@@ -207,65 +207,7 @@ impl CursorLayer {
     }
 
     fn create_circle_cursor(constants: &RenderVideoConstants) -> CursorTexture {
-        let size = CIRCLE_CURSOR_SIZE;
-        let mut rgba = vec![0u8; (size * size * 4) as usize];
-        let center = size as f32 / 2.0;
-        let outer_radius = center - size as f32 * 0.08;
-        let dark_ring_width = size as f32 * 0.014;
-        let light_ring_width = size as f32 * 0.016;
-        let shadow_spread = size as f32 * 0.035;
-        let edge_softness = size as f32 * 0.018;
-
-        let inner_edge = outer_radius - dark_ring_width - light_ring_width;
-
-        for y in 0..size {
-            for x in 0..size {
-                let dx = x as f32 - center + 0.5;
-                let dy = y as f32 - center + 0.5;
-                let dist = (dx * dx + dy * dy).sqrt();
-                let idx = ((y * size + x) * 4) as usize;
-
-                if dist > outer_radius + shadow_spread {
-                    continue;
-                }
-
-                let mut color = [0.0_f32; 4];
-
-                if dist > outer_radius {
-                    let shadow_t = ((dist - outer_radius) / shadow_spread).clamp(0.0, 1.0);
-                    let shadow_alpha = 0.16 * (1.0 - shadow_t * shadow_t);
-                    composite_cursor_layer(&mut color, [0.0, 0.0, 0.0, shadow_alpha]);
-                }
-
-                if dist <= outer_radius + edge_softness {
-                    let outer_fade = 1.0
-                        - ((dist - outer_radius) / edge_softness)
-                            .clamp(0.0, 1.0)
-                            .powf(1.2);
-
-                    if dist > outer_radius - dark_ring_width {
-                        let ring_alpha = 0.38 * outer_fade;
-                        composite_cursor_layer(&mut color, [0.0, 0.0, 0.0, ring_alpha]);
-                    } else if dist > inner_edge {
-                        let ring_alpha = 0.42 * outer_fade;
-                        composite_cursor_layer(&mut color, [1.0, 1.0, 1.0, ring_alpha]);
-                    } else {
-                        let fill_alpha = 0.14 * outer_fade;
-                        composite_cursor_layer(&mut color, [1.0, 1.0, 1.0, fill_alpha]);
-                    }
-                }
-
-                if color[3] > 0.0 {
-                    let a = color[3];
-                    rgba[idx] = (color[0] * a * 255.0).round() as u8;
-                    rgba[idx + 1] = (color[1] * a * 255.0).round() as u8;
-                    rgba[idx + 2] = (color[2] * a * 255.0).round() as u8;
-                    rgba[idx + 3] = (a * 255.0).round() as u8;
-                }
-            }
-        }
-
-        CursorTexture::prepare(constants, &rgba, (size, size), XY::new(0.5, 0.5))
+        CursorTexture::prepare(constants, &circle_cursor_image())
     }
 
     fn load_cursor_texture(
@@ -273,46 +215,13 @@ impl CursorLayer {
         cursor_id: &str,
         use_svg: bool,
     ) -> Option<CursorTexture> {
-        let mut loaded_cursor = None;
-
-        let cursor_shape = match &constants.recording_meta.inner {
-            RecordingMetaInner::Studio(studio) => match studio.as_ref() {
-                StudioRecordingMeta::MultipleSegments {
-                    inner:
-                        MultipleSegments {
-                            cursors: Cursors::Correct(cursors),
-                            ..
-                        },
-                } => cursors.get(cursor_id).and_then(|v| v.shape),
-                _ => None,
-            },
-            _ => None,
-        };
-
-        if let Some(cursor_shape) = cursor_shape
-            && use_svg
-            && let Some(info) = cursor_shape.resolve()
-        {
-            loaded_cursor = CursorTexture::prepare_svg(constants, info.raw, info.hotspot.into())
-                .map_err(|err| error!("Error loading SVG cursor {cursor_id:?}: {err}"))
-                .ok();
-        }
-
-        if let StudioRecordingMeta::MultipleSegments { inner, .. } = &constants.meta
-            && loaded_cursor.is_none()
-            && let Some(c) = inner.get_cursor_image(&constants.recording_meta, cursor_id)
-            && let Ok(img) = image::open(&c.path)
-                .map_err(|err| error!("Failed to load cursor image from {:?}: {err}", c.path))
-        {
-            loaded_cursor = Some(CursorTexture::prepare(
-                constants,
-                &img.to_rgba8(),
-                img.dimensions(),
-                c.hotspot,
-            ));
-        }
-
-        loaded_cursor
+        load_cursor_image(
+            &constants.recording_meta,
+            &constants.meta,
+            cursor_id,
+            use_svg,
+        )
+        .map(|image| CursorTexture::prepare(constants, &image))
     }
 
     fn preload_cursor_textures(&mut self, constants: &RenderVideoConstants, use_svg: bool) {
@@ -369,7 +278,10 @@ impl CursorLayer {
 
         let time_s = segment_frames.recording_time;
 
+        // No position means the cursor is hidden for this frame, either by a
+        // cursor edit or because there is no cursor data at all.
         let Some(interpolated_cursor) = &uniforms.interpolated_cursor else {
+            self.bind_group = None;
             return;
         };
 
@@ -384,60 +296,6 @@ impl CursorLayer {
             return;
         }
 
-        let fps = uniforms.frame_rate.max(1) as f32;
-        let screen_size = constants.options.screen_size;
-        let fps_scale = fps / CURSOR_BASELINE_FPS;
-        let cursor_strength = (uniforms.motion_blur_amount * CURSOR_MULTIPLIER * fps_scale)
-            .clamp(0.0, CURSOR_MAX_STRENGTH);
-        let child_motion = uniforms
-            .prev_cursor
-            .as_ref()
-            .filter(|prev| prev.cursor_id == interpolated_cursor.cursor_id)
-            .map(|prev| {
-                let delta_uv = XY::new(
-                    (interpolated_cursor.position.coord.x - prev.position.coord.x) as f32,
-                    (interpolated_cursor.position.coord.y - prev.position.coord.y) as f32,
-                );
-                XY::new(
-                    delta_uv.x * screen_size.x as f32,
-                    delta_uv.y * screen_size.y as f32,
-                )
-            })
-            .unwrap_or_else(|| XY::new(0.0, 0.0));
-
-        let combined_motion_px = if cursor_strength <= f32::EPSILON {
-            XY::new(0.0, 0.0)
-        } else {
-            child_motion
-        };
-
-        // Screen Studio semantics: smear length = per-frame travel x amount,
-        // linear all the way down (a sub-pixel kernel is the identity, so no
-        // response ramp is needed to avoid popping).
-        let scaled_motion = if cursor_strength > f32::EPSILON {
-            cursor_blur_vector(combined_motion_px, cursor_strength)
-        } else {
-            XY::new(0.0, 0.0)
-        };
-
-        let mut cursor_opacity = 1.0f32;
-        if uniforms.project.cursor.hide_when_idle && !cursor.moves.is_empty() {
-            let hide_delay_secs = uniforms
-                .project
-                .cursor
-                .hide_when_idle_delay
-                .max((CURSOR_IDLE_MIN_DELAY_MS / 1000.0) as f32);
-            let hide_delay_ms = (hide_delay_secs as f64 * 1000.0).max(CURSOR_IDLE_MIN_DELAY_MS);
-            cursor_opacity = compute_cursor_idle_opacity(
-                cursor,
-                segment_frames.recording_time as f64 * 1000.0,
-                hide_delay_ms,
-            );
-            if cursor_opacity <= f32::EPSILON {
-                cursor_opacity = 0.0;
-            }
-        }
-
         let cursor_type = uniforms.project.cursor.cursor_type().clone();
 
         if self.prev_cursor_type.as_ref() != Some(&cursor_type) {
@@ -478,188 +336,20 @@ impl CursorLayer {
             tex
         };
 
-        let size = {
-            let click_t = get_click_t(&cursor.clicks, (time_s as f64) * 1000.0);
-            let click_scale_factor = click_t * 1.0 + (1.0 - click_t) * CLICK_SHRINK_SIZE;
-            let crop = ProjectUniforms::get_crop(&constants.options, &uniforms.project);
-            let display_size = ProjectUniforms::display_size(
-                &constants.options,
-                &uniforms.project,
-                resolution_base,
-            );
-            let size = cursor_height_px(
-                constants.options.screen_size.y as f32,
-                crop.size.y as f32,
-                display_size.y as f32,
-                uniforms.cursor_size,
-                click_scale_factor,
-            );
-
-            let texture_size_aspect = {
-                let texture_size = cursor_texture.texture.size();
-                texture_size.width as f32 / texture_size.height as f32
-            };
-
-            Coord::<FrameSpace>::new(if texture_size_aspect > 1.0 {
-                // Wide cursor: base sizing on width to prevent excessive width
-                let width = size;
-                let height = size / texture_size_aspect;
-                XY::new(width, height).into()
-            } else {
-                // Tall or square cursor: base sizing on height (current behavior)
-                XY::new(size * texture_size_aspect, size).into()
-            })
-        };
-
-        let hotspot = Coord::<FrameSpace>::new(size.coord * cursor_texture.hotspot);
-
-        // Calculate position without hotspot first
-        let position = interpolated_cursor.position.to_frame_space(
+        let texture_size = cursor_texture.texture.size();
+        let cursor_uniforms = cursor_uniforms(
+            uniforms,
             &constants.options,
-            &uniforms.project,
-            resolution_base,
-        ) - hotspot;
-
-        // Transform to zoomed space
-        let zoomed_position = position.to_zoomed_frame_space(
-            &constants.options,
-            &uniforms.project,
+            cursor,
+            time_s,
             resolution_base,
             zoom,
+            CursorSprite {
+                size: XY::new(texture_size.width, texture_size.height),
+                hotspot: cursor_texture.hotspot,
+            },
         );
 
-        let zoomed_size = (position + size).to_zoomed_frame_space(
-            &constants.options,
-            &uniforms.project,
-            resolution_base,
-            zoom,
-        ) - zoomed_position;
-
-        // In split-screen the screen only occupies a half-rect, so remap the
-        // cursor from its raw source UV into that pane (matching the display
-        // layer's split crop -> half-rect mapping) and scale the sprite by the
-        // same factor. The cursor shader's screen_bounds clip already follows
-        // uniforms.display.target_bounds (the morphing half), so a cursor that
-        // lands outside the visible crop is confined automatically.
-        let position_size = match &uniforms.split {
-            Some(split) if split.factor > 0.001 => {
-                let screen_size = constants.options.screen_size;
-                let crop = ProjectUniforms::get_crop(&constants.options, &uniforms.project);
-                let display_size = ProjectUniforms::display_size(
-                    &constants.options,
-                    &uniforms.project,
-                    resolution_base,
-                );
-
-                let scrop = split.screen.crop;
-                let starget = split.screen.target;
-                let crop_w = (scrop[2] - scrop[0]).max(f32::EPSILON);
-                let crop_h = (scrop[3] - scrop[1]).max(f32::EPSILON);
-                let target_w = starget[2] - starget[0];
-                let target_h = starget[3] - starget[1];
-
-                let cursor_px = [
-                    cursor_uv.x as f32 * screen_size.x as f32,
-                    cursor_uv.y as f32 * screen_size.y as f32,
-                ];
-                let tip = [
-                    starget[0] + (cursor_px[0] - scrop[0]) / crop_w * target_w,
-                    starget[1] + (cursor_px[1] - scrop[1]) / crop_h * target_h,
-                ];
-
-                // Source->pane scale (uniform; the split crop matches the pane
-                // aspect) relative to the normal source->frame scale.
-                let normal_scale = display_size.x as f32 / (crop.size.x as f32).max(f32::EPSILON);
-                let size_factor = (target_w / crop_w) / normal_scale.max(f32::EPSILON);
-
-                let split_pos = [
-                    tip[0] - hotspot.x as f32 * size_factor,
-                    tip[1] - hotspot.y as f32 * size_factor,
-                ];
-                let split_size = [size.x as f32 * size_factor, size.y as f32 * size_factor];
-
-                let t = split.factor as f32;
-                [
-                    crate::lerp_f32(zoomed_position.x as f32, split_pos[0], t),
-                    crate::lerp_f32(zoomed_position.y as f32, split_pos[1], t),
-                    crate::lerp_f32(zoomed_size.x as f32, split_size[0], t),
-                    crate::lerp_f32(zoomed_size.y as f32, split_size[1], t),
-                ]
-            }
-            _ => [
-                zoomed_position.x as f32,
-                zoomed_position.y as f32,
-                zoomed_size.x as f32,
-                zoomed_size.y as f32,
-            ],
-        };
-
-        // A text takeover relocates the display card by a uniform scale +
-        // translate (the takeover target preserves the card's aspect), so the
-        // cursor follows with the same affine map — and fades with the card
-        // when a Fullscreen takeover hides it.
-        let (position_size, cursor_opacity) = match &uniforms.takeover {
-            Some(takeover) if takeover.t > 0.001 => {
-                let from_w = (takeover.from[2] - takeover.from[0]).max(f32::EPSILON);
-                let scale = (takeover.to[2] - takeover.to[0]) / from_w;
-                let mapped = [
-                    takeover.to[0] + (position_size[0] - takeover.from[0]) * scale,
-                    takeover.to[1] + (position_size[1] - takeover.from[1]) * scale,
-                    position_size[2] * scale,
-                    position_size[3] * scale,
-                ];
-                let t = takeover.t;
-                (
-                    [
-                        crate::lerp_f32(position_size[0], mapped[0], t),
-                        crate::lerp_f32(position_size[1], mapped[1], t),
-                        crate::lerp_f32(position_size[2], mapped[2], t),
-                        crate::lerp_f32(position_size[3], mapped[3], t),
-                    ],
-                    cursor_opacity * takeover.overlay_fade,
-                )
-            }
-            _ => (position_size, cursor_opacity),
-        };
-
-        let cursor_grade = if uniforms.project.color_correction.grade_cursor {
-            uniforms.screen_color_grade
-        } else {
-            ColorGradeUniformParams::IDENTITY
-        };
-
-        let cursor_uniforms = CursorUniforms {
-            position_size,
-            output_size: [
-                uniforms.output_size.0 as f32,
-                uniforms.output_size.1 as f32,
-                0.0,
-                0.0,
-            ],
-            screen_bounds: uniforms.display.target_bounds,
-            motion_vector_strength: [
-                scaled_motion.x,
-                scaled_motion.y,
-                // Pure on/off gate: the amount is baked into the smear
-                // length (scaled_motion), never an opacity mix.
-                if cursor_strength > f32::EPSILON {
-                    1.0
-                } else {
-                    0.0
-                },
-                cursor_opacity,
-            ],
-            rotation_params: [
-                0.0,
-                uniforms.project.cursor.base_rotation,
-                uniforms.cursor_x_axis_tilt_radians,
-                0.0,
-            ],
-            color_adjust_a: cursor_grade.color_adjust_a,
-            color_adjust_b: cursor_grade.color_adjust_b,
-            grain_params: cursor_grade.grain_params,
-        };
-
         constants.queue.write_buffer(
             &self.statics.uniform_buffer,
             0,
@@ -681,6 +371,381 @@ impl CursorLayer {
     }
 }
 
+/// Size and hotspot of the cursor image being drawn.
+#[derive(Clone, Copy)]
+pub(crate) struct CursorSprite {
+    pub size: XY<u32>,
+    pub hotspot: XY<f64>,
+}
+
+/// Cursor shader uniforms for the current frame. Callers must have checked
+/// that `uniforms.interpolated_cursor` is present and finite.
+pub(crate) fn cursor_uniforms(
+    uniforms: &ProjectUniforms,
+    options: &RenderOptions,
+    cursor: &CursorEvents,
+    recording_time: f32,
+    resolution_base: XY<u32>,
+    zoom: &InterpolatedZoom,
+    sprite: CursorSprite,
+) -> CursorUniforms {
+    let Some(interpolated_cursor) = &uniforms.interpolated_cursor else {
+        return CursorUniforms::default();
+    };
+    let cursor_uv = &interpolated_cursor.position.coord;
+
+    let fps = uniforms.frame_rate.max(1) as f32;
+    let screen_size = options.screen_size;
+    let fps_scale = fps / CURSOR_BASELINE_FPS;
+    let cursor_strength = (uniforms.motion_blur_amount * CURSOR_MULTIPLIER * fps_scale)
+        .clamp(0.0, CURSOR_MAX_STRENGTH);
+    let child_motion = uniforms
+        .prev_cursor
+        .as_ref()
+        .filter(|prev| prev.cursor_id == interpolated_cursor.cursor_id)
+        .map(|prev| {
+            let delta_uv = XY::new(
+                (interpolated_cursor.position.coord.x - prev.position.coord.x) as f32,
+                (interpolated_cursor.position.coord.y - prev.position.coord.y) as f32,
+            );
+            XY::new(
+                delta_uv.x * screen_size.x as f32,
+                delta_uv.y * screen_size.y as f32,
+            )
+        })
+        .unwrap_or_else(|| XY::new(0.0, 0.0));
+
+    let combined_motion_px = if cursor_strength <= f32::EPSILON {
+        XY::new(0.0, 0.0)
+    } else {
+        child_motion
+    };
+
+    // Screen Studio semantics: smear length = per-frame travel x amount,
+    // linear all the way down (a sub-pixel kernel is the identity, so no
+    // response ramp is needed to avoid popping).
+    let scaled_motion = if cursor_strength > f32::EPSILON {
+        cursor_blur_vector(combined_motion_px, cursor_strength)
+    } else {
+        XY::new(0.0, 0.0)
+    };
+
+    let mut cursor_opacity = 1.0f32;
+    if uniforms.project.cursor.hide_when_idle && !cursor.moves.is_empty() {
+        let hide_delay_secs = uniforms
+            .project
+            .cursor
+            .hide_when_idle_delay
+            .max((CURSOR_IDLE_MIN_DELAY_MS / 1000.0) as f32);
+        let hide_delay_ms = (hide_delay_secs as f64 * 1000.0).max(CURSOR_IDLE_MIN_DELAY_MS);
+        cursor_opacity =
+            compute_cursor_idle_opacity(cursor, recording_time as f64 * 1000.0, hide_delay_ms);
+        if cursor_opacity <= f32::EPSILON {
+            cursor_opacity = 0.0;
+        }
+    }
+
+    let size = {
+        let click_t = get_click_t(&cursor.clicks, (recording_time as f64) * 1000.0);
+        let click_scale_factor = click_t * 1.0 + (1.0 - click_t) * CLICK_SHRINK_SIZE;
+        let crop = ProjectUniforms::get_crop(options, &uniforms.project);
+        let display_size =
+            ProjectUniforms::display_size(options, &uniforms.project, resolution_base);
+        let size = cursor_height_px(
+            options.screen_size.y as f32,
+            crop.size.y as f32,
+            display_size.y as f32,
+            uniforms.cursor_size,
+            click_scale_factor,
+        );
+
+        let texture_size_aspect = sprite.size.x as f32 / sprite.size.y as f32;
+
+        Coord::<FrameSpace>::new(if texture_size_aspect > 1.0 {
+            // Wide cursor: base sizing on width to prevent excessive width
+            let width = size;
+            let height = size / texture_size_aspect;
+            XY::new(width, height).into()
+        } else {
+            // Tall or square cursor: base sizing on height (current behavior)
+            XY::new(size * texture_size_aspect, size).into()
+        })
+    };
+
+    let hotspot = Coord::<FrameSpace>::new(size.coord * sprite.hotspot);
+
+    // Calculate position without hotspot first
+    let position =
+        interpolated_cursor
+            .position
+            .to_frame_space(options, &uniforms.project, resolution_base)
+            - hotspot;
+
+    // Transform to zoomed space
+    let zoomed_position =
+        position.to_zoomed_frame_space(options, &uniforms.project, resolution_base, zoom);
+
+    let zoomed_size =
+        (position + size).to_zoomed_frame_space(options, &uniforms.project, resolution_base, zoom)
+            - zoomed_position;
+
+    // In split-screen the screen only occupies a half-rect, so remap the
+    // cursor from its raw source UV into that pane (matching the display
+    // layer's split crop -> half-rect mapping) and scale the sprite by the
+    // same factor. The cursor shader's screen_bounds clip already follows
+    // uniforms.display.target_bounds (the morphing half), so a cursor that
+    // lands outside the visible crop is confined automatically.
+    let position_size = match &uniforms.split {
+        Some(split) if split.factor > 0.001 => {
+            let screen_size = options.screen_size;
+            let crop = ProjectUniforms::get_crop(options, &uniforms.project);
+            let display_size =
+                ProjectUniforms::display_size(options, &uniforms.project, resolution_base);
+
+            let scrop = split.screen.crop;
+            let starget = split.screen.target;
+            let crop_w = (scrop[2] - scrop[0]).max(f32::EPSILON);
+            let crop_h = (scrop[3] - scrop[1]).max(f32::EPSILON);
+            let target_w = starget[2] - starget[0];
+            let target_h = starget[3] - starget[1];
+
+            let cursor_px = [
+                cursor_uv.x as f32 * screen_size.x as f32,
+                cursor_uv.y as f32 * screen_size.y as f32,
+            ];
+            let tip = [
+                starget[0] + (cursor_px[0] - scrop[0]) / crop_w * target_w,
+                starget[1] + (cursor_px[1] - scrop[1]) / crop_h * target_h,
+            ];
+
+            // Source->pane scale (uniform; the split crop matches the pane
+            // aspect) relative to the normal source->frame scale.
+            let normal_scale = display_size.x as f32 / (crop.size.x as f32).max(f32::EPSILON);
+            let size_factor = (target_w / crop_w) / normal_scale.max(f32::EPSILON);
+
+            let split_pos = [
+                tip[0] - hotspot.x as f32 * size_factor,
+                tip[1] - hotspot.y as f32 * size_factor,
+            ];
+            let split_size = [size.x as f32 * size_factor, size.y as f32 * size_factor];
+
+            let t = split.factor as f32;
+            [
+                crate::lerp_f32(zoomed_position.x as f32, split_pos[0], t),
+                crate::lerp_f32(zoomed_position.y as f32, split_pos[1], t),
+                crate::lerp_f32(zoomed_size.x as f32, split_size[0], t),
+                crate::lerp_f32(zoomed_size.y as f32, split_size[1], t),
+            ]
+        }
+        _ => [
+            zoomed_position.x as f32,
+            zoomed_position.y as f32,
+            zoomed_size.x as f32,
+            zoomed_size.y as f32,
+        ],
+    };
+
+    // A text takeover relocates the display card by a uniform scale +
+    // translate (the takeover target preserves the card's aspect), so the
+    // cursor follows with the same affine map — and fades with the card
+    // when a Fullscreen takeover hides it.
+    let (position_size, cursor_opacity) = match &uniforms.takeover {
+        Some(takeover) if takeover.t > 0.001 => {
+            let from_w = (takeover.from[2] - takeover.from[0]).max(f32::EPSILON);
+            let scale = (takeover.to[2] - takeover.to[0]) / from_w;
+            let mapped = [
+                takeover.to[0] + (position_size[0] - takeover.from[0]) * scale,
+                takeover.to[1] + (position_size[1] - takeover.from[1]) * scale,
+                position_size[2] * scale,
+                position_size[3] * scale,
+            ];
+            let t = takeover.t;
+            (
+                [
+                    crate::lerp_f32(position_size[0], mapped[0], t),
+                    crate::lerp_f32(position_size[1], mapped[1], t),
+                    crate::lerp_f32(position_size[2], mapped[2], t),
+                    crate::lerp_f32(position_size[3], mapped[3], t),
+                ],
+                cursor_opacity * takeover.overlay_fade,
+            )
+        }
+        _ => (position_size, cursor_opacity),
+    };
+
+    let cursor_grade = if uniforms.project.color_correction.grade_cursor {
+        uniforms.screen_color_grade
+    } else {
+        ColorGradeUniformParams::IDENTITY
+    };
+
+    CursorUniforms {
+        position_size,
+        output_size: [
+            uniforms.output_size.0 as f32,
+            uniforms.output_size.1 as f32,
+            0.0,
+            0.0,
+        ],
+        screen_bounds: uniforms.display.target_bounds,
+        motion_vector_strength: [
+            scaled_motion.x,
+            scaled_motion.y,
+            // Pure on/off gate: the amount is baked into the smear
+            // length (scaled_motion), never an opacity mix.
+            if cursor_strength > f32::EPSILON {
+                1.0
+            } else {
+                0.0
+            },
+            cursor_opacity,
+        ],
+        rotation_params: [
+            0.0,
+            uniforms.project.cursor.base_rotation,
+            uniforms.cursor_x_axis_tilt_radians,
+            0.0,
+        ],
+        color_adjust_a: cursor_grade.color_adjust_a,
+        color_adjust_b: cursor_grade.color_adjust_b,
+        grain_params: cursor_grade.grain_params,
+    }
+}
+
+/// The translucent ring drawn for `CursorType::Circle`, premultiplied.
+pub(crate) fn circle_cursor_image() -> CursorImage {
+    let size = CIRCLE_CURSOR_SIZE;
+    let mut rgba = vec![0u8; (size * size * 4) as usize];
+    let center = size as f32 / 2.0;
+    let outer_radius = center - size as f32 * 0.08;
+    let dark_ring_width = size as f32 * 0.014;
+    let light_ring_width = size as f32 * 0.016;
+    let shadow_spread = size as f32 * 0.035;
+    let edge_softness = size as f32 * 0.018;
+
+    let inner_edge = outer_radius - dark_ring_width - light_ring_width;
+
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 - center + 0.5;
+            let dy = y as f32 - center + 0.5;
+            let dist = (dx * dx + dy * dy).sqrt();
+            let idx = ((y * size + x) * 4) as usize;
+
+            if dist > outer_radius + shadow_spread {
+                continue;
+            }
+
+            let mut color = [0.0_f32; 4];
+
+            if dist > outer_radius {
+                let shadow_t = ((dist - outer_radius) / shadow_spread).clamp(0.0, 1.0);
+                let shadow_alpha = 0.16 * (1.0 - shadow_t * shadow_t);
+                composite_cursor_layer(&mut color, [0.0, 0.0, 0.0, shadow_alpha]);
+            }
+
+            if dist <= outer_radius + edge_softness {
+                let outer_fade = 1.0
+                    - ((dist - outer_radius) / edge_softness)
+                        .clamp(0.0, 1.0)
+                        .powf(1.2);
+
+                if dist > outer_radius - dark_ring_width {
+                    let ring_alpha = 0.38 * outer_fade;
+                    composite_cursor_layer(&mut color, [0.0, 0.0, 0.0, ring_alpha]);
+                } else if dist > inner_edge {
+                    let ring_alpha = 0.42 * outer_fade;
+                    composite_cursor_layer(&mut color, [1.0, 1.0, 1.0, ring_alpha]);
+                } else {
+                    let fill_alpha = 0.14 * outer_fade;
+                    composite_cursor_layer(&mut color, [1.0, 1.0, 1.0, fill_alpha]);
+                }
+            }
+
+            if color[3] > 0.0 {
+                let a = color[3];
+                rgba[idx] = (color[0] * a * 255.0).round() as u8;
+                rgba[idx + 1] = (color[1] * a * 255.0).round() as u8;
+                rgba[idx + 2] = (color[2] * a * 255.0).round() as u8;
+                rgba[idx + 3] = (a * 255.0).round() as u8;
+            }
+        }
+    }
+
+    CursorImage {
+        rgba,
+        width: size,
+        height: size,
+        hotspot: XY::new(0.5, 0.5),
+    }
+}
+
+/// Loads a recorded cursor's image, preferring the bundled SVG for known
+/// shapes when `use_svg` is set. Ids of custom images from cursor edits load
+/// the file from the project directory instead.
+pub(crate) fn load_cursor_image(
+    recording_meta: &RecordingMeta,
+    meta: &StudioRecordingMeta,
+    cursor_id: &str,
+    use_svg: bool,
+) -> Option<CursorImage> {
+    if let Some((path, hotspot)) = CursorEditImage::parse_custom_id(cursor_id) {
+        let path = recording_meta.project_path.join(path);
+        let img = image::open(&path)
+            .map_err(|err| error!("Failed to load custom cursor image from {path:?}: {err}"))
+            .ok()?;
+        let (width, height) = img.dimensions();
+        return Some(CursorImage {
+            rgba: img.to_rgba8().into_raw(),
+            width,
+            height,
+            hotspot,
+        });
+    }
+
+    let mut loaded_cursor = None;
+
+    let cursor_shape = match &recording_meta.inner {
+        RecordingMetaInner::Studio(studio) => match studio.as_ref() {
+            StudioRecordingMeta::MultipleSegments {
+                inner:
+                    MultipleSegments {
+                        cursors: Cursors::Correct(cursors),
+                        ..
+                    },
+            } => cursors.get(cursor_id).and_then(|v| v.shape),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(cursor_shape) = cursor_shape
+        && use_svg
+        && let Some(info) = cursor_shape.resolve()
+    {
+        loaded_cursor = rasterize_svg_cursor(info.raw, info.hotspot.into())
+            .map_err(|err| error!("Error loading SVG cursor {cursor_id:?}: {err}"))
+            .ok();
+    }
+
+    if let StudioRecordingMeta::MultipleSegments { inner, .. } = meta
+        && loaded_cursor.is_none()
+        && let Some(c) = inner.get_cursor_image(recording_meta, cursor_id)
+        && let Ok(img) = image::open(&c.path)
+            .map_err(|err| error!("Failed to load cursor image from {:?}: {err}", c.path))
+    {
+        let (width, height) = img.dimensions();
+        loaded_cursor = Some(CursorImage {
+            rgba: img.to_rgba8().into_raw(),
+            width,
+            height,
+            hotspot: c.hotspot,
+        });
+    }
+
+    loaded_cursor
+}
+
 fn composite_cursor_layer(dst: &mut [f32; 4], src: [f32; 4]) {
     let src_a = src[3];
     if src_a <= 0.0 {
@@ -746,16 +811,16 @@ fn clamp_cursor_vector(vec: XY<f32>) -> XY<f32> {
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable, Default)]
 pub struct CursorUniforms {
-    position_size: [f32; 4],
-    output_size: [f32; 4],
-    screen_bounds: [f32; 4],
-    motion_vector_strength: [f32; 4],
-    rotation_params: [f32; 4],
+    pub(crate) position_size: [f32; 4],
+    pub(crate) output_size: [f32; 4],
+    pub(crate) screen_bounds: [f32; 4],
+    pub(crate) motion_vector_strength: [f32; 4],
+    pub(crate) rotation_params: [f32; 4],
     /// Screen grade (see `ColorGradeUniformParams`); identity when the
     /// cursor opts out.
-    color_adjust_a: [f32; 4],
-    color_adjust_b: [f32; 4],
-    grain_params: [f32; 4],
+    pub(crate) color_adjust_a: [f32; 4],
+    pub(crate) color_adjust_b: [f32; 4],
+    pub(crate) grain_params: [f32; 4],
 }
 
 fn compute_cursor_idle_opacity(
@@ -880,6 +945,14 @@ fn get_click_t(clicks: &[CursorClickEvent], time_ms: f64) -> f32 {
     1.0
 }
 
+/// Tightly packed RGBA8 cursor pixels with the hotspot in image UV.
+pub(crate) struct CursorImage {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub hotspot: XY<f64>,
+}
+
 struct CursorTexture {
     texture: wgpu::Texture,
     hotspot: XY<f64>,
@@ -887,12 +960,8 @@ struct CursorTexture {
 
 impl CursorTexture {
     /// Prepare a cursor texture on the GPU from RGBA data.
-    fn prepare(
-        constants: &RenderVideoConstants,
-        rgba: &[u8],
-        dimensions: (u32, u32),
-        hotspot: XY<f64>,
-    ) -> Self {
+    fn prepare(constants: &RenderVideoConstants, image: &CursorImage) -> Self {
+        let dimensions = (image.width, image.height);
         let texture = constants.device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Cursor Texture"),
             size: wgpu::Extent3d {
@@ -915,7 +984,7 @@ impl CursorTexture {
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
-            rgba,
+            &image.rgba,
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * dimensions.0),
@@ -928,50 +997,49 @@ impl CursorTexture {
             },
         );
 
-        Self { texture, hotspot }
+        Self {
+            texture,
+            hotspot: image.hotspot,
+        }
     }
+}
 
-    /// Prepare a cursor texture on the GPU from a raw SVG file
-    fn prepare_svg(
-        constants: &RenderVideoConstants,
-        svg_data: &str,
-        hotspot: XY<f64>,
-    ) -> Result<Self, String> {
-        let rtree = resvg::usvg::Tree::from_str(svg_data, &resvg::usvg::Options::default())
-            .map_err(|e| format!("Failed to parse SVG: {e}"))?;
-
-        // Although we could probably determine the size that the cursor is going to be render,
-        // that would depend on the cursor size the user selects.
-        //
-        // This would require reinitializing the texture every time that changes which would be more complicated.
-        // So we trade a small about VRAM for only initializing it once.
-        let aspect_ratio = rtree.size().width() / rtree.size().height();
-        let width = (aspect_ratio * SVG_CURSOR_RASTERIZED_HEIGHT as f32) as u32;
-
-        let mut pixmap = tiny_skia::Pixmap::new(width, SVG_CURSOR_RASTERIZED_HEIGHT)
-            .ok_or("Failed to create pixmap")?;
-
-        // Calculate scale to fit the SVG into the target size while maintaining aspect ratio
-        let scale_x = width as f32 / rtree.size().width();
-        let scale_y = SVG_CURSOR_RASTERIZED_HEIGHT as f32 / rtree.size().height();
-        let scale = scale_x.min(scale_y);
-        let transform = tiny_skia::Transform::from_scale(scale, scale);
-
-        resvg::render(&rtree, transform, &mut pixmap.as_mut());
-
-        let rgba: Vec<u8> = pixmap
-            .pixels()
-            .iter()
-            .flat_map(|p| [p.red(), p.green(), p.blue(), p.alpha()])
-            .collect();
-
-        Ok(Self::prepare(
-            constants,
-            &rgba,
-            (pixmap.width(), pixmap.height()),
-            hotspot,
-        ))
-    }
+/// Rasterizes a cursor SVG at a fixed height.
+fn rasterize_svg_cursor(svg_data: &str, hotspot: XY<f64>) -> Result<CursorImage, String> {
+    let rtree = resvg::usvg::Tree::from_str(svg_data, &resvg::usvg::Options::default())
+        .map_err(|e| format!("Failed to parse SVG: {e}"))?;
+
+    // Although we could probably determine the size that the cursor is going to be render,
+    // that would depend on the cursor size the user selects.
+    //
+    // This would require reinitializing the texture every time that changes which would be more complicated.
+    // So we trade a small about VRAM for only initializing it once.
+    let aspect_ratio = rtree.size().width() / rtree.size().height();
+    let width = (aspect_ratio * SVG_CURSOR_RASTERIZED_HEIGHT as f32) as u32;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, SVG_CURSOR_RASTERIZED_HEIGHT)
+        .ok_or("Failed to create pixmap")?;
+
+    // Calculate scale to fit the SVG into the target size while maintaining aspect ratio
+    let scale_x = width as f32 / rtree.size().width();
+    let scale_y = SVG_CURSOR_RASTERIZED_HEIGHT as f32 / rtree.size().height();
+    let scale = scale_x.min(scale_y);
+    let transform = tiny_skia::Transform::from_scale(scale, scale);
+
+    resvg::render(&rtree, transform, &mut pixmap.as_mut());
+
+    let rgba: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| [p.red(), p.green(), p.blue(), p.alpha()])
+        .collect();
+
+    Ok(CursorImage {
+        rgba,
+        width: pixmap.width(),
+        height: pixmap.height(),
+        hotspot,
+    })
 }
 
 #[cfg(test)]
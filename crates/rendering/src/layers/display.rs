@@ -14,7 +14,7 @@ struct PendingTextureCopy {
     dst_texture_index: usize,
 }
 
-fn uniforms_for_source_frame(
+pub(crate) fn uniforms_for_source_frame(
     mut uniforms: CompositeVideoFrameUniforms,
     base_size: XY<u32>,
     source_size: XY<u32>,
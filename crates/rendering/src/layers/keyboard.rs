@@ -2,12 +2,13 @@ use bytemuck::{Pod, Zeroable};
 use cap_project::XY;
 use glyphon::{
     Attrs, Buffer, Cache, Color, Family, FontSystem, Metrics, Resolution, Shaping, SwashCache,
-    TextArea, TextAtlas, TextBounds, TextRenderer, Viewport, Weight,
+    TextAtlas, TextBounds, TextRenderer, Viewport, Weight,
 };
 use log::warn;
 use wgpu::{Device, Queue, include_wgsl, util::DeviceExt};
 
-use super::captions::{CaptionOverlayLayout, CaptionPosition};
+use super::TextPlacement;
+use super::captions::{CaptionBox, CaptionOverlayLayout, CaptionPosition};
 use crate::{DecodedSegmentFrames, ProjectUniforms, RenderVideoConstants, parse_color_component};
 
 #[repr(C)]
@@ -20,6 +21,18 @@ struct KeyboardBackgroundUniforms {
     _padding2: [f32; 4],
 }
 
+impl From<CaptionBox> for KeyboardBackgroundUniforms {
+    fn from(value: CaptionBox) -> Self {
+        Self {
+            rect: value.rect,
+            color: value.color,
+            radius: value.radius,
+            _padding: [0.0; 3],
+            _padding2: [0.0; 4],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum KeyboardPosition {
     AboveCaptions,
@@ -149,6 +162,264 @@ fn resolve_background_top(
     adjusted_top.clamp(0.0, max_top)
 }
 
+/// One frame's key-press overlay, laid out but not yet drawn.
+pub(crate) struct KeyboardFrame {
+    pub buffer: Buffer,
+    pub placement: TextPlacement,
+    pub background: CaptionBox,
+    /// Scissor around the box; `None` when it falls off-frame and nothing
+    /// should be drawn.
+    pub scissor: Option<[u32; 4]>,
+}
+
+/// Lays out the key presses visible at the current frame, if any, keeping
+/// clear of the caption when they share a lane.
+pub(crate) fn layout_keyboard(
+    font_system: &mut FontSystem,
+    uniforms: &ProjectUniforms,
+    output_size: XY<u32>,
+    caption_layout: Option<CaptionOverlayLayout>,
+) -> Option<KeyboardFrame> {
+    let Some(keyboard_data) = &uniforms.project.keyboard else {
+        return None;
+    };
+
+    if !keyboard_data.settings.enabled {
+        return None;
+    }
+
+    let timeline = match &uniforms.project.timeline {
+        Some(t) => t,
+        None => return None,
+    };
+
+    if timeline.keyboard_segments.is_empty() {
+        return None;
+    }
+
+    let current_time = uniforms.frame_number as f64 / uniforms.frame_rate as f64;
+    let settings = &keyboard_data.settings;
+
+    let active_segment = find_active_keyboard_segment(
+        current_time,
+        &timeline.keyboard_segments,
+        settings.fade_duration,
+    );
+
+    let Some(active) = active_segment else {
+        return None;
+    };
+
+    let segment_fade = active
+        .segment
+        .fade_duration_override
+        .unwrap_or(settings.fade_duration) as f64;
+
+    let raw_text = build_visible_text(active.segment, current_time);
+
+    if raw_text.is_empty() {
+        return None;
+    }
+
+    let should_uppercase = active
+        .segment
+        .uppercase_override
+        .unwrap_or(settings.uppercase);
+    let visible_text = if should_uppercase {
+        raw_text.to_uppercase()
+    } else {
+        raw_text
+    };
+
+    let fade_opacity = calculate_fade(
+        current_time,
+        active.segment.start,
+        active.segment.end,
+        segment_fade,
+    ) * uniforms.takeover_overlay_fade();
+
+    if fade_opacity <= 0.0 {
+        return None;
+    }
+
+    let bounce_offset = calculate_keyboard_bounce(
+        current_time,
+        active.segment.start,
+        active.segment.end,
+        segment_fade,
+    );
+
+    let (width, height) = (output_size.x, output_size.y);
+
+    let position = active
+        .segment
+        .position_override
+        .as_deref()
+        .map(KeyboardPosition::from_str)
+        .unwrap_or_else(|| KeyboardPosition::from_str(&settings.position));
+
+    let margin = width as f32 * 0.05;
+
+    let color_hex = active
+        .segment
+        .color_override
+        .as_deref()
+        .unwrap_or(&settings.color);
+    let text_color = [
+        parse_color_component(color_hex, 0),
+        parse_color_component(color_hex, 1),
+        parse_color_component(color_hex, 2),
+    ];
+
+    let bg_color_hex = active
+        .segment
+        .background_color_override
+        .as_deref()
+        .unwrap_or(&settings.background_color);
+    let background_color_rgb = [
+        parse_color_component(bg_color_hex, 0),
+        parse_color_component(bg_color_hex, 1),
+        parse_color_component(bg_color_hex, 2),
+    ];
+
+    let background_alpha =
+        ((settings.background_opacity as f32 / 100.0) * fade_opacity).clamp(0.0, 1.0);
+
+    let font_size_base = active.segment.font_size_override.unwrap_or(settings.size) as f32;
+    let font_size = font_size_base * (height as f32 / 1080.0);
+    let metrics = Metrics::new(font_size, font_size * 1.2);
+
+    let mut updated_buffer = Buffer::new(font_system, metrics);
+    let wrap_width = (width as f32 - margin * 2.0).max(font_size);
+    updated_buffer.set_size(font_system, Some(wrap_width), None);
+
+    let font_family = match settings.font.as_str() {
+        "System Serif" => Family::Serif,
+        "System Monospace" => Family::Monospace,
+        _ => Family::SansSerif,
+    };
+
+    let weight = if settings.font_weight >= 700 {
+        Weight::BOLD
+    } else if settings.font_weight >= 500 {
+        Weight::MEDIUM
+    } else {
+        Weight::NORMAL
+    };
+
+    let text_alpha = fade_opacity.clamp(0.0, 1.0);
+    let color = Color::rgba(
+        (text_color[0] * 255.0) as u8,
+        (text_color[1] * 255.0) as u8,
+        (text_color[2] * 255.0) as u8,
+        (text_alpha * 255.0) as u8,
+    );
+
+    let attrs = Attrs::new().family(font_family).weight(weight).color(color);
+    updated_buffer.set_text(font_system, &visible_text, &attrs, Shaping::Advanced);
+
+    let mut layout_width: f32 = 0.0;
+    let mut layout_height: f32 = 0.0;
+    for run in glyphon::cosmic_text::LayoutRunIter::new(&updated_buffer) {
+        layout_width = layout_width.max(run.line_w);
+        layout_height = layout_height.max(run.line_top + run.line_height);
+    }
+
+    if layout_height == 0.0 {
+        layout_height = font_size * 1.2;
+        layout_width = layout_width.max(font_size);
+    }
+
+    let available_width = (width as f32 - margin * 2.0).max(1.0);
+    let padding = font_size * 0.45;
+    let corner_radius = font_size * 0.5;
+    let text_width = layout_width.min(available_width);
+    let text_height = layout_height;
+    let box_width = (text_width + padding * 2.0).min(available_width).max(1.0);
+    let box_height = (text_height + padding * 2.0).min(height as f32).max(1.0);
+
+    let resolved_position = resolve_keyboard_position(position, caption_layout);
+
+    let background_left = match resolved_position {
+        KeyboardPosition::TopLeft | KeyboardPosition::BottomLeft => margin,
+        KeyboardPosition::TopRight | KeyboardPosition::BottomRight => {
+            (width as f32 - margin - box_width).max(0.0)
+        }
+        _ => ((width as f32 - box_width) / 2.0).max(0.0),
+    };
+
+    let center_y = height as f32 * resolved_position.y_factor();
+    let max_background_top = (height as f32 - box_height).max(0.0);
+    let base_background_top = (center_y - box_height / 2.0).clamp(0.0, max_background_top);
+    let background_top = resolve_background_top(
+        (base_background_top + bounce_offset as f32).clamp(0.0, max_background_top),
+        box_height,
+        height as f32,
+        position,
+        caption_layout,
+    );
+
+    let text_left = background_left + padding;
+    let text_top = background_top + padding;
+
+    let bounds = TextBounds {
+        left: (text_left - 2.0).floor() as i32,
+        top: (text_top - 2.0).floor() as i32,
+        right: (text_left + text_width + 2.0).ceil() as i32,
+        bottom: (text_top + text_height + 2.0).ceil() as i32,
+    };
+
+    let background = CaptionBox {
+        rect: [
+            background_left.max(0.0),
+            background_top.max(0.0),
+            box_width,
+            box_height,
+        ],
+        color: [
+            background_color_rgb[0],
+            background_color_rgb[1],
+            background_color_rgb[2],
+            background_alpha,
+        ],
+        radius: corner_radius.min(box_width / 2.0).min(box_height / 2.0),
+    };
+
+    let scissor_padding = 4.0;
+    let scissor_x = (background_left - scissor_padding).max(0.0).floor() as u32;
+    let scissor_y = (background_top - scissor_padding).max(0.0).floor() as u32;
+    let max_width = width.saturating_sub(scissor_x);
+    let max_height = height.saturating_sub(scissor_y);
+
+    let scissor = (max_width > 0 && max_height > 0)
+        .then(|| {
+            let scissor_width = (box_width + scissor_padding * 2.0)
+                .ceil()
+                .max(1.0)
+                .min(max_width as f32) as u32;
+            let scissor_height = (box_height + scissor_padding * 2.0)
+                .ceil()
+                .max(1.0)
+                .min(max_height as f32) as u32;
+            [scissor_x, scissor_y, scissor_width, scissor_height]
+        })
+        .filter(|scissor| scissor[2] > 0 && scissor[3] > 0);
+
+    Some(KeyboardFrame {
+        buffer: updated_buffer,
+        placement: TextPlacement {
+            buffer: 0,
+            left: text_left,
+            top: text_top,
+            scale: 1.0,
+            bounds,
+            default_color: color,
+        },
+        background,
+        scissor,
+    })
+}
+
 pub struct KeyboardLayer {
     font_system: FontSystem,
     swash_cache: SwashCache,
@@ -287,217 +558,30 @@ impl KeyboardLayer {
         self.background_scissor = None;
         self.output_size = (output_size.x, output_size.y);
 
-        let Some(keyboard_data) = &uniforms.project.keyboard else {
-            return;
-        };
-
-        if !keyboard_data.settings.enabled {
-            return;
-        }
-
-        let timeline = match &uniforms.project.timeline {
-            Some(t) => t,
-            None => return,
-        };
-
-        if timeline.keyboard_segments.is_empty() {
-            return;
-        }
-
-        let current_time = uniforms.frame_number as f64 / uniforms.frame_rate as f64;
-        let settings = &keyboard_data.settings;
-
-        let active_segment = find_active_keyboard_segment(
-            current_time,
-            &timeline.keyboard_segments,
-            settings.fade_duration,
-        );
-
-        let Some(active) = active_segment else {
+        let Some(frame) =
+            layout_keyboard(&mut self.font_system, uniforms, output_size, caption_layout)
+        else {
             return;
         };
 
-        let segment_fade = active
-            .segment
-            .fade_duration_override
-            .unwrap_or(settings.fade_duration) as f64;
-
-        let raw_text = build_visible_text(active.segment, current_time);
-
-        if raw_text.is_empty() {
-            return;
-        }
-
-        let should_uppercase = active
-            .segment
-            .uppercase_override
-            .unwrap_or(settings.uppercase);
-        let visible_text = if should_uppercase {
-            raw_text.to_uppercase()
-        } else {
-            raw_text
-        };
-
-        let fade_opacity = calculate_fade(
-            current_time,
-            active.segment.start,
-            active.segment.end,
-            segment_fade,
-        ) * uniforms.takeover_overlay_fade();
-
-        if fade_opacity <= 0.0 {
-            return;
-        }
-
-        let bounce_offset = calculate_keyboard_bounce(
-            current_time,
-            active.segment.start,
-            active.segment.end,
-            segment_fade,
-        );
-
-        let (width, height) = (output_size.x, output_size.y);
-        let device = &constants.device;
         let queue = &constants.queue;
-
-        let position = active
-            .segment
-            .position_override
-            .as_deref()
-            .map(KeyboardPosition::from_str)
-            .unwrap_or_else(|| KeyboardPosition::from_str(&settings.position));
-
-        let margin = width as f32 * 0.05;
-
-        let color_hex = active
-            .segment
-            .color_override
-            .as_deref()
-            .unwrap_or(&settings.color);
-        let text_color = [
-            parse_color_component(color_hex, 0),
-            parse_color_component(color_hex, 1),
-            parse_color_component(color_hex, 2),
-        ];
-
-        let bg_color_hex = active
-            .segment
-            .background_color_override
-            .as_deref()
-            .unwrap_or(&settings.background_color);
-        let background_color_rgb = [
-            parse_color_component(bg_color_hex, 0),
-            parse_color_component(bg_color_hex, 1),
-            parse_color_component(bg_color_hex, 2),
-        ];
-
-        let background_alpha =
-            ((settings.background_opacity as f32 / 100.0) * fade_opacity).clamp(0.0, 1.0);
-
-        let font_size_base = active.segment.font_size_override.unwrap_or(settings.size) as f32;
-        let font_size = font_size_base * (height as f32 / 1080.0);
-        let metrics = Metrics::new(font_size, font_size * 1.2);
-
-        let mut updated_buffer = Buffer::new(&mut self.font_system, metrics);
-        let wrap_width = (width as f32 - margin * 2.0).max(font_size);
-        updated_buffer.set_size(&mut self.font_system, Some(wrap_width), None);
-
-        let font_family = match settings.font.as_str() {
-            "System Serif" => Family::Serif,
-            "System Monospace" => Family::Monospace,
-            _ => Family::SansSerif,
-        };
-
-        let weight = if settings.font_weight >= 700 {
-            Weight::BOLD
-        } else if settings.font_weight >= 500 {
-            Weight::MEDIUM
-        } else {
-            Weight::NORMAL
-        };
-
-        let text_alpha = fade_opacity.clamp(0.0, 1.0);
-        let color = Color::rgba(
-            (text_color[0] * 255.0) as u8,
-            (text_color[1] * 255.0) as u8,
-            (text_color[2] * 255.0) as u8,
-            (text_alpha * 255.0) as u8,
-        );
-
-        let attrs = Attrs::new().family(font_family).weight(weight).color(color);
-        updated_buffer.set_text(
-            &mut self.font_system,
-            &visible_text,
-            &attrs,
-            Shaping::Advanced,
-        );
-
-        let mut layout_width: f32 = 0.0;
-        let mut layout_height: f32 = 0.0;
-        for run in glyphon::cosmic_text::LayoutRunIter::new(&updated_buffer) {
-            layout_width = layout_width.max(run.line_w);
-            layout_height = layout_height.max(run.line_top + run.line_height);
-        }
-
-        if layout_height == 0.0 {
-            layout_height = font_size * 1.2;
-            layout_width = layout_width.max(font_size);
-        }
-
-        let available_width = (width as f32 - margin * 2.0).max(1.0);
-        let padding = font_size * 0.45;
-        let corner_radius = font_size * 0.5;
-        let text_width = layout_width.min(available_width);
-        let text_height = layout_height;
-        let box_width = (text_width + padding * 2.0).min(available_width).max(1.0);
-        let box_height = (text_height + padding * 2.0).min(height as f32).max(1.0);
-
-        let resolved_position = resolve_keyboard_position(position, caption_layout);
-
-        let background_left = match resolved_position {
-            KeyboardPosition::TopLeft | KeyboardPosition::BottomLeft => margin,
-            KeyboardPosition::TopRight | KeyboardPosition::BottomRight => {
-                (width as f32 - margin - box_width).max(0.0)
-            }
-            _ => ((width as f32 - box_width) / 2.0).max(0.0),
-        };
-
-        let center_y = height as f32 * resolved_position.y_factor();
-        let max_background_top = (height as f32 - box_height).max(0.0);
-        let base_background_top = (center_y - box_height / 2.0).clamp(0.0, max_background_top);
-        let background_top = resolve_background_top(
-            (base_background_top + bounce_offset as f32).clamp(0.0, max_background_top),
-            box_height,
-            height as f32,
-            position,
-            caption_layout,
+        self.text_buffer = frame.buffer;
+        self.viewport.update(
+            queue,
+            Resolution {
+                width: output_size.x,
+                height: output_size.y,
+            },
         );
 
-        let text_left = background_left + padding;
-        let text_top = background_top + padding;
-
-        let bounds = TextBounds {
-            left: (text_left - 2.0).floor() as i32,
-            top: (text_top - 2.0).floor() as i32,
-            right: (text_left + text_width + 2.0).ceil() as i32,
-            bottom: (text_top + text_height + 2.0).ceil() as i32,
-        };
-
-        self.text_buffer = updated_buffer;
-        self.viewport.update(queue, Resolution { width, height });
-
-        let text_areas = vec![TextArea {
-            buffer: &self.text_buffer,
-            left: text_left,
-            top: text_top,
-            scale: 1.0,
-            bounds,
-            default_color: color,
-            custom_glyphs: &[],
-        }];
+        let text_areas = vec![
+            frame
+                .placement
+                .to_text_area(std::slice::from_ref(&self.text_buffer)),
+        ];
 
         match self.text_renderer.prepare(
-            device,
+            &constants.device,
             queue,
             &mut self.font_system,
             &mut self.text_atlas,
@@ -509,57 +593,14 @@ impl KeyboardLayer {
             Err(e) => warn!("Error preparing keyboard text: {e:?}"),
         }
 
-        let rect = KeyboardBackgroundUniforms {
-            rect: [
-                background_left.max(0.0),
-                background_top.max(0.0),
-                box_width,
-                box_height,
-            ],
-            color: [
-                background_color_rgb[0],
-                background_color_rgb[1],
-                background_color_rgb[2],
-                background_alpha,
-            ],
-            radius: corner_radius.min(box_width / 2.0).min(box_height / 2.0),
-            _padding: [0.0; 3],
-            _padding2: [0.0; 4],
-        };
-
         queue.write_buffer(
             &self.background_uniform_buffer,
             0,
-            bytemuck::bytes_of(&rect),
+            bytemuck::bytes_of(&KeyboardBackgroundUniforms::from(frame.background)),
         );
 
-        let scissor_padding = 4.0;
-        let scissor_x = (background_left - scissor_padding).max(0.0).floor() as u32;
-        let scissor_y = (background_top - scissor_padding).max(0.0).floor() as u32;
-        let max_width = width.saturating_sub(scissor_x);
-        let max_height = height.saturating_sub(scissor_y);
-
-        if max_width == 0 || max_height == 0 {
-            self.has_content = false;
-            return;
-        }
-
-        let scissor_width = (box_width + scissor_padding * 2.0)
-            .ceil()
-            .max(1.0)
-            .min(max_width as f32) as u32;
-        let scissor_height = (box_height + scissor_padding * 2.0)
-            .ceil()
-            .max(1.0)
-            .min(max_height as f32) as u32;
-
-        if scissor_width == 0 || scissor_height == 0 {
-            self.has_content = false;
-            return;
-        }
-
-        self.background_scissor = Some([scissor_x, scissor_y, scissor_width, scissor_height]);
-        self.has_content = true;
+        self.background_scissor = frame.scissor;
+        self.has_content = frame.scissor.is_some();
     }
 
     pub fn has_content(&self) -> bool {
@@ -63,6 +63,33 @@ pub(crate) fn new_font_system() -> glyphon::FontSystem {
     glyphon::FontSystem::new_with_locale_and_db(locale.clone(), db.clone())
 }
 
+/// Where one shaped buffer is drawn: a `glyphon::TextArea` that refers to its
+/// buffer by index, so layout can be shared by the glyphon renderer and the
+/// CPU rasterizer without borrowing the buffers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TextPlacement {
+    pub buffer: usize,
+    pub left: f32,
+    pub top: f32,
+    pub scale: f32,
+    pub bounds: glyphon::TextBounds,
+    pub default_color: glyphon::Color,
+}
+
+impl TextPlacement {
+    pub fn to_text_area<'a>(&self, buffers: &'a [glyphon::Buffer]) -> glyphon::TextArea<'a> {
+        glyphon::TextArea {
+            buffer: &buffers[self.buffer],
+            left: self.left,
+            top: self.top,
+            scale: self.scale,
+            bounds: self.bounds,
+            default_color: self.default_color,
+            custom_glyphs: &[],
+        }
+    }
+}
+
 pub use background::*;
 pub use blur::*;
 pub use camera::*;
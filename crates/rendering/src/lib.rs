@@ -33,6 +33,7 @@ use tokio::sync::mpsc;
 pub mod camera3d;
 pub mod composite_frame;
 mod coord;
+mod cpu;
 pub mod cpu_yuv;
 mod cursor_interpolation;
 #[cfg(target_os = "windows")]
@@ -42,9 +43,11 @@ pub mod frame_chrome;
 mod frame_pipeline;
 #[cfg(target_os = "macos")]
 pub mod iosurface_texture;
+pub mod label;
 mod layers;
 mod mask;
 pub mod notch_shape;
+mod pip;
 mod project_recordings;
 mod scene;
 pub mod spring_mass_damper;
@@ -56,10 +59,13 @@ mod zoom;
 mod zoom_spring;
 
 pub use coord::*;
+pub use cpu::{CpuRenderConstants, render_video_to_channel_cpu, render_video_to_channel_cpu_nv12};
 pub use decoder::{DecodedFrame, DecoderStatus, DecoderType, PixelFormat};
 pub use frame_pipeline::{GpuOutputFormat, Nv12RenderedFrame, RenderedFrame, SharedNv12Buffer};
 pub use layers::{BackgroundTextureCache, clean_background_path};
-pub use project_recordings::{ProjectRecordingsMeta, SegmentRecordings, Video};
+pub use project_recordings::{
+    DEFAULT_STILL_DURATION, ProjectRecordingsMeta, SegmentRecordings, Video,
+};
 use transition::{TransitionCompositor, TransitionParameters};
 
 /// Warms the process-wide system-font scan used by the text/captions/keyboard
@@ -281,6 +287,8 @@ pub struct PreparedMask {
 pub struct RecordingSegmentDecoders {
     screen: AsyncVideoDecoderHandle,
     camera: Option<AsyncVideoDecoderHandle>,
+    /// Picture-in-picture sources, keyed by `AdditionalVideoMeta::id`.
+    additional: Vec<(String, AsyncVideoDecoderHandle)>,
     pub segment_offset: f64,
 }
 
@@ -294,7 +302,7 @@ pub struct SegmentVideoPaths {
 
 impl RecordingSegmentDecoders {
     pub async fn new(
-        _recording_meta: &RecordingMeta,
+        recording_meta: &RecordingMeta,
         meta: &StudioRecordingMeta,
         segment: SegmentVideoPaths,
         segment_i: usize,
@@ -384,17 +392,76 @@ impl RecordingSegmentDecoders {
             Ok(Some(camera))
         };
 
+        let additional_future = async {
+            let StudioRecordingMeta::MultipleSegments { inner, .. } = &meta else {
+                return Vec::new();
+            };
+
+            let mut decoders = Vec::new();
+            for additional in &inner.segments[segment_i].additional_videos {
+                let offset = latest_start_time
+                    .zip(additional.video.start_time)
+                    .map(|(latest_start_time, start_time)| latest_start_time - start_time)
+                    .unwrap_or(0.0);
+
+                // A missing or broken extra source shouldn't take the whole
+                // segment down with it; its layer just stays empty.
+                match spawn_decoder(
+                    "additional",
+                    recording_meta.path(&additional.video.path),
+                    additional.video.fps,
+                    offset,
+                    force_ffmpeg,
+                )
+                .await
+                {
+                    Ok(decoder) => decoders.push((
+                        additional.id.clone(),
+                        decoder.with_max_fallback_distance(CAMERA_MAX_FALLBACK_DISTANCE),
+                    )),
+                    Err(e) => {
+                        tracing::warn!(id = %additional.id, "Failed to open additional video: {e}")
+                    }
+                }
+            }
+            decoders
+        };
+
         // Decoders spawn their own threads and just signal readiness, so screen
         // and camera can always initialize concurrently.
-        let (screen, camera) = tokio::try_join!(screen_future, camera_future)?;
+        let (primary, additional) = tokio::join!(
+            async { tokio::try_join!(screen_future, camera_future) },
+            additional_future
+        );
+        let (screen, camera) = primary?;
 
         Ok(Self {
             screen,
             camera,
+            additional,
             segment_offset: latest_start_time.unwrap_or(0.0),
         })
     }
 
+    async fn get_additional_frames(
+        &self,
+        segment_time: f32,
+        initial: bool,
+    ) -> Vec<(String, DecodedFrame)> {
+        let frames =
+            futures::future::join_all(self.additional.iter().map(|(id, decoder)| async move {
+                let frame = if initial {
+                    decoder.get_frame_initial(segment_time).await
+                } else {
+                    decoder.get_frame(segment_time).await
+                };
+                frame.map(|frame| (id.clone(), frame))
+            }))
+            .await;
+
+        frames.into_iter().flatten().collect()
+    }
+
     pub async fn get_frames(
         &self,
         segment_time: f32,
@@ -405,7 +472,7 @@ impl RecordingSegmentDecoders {
         let camera_request_time = segment_time + offsets.camera;
 
         if needs_display {
-            let (screen, camera) = tokio::join!(
+            let (screen, camera, additional_frames) = tokio::join!(
                 self.screen.get_frame(segment_time),
                 OptionFuture::from(
                     needs_camera
@@ -414,7 +481,8 @@ impl RecordingSegmentDecoders {
                             .as_ref()
                             .map(|d| d.get_frame(camera_request_time)))
                         .flatten()
-                )
+                ),
+                self.get_additional_frames(segment_time, false)
             );
 
             let camera_frame = camera.flatten();
@@ -426,6 +494,7 @@ impl RecordingSegmentDecoders {
             Some(DecodedSegmentFrames {
                 screen_frame: Some(screen?),
                 camera_frame,
+                additional_frames,
                 segment_time,
                 recording_time: segment_time + self.segment_offset as f32,
                 segment_has_camera: self.camera.is_some(),
@@ -451,6 +520,7 @@ impl RecordingSegmentDecoders {
             Some(DecodedSegmentFrames {
                 screen_frame: None,
                 camera_frame,
+                additional_frames: Vec::new(),
                 segment_time,
                 recording_time: segment_time + self.segment_offset as f32,
                 segment_has_camera: self.camera.is_some(),
@@ -468,7 +538,7 @@ impl RecordingSegmentDecoders {
         let camera_request_time = segment_time + offsets.camera;
 
         if needs_display {
-            let (screen, camera) = tokio::join!(
+            let (screen, camera, additional_frames) = tokio::join!(
                 self.screen.get_frame_initial(segment_time),
                 OptionFuture::from(
                     needs_camera
@@ -477,7 +547,8 @@ impl RecordingSegmentDecoders {
                             .as_ref()
                             .map(|d| d.get_frame_initial(camera_request_time)))
                         .flatten()
-                )
+                ),
+                self.get_additional_frames(segment_time, true)
             );
 
             let camera_frame = camera.flatten();
@@ -485,6 +556,7 @@ impl RecordingSegmentDecoders {
             Some(DecodedSegmentFrames {
                 screen_frame: Some(screen?),
                 camera_frame,
+                additional_frames,
                 segment_time,
                 recording_time: segment_time + self.segment_offset as f32,
                 segment_has_camera: self.camera.is_some(),
@@ -510,6 +582,7 @@ impl RecordingSegmentDecoders {
             Some(DecodedSegmentFrames {
                 screen_frame: None,
                 camera_frame,
+                additional_frames: Vec::new(),
                 segment_time,
                 recording_time: segment_time + self.segment_offset as f32,
                 segment_has_camera: self.camera.is_some(),
@@ -558,6 +631,10 @@ pub enum RenderingError {
         frame_number: u32,
         consecutive_failures: u32,
     },
+    #[error("CPU renderer: {0}")]
+    CpuRender(#[from] cap_rendering_skia::SkiaRenderingError),
+    #[error("The CPU renderer does not support {0}; turn it off or export with the GPU renderer")]
+    CpuUnsupported(&'static str),
 }
 
 pub struct RenderSegment {
@@ -598,11 +675,13 @@ pub async fn render_video_to_channel(
 
     let precomputed_cursor_timelines: Vec<Arc<PrecomputedCursorTimeline>> = render_segments
         .iter()
-        .map(|segment| {
+        .enumerate()
+        .map(|(clip, segment)| {
             Arc::new(PrecomputedCursorTimeline::new(
                 &segment.cursor,
                 cursor_smoothing,
                 Some(click_spring),
+                &project.cursor.clip_edits(clip as u32),
             ))
         })
         .collect();
@@ -1033,11 +1112,13 @@ pub async fn render_video_to_channel_nv12(
 
     let precomputed_cursor_timelines: Vec<Arc<PrecomputedCursorTimeline>> = render_segments
         .iter()
-        .map(|segment| {
+        .enumerate()
+        .map(|(clip, segment)| {
             Arc::new(PrecomputedCursorTimeline::new(
                 &segment.cursor,
                 cursor_smoothing,
                 Some(click_spring),
+                &project.cursor.clip_edits(clip as u32),
             ))
         })
         .collect();
@@ -3270,7 +3351,8 @@ impl ProjectUniforms {
         };
 
         Self::new_inner(
-            constants,
+            &constants.options,
+            &constants.meta,
             project,
             frame_number,
             fps,
@@ -3301,7 +3383,8 @@ impl ProjectUniforms {
         };
 
         Self::new_inner(
-            constants,
+            &constants.options,
+            &constants.meta,
             project,
             frame_number,
             fps,
@@ -3316,7 +3399,8 @@ impl ProjectUniforms {
 
     #[allow(clippy::too_many_arguments)]
     fn new_inner(
-        constants: &RenderVideoConstants,
+        options: &RenderOptions,
+        meta: &StudioRecordingMeta,
         project: &ProjectConfiguration,
         frame_number: u32,
         fps: u32,
@@ -3327,7 +3411,6 @@ impl ProjectUniforms {
         zoom_timeline: &ZoomTransformTimeline,
         cursor_interp_fn: &dyn Fn(f32) -> Option<InterpolatedCursorPosition>,
     ) -> Self {
-        let options = &constants.options;
         let output_size = Self::get_output_size(options, project, resolution_base);
         let fps_f32 = fps as f32;
         let frame_time = frame_number as f32 / fps_f32;
@@ -3859,7 +3942,7 @@ impl ProjectUniforms {
             let notch = project
                 .background
                 .notch
-                .and_then(|config| config.resolve(constants.meta.display_notch()))
+                .and_then(|config| config.resolve(meta.display_notch()))
                 .and_then(|notch| {
                     let placement = notch_bounds(notch, options, project, resolution_base, &zoom)?;
                     // Rasterize at the unzoomed size and let zoom scale the
@@ -4771,6 +4854,8 @@ mod tests {
 pub struct DecodedSegmentFrames {
     pub screen_frame: Option<DecodedFrame>,
     pub camera_frame: Option<DecodedFrame>,
+    /// Frames for the segment's picture-in-picture sources, by source id.
+    pub additional_frames: Vec<(String, DecodedFrame)>,
     pub segment_time: f32,
     pub recording_time: f32,
     pub segment_has_camera: bool,
@@ -5235,78 +5320,7 @@ impl<'a> FrameRenderer<'a> {
         &mut self,
         rgba_frame: RenderedFrame,
     ) -> frame_pipeline::Nv12RenderedFrame {
-        let width = rgba_frame.width;
-        let height = rgba_frame.height;
-        let padded_bytes_per_row = rgba_frame.padded_bytes_per_row;
-        let frame_number = rgba_frame.frame_number;
-        let target_time_ns = rgba_frame.target_time_ns;
-
-        let nv12_size = (width as usize) * (height as usize) * 3 / 2;
-        let mut nv12_buf = self.nv12_buffer_pool.acquire(nv12_size);
-
-        let y_stride = width as usize;
-        let uv_stride = width as usize;
-        let y_plane_size = y_stride * height as usize;
-        let uv_plane_size = uv_stride * (height as usize / 2);
-        nv12_buf.resize(y_plane_size + uv_plane_size, 0);
-
-        let src_data = &rgba_frame.data;
-        let src_stride = padded_bytes_per_row as usize;
-
-        for row in 0..height as usize {
-            let src_row = &src_data[row * src_stride..row * src_stride + width as usize * 4];
-            let y_row = &mut nv12_buf[row * y_stride..(row + 1) * y_stride];
-            for col in 0..width as usize {
-                let r = src_row[col * 4] as i32;
-                let g = src_row[col * 4 + 1] as i32;
-                let b = src_row[col * 4 + 2] as i32;
-                y_row[col] = ((16 + ((65 * r + 129 * g + 25 * b + 128) >> 8)) as u8).clamp(16, 235);
-            }
-        }
-
-        let uv_offset = y_plane_size;
-        for row in 0..(height as usize / 2) {
-            let src_row0 =
-                &src_data[row * 2 * src_stride..row * 2 * src_stride + width as usize * 4];
-            let src_row1 = &src_data
-                [(row * 2 + 1) * src_stride..(row * 2 + 1) * src_stride + width as usize * 4];
-            let uv_row =
-                &mut nv12_buf[uv_offset + row * uv_stride..uv_offset + (row + 1) * uv_stride];
-            for col in 0..(width as usize / 2) {
-                let r = (src_row0[col * 8] as i32
-                    + src_row0[col * 8 + 4] as i32
-                    + src_row1[col * 8] as i32
-                    + src_row1[col * 8 + 4] as i32
-                    + 2)
-                    / 4;
-                let g = (src_row0[col * 8 + 1] as i32
-                    + src_row0[col * 8 + 5] as i32
-                    + src_row1[col * 8 + 1] as i32
-                    + src_row1[col * 8 + 5] as i32
-                    + 2)
-                    / 4;
-                let b = (src_row0[col * 8 + 2] as i32
-                    + src_row0[col * 8 + 6] as i32
-                    + src_row1[col * 8 + 2] as i32
-                    + src_row1[col * 8 + 6] as i32
-                    + 2)
-                    / 4;
-                uv_row[col * 2] =
-                    ((128 + ((-38 * r - 74 * g + 112 * b + 128) >> 8)) as u8).clamp(16, 240);
-                uv_row[col * 2 + 1] =
-                    ((128 + ((112 * r - 94 * g - 18 * b + 128) >> 8)) as u8).clamp(16, 240);
-            }
-        }
-
-        frame_pipeline::Nv12RenderedFrame {
-            data: self.nv12_buffer_pool.wrap(nv12_buf),
-            width,
-            height,
-            y_stride: width,
-            frame_number,
-            target_time_ns,
-            format: frame_pipeline::GpuOutputFormat::Nv12,
-        }
+        rgba_frame_to_nv12(&rgba_frame, &self.nv12_buffer_pool)
     }
 
     async fn render_nv12_gpu_path(
@@ -5416,6 +5430,11 @@ pub struct RendererLayers {
     cursor: CursorLayer,
     camera: CameraLayer,
     camera_only: CameraLayer,
+    /// One layer per picture-in-picture source drawn this frame, grown on
+    /// demand and reused across frames.
+    pip: Vec<CameraLayer>,
+    shared_yuv_pipelines: Arc<yuv_converter::YuvConverterPipelines>,
+    shared_composite_pipeline: Arc<composite_frame::CompositeVideoFramePipeline>,
     mask: MaskLayer,
     text: TextLayer,
     captions: CaptionsLayer,
@@ -5459,9 +5478,12 @@ impl RendererLayers {
             ),
             camera_only: CameraLayer::new_with_all_shared_pipelines(
                 device,
-                shared_yuv_pipelines,
-                shared_composite_pipeline,
+                shared_yuv_pipelines.clone(),
+                shared_composite_pipeline.clone(),
             ),
+            pip: Vec::new(),
+            shared_yuv_pipelines,
+            shared_composite_pipeline,
             mask: MaskLayer::new(device),
             text: TextLayer::new(device, queue),
             captions: CaptionsLayer::new(device, queue),
@@ -5472,6 +5494,45 @@ impl RendererLayers {
         }
     }
 
+    /// Uniforms and frames for the picture-in-picture sources with a frame
+    /// this tick, in `ProjectConfiguration::picture_in_picture` order so later
+    /// entries draw on top.
+    fn pip_frames<'a>(
+        uniforms: &ProjectUniforms,
+        segment_frames: &'a DecodedSegmentFrames,
+    ) -> Vec<(CompositeVideoFrameUniforms, &'a DecodedFrame)> {
+        let output_size = [uniforms.output_size.0 as f32, uniforms.output_size.1 as f32];
+        let opacity = uniforms.scene.screen_opacity as f32;
+
+        uniforms
+            .project
+            .picture_in_picture
+            .iter()
+            .filter(|layer| !layer.hide)
+            .filter_map(|layer| {
+                let (_, frame) = segment_frames
+                    .additional_frames
+                    .iter()
+                    .find(|(id, _)| *id == layer.source_id)?;
+                let frame_size = [frame.width() as f32, frame.height() as f32];
+                Some((
+                    pip::layer_uniforms(layer, output_size, frame_size, opacity),
+                    frame,
+                ))
+            })
+            .collect()
+    }
+
+    fn ensure_pip_layers(&mut self, device: &wgpu::Device, count: usize) {
+        while self.pip.len() < count {
+            self.pip.push(CameraLayer::new_with_all_shared_pipelines(
+                device,
+                self.shared_yuv_pipelines.clone(),
+                self.shared_composite_pipeline.clone(),
+            ));
+        }
+    }
+
     fn ensure_camera_blur_processor(&mut self, device: &wgpu::Device) {
         if self.camera_blur_processor.is_none() && !self.camera_blur_init_failed {
             match cap_camera_effects::BlurProcessor::new(device, wgpu::TextureFormat::Rgba8Unorm) {
@@ -5677,6 +5738,28 @@ impl RendererLayers {
             self.run_shared_camera_blur(&constants.device, &constants.queue, mode);
         }
 
+        let pip_frames = if render_display {
+            Self::pip_frames(uniforms, segment_frames)
+        } else {
+            Vec::new()
+        };
+        self.ensure_pip_layers(&constants.device, pip_frames.len());
+        for (i, layer) in self.pip.iter_mut().enumerate() {
+            let pip_frame = pip_frames.get(i);
+            layer.prepare(
+                &constants.device,
+                &constants.queue,
+                pip_frame.map(|(uniforms, _)| *uniforms),
+                pip_frame.map(|(_, frame)| {
+                    (
+                        XY::new(frame.width(), frame.height()),
+                        *frame,
+                        segment_frames.recording_time,
+                    )
+                }),
+            );
+        }
+
         self.text.prepare(
             &constants.device,
             &constants.queue,
@@ -5846,6 +5929,29 @@ impl RendererLayers {
         }
         timings.camera_blur_prepare_duration = start.elapsed();
 
+        let pip_frames = if render_display {
+            Self::pip_frames(uniforms, segment_frames)
+        } else {
+            Vec::new()
+        };
+        self.ensure_pip_layers(&constants.device, pip_frames.len());
+        for (i, layer) in self.pip.iter_mut().enumerate() {
+            let pip_frame = pip_frames.get(i);
+            layer.prepare_with_encoder(
+                &constants.device,
+                &constants.queue,
+                pip_frame.map(|(uniforms, _)| *uniforms),
+                pip_frame.map(|(_, frame)| {
+                    (
+                        XY::new(frame.width(), frame.height()),
+                        *frame,
+                        segment_frames.recording_time,
+                    )
+                }),
+                encoder,
+            );
+        }
+
         let start = Instant::now();
         self.text.prepare(
             &constants.device,
@@ -5912,6 +6018,9 @@ impl RendererLayers {
         }
         self.camera.copy_to_texture(encoder);
         self.camera_only.copy_to_texture(encoder);
+        for layer in &mut self.pip {
+            layer.copy_to_texture(encoder);
+        }
 
         {
             let mut pass = render_pass!(
@@ -6005,6 +6114,15 @@ impl RendererLayers {
             self.notch.render(&mut pass);
         }
 
+        // Extra cameras/displays sit above the screen but under the main
+        // camera bubble, which stays the primary subject.
+        if render_display && uniforms.scene.should_render_screen() {
+            for layer in &self.pip {
+                let mut pass = render_pass!(content_view!(), wgpu::LoadOp::Load);
+                layer.render(&mut pass);
+            }
+        }
+
         // Render camera-only layer when transitioning with CameraOnly mode
         if uniforms.scene.is_transitioning_camera_only() {
             let mut pass = render_pass!(content_view!(), wgpu::LoadOp::Load);
@@ -6072,6 +6190,84 @@ impl RendererLayers {
     }
 }
 
+/// BT.601 limited-range RGBA to NV12 conversion on the CPU, for frames that
+/// never touched the GPU converter.
+fn rgba_frame_to_nv12(
+    rgba_frame: &RenderedFrame,
+    nv12_buffer_pool: &NV12BufferPool,
+) -> frame_pipeline::Nv12RenderedFrame {
+    let width = rgba_frame.width;
+    let height = rgba_frame.height;
+    let padded_bytes_per_row = rgba_frame.padded_bytes_per_row;
+    let frame_number = rgba_frame.frame_number;
+    let target_time_ns = rgba_frame.target_time_ns;
+
+    let nv12_size = (width as usize) * (height as usize) * 3 / 2;
+    let mut nv12_buf = nv12_buffer_pool.acquire(nv12_size);
+
+    let y_stride = width as usize;
+    let uv_stride = width as usize;
+    let y_plane_size = y_stride * height as usize;
+    let uv_plane_size = uv_stride * (height as usize / 2);
+    nv12_buf.resize(y_plane_size + uv_plane_size, 0);
+
+    let src_data = &rgba_frame.data;
+    let src_stride = padded_bytes_per_row as usize;
+
+    for row in 0..height as usize {
+        let src_row = &src_data[row * src_stride..row * src_stride + width as usize * 4];
+        let y_row = &mut nv12_buf[row * y_stride..(row + 1) * y_stride];
+        for col in 0..width as usize {
+            let r = src_row[col * 4] as i32;
+            let g = src_row[col * 4 + 1] as i32;
+            let b = src_row[col * 4 + 2] as i32;
+            y_row[col] = ((16 + ((65 * r + 129 * g + 25 * b + 128) >> 8)) as u8).clamp(16, 235);
+        }
+    }
+
+    let uv_offset = y_plane_size;
+    for row in 0..(height as usize / 2) {
+        let src_row0 = &src_data[row * 2 * src_stride..row * 2 * src_stride + width as usize * 4];
+        let src_row1 =
+            &src_data[(row * 2 + 1) * src_stride..(row * 2 + 1) * src_stride + width as usize * 4];
+        let uv_row = &mut nv12_buf[uv_offset + row * uv_stride..uv_offset + (row + 1) * uv_stride];
+        for col in 0..(width as usize / 2) {
+            let r = (src_row0[col * 8] as i32
+                + src_row0[col * 8 + 4] as i32
+                + src_row1[col * 8] as i32
+                + src_row1[col * 8 + 4] as i32
+                + 2)
+                / 4;
+            let g = (src_row0[col * 8 + 1] as i32
+                + src_row0[col * 8 + 5] as i32
+                + src_row1[col * 8 + 1] as i32
+                + src_row1[col * 8 + 5] as i32
+                + 2)
+                / 4;
+            let b = (src_row0[col * 8 + 2] as i32
+                + src_row0[col * 8 + 6] as i32
+                + src_row1[col * 8 + 2] as i32
+                + src_row1[col * 8 + 6] as i32
+                + 2)
+                / 4;
+            uv_row[col * 2] =
+                ((128 + ((-38 * r - 74 * g + 112 * b + 128) >> 8)) as u8).clamp(16, 240);
+            uv_row[col * 2 + 1] =
+                ((128 + ((112 * r - 94 * g - 18 * b + 128) >> 8)) as u8).clamp(16, 240);
+        }
+    }
+
+    frame_pipeline::Nv12RenderedFrame {
+        data: nv12_buffer_pool.wrap(nv12_buf),
+        width,
+        height,
+        y_stride: width,
+        frame_number,
+        target_time_ns,
+        format: frame_pipeline::GpuOutputFormat::Nv12,
+    }
+}
+
 async fn produce_frame_with_timings(
     constants: &RenderVideoConstants,
     segment_frames: DecodedSegmentFrames,
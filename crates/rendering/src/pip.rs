@@ -0,0 +1,149 @@
+use cap_project::{CameraShape, PictureInPictureLayer, ShadowConfiguration};
+
+use crate::{
+    CAMERA_EDGE_CROP_INSET_PX, composite_frame::CompositeVideoFrameUniforms, inset_crop_bounds,
+    rounding_type_value, snap_bounds_to_output_pixels,
+};
+
+/// Source-pixel region of the frame the layer samples: the configured crop
+/// clamped to the frame, then center-cropped to a square for `Square`.
+fn source_region(layer: &PictureInPictureLayer, frame_size: [f32; 2]) -> [f32; 4] {
+    let [x0, y0, x1, y1] = match &layer.crop {
+        Some(crop) if crop.size.x > 0 && crop.size.y > 0 => {
+            let x0 = (crop.position.x as f32).min(frame_size[0] - 1.0).max(0.0);
+            let y0 = (crop.position.y as f32).min(frame_size[1] - 1.0).max(0.0);
+            [
+                x0,
+                y0,
+                (x0 + crop.size.x as f32).min(frame_size[0]),
+                (y0 + crop.size.y as f32).min(frame_size[1]),
+            ]
+        }
+        _ => [0.0, 0.0, frame_size[0], frame_size[1]],
+    };
+
+    match layer.shape {
+        CameraShape::Source => [x0, y0, x1, y1],
+        CameraShape::Square => {
+            let (w, h) = (x1 - x0, y1 - y0);
+            if w > h {
+                let offset = (w - h) / 2.0;
+                [x0 + offset, y0, x1 - offset, y1]
+            } else {
+                let offset = (h - w) / 2.0;
+                [x0, y0 + offset, x1, y1 - offset]
+            }
+        }
+    }
+}
+
+/// Placement of one additional video source, sized like the camera bubble
+/// and kept fully inside the output.
+pub fn layer_uniforms(
+    layer: &PictureInPictureLayer,
+    output_size: [f32; 2],
+    frame_size: [f32; 2],
+    opacity: f32,
+) -> CompositeVideoFrameUniforms {
+    let crop_bounds = source_region(layer, frame_size);
+    let aspect = (crop_bounds[2] - crop_bounds[0]) / (crop_bounds[3] - crop_bounds[1]).max(1.0);
+
+    let side = output_size[0].min(output_size[1]) * (layer.size / 100.0).clamp(0.01, 1.0);
+    let size = if aspect >= 1.0 {
+        [side * aspect, side]
+    } else {
+        [side, side / aspect]
+    };
+    let size = [size[0].min(output_size[0]), size[1].min(output_size[1])];
+
+    let center = [
+        layer.position.x as f32 * output_size[0],
+        layer.position.y as f32 * output_size[1],
+    ];
+    let x = (center[0] - size[0] / 2.0).clamp(0.0, output_size[0] - size[0]);
+    let y = (center[1] - size[1] / 2.0).clamp(0.0, output_size[1] - size[1]);
+
+    let target_bounds = snap_bounds_to_output_pixels([x, y, x + size[0], y + size[1]], output_size);
+    let target_size = [
+        target_bounds[2] - target_bounds[0],
+        target_bounds[3] - target_bounds[1],
+    ];
+    let shadow = ShadowConfiguration::default();
+
+    CompositeVideoFrameUniforms {
+        output_size,
+        frame_size,
+        crop_bounds: inset_crop_bounds(crop_bounds, frame_size, CAMERA_EDGE_CROP_INSET_PX),
+        target_bounds,
+        target_size,
+        rounding_px: layer.rounding.clamp(0.0, 100.0) / 100.0
+            * 0.5
+            * target_size[0].min(target_size[1]),
+        rounding_type: rounding_type_value(layer.rounding_type),
+        mirror_x: if layer.mirror { 1.0 } else { 0.0 },
+        shadow: layer.shadow,
+        shadow_size: shadow.size,
+        shadow_opacity: shadow.opacity,
+        shadow_blur: shadow.blur,
+        opacity,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cap_project::{Crop, XY};
+
+    use super::*;
+
+    const OUTPUT: [f32; 2] = [1920.0, 1080.0];
+
+    fn layer() -> PictureInPictureLayer {
+        PictureInPictureLayer {
+            position: XY::new(0.5, 0.5),
+            size: 50.0,
+            ..PictureInPictureLayer::new("display-1")
+        }
+    }
+
+    #[test]
+    fn source_shape_keeps_frame_aspect() {
+        let uniforms = layer_uniforms(&layer(), OUTPUT, [1280.0, 720.0], 1.0);
+
+        assert_eq!(uniforms.target_size[1], 540.0);
+        assert_eq!(uniforms.target_size[0], 960.0);
+        assert_eq!(uniforms.target_bounds[0], 480.0);
+        assert_eq!(uniforms.target_bounds[1], 270.0);
+    }
+
+    #[test]
+    fn square_shape_center_crops_the_crop_region() {
+        let layer = PictureInPictureLayer {
+            shape: CameraShape::Square,
+            crop: Some(Crop {
+                position: XY::new(100, 0),
+                size: XY::new(400, 200),
+            }),
+            ..layer()
+        };
+        let uniforms = layer_uniforms(&layer, OUTPUT, [1280.0, 720.0], 1.0);
+
+        assert_eq!(uniforms.target_size, [540.0, 540.0]);
+        let [x0, y0, x1, y1] = uniforms.crop_bounds;
+        assert!((x0 - 202.0).abs() < 1e-3 && (x1 - 398.0).abs() < 1e-3);
+        assert!((y0 - 2.0).abs() < 1e-3 && (y1 - 198.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn layers_stay_inside_the_output() {
+        let layer = PictureInPictureLayer {
+            position: XY::new(1.0, 1.0),
+            ..layer()
+        };
+        let uniforms = layer_uniforms(&layer, OUTPUT, [1280.0, 720.0], 0.5);
+
+        assert_eq!(uniforms.target_bounds[2], 1920.0);
+        assert_eq!(uniforms.target_bounds[3], 1080.0);
+        assert_eq!(uniforms.opacity, 0.5);
+    }
+}
@@ -3,10 +3,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use cap_project::{AudioMeta, StudioRecordingMeta, VideoMeta};
+use cap_project::{
+    AudioMeta, ImportedMediaKind, ImportedMediaMeta, StudioRecordingMeta, TimelineConfiguration,
+    TimelineSegment, VideoMeta,
+};
 use serde::Serialize;
 use specta::Type;
 
+/// How long an imported image stays on screen when its import didn't say.
+pub const DEFAULT_STILL_DURATION: f64 = 5.0;
+
 #[derive(Debug, Clone, Copy, Serialize, Type)]
 pub struct Video {
     pub duration: f64,
@@ -75,6 +81,25 @@ impl Video {
         inner(path.as_ref(), start_time)
     }
 
+    /// An imported image shown for `duration` seconds.
+    pub fn still(
+        path: impl AsRef<Path>,
+        start_time: f64,
+        duration: f64,
+        fps: u32,
+    ) -> Result<Self, String> {
+        let (width, height) = image::image_dimensions(path.as_ref())
+            .map_err(|e| format!("Failed to read image: {e}"))?;
+
+        Ok(Video {
+            duration,
+            width,
+            height,
+            fps,
+            start_time,
+        })
+    }
+
     pub fn fps(&self) -> u32 {
         self.fps
     }
@@ -181,6 +206,22 @@ impl ProjectRecordingsMeta {
                         })
                     };
 
+                    let load_display = |meta: &VideoMeta| match &s.imported {
+                        Some(ImportedMediaMeta {
+                            kind: ImportedMediaKind::Image,
+                            duration,
+                            ..
+                        }) => ensure_start_time(meta.start_time).and_then(|start_time| {
+                            Video::still(
+                                meta.path.to_path(recording_path),
+                                start_time,
+                                duration.unwrap_or(DEFAULT_STILL_DURATION),
+                                meta.fps,
+                            )
+                        }),
+                        _ => load_video(meta),
+                    };
+
                     let load_audio = |meta: &AudioMeta| {
                         ensure_start_time(meta.start_time).and_then(|start_time| {
                             Audio::new(meta.path.to_path(recording_path), start_time)
@@ -200,7 +241,7 @@ impl ProjectRecordingsMeta {
                     };
 
                     Ok::<_, String>(SegmentRecordings {
-                        display: load_video(&s.display).map_err(|e| format!("video / {e}"))?,
+                        display: load_display(&s.display).map_err(|e| format!("video / {e}"))?,
                         camera: Option::map(s.camera.as_ref(), load_video)
                             .transpose()
                             .map_err(|e| format!("camera / {e}"))?,
@@ -231,6 +272,40 @@ impl ProjectRecordingsMeta {
     pub fn get_source_duration(&self, path: &PathBuf) -> Result<f64, String> {
         Video::new(path, 0.0).map(|v| v.duration)
     }
+
+    /// The timeline the editor creates for an un-edited project: one segment per
+    /// recording, spanning its full duration. `None` if no segment has any media.
+    pub fn default_timeline(&self) -> Option<TimelineConfiguration> {
+        let segments: Vec<TimelineSegment> = self
+            .segments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, segment)| {
+                let duration = segment.duration();
+                (duration > 0.0).then_some(TimelineSegment {
+                    recording_clip: i as u32,
+                    start: 0.0,
+                    end: duration,
+                    timescale: 1.0,
+                    name: None,
+                    speed_audio_mode: None,
+                })
+            })
+            .collect();
+
+        (!segments.is_empty()).then(|| TimelineConfiguration {
+            segments,
+            transitions: Vec::new(),
+            zoom_segments: Vec::new(),
+            scene_segments: Vec::new(),
+            mask_segments: Vec::new(),
+            text_segments: Vec::new(),
+            caption_segments: Vec::new(),
+            keyboard_segments: Vec::new(),
+            audio_segments: Vec::new(),
+            camera3d_segments: Vec::new(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Type)]
@@ -102,6 +102,19 @@ impl Capturer {
     pub async fn stop(&self) -> Result<(), arc::R<ns::Error>> {
         self.stream.stop().await
     }
+
+    /// Applies a new configuration to the running stream, e.g. to move its source rect.
+    pub async fn update_config(&self, config: &sc::StreamCfg) -> Result<(), arc::R<ns::Error>> {
+        self.stream.update_cfg(config).await
+    }
+
+    /// Points the running stream at different content, e.g. another display.
+    pub async fn update_content_filter(
+        &self,
+        target: &sc::ContentFilter,
+    ) -> Result<(), arc::R<ns::Error>> {
+        self.stream.update_content_filter(target).await
+    }
 }
 
 pub struct VideoFrame {